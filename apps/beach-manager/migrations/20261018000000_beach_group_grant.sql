@@ -0,0 +1,17 @@
+-- Scopes granted to every member of a beach group, optionally limited to
+-- sessions carrying a tag (e.g. `pb:control.write` on sessions tagged `prod`).
+CREATE TABLE public.beach_group_grant (
+    id uuid DEFAULT public.uuid_generate_v4() NOT NULL,
+    beach_group_id uuid NOT NULL,
+    scope text NOT NULL,
+    session_tag text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+ALTER TABLE ONLY public.beach_group_grant
+    ADD CONSTRAINT beach_group_grant_pkey PRIMARY KEY (id);
+
+CREATE UNIQUE INDEX idx_beach_group_grant_unique ON public.beach_group_grant USING btree (beach_group_id, scope, lower(COALESCE(session_tag, '')));
+
+ALTER TABLE ONLY public.beach_group_grant
+    ADD CONSTRAINT beach_group_grant_beach_group_id_fkey FOREIGN KEY (beach_group_id) REFERENCES public.beach_group(id) ON DELETE CASCADE;
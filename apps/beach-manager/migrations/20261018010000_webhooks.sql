@@ -0,0 +1,82 @@
+-- Outbound webhooks: per-beach subscriptions, the pending delivery queue and
+-- deliveries that exhausted their retries.
+CREATE TABLE public.webhook_subscription (
+    id uuid DEFAULT public.uuid_generate_v4() NOT NULL,
+    private_beach_id uuid NOT NULL,
+    url text NOT NULL,
+    event_types text[] NOT NULL,
+    secret text NOT NULL,
+    active boolean DEFAULT true NOT NULL,
+    description text,
+    created_by_account_id uuid,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE TABLE public.webhook_delivery (
+    id uuid DEFAULT public.uuid_generate_v4() NOT NULL,
+    subscription_id uuid NOT NULL,
+    private_beach_id uuid NOT NULL,
+    event_type text NOT NULL,
+    session_id text,
+    payload jsonb NOT NULL,
+    attempts integer DEFAULT 0 NOT NULL,
+    next_attempt_at timestamp with time zone DEFAULT now() NOT NULL,
+    last_status integer,
+    last_error text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE TABLE public.webhook_dead_letter (
+    id uuid NOT NULL,
+    subscription_id uuid NOT NULL,
+    private_beach_id uuid NOT NULL,
+    event_type text NOT NULL,
+    session_id text,
+    payload jsonb NOT NULL,
+    attempts integer NOT NULL,
+    last_status integer,
+    last_error text,
+    created_at timestamp with time zone NOT NULL,
+    dead_at timestamp with time zone DEFAULT now() NOT NULL,
+    replayed_at timestamp with time zone
+);
+
+ALTER TABLE ONLY public.webhook_subscription
+    ADD CONSTRAINT webhook_subscription_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.webhook_dead_letter
+    ADD CONSTRAINT webhook_dead_letter_pkey PRIMARY KEY (id);
+
+CREATE INDEX idx_webhook_subscription_beach ON public.webhook_subscription USING btree (private_beach_id);
+
+CREATE INDEX idx_webhook_delivery_due ON public.webhook_delivery USING btree (next_attempt_at);
+
+CREATE INDEX idx_webhook_dead_letter_beach ON public.webhook_dead_letter USING btree (private_beach_id, dead_at DESC);
+
+ALTER TABLE ONLY public.webhook_subscription
+    ADD CONSTRAINT webhook_subscription_private_beach_id_fkey FOREIGN KEY (private_beach_id) REFERENCES public.private_beach(id) ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.webhook_subscription
+    ADD CONSTRAINT webhook_subscription_created_by_account_id_fkey FOREIGN KEY (created_by_account_id) REFERENCES public.account(id);
+
+ALTER TABLE ONLY public.webhook_delivery
+    ADD CONSTRAINT webhook_delivery_subscription_id_fkey FOREIGN KEY (subscription_id) REFERENCES public.webhook_subscription(id) ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.webhook_dead_letter
+    ADD CONSTRAINT webhook_dead_letter_subscription_id_fkey FOREIGN KEY (subscription_id) REFERENCES public.webhook_subscription(id) ON DELETE CASCADE;
+
+ALTER TABLE public.webhook_subscription ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY webhook_subscription_all ON public.webhook_subscription USING (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true))) WITH CHECK (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true)));
+
+ALTER TABLE public.webhook_delivery ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY webhook_delivery_all ON public.webhook_delivery USING (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true))) WITH CHECK (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true)));
+
+ALTER TABLE public.webhook_dead_letter ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY webhook_dead_letter_all ON public.webhook_dead_letter USING (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true))) WITH CHECK (((private_beach_id)::text = current_setting('beach.private_beach_id'::text, true)));
@@ -0,0 +1,9 @@
+ALTER TYPE public.controller_event_type ADD VALUE IF NOT EXISTS 'emergency_stop';
+
+CREATE INDEX idx_controller_event_occurred_at ON public.controller_event USING btree (occurred_at);
+
+CREATE INDEX idx_controller_lease_issued_at ON public.controller_lease USING btree (issued_at);
+
+CREATE INDEX idx_controller_lease_ended_at ON public.controller_lease USING btree ((COALESCE(revoked_at, expires_at)));
+
+CREATE INDEX idx_controller_event_controller_token_id ON public.controller_event USING btree (controller_token_id);
@@ -0,0 +1,8 @@
+-- Records which automation assignment created a controller pairing so that
+-- only that assignment removes it, even after a manager restart. Pairings
+-- set up by hand leave it NULL.
+ALTER TABLE ONLY public.controller_pairing
+    ADD COLUMN automation_assignment_id uuid;
+
+ALTER TABLE ONLY public.controller_pairing
+    ADD CONSTRAINT controller_pairing_automation_assignment_id_fkey FOREIGN KEY (automation_assignment_id) REFERENCES public.automation_assignment(id) ON DELETE SET NULL;
@@ -0,0 +1,441 @@
+//! Controller audit trail: who held control of which session, and when.
+//!
+//! Every lease grant and controller event is already recorded; auditors read
+//! them back through the export and summary endpoints as one timeline of
+//! [`ControllerAuditRecord`]s, and rows older than the [`AuditRetention`]
+//! window are shipped to an archive store before being deleted. The SQL lives
+//! with the rest of the queries in `state.rs`.
+
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::blob_store::BlobStore;
+
+/// Exports stop after this many records and flag the response as truncated.
+pub const MAX_AUDIT_EXPORT_ROWS: usize = 100_000;
+/// Window used when a request omits `from_ms`.
+pub const DEFAULT_AUDIT_WINDOW_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+/// How often the background loop archives rows past the retention cutoff.
+pub const AUDIT_RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Rows archived per file (and per delete transaction) during a sweep.
+pub const AUDIT_ARCHIVE_BATCH: i64 = 5_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditFormat {
+    #[default]
+    Jsonl,
+    Csv,
+}
+
+impl AuditFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            AuditFormat::Jsonl => "application/x-ndjson",
+            AuditFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            AuditFormat::Jsonl => "jsonl",
+            AuditFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditRecordKind {
+    Event,
+    Lease,
+}
+
+/// One `controller_event` or `controller_lease` row, flattened so both kinds
+/// share a single CSV layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ControllerAuditRecord {
+    pub kind: AuditRecordKind,
+    pub id: String,
+    pub private_beach_id: String,
+    pub session_id: String,
+    /// Event time, or lease issue time.
+    pub occurred_at_ms: i64,
+    /// Controller event type; unset for leases.
+    pub event_type: Option<String>,
+    pub controller_account_id: Option<String>,
+    pub issued_by_account_id: Option<String>,
+    pub reason: Option<String>,
+    /// Lease expiry; unset for events.
+    pub expires_at_ms: Option<i64>,
+    pub revoked_at_ms: Option<i64>,
+}
+
+impl ControllerAuditRecord {
+    /// When the lease stopped granting control, clamped to `now_ms` for leases
+    /// that are still live. `None` for events.
+    pub fn lease_end_ms(&self, now_ms: i64) -> Option<i64> {
+        if self.kind != AuditRecordKind::Lease {
+            return None;
+        }
+        let end = match (self.revoked_at_ms, self.expires_at_ms) {
+            (Some(revoked), Some(expires)) => revoked.min(expires),
+            (Some(at), None) | (None, Some(at)) => at,
+            (None, None) => now_ms,
+        };
+        Some(end.min(now_ms).max(self.occurred_at_ms))
+    }
+
+    fn is_emergency_stop(&self) -> bool {
+        self.event_type.as_deref() == Some("emergency_stop")
+    }
+}
+
+/// Time range and optional account/session filters for exports and summaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub from_ms: i64,
+    pub to_ms: i64,
+    /// Matches rows where the account held control or issued the change.
+    pub account_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+}
+
+impl AuditFilter {
+    /// Defaults to the last [`DEFAULT_AUDIT_WINDOW_MS`] ending at `now_ms`.
+    pub fn new(
+        from_ms: Option<i64>,
+        to_ms: Option<i64>,
+        account_id: Option<&str>,
+        session_id: Option<&str>,
+        now_ms: i64,
+    ) -> Result<Self, String> {
+        let to_ms = to_ms.unwrap_or(now_ms);
+        let from_ms = from_ms.unwrap_or(to_ms.saturating_sub(DEFAULT_AUDIT_WINDOW_MS));
+        if from_ms >= to_ms {
+            return Err("from_ms must be earlier than to_ms".into());
+        }
+        let parse = |value: Option<&str>, field: &str| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| Uuid::parse_str(value).map_err(|_| format!("invalid {field}")))
+                .transpose()
+        };
+        Ok(Self {
+            from_ms,
+            to_ms,
+            account_id: parse(account_id, "account_id")?,
+            session_id: parse(session_id, "session_id")?,
+        })
+    }
+}
+
+const CSV_COLUMNS: &[&str] = &[
+    "kind",
+    "id",
+    "private_beach_id",
+    "session_id",
+    "occurred_at",
+    "event_type",
+    "controller_account_id",
+    "issued_by_account_id",
+    "reason",
+    "expires_at",
+    "revoked_at",
+];
+
+pub fn encode(records: &[ControllerAuditRecord], format: AuditFormat) -> Vec<u8> {
+    match format {
+        AuditFormat::Jsonl => encode_jsonl(records),
+        AuditFormat::Csv => encode_csv(records),
+    }
+}
+
+pub fn encode_jsonl(records: &[ControllerAuditRecord]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for record in records {
+        // Serializing a struct of strings and integers cannot fail.
+        serde_json::to_writer(&mut out, record).expect("audit record serializes");
+        out.push(b'\n');
+    }
+    out
+}
+
+/// RFC 4180 CSV with RFC 3339 timestamps, which spreadsheets parse directly.
+pub fn encode_csv(records: &[ControllerAuditRecord]) -> Vec<u8> {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for record in records {
+        let kind = match record.kind {
+            AuditRecordKind::Event => "event",
+            AuditRecordKind::Lease => "lease",
+        };
+        let fields = [
+            kind.to_string(),
+            record.id.clone(),
+            record.private_beach_id.clone(),
+            record.session_id.clone(),
+            rfc3339(Some(record.occurred_at_ms)),
+            record.event_type.clone().unwrap_or_default(),
+            record.controller_account_id.clone().unwrap_or_default(),
+            record.issued_by_account_id.clone().unwrap_or_default(),
+            record.reason.clone().unwrap_or_default(),
+            rfc3339(record.expires_at_ms),
+            rfc3339(record.revoked_at_ms),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&line.join(","));
+        out.push_str("\r\n");
+    }
+    out.into_bytes()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn rfc3339(ms: Option<i64>) -> String {
+    ms.and_then(DateTime::<Utc>::from_timestamp_millis)
+        .map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ControllerAuditSummary {
+    pub private_beach_id: String,
+    pub from_ms: i64,
+    pub to_ms: i64,
+    /// Sorted by total lease time, longest first.
+    pub accounts: Vec<AccountAuditSummary>,
+    /// Set when the window held more than [`MAX_AUDIT_EXPORT_ROWS`] records,
+    /// so the totals only cover the oldest of them.
+    pub truncated: bool,
+}
+
+/// Control held and emergency stops issued by one account. Leases and stops
+/// without an account (dev tokens, harness-issued leases) roll up under an
+/// unset `account_id`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct AccountAuditSummary {
+    pub account_id: Option<String>,
+    pub lease_count: u64,
+    /// Leases ended by a release, handshake revoke or emergency stop rather
+    /// than by expiring.
+    pub revoked_lease_count: u64,
+    pub total_lease_ms: i64,
+    pub longest_lease_ms: i64,
+    pub average_lease_ms: i64,
+    pub emergency_stops_issued: u64,
+}
+
+/// Aggregates lease durations per controlling account and emergency stops
+/// per issuing account. Live leases count up to `now_ms`.
+pub fn summarize(records: &[ControllerAuditRecord], now_ms: i64) -> Vec<AccountAuditSummary> {
+    let mut by_account: BTreeMap<Option<String>, AccountAuditSummary> = BTreeMap::new();
+    for record in records {
+        if let Some(end_ms) = record.lease_end_ms(now_ms) {
+            let duration = end_ms - record.occurred_at_ms;
+            let entry = by_account
+                .entry(record.controller_account_id.clone())
+                .or_default();
+            entry.lease_count += 1;
+            entry.total_lease_ms += duration;
+            entry.longest_lease_ms = entry.longest_lease_ms.max(duration);
+            if record.revoked_at_ms.is_some() {
+                entry.revoked_lease_count += 1;
+            }
+        } else if record.is_emergency_stop() {
+            by_account
+                .entry(record.issued_by_account_id.clone())
+                .or_default()
+                .emergency_stops_issued += 1;
+        }
+    }
+    let mut accounts: Vec<AccountAuditSummary> = by_account
+        .into_iter()
+        .map(|(account_id, mut summary)| {
+            summary.account_id = account_id;
+            if summary.lease_count > 0 {
+                summary.average_lease_ms = summary.total_lease_ms / summary.lease_count as i64;
+            }
+            summary
+        })
+        .collect();
+    accounts.sort_by(|a, b| {
+        b.total_lease_ms
+            .cmp(&a.total_lease_ms)
+            .then_with(|| a.account_id.cmp(&b.account_id))
+    });
+    accounts
+}
+
+/// Rows older than `max_age` are written to `archive` as JSONL and then
+/// deleted. Events use `occurred_at`; leases use the time they ended.
+#[derive(Clone)]
+pub struct AuditRetention {
+    pub max_age: Duration,
+    pub archive: Arc<dyn BlobStore>,
+}
+
+impl AuditRetention {
+    pub fn new(max_age: Duration, archive: Arc<dyn BlobStore>) -> Self {
+        Self { max_age, archive }
+    }
+
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        chrono::Duration::from_std(self.max_age)
+            .ok()
+            .and_then(|age| now.checked_sub_signed(age))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+}
+
+impl std::fmt::Debug for AuditRetention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditRetention")
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `controller-audit/YYYY/MM/DD/<run-ms>-<batch>.jsonl`, so archives sort by
+/// the sweep that wrote them.
+pub fn archive_key(run_at: DateTime<Utc>, batch: Uuid) -> String {
+    format!(
+        "controller-audit/{}/{}-{}.jsonl",
+        run_at.format("%Y/%m/%d"),
+        run_at.timestamp_millis(),
+        batch
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lease(
+        account: &str,
+        issued: i64,
+        expires: i64,
+        revoked: Option<i64>,
+    ) -> ControllerAuditRecord {
+        ControllerAuditRecord {
+            kind: AuditRecordKind::Lease,
+            id: Uuid::new_v4().to_string(),
+            private_beach_id: "pb".into(),
+            session_id: "sess".into(),
+            occurred_at_ms: issued,
+            event_type: None,
+            controller_account_id: Some(account.into()),
+            issued_by_account_id: None,
+            reason: None,
+            expires_at_ms: Some(expires),
+            revoked_at_ms: revoked,
+        }
+    }
+
+    fn event(event_type: &str, issued_by: Option<&str>) -> ControllerAuditRecord {
+        ControllerAuditRecord {
+            kind: AuditRecordKind::Event,
+            id: Uuid::new_v4().to_string(),
+            private_beach_id: "pb".into(),
+            session_id: "sess".into(),
+            occurred_at_ms: 1_000,
+            event_type: Some(event_type.into()),
+            controller_account_id: None,
+            issued_by_account_id: issued_by.map(str::to_string),
+            reason: Some("runaway agent, \"stop\"".into()),
+            expires_at_ms: None,
+            revoked_at_ms: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_lease_durations_and_emergency_stops() {
+        let records = vec![
+            lease("alice", 0, 10_000, None),
+            lease("alice", 20_000, 50_000, Some(25_000)),
+            // Still live: counted up to `now`.
+            lease("bob", 90_000, 200_000, None),
+            event("emergency_stop", Some("bob")),
+            event("emergency_stop", None),
+            event("lease_released", Some("alice")),
+        ];
+        let summary = summarize(&records, 100_000);
+
+        assert_eq!(summary.len(), 3);
+        let alice = &summary[0];
+        assert_eq!(alice.account_id.as_deref(), Some("alice"));
+        assert_eq!(alice.lease_count, 2);
+        assert_eq!(alice.revoked_lease_count, 1);
+        assert_eq!(alice.total_lease_ms, 15_000);
+        assert_eq!(alice.longest_lease_ms, 10_000);
+        assert_eq!(alice.average_lease_ms, 7_500);
+        assert_eq!(alice.emergency_stops_issued, 0);
+
+        let bob = &summary[1];
+        assert_eq!(bob.account_id.as_deref(), Some("bob"));
+        assert_eq!(bob.total_lease_ms, 10_000);
+        assert_eq!(bob.emergency_stops_issued, 1);
+
+        let unattributed = &summary[2];
+        assert_eq!(unattributed.account_id, None);
+        assert_eq!(unattributed.lease_count, 0);
+        assert_eq!(unattributed.emergency_stops_issued, 1);
+    }
+
+    #[test]
+    fn encodes_csv_and_jsonl() {
+        let records = vec![
+            lease("alice", 0, 10_000, None),
+            event("emergency_stop", None),
+        ];
+
+        let csv = String::from_utf8(encode(&records, AuditFormat::Csv)).unwrap();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[1].starts_with("lease,"));
+        assert!(lines[1].contains(",1970-01-01T00:00:00.000Z,"));
+        assert!(lines[1].ends_with(",1970-01-01T00:00:10.000Z,"));
+        assert!(lines[2].contains(",\"runaway agent, \"\"stop\"\"\","));
+        assert_eq!(lines[3], "");
+
+        let jsonl = encode(&records, AuditFormat::Jsonl);
+        let decoded: Vec<ControllerAuditRecord> = jsonl
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(decoded, records);
+    }
+
+    #[test]
+    fn filter_defaults_and_validation() {
+        let filter =
+            AuditFilter::new(None, None, Some(" "), None, DEFAULT_AUDIT_WINDOW_MS + 5).unwrap();
+        assert_eq!(filter.from_ms, 5);
+        assert_eq!(filter.account_id, None);
+        assert!(AuditFilter::new(Some(10), Some(10), None, None, 0).is_err());
+        assert!(AuditFilter::new(None, None, Some("nope"), None, 0).is_err());
+
+        let now = DateTime::<Utc>::from_timestamp_millis(1_800_000_000_000).unwrap();
+        let retention = AuditRetention::new(
+            Duration::from_secs(24 * 60 * 60),
+            Arc::new(crate::blob_store::MemoryBlobStore::new()),
+        );
+        assert_eq!(
+            retention.cutoff(now).timestamp_millis(),
+            1_800_000_000_000 - 86_400_000
+        );
+        assert!(archive_key(now, Uuid::nil()).starts_with("controller-audit/2027/01/15/"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Content storage backing the private beach file store.
+//!
+//! `file_record` rows only hold metadata; the bytes live behind a [`BlobStore`]
+//! addressed by the row's `storage_key`. The local filesystem backend is the
+//! default for self-hosted deployments; object stores can slot in later by
+//! implementing the same trait.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum BlobStoreError {
+    #[error("blob not found: {0}")]
+    NotFound(String),
+    #[error("invalid storage key: {0}")]
+    InvalidKey(String),
+    #[error("blob io error: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[async_trait]
+pub trait BlobStore: Send + Sync + 'static {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), BlobStoreError>;
+
+    async fn get(&self, key: &str) -> Result<Bytes, BlobStoreError>;
+
+    /// Removes the blob; deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError>;
+}
+
+/// Stores blobs as files under a root directory, one file per storage key.
+#[derive(Debug, Clone)]
+pub struct LocalFsBlobStore {
+    root: PathBuf,
+}
+
+impl LocalFsBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, BlobStoreError> {
+        let relative = Path::new(key);
+        let valid = !key.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !valid {
+            return Err(BlobStoreError::InvalidKey(key.to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalFsBlobStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), BlobStoreError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write to a sibling temp file first so readers never observe a partial blob.
+        let tmp = path.with_extension(format!("tmp-{}", Uuid::new_v4()));
+        tokio::fs::write(&tmp, &data).await?;
+        if let Err(err) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, BlobStoreError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::read(&path).await {
+            Ok(data) => Ok(Bytes::from(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(BlobStoreError::NotFound(key.to_string()))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Process-local store used by the in-memory backend and tests.
+#[derive(Debug, Default)]
+pub struct MemoryBlobStore {
+    blobs: RwLock<HashMap<String, Bytes>>,
+}
+
+impl MemoryBlobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), BlobStoreError> {
+        self.blobs.write().await.insert(key.to_string(), data);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, BlobStoreError> {
+        self.blobs
+            .read()
+            .await
+            .get(key)
+            .cloned()
+            .ok_or_else(|| BlobStoreError::NotFound(key.to_string()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError> {
+        self.blobs.write().await.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn local_fs_round_trips_and_rejects_escaping_keys() {
+        let root = std::env::temp_dir().join(format!("beach-blob-{}", Uuid::new_v4()));
+        let store = LocalFsBlobStore::new(&root);
+
+        store
+            .put("pb-1/blob-a", Bytes::from_static(b"hello"))
+            .await
+            .expect("put");
+        assert_eq!(
+            store.get("pb-1/blob-a").await.expect("get"),
+            Bytes::from_static(b"hello")
+        );
+        store.delete("pb-1/blob-a").await.expect("delete");
+        store
+            .delete("pb-1/blob-a")
+            .await
+            .expect("delete is idempotent");
+        assert!(matches!(
+            store.get("pb-1/blob-a").await,
+            Err(BlobStoreError::NotFound(_))
+        ));
+
+        for key in ["", "../escape", "/etc/passwd", "pb-1/../../escape"] {
+            assert!(
+                matches!(
+                    store.put(key, Bytes::new()).await,
+                    Err(BlobStoreError::InvalidKey(_))
+                ),
+                "key {key:?} should be rejected"
+            );
+        }
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
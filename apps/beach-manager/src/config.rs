@@ -40,6 +40,18 @@ pub struct AppConfig {
     pub idle_snapshot_interval_ms: Option<u64>,
     #[serde(default = "default_signing_key_check_interval")]
     pub signing_key_check_interval_secs: u64,
+    #[serde(default = "default_file_store_dir")]
+    pub file_store_dir: String,
+    /// Controller events and leases older than this are archived and deleted;
+    /// unset keeps them forever.
+    #[serde(default)]
+    pub controller_audit_retention_days: Option<u64>,
+    #[serde(default = "default_audit_archive_dir")]
+    pub controller_audit_archive_dir: String,
+    /// Allows webhooks to target loopback and private networks, for
+    /// self-hosted deployments whose receivers live next to the manager.
+    #[serde(default)]
+    pub webhook_allow_private_targets: bool,
 }
 
 impl AppConfig {
@@ -70,6 +82,10 @@ impl AppConfig {
                     controller_strict_gating: true,
                     idle_snapshot_interval_ms: default_idle_snapshot_interval(),
                     signing_key_check_interval_secs: default_signing_key_check_interval(),
+                    file_store_dir: default_file_store_dir(),
+                    controller_audit_retention_days: None,
+                    controller_audit_archive_dir: default_audit_archive_dir(),
+                    webhook_allow_private_targets: false,
                 }
                 .normalize()
             })
@@ -121,3 +137,11 @@ fn default_idle_snapshot_interval() -> Option<u64> {
 fn default_signing_key_check_interval() -> u64 {
     60
 }
+
+fn default_file_store_dir() -> String {
+    "./data/files".to_string()
+}
+
+fn default_audit_archive_dir() -> String {
+    "./data/audit-archive".to_string()
+}
@@ -1,7 +1,10 @@
+pub mod audit;
 pub mod auth;
+pub mod blob_store;
 pub mod config;
 pub mod log_throttle;
 pub mod metrics;
 pub mod publish_token;
 pub mod routes;
 pub mod state;
+pub mod webhooks;
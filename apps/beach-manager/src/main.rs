@@ -1,20 +1,32 @@
+mod audit;
 mod auth;
+mod blob_store;
 mod config;
 mod log_throttle;
 mod metrics;
 mod publish_token;
 mod routes;
 mod state;
+mod webhooks;
 
+use audit::{AuditRetention, AUDIT_RETENTION_SWEEP_INTERVAL};
 use auth::{AuthAuthority, AuthConfig, AuthContext};
+use blob_store::LocalFsBlobStore;
 use config::AppConfig;
 use routes::build_router;
 use serde::Deserialize;
 use sqlx::postgres::PgPoolOptions;
 use state::{
-    viewer_health_report_interval, AppState, STALE_SESSION_MAX_IDLE, STALE_SESSION_SWEEP_INTERVAL,
+    viewer_health_report_interval, AppState, AUTOMATION_RECONCILE_INTERVAL, STALE_SESSION_MAX_IDLE,
+    STALE_SESSION_SWEEP_INTERVAL, WEBHOOK_DELIVERY_INTERVAL,
+};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, OnceLock},
+    time::Duration,
 };
-use std::{collections::HashSet, net::SocketAddr, path::Path, sync::OnceLock, time::Duration};
 use tokio::time::sleep;
 use tracing::{error, info, warn};
 use tracing_appender::non_blocking::WorkerGuard;
@@ -42,7 +54,8 @@ async fn main() -> anyhow::Result<()> {
         viewer_health_interval_secs = viewer_health_report_interval().as_secs(),
         sweep_interval_secs = STALE_SESSION_SWEEP_INTERVAL.as_secs(),
         log_path = %cfg.log_path.as_deref().unwrap_or("unset"),
-        controller_strict_gating = cfg.controller_strict_gating
+        controller_strict_gating = cfg.controller_strict_gating,
+        file_store_dir = %cfg.file_store_dir
     );
 
     let mut state = if let Some(db_url) = &cfg.database_url {
@@ -103,6 +116,19 @@ async fn main() -> anyhow::Result<()> {
     state = state.with_gate_client(cfg.beach_gate_url.clone());
     state = state.with_controller_strict_gating(cfg.controller_strict_gating);
     state = state.with_idle_snapshot_interval(cfg.idle_snapshot_interval_ms);
+    state = state.with_blob_store(Arc::new(LocalFsBlobStore::new(&cfg.file_store_dir)));
+    state = state.with_private_webhook_targets(cfg.webhook_allow_private_targets);
+    if let Some(days) = cfg.controller_audit_retention_days {
+        info!(
+            retention_days = days,
+            archive_dir = %cfg.controller_audit_archive_dir,
+            "controller audit retention enabled"
+        );
+        state = state.with_audit_retention(AuditRetention::new(
+            Duration::from_secs(days.saturating_mul(24 * 60 * 60)),
+            Arc::new(LocalFsBlobStore::new(&cfg.controller_audit_archive_dir)),
+        ));
+    }
 
     init_signing_key_monitor(&cfg).await?;
 
@@ -144,6 +170,37 @@ async fn main() -> anyhow::Result<()> {
         metrics::REDIS_AVAILABLE.set(0);
     }
 
+    {
+        // Spawned after redis is attached so assignment leases use the same queue backend.
+        let reconcile_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                reconcile_state.reconcile_automation_assignments().await;
+                sleep(AUTOMATION_RECONCILE_INTERVAL).await;
+            }
+        });
+    }
+
+    {
+        let webhook_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                webhook_state.deliver_due_webhooks().await;
+                sleep(WEBHOOK_DELIVERY_INTERVAL).await;
+            }
+        });
+    }
+
+    if cfg.controller_audit_retention_days.is_some() {
+        let audit_state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                audit_state.archive_expired_controller_audit().await;
+                sleep(AUDIT_RETENTION_SWEEP_INTERVAL).await;
+            }
+        });
+    }
+
     let app = build_router(state);
 
     let addr: SocketAddr = cfg.bind_addr.parse()?;
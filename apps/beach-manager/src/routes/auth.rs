@@ -104,6 +104,13 @@ impl FromRequestParts<AppState> for AuthToken {
     }
 }
 
+/// `candidate` grants `scope` when equal, `*`, or a `prefix.*` wildcard.
+pub(crate) fn matches_scope(candidate: &str, scope: &str) -> bool {
+    candidate == "*"
+        || candidate == scope
+        || (candidate.ends_with(".*") && scope.starts_with(&candidate[..candidate.len() - 2]))
+}
+
 #[allow(dead_code)]
 impl AuthToken {
     pub fn as_str(&self) -> &str {
@@ -111,13 +118,6 @@ impl AuthToken {
     }
 
     pub fn has_scope(&self, scope: &str) -> bool {
-        fn matches_scope(candidate: &str, scope: &str) -> bool {
-            candidate == "*"
-                || candidate == scope
-                || (candidate.ends_with(".*")
-                    && scope.starts_with(&candidate[..candidate.len() - 2]))
-        }
-
         if let Some(value) = &self.claims.scope {
             for item in value.split_whitespace() {
                 if matches_scope(item, scope) {
@@ -0,0 +1,224 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+    Json,
+};
+use beach_buggy::FileRecord;
+use serde::Deserialize;
+use tracing::info;
+use utoipa::IntoParams;
+
+use crate::state::AppState;
+
+use super::{
+    private_beaches::map_state_err,
+    sessions::{authorize_publish, ensure_beach_scope},
+    ApiError, ApiResult, AuthToken,
+};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListFilesQuery {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DownloadFileQuery {
+    #[serde(default)]
+    pub version: Option<i32>,
+}
+
+fn content_type_from(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/files",
+    tag = "files",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ListFilesQuery,
+    ),
+    responses((status = 200, description = "Latest version of each file", body = [FileRecord]))
+)]
+pub async fn list_files(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Query(query): Query<ListFilesQuery>,
+) -> ApiResult<Vec<FileRecord>> {
+    ensure_beach_scope(&state, &token, &id, "pb:files.read").await?;
+    let files = state
+        .list_files(
+            &id,
+            token.account_uuid(),
+            query.prefix.as_deref(),
+            query.include_deleted,
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(files))
+}
+
+#[utoipa::path(
+    put,
+    path = "/private-beaches/{id}/files/{path}",
+    tag = "files",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("path" = String, Path, description = "File path within the beach"),
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses((status = 200, description = "Stored file version", body = FileRecord))
+)]
+pub async fn upload_file(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, path)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<FileRecord> {
+    ensure_beach_scope(&state, &token, &id, "pb:files.write").await?;
+    let record = state
+        .upload_file(
+            &id,
+            token.account_uuid(),
+            &path,
+            content_type_from(&headers),
+            body,
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/files/{path}",
+    tag = "files",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("path" = String, Path, description = "File path within the beach"),
+        DownloadFileQuery,
+    ),
+    responses((status = 200, description = "File contents", content_type = "application/octet-stream", body = Vec<u8>))
+)]
+pub async fn download_file(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, path)): Path<(String, String)>,
+    Query(query): Query<DownloadFileQuery>,
+) -> Result<Response, ApiError> {
+    ensure_beach_scope(&state, &token, &id, "pb:files.read").await?;
+    let (record, data) = state
+        .download_file(&id, token.account_uuid(), &path, query.version)
+        .await
+        .map_err(map_state_err)?;
+    let content_type = record
+        .content_type
+        .as_deref()
+        .and_then(|value| HeaderValue::from_str(value).ok())
+        .unwrap_or_else(|| HeaderValue::from_static("application/octet-stream"));
+    let mut response = Body::from(data).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, content_type);
+    if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", record.checksum)) {
+        headers.insert(header::ETAG, etag);
+    }
+    headers.insert("x-beach-file-version", HeaderValue::from(record.version));
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/files/{path}",
+    tag = "files",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("path" = String, Path, description = "File path within the beach"),
+    ),
+    responses((status = 200, description = "File deleted", body = serde_json::Value))
+)]
+pub async fn delete_file(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, path)): Path<(String, String)>,
+) -> ApiResult<serde_json::Value> {
+    ensure_beach_scope(&state, &token, &id, "pb:files.write").await?;
+    let versions = state
+        .delete_file(&id, token.account_uuid(), &path)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(
+        serde_json::json!({ "deleted": true, "versions": versions }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/file-versions/{path}",
+    tag = "files",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("path" = String, Path, description = "File path within the beach"),
+    ),
+    responses((status = 200, description = "All versions of the file", body = [FileRecord]))
+)]
+pub async fn list_file_versions(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, path)): Path<(String, String)>,
+) -> ApiResult<Vec<FileRecord>> {
+    ensure_beach_scope(&state, &token, &id, "pb:files.read").await?;
+    let versions = state
+        .file_versions(&id, token.account_uuid(), &path)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(versions))
+}
+
+/// Harness-facing upload used by beach-buggy's `ManagerTransport::upload_artifact`;
+/// authorized like the other harness publish routes.
+#[utoipa::path(
+    put,
+    path = "/sessions/{session_id}/files/{path}",
+    tag = "files",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ("path" = String, Path, description = "File path within the beach"),
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses((status = 200, description = "Stored artifact", body = FileRecord))
+)]
+pub async fn upload_session_artifact(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((session_id, path)): Path<(String, String)>,
+    body: Bytes,
+) -> ApiResult<FileRecord> {
+    let auth_path = authorize_publish(&state, &headers, &session_id).await?;
+    let record = state
+        .upload_session_artifact(&session_id, &path, content_type_from(&headers), body)
+        .await
+        .map_err(map_state_err)?;
+    info!(
+        target = "private_beach",
+        session_id = %session_id,
+        path = %record.path,
+        version = record.version,
+        size_bytes = record.size_bytes,
+        auth_path,
+        "session artifact stored"
+    );
+    Ok(Json(record))
+}
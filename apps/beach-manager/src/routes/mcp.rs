@@ -3,9 +3,10 @@ use beach_buggy::{ActionAck, ActionCommand, RegisterSessionRequest};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::error;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::state::{AppState, ControllerUpdateCadence, StateError};
+use crate::state::{AppState, ControllerUpdateCadence, SessionFilter, StateError};
 
 use super::AuthToken;
 
@@ -22,7 +23,7 @@ fn require_scope(
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub(super) struct JsonRpcRequest {
     pub jsonrpc: Option<String>,
     pub id: Option<Value>,
@@ -30,7 +31,7 @@ pub(super) struct JsonRpcRequest {
     pub params: Option<Value>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub(super) struct JsonRpcResponse {
     jsonrpc: &'static str,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -41,7 +42,7 @@ pub(super) struct JsonRpcResponse {
     error: Option<JsonRpcError>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub(super) struct JsonRpcError {
     code: i32,
     message: String,
@@ -50,6 +51,8 @@ pub(super) struct JsonRpcError {
 #[derive(Debug, Deserialize)]
 struct ListSessionsParams {
     private_beach_id: String,
+    #[serde(flatten)]
+    filter: SessionFilter,
 }
 
 #[derive(Debug, Deserialize)]
@@ -103,6 +106,13 @@ struct DeletePairingParams {
     child_session_id: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/mcp",
+    tag = "agents",
+    request_body = JsonRpcRequest,
+    responses((status = 200, description = "JSON-RPC response", body = JsonRpcResponse))
+)]
 pub async fn handle_mcp(
     State(state): State<AppState>,
     token: AuthToken,
@@ -127,7 +137,10 @@ pub async fn handle_mcp(
                 return Json(resp);
             }
             match decode_params::<ListSessionsParams>(request.params) {
-                Ok(params) => match state.list_sessions(&params.private_beach_id).await {
+                Ok(params) => match state
+                    .list_sessions_filtered(&params.private_beach_id, &params.filter)
+                    .await
+                {
                     Ok(rows) => success(id, rows),
                     Err(err) => state_error(id, err),
                 },
@@ -369,6 +382,24 @@ fn state_error(id: Option<Value>, err: StateError) -> JsonRpcResponse {
             (-32603, "serialization error".into())
         }
         StateError::ControllerCommandRejected { reason } => (-32014, reason.code().into()),
+        StateError::ShareLinkNotFound => (-32016, "share link not found".into()),
+        StateError::ShareLinkRejected(rejection) => (-32017, rejection.code().into()),
+        StateError::InsufficientRole => (-32018, "insufficient private beach role".into()),
+        StateError::GroupNotFound => (-32019, "group not found".into()),
+        StateError::GroupNameConflict => (-32020, "group name already exists".into()),
+        StateError::FileNotFound => (-32021, "file not found".into()),
+        StateError::AutomationAssignmentNotFound => {
+            (-32022, "automation assignment not found".into())
+        }
+        StateError::AutomationAssignmentConflict => {
+            (-32023, "automation assignment already exists".into())
+        }
+        StateError::WebhookNotFound => (-32024, "webhook not found".into()),
+        StateError::WebhookDeadLetterNotFound => (-32025, "webhook dead letter not found".into()),
+        StateError::InvalidAction { action_id, error } => (
+            -32602,
+            format!("{}: action {action_id}: {error}", error.code()),
+        ),
         StateError::External(message) => {
             error!(message = %message, "external service error while processing MCP request");
             (-32012, "external service error".into())
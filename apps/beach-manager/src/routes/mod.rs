@@ -1,20 +1,26 @@
 mod auth;
+mod files;
 mod mcp;
+mod openapi;
 mod private_beaches;
 mod sessions;
 mod sse;
 
 use axum::{
+    extract::DefaultBodyLimit,
     response::{IntoResponse, Response},
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
 use serde::Serialize;
 use tower_http::cors::{Any, CorsLayer};
+use utoipa::ToSchema;
 
-use crate::state::AppState;
+use crate::state::{AppState, MAX_FILE_BYTES};
 
 pub use auth::AuthToken;
+pub use files::*;
+pub use openapi::ApiDoc;
 pub use private_beaches::*;
 pub use sessions::*;
 
@@ -23,9 +29,15 @@ pub fn build_router(state: AppState) -> Router {
         .route("/healthz", get(health_check))
         .route("/readyz", get(health_check))
         .route("/metrics", get(sse::prometheus_metrics))
+        .route("/openapi.json", get(openapi::openapi_json))
         .route("/sessions/register", post(register_session))
         .route("/sessions/:session_id", patch(update_session))
         .route("/sessions/:session_id/join", post(join_session))
+        .route("/sessions/:session_id/tags", post(add_session_tag))
+        .route(
+            "/sessions/:session_id/tags/:tag",
+            delete(remove_session_tag),
+        )
         .route(
             "/sessions/:session_id/state",
             // Allow dev insecure token to hit state snapshot/push.
@@ -118,6 +130,79 @@ pub fn build_router(state: AppState) -> Router {
             "/private-beaches/:id/controller-assignments/batch",
             post(batch_controller_assignments),
         )
+        .route(
+            "/private-beaches/:id/share-links",
+            get(list_share_links).post(create_share_link),
+        )
+        .route(
+            "/private-beaches/:id/share-links/:link_id",
+            delete(revoke_share_link),
+        )
+        .route(
+            "/private-beaches/:id/groups",
+            get(list_groups).post(create_group),
+        )
+        .route(
+            "/private-beaches/:id/groups/:group_id",
+            patch(update_group).delete(delete_group),
+        )
+        .route(
+            "/private-beaches/:id/groups/:group_id/members",
+            get(list_group_members),
+        )
+        .route(
+            "/private-beaches/:id/groups/:group_id/members/:account_id",
+            put(put_group_member).delete(delete_group_member),
+        )
+        .route(
+            "/private-beaches/:id/automation-assignments",
+            get(list_automation_assignments).post(create_automation_assignment),
+        )
+        .route(
+            "/private-beaches/:id/automation-assignments/:assignment_id",
+            patch(update_automation_assignment).delete(delete_automation_assignment),
+        )
+        .route(
+            "/private-beaches/:id/webhooks",
+            get(list_webhooks).post(create_webhook),
+        )
+        .route(
+            "/private-beaches/:id/webhooks/:webhook_id",
+            patch(update_webhook).delete(delete_webhook),
+        )
+        .route(
+            "/private-beaches/:id/webhook-dead-letters",
+            get(list_webhook_dead_letters),
+        )
+        .route(
+            "/private-beaches/:id/webhook-dead-letters/:dead_letter_id/replay",
+            post(replay_webhook_dead_letter),
+        )
+        .route(
+            "/private-beaches/:id/controller-audit/export",
+            get(export_controller_audit),
+        )
+        .route(
+            "/private-beaches/:id/controller-audit/summary",
+            get(controller_audit_summary),
+        )
+        .route("/private-beaches/:id/files", get(list_files))
+        .route(
+            "/private-beaches/:id/files/*path",
+            get(download_file)
+                .put(upload_file)
+                .delete(delete_file)
+                .layer(DefaultBodyLimit::max(MAX_FILE_BYTES)),
+        )
+        .route(
+            "/private-beaches/:id/file-versions/*path",
+            get(list_file_versions),
+        )
+        .route(
+            "/sessions/:session_id/files/*path",
+            put(upload_session_artifact).layer(DefaultBodyLimit::max(MAX_FILE_BYTES)),
+        )
+        .route("/share-links/redeem", post(redeem_share_link))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -143,11 +228,12 @@ pub enum ApiError {
     PreconditionFailed { message: String, code: &'static str },
     TooManyRequests(&'static str),
     BadRequest(String),
+    BadRequestWithCode { message: String, code: &'static str },
     Upstream(&'static str),
     Internal,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 struct ApiErrorBody<'a> {
     error: &'a str,
     message: Option<String>,
@@ -230,6 +316,15 @@ impl IntoResponse for ApiError {
                 }),
             )
                 .into_response(),
+            ApiError::BadRequestWithCode { message, code } => (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(ApiErrorBody {
+                    error: "bad_request",
+                    message: Some(message),
+                    error_code: Some(code.to_string()),
+                }),
+            )
+                .into_response(),
             ApiError::Upstream(msg) => (
                 axum::http::StatusCode::BAD_GATEWAY,
                 Json(ApiErrorBody {
@@ -0,0 +1,901 @@
+use axum::{http::header, response::IntoResponse};
+use once_cell::sync::Lazy;
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        ContentBuilder, Ref, RefOr, ResponseBuilder,
+    },
+    Modify, OpenApi,
+};
+
+use super::{files, mcp, private_beaches, sessions, sse, ApiErrorBody};
+use crate::{audit, state};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Beach Manager API",
+        description = "Control plane for private beaches, sessions, controllers and automation."
+    ),
+    paths(
+        sessions::register_session,
+        sessions::update_session,
+        sessions::join_session,
+        sessions::add_session_tag,
+        sessions::remove_session_tag,
+        sessions::fetch_state_snapshot,
+        sessions::push_state,
+        sse::stream_state,
+        sse::stream_devtools,
+        sessions::queue_actions,
+        sessions::poll_actions,
+        sessions::pending_actions,
+        sessions::ack_actions,
+        sessions::update_transport_status,
+        sessions::acquire_controller,
+        sessions::release_controller,
+        sessions::issue_controller_handshake,
+        sessions::revoke_controller_handshake,
+        sessions::list_controller_events,
+        sessions::list_controller_pairings_route,
+        sessions::create_controller_pairing,
+        sse::stream_controller_pairings,
+        sessions::delete_controller_pairing,
+        sessions::signal_health,
+        sessions::list_sessions,
+        sessions::attach_by_code,
+        sessions::attach_owned,
+        private_beaches::get_viewer_credential,
+        sessions::emergency_stop,
+        sessions::onboard_agent,
+        mcp::handle_mcp,
+        private_beaches::list_private_beaches,
+        private_beaches::create_private_beach,
+        private_beaches::get_private_beach,
+        private_beaches::update_private_beach,
+        private_beaches::delete_private_beach,
+        private_beaches::get_private_beach_layout,
+        private_beaches::put_private_beach_layout,
+        private_beaches::showcase_preflight,
+        private_beaches::install_session_graph,
+        private_beaches::batch_controller_assignments,
+        private_beaches::list_share_links,
+        private_beaches::create_share_link,
+        private_beaches::revoke_share_link,
+        private_beaches::redeem_share_link,
+        private_beaches::list_groups,
+        private_beaches::create_group,
+        private_beaches::update_group,
+        private_beaches::delete_group,
+        private_beaches::list_group_members,
+        private_beaches::put_group_member,
+        private_beaches::delete_group_member,
+        private_beaches::list_automation_assignments,
+        private_beaches::create_automation_assignment,
+        private_beaches::update_automation_assignment,
+        private_beaches::delete_automation_assignment,
+        private_beaches::list_webhooks,
+        private_beaches::create_webhook,
+        private_beaches::update_webhook,
+        private_beaches::delete_webhook,
+        private_beaches::list_webhook_dead_letters,
+        private_beaches::replay_webhook_dead_letter,
+        private_beaches::export_controller_audit,
+        private_beaches::controller_audit_summary,
+        files::list_files,
+        files::download_file,
+        files::upload_file,
+        files::delete_file,
+        files::list_file_versions,
+        files::upload_session_artifact,
+    ),
+    components(schemas(
+        ApiErrorBody,
+        // Harness wire types shared with beach-buggy.
+        beach_buggy::RegisterSessionRequest,
+        beach_buggy::RegisterSessionResponse,
+        beach_buggy::HarnessType,
+        beach_buggy::TransportMode,
+        beach_buggy::StateDiff,
+        beach_buggy::ActionCommand,
+        beach_buggy::ActionAck,
+        beach_buggy::AckStatus,
+        beach_buggy::HealthHeartbeat,
+        beach_buggy::FileRecord,
+        // REST models shared with manager-sdk.
+        manager_sdk::models::CreateBeachRequest,
+        manager_sdk::models::UpdateBeachRequest,
+        manager_sdk::models::BeachSummary,
+        manager_sdk::models::BeachMeta,
+        manager_sdk::models::CanvasPoint,
+        manager_sdk::models::CanvasSize,
+        manager_sdk::models::CanvasViewport,
+        manager_sdk::models::CanvasTileNode,
+        manager_sdk::models::CanvasAgentNode,
+        manager_sdk::models::CanvasGroupNode,
+        manager_sdk::models::CanvasAssignment,
+        manager_sdk::models::CanvasAgentUpdateMode,
+        manager_sdk::models::CanvasAgentRelationship,
+        manager_sdk::models::CanvasMetadata,
+        manager_sdk::models::CanvasLayout,
+        manager_sdk::models::SessionTagRequest,
+        manager_sdk::models::SessionTagsResponse,
+        manager_sdk::models::AttachByCodeRequest,
+        manager_sdk::models::AttachOwnedRequest,
+        manager_sdk::models::AttachOwnedResponse,
+        manager_sdk::models::ControllerLeaseRequest,
+        manager_sdk::models::ControllerLeaseResponse,
+        manager_sdk::models::ReleaseControllerRequest,
+        manager_sdk::models::QueueActionsRequest,
+        manager_sdk::models::PendingActionsResponse,
+        // Manager state types returned directly by handlers.
+        state::SessionSummary,
+        state::SessionKind,
+        state::SessionStatus,
+        state::ControllerEvent,
+        state::ControllerEventType,
+        state::ControllerUpdateCadence,
+        state::PairingTransportKind,
+        state::PairingTransportStatus,
+        state::ControllerPairing,
+        state::AgentOnboardResponse,
+        state::McpBridge,
+        state::JoinSessionResponsePayload,
+        state::AdvertisedTransport,
+        state::AdvertisedTransportKind,
+        state::ControllerAutoAttachHint,
+        state::IdlePublishTokenHint,
+        state::ShareLink,
+        state::CreatedShareLink,
+        state::ShareLinkRedemption,
+        state::MembershipRole,
+        state::BeachGroup,
+        state::GroupGrant,
+        state::GroupMember,
+        state::GroupRole,
+        state::AutomationAssignment,
+        state::AutomationRole,
+        state::AssignmentStatus,
+        state::AssignmentState,
+        state::WebhookSubscription,
+        state::WebhookUpdate,
+        state::WebhookDeadLetter,
+        audit::AuditFormat,
+        audit::AuditRecordKind,
+        audit::ControllerAuditRecord,
+        audit::ControllerAuditSummary,
+        audit::AccountAuditSummary,
+        // Route-local request and response bodies.
+        sessions::SessionUpdateRequest,
+        sessions::OnboardAgentRequest,
+        sessions::EmergencyStopRequest,
+        sessions::CreateControllerPairingRequest,
+        sessions::JoinSessionRequestBody,
+        sessions::ControllerHandshakeRequest,
+        sessions::ControllerHandshakeKind,
+        sessions::ControllerHandshakeResponse,
+        sessions::TransportStatusUpdateRequest,
+        sessions::AttachByCodeResponse,
+        private_beaches::CreateShareLinkRequest,
+        private_beaches::RedeemShareLinkRequest,
+        private_beaches::CreateGroupRequest,
+        private_beaches::UpdateGroupRequest,
+        private_beaches::GroupMemberRequest,
+        private_beaches::CreateAutomationAssignmentRequest,
+        private_beaches::UpdateAutomationAssignmentRequest,
+        private_beaches::CreateWebhookRequest,
+        private_beaches::ViewerCredentialResponse,
+        private_beaches::BatchAssignmentItem,
+        private_beaches::BatchAssignmentResultItem,
+        private_beaches::BatchAssignmentsRequest,
+        private_beaches::BatchAssignmentsResponse,
+        private_beaches::SessionGraphRequest,
+        private_beaches::SessionGraphTile,
+        private_beaches::SessionGraphNodeType,
+        private_beaches::SessionGraphTileSession,
+        private_beaches::SessionGraphAgentSpec,
+        private_beaches::SessionGraphAgentTrace,
+        private_beaches::SessionGraphRelationship,
+        private_beaches::SessionGraphAttachmentResult,
+        private_beaches::SessionGraphPairingResult,
+        private_beaches::SessionGraphResponse,
+        private_beaches::ShowcasePreflightIssue,
+        private_beaches::ShowcasePreflightResponse,
+        mcp::JsonRpcRequest,
+        mcp::JsonRpcResponse,
+        mcp::JsonRpcError,
+    )),
+    tags(
+        (name = "sessions", description = "Harness registration, session metadata and state"),
+        (name = "controllers", description = "Controller leases, action queues and pairings"),
+        (name = "streams", description = "Server-sent event streams"),
+        (name = "agents", description = "Agent onboarding and the MCP bridge"),
+        (name = "private-beaches", description = "Private beach CRUD, layout and session graphs"),
+        (name = "access", description = "Share links, groups and memberships"),
+        (name = "automation", description = "Automation assignments"),
+        (name = "webhooks", description = "Outbound webhook subscriptions and dead letters"),
+        (name = "files", description = "Private beach file store"),
+    ),
+    security(("bearer" = [])),
+    modifiers(&BearerAuth, &ErrorResponses),
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Status codes any handler can produce through `ApiError` or axum's
+/// extractor rejections. Handlers only document their success response; the
+/// shared failures are attached here so the two cannot drift.
+const ERROR_RESPONSES: &[(&str, &str, bool)] = &[
+    ("400", "Malformed request", true),
+    ("401", "Missing or invalid bearer token", true),
+    ("403", "Token lacks the required scope or membership", true),
+    ("404", "Resource not found", true),
+    ("409", "Conflicting state", true),
+    ("412", "Precondition failed", true),
+    ("413", "Request body too large", false),
+    ("415", "Unsupported content type", false),
+    ("422", "Request body failed to deserialize", false),
+    ("429", "Rate limited", true),
+    ("500", "Internal error", true),
+    ("502", "Upstream dependency failed", true),
+];
+
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (status, description, has_body) in ERROR_RESPONSES {
+            let mut response = ResponseBuilder::new().description(*description);
+            if *has_body {
+                response = response.content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Ref::from_schema_name("ApiErrorBody"))
+                        .build(),
+                );
+            }
+            components
+                .responses
+                .insert(error_response_name(status), RefOr::T(response.build()));
+        }
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in item.operations.values_mut() {
+                for (status, _, _) in ERROR_RESPONSES {
+                    operation
+                        .responses
+                        .responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| {
+                            RefOr::Ref(Ref::new(format!(
+                                "#/components/responses/{}",
+                                error_response_name(status)
+                            )))
+                        });
+                }
+            }
+        }
+    }
+}
+
+fn error_response_name(status: &str) -> String {
+    format!("Error{status}")
+}
+
+static SPEC: Lazy<String> = Lazy::new(|| {
+    ApiDoc::openapi()
+        .to_json()
+        .expect("openapi document serializes")
+});
+
+pub async fn openapi_json() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], SPEC.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::build_router;
+    use crate::state::AppState;
+    use axum::{
+        body::{self, Body},
+        http::{Method, Request, StatusCode},
+        Router,
+    };
+    use serde_json::{json, Value};
+    use std::collections::{BTreeSet, HashMap};
+    use std::time::Duration;
+    use tower::util::ServiceExt;
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    /// Values substituted for path parameters; unknown ids fall back to a
+    /// nil UUID, which handlers answer with a documented 404.
+    const PLACEHOLDERS: &[(&str, &str)] = &[
+        ("id", BEACH_ID),
+        ("private_beach_id", BEACH_ID),
+        ("session_id", SESSION_ID),
+        ("controller_id", SESSION_ID),
+        ("child_session_id", SESSION_ID),
+        ("path", "contract/notes.txt"),
+        ("tag", "contract"),
+    ];
+    const FALLBACK_ID: &str = "00000000-0000-0000-0000-000000000000";
+    // The in-memory backend has no beach table; any id scopes the session.
+    const BEACH_ID: &str = "5be1c0de-0000-4000-8000-000000000001";
+    const SESSION_ID: &str = "sess-contract";
+
+    fn concrete_uri(path: &str) -> String {
+        path.split('/')
+            .map(|segment| match segment.strip_prefix('{') {
+                Some(name) => {
+                    let name = name.trim_end_matches('}');
+                    PLACEHOLDERS
+                        .iter()
+                        .find(|(key, _)| *key == name)
+                        .map_or(FALLBACK_ID, |(_, value)| value)
+                        .to_string()
+                }
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn documented_operations(spec: &Value) -> BTreeSet<(String, String)> {
+        let mut operations = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                operations.insert((method.to_uppercase(), path.clone()));
+            }
+        }
+        operations
+    }
+
+    /// Operational endpoints that are intentionally left out of the document.
+    const UNDOCUMENTED: &[&str] = &["/healthz", "/readyz", "/metrics", "/openapi.json"];
+
+    /// Extracts the paths registered in `build_router`, in OpenAPI form, from
+    /// its source so a new `.route(...)` cannot ship without a matching
+    /// `#[utoipa::path]`.
+    fn routed_paths() -> BTreeSet<String> {
+        let source = include_str!("mod.rs");
+        let start = source.find("pub fn build_router").unwrap();
+        let end = start + source[start..].find(".with_state(state)").unwrap();
+        source[start..end]
+            .split(".route(")
+            .skip(1)
+            .map(|call| openapi_path(call.split('"').nth(1).unwrap()))
+            .collect()
+    }
+
+    fn openapi_path(axum_path: &str) -> String {
+        axum_path
+            .split('/')
+            .map(|segment| match segment.strip_prefix([':', '*']) {
+                Some(name) => format!("{{{name}}}"),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Every routed path must be documented and every documented path routed.
+    /// The router then answers a method nobody routes with 405 and the routed
+    /// methods in `Allow`, which must be exactly the documented ones.
+    #[tokio::test]
+    async fn every_route_is_documented() {
+        let spec = spec();
+        let app = build_router(AppState::new());
+        let documented = documented_operations(&spec);
+        let routed: BTreeSet<String> = routed_paths()
+            .into_iter()
+            .filter(|path| !UNDOCUMENTED.contains(&path.as_str()))
+            .collect();
+        assert!(routed.len() > 50, "route parser found {}", routed.len());
+        let paths: BTreeSet<String> = documented.iter().map(|(_, path)| path.clone()).collect();
+
+        let missing: Vec<_> = routed.difference(&paths).collect();
+        assert!(
+            missing.is_empty(),
+            "routes without #[utoipa::path] (add them to ApiDoc): {missing:?}"
+        );
+        let stale: Vec<_> = paths.difference(&routed).collect();
+        assert!(
+            stale.is_empty(),
+            "documented paths with no route: {stale:?}"
+        );
+
+        for path in paths {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::TRACE)
+                        .uri(concrete_uri(&path))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{path} does not reach its route"
+            );
+            let allow = response
+                .headers()
+                .get(header::ALLOW)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default();
+            let routed: BTreeSet<String> = allow
+                .split(',')
+                .map(|method| method.trim().to_string())
+                .filter(|method| !method.is_empty() && method != "HEAD")
+                .collect();
+            let expected: BTreeSet<String> = documented
+                .iter()
+                .filter(|(_, documented_path)| *documented_path == path)
+                .map(|(method, _)| method.clone())
+                .collect();
+            assert_eq!(
+                routed, expected,
+                "{path}: routed methods differ from #[utoipa::path] operations"
+            );
+        }
+    }
+
+    fn collect_refs(value: &Value, refs: &mut BTreeSet<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    refs.insert(target.clone());
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    fn resolve<'a>(spec: &'a Value, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        spec.pointer(pointer)
+    }
+
+    #[test]
+    fn every_reference_resolves() {
+        let spec = spec();
+        let mut refs = BTreeSet::new();
+        collect_refs(&spec, &mut refs);
+        let dangling: Vec<_> = refs
+            .iter()
+            .filter(|reference| resolve(&spec, reference).is_none())
+            .collect();
+        assert!(
+            dangling.is_empty(),
+            "unresolved $refs (register the type in ApiDoc components): {dangling:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn openapi_json_is_served() {
+        let response = build_router(AppState::new())
+            .oneshot(
+                Request::builder()
+                    .uri("/openapi.json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let served: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(served, spec());
+    }
+
+    /// Checks `value` against the subset of JSON Schema utoipa emits for our
+    /// types: refs, allOf/oneOf, nullable, and typed objects/arrays/scalars.
+    fn conforms(spec: &Value, schema: &Value, value: &Value) -> Result<(), String> {
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            let target = resolve(spec, reference).ok_or_else(|| reference.clone())?;
+            return conforms(spec, target, value);
+        }
+        if value.is_null() && schema.get("nullable") == Some(&Value::Bool(true)) {
+            return Ok(());
+        }
+        if let Some(Value::Array(parts)) = schema.get("allOf") {
+            for part in parts {
+                conforms(spec, part, value)?;
+            }
+        }
+        if let Some(Value::Array(options)) = schema.get("oneOf") {
+            if !options.iter().any(|o| conforms(spec, o, value).is_ok()) {
+                return Err(format!("{value} matches no oneOf variant"));
+            }
+        }
+        if let Some(Value::Array(variants)) = schema.get("enum") {
+            if !variants.contains(value) {
+                return Err(format!("{value} is not one of {variants:?}"));
+            }
+        }
+        let Some(kind) = schema.get("type").and_then(Value::as_str) else {
+            return Ok(());
+        };
+        let matches = match kind {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("expected {kind}, got {value}"));
+        }
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::Array(required)) = schema.get("required") {
+                    for field in required.iter().filter_map(Value::as_str) {
+                        if !map.contains_key(field) {
+                            return Err(format!("missing required field `{field}`"));
+                        }
+                    }
+                }
+                if let Some(Value::Object(properties)) = schema.get("properties") {
+                    for (field, property) in properties {
+                        if let Some(field_value) = map.get(field) {
+                            conforms(spec, property, field_value)
+                                .map_err(|err| format!("{field}: {err}"))?;
+                        }
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for item in items {
+                        conforms(spec, item_schema, item)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// A valid body for every documented operation that takes JSON, keyed by
+    /// `"METHOD /path"`. Each one is checked against the request schema, and
+    /// the handler must get past body extraction with it.
+    fn request_fixtures() -> HashMap<&'static str, Value> {
+        let now = json!({ "secs_since_epoch": 1_700_000_000u64, "nanos_since_epoch": 0 });
+        HashMap::from([
+            (
+                "POST /agents/onboard",
+                json!({ "session_id": SESSION_ID, "template_id": "pong" }),
+            ),
+            (
+                "POST /mcp",
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+            ),
+            ("POST /private-beaches", json!({ "name": "Contract Beach" })),
+            ("PATCH /private-beaches/{id}", json!({ "name": "Renamed" })),
+            (
+                "POST /private-beaches/{id}/automation-assignments",
+                json!({
+                    "controller_account_id": FALLBACK_ID,
+                    "role": "observer",
+                    "config": {},
+                }),
+            ),
+            (
+                "PATCH /private-beaches/{id}/automation-assignments/{assignment_id}",
+                json!({ "role": "controller" }),
+            ),
+            (
+                "POST /private-beaches/{id}/controller-assignments/batch",
+                json!({
+                    "assignments": [{
+                        "controller_session_id": SESSION_ID,
+                        "child_session_id": SESSION_ID,
+                        "update_cadence": "balanced",
+                    }],
+                }),
+            ),
+            (
+                "POST /private-beaches/{id}/groups",
+                json!({
+                    "name": "operators",
+                    "grants": [{ "scope": "pb:sessions.read" }],
+                }),
+            ),
+            (
+                "PATCH /private-beaches/{id}/groups/{group_id}",
+                json!({ "description": "on call" }),
+            ),
+            (
+                "PUT /private-beaches/{id}/groups/{group_id}/members/{account_id}",
+                json!({ "role": "member" }),
+            ),
+            (
+                "PUT /private-beaches/{id}/layout",
+                json!({
+                    "version": 3,
+                    "viewport": { "zoom": 1.0, "pan": { "x": 0.0, "y": 0.0 } },
+                    "tiles": {},
+                    "agents": {},
+                    "groups": {},
+                    "controlAssignments": {},
+                    "metadata": { "createdAt": 1_700_000_000_000i64, "updatedAt": 1_700_000_000_000i64 },
+                }),
+            ),
+            (
+                "POST /private-beaches/{id}/session-graph",
+                json!({ "tiles": [], "relationships": [] }),
+            ),
+            (
+                "POST /private-beaches/{id}/share-links",
+                json!({ "role": "viewer", "max_uses": 1 }),
+            ),
+            (
+                "POST /private-beaches/{id}/webhooks",
+                json!({
+                    "url": "https://hooks.example.com/beach",
+                    "event_types": ["controller.*"],
+                }),
+            ),
+            (
+                "PATCH /private-beaches/{id}/webhooks/{webhook_id}",
+                json!({ "active": false }),
+            ),
+            (
+                "POST /private-beaches/{private_beach_id}/sessions/attach",
+                json!({ "origin_session_ids": [SESSION_ID] }),
+            ),
+            (
+                "POST /private-beaches/{private_beach_id}/sessions/attach-by-code",
+                json!({ "session_id": SESSION_ID, "code": "123456" }),
+            ),
+            (
+                "POST /sessions/register",
+                json!({
+                    "session_id": SESSION_ID,
+                    "private_beach_id": BEACH_ID,
+                    "harness_type": "terminal_shim",
+                    "capabilities": [],
+                    "metadata": null,
+                    "version": "0.1.0",
+                }),
+            ),
+            (
+                "POST /sessions/{controller_id}/controllers",
+                json!({ "child_session_id": SESSION_ID, "update_cadence": "fast" }),
+            ),
+            (
+                "PATCH /sessions/{session_id}",
+                json!({ "location_hint": "us-east" }),
+            ),
+            (
+                "POST /sessions/{session_id}/actions",
+                json!({
+                    "controller_token": FALLBACK_ID,
+                    "actions": [{ "id": "a-1", "action_type": "key", "payload": { "key": "enter" } }],
+                }),
+            ),
+            (
+                "POST /sessions/{session_id}/actions/ack",
+                json!([{ "id": "a-1", "status": "ok", "applied_at": now }]),
+            ),
+            (
+                "DELETE /sessions/{session_id}/controller-handshake",
+                json!({ "controller_token": FALLBACK_ID }),
+            ),
+            (
+                "POST /sessions/{session_id}/controller-handshake",
+                json!({ "passcode": "123456" }),
+            ),
+            (
+                "DELETE /sessions/{session_id}/controller/lease",
+                json!({ "controller_token": FALLBACK_ID }),
+            ),
+            (
+                "POST /sessions/{session_id}/controller/lease",
+                json!({ "ttl_ms": 30_000, "reason": "contract" }),
+            ),
+            (
+                "POST /sessions/{session_id}/emergency-stop",
+                json!({ "reason": "contract" }),
+            ),
+            (
+                "POST /sessions/{session_id}/health",
+                json!({ "queue_depth": 0, "degraded": false, "warnings": [] }),
+            ),
+            (
+                "POST /sessions/{session_id}/join",
+                json!({ "passphrase": "123456", "mcp": false }),
+            ),
+            (
+                "POST /sessions/{session_id}/state",
+                json!({ "sequence": 1, "emitted_at": now, "payload": { "rows": [] } }),
+            ),
+            (
+                "POST /sessions/{session_id}/tags",
+                json!({ "tag": "contract" }),
+            ),
+            (
+                "POST /sessions/{session_id}/transport-status",
+                json!({ "transport": "http_fallback", "latency_ms": 12 }),
+            ),
+            ("POST /share-links/redeem", json!({ "token": "contract" })),
+        ])
+    }
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<(&str, Vec<u8>)>,
+    ) -> Option<(StatusCode, Vec<u8>, Option<String>)> {
+        let mut request = Request::builder()
+            .method(Method::from_bytes(method.as_bytes()).unwrap())
+            .uri(uri)
+            .header("authorization", "Bearer test-token");
+        let body = match body {
+            Some((content_type, bytes)) => {
+                request = request.header("content-type", content_type);
+                Body::from(bytes)
+            }
+            None => Body::empty(),
+        };
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            app.clone().oneshot(request.body(body).unwrap()),
+        )
+        .await
+        .ok()?
+        .unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        if content_type.as_deref() == Some("text/event-stream") {
+            return Some((status, Vec::new(), content_type));
+        }
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        Some((status, bytes.to_vec(), content_type))
+    }
+
+    /// Exercises every documented operation against the in-memory backend and
+    /// checks that the router serves it within the timeout, that the request
+    /// fixture passes extraction, that the status is documented, and that
+    /// successful JSON bodies match the documented schema.
+    #[tokio::test]
+    async fn documented_routes_honour_contract() {
+        let spec = spec();
+        let app = build_router(AppState::new());
+        let fixtures = request_fixtures();
+
+        let register = fixtures["POST /sessions/register"].to_string().into_bytes();
+        let (status, _, _) = send(
+            &app,
+            "POST",
+            "/sessions/register",
+            Some(("application/json", register)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+
+        // Reads first so the seeded beach and session are still present, and
+        // deletes last.
+        let mut operations: Vec<(String, String, Value)> = Vec::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for (method, operation) in item.as_object().unwrap() {
+                operations.push((method.clone(), path.clone(), operation.clone()));
+            }
+        }
+        let rank = |method: &str| match method {
+            "get" => 0,
+            "delete" => 2,
+            _ => 1,
+        };
+        operations.sort_by_key(|(method, _, _)| rank(method));
+
+        let mut used = BTreeSet::new();
+        for (method, path, operation) in operations {
+            let label = format!("{} {}", method.to_uppercase(), path);
+            let request_body = if let Some(schema) =
+                operation.pointer("/requestBody/content/application~1json/schema")
+            {
+                let fixture = fixtures
+                    .get(label.as_str())
+                    .unwrap_or_else(|| panic!("{label} has no request fixture"));
+                if let Err(err) = conforms(&spec, schema, fixture) {
+                    panic!("{label} fixture does not match its request schema: {err}");
+                }
+                used.insert(label.clone());
+                Some(("application/json", fixture.to_string().into_bytes()))
+            } else if operation
+                .pointer("/requestBody/content/application~1octet-stream")
+                .is_some()
+            {
+                Some(("application/octet-stream", b"contract".to_vec()))
+            } else {
+                None
+            };
+
+            let Some((status, bytes, content_type)) = send(
+                &app,
+                &method.to_uppercase(),
+                &concrete_uri(&path),
+                request_body,
+            )
+            .await
+            else {
+                panic!("{label} did not respond within 5s");
+            };
+            assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{label}");
+            assert!(
+                !(status == StatusCode::NOT_FOUND && bytes.is_empty()),
+                "{label} is documented but not routed"
+            );
+            assert!(
+                !matches!(
+                    status,
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE | StatusCode::UNPROCESSABLE_ENTITY
+                ),
+                "{label} rejected its fixture: {}",
+                String::from_utf8_lossy(&bytes)
+            );
+            let documented = operation["responses"]
+                .get(status.as_str())
+                .unwrap_or_else(|| panic!("{label} returned undocumented {status}"));
+            let documented = match documented.get("$ref").and_then(Value::as_str) {
+                Some(reference) => resolve(&spec, reference).unwrap(),
+                None => documented,
+            };
+
+            let is_json = content_type
+                .as_deref()
+                .is_some_and(|value| value.starts_with("application/json"));
+            if status.is_success() && is_json {
+                let schema = documented
+                    .pointer("/content/application~1json/schema")
+                    .unwrap_or_else(|| panic!("{label} returned undocumented JSON"));
+                let value: Value = serde_json::from_slice(&bytes).unwrap();
+                if let Err(err) = conforms(&spec, schema, &value) {
+                    panic!("{label} response does not match schema: {err}\n{value}");
+                }
+            }
+        }
+        let stale: Vec<_> = fixtures
+            .keys()
+            .filter(|label| !used.contains(**label))
+            .collect();
+        assert!(
+            stale.is_empty(),
+            "fixtures for undocumented operations: {stale:?}"
+        );
+    }
+}
@@ -2,7 +2,8 @@ use axum::{
     async_trait,
     body::Body,
     extract::{rejection::JsonRejection, FromRequest, Path, Query, State},
-    http::{HeaderMap, Request},
+    http::{header, HeaderMap, HeaderValue, Request},
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
@@ -11,244 +12,110 @@ use std::collections::{HashMap, HashSet};
 use std::env;
 use std::sync::Arc;
 use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::audit::{self, AuditFilter, AuditFormat, ControllerAuditRecord, ControllerAuditSummary};
 use crate::state::{
-    AppState, AttachHandshakeDisposition, ControllerPairing, ControllerUpdateCadence,
-    SessionSummary, StateError, ViewerTokenError,
+    AppState, AssignmentConfig, AttachHandshakeDisposition, AutomationAssignment, AutomationRole,
+    BeachGroup, ControllerPairing, ControllerUpdateCadence, CreatedShareLink, GroupGrant,
+    GroupMember, GroupRole, MembershipRole, SessionSummary, ShareLink, ShareLinkRedemption,
+    StateError, ViewerTokenError, WebhookDeadLetter, WebhookSubscription, WebhookUpdate,
 };
 
-use super::{sessions::ensure_scope, ApiError, ApiResult, AuthToken};
+use super::{
+    sessions::{ensure_scope, ensure_session_scope},
+    ApiError, ApiResult, AuthToken,
+};
 
-#[derive(Debug, Deserialize)]
-pub struct CreateBeachRequest {
-    pub name: String,
-    #[serde(default)]
-    pub slug: Option<String>,
-}
+pub use manager_sdk::models::{
+    BeachMeta, BeachSummary, CanvasAgentNode, CanvasAgentRelationship, CanvasAgentUpdateMode,
+    CanvasAssignment, CanvasGroupNode, CanvasLayout, CanvasMetadata, CanvasPoint, CanvasSize,
+    CanvasTileNode, CanvasViewport, CreateBeachRequest, UpdateBeachRequest,
+};
 
-#[derive(Debug, Deserialize)]
-pub struct UpdateBeachRequest {
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateShareLinkRequest {
     #[serde(default)]
-    pub name: Option<String>,
+    pub label: Option<String>,
+    #[serde(default = "default_share_link_role")]
+    pub role: MembershipRole,
     #[serde(default)]
-    pub slug: Option<String>,
+    pub max_uses: Option<i32>,
     #[serde(default)]
-    pub settings: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct BeachSummary {
-    pub id: String,
-    pub name: String,
-    pub slug: String,
-    pub created_at: i64,
-}
-
-#[derive(Debug, Serialize)]
-pub struct BeachMeta {
-    pub id: String,
-    pub name: String,
-    pub slug: String,
-    pub settings: serde_json::Value,
-    pub created_at: i64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasPoint {
-    pub x: f64,
-    pub y: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasSize {
-    pub width: f64,
-    pub height: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasViewport {
-    pub zoom: f64,
-    pub pan: CanvasPoint,
+    pub expires_at_ms: Option<i64>,
 }
 
-impl Default for CanvasViewport {
-    fn default() -> Self {
-        Self {
-            zoom: 1.0,
-            pan: CanvasPoint::default(),
-        }
-    }
+fn default_share_link_role() -> MembershipRole {
+    MembershipRole::Viewer
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasTileNode {
-    pub id: String,
-    pub position: CanvasPoint,
-    pub size: CanvasSize,
-    pub z_index: i32,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub group_id: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub zoom: Option<f64>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub locked: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub toolbar_pinned: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RedeemShareLinkRequest {
+    pub token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasAgentNode {
-    pub id: String,
-    pub position: CanvasPoint,
-    pub size: CanvasSize,
-    pub z_index: i32,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub icon: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub status: Option<String>,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateGroupRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub grants: Vec<GroupGrant>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasGroupNode {
-    pub id: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateGroupRequest {
+    #[serde(default)]
     pub name: Option<String>,
     #[serde(default)]
-    pub member_ids: Vec<String>,
-    pub position: CanvasPoint,
-    pub size: CanvasSize,
-    pub z_index: i32,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub collapsed: Option<bool>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasAssignment {
-    pub controller_id: String,
-    pub target_type: String,
-    pub target_id: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum CanvasAgentUpdateMode {
-    #[serde(rename = "idle-summary")]
-    IdleSummary,
-    #[serde(rename = "push")]
-    Push,
-    #[serde(rename = "poll")]
-    Poll,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasAgentRelationship {
-    pub id: String,
-    pub source_id: String,
-    pub target_id: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub source_handle_id: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub target_handle_id: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub instructions: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub update_mode: Option<CanvasAgentUpdateMode>,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub poll_frequency: Option<i64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasMetadata {
-    pub created_at: i64,
-    pub updated_at: i64,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub migrated_from: Option<i64>,
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
-    pub agent_relationships: HashMap<String, CanvasAgentRelationship>,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub agent_relationship_order: Vec<String>,
-}
-
-impl Default for CanvasMetadata {
-    fn default() -> Self {
-        Self {
-            created_at: 0,
-            updated_at: 0,
-            migrated_from: None,
-            agent_relationships: HashMap::new(),
-            agent_relationship_order: Vec::new(),
-        }
-    }
+    pub description: Option<String>,
+    /// Replaces every grant on the group when present.
+    #[serde(default)]
+    pub grants: Option<Vec<GroupGrant>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CanvasLayout {
-    #[serde(default = "CanvasLayout::default_version")]
-    pub version: u8,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAutomationAssignmentRequest {
+    pub controller_account_id: String,
+    pub role: AutomationRole,
+    /// Omit to assign every session in the beach.
     #[serde(default)]
-    pub viewport: CanvasViewport,
+    pub session_id: Option<String>,
     #[serde(default)]
-    pub tiles: HashMap<String, CanvasTileNode>,
+    #[schema(value_type = Object)]
+    pub config: AssignmentConfig,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAutomationAssignmentRequest {
     #[serde(default)]
-    pub agents: HashMap<String, CanvasAgentNode>,
+    pub role: Option<AutomationRole>,
+    /// Replaces the whole config when present.
     #[serde(default)]
-    pub groups: HashMap<String, CanvasGroupNode>,
+    #[schema(value_type = Option<Object>)]
+    pub config: Option<AssignmentConfig>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<String>,
+    /// Generated when omitted; returned only in the create response.
     #[serde(default)]
-    pub control_assignments: HashMap<String, CanvasAssignment>,
+    pub secret: Option<String>,
     #[serde(default)]
-    pub metadata: CanvasMetadata,
+    pub description: Option<String>,
 }
 
-impl CanvasLayout {
-    const fn default_version() -> u8 {
-        3
-    }
-
-    pub fn empty(now_ms: i64) -> Self {
-        Self {
-            version: 3,
-            viewport: CanvasViewport::default(),
-            tiles: HashMap::new(),
-            agents: HashMap::new(),
-            groups: HashMap::new(),
-            control_assignments: HashMap::new(),
-            metadata: CanvasMetadata {
-                created_at: now_ms,
-                updated_at: now_ms,
-                migrated_from: None,
-                agent_relationships: HashMap::new(),
-                agent_relationship_order: Vec::new(),
-            },
-        }
-    }
-
-    pub fn ensure_version(self) -> Result<Self, String> {
-        if self.version != 3 {
-            return Err("layout version must be 3".into());
-        }
-        Ok(self)
-    }
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GroupMemberRequest {
+    #[serde(default = "default_group_role")]
+    pub role: GroupRole,
+}
 
-    pub fn with_updated_timestamp(mut self, now_ms: i64) -> Self {
-        if self.metadata.created_at == 0 {
-            self.metadata.created_at = now_ms;
-        }
-        self.metadata.updated_at = now_ms;
-        self
-    }
+fn default_group_role() -> GroupRole {
+    GroupRole::Member
 }
 
 /// JSON extractor that logs deserialization failures for canvas layout writes.
@@ -334,7 +201,7 @@ mod tests {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ViewerCredentialResponse {
     pub credential_type: &'static str,
     pub credential: String,
@@ -348,17 +215,17 @@ pub struct ViewerCredentialResponse {
     pub passcode: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BatchAssignmentItem {
     pub controller_session_id: String,
     pub child_session_id: String,
     #[serde(default)]
     pub prompt_template: Option<String>,
     #[serde(default)]
-    pub update_cadence: Option<crate::state::ControllerUpdateCadence>,
+    pub update_cadence: Option<ControllerUpdateCadence>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BatchAssignmentResultItem {
     pub controller_session_id: String,
     pub child_session_id: String,
@@ -366,20 +233,20 @@ pub struct BatchAssignmentResultItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pairing: Option<crate::state::ControllerPairing>,
+    pub pairing: Option<ControllerPairing>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BatchAssignmentsRequest {
     pub assignments: Vec<BatchAssignmentItem>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BatchAssignmentsResponse {
     pub results: Vec<BatchAssignmentResultItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphRequest {
     pub tiles: Vec<SessionGraphTile>,
@@ -391,7 +258,7 @@ pub struct SessionGraphRequest {
     pub clear_existing: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphTile {
     pub id: String,
@@ -417,7 +284,7 @@ pub struct SessionGraphTile {
     pub agent: Option<SessionGraphAgentSpec>,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionGraphNodeType {
     Application,
@@ -439,7 +306,7 @@ impl SessionGraphNodeType {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphTileSession {
     pub session_id: String,
@@ -448,7 +315,7 @@ pub struct SessionGraphTileSession {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphAgentSpec {
     pub role: String,
@@ -458,7 +325,7 @@ pub struct SessionGraphAgentSpec {
     pub trace: Option<SessionGraphAgentTrace>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphAgentTrace {
     pub enabled: bool,
@@ -466,7 +333,7 @@ pub struct SessionGraphAgentTrace {
     pub trace_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGraphRelationship {
     pub id: String,
@@ -488,7 +355,7 @@ pub struct SessionGraphRelationship {
     pub update_cadence: Option<ControllerUpdateCadence>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SessionGraphAttachmentResult {
     pub tile_id: String,
     pub session_id: String,
@@ -496,7 +363,7 @@ pub struct SessionGraphAttachmentResult {
     pub handshake_dispatched: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SessionGraphPairingResult {
     pub relationship_id: String,
     pub controller_session_id: String,
@@ -504,20 +371,21 @@ pub struct SessionGraphPairingResult {
     pub pairing: ControllerPairing,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SessionGraphResponse {
     pub layout: CanvasLayout,
     pub attachments: Vec<SessionGraphAttachmentResult>,
     pub pairings: Vec<SessionGraphPairingResult>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ShowcasePreflightQuery {
     #[serde(default)]
     pub refresh: Option<u8>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ShowcasePreflightIssue {
     pub code: String,
     pub severity: String,
@@ -526,7 +394,7 @@ pub struct ShowcasePreflightIssue {
     pub remediation: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ShowcasePreflightResponse {
     pub status: String,
     pub issues: Vec<ShowcasePreflightIssue>,
@@ -534,6 +402,14 @@ pub struct ShowcasePreflightResponse {
     pub cached: Option<bool>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/controller-assignments/batch",
+    tag = "controllers",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = BatchAssignmentsRequest,
+    responses((status = 200, description = "Per-assignment results", body = BatchAssignmentsResponse))
+)]
 pub async fn batch_controller_assignments(
     State(state): State<AppState>,
     token: AuthToken,
@@ -588,6 +464,14 @@ pub async fn batch_controller_assignments(
     Ok(Json(BatchAssignmentsResponse { results }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/session-graph",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = SessionGraphRequest,
+    responses((status = 200, description = "Installed graph", body = SessionGraphResponse))
+)]
 pub async fn install_session_graph(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1148,6 +1032,16 @@ impl ShowcasePreflightCheck for TransportWarningCheck {
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/showcase-preflight",
+    tag = "private-beaches",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ShowcasePreflightQuery,
+    ),
+    responses((status = 200, description = "Preflight report", body = ShowcasePreflightResponse))
+)]
 pub async fn showcase_preflight(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1210,6 +1104,13 @@ pub async fn showcase_preflight(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches",
+    tag = "private-beaches",
+    request_body = CreateBeachRequest,
+    responses((status = 200, description = "Beach created", body = BeachSummary))
+)]
 pub async fn create_private_beach(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1224,6 +1125,12 @@ pub async fn create_private_beach(
     Ok(Json(created))
 }
 
+#[utoipa::path(
+    get,
+    path = "/private-beaches",
+    tag = "private-beaches",
+    responses((status = 200, description = "Beaches visible to the caller", body = [BeachSummary]))
+)]
 pub async fn list_private_beaches(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1236,6 +1143,13 @@ pub async fn list_private_beaches(
     Ok(Json(list))
 }
 
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Beach metadata", body = BeachMeta))
+)]
 pub async fn get_private_beach(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1249,6 +1163,14 @@ pub async fn get_private_beach(
     Ok(Json(meta))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/private-beaches/{id}",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = UpdateBeachRequest,
+    responses((status = 200, description = "Updated beach", body = BeachMeta))
+)]
 pub async fn update_private_beach(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1269,6 +1191,13 @@ pub async fn update_private_beach(
     Ok(Json(updated))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Beach deleted", body = serde_json::Value))
+)]
 pub async fn delete_private_beach(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1282,6 +1211,13 @@ pub async fn delete_private_beach(
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/layout",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Canvas layout", body = CanvasLayout))
+)]
 pub async fn get_private_beach_layout(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1295,6 +1231,14 @@ pub async fn get_private_beach_layout(
     Ok(Json(layout))
 }
 
+#[utoipa::path(
+    put,
+    path = "/private-beaches/{id}/layout",
+    tag = "private-beaches",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = CanvasLayout,
+    responses((status = 200, description = "Saved layout", body = CanvasLayout))
+)]
 pub async fn put_private_beach_layout(
     State(state): State<AppState>,
     token: AuthToken,
@@ -1309,12 +1253,655 @@ pub async fn put_private_beach_layout(
     Ok(Json(layout))
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/share-links",
+    tag = "access",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = CreateShareLinkRequest,
+    responses((status = 200, description = "Share link created", body = CreatedShareLink))
+)]
+pub async fn create_share_link(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Json(body): Json<CreateShareLinkRequest>,
+) -> ApiResult<CreatedShareLink> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    if body.max_uses.is_some_and(|max| max <= 0) {
+        return Err(ApiError::BadRequest("max_uses must be positive".into()));
+    }
+    let expires_at = match body.expires_at_ms {
+        Some(ms) => {
+            let expires_at = chrono::DateTime::<Utc>::from_timestamp_millis(ms)
+                .ok_or_else(|| ApiError::BadRequest("invalid expires_at_ms".into()))?;
+            if expires_at <= Utc::now() {
+                return Err(ApiError::BadRequest(
+                    "expires_at_ms must be in the future".into(),
+                ));
+            }
+            Some(expires_at)
+        }
+        None => None,
+    };
+    let created = state
+        .create_share_link(
+            &id,
+            body.label.as_deref(),
+            body.role,
+            body.max_uses,
+            expires_at,
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(created))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/share-links",
+    tag = "access",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Share links", body = [ShareLink]))
+)]
+pub async fn list_share_links(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<ShareLink>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let links = state
+        .list_share_links(&id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(links))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/share-links/{link_id}",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("link_id" = String, Path, description = "Share link identifier"),
+    ),
+    responses((status = 200, description = "Revoked share link", body = ShareLink))
+)]
+pub async fn revoke_share_link(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, link_id)): Path<(String, String)>,
+) -> ApiResult<ShareLink> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let link = state
+        .revoke_share_link(&id, &link_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(link))
+}
+
+#[utoipa::path(
+    post,
+    path = "/share-links/redeem",
+    tag = "access",
+    request_body = RedeemShareLinkRequest,
+    responses((status = 200, description = "Membership granted", body = ShareLinkRedemption))
+)]
+pub async fn redeem_share_link(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Json(body): Json<RedeemShareLinkRequest>,
+) -> ApiResult<ShareLinkRedemption> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let account = token.account_uuid().ok_or(ApiError::Unauthorized)?;
+    let redemption = state
+        .redeem_share_link(&body.token, account)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(redemption))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/groups",
+    tag = "access",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Groups", body = [BeachGroup]))
+)]
+pub async fn list_groups(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<BeachGroup>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let groups = state
+        .list_beach_groups(&id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(groups))
+}
+
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/groups",
+    tag = "access",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = CreateGroupRequest,
+    responses((status = 200, description = "Group created", body = BeachGroup))
+)]
+pub async fn create_group(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Json(body): Json<CreateGroupRequest>,
+) -> ApiResult<BeachGroup> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let group = state
+        .create_beach_group(
+            &id,
+            &body.name,
+            body.description.as_deref(),
+            body.grants,
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(group))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/private-beaches/{id}/groups/{group_id}",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("group_id" = String, Path, description = "Group identifier"),
+    ),
+    request_body = UpdateGroupRequest,
+    responses((status = 200, description = "Updated group", body = BeachGroup))
+)]
+pub async fn update_group(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, group_id)): Path<(String, String)>,
+    Json(body): Json<UpdateGroupRequest>,
+) -> ApiResult<BeachGroup> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let group = state
+        .update_beach_group(
+            &id,
+            &group_id,
+            body.name.as_deref(),
+            body.description.as_deref(),
+            body.grants,
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(group))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/groups/{group_id}",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("group_id" = String, Path, description = "Group identifier"),
+    ),
+    responses((status = 200, description = "Group deleted", body = serde_json::Value))
+)]
+pub async fn delete_group(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, group_id)): Path<(String, String)>,
+) -> ApiResult<serde_json::Value> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    state
+        .delete_beach_group(&id, &group_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/groups/{group_id}/members",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("group_id" = String, Path, description = "Group identifier"),
+    ),
+    responses((status = 200, description = "Group members", body = [GroupMember]))
+)]
+pub async fn list_group_members(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, group_id)): Path<(String, String)>,
+) -> ApiResult<Vec<GroupMember>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let members = state
+        .list_group_members(&id, &group_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(members))
+}
+
+#[utoipa::path(
+    put,
+    path = "/private-beaches/{id}/groups/{group_id}/members/{account_id}",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("group_id" = String, Path, description = "Group identifier"),
+        ("account_id" = String, Path, description = "Account identifier"),
+    ),
+    request_body = GroupMemberRequest,
+    responses((status = 200, description = "Member added or updated", body = GroupMember))
+)]
+pub async fn put_group_member(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, group_id, account_id)): Path<(String, String, String)>,
+    Json(body): Json<GroupMemberRequest>,
+) -> ApiResult<GroupMember> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let member = state
+        .upsert_group_member(&id, &group_id, &account_id, body.role, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(member))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/groups/{group_id}/members/{account_id}",
+    tag = "access",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("group_id" = String, Path, description = "Group identifier"),
+        ("account_id" = String, Path, description = "Account identifier"),
+    ),
+    responses((status = 200, description = "Member removed", body = serde_json::Value))
+)]
+pub async fn delete_group_member(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, group_id, account_id)): Path<(String, String, String)>,
+) -> ApiResult<serde_json::Value> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    state
+        .remove_group_member(&id, &group_id, &account_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(serde_json::json!({ "removed": true })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/automation-assignments",
+    tag = "automation",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Automation assignments", body = [AutomationAssignment]))
+)]
+pub async fn list_automation_assignments(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<AutomationAssignment>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let assignments = state
+        .list_automation_assignments(&id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(assignments))
+}
+
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/automation-assignments",
+    tag = "automation",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = CreateAutomationAssignmentRequest,
+    responses((status = 200, description = "Assignment created", body = AutomationAssignment))
+)]
+pub async fn create_automation_assignment(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Json(body): Json<CreateAutomationAssignmentRequest>,
+) -> ApiResult<AutomationAssignment> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let assignment = state
+        .create_automation_assignment(
+            &id,
+            &body.controller_account_id,
+            body.role,
+            body.session_id.as_deref(),
+            body.config,
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(assignment))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/private-beaches/{id}/automation-assignments/{assignment_id}",
+    tag = "automation",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("assignment_id" = String, Path, description = "Automation assignment identifier"),
+    ),
+    request_body = UpdateAutomationAssignmentRequest,
+    responses((status = 200, description = "Updated assignment", body = AutomationAssignment))
+)]
+pub async fn update_automation_assignment(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, assignment_id)): Path<(String, String)>,
+    Json(body): Json<UpdateAutomationAssignmentRequest>,
+) -> ApiResult<AutomationAssignment> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let assignment = state
+        .update_automation_assignment(
+            &id,
+            &assignment_id,
+            body.role,
+            body.config,
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(assignment))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/automation-assignments/{assignment_id}",
+    tag = "automation",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("assignment_id" = String, Path, description = "Automation assignment identifier"),
+    ),
+    responses((status = 200, description = "Assignment deleted", body = serde_json::Value))
+)]
+pub async fn delete_automation_assignment(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, assignment_id)): Path<(String, String)>,
+) -> ApiResult<serde_json::Value> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    state
+        .delete_automation_assignment(&id, &assignment_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/webhooks",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Webhook subscriptions", body = [WebhookSubscription]))
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<WebhookSubscription>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let webhooks = state
+        .list_webhooks(&id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(webhooks))
+}
+
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/webhooks",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    request_body = CreateWebhookRequest,
+    responses((status = 200, description = "Webhook created", body = WebhookSubscription))
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Json(body): Json<CreateWebhookRequest>,
+) -> ApiResult<WebhookSubscription> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let webhook = state
+        .create_webhook(
+            &id,
+            &body.url,
+            body.event_types,
+            body.secret.as_deref(),
+            body.description.as_deref(),
+            token.account_uuid(),
+        )
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(webhook))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/private-beaches/{id}/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier"),
+    ),
+    request_body = WebhookUpdate,
+    responses((status = 200, description = "Updated webhook", body = WebhookSubscription))
+)]
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, webhook_id)): Path<(String, String)>,
+    Json(body): Json<WebhookUpdate>,
+) -> ApiResult<WebhookSubscription> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let webhook = state
+        .update_webhook(&id, &webhook_id, body, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(webhook))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/private-beaches/{id}/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("webhook_id" = String, Path, description = "Webhook identifier"),
+    ),
+    responses((status = 200, description = "Webhook deleted", body = serde_json::Value))
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, webhook_id)): Path<(String, String)>,
+) -> ApiResult<serde_json::Value> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    state
+        .delete_webhook(&id, &webhook_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/webhook-dead-letters",
+    tag = "webhooks",
+    params(("id" = String, Path, description = "Private beach identifier")),
+    responses((status = 200, description = "Undelivered events", body = [WebhookDeadLetter]))
+)]
+pub async fn list_webhook_dead_letters(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<WebhookDeadLetter>> {
+    ensure_scope(&token, "pb:beaches.read")?;
+    let dead_letters = state
+        .list_webhook_dead_letters(&id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(dead_letters))
+}
+
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{id}/webhook-dead-letters/{dead_letter_id}/replay",
+    tag = "webhooks",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ("dead_letter_id" = String, Path, description = "Dead letter identifier"),
+    ),
+    responses((status = 200, description = "Dead letter requeued", body = WebhookDeadLetter))
+)]
+pub async fn replay_webhook_dead_letter(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((id, dead_letter_id)): Path<(String, String)>,
+) -> ApiResult<WebhookDeadLetter> {
+    ensure_scope(&token, "pb:beaches.write")?;
+    let dead_letter = state
+        .replay_webhook_dead_letter(&id, &dead_letter_id, token.account_uuid())
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(dead_letter))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ControllerAuditQuery {
+    /// Start of the window (inclusive); defaults to 30 days before `to_ms`.
+    #[serde(default)]
+    pub from_ms: Option<i64>,
+    /// End of the window (exclusive); defaults to now.
+    #[serde(default)]
+    pub to_ms: Option<i64>,
+    /// Only rows where this account held control or issued the change.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    #[param(inline)]
+    pub format: AuditFormat,
+}
+
+impl ControllerAuditQuery {
+    fn filter(&self) -> Result<AuditFilter, ApiError> {
+        AuditFilter::new(
+            self.from_ms,
+            self.to_ms,
+            self.account_id.as_deref(),
+            self.session_id.as_deref(),
+            Utc::now().timestamp_millis(),
+        )
+        .map_err(ApiError::BadRequest)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/controller-audit/export",
+    tag = "controllers",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ControllerAuditQuery,
+    ),
+    responses((
+        status = 200,
+        description = "Controller events and leases in the window, oldest first",
+        content(
+            ("application/x-ndjson" = Vec<ControllerAuditRecord>),
+            ("text/csv" = String),
+        )
+    ))
+)]
+pub async fn export_controller_audit(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Query(query): Query<ControllerAuditQuery>,
+) -> Result<Response, ApiError> {
+    ensure_scope(&token, "pb:control.read")?;
+    let filter = query.filter()?;
+    let (records, truncated): (Vec<ControllerAuditRecord>, bool) = state
+        .controller_audit_records(&id, token.account_uuid(), &filter)
+        .await
+        .map_err(map_state_err)?;
+    let mut response = Body::from(audit::encode(&records, query.format)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(query.format.content_type()),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"controller-audit-{}.{}\"",
+        id,
+        query.format.extension()
+    )) {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    if truncated {
+        headers.insert("x-beach-audit-truncated", HeaderValue::from_static("true"));
+    }
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{id}/controller-audit/summary",
+    tag = "controllers",
+    params(
+        ("id" = String, Path, description = "Private beach identifier"),
+        ControllerAuditQuery,
+    ),
+    responses((status = 200, description = "Lease durations and emergency stops per account", body = ControllerAuditSummary))
+)]
+pub async fn controller_audit_summary(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(id): Path<String>,
+    Query(query): Query<ControllerAuditQuery>,
+) -> ApiResult<ControllerAuditSummary> {
+    ensure_scope(&token, "pb:control.read")?;
+    let filter = query.filter()?;
+    let summary = state
+        .controller_audit_summary(&id, token.account_uuid(), &filter)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(summary))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{private_beach_id}/sessions/{session_id}/viewer-credential",
+    tag = "sessions",
+    params(
+        ("private_beach_id" = String, Path, description = "Private beach identifier"),
+        ("session_id" = String, Path, description = "Session identifier"),
+    ),
+    responses((status = 200, description = "Viewer credential", body = ViewerCredentialResponse))
+)]
 pub async fn get_viewer_credential(
     State(state): State<AppState>,
     token: AuthToken,
     Path((private_beach_id, session_id)): Path<(String, String)>,
 ) -> ApiResult<ViewerCredentialResponse> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     let passcode = state
         .viewer_passcode(&private_beach_id, &session_id)
         .await
@@ -1356,7 +1943,7 @@ pub async fn get_viewer_credential(
     }
 }
 
-fn map_state_err(err: StateError) -> ApiError {
+pub(super) fn map_state_err(err: StateError) -> ApiError {
     match err {
         StateError::SessionNotFound => ApiError::NotFound("session not found"),
         StateError::ControllerMismatch => ApiError::Conflict("controller mismatch"),
@@ -1402,6 +1989,29 @@ fn map_state_err(err: StateError) -> ApiError {
             message: reason.default_message().to_string(),
             code: reason.code(),
         },
+        StateError::ShareLinkNotFound => ApiError::NotFound("share link not found"),
+        StateError::ShareLinkRejected(rejection) => ApiError::ConflictWithCode {
+            message: rejection.default_message().to_string(),
+            code: rejection.code(),
+        },
+        StateError::InsufficientRole => ApiError::Forbidden("insufficient private beach role"),
+        StateError::GroupNotFound => ApiError::NotFound("group not found"),
+        StateError::GroupNameConflict => ApiError::Conflict("group name already exists"),
+        StateError::FileNotFound => ApiError::NotFound("file not found"),
+        StateError::AutomationAssignmentNotFound => {
+            ApiError::NotFound("automation assignment not found")
+        }
+        StateError::AutomationAssignmentConflict => {
+            ApiError::Conflict("automation assignment already exists")
+        }
+        StateError::WebhookNotFound => ApiError::NotFound("webhook not found"),
+        StateError::WebhookDeadLetterNotFound => {
+            ApiError::NotFound("webhook dead letter not found")
+        }
+        StateError::InvalidAction { action_id, error } => ApiError::BadRequestWithCode {
+            message: format!("action {action_id}: {error}"),
+            code: error.code(),
+        },
     }
 }
 
@@ -1697,3 +2307,63 @@ mod showcase_preflight_tests {
             .any(|issue| issue.code == "pairing_missing" && issue.severity == "error"));
     }
 }
+
+#[cfg(test)]
+mod controller_audit_tests {
+    use crate::routes::build_router;
+    use crate::state::AppState;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn get(uri: String) -> axum::response::Response {
+        build_router(AppState::new())
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(uri)
+                    .header("authorization", "Bearer test-token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_sets_download_headers_for_format() {
+        let beach_id = Uuid::new_v4();
+        let response = get(format!(
+            "/private-beaches/{beach_id}/controller-audit/export?format=csv"
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            format!("attachment; filename=\"controller-audit-{beach_id}.csv\"").as_str()
+        );
+        assert!(response.headers().get("x-beach-audit-truncated").is_none());
+    }
+
+    #[tokio::test]
+    async fn export_rejects_inverted_range() {
+        let beach_id = Uuid::new_v4();
+        let response = get(format!(
+            "/private-beaches/{beach_id}/controller-audit/export?from_ms=2000&to_ms=1000"
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = get(format!(
+            "/private-beaches/{beach_id}/controller-audit/summary?account_id=nope"
+        ))
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
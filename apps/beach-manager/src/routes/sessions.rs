@@ -15,18 +15,25 @@ use beach_buggy::{
 use reqwest::StatusCode;
 use serde::Deserialize;
 use tracing::{debug, error, info, warn};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::state::{
-    AgentOnboardResponse, AppState, AttachHandshakeDisposition, ControllerEvent,
-    ControllerLeaseResponse, ControllerPairing, ControllerUpdateCadence,
-    JoinSessionResponsePayload, PairingTransportKind, PairingTransportStatus, SessionSummary,
-    StateError,
+    AgentOnboardResponse, AppState, AttachHandshakeDisposition, ControllerAutoAttachHint,
+    ControllerEvent, ControllerLeaseResponse, ControllerPairing, ControllerUpdateCadence,
+    IdlePublishTokenHint, JoinSessionResponsePayload, PairingTransportKind,
+    PairingTransportStatus, SessionFilter, SessionSummary, StateError,
 };
 
-use super::{ApiError, ApiResult, AuthToken};
+use super::{auth::matches_scope, ApiError, ApiResult, AuthToken};
 use crate::auth::Claims;
 
+pub use manager_sdk::models::{
+    AttachByCodeRequest, AttachOwnedRequest, AttachOwnedResponse, ControllerLeaseRequest,
+    PendingActionsResponse, QueueActionsRequest, ReleaseControllerRequest, SessionTagRequest,
+    SessionTagsResponse,
+};
+
 pub const CONTROLLER_HANDSHAKE_HEADER: &str = "x-beach-handshake-id";
 
 fn dev_bypass_token() -> Option<String> {
@@ -49,31 +56,64 @@ pub(crate) fn ensure_scope(token: &AuthToken, scope: &'static str) -> Result<(),
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SessionUpdateRequest {
-    pub metadata: Option<serde_json::Value>,
-    pub location_hint: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ControllerLeaseRequest {
-    pub requesting_account_id: Option<String>,
-    pub ttl_ms: Option<u64>,
-    pub reason: Option<String>,
+/// Like [`ensure_scope`], but also honours scopes granted to the caller's
+/// beach groups, including grants limited to tags the session carries.
+pub(crate) async fn ensure_session_scope(
+    state: &AppState,
+    token: &AuthToken,
+    session_id: &str,
+    scope: &'static str,
+) -> Result<(), ApiError> {
+    if token.has_scope(scope) {
+        return Ok(());
+    }
+    if let Some(account) = token.account_uuid() {
+        let granted = state
+            .group_scopes_for_session(account, session_id)
+            .await
+            .map_err(map_state_err)?;
+        if granted
+            .iter()
+            .any(|candidate| matches_scope(candidate, scope))
+        {
+            return Ok(());
+        }
+    }
+    Err(ApiError::Forbidden(scope))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ReleaseControllerRequest {
-    pub controller_token: String,
+/// Beach-wide variant of [`ensure_session_scope`]; tag-limited grants do not apply.
+pub(crate) async fn ensure_beach_scope(
+    state: &AppState,
+    token: &AuthToken,
+    private_beach_id: &str,
+    scope: &'static str,
+) -> Result<(), ApiError> {
+    if token.has_scope(scope) {
+        return Ok(());
+    }
+    if let Some(account) = token.account_uuid() {
+        let granted = state
+            .group_scopes_for_beach(account, private_beach_id)
+            .await
+            .map_err(map_state_err)?;
+        if granted
+            .iter()
+            .any(|candidate| matches_scope(candidate, scope))
+        {
+            return Ok(());
+        }
+    }
+    Err(ApiError::Forbidden(scope))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct QueueActionsRequest {
-    pub controller_token: String,
-    pub actions: Vec<ActionCommand>,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SessionUpdateRequest {
+    pub metadata: Option<serde_json::Value>,
+    pub location_hint: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct OnboardAgentRequest {
     pub session_id: String,
     pub template_id: String,
@@ -83,30 +123,19 @@ pub struct OnboardAgentRequest {
     pub options: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct EmergencyStopRequest {
     pub reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct AttachByCodeRequest {
-    pub session_id: String,
-    pub code: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct AttachOwnedRequest {
-    pub origin_session_ids: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateControllerPairingRequest {
     pub child_session_id: String,
     pub prompt_template: Option<String>,
     pub update_cadence: Option<ControllerUpdateCadence>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JoinSessionRequestBody {
     pub passphrase: Option<String>,
     #[serde(default)]
@@ -117,21 +146,21 @@ pub struct JoinSessionRequestBody {
     pub label: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ControllerHandshakeRequest {
     pub passcode: String,
     #[serde(default)]
     pub requester_private_beach_id: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerHandshakeKind {
     Refresh,
     Renegotiate,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, ToSchema)]
 pub struct ControllerHandshakeResponse {
     pub private_beach_id: String,
     pub manager_url: String,
@@ -141,19 +170,20 @@ pub struct ControllerHandshakeResponse {
     pub stale_session_idle_secs: u64,
     pub viewer_health_interval_secs: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub controller_auto_attach: Option<crate::state::ControllerAutoAttachHint>,
+    pub controller_auto_attach: Option<ControllerAutoAttachHint>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub idle_publish_token: Option<crate::state::IdlePublishTokenHint>,
+    pub idle_publish_token: Option<IdlePublishTokenHint>,
     pub handshake_kind: ControllerHandshakeKind,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ControllerConsumeQuery {
     #[serde(default)]
     pub controller_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct TransportStatusUpdateRequest {
     pub transport: PairingTransportKind,
     #[serde(default)]
@@ -162,19 +192,20 @@ pub struct TransportStatusUpdateRequest {
     pub last_error: Option<String>,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, ToSchema)]
 pub struct AttachByCodeResponse {
     pub ok: bool,
     pub attach_method: &'static str,
     pub session: SessionSummary,
 }
 
-#[derive(Debug, serde::Serialize)]
-pub struct AttachOwnedResponse {
-    pub attached: usize,
-    pub duplicates: usize,
-}
-
+#[utoipa::path(
+    post,
+    path = "/sessions/register",
+    tag = "sessions",
+    request_body = RegisterSessionRequest,
+    responses((status = 200, description = "Session registered", body = RegisterSessionResponse))
+)]
 pub async fn register_session(
     State(state): State<AppState>,
     token: AuthToken,
@@ -194,13 +225,21 @@ pub async fn register_session(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/sessions/{session_id}",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = SessionUpdateRequest,
+    responses((status = 200, description = "Metadata updated", body = serde_json::Value))
+)]
 pub async fn update_session(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<SessionUpdateRequest>,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:sessions.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.write").await?;
     state
         .update_session_metadata(&session_id, body.metadata, body.location_hint)
         .await
@@ -208,15 +247,71 @@ pub async fn update_session(
     Ok(Json(serde_json::json!({ "updated": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/tags",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = SessionTagRequest,
+    responses((status = 200, description = "Updated tags", body = SessionTagsResponse))
+)]
+pub async fn add_session_tag(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path(session_id): Path<String>,
+    Json(body): Json<SessionTagRequest>,
+) -> ApiResult<SessionTagsResponse> {
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.write").await?;
+    let tags = state
+        .add_session_tag(&session_id, &body.tag)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(SessionTagsResponse { session_id, tags }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/tags/{tag}",
+    tag = "sessions",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ("tag" = String, Path, description = "Tag to remove"),
+    ),
+    responses((status = 200, description = "Updated tags", body = SessionTagsResponse))
+)]
+pub async fn remove_session_tag(
+    State(state): State<AppState>,
+    token: AuthToken,
+    Path((session_id, tag)): Path<(String, String)>,
+) -> ApiResult<SessionTagsResponse> {
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.write").await?;
+    let tags = state
+        .remove_session_tag(&session_id, &tag)
+        .await
+        .map_err(map_state_err)?;
+    Ok(Json(SessionTagsResponse { session_id, tags }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/private-beaches/{private_beach_id}/sessions",
+    tag = "sessions",
+    params(
+        ("private_beach_id" = String, Path, description = "Private beach identifier"),
+        SessionFilter,
+    ),
+    responses((status = 200, description = "Sessions in the beach", body = [SessionSummary]))
+)]
 pub async fn list_sessions(
     State(state): State<AppState>,
     token: AuthToken,
     Path(private_beach_id): Path<String>,
+    Query(filter): Query<SessionFilter>,
     headers: HeaderMap,
 ) -> ApiResult<Vec<SessionSummary>> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_beach_scope(&state, &token, &private_beach_id, "pb:sessions.read").await?;
     let sessions = state
-        .list_sessions(&private_beach_id)
+        .list_sessions_filtered(&private_beach_id, &filter)
         .await
         .map_err(map_state_err)?;
     if let Some(trace_id) = headers
@@ -236,13 +331,21 @@ pub async fn list_sessions(
     Ok(Json(sessions))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/controller/lease",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = ControllerLeaseRequest,
+    responses((status = 200, description = "Lease granted", body = ControllerLeaseResponse))
+)]
 pub async fn acquire_controller(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<ControllerLeaseRequest>,
 ) -> ApiResult<ControllerLeaseResponse> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:control.write").await?;
     let requester = token.account_uuid().or_else(|| {
         body.requesting_account_id
             .as_deref()
@@ -255,13 +358,21 @@ pub async fn acquire_controller(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/controller/lease",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = ReleaseControllerRequest,
+    responses((status = 200, description = "Lease released", body = serde_json::Value))
+)]
 pub async fn release_controller(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<ReleaseControllerRequest>,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:control.write").await?;
     state
         .release_controller(&session_id, &body.controller_token, token.account_uuid())
         .await
@@ -269,13 +380,21 @@ pub async fn release_controller(
     Ok(Json(serde_json::json!({ "released": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/controller-handshake",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = ControllerHandshakeRequest,
+    responses((status = 200, description = "Handshake issued", body = ControllerHandshakeResponse))
+)]
 pub async fn issue_controller_handshake(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<ControllerHandshakeRequest>,
 ) -> ApiResult<ControllerHandshakeResponse> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     let target_beach = body
         .requester_private_beach_id
         .unwrap_or_else(|| "pb-unknown".into());
@@ -350,13 +469,21 @@ pub async fn issue_controller_handshake(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/controller-handshake",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = ReleaseControllerRequest,
+    responses((status = 200, description = "Handshake revoked", body = serde_json::Value))
+)]
 pub async fn revoke_controller_handshake(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<ReleaseControllerRequest>,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:control.write").await?;
     state
         .release_controller(&session_id, &body.controller_token, token.account_uuid())
         .await
@@ -364,6 +491,13 @@ pub async fn revoke_controller_handshake(
     Ok(Json(serde_json::json!({ "released": true })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{controller_id}/controllers",
+    tag = "controllers",
+    params(("controller_id" = String, Path, description = "Controller session identifier")),
+    responses((status = 200, description = "Pairings for the controller", body = [ControllerPairing]))
+)]
 pub async fn list_controller_pairings_route(
     State(state): State<AppState>,
     token: AuthToken,
@@ -379,13 +513,21 @@ pub async fn list_controller_pairings_route(
     Ok(Json(pairings))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{controller_id}/controllers",
+    tag = "controllers",
+    params(("controller_id" = String, Path, description = "Controller session identifier")),
+    request_body = CreateControllerPairingRequest,
+    responses((status = 200, description = "Pairing created or updated", body = ControllerPairing))
+)]
 pub async fn create_controller_pairing(
     State(state): State<AppState>,
     token: AuthToken,
     Path(controller_session_id): Path<String>,
     Json(body): Json<CreateControllerPairingRequest>,
 ) -> ApiResult<ControllerPairing> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &controller_session_id, "pb:control.write").await?;
     let pairing = state
         .upsert_controller_pairing(
             &controller_session_id,
@@ -399,13 +541,23 @@ pub async fn create_controller_pairing(
     Ok(Json(pairing))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sessions/{controller_id}/controllers/{child_session_id}",
+    tag = "controllers",
+    params(
+        ("controller_id" = String, Path, description = "Controller session identifier"),
+        ("child_session_id" = String, Path, description = "Child session identifier"),
+    ),
+    responses((status = 200, description = "Pairing removed", body = serde_json::Value))
+)]
 pub async fn delete_controller_pairing(
     State(state): State<AppState>,
     token: AuthToken,
     Path((controller_session_id, child_session_id)): Path<(String, String)>,
     headers: HeaderMap,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &controller_session_id, "pb:control.write").await?;
     if let Some(trace_header) = headers
         .get("x-trace-id")
         .and_then(|value| value.to_str().ok())
@@ -429,6 +581,14 @@ pub async fn delete_controller_pairing(
     Ok(Json(serde_json::json!({ "deleted": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/actions",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = QueueActionsRequest,
+    responses((status = 200, description = "Actions queued", body = serde_json::Value))
+)]
 pub async fn queue_actions(
     State(state): State<AppState>,
     token: AuthToken,
@@ -436,7 +596,7 @@ pub async fn queue_actions(
     headers: HeaderMap,
     Json(body): Json<QueueActionsRequest>,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:control.write").await?;
     if body.actions.is_empty() {
         return Err(ApiError::BadRequest("actions array required".into()));
     }
@@ -495,6 +655,16 @@ pub async fn queue_actions(
     Ok(Json(serde_json::json!({ "accepted": true })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/actions/poll",
+    tag = "controllers",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ControllerConsumeQuery,
+    ),
+    responses((status = 200, description = "Queued actions", body = [ActionCommand]))
+)]
 pub async fn poll_actions(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
@@ -516,6 +686,16 @@ pub async fn poll_actions(
     Ok(Json(commands))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/actions/pending",
+    tag = "controllers",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ControllerConsumeQuery,
+    ),
+    responses((status = 200, description = "Queue depth and transport", body = PendingActionsResponse))
+)]
 pub async fn pending_actions(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
@@ -546,6 +726,17 @@ pub async fn pending_actions(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/actions/ack",
+    tag = "controllers",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ControllerConsumeQuery,
+    ),
+    request_body = Vec<ActionAck>,
+    responses((status = 200, description = "Acks recorded", body = serde_json::Value))
+)]
 pub async fn ack_actions(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
@@ -568,6 +759,17 @@ pub async fn ack_actions(
     Ok(Json(serde_json::json!({ "acknowledged": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/transport-status",
+    tag = "controllers",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        ControllerConsumeQuery,
+    ),
+    request_body = TransportStatusUpdateRequest,
+    responses((status = 200, description = "Transport status recorded", body = serde_json::Value))
+)]
 pub async fn update_transport_status(
     State(state): State<AppState>,
     Path(session_id): Path<String>,
@@ -637,6 +839,14 @@ async fn resolve_control_consumer(
     Ok(auth.account_uuid())
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/health",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = HealthHeartbeat,
+    responses((status = 200, description = "Heartbeat recorded", body = serde_json::Value))
+)]
 pub async fn signal_health(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -657,6 +867,14 @@ pub async fn signal_health(
     Ok(Json(serde_json::json!({ "recorded": true })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/state",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = StateDiff,
+    responses((status = 200, description = "State accepted", body = serde_json::Value))
+)]
 pub async fn push_state(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -709,7 +927,7 @@ pub(crate) fn claims_has_scope(claims: &Claims, scope: &str) -> bool {
     false
 }
 
-async fn authorize_publish(
+pub(crate) async fn authorize_publish(
     state: &AppState,
     headers: &HeaderMap,
     session_id: &str,
@@ -758,13 +976,20 @@ async fn authorize_publish(
     Ok("bearer")
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/state",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    responses((status = 200, description = "Latest state diff, if any", body = Option<StateDiff>))
+)]
 pub async fn fetch_state_snapshot(
     State(state): State<AppState>,
     token: Option<AuthToken>,
     Path(session_id): Path<String>,
 ) -> ApiResult<Option<StateDiff>> {
     if let Some(token) = token {
-        ensure_scope(&token, "pb:sessions.read")?;
+        ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     } else if !(std::env::var("DEV_ALLOW_INSECURE_MANAGER_TOKEN").unwrap_or_default() == "1"
         && std::env::var("NODE_ENV").unwrap_or_default() != "production")
     {
@@ -777,13 +1002,23 @@ pub async fn fetch_state_snapshot(
     Ok(Json(snapshot))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/controller-events",
+    tag = "controllers",
+    params(
+        ("session_id" = String, Path, description = "Session identifier"),
+        EventsFilter,
+    ),
+    responses((status = 200, description = "Controller events", body = [ControllerEvent]))
+)]
 pub async fn list_controller_events(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Query(filter): Query<EventsFilter>,
 ) -> ApiResult<Vec<ControllerEvent>> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     let events = state
         .controller_events_filtered(
             &session_id,
@@ -796,7 +1031,8 @@ pub async fn list_controller_events(
     Ok(Json(events))
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct EventsFilter {
     #[serde(default)]
     pub event_type: Option<String>,
@@ -806,6 +1042,13 @@ pub struct EventsFilter {
     pub limit: Option<usize>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/agents/onboard",
+    tag = "agents",
+    request_body = OnboardAgentRequest,
+    responses((status = 200, description = "Agent onboarded", body = AgentOnboardResponse))
+)]
 pub async fn onboard_agent(
     State(state): State<AppState>,
     token: AuthToken,
@@ -840,13 +1083,21 @@ pub async fn onboard_agent(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/emergency-stop",
+    tag = "controllers",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = EmergencyStopRequest,
+    responses((status = 200, description = "Session stopped", body = serde_json::Value))
+)]
 pub async fn emergency_stop(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<EmergencyStopRequest>,
 ) -> ApiResult<serde_json::Value> {
-    ensure_scope(&token, "pb:control.write")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:control.write").await?;
     state
         .emergency_stop(&session_id, token.account_uuid(), body.reason.clone())
         .await
@@ -954,6 +1205,14 @@ async fn authorize_attach(
     Ok(account_uuid)
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{private_beach_id}/sessions/attach-by-code",
+    tag = "sessions",
+    params(("private_beach_id" = String, Path, description = "Private beach identifier")),
+    request_body = AttachByCodeRequest,
+    responses((status = 200, description = "Session attached", body = AttachByCodeResponse))
+)]
 pub async fn attach_by_code(
     State(state): State<AppState>,
     Path(private_beach_id): Path<String>,
@@ -1034,13 +1293,21 @@ pub async fn attach_by_code(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/private-beaches/{private_beach_id}/sessions/attach",
+    tag = "sessions",
+    params(("private_beach_id" = String, Path, description = "Private beach identifier")),
+    request_body = AttachOwnedRequest,
+    responses((status = 200, description = "Sessions attached", body = AttachOwnedResponse))
+)]
 pub async fn attach_owned(
     State(state): State<AppState>,
     token: AuthToken,
     Path(private_beach_id): Path<String>,
     Json(body): Json<AttachOwnedRequest>,
 ) -> ApiResult<AttachOwnedResponse> {
-    ensure_scope(&token, "pb:sessions.write")?;
+    ensure_beach_scope(&state, &token, &private_beach_id, "pb:sessions.write").await?;
     if body.origin_session_ids.is_empty() {
         return Err(ApiError::BadRequest("origin_session_ids required".into()));
     }
@@ -1058,13 +1325,21 @@ pub async fn attach_owned(
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/join",
+    tag = "sessions",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    request_body = JoinSessionRequestBody,
+    responses((status = 200, description = "Join credentials", body = JoinSessionResponsePayload))
+)]
 pub async fn join_session(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     Json(body): Json<JoinSessionRequestBody>,
 ) -> ApiResult<JoinSessionResponsePayload> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     info!(
         target = "private_beach",
         session_id = %session_id,
@@ -1176,5 +1451,28 @@ fn map_state_err(err: StateError) -> ApiError {
             message: reason.default_message().to_string(),
             code: reason.code(),
         },
+        StateError::ShareLinkNotFound => ApiError::NotFound("share link not found"),
+        StateError::ShareLinkRejected(rejection) => ApiError::ConflictWithCode {
+            message: rejection.default_message().to_string(),
+            code: rejection.code(),
+        },
+        StateError::InsufficientRole => ApiError::Forbidden("insufficient private beach role"),
+        StateError::GroupNotFound => ApiError::NotFound("group not found"),
+        StateError::GroupNameConflict => ApiError::Conflict("group name already exists"),
+        StateError::FileNotFound => ApiError::NotFound("file not found"),
+        StateError::AutomationAssignmentNotFound => {
+            ApiError::NotFound("automation assignment not found")
+        }
+        StateError::AutomationAssignmentConflict => {
+            ApiError::Conflict("automation assignment already exists")
+        }
+        StateError::WebhookNotFound => ApiError::NotFound("webhook not found"),
+        StateError::WebhookDeadLetterNotFound => {
+            ApiError::NotFound("webhook dead letter not found")
+        }
+        StateError::InvalidAction { action_id, error } => ApiError::BadRequestWithCode {
+            message: format!("action {action_id}: {error}"),
+            code: error.code(),
+        },
     }
 }
@@ -15,19 +15,26 @@ use crate::{
     state::{AppState, DevtoolsTimelineEvent},
 };
 
-use super::{ApiError, AuthToken};
+use super::{sessions::ensure_session_scope, ApiError, AuthToken};
 
 pub async fn prometheus_metrics() -> String {
     metrics::export_prometheus()
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/state/stream",
+    tag = "streams",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    responses((status = 200, description = "`state` and `automation_assignment` events", content_type = "text/event-stream", body = String))
+)]
 pub async fn stream_state(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
     headers: HeaderMap,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     let rx = state.subscribe_session(&session_id).await;
     let trace_id: Option<Arc<str>> = headers
         .get("x-trace-id")
@@ -60,12 +67,25 @@ pub async fn stream_state(
                 let data = serde_json::to_string(&diff).unwrap_or_else(|_| "{}".into());
                 Some(Ok(Event::default().event("state").data(data)))
             }
+            Ok(crate::state::StreamEvent::AutomationAssignment(status)) => {
+                let data = serde_json::to_string(&status).unwrap_or_else(|_| "{}".into());
+                Some(Ok(Event::default()
+                    .event("automation_assignment")
+                    .data(data)))
+            }
             _ => None,
         }
     });
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{controller_id}/controllers/stream",
+    tag = "streams",
+    params(("controller_id" = String, Path, description = "Controller session identifier")),
+    responses((status = 200, description = "Pairing change events", content_type = "text/event-stream", body = String))
+)]
 pub async fn stream_controller_pairings(
     State(state): State<AppState>,
     token: AuthToken,
@@ -114,12 +134,19 @@ pub async fn stream_controller_pairings(
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/devtools/stream",
+    tag = "streams",
+    params(("session_id" = String, Path, description = "Session identifier")),
+    responses((status = 200, description = "Devtools events", content_type = "text/event-stream", body = String))
+)]
 pub async fn stream_devtools(
     State(state): State<AppState>,
     token: AuthToken,
     Path(session_id): Path<String>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
-    ensure_scope(&token, "pb:sessions.read")?;
+    ensure_session_scope(&state, &token, &session_id, "pb:sessions.read").await?;
     let stream = BroadcastStream::new(state.subscribe_devtools(&session_id).await).filter_map(
         |msg| match msg {
             Ok(event) => Some(Ok(to_sse_event("devtools_event", &event))),
@@ -129,14 +156,6 @@ pub async fn stream_devtools(
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
-fn ensure_scope(token: &AuthToken, scope: &'static str) -> Result<(), ApiError> {
-    if token.has_scope(scope) {
-        Ok(())
-    } else {
-        Err(ApiError::Forbidden(scope))
-    }
-}
-
 fn to_sse_event(name: &'static str, event: &DevtoolsTimelineEvent) -> Event {
     let data = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
     Event::default().event(name).data(data)
@@ -17,32 +17,42 @@ use std::{
     time::{Duration as StdDuration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use crate::audit::{
+    self, AuditFilter, AuditRecordKind, AuditRetention, ControllerAuditRecord,
+    ControllerAuditSummary,
+};
 use crate::auth::{AuthConfig, AuthContext};
+use crate::blob_store::{BlobStore, BlobStoreError, MemoryBlobStore};
 use crate::publish_token::{PublishTokenManager, SignedPublishToken};
 use crate::routes::ShowcasePreflightResponse;
+use crate::webhooks::{self, WebhookIndex};
 use crate::{
     log_throttle::{should_log_custom_event, should_log_queue_event, QueueLogKind},
     metrics,
 };
 use base64::{engine::general_purpose, Engine as _};
 use beach_buggy::{
-    AckStatus, ActionAck, ActionCommand, CellStylePayload, CursorPosition, HarnessType,
+    AckStatus, ActionAck, ActionCommand, CellStylePayload, CursorPosition, FileRecord, HarnessType,
     HealthHeartbeat, RegisterSessionRequest, RegisterSessionResponse, StateDiff, StyleDefinition,
     StyledCell, TerminalFrame, TransportMode,
 };
 use beach_client_core::auth::config::AuthConfig as GateAuthConfig;
 use beach_client_core::auth::gate::{BeachGateClient, TurnIceServer};
 use beach_client_core::cache::terminal::packed::unpack_cell;
-use beach_client_core::protocol::{ClientFrame, CursorFrame, Update as WireUpdate};
+use beach_client_core::protocol::{ClientFrame, CursorFrame, ExtensionFrame, Update as WireUpdate};
 use beach_client_core::transport::webrtc::{transport_diagnostics, warm_session_key};
-use beach_client_core::transport::{extensions, framed};
+use beach_client_core::transport::{bus::manager_topics, extensions, framed};
 use beach_client_core::{
     decode_host_frame_binary, encode_client_frame_binary, negotiate_transport, CliError,
     HostFrame as WireHostFrame, NegotiatedSingle, NegotiatedTransport, PackedCell, Payload,
     SessionConfig, SessionError, SessionHandle, SessionManager, Style, StyleId, TerminalGrid,
     Transport, TransportError, TransportKind, TransportOffer, WebRtcChannels,
 };
+use bytes::Bytes;
 use chrono::{DateTime, Duration, Utc};
+use harness_proto::actions::{
+    ActionKind, ActionValidationError, ControllerAction, ACTION_CAPABILITY_PREFIX,
+};
 use hmac::{Hmac, Mac};
 use prometheus::IntGauge;
 use reqwest::StatusCode;
@@ -56,6 +66,7 @@ use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, trace, warn, Level};
 use url::Url;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 const DEFAULT_LEASE_TTL_MS: u64 = 30_000;
@@ -184,6 +195,13 @@ pub struct AppState {
     controller_handshakes: Arc<RwLock<HashMap<String, ControllerHandshakeInfo>>>,
     state_keepalive: StateKeepaliveManager,
     showcase_preflight_cache: Arc<RwLock<HashMap<String, ShowcasePreflightCacheEntry>>>,
+    blob_store: Arc<dyn BlobStore>,
+    /// Latest reconcile outcome per beach, keyed by (assignment id, session id).
+    assignment_status: Arc<RwLock<HashMap<String, HashMap<(String, String), AssignmentStatus>>>>,
+    webhook_index: Arc<RwLock<WebhookIndex>>,
+    /// Lets webhooks target loopback and private networks; off in production.
+    webhook_private_targets: bool,
+    audit_retention: Option<AuditRetention>,
 }
 
 struct ControllerHandshakeInfo {
@@ -207,6 +225,8 @@ struct InnerState {
     pairings: RwLock<HashMap<String, Vec<ControllerPairing>>>,
     canvas_layouts: RwLock<HashMap<String, crate::routes::CanvasLayout>>,
     pending_transport: RwLock<HashMap<String, PairingTransportStatus>>,
+    files: RwLock<HashMap<String, Vec<FileRecord>>>,
+    automation_assignments: RwLock<HashMap<String, AutomationAssignment>>,
 }
 
 #[derive(Debug, Clone)]
@@ -232,6 +252,8 @@ struct SessionRecord {
     attached_at_ms: Option<i64>,
     http_ready_since_ms: Option<i64>,
     transport_mode: TransportMode,
+    tags: Vec<String>,
+    last_seen_at_ms: i64,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -241,7 +263,7 @@ struct SessionReadinessSnapshot {
     last_health_at: Option<Instant>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 pub struct ControllerAutoAttachHint {
     private_beach_id: String,
     attach_code: String,
@@ -253,7 +275,7 @@ pub struct ControllerAutoAttachHint {
     handshake_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 pub struct IdlePublishTokenHint {
     pub token: String,
     pub expires_at_ms: i64,
@@ -586,6 +608,8 @@ impl ManagerViewerState {
             | WireHostFrame::Heartbeat { .. }
             | WireHostFrame::InputAck { .. }
             | WireHostFrame::Extension { .. }
+            | WireHostFrame::SearchResults { .. }
+            | WireHostFrame::Modes { .. }
             | WireHostFrame::Shutdown => None,
         }
     }
@@ -851,9 +875,34 @@ pub enum StateError {
     },
     #[error("controller command rejected ({reason})")]
     ControllerCommandRejected { reason: ControllerCommandDropReason },
+    #[error("share link not found")]
+    ShareLinkNotFound,
+    #[error("share link rejected ({0})")]
+    ShareLinkRejected(ShareLinkRejection),
+    #[error("insufficient private beach role")]
+    InsufficientRole,
+    #[error("group not found")]
+    GroupNotFound,
+    #[error("group name already exists")]
+    GroupNameConflict,
+    #[error("file not found")]
+    FileNotFound,
+    #[error("automation assignment not found")]
+    AutomationAssignmentNotFound,
+    #[error("automation assignment already exists")]
+    AutomationAssignmentConflict,
+    #[error("webhook not found")]
+    WebhookNotFound,
+    #[error("webhook dead letter not found")]
+    WebhookDeadLetterNotFound,
+    #[error("invalid controller action {action_id}: {error}")]
+    InvalidAction {
+        action_id: String,
+        error: ActionValidationError,
+    },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SessionSummary {
     pub session_id: String,
     pub private_beach_id: String,
@@ -868,9 +917,146 @@ pub struct SessionSummary {
     pub pending_actions: usize,
     pub pending_unacked: usize,
     pub last_health: Option<HealthHeartbeat>,
+    #[serde(default)]
+    pub kind: Option<SessionKind>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub last_seen_at_ms: Option<i64>,
+    #[serde(default)]
+    pub ended_at_ms: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "session_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKind {
+    Terminal,
+    CabanaGui,
+    ManagerConsole,
+    Widget,
+    SpectatorFeed,
+    ServiceDaemon,
+}
+
+impl SessionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionKind::Terminal => "terminal",
+            SessionKind::CabanaGui => "cabana_gui",
+            SessionKind::ManagerConsole => "manager_console",
+            SessionKind::Widget => "widget",
+            SessionKind::SpectatorFeed => "spectator_feed",
+            SessionKind::ServiceDaemon => "service_daemon",
+        }
+    }
+}
+
+impl From<&HarnessType> for SessionKind {
+    fn from(value: &HarnessType) -> Self {
+        match value {
+            HarnessType::TerminalShim => SessionKind::Terminal,
+            HarnessType::CabanaAdapter => SessionKind::CabanaGui,
+            HarnessType::RemoteWidget => SessionKind::Widget,
+            HarnessType::ServiceProxy => SessionKind::ServiceDaemon,
+            HarnessType::Custom => SessionKind::Widget,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Active,
+    Ended,
+}
+
+/// Optional narrowing applied by [`AppState::list_sessions_filtered`]. Every
+/// populated field must match for a session to be returned.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SessionFilter {
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub harness_type: Option<HarnessType>,
+    #[serde(default)]
+    pub kind: Option<SessionKind>,
+    #[serde(default)]
+    pub last_seen_after_ms: Option<i64>,
+    #[serde(default)]
+    pub last_seen_before_ms: Option<i64>,
+    #[serde(default)]
+    pub status: Option<SessionStatus>,
+    /// Case-insensitive substring match against title and location hint.
+    #[serde(default)]
+    pub q: Option<String>,
+}
+
+impl SessionFilter {
+    fn normalized(&self) -> Self {
+        let clean = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            tag: clean(&self.tag),
+            q: clean(&self.q),
+            ..self.clone()
+        }
+    }
+
+    fn matches_summary(&self, summary: &SessionSummary) -> bool {
+        if let Some(tag) = &self.tag {
+            if !summary.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                return false;
+            }
+        }
+        if let Some(harness) = &self.harness_type {
+            if &summary.harness_type != harness {
+                return false;
+            }
+        }
+        if let Some(kind) = self.kind {
+            if summary.kind != Some(kind) {
+                return false;
+            }
+        }
+        if let Some(after) = self.last_seen_after_ms {
+            if summary.last_seen_at_ms.is_none_or(|seen| seen < after) {
+                return false;
+            }
+        }
+        if let Some(before) = self.last_seen_before_ms {
+            if summary.last_seen_at_ms.is_none_or(|seen| seen > before) {
+                return false;
+            }
+        }
+        match self.status {
+            Some(SessionStatus::Active) if summary.ended_at_ms.is_some() => return false,
+            Some(SessionStatus::Ended) if summary.ended_at_ms.is_none() => return false,
+            _ => {}
+        }
+        if let Some(q) = &self.q {
+            let needle = q.to_lowercase();
+            let hit = [summary.title.as_deref(), summary.location_hint.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|haystack| haystack.to_lowercase().contains(&needle));
+            if !hit {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct ControllerEvent {
     pub id: String,
     pub event_type: ControllerEventType,
@@ -881,7 +1067,7 @@ pub struct ControllerEvent {
     pub issued_by_account_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerEventType {
     Registered,
@@ -893,9 +1079,12 @@ pub enum ControllerEventType {
     StateUpdated,
     PairingAdded,
     PairingRemoved,
+    /// Recorded in place of `LeaseReleased` when a lease is cut by an
+    /// emergency stop; live streams still see `LeaseReleased`.
+    EmergencyStop,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "controller_update_cadence", rename_all = "snake_case")]
 pub enum ControllerUpdateCadence {
@@ -910,7 +1099,7 @@ impl Default for ControllerUpdateCadence {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 pub enum PairingTransportKind {
     #[serde(rename = "webrtc")]
     Rtc,
@@ -920,7 +1109,7 @@ pub enum PairingTransportKind {
     Pending,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct PairingTransportStatus {
     pub transport: PairingTransportKind,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -976,7 +1165,7 @@ impl PairingTransportStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ControllerPairing {
     pub pairing_id: String,
     pub private_beach_id: String,
@@ -986,6 +1175,10 @@ pub struct ControllerPairing {
     pub update_cadence: ControllerUpdateCadence,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transport_status: Option<PairingTransportStatus>,
+    /// Automation assignment that created the pairing and removes it on
+    /// release; `None` for pairings set up by hand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub automation_assignment_id: Option<String>,
     pub created_at_ms: i64,
     pub updated_at_ms: i64,
 }
@@ -1032,6 +1225,7 @@ pub enum StreamEvent {
     Health(HealthHeartbeat),
     ControllerPairing(ControllerPairingEvent),
     Devtools(DevtoolsTimelineEvent),
+    AutomationAssignment(AssignmentStatus),
 }
 
 impl StreamEvent {
@@ -1046,6 +1240,9 @@ impl StreamEvent {
                 ("controller_pairing", serde_json::to_string(event).ok())
             }
             StreamEvent::Devtools(event) => ("devtools_event", serde_json::to_string(event).ok()),
+            StreamEvent::AutomationAssignment(status) => {
+                ("automation_assignment", serde_json::to_string(status).ok())
+            }
         }
     }
 }
@@ -1124,21 +1321,16 @@ pub enum DevtoolsEventLevel {
     Error,
 }
 
-/// Payload returned to a controller session when the manager grants (or renews) a lease.
-#[derive(Debug, Clone, Serialize)]
-pub struct ControllerLeaseResponse {
-    pub controller_token: String,
-    pub expires_at_ms: i64,
-}
+pub use manager_sdk::models::ControllerLeaseResponse;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct AgentOnboardResponse {
     pub agent_token: String,
     pub prompt_pack: serde_json::Value,
     pub mcp_bridges: Vec<McpBridge>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct McpBridge {
     pub id: String,
     pub name: String,
@@ -1165,6 +1357,11 @@ struct SessionRow {
     controller_token: Option<Uuid>,
     expires_at: Option<DateTime<Utc>>,
     revoked_at: Option<DateTime<Utc>>,
+    kind: SessionKind,
+    title: Option<String>,
+    tags: Vec<String>,
+    last_seen_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, FromRow)]
@@ -1188,6 +1385,7 @@ struct ControllerPairingRow {
     child_origin_session_id: Uuid,
     prompt_template: Option<String>,
     update_cadence: ControllerUpdateCadence,
+    automation_assignment_id: Option<Uuid>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -1219,6 +1417,7 @@ impl ControllerPairingRow {
             prompt_template: self.prompt_template,
             update_cadence: self.update_cadence,
             transport_status: None,
+            automation_assignment_id: self.automation_assignment_id.map(|id| id.to_string()),
             created_at_ms: self.created_at.timestamp_millis(),
             updated_at_ms: self.updated_at.timestamp_millis(),
         }
@@ -1256,6 +1455,11 @@ impl AppState {
             controller_handshakes: Arc::new(RwLock::new(HashMap::new())),
             state_keepalive: StateKeepaliveManager::new(),
             showcase_preflight_cache: Arc::new(RwLock::new(HashMap::new())),
+            blob_store: Arc::new(MemoryBlobStore::new()),
+            assignment_status: Arc::new(RwLock::new(HashMap::new())),
+            webhook_index: Arc::new(RwLock::new(WebhookIndex::default())),
+            webhook_private_targets: false,
+            audit_retention: None,
         }
     }
 
@@ -1289,6 +1493,11 @@ impl AppState {
             controller_handshakes: Arc::new(RwLock::new(HashMap::new())),
             state_keepalive: StateKeepaliveManager::new(),
             showcase_preflight_cache: Arc::new(RwLock::new(HashMap::new())),
+            blob_store: Arc::new(MemoryBlobStore::new()),
+            assignment_status: Arc::new(RwLock::new(HashMap::new())),
+            webhook_index: Arc::new(RwLock::new(WebhookIndex::default())),
+            webhook_private_targets: false,
+            audit_retention: None,
         }
     }
 
@@ -1350,6 +1559,21 @@ impl AppState {
         self
     }
 
+    pub fn with_blob_store(mut self, store: Arc<dyn BlobStore>) -> Self {
+        self.blob_store = store;
+        self
+    }
+
+    pub fn with_audit_retention(mut self, retention: AuditRetention) -> Self {
+        self.audit_retention = Some(retention);
+        self
+    }
+
+    pub fn with_private_webhook_targets(mut self, enabled: bool) -> Self {
+        self.webhook_private_targets = enabled;
+        self
+    }
+
     async fn ensure_controller_account_active(
         &self,
         account_id: &Uuid,
@@ -2040,6 +2264,7 @@ impl AppState {
         if map.remove(session_id).is_some() {
             debug!(session_id = %session_id, "session stream cleared");
         }
+        self.webhook_index.write().await.forget_session(session_id);
         {
             let mut devtools = self.devtools_events.write().await;
             if devtools.remove(session_id).is_some() {
@@ -2050,6 +2275,7 @@ impl AppState {
     }
 
     async fn publish(&self, session_id: &str, event: StreamEvent) {
+        self.queue_webhook_event(session_id, &event).await;
         let tx_opt = { self.events.read().await.get(session_id).cloned() };
         if let Some(tx) = tx_opt {
             let (event_kind, _) = event.as_named_json();
@@ -2355,6 +2581,7 @@ impl AppState {
                             )
                         });
                     rec.viewer_passcode = Some(code.to_string());
+                    rec.advertise_host_actions();
                     rec.mark_attached();
                     let hint = self.build_controller_auto_attach_hint(private_beach_id, code, None);
                     rec.upsert_controller_auto_attach_hint(&hint)?;
@@ -2453,6 +2680,7 @@ impl AppState {
                             )
                         });
                     rec.viewer_passcode = Some(code.to_string());
+                    rec.advertise_host_actions();
                     rec.upsert_controller_auto_attach_hint(
                         &self.build_controller_auto_attach_hint(private_beach_id, code, None),
                     )?;
@@ -2472,14 +2700,15 @@ impl AppState {
                 // Insert session row if not exists, leave harness fields null
                 let insert_result = sqlx::query(
                     r#"
-                    INSERT INTO session (private_beach_id, origin_session_id, kind, created_by_account_id, attach_method)
-                    VALUES ($1, $2, 'terminal', $3, 'code')
+                    INSERT INTO session (private_beach_id, origin_session_id, kind, created_by_account_id, attach_method, capabilities)
+                    VALUES ($1, $2, 'terminal', $3, 'code', $4)
                     ON CONFLICT (private_beach_id, origin_session_id) DO NOTHING
                     "#,
                 )
                 .bind(beach_uuid)
                 .bind(origin_uuid)
                 .bind(requester)
+                .bind(Json(ActionKind::all_capabilities()))
                 .execute(tx.as_mut())
                 .await?;
                 let inserted = insert_result.rows_affected() > 0;
@@ -2534,6 +2763,7 @@ impl AppState {
                             )
                         });
                     rec.viewer_passcode = Some(code.to_string());
+                    rec.advertise_host_actions();
                     rec.mark_attached();
                 }
                 log_session_attachment(
@@ -2632,6 +2862,7 @@ impl AppState {
                             TransportMode::FastPath,
                         )
                     });
+                    entry.advertise_host_actions();
                     entry.mark_attached();
                     if existed {
                         duplicates += 1;
@@ -2671,14 +2902,15 @@ impl AppState {
                     if let Ok(origin_uuid) = Uuid::parse_str(&id) {
                         let result = sqlx::query(
                     r#"
-                    INSERT INTO session (private_beach_id, origin_session_id, kind, created_by_account_id, attach_method)
-                    VALUES ($1, $2, 'terminal', $3, 'owned')
+                    INSERT INTO session (private_beach_id, origin_session_id, kind, created_by_account_id, attach_method, capabilities)
+                    VALUES ($1, $2, 'terminal', $3, 'owned', $4)
                     ON CONFLICT (private_beach_id, origin_session_id) DO NOTHING
                     "#,
                 )
                 .bind(beach_uuid)
                 .bind(origin_uuid)
                 .bind(requester)
+                .bind(Json(ActionKind::all_capabilities()))
                 .execute(tx.as_mut())
                 .await?;
                         if result.rows_affected() == 0 {
@@ -2846,6 +3078,7 @@ impl AppState {
                 if let Some(loc) = location_hint {
                     record.location_hint = Some(loc);
                 }
+                record.last_seen_at_ms = now_ms();
                 Ok(())
             }
             Backend::Postgres(pool) => {
@@ -2887,6 +3120,16 @@ impl AppState {
         &self,
         private_beach_id: &str,
     ) -> Result<Vec<SessionSummary>, StateError> {
+        self.list_sessions_filtered(private_beach_id, &SessionFilter::default())
+            .await
+    }
+
+    pub async fn list_sessions_filtered(
+        &self,
+        private_beach_id: &str,
+        filter: &SessionFilter,
+    ) -> Result<Vec<SessionSummary>, StateError> {
+        let filter = filter.normalized();
         match &self.backend {
             Backend::Memory => {
                 let sessions = self.fallback.sessions.read().await;
@@ -2894,6 +3137,7 @@ impl AppState {
                     .values()
                     .filter(|record| record.private_beach_id == private_beach_id)
                     .map(SessionSummary::from_record)
+                    .filter(|summary| filter.matches_summary(summary))
                     .collect();
                 Ok(summaries)
             }
@@ -2901,7 +3145,7 @@ impl AppState {
                 let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
                 let mut tx = pool.begin().await?;
                 self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
-                let rows: Vec<SessionRow> = sqlx::query_as(
+                let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new(
                     r#"
                     SELECT
                         s.origin_session_id,
@@ -2914,7 +3158,16 @@ impl AppState {
                         sr.last_health,
                         lease.id AS controller_token,
                         lease.expires_at,
-                        lease.revoked_at
+                        lease.revoked_at,
+                        s.kind,
+                        s.title,
+                        COALESCE(
+                            (SELECT array_agg(t.tag ORDER BY lower(t.tag))
+                             FROM session_tag t WHERE t.session_id = s.id),
+                            '{}'::text[]
+                        ) AS tags,
+                        s.last_seen_at,
+                        s.ended_at
                     FROM session s
                     LEFT JOIN session_runtime sr ON sr.session_id = s.id
                     LEFT JOIN LATERAL (
@@ -2926,13 +3179,55 @@ impl AppState {
                         ORDER BY cl.expires_at DESC
                         LIMIT 1
                     ) AS lease ON TRUE
-                    WHERE s.private_beach_id = $1
-                    ORDER BY s.created_at ASC
-                    "#,
-                )
-                .bind(beach_uuid)
-                .fetch_all(tx.as_mut())
-                .await?;
+                    WHERE s.private_beach_id = "#,
+                );
+                query.push_bind(beach_uuid);
+                if let Some(tag) = &filter.tag {
+                    query
+                        .push(" AND EXISTS (SELECT 1 FROM session_tag t WHERE t.session_id = s.id AND lower(t.tag) = lower(")
+                        .push_bind(tag.clone())
+                        .push("))");
+                }
+                if let Some(harness) = &filter.harness_type {
+                    query
+                        .push(" AND s.harness_type = ")
+                        .push_bind(HarnessTypeDb::from(harness.clone()));
+                }
+                if let Some(kind) = filter.kind {
+                    query.push(" AND s.kind = ").push_bind(kind);
+                }
+                if let Some(after) = filter.last_seen_after_ms {
+                    query
+                        .push(" AND s.last_seen_at >= to_timestamp(")
+                        .push_bind(after)
+                        .push(" / 1000.0)");
+                }
+                if let Some(before) = filter.last_seen_before_ms {
+                    query
+                        .push(" AND s.last_seen_at <= to_timestamp(")
+                        .push_bind(before)
+                        .push(" / 1000.0)");
+                }
+                match filter.status {
+                    Some(SessionStatus::Active) => {
+                        query.push(" AND s.ended_at IS NULL");
+                    }
+                    Some(SessionStatus::Ended) => {
+                        query.push(" AND s.ended_at IS NOT NULL");
+                    }
+                    None => {}
+                }
+                if let Some(q) = &filter.q {
+                    let pattern = format!("%{}%", escape_like(q));
+                    query
+                        .push(" AND (s.title ILIKE ")
+                        .push_bind(pattern.clone())
+                        .push(" OR s.location_hint ILIKE ")
+                        .push_bind(pattern)
+                        .push(")");
+                }
+                query.push(" ORDER BY s.created_at ASC");
+                let rows: Vec<SessionRow> = query.build_query_as().fetch_all(tx.as_mut()).await?;
 
                 let mut summaries = Vec::with_capacity(rows.len());
                 for row in rows {
@@ -2985,6 +3280,11 @@ impl AppState {
                         pending_actions,
                         pending_unacked,
                         last_health,
+                        kind: Some(row.kind),
+                        title: row.title,
+                        tags: row.tags,
+                        last_seen_at_ms: row.last_seen_at.map(|t| t.timestamp_millis()),
+                        ended_at_ms: row.ended_at.map(|t| t.timestamp_millis()),
                     });
                 }
                 tx.commit().await?;
@@ -2993,6 +3293,100 @@ impl AppState {
         }
     }
 
+    /// Adds `tag` to a session (case-insensitive, idempotent) and returns the
+    /// session's resulting tag list.
+    pub async fn add_session_tag(
+        &self,
+        session_id: &str,
+        tag: &str,
+    ) -> Result<Vec<String>, StateError> {
+        let tag = normalize_session_tag(tag)?;
+        match &self.backend {
+            Backend::Memory => {
+                let mut sessions = self.fallback.sessions.write().await;
+                let record = sessions
+                    .get_mut(session_id)
+                    .ok_or(StateError::SessionNotFound)?;
+                if !record.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                    record.tags.push(tag);
+                    record.tags.sort_by_key(|t| t.to_lowercase());
+                }
+                Ok(record.tags.clone())
+            }
+            Backend::Postgres(pool) => {
+                let session_uuid = parse_uuid(session_id, "session_id")?;
+                let identifiers = self.fetch_session_identifiers(pool, &session_uuid).await?;
+                let mut tx = pool.begin().await?;
+                self.set_rls_context_tx(&mut tx, &identifiers.private_beach_id)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO session_tag (session_id, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                )
+                .bind(identifiers.session_id)
+                .bind(&tag)
+                .execute(tx.as_mut())
+                .await?;
+                let tags = self
+                    .fetch_session_tags_tx(&mut tx, identifiers.session_id)
+                    .await?;
+                tx.commit().await?;
+                Ok(tags)
+            }
+        }
+    }
+
+    /// Removes `tag` from a session if present and returns the remaining tags.
+    pub async fn remove_session_tag(
+        &self,
+        session_id: &str,
+        tag: &str,
+    ) -> Result<Vec<String>, StateError> {
+        let tag = tag.trim();
+        match &self.backend {
+            Backend::Memory => {
+                let mut sessions = self.fallback.sessions.write().await;
+                let record = sessions
+                    .get_mut(session_id)
+                    .ok_or(StateError::SessionNotFound)?;
+                record.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+                Ok(record.tags.clone())
+            }
+            Backend::Postgres(pool) => {
+                let session_uuid = parse_uuid(session_id, "session_id")?;
+                let identifiers = self.fetch_session_identifiers(pool, &session_uuid).await?;
+                let mut tx = pool.begin().await?;
+                self.set_rls_context_tx(&mut tx, &identifiers.private_beach_id)
+                    .await?;
+                sqlx::query(
+                    "DELETE FROM session_tag WHERE session_id = $1 AND lower(tag) = lower($2)",
+                )
+                .bind(identifiers.session_id)
+                .bind(tag)
+                .execute(tx.as_mut())
+                .await?;
+                let tags = self
+                    .fetch_session_tags_tx(&mut tx, identifiers.session_id)
+                    .await?;
+                tx.commit().await?;
+                Ok(tags)
+            }
+        }
+    }
+
+    async fn fetch_session_tags_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        session_id: Uuid,
+    ) -> Result<Vec<String>, StateError> {
+        let tags: Vec<String> = sqlx::query_scalar(
+            "SELECT tag FROM session_tag WHERE session_id = $1 ORDER BY lower(tag)",
+        )
+        .bind(session_id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(tags)
+    }
+
     pub async fn list_controller_pairings(
         &self,
         controller_session_id: &str,
@@ -3032,6 +3426,7 @@ impl AppState {
                         child.origin_session_id AS child_origin_session_id,
                         cp.prompt_template,
                         cp.update_cadence,
+                        cp.automation_assignment_id,
                         cp.created_at,
                         cp.updated_at
                     FROM controller_pairing cp
@@ -3071,6 +3466,29 @@ impl AppState {
         prompt_template: Option<String>,
         update_cadence: Option<ControllerUpdateCadence>,
         actor_account_id: Option<Uuid>,
+    ) -> Result<ControllerPairing, StateError> {
+        self.upsert_controller_pairing_with_owner(
+            controller_session_id,
+            child_session_id,
+            prompt_template,
+            update_cadence,
+            actor_account_id,
+            None,
+        )
+        .await
+    }
+
+    /// Upserts a pairing on behalf of `automation_assignment_id`, or by hand
+    /// when it is `None`. Editing a pairing by hand takes it over from the
+    /// assignment that created it.
+    async fn upsert_controller_pairing_with_owner(
+        &self,
+        controller_session_id: &str,
+        child_session_id: &str,
+        prompt_template: Option<String>,
+        update_cadence: Option<ControllerUpdateCadence>,
+        actor_account_id: Option<Uuid>,
+        automation_assignment_id: Option<&str>,
     ) -> Result<ControllerPairing, StateError> {
         if let Some(account_id) = actor_account_id.as_ref() {
             self.ensure_controller_account_active(
@@ -3119,6 +3537,7 @@ impl AppState {
                     prompt_template: prompt_template.clone(),
                     update_cadence: update_cadence.unwrap_or_default(),
                     transport_status: None,
+                    automation_assignment_id: automation_assignment_id.map(str::to_string),
                     created_at_ms: now,
                     updated_at_ms: now,
                 };
@@ -3208,6 +3627,9 @@ impl AppState {
                     .ok_or(StateError::ControllerLeaseRequired)?;
 
                 let cadence = update_cadence.unwrap_or_default();
+                let automation_assignment_uuid = automation_assignment_id
+                    .map(|id| parse_uuid(id, "automation_assignment_id"))
+                    .transpose()?;
                 let mut tx = pool.begin().await?;
                 self.set_rls_context_tx(&mut tx, &controller_identifiers.private_beach_id)
                     .await?;
@@ -3221,13 +3643,15 @@ impl AppState {
                         controller_session_id,
                         child_session_id,
                         prompt_template,
-                        update_cadence
+                        update_cadence,
+                        automation_assignment_id
                     )
-                    VALUES ($1, $2, $3, $4)
+                    VALUES ($1, $2, $3, $4, $5)
                     ON CONFLICT (controller_session_id, child_session_id)
                     DO UPDATE SET
                         prompt_template = EXCLUDED.prompt_template,
                         update_cadence = EXCLUDED.update_cadence,
+                        automation_assignment_id = EXCLUDED.automation_assignment_id,
                         updated_at = NOW()
                     RETURNING
                         controller_session_id,
@@ -3236,6 +3660,7 @@ impl AppState {
                         (SELECT origin_session_id FROM session WHERE id = controller_pairing.child_session_id) AS child_origin_session_id,
                         prompt_template,
                         update_cadence,
+                        automation_assignment_id,
                         created_at,
                         updated_at
                     "#,
@@ -3244,6 +3669,7 @@ impl AppState {
                 .bind(child_identifiers.session_id)
                 .bind(prompt_template)
                 .bind(cadence)
+                .bind(automation_assignment_uuid)
                 .fetch_one(tx.as_mut())
                 .await?;
 
@@ -3415,6 +3841,7 @@ impl AppState {
                         (SELECT origin_session_id FROM session WHERE id = controller_pairing.child_session_id) AS child_origin_session_id,
                         prompt_template,
                         update_cadence,
+                        automation_assignment_id,
                         created_at,
                         updated_at
                     "#,
@@ -3488,6 +3915,31 @@ impl AppState {
         ttl_override: Option<u64>,
         reason: Option<String>,
         requester: Option<Uuid>,
+    ) -> Result<ControllerLeaseResponse, StateError> {
+        self.issue_controller_lease(session_id, ttl_override, reason, requester, true)
+            .await
+    }
+
+    /// Keeps a lease alive for a background holder: extending an existing lease
+    /// records no controller event, only a fresh acquisition does.
+    async fn renew_controller_lease(
+        &self,
+        session_id: &str,
+        ttl_override: Option<u64>,
+        reason: Option<String>,
+        requester: Option<Uuid>,
+    ) -> Result<ControllerLeaseResponse, StateError> {
+        self.issue_controller_lease(session_id, ttl_override, reason, requester, false)
+            .await
+    }
+
+    async fn issue_controller_lease(
+        &self,
+        session_id: &str,
+        ttl_override: Option<u64>,
+        reason: Option<String>,
+        requester: Option<Uuid>,
+        record_renewal: bool,
     ) -> Result<ControllerLeaseResponse, StateError> {
         let backend_label = match &self.backend {
             Backend::Memory => "memory",
@@ -3506,8 +3958,13 @@ impl AppState {
 
         let response = match &self.backend {
             Backend::Memory => {
-                self.acquire_controller_memory(session_id, ttl_override, reason.clone())
-                    .await?
+                self.acquire_controller_memory(
+                    session_id,
+                    ttl_override,
+                    reason.clone(),
+                    record_renewal,
+                )
+                .await?
             }
             Backend::Postgres(pool) => {
                 let session_uuid = parse_uuid(session_id, "session_id")?;
@@ -3533,6 +3990,7 @@ impl AppState {
                         reason.as_deref(),
                     )
                     .await?;
+                let announce = record_renewal || existing.is_none();
 
                 let (controller_token, lease_account_id) = if let Some(lease) = existing {
                     sqlx::query(
@@ -3594,16 +4052,18 @@ impl AppState {
                     }
                 };
 
-                self.insert_controller_event(
-                    &mut tx,
-                    identifiers.session_id,
-                    "lease_acquired",
-                    Some(controller_token),
-                    lease_account_id,
-                    requester_uuid,
-                    reason.clone(),
-                )
-                .await?;
+                if announce {
+                    self.insert_controller_event(
+                        &mut tx,
+                        identifiers.session_id,
+                        "lease_acquired",
+                        Some(controller_token),
+                        lease_account_id,
+                        requester_uuid,
+                        reason.clone(),
+                    )
+                    .await?;
+                }
 
                 let active_leases = self
                     .list_active_leases_tx(&mut tx, identifiers.session_id)
@@ -3623,19 +4083,21 @@ impl AppState {
                     .await;
 
                 // Emit a controller event on the SSE channel
-                self.publish(
-                    session_id,
-                    StreamEvent::ControllerEvent(ControllerEvent {
-                        id: Uuid::new_v4().to_string(),
-                        event_type: ControllerEventType::LeaseAcquired,
-                        controller_token: Some(controller_token.to_string()),
-                        timestamp_ms: now_ms(),
-                        reason: reason.clone(),
-                        controller_account_id: lease_account_id.map(|u| u.to_string()),
-                        issued_by_account_id: requester_uuid.map(|u| u.to_string()),
-                    }),
-                )
-                .await;
+                if announce {
+                    self.publish(
+                        session_id,
+                        StreamEvent::ControllerEvent(ControllerEvent {
+                            id: Uuid::new_v4().to_string(),
+                            event_type: ControllerEventType::LeaseAcquired,
+                            controller_token: Some(controller_token.to_string()),
+                            timestamp_ms: now_ms(),
+                            reason: reason.clone(),
+                            controller_account_id: lease_account_id.map(|u| u.to_string()),
+                            issued_by_account_id: requester_uuid.map(|u| u.to_string()),
+                        }),
+                    )
+                    .await;
+                }
 
                 self.log_controller_leases(
                     "lease_update",
@@ -3807,6 +4269,14 @@ impl AppState {
                     )
                     .await?;
                 }
+                let capabilities = self
+                    .fetch_session_capabilities(pool, identifiers.session_id)
+                    .await?;
+                validate_controller_actions(&actions, &capabilities)?;
+                let mut actions = actions;
+                if let Some(expires_at) = lease.expires_at {
+                    bind_actions_to_lease(&mut actions, expires_at.into());
+                }
                 let active_leases = self
                     .list_active_leases(pool, identifiers.session_id)
                     .await?;
@@ -4871,7 +5341,7 @@ impl AppState {
                 self.insert_controller_event(
                     &mut tx,
                     identifiers.session_id,
-                    "lease_released",
+                    "emergency_stop",
                     None,
                     None,
                     actor_account_id,
@@ -4884,6 +5354,17 @@ impl AppState {
                 self.fallback.clear_pending_actions(session_id).await;
                 self.fallback.clear_controller(session_id, None).await;
 
+                self.enqueue_webhook(
+                    session_id,
+                    "controller.emergency_stop".into(),
+                    json!({
+                        "session_id": session_id,
+                        "issued_by_account_id": actor_account_id,
+                        "reason": reason,
+                        "timestamp_ms": now_ms(),
+                    }),
+                )
+                .await;
                 self.publish(
                     session_id,
                     StreamEvent::ControllerEvent(ControllerEvent {
@@ -5291,6 +5772,8 @@ impl InnerState {
             pairings: RwLock::new(HashMap::new()),
             canvas_layouts: RwLock::new(HashMap::new()),
             pending_transport: RwLock::new(HashMap::new()),
+            files: RwLock::new(HashMap::new()),
+            automation_assignments: RwLock::new(HashMap::new()),
         }
     }
 
@@ -5686,6 +6169,8 @@ impl SessionRecord {
             attached_at_ms: None,
             http_ready_since_ms: None,
             transport_mode,
+            tags: Vec::new(),
+            last_seen_at_ms: now_ms(),
         }
     }
 
@@ -5784,6 +6269,18 @@ impl SessionRecord {
         self.attached_at_ms = Some(now_ms());
     }
 
+    /// Attached sessions run the beach terminal host, which applies every
+    /// catalog action; record that unless the harness registered its own.
+    fn advertise_host_actions(&mut self) {
+        if !self
+            .capabilities
+            .iter()
+            .any(|cap| cap.starts_with(ACTION_CAPABILITY_PREFIX))
+        {
+            self.capabilities.extend(ActionKind::all_capabilities());
+        }
+    }
+
     fn mark_http_ready(&mut self) {
         self.http_ready_since_ms = Some(now_ms());
     }
@@ -5847,6 +6344,11 @@ impl SessionSummary {
             pending_actions: record.pending_actions.len(),
             pending_unacked: record.pending_actions.len(),
             last_health: record.last_health.clone(),
+            kind: Some(SessionKind::from(&record.harness_type)),
+            title: None,
+            tags: record.tags.clone(),
+            last_seen_at_ms: Some(record.last_seen_at_ms),
+            ended_at_ms: None,
         }
     }
 }
@@ -5870,6 +6372,35 @@ fn redact_controller_token(token: &str) -> String {
     }
 }
 
+const MAX_SESSION_TAG_LEN: usize = 64;
+
+fn normalize_session_tag(tag: &str) -> Result<String, StateError> {
+    let tag = tag.trim();
+    if tag.is_empty()
+        || tag.chars().count() > MAX_SESSION_TAG_LEN
+        || tag
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == ',')
+    {
+        return Err(StateError::InvalidIdentifier(format!(
+            "invalid session tag '{tag}'"
+        )));
+    }
+    Ok(tag.to_string())
+}
+
+/// Escapes `%`, `_` and `\\` so user input is matched literally by `ILIKE`.
+fn escape_like(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
 fn parse_uuid(value: &str, label: &str) -> Result<Uuid, StateError> {
     Uuid::parse_str(value).map_err(|_| StateError::InvalidIdentifier(format!("{label}={value}")))
 }
@@ -5909,13 +6440,7 @@ impl From<HarnessType> for HarnessTypeDb {
 }
 
 fn harness_to_session_kind(value: &HarnessType) -> &'static str {
-    match value {
-        HarnessType::TerminalShim => "terminal",
-        HarnessType::CabanaAdapter => "cabana_gui",
-        HarnessType::RemoteWidget => "widget",
-        HarnessType::ServiceProxy => "service_daemon",
-        HarnessType::Custom => "widget",
-    }
+    SessionKind::from(value).as_str()
 }
 
 fn default_transport_hints(
@@ -5961,6 +6486,7 @@ fn controller_event_from_str(value: &str) -> ControllerEventType {
         "state_updated" => ControllerEventType::StateUpdated,
         "pairing_added" => ControllerEventType::PairingAdded,
         "pairing_removed" => ControllerEventType::PairingRemoved,
+        "emergency_stop" => ControllerEventType::EmergencyStop,
         _ => ControllerEventType::Registered,
     }
 }
@@ -6307,6 +6833,7 @@ impl AppState {
         session_id: &str,
         ttl_override: Option<u64>,
         reason: Option<String>,
+        record_renewal: bool,
     ) -> Result<ControllerLeaseResponse, StateError> {
         let ttl = ttl_override.unwrap_or(DEFAULT_LEASE_TTL_MS);
         let mut sessions = self.fallback.sessions.write().await;
@@ -6316,20 +6843,23 @@ impl AppState {
 
         record.lease_ttl_ms = ttl;
         let expires_at_ms = now_ms() + ttl as i64;
-        let token = if let Some((token, lease)) = record.find_lease_mut_by_reason(reason.as_deref())
+        let (token, renewed) = if let Some((token, lease)) =
+            record.find_lease_mut_by_reason(reason.as_deref())
         {
             lease.expires_at_ms = expires_at_ms;
-            token.clone()
+            (token.clone(), true)
         } else {
             let token = Uuid::new_v4().to_string();
             record.ensure_lease(token.clone(), expires_at_ms, None, None, reason.clone());
-            token
+            (token, false)
         };
-        record.append_event(
-            ControllerEventType::LeaseAcquired,
-            Some(token.clone()),
-            reason,
-        );
+        if record_renewal || !renewed {
+            record.append_event(
+                ControllerEventType::LeaseAcquired,
+                Some(token.clone()),
+                reason,
+            );
+        }
         self.log_memory_leases("lease_update", record);
 
         Ok(ControllerLeaseResponse {
@@ -6477,6 +7007,13 @@ impl AppState {
         let record = sessions
             .get_mut(session_id)
             .ok_or(StateError::SessionNotFound)?;
+        validate_controller_actions(&actions, &record.capabilities)?;
+        let mut actions = actions;
+        if let Some(lease) = record.lease(controller_token) {
+            let expires_at =
+                UNIX_EPOCH + StdDuration::from_millis(lease.expires_at_ms.max(0) as u64);
+            bind_actions_to_lease(&mut actions, expires_at);
+        }
         let action_count = actions.len();
         for action in actions {
             record.pending_actions.push_back(action);
@@ -6721,6 +7258,23 @@ impl AppState {
         row.ok_or(StateError::SessionNotFound)
     }
 
+    async fn fetch_session_capabilities(
+        &self,
+        pool: &PgPool,
+        session_id: Uuid,
+    ) -> Result<Vec<String>, StateError> {
+        let capabilities = sqlx::query_scalar::<_, Option<Json<serde_json::Value>>>(
+            "SELECT capabilities FROM session WHERE id = $1",
+        )
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+        Ok(capabilities
+            .and_then(|Json(value)| serde_json::from_value(value).ok())
+            .unwrap_or_default())
+    }
+
     async fn fetch_session_identifiers_for_private_beach(
         &self,
         pool: &PgPool,
@@ -7798,6 +8352,7 @@ impl AppState {
             let mut layouts = self.fallback.canvas_layouts.write().await;
             layouts.remove(private_beach_id);
         }
+        self.webhook_index.write().await.forget_beach(private_beach_id);
         {
             let mut handshakes = self.controller_handshakes.write().await;
             for session_id in &session_set {
@@ -8022,21 +8577,3501 @@ impl AppState {
         if agent_sessions.is_empty() || !tracing::enabled!(Level::TRACE) {
             return;
         }
-        let joined = agent_sessions.join(",");
-        trace!(
-            target = "agent_controller_comms",
-            direction = direction,
-            transport = transport,
-            agent_sessions = %joined,
-            child_session_id = %child_session_id,
-            private_beach_id = %private_beach_id,
-            bytes = payload.as_bytes().len(),
-            payload = %payload,
-            "agent harness communication"
-        );
+        let joined = agent_sessions.join(",");
+        trace!(
+            target = "agent_controller_comms",
+            direction = direction,
+            transport = transport,
+            agent_sessions = %joined,
+            child_session_id = %child_session_id,
+            private_beach_id = %private_beach_id,
+            bytes = payload.as_bytes().len(),
+            payload = %payload,
+            "agent harness communication"
+        );
+    }
+}
+
+// ---- Private Beaches: share links ----
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "membership_role", rename_all = "snake_case")]
+pub enum MembershipRole {
+    Owner,
+    Admin,
+    Contributor,
+    Viewer,
+}
+
+impl MembershipRole {
+    pub fn can_manage_members(self) -> bool {
+        matches!(self, MembershipRole::Owner | MembershipRole::Admin)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareLinkRejection {
+    Revoked,
+    Expired,
+    Exhausted,
+}
+
+impl ShareLinkRejection {
+    pub fn code(self) -> &'static str {
+        match self {
+            ShareLinkRejection::Revoked => "share_link_revoked",
+            ShareLinkRejection::Expired => "share_link_expired",
+            ShareLinkRejection::Exhausted => "share_link_exhausted",
+        }
+    }
+
+    pub fn default_message(self) -> &'static str {
+        match self {
+            ShareLinkRejection::Revoked => "share link has been revoked",
+            ShareLinkRejection::Expired => "share link has expired",
+            ShareLinkRejection::Exhausted => "share link has no uses remaining",
+        }
+    }
+}
+
+impl std::fmt::Display for ShareLinkRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ShareLink {
+    pub id: String,
+    pub private_beach_id: String,
+    pub label: Option<String>,
+    pub granted_role: MembershipRole,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at_ms: Option<i64>,
+    pub revoked_at_ms: Option<i64>,
+    pub created_by_account_id: Option<String>,
+    pub created_at_ms: i64,
+}
+
+/// Returned once at creation; only the token hash is stored.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreatedShareLink {
+    #[serde(flatten)]
+    pub link: ShareLink,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ShareLinkRedemption {
+    pub private_beach_id: String,
+    pub membership_id: String,
+    pub role: MembershipRole,
+    /// `true` when the caller was already a member and no use was consumed.
+    pub already_member: bool,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ShareLinkRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    created_by_account_id: Option<Uuid>,
+    label: Option<String>,
+    granted_role: MembershipRole,
+    max_uses: Option<i32>,
+    use_count: i32,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl ShareLinkRow {
+    fn into_share_link(self) -> ShareLink {
+        ShareLink {
+            id: self.id.to_string(),
+            private_beach_id: self.private_beach_id.to_string(),
+            label: self.label,
+            granted_role: self.granted_role,
+            max_uses: self.max_uses,
+            use_count: self.use_count,
+            expires_at_ms: self.expires_at.map(|ts| ts.timestamp_millis()),
+            revoked_at_ms: self.revoked_at.map(|ts| ts.timestamp_millis()),
+            created_by_account_id: self.created_by_account_id.map(|id| id.to_string()),
+            created_at_ms: self.created_at.timestamp_millis(),
+        }
+    }
+
+    fn rejection(&self, now: DateTime<Utc>) -> Option<ShareLinkRejection> {
+        share_link_rejection(
+            self.revoked_at,
+            self.expires_at,
+            self.max_uses,
+            self.use_count,
+            now,
+        )
+    }
+
+    /// Refuses a revoked, expired or used-up link before looking at
+    /// `existing`, so a member cannot use it to learn the token was once
+    /// valid. A live link hands an existing member their membership without
+    /// consuming a use; `None` means the caller still needs one.
+    fn member_redemption(
+        &self,
+        existing: Option<(Uuid, MembershipRole)>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ShareLinkRedemption>, ShareLinkRejection> {
+        if let Some(rejection) = self.rejection(now) {
+            return Err(rejection);
+        }
+        Ok(existing.map(|(membership_id, role)| ShareLinkRedemption {
+            private_beach_id: self.private_beach_id.to_string(),
+            membership_id: membership_id.to_string(),
+            role,
+            already_member: true,
+        }))
+    }
+}
+
+const SHARE_LINK_COLUMNS: &str = "id, private_beach_id, created_by_account_id, label, granted_role, max_uses, use_count, expires_at, revoked_at, created_at";
+
+fn share_link_rejection(
+    revoked_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    max_uses: Option<i32>,
+    use_count: i32,
+    now: DateTime<Utc>,
+) -> Option<ShareLinkRejection> {
+    if revoked_at.is_some() {
+        Some(ShareLinkRejection::Revoked)
+    } else if expires_at.is_some_and(|expires| expires <= now) {
+        Some(ShareLinkRejection::Expired)
+    } else if max_uses.is_some_and(|max| use_count >= max) {
+        Some(ShareLinkRejection::Exhausted)
+    } else {
+        None
+    }
+}
+
+fn generate_share_link_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("bsl_{}", hex::encode(bytes))
+}
+
+fn hash_share_link_token(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(token.trim().as_bytes()))
+}
+
+impl AppState {
+    /// Only owners and admins (or anyone, for ownerless dev beaches without
+    /// an account) may manage a beach's share links.
+    async fn ensure_member_manager_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        let owner: Option<(Option<Uuid>,)> =
+            sqlx::query_as("SELECT owner_account_id FROM private_beach WHERE id = $1")
+                .bind(private_beach_id)
+                .fetch_optional(tx.as_mut())
+                .await?;
+        let Some((owner,)) = owner else {
+            return Err(StateError::PrivateBeachNotFound);
+        };
+        let Some(account) = account else {
+            return if owner.is_none() {
+                Ok(())
+            } else {
+                Err(StateError::PrivateBeachNotFound)
+            };
+        };
+        if owner == Some(account) {
+            return Ok(());
+        }
+        let role: Option<MembershipRole> = sqlx::query_scalar(
+            r#"
+            SELECT role
+            FROM private_beach_membership
+            WHERE private_beach_id = $1 AND account_id = $2 AND status = 'active'
+            "#,
+        )
+        .bind(private_beach_id)
+        .bind(account)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        match role {
+            Some(role) if role.can_manage_members() => Ok(()),
+            _ => Err(StateError::InsufficientRole),
+        }
+    }
+
+    pub async fn create_share_link(
+        &self,
+        private_beach_id: &str,
+        label: Option<&str>,
+        granted_role: MembershipRole,
+        max_uses: Option<i32>,
+        expires_at: Option<DateTime<Utc>>,
+        account: Option<Uuid>,
+    ) -> Result<CreatedShareLink, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => {
+                return Err(StateError::Database(sqlx::Error::Protocol(
+                    "requires postgres backend".into(),
+                )));
+            }
+        };
+        if granted_role == MembershipRole::Owner {
+            return Err(StateError::InvalidIdentifier(
+                "share links cannot grant the owner role".into(),
+            ));
+        }
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+
+        let token = generate_share_link_token();
+        let row: ShareLinkRow = sqlx::query_as(&format!(
+            r#"
+            INSERT INTO share_link
+                (private_beach_id, created_by_account_id, label, token_hash, granted_role, max_uses, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {SHARE_LINK_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .bind(account)
+        .bind(label)
+        .bind(hash_share_link_token(&token))
+        .bind(granted_role)
+        .bind(max_uses)
+        .bind(expires_at)
+        .fetch_one(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        info!(
+            target = "private_beach",
+            private_beach_id = %id,
+            share_link_id = %row.id,
+            role = ?granted_role,
+            "share link created"
+        );
+        Ok(CreatedShareLink {
+            link: row.into_share_link(),
+            token,
+        })
+    }
+
+    pub async fn list_share_links(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<ShareLink>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let rows: Vec<ShareLinkRow> = sqlx::query_as(&format!(
+            r#"
+            SELECT {SHARE_LINK_COLUMNS}
+            FROM share_link
+            WHERE private_beach_id = $1
+            ORDER BY created_at DESC
+            "#
+        ))
+        .bind(id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(rows
+            .into_iter()
+            .map(ShareLinkRow::into_share_link)
+            .collect())
+    }
+
+    pub async fn revoke_share_link(
+        &self,
+        private_beach_id: &str,
+        link_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<ShareLink, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::ShareLinkNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let link_id = parse_uuid(link_id, "link_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let row: Option<ShareLinkRow> = sqlx::query_as(&format!(
+            r#"
+            UPDATE share_link
+            SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = $1 AND private_beach_id = $2
+            RETURNING {SHARE_LINK_COLUMNS}
+            "#
+        ))
+        .bind(link_id)
+        .bind(id)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        let row = row.ok_or(StateError::ShareLinkNotFound)?;
+        info!(
+            target = "private_beach",
+            private_beach_id = %id,
+            share_link_id = %link_id,
+            "share link revoked"
+        );
+        Ok(row.into_share_link())
+    }
+
+    /// Consumes one use of the link and adds `account` to its beach. The link
+    /// row stays locked until commit so concurrent redemptions cannot exceed
+    /// `max_uses`.
+    pub async fn redeem_share_link(
+        &self,
+        token: &str,
+        account: Uuid,
+    ) -> Result<ShareLinkRedemption, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::ShareLinkNotFound),
+        };
+        let token_hash = hash_share_link_token(token);
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, Some(&account)).await?;
+        let link: ShareLinkRow = sqlx::query_as(&format!(
+            "SELECT {SHARE_LINK_COLUMNS} FROM share_link WHERE token_hash = $1 FOR UPDATE"
+        ))
+        .bind(&token_hash)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(StateError::ShareLinkNotFound)?;
+        self.set_rls_context_tx(&mut tx, &link.private_beach_id)
+            .await?;
+
+        let existing: Option<(Uuid, MembershipRole)> = sqlx::query_as(
+            r#"
+            SELECT id, role
+            FROM private_beach_membership
+            WHERE private_beach_id = $1 AND account_id = $2 AND status = 'active'
+            "#,
+        )
+        .bind(link.private_beach_id)
+        .bind(account)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        if let Some(redemption) = link
+            .member_redemption(existing, Utc::now())
+            .map_err(StateError::ShareLinkRejected)?
+        {
+            tx.commit().await?;
+            return Ok(redemption);
+        }
+
+        sqlx::query("UPDATE share_link SET use_count = use_count + 1 WHERE id = $1")
+            .bind(link.id)
+            .execute(tx.as_mut())
+            .await?;
+        let membership_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO private_beach_membership
+                (private_beach_id, account_id, role, status, invited_by_account_id, invitation_token_hash, invited_at, activated_at)
+            VALUES ($1, $2, $3, 'active', $4, $5, NOW(), NOW())
+            ON CONFLICT (private_beach_id, account_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(link.private_beach_id)
+        .bind(account)
+        .bind(link.granted_role)
+        .bind(link.created_by_account_id)
+        .bind(&token_hash)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        // A suspended or revoked membership row blocks re-entry via links.
+        let membership_id = membership_id.ok_or(StateError::InsufficientRole)?;
+        tx.commit().await?;
+        info!(
+            target = "private_beach",
+            private_beach_id = %link.private_beach_id,
+            share_link_id = %link.id,
+            account_id = %account,
+            role = ?link.granted_role,
+            "share link redeemed"
+        );
+        Ok(ShareLinkRedemption {
+            private_beach_id: link.private_beach_id.to_string(),
+            membership_id: membership_id.to_string(),
+            role: link.granted_role,
+            already_member: false,
+        })
+    }
+}
+
+// ---- Private Beaches: groups ----
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "group_role", rename_all = "snake_case")]
+pub enum GroupRole {
+    Admin,
+    Member,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct GroupGrant {
+    pub scope: String,
+    /// Limits the grant to sessions carrying this tag; applies beach-wide when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BeachGroup {
+    pub id: String,
+    pub private_beach_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub grants: Vec<GroupGrant>,
+    pub member_count: i64,
+    pub created_by_account_id: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GroupMember {
+    pub account_id: String,
+    pub role: GroupRole,
+    pub added_at_ms: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct BeachGroupRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    name: String,
+    description: Option<String>,
+    created_by_account_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    member_count: i64,
+}
+
+const BEACH_GROUP_COLUMNS: &str = "g.id, g.private_beach_id, g.name, g.description, g.created_by_account_id, g.created_at, g.updated_at, (SELECT COUNT(*) FROM group_membership gm WHERE gm.beach_group_id = g.id) AS member_count";
+
+/// Scopes a group grant may carry: the ones session and beach routes resolve
+/// through group membership. Wildcards are never grantable.
+pub const GROUP_GRANTABLE_SCOPES: &[&str] = &[
+    "pb:sessions.read",
+    "pb:sessions.write",
+    "pb:control.write",
+    "pb:files.read",
+    "pb:files.write",
+];
+
+fn normalize_group_grants(grants: Vec<GroupGrant>) -> Result<Vec<GroupGrant>, StateError> {
+    let mut out: Vec<GroupGrant> = Vec::with_capacity(grants.len());
+    for grant in grants {
+        let scope = grant.scope.trim().to_string();
+        if !GROUP_GRANTABLE_SCOPES.contains(&scope.as_str()) {
+            return Err(StateError::InvalidIdentifier(format!(
+                "invalid group grant scope '{}'",
+                grant.scope
+            )));
+        }
+        let session_tag = grant
+            .session_tag
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty());
+        let normalized = GroupGrant { scope, session_tag };
+        if !out.iter().any(|existing| {
+            existing.scope == normalized.scope
+                && existing.session_tag.as_deref().map(str::to_lowercase)
+                    == normalized.session_tag.as_deref().map(str::to_lowercase)
+        }) {
+            out.push(normalized);
+        }
+    }
+    Ok(out)
+}
+
+impl AppState {
+    async fn ensure_beach_visible_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+    ) -> Result<(), StateError> {
+        let exists: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM private_beach WHERE id = $1")
+            .bind(private_beach_id)
+            .fetch_optional(tx.as_mut())
+            .await?;
+        exists.map(|_| ()).ok_or(StateError::PrivateBeachNotFound)
+    }
+
+    async fn fetch_beach_group_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+        group_id: &Uuid,
+    ) -> Result<BeachGroup, StateError> {
+        let row: BeachGroupRow = sqlx::query_as(&format!(
+            "SELECT {BEACH_GROUP_COLUMNS} FROM beach_group g WHERE g.id = $1 AND g.private_beach_id = $2"
+        ))
+        .bind(group_id)
+        .bind(private_beach_id)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(StateError::GroupNotFound)?;
+        let mut grants = self.fetch_group_grants_tx(tx, &[row.id]).await?;
+        let row_grants = grants.remove(&row.id).unwrap_or_default();
+        Ok(beach_group_from_row(row, row_grants))
+    }
+
+    async fn fetch_group_grants_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        group_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, Vec<GroupGrant>>, StateError> {
+        let rows: Vec<(Uuid, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT beach_group_id, scope, session_tag
+            FROM beach_group_grant
+            WHERE beach_group_id = ANY($1)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(group_ids)
+        .fetch_all(tx.as_mut())
+        .await?;
+        let mut grants: HashMap<Uuid, Vec<GroupGrant>> = HashMap::new();
+        for (group_id, scope, session_tag) in rows {
+            grants
+                .entry(group_id)
+                .or_default()
+                .push(GroupGrant { scope, session_tag });
+        }
+        Ok(grants)
+    }
+
+    async fn replace_group_grants_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        group_id: &Uuid,
+        grants: &[GroupGrant],
+    ) -> Result<(), StateError> {
+        sqlx::query("DELETE FROM beach_group_grant WHERE beach_group_id = $1")
+            .bind(group_id)
+            .execute(tx.as_mut())
+            .await?;
+        for grant in grants {
+            sqlx::query(
+                "INSERT INTO beach_group_grant (beach_group_id, scope, session_tag) VALUES ($1, $2, $3)",
+            )
+            .bind(group_id)
+            .bind(&grant.scope)
+            .bind(grant.session_tag.as_deref())
+            .execute(tx.as_mut())
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Beach owners/admins manage every group; group admins manage their own
+    /// group's membership.
+    async fn ensure_group_manager_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+        group_id: &Uuid,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        match self
+            .ensure_member_manager_tx(tx, private_beach_id, account)
+            .await
+        {
+            Err(StateError::InsufficientRole) => {}
+            other => return other,
+        }
+        let role: Option<GroupRole> = sqlx::query_scalar(
+            "SELECT role FROM group_membership WHERE beach_group_id = $1 AND account_id = $2",
+        )
+        .bind(group_id)
+        .bind(account)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        match role {
+            Some(GroupRole::Admin) => Ok(()),
+            _ => Err(StateError::InsufficientRole),
+        }
+    }
+
+    /// Only accounts that own or actively belong to the beach can join its groups.
+    async fn ensure_active_member_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+        member: &Uuid,
+    ) -> Result<(), StateError> {
+        let member_of_beach: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM private_beach
+                WHERE id = $1 AND owner_account_id = $2
+            ) OR EXISTS (
+                SELECT 1 FROM private_beach_membership
+                WHERE private_beach_id = $1 AND account_id = $2 AND status = 'active'
+            )
+            "#,
+        )
+        .bind(private_beach_id)
+        .bind(member)
+        .fetch_one(tx.as_mut())
+        .await?;
+        if member_of_beach {
+            Ok(())
+        } else {
+            Err(StateError::InvalidIdentifier(format!(
+                "account {member} is not a member of this private beach"
+            )))
+        }
+    }
+
+    pub async fn list_beach_groups(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<BeachGroup>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_beach_visible_tx(&mut tx, &id).await?;
+        let rows: Vec<BeachGroupRow> = sqlx::query_as(&format!(
+            "SELECT {BEACH_GROUP_COLUMNS} FROM beach_group g WHERE g.private_beach_id = $1 ORDER BY lower(g.name)"
+        ))
+        .bind(id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        let mut grants = self.fetch_group_grants_tx(&mut tx, &ids).await?;
+        tx.commit().await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let row_grants = grants.remove(&row.id).unwrap_or_default();
+                beach_group_from_row(row, row_grants)
+            })
+            .collect())
+    }
+
+    pub async fn create_beach_group(
+        &self,
+        private_beach_id: &str,
+        name: &str,
+        description: Option<&str>,
+        grants: Vec<GroupGrant>,
+        account: Option<Uuid>,
+    ) -> Result<BeachGroup, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => {
+                return Err(StateError::Database(sqlx::Error::Protocol(
+                    "requires postgres backend".into(),
+                )));
+            }
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(StateError::InvalidIdentifier(
+                "group name is required".into(),
+            ));
+        }
+        let grants = normalize_group_grants(grants)?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let group_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO beach_group (private_beach_id, name, description, created_by_account_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (private_beach_id, lower(name)) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(id)
+        .bind(name)
+        .bind(description)
+        .bind(account)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        let group_id = group_id.ok_or(StateError::GroupNameConflict)?;
+        self.replace_group_grants_tx(&mut tx, &group_id, &grants)
+            .await?;
+        let group = self.fetch_beach_group_tx(&mut tx, &id, &group_id).await?;
+        tx.commit().await?;
+        info!(
+            target = "private_beach",
+            private_beach_id = %id,
+            group_id = %group_id,
+            grants = grants.len(),
+            "beach group created"
+        );
+        Ok(group)
+    }
+
+    pub async fn update_beach_group(
+        &self,
+        private_beach_id: &str,
+        group_id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        grants: Option<Vec<GroupGrant>>,
+        account: Option<Uuid>,
+    ) -> Result<BeachGroup, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::GroupNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let group_id = parse_uuid(group_id, "group_id")?;
+        let name = name.map(str::trim);
+        if name.is_some_and(str::is_empty) {
+            return Err(StateError::InvalidIdentifier(
+                "group name cannot be empty".into(),
+            ));
+        }
+        let grants = grants.map(normalize_group_grants).transpose()?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let updated = sqlx::query(
+            r#"
+            UPDATE beach_group
+            SET name = COALESCE($3, name),
+                description = COALESCE($4, description),
+                updated_at = NOW()
+            WHERE id = $1 AND private_beach_id = $2
+            "#,
+        )
+        .bind(group_id)
+        .bind(id)
+        .bind(name)
+        .bind(description)
+        .execute(tx.as_mut())
+        .await;
+        match updated {
+            Ok(result) if result.rows_affected() == 0 => return Err(StateError::GroupNotFound),
+            Ok(_) => {}
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                return Err(StateError::GroupNameConflict);
+            }
+            Err(err) => return Err(err.into()),
+        }
+        if let Some(grants) = grants.as_deref() {
+            self.replace_group_grants_tx(&mut tx, &group_id, grants)
+                .await?;
+        }
+        let group = self.fetch_beach_group_tx(&mut tx, &id, &group_id).await?;
+        tx.commit().await?;
+        Ok(group)
+    }
+
+    pub async fn delete_beach_group(
+        &self,
+        private_beach_id: &str,
+        group_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::GroupNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let group_id = parse_uuid(group_id, "group_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let deleted =
+            sqlx::query("DELETE FROM beach_group WHERE id = $1 AND private_beach_id = $2")
+                .bind(group_id)
+                .bind(id)
+                .execute(tx.as_mut())
+                .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(StateError::GroupNotFound);
+        }
+        tx.commit().await?;
+        info!(
+            target = "private_beach",
+            private_beach_id = %id,
+            group_id = %group_id,
+            "beach group deleted"
+        );
+        Ok(())
+    }
+
+    pub async fn list_group_members(
+        &self,
+        private_beach_id: &str,
+        group_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<GroupMember>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::GroupNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let group_id = parse_uuid(group_id, "group_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_beach_visible_tx(&mut tx, &id).await?;
+        self.fetch_beach_group_tx(&mut tx, &id, &group_id).await?;
+        let rows: Vec<(Uuid, GroupRole, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT account_id, role, created_at
+            FROM group_membership
+            WHERE beach_group_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(group_id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(rows
+            .into_iter()
+            .map(|(account_id, role, created_at)| GroupMember {
+                account_id: account_id.to_string(),
+                role,
+                added_at_ms: created_at.timestamp_millis(),
+            })
+            .collect())
+    }
+
+    pub async fn upsert_group_member(
+        &self,
+        private_beach_id: &str,
+        group_id: &str,
+        member_account_id: &str,
+        role: GroupRole,
+        account: Option<Uuid>,
+    ) -> Result<GroupMember, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::GroupNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let group_id = parse_uuid(group_id, "group_id")?;
+        let member = parse_uuid(member_account_id, "account_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.fetch_beach_group_tx(&mut tx, &id, &group_id).await?;
+        if role == GroupRole::Admin {
+            // Group admins manage membership but cannot mint further admins.
+            self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        } else {
+            self.ensure_group_manager_tx(&mut tx, &id, &group_id, account)
+                .await?;
+        }
+        self.ensure_active_member_tx(&mut tx, &id, &member).await?;
+        let (role, created_at): (GroupRole, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO group_membership (beach_group_id, account_id, role)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (beach_group_id, account_id) DO UPDATE SET role = EXCLUDED.role
+            RETURNING role, created_at
+            "#,
+        )
+        .bind(group_id)
+        .bind(member)
+        .bind(role)
+        .fetch_one(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(GroupMember {
+            account_id: member.to_string(),
+            role,
+            added_at_ms: created_at.timestamp_millis(),
+        })
+    }
+
+    pub async fn remove_group_member(
+        &self,
+        private_beach_id: &str,
+        group_id: &str,
+        member_account_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::GroupNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let group_id = parse_uuid(group_id, "group_id")?;
+        let member = parse_uuid(member_account_id, "account_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.fetch_beach_group_tx(&mut tx, &id, &group_id).await?;
+        self.ensure_group_manager_tx(&mut tx, &id, &group_id, account)
+            .await?;
+        sqlx::query("DELETE FROM group_membership WHERE beach_group_id = $1 AND account_id = $2")
+            .bind(group_id)
+            .bind(member)
+            .execute(tx.as_mut())
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Scopes `account` holds on a session through group grants, including
+    /// tag-limited grants whose tag the session carries. Grants lapse as soon
+    /// as the account stops being an active member of the beach.
+    pub async fn group_scopes_for_session(
+        &self,
+        account: Uuid,
+        session_id: &str,
+    ) -> Result<Vec<String>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let Ok(session_uuid) = Uuid::parse_str(session_id) else {
+            return Ok(Vec::new());
+        };
+        let identifiers = match self.fetch_session_identifiers(pool, &session_uuid).await {
+            Ok(identifiers) => identifiers,
+            Err(StateError::SessionNotFound) => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, Some(&account)).await?;
+        self.set_rls_context_tx(&mut tx, &identifiers.private_beach_id)
+            .await?;
+        let scopes: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT gg.scope
+            FROM beach_group g
+            JOIN private_beach_membership pbm
+              ON pbm.private_beach_id = g.private_beach_id
+             AND pbm.account_id = $2
+             AND pbm.status = 'active'
+            JOIN group_membership gm ON gm.beach_group_id = g.id AND gm.account_id = $2
+            JOIN beach_group_grant gg ON gg.beach_group_id = g.id
+            WHERE g.private_beach_id = $1
+              AND (
+                gg.session_tag IS NULL
+                OR EXISTS (
+                    SELECT 1 FROM session_tag t
+                    WHERE t.session_id = $3 AND lower(t.tag) = lower(gg.session_tag)
+                )
+              )
+            "#,
+        )
+        .bind(identifiers.private_beach_id)
+        .bind(account)
+        .bind(identifiers.session_id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(scopes)
+    }
+
+    /// Scopes `account` holds across a whole beach; tag-limited grants are
+    /// excluded because they only apply to individual sessions.
+    pub async fn group_scopes_for_beach(
+        &self,
+        account: Uuid,
+        private_beach_id: &str,
+    ) -> Result<Vec<String>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let Ok(beach_uuid) = Uuid::parse_str(private_beach_id) else {
+            return Ok(Vec::new());
+        };
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, Some(&account)).await?;
+        self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+        let scopes: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT gg.scope
+            FROM beach_group g
+            JOIN private_beach_membership pbm
+              ON pbm.private_beach_id = g.private_beach_id
+             AND pbm.account_id = $2
+             AND pbm.status = 'active'
+            JOIN group_membership gm ON gm.beach_group_id = g.id AND gm.account_id = $2
+            JOIN beach_group_grant gg ON gg.beach_group_id = g.id
+            WHERE g.private_beach_id = $1 AND gg.session_tag IS NULL
+            "#,
+        )
+        .bind(beach_uuid)
+        .bind(account)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(scopes)
+    }
+}
+
+fn beach_group_from_row(row: BeachGroupRow, grants: Vec<GroupGrant>) -> BeachGroup {
+    BeachGroup {
+        id: row.id.to_string(),
+        private_beach_id: row.private_beach_id.to_string(),
+        name: row.name,
+        description: row.description,
+        grants,
+        member_count: row.member_count,
+        created_by_account_id: row.created_by_account_id.map(|id| id.to_string()),
+        created_at_ms: row.created_at.timestamp_millis(),
+        updated_at_ms: row.updated_at.timestamp_millis(),
+    }
+}
+
+// ---- Private Beaches: file store ----
+
+/// Upper bound for a single uploaded file; also applied as the upload route body limit.
+pub const MAX_FILE_BYTES: usize = 64 * 1024 * 1024;
+const MAX_FILE_PATH_LEN: usize = 1024;
+
+#[derive(Debug, FromRow)]
+struct FileRecordRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    path: String,
+    version: i32,
+    storage_key: String,
+    size_bytes: Option<i64>,
+    content_type: Option<String>,
+    checksum: Option<String>,
+    uploaded_by_account_id: Option<Uuid>,
+    uploaded_by_session_id: Option<Uuid>,
+    uploaded_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Selects `FileRecordRow` columns; the uploading session is reported by its
+/// origin id rather than the internal `session.id`.
+const FILE_RECORD_SELECT: &str = "SELECT f.id, f.private_beach_id, f.path, f.version, f.storage_key, f.size_bytes, f.content_type, f.checksum, f.uploaded_by_account_id, s.origin_session_id AS uploaded_by_session_id, f.uploaded_at, f.deleted_at FROM file_record f LEFT JOIN session s ON s.id = f.uploaded_by_session_id";
+
+impl From<FileRecordRow> for FileRecord {
+    fn from(row: FileRecordRow) -> Self {
+        FileRecord {
+            id: row.id.to_string(),
+            private_beach_id: row.private_beach_id.to_string(),
+            path: row.path,
+            version: row.version,
+            size_bytes: row.size_bytes.unwrap_or(0),
+            content_type: row.content_type,
+            checksum: row.checksum.unwrap_or_default(),
+            uploaded_by_account_id: row.uploaded_by_account_id.map(|id| id.to_string()),
+            uploaded_by_session_id: row.uploaded_by_session_id.map(|id| id.to_string()),
+            uploaded_at_ms: row.uploaded_at.timestamp_millis(),
+            deleted_at_ms: row.deleted_at.map(|t| t.timestamp_millis()),
+        }
+    }
+}
+
+/// Who is storing a file: an account through the API, or a harness session.
+enum FileUploader {
+    Account(Option<Uuid>),
+    Session { id: Uuid, origin_id: String },
+}
+
+/// Normalizes a beach-relative file path to `a/b/c` form, rejecting traversal
+/// segments and control characters.
+fn normalize_file_path(path: &str) -> Result<String, StateError> {
+    let invalid = || StateError::InvalidIdentifier(format!("invalid file path '{path}'"));
+    let segments: Vec<&str> = path
+        .trim()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty()
+        || segments
+            .iter()
+            .any(|segment| matches!(*segment, "." | ".."))
+        || path.chars().any(char::is_control)
+    {
+        return Err(invalid());
+    }
+    let normalized = segments.join("/");
+    if normalized.len() > MAX_FILE_PATH_LEN {
+        return Err(invalid());
+    }
+    Ok(normalized)
+}
+
+fn file_storage_key(private_beach_id: &str, file_id: &str) -> String {
+    format!("{private_beach_id}/{file_id}")
+}
+
+fn blob_state_err(err: BlobStoreError) -> StateError {
+    match err {
+        BlobStoreError::NotFound(_) => StateError::FileNotFound,
+        other => StateError::External(other.to_string()),
+    }
+}
+
+impl AppState {
+    /// Stores a new version of `path` uploaded by an account.
+    pub async fn upload_file(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        path: &str,
+        content_type: Option<String>,
+        data: Bytes,
+    ) -> Result<FileRecord, StateError> {
+        self.store_file(
+            private_beach_id,
+            FileUploader::Account(account),
+            path,
+            content_type,
+            data,
+        )
+        .await
+    }
+
+    /// Stores a new version of `path` in the beach the session belongs to.
+    pub async fn upload_session_artifact(
+        &self,
+        session_id: &str,
+        path: &str,
+        content_type: Option<String>,
+        data: Bytes,
+    ) -> Result<FileRecord, StateError> {
+        match &self.backend {
+            Backend::Memory => {
+                let private_beach_id = {
+                    let sessions = self.fallback.sessions.read().await;
+                    sessions
+                        .get(session_id)
+                        .map(|record| record.private_beach_id.clone())
+                        .ok_or(StateError::SessionNotFound)?
+                };
+                let uploader = FileUploader::Session {
+                    id: Uuid::nil(),
+                    origin_id: session_id.to_string(),
+                };
+                self.store_file(&private_beach_id, uploader, path, content_type, data)
+                    .await
+            }
+            Backend::Postgres(pool) => {
+                let session_uuid = parse_uuid(session_id, "session_id")?;
+                let identifiers = self.fetch_session_identifiers(pool, &session_uuid).await?;
+                let uploader = FileUploader::Session {
+                    id: identifiers.session_id,
+                    origin_id: session_id.to_string(),
+                };
+                self.store_file(
+                    &identifiers.private_beach_id.to_string(),
+                    uploader,
+                    path,
+                    content_type,
+                    data,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn store_file(
+        &self,
+        private_beach_id: &str,
+        uploader: FileUploader,
+        path: &str,
+        content_type: Option<String>,
+        data: Bytes,
+    ) -> Result<FileRecord, StateError> {
+        use sha2::{Digest, Sha256};
+
+        let path = normalize_file_path(path)?;
+        if data.len() > MAX_FILE_BYTES {
+            return Err(StateError::InvalidIdentifier(format!(
+                "file exceeds {MAX_FILE_BYTES} bytes"
+            )));
+        }
+        let content_type = content_type
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        let checksum = hex::encode(Sha256::digest(&data));
+        let size_bytes = data.len() as i64;
+        let file_id = Uuid::new_v4();
+        let storage_key = file_storage_key(private_beach_id, &file_id.to_string());
+        let (account, session) = match uploader {
+            FileUploader::Account(account) => (account, None),
+            FileUploader::Session { id, origin_id } => (None, Some((id, origin_id))),
+        };
+
+        match &self.backend {
+            Backend::Memory => {
+                let mut files = self.fallback.files.write().await;
+                let entries = files.entry(private_beach_id.to_string()).or_default();
+                let version = entries
+                    .iter()
+                    .filter(|record| record.path == path)
+                    .map(|record| record.version)
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                self.blob_store
+                    .put(&storage_key, data)
+                    .await
+                    .map_err(blob_state_err)?;
+                let record = FileRecord {
+                    id: file_id.to_string(),
+                    private_beach_id: private_beach_id.to_string(),
+                    path,
+                    version,
+                    size_bytes,
+                    content_type,
+                    checksum,
+                    uploaded_by_account_id: account.map(|id| id.to_string()),
+                    uploaded_by_session_id: session.map(|(_, origin)| origin),
+                    uploaded_at_ms: now_ms(),
+                    deleted_at_ms: None,
+                };
+                entries.push(record.clone());
+                Ok(record)
+            }
+            Backend::Postgres(pool) => {
+                let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+                self.blob_store
+                    .put(&storage_key, data)
+                    .await
+                    .map_err(blob_state_err)?;
+                let inserted = async {
+                    let mut tx = pool.begin().await?;
+                    self.set_account_context_tx(&mut tx, account.as_ref())
+                        .await?;
+                    self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+                    self.ensure_beach_visible_tx(&mut tx, &beach_uuid).await?;
+                    // Serialize concurrent uploads of the same path so versions stay dense.
+                    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+                        .bind(format!("file_record:{beach_uuid}:{path}"))
+                        .execute(tx.as_mut())
+                        .await?;
+                    let (version, uploaded_at): (i32, DateTime<Utc>) = sqlx::query_as(
+                        r#"
+                        INSERT INTO file_record (
+                            id, private_beach_id, path, version, storage_key, size_bytes,
+                            content_type, checksum, uploaded_by_account_id, uploaded_by_session_id
+                        )
+                        SELECT $1, $2, $3, COALESCE(MAX(version), 0) + 1, $4, $5, $6, $7, $8, $9
+                        FROM file_record
+                        WHERE private_beach_id = $2 AND path = $3
+                        RETURNING version, uploaded_at
+                        "#,
+                    )
+                    .bind(file_id)
+                    .bind(beach_uuid)
+                    .bind(&path)
+                    .bind(&storage_key)
+                    .bind(size_bytes)
+                    .bind(content_type.as_deref())
+                    .bind(&checksum)
+                    .bind(account)
+                    .bind(session.as_ref().map(|(id, _)| *id))
+                    .fetch_one(tx.as_mut())
+                    .await?;
+                    tx.commit().await?;
+                    Ok::<_, StateError>((version, uploaded_at))
+                }
+                .await;
+                let (version, uploaded_at) = match inserted {
+                    Ok(values) => values,
+                    Err(err) => {
+                        if let Err(cleanup) = self.blob_store.delete(&storage_key).await {
+                            warn!(
+                                storage_key = %storage_key,
+                                error = %cleanup,
+                                "failed to remove orphaned file blob"
+                            );
+                        }
+                        return Err(err);
+                    }
+                };
+                Ok(FileRecord {
+                    id: file_id.to_string(),
+                    private_beach_id: beach_uuid.to_string(),
+                    path,
+                    version,
+                    size_bytes,
+                    content_type,
+                    checksum,
+                    uploaded_by_account_id: account.map(|id| id.to_string()),
+                    uploaded_by_session_id: session.map(|(_, origin)| origin),
+                    uploaded_at_ms: uploaded_at.timestamp_millis(),
+                    deleted_at_ms: None,
+                })
+            }
+        }
+    }
+
+    /// Latest version of every file in the beach, optionally limited to a path
+    /// prefix. Soft-deleted files are omitted unless `include_deleted` is set.
+    pub async fn list_files(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        prefix: Option<&str>,
+        include_deleted: bool,
+    ) -> Result<Vec<FileRecord>, StateError> {
+        let prefix = prefix
+            .map(|value| value.trim().trim_start_matches('/'))
+            .filter(|value| !value.is_empty());
+        let mut latest: Vec<FileRecord> = match &self.backend {
+            Backend::Memory => {
+                let files = self.fallback.files.read().await;
+                let mut by_path: HashMap<&str, &FileRecord> = HashMap::new();
+                for record in files.get(private_beach_id).into_iter().flatten() {
+                    let entry = by_path.entry(record.path.as_str()).or_insert(record);
+                    if record.version > entry.version {
+                        *entry = record;
+                    }
+                }
+                let mut records: Vec<FileRecord> = by_path.into_values().cloned().collect();
+                records.sort_by(|a, b| a.path.cmp(&b.path));
+                records
+            }
+            Backend::Postgres(pool) => {
+                let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+                self.ensure_beach_visible_tx(&mut tx, &beach_uuid).await?;
+                let rows: Vec<FileRecordRow> = sqlx::query_as(&format!(
+                    "{FILE_RECORD_SELECT} WHERE f.private_beach_id = $1 AND ($2::text IS NULL OR f.path LIKE $2 ESCAPE '\\') ORDER BY f.path, f.version DESC"
+                ))
+                .bind(beach_uuid)
+                .bind(prefix.map(|p| format!("{}%", escape_like(p))))
+                .fetch_all(tx.as_mut())
+                .await?;
+                tx.commit().await?;
+                let mut records: Vec<FileRecord> = Vec::new();
+                for row in rows {
+                    if records.last().map(|last| last.path.as_str()) != Some(row.path.as_str()) {
+                        records.push(row.into());
+                    }
+                }
+                records
+            }
+        };
+        latest.retain(|record| {
+            (include_deleted || record.deleted_at_ms.is_none())
+                && prefix.is_none_or(|p| record.path.starts_with(p))
+        });
+        Ok(latest)
+    }
+
+    /// Every stored version of `path`, newest first, including soft-deleted ones.
+    pub async fn file_versions(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        path: &str,
+    ) -> Result<Vec<FileRecord>, StateError> {
+        let path = normalize_file_path(path)?;
+        let versions: Vec<FileRecord> = match &self.backend {
+            Backend::Memory => {
+                let files = self.fallback.files.read().await;
+                let mut versions: Vec<FileRecord> = files
+                    .get(private_beach_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|record| record.path == path)
+                    .cloned()
+                    .collect();
+                versions.sort_by(|a, b| b.version.cmp(&a.version));
+                versions
+            }
+            Backend::Postgres(pool) => {
+                let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+                self.ensure_beach_visible_tx(&mut tx, &beach_uuid).await?;
+                let rows: Vec<FileRecordRow> = sqlx::query_as(&format!(
+                    "{FILE_RECORD_SELECT} WHERE f.private_beach_id = $1 AND f.path = $2 ORDER BY f.version DESC"
+                ))
+                .bind(beach_uuid)
+                .bind(&path)
+                .fetch_all(tx.as_mut())
+                .await?;
+                tx.commit().await?;
+                rows.into_iter().map(FileRecord::from).collect()
+            }
+        };
+        if versions.is_empty() {
+            return Err(StateError::FileNotFound);
+        }
+        Ok(versions)
+    }
+
+    /// Fetches the content of `path`: the latest live version, or a specific
+    /// `version` as long as it has not been deleted.
+    pub async fn download_file(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        path: &str,
+        version: Option<i32>,
+    ) -> Result<(FileRecord, Bytes), StateError> {
+        let path = normalize_file_path(path)?;
+        let (record, storage_key) = match &self.backend {
+            Backend::Memory => {
+                let files = self.fallback.files.read().await;
+                let record = files
+                    .get(private_beach_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|record| record.path == path && record.deleted_at_ms.is_none())
+                    .filter(|record| version.is_none_or(|v| record.version == v))
+                    .max_by_key(|record| record.version)
+                    .cloned()
+                    .ok_or(StateError::FileNotFound)?;
+                let key = file_storage_key(private_beach_id, &record.id);
+                (record, key)
+            }
+            Backend::Postgres(pool) => {
+                let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+                self.ensure_beach_visible_tx(&mut tx, &beach_uuid).await?;
+                let row: FileRecordRow = sqlx::query_as(&format!(
+                    "{FILE_RECORD_SELECT} WHERE f.private_beach_id = $1 AND f.path = $2 AND f.deleted_at IS NULL AND ($3::int IS NULL OR f.version = $3) ORDER BY f.version DESC LIMIT 1"
+                ))
+                .bind(beach_uuid)
+                .bind(&path)
+                .bind(version)
+                .fetch_optional(tx.as_mut())
+                .await?
+                .ok_or(StateError::FileNotFound)?;
+                tx.commit().await?;
+                let key = row.storage_key.clone();
+                (FileRecord::from(row), key)
+            }
+        };
+        let data = self
+            .blob_store
+            .get(&storage_key)
+            .await
+            .map_err(blob_state_err)?;
+        Ok((record, data))
+    }
+
+    /// Soft-deletes every live version of `path` and returns how many were
+    /// marked. Blobs are retained so deleted versions remain auditable.
+    pub async fn delete_file(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        path: &str,
+    ) -> Result<usize, StateError> {
+        let path = normalize_file_path(path)?;
+        let deleted = match &self.backend {
+            Backend::Memory => {
+                let mut files = self.fallback.files.write().await;
+                let now = now_ms();
+                let mut deleted = 0;
+                for record in files.get_mut(private_beach_id).into_iter().flatten() {
+                    if record.path == path && record.deleted_at_ms.is_none() {
+                        record.deleted_at_ms = Some(now);
+                        deleted += 1;
+                    }
+                }
+                deleted
+            }
+            Backend::Postgres(pool) => {
+                let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+                self.ensure_beach_visible_tx(&mut tx, &beach_uuid).await?;
+                let result = sqlx::query(
+                    "UPDATE file_record SET deleted_at = NOW() WHERE private_beach_id = $1 AND path = $2 AND deleted_at IS NULL",
+                )
+                .bind(beach_uuid)
+                .bind(&path)
+                .execute(tx.as_mut())
+                .await?;
+                tx.commit().await?;
+                result.rows_affected() as usize
+            }
+        };
+        if deleted == 0 {
+            return Err(StateError::FileNotFound);
+        }
+        Ok(deleted)
+    }
+}
+
+// ---- Private Beaches: automation assignments ----
+
+/// How often the background loop re-applies automation assignments.
+#[allow(dead_code)]
+pub(crate) const AUTOMATION_RECONCILE_INTERVAL: StdDuration = StdDuration::from_secs(10);
+/// Assignment leases outlive a couple of missed reconcile passes.
+const AUTOMATION_MIN_LEASE_TTL_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "automation_role", rename_all = "snake_case")]
+pub enum AutomationRole {
+    Observer,
+    Controller,
+    Coordinator,
+}
+
+impl AutomationRole {
+    /// Observers only receive pairing updates; the other roles also hold a controller lease.
+    pub fn holds_lease(self) -> bool {
+        !matches!(self, AutomationRole::Observer)
+    }
+}
+
+/// Stored in `automation_assignment.config`. Unknown keys are kept for the agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentConfig {
+    /// Agent session that is paired with every assigned session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controller_session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_cadence: Option<ControllerUpdateCadence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lease_ttl_ms: Option<u64>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AutomationAssignment {
+    pub id: String,
+    pub private_beach_id: String,
+    pub controller_account_id: String,
+    pub role: AutomationRole,
+    /// `None` assigns every session in the beach.
+    pub session_id: Option<String>,
+    #[schema(value_type = Object)]
+    pub config: AssignmentConfig,
+    pub created_by_account_id: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+    /// Reconciliation outcome per targeted session.
+    pub status: Vec<AssignmentStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentState {
+    Active,
+    Pending,
+    Failed,
+    /// The controller already had a pairing with this session that the
+    /// assignment did not create; it is left as configured.
+    Conflict,
+    Released,
+}
+
+/// Published on the targeted session's SSE stream as `automation_assignment`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AssignmentStatus {
+    pub assignment_id: String,
+    pub session_id: String,
+    pub controller_account_id: String,
+    pub role: AutomationRole,
+    pub state: AssignmentState,
+    pub lease_expires_at_ms: Option<i64>,
+    pub paired: bool,
+    /// `true` when the assignment created the pairing and removes it on release.
+    pub owns_pairing: bool,
+    pub message: Option<String>,
+    pub updated_at_ms: i64,
+    #[serde(skip)]
+    lease_token: Option<String>,
+    #[serde(skip)]
+    controller_session_id: Option<String>,
+}
+
+impl AssignmentStatus {
+    fn new(assignment: &AutomationAssignment, session_id: &str) -> Self {
+        Self {
+            assignment_id: assignment.id.clone(),
+            session_id: session_id.to_string(),
+            controller_account_id: assignment.controller_account_id.clone(),
+            role: assignment.role,
+            state: AssignmentState::Active,
+            lease_expires_at_ms: None,
+            paired: false,
+            owns_pairing: false,
+            message: None,
+            updated_at_ms: now_ms(),
+            lease_token: None,
+            controller_session_id: None,
+        }
+    }
+
+    fn with_state(mut self, state: AssignmentState, message: impl Into<String>) -> Self {
+        self.state = state;
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Lease renewals alone are not worth an SSE event.
+    fn same_outcome(&self, other: &AssignmentStatus) -> bool {
+        self.state == other.state
+            && self.paired == other.paired
+            && self.owns_pairing == other.owns_pairing
+            && self.message == other.message
+            && self.lease_token == other.lease_token
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct AutomationAssignmentRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    controller_account_id: Uuid,
+    role: AutomationRole,
+    session_id: Option<Uuid>,
+    config: Option<Json<serde_json::Value>>,
+    created_by_account_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Selects `AutomationAssignmentRow` columns with the session reported by its origin id.
+const AUTOMATION_ASSIGNMENT_SELECT: &str = "SELECT a.id, a.private_beach_id, a.controller_account_id, a.role, s.origin_session_id AS session_id, a.config, a.created_by_account_id, a.created_at, a.updated_at FROM automation_assignment a LEFT JOIN session s ON s.id = a.session_id";
+
+impl From<AutomationAssignmentRow> for AutomationAssignment {
+    fn from(row: AutomationAssignmentRow) -> Self {
+        AutomationAssignment {
+            id: row.id.to_string(),
+            private_beach_id: row.private_beach_id.to_string(),
+            controller_account_id: row.controller_account_id.to_string(),
+            role: row.role,
+            session_id: row.session_id.map(|id| id.to_string()),
+            config: row
+                .config
+                .and_then(|Json(value)| serde_json::from_value(value).ok())
+                .unwrap_or_default(),
+            created_by_account_id: row.created_by_account_id.map(|id| id.to_string()),
+            created_at_ms: row.created_at.timestamp_millis(),
+            updated_at_ms: row.updated_at.timestamp_millis(),
+            status: Vec::new(),
+        }
+    }
+}
+
+fn assignment_lease_reason(assignment_id: &str) -> String {
+    format!("automation:{assignment_id}")
+}
+
+impl AppState {
+    pub async fn list_automation_assignments(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<AutomationAssignment>, StateError> {
+        let assignments = match &self.backend {
+            Backend::Memory => self.memory_automation_assignments(private_beach_id).await,
+            Backend::Postgres(pool) => {
+                let id = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &id).await?;
+                self.ensure_beach_visible_tx(&mut tx, &id).await?;
+                let assignments = self.fetch_automation_assignments_tx(&mut tx, &id).await?;
+                tx.commit().await?;
+                assignments
+            }
+        };
+        Ok(self
+            .attach_assignment_status(private_beach_id, assignments)
+            .await)
+    }
+
+    pub async fn create_automation_assignment(
+        &self,
+        private_beach_id: &str,
+        controller_account_id: &str,
+        role: AutomationRole,
+        session_id: Option<&str>,
+        config: AssignmentConfig,
+        account: Option<Uuid>,
+    ) -> Result<AutomationAssignment, StateError> {
+        let controller_account = parse_uuid(controller_account_id, "controller_account_id")?;
+        let assignment = match &self.backend {
+            Backend::Memory => {
+                self.ensure_memory_assignment_sessions(private_beach_id, session_id, &config)
+                    .await?;
+                let mut assignments = self.fallback.automation_assignments.write().await;
+                let controller_account_id = controller_account.to_string();
+                let duplicate = assignments.values().any(|existing| {
+                    existing.private_beach_id == private_beach_id
+                        && existing.controller_account_id == controller_account_id
+                        && existing.session_id.as_deref() == session_id
+                });
+                if duplicate {
+                    return Err(StateError::AutomationAssignmentConflict);
+                }
+                let now = now_ms();
+                let assignment = AutomationAssignment {
+                    id: Uuid::new_v4().to_string(),
+                    private_beach_id: private_beach_id.to_string(),
+                    controller_account_id,
+                    role,
+                    session_id: session_id.map(str::to_string),
+                    config,
+                    created_by_account_id: account.map(|id| id.to_string()),
+                    created_at_ms: now,
+                    updated_at_ms: now,
+                    status: Vec::new(),
+                };
+                assignments.insert(assignment.id.clone(), assignment.clone());
+                assignment
+            }
+            Backend::Postgres(pool) => {
+                let id = parse_uuid(private_beach_id, "private_beach_id")?;
+                let session = match session_id {
+                    Some(session_id) => Some(
+                        self.resolve_assignment_session(pool, session_id, &id)
+                            .await?,
+                    ),
+                    None => None,
+                };
+                if let Some(controller_session_id) = config.controller_session_id.as_deref() {
+                    self.resolve_assignment_session(pool, controller_session_id, &id)
+                        .await?;
+                }
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &id).await?;
+                self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+                // The unique index only covers session-scoped rows; beach-wide
+                // duplicates are rejected here.
+                let existing: Option<(Uuid,)> = sqlx::query_as(
+                    r#"
+                    SELECT id
+                    FROM automation_assignment
+                    WHERE private_beach_id = $1
+                      AND controller_account_id = $2
+                      AND session_id IS NOT DISTINCT FROM $3
+                    "#,
+                )
+                .bind(id)
+                .bind(controller_account)
+                .bind(session)
+                .fetch_optional(tx.as_mut())
+                .await?;
+                if existing.is_some() {
+                    return Err(StateError::AutomationAssignmentConflict);
+                }
+                let inserted: Result<Uuid, sqlx::Error> = sqlx::query_scalar(
+                    r#"
+                    INSERT INTO automation_assignment (
+                        private_beach_id,
+                        controller_account_id,
+                        role,
+                        session_id,
+                        config,
+                        created_by_account_id
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id
+                    "#,
+                )
+                .bind(id)
+                .bind(controller_account)
+                .bind(role)
+                .bind(session)
+                .bind(Json(serde_json::to_value(&config)?))
+                .bind(account)
+                .fetch_one(tx.as_mut())
+                .await;
+                let assignment_id = match inserted {
+                    Ok(assignment_id) => assignment_id,
+                    Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                        return Err(StateError::AutomationAssignmentConflict);
+                    }
+                    Err(sqlx::Error::Database(db)) if db.is_foreign_key_violation() => {
+                        return Err(StateError::AccountMissing(controller_account));
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                let assignment = self
+                    .fetch_automation_assignment_tx(&mut tx, &id, &assignment_id)
+                    .await?;
+                tx.commit().await?;
+                assignment
+            }
+        };
+        info!(
+            target = "private_beach",
+            private_beach_id = %private_beach_id,
+            assignment_id = %assignment.id,
+            controller_account_id = %assignment.controller_account_id,
+            role = ?assignment.role,
+            session_id = assignment.session_id.as_deref().unwrap_or("*"),
+            "automation assignment created"
+        );
+        self.reconcile_after_assignment_change(private_beach_id)
+            .await;
+        Ok(self
+            .attach_assignment_status(private_beach_id, vec![assignment])
+            .await
+            .remove(0))
+    }
+
+    pub async fn update_automation_assignment(
+        &self,
+        private_beach_id: &str,
+        assignment_id: &str,
+        role: Option<AutomationRole>,
+        config: Option<AssignmentConfig>,
+        account: Option<Uuid>,
+    ) -> Result<AutomationAssignment, StateError> {
+        let assignment = match &self.backend {
+            Backend::Memory => {
+                let session_id = {
+                    let assignments = self.fallback.automation_assignments.read().await;
+                    assignments
+                        .get(assignment_id)
+                        .filter(|existing| existing.private_beach_id == private_beach_id)
+                        .ok_or(StateError::AutomationAssignmentNotFound)?
+                        .session_id
+                        .clone()
+                };
+                if let Some(config) = config.as_ref() {
+                    self.ensure_memory_assignment_sessions(
+                        private_beach_id,
+                        session_id.as_deref(),
+                        config,
+                    )
+                    .await?;
+                }
+                let mut assignments = self.fallback.automation_assignments.write().await;
+                let existing = assignments
+                    .get_mut(assignment_id)
+                    .ok_or(StateError::AutomationAssignmentNotFound)?;
+                if let Some(role) = role {
+                    existing.role = role;
+                }
+                if let Some(config) = config {
+                    existing.config = config;
+                }
+                existing.updated_at_ms = now_ms();
+                existing.clone()
+            }
+            Backend::Postgres(pool) => {
+                let id = parse_uuid(private_beach_id, "private_beach_id")?;
+                let assignment_uuid = parse_uuid(assignment_id, "assignment_id")?;
+                if let Some(controller_session_id) = config
+                    .as_ref()
+                    .and_then(|config| config.controller_session_id.as_deref())
+                {
+                    self.resolve_assignment_session(pool, controller_session_id, &id)
+                        .await?;
+                }
+                let config_value = config.as_ref().map(serde_json::to_value).transpose()?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &id).await?;
+                self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE automation_assignment
+                    SET role = COALESCE($3, role),
+                        config = COALESCE($4, config),
+                        updated_at = NOW()
+                    WHERE id = $1 AND private_beach_id = $2
+                    "#,
+                )
+                .bind(assignment_uuid)
+                .bind(id)
+                .bind(role)
+                .bind(config_value.map(Json))
+                .execute(tx.as_mut())
+                .await?;
+                if updated.rows_affected() == 0 {
+                    return Err(StateError::AutomationAssignmentNotFound);
+                }
+                let assignment = self
+                    .fetch_automation_assignment_tx(&mut tx, &id, &assignment_uuid)
+                    .await?;
+                tx.commit().await?;
+                assignment
+            }
+        };
+        // Drop leases and pairings made under the old settings before re-applying.
+        self.release_automation_assignment(private_beach_id, &assignment.id)
+            .await;
+        self.reconcile_after_assignment_change(private_beach_id)
+            .await;
+        Ok(self
+            .attach_assignment_status(private_beach_id, vec![assignment])
+            .await
+            .remove(0))
+    }
+
+    pub async fn delete_automation_assignment(
+        &self,
+        private_beach_id: &str,
+        assignment_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        match &self.backend {
+            Backend::Memory => {
+                let mut assignments = self.fallback.automation_assignments.write().await;
+                let found = assignments
+                    .get(assignment_id)
+                    .is_some_and(|existing| existing.private_beach_id == private_beach_id);
+                if !found {
+                    return Err(StateError::AutomationAssignmentNotFound);
+                }
+                assignments.remove(assignment_id);
+            }
+            Backend::Postgres(pool) => {
+                let id = parse_uuid(private_beach_id, "private_beach_id")?;
+                let assignment_uuid = parse_uuid(assignment_id, "assignment_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_account_context_tx(&mut tx, account.as_ref())
+                    .await?;
+                self.set_rls_context_tx(&mut tx, &id).await?;
+                self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+                let deleted = sqlx::query(
+                    "DELETE FROM automation_assignment WHERE id = $1 AND private_beach_id = $2",
+                )
+                .bind(assignment_uuid)
+                .bind(id)
+                .execute(tx.as_mut())
+                .await?;
+                if deleted.rows_affected() == 0 {
+                    return Err(StateError::AutomationAssignmentNotFound);
+                }
+                tx.commit().await?;
+            }
+        }
+        info!(
+            target = "private_beach",
+            private_beach_id = %private_beach_id,
+            assignment_id = %assignment_id,
+            "automation assignment deleted"
+        );
+        self.release_automation_assignment(private_beach_id, assignment_id)
+            .await;
+        Ok(())
+    }
+
+    /// Applies every beach's assignments: renews leases, creates missing
+    /// pairings, and releases targets that no longer apply.
+    pub async fn reconcile_automation_assignments(&self) {
+        let mut beaches: HashSet<String> = match &self.backend {
+            Backend::Memory => {
+                let assignments = self.fallback.automation_assignments.read().await;
+                assignments
+                    .values()
+                    .map(|assignment| assignment.private_beach_id.clone())
+                    .collect()
+            }
+            Backend::Postgres(pool) => {
+                match sqlx::query_scalar::<_, Uuid>(
+                    "SELECT DISTINCT private_beach_id FROM automation_assignment",
+                )
+                .fetch_all(pool)
+                .await
+                {
+                    Ok(ids) => ids.into_iter().map(|id| id.to_string()).collect(),
+                    Err(err) => {
+                        warn!(
+                            target = "private_beach.automation",
+                            error = %err,
+                            "failed to enumerate automation assignments"
+                        );
+                        return;
+                    }
+                }
+            }
+        };
+        // Beaches whose assignments vanished (e.g. cascaded deletes) still hold
+        // statuses that need releasing.
+        beaches.extend(self.assignment_status.read().await.keys().cloned());
+        for private_beach_id in beaches {
+            if let Err(err) = self.reconcile_beach_assignments(&private_beach_id).await {
+                warn!(
+                    target = "private_beach.automation",
+                    private_beach_id = %private_beach_id,
+                    error = %err,
+                    "automation reconcile failed"
+                );
+            }
+        }
+    }
+
+    async fn reconcile_after_assignment_change(&self, private_beach_id: &str) {
+        if let Err(err) = self.reconcile_beach_assignments(private_beach_id).await {
+            warn!(
+                target = "private_beach.automation",
+                private_beach_id = %private_beach_id,
+                error = %err,
+                "automation reconcile failed; background loop will retry"
+            );
+        }
+    }
+
+    async fn reconcile_beach_assignments(&self, private_beach_id: &str) -> Result<(), StateError> {
+        let assignments = self.load_beach_assignments(private_beach_id).await?;
+        let active = if assignments.is_empty() {
+            Vec::new()
+        } else {
+            let filter = SessionFilter {
+                status: Some(SessionStatus::Active),
+                ..SessionFilter::default()
+            };
+            self.list_sessions_filtered(private_beach_id, &filter)
+                .await?
+        };
+        let live: HashSet<&str> = active
+            .iter()
+            .map(|summary| summary.session_id.as_str())
+            .collect();
+        let previous = self
+            .assignment_status
+            .read()
+            .await
+            .get(private_beach_id)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut next = HashMap::new();
+        for assignment in &assignments {
+            let controller_session = assignment.config.controller_session_id.as_deref();
+            let targets: Vec<&str> = match assignment.session_id.as_deref() {
+                Some(session_id) => vec![session_id],
+                None => active
+                    .iter()
+                    .map(|summary| summary.session_id.as_str())
+                    .filter(|session_id| Some(*session_id) != controller_session)
+                    .collect(),
+            };
+            for target in targets {
+                let key = (assignment.id.clone(), target.to_string());
+                let status = if live.contains(target) {
+                    self.apply_automation_assignment(assignment, target).await
+                } else {
+                    AssignmentStatus::new(assignment, target)
+                        .with_state(AssignmentState::Pending, "session is not active")
+                };
+                if previous
+                    .get(&key)
+                    .is_none_or(|prior| !prior.same_outcome(&status))
+                {
+                    self.publish_assignment_status(&status).await;
+                }
+                next.insert(key, status);
+            }
+        }
+
+        let stale: Vec<AssignmentStatus> = previous
+            .into_iter()
+            .filter(|(key, _)| !next.contains_key(key))
+            .map(|(_, status)| status)
+            .collect();
+        {
+            let mut statuses = self.assignment_status.write().await;
+            if next.is_empty() {
+                statuses.remove(private_beach_id);
+            } else {
+                statuses.insert(private_beach_id.to_string(), next);
+            }
+        }
+        for status in stale {
+            self.release_assignment_target(status).await;
+        }
+        Ok(())
+    }
+
+    async fn apply_automation_assignment(
+        &self,
+        assignment: &AutomationAssignment,
+        session_id: &str,
+    ) -> AssignmentStatus {
+        let mut status = AssignmentStatus::new(assignment, session_id);
+        let account = Uuid::parse_str(&assignment.controller_account_id).ok();
+        if assignment.role.holds_lease() {
+            let ttl = assignment
+                .config
+                .lease_ttl_ms
+                .unwrap_or(DEFAULT_LEASE_TTL_MS)
+                .max(AUTOMATION_MIN_LEASE_TTL_MS);
+            match self
+                .renew_controller_lease(
+                    session_id,
+                    Some(ttl),
+                    Some(assignment_lease_reason(&assignment.id)),
+                    account,
+                )
+                .await
+            {
+                Ok(lease) => {
+                    status.lease_expires_at_ms = Some(lease.expires_at_ms);
+                    status.lease_token = Some(lease.controller_token);
+                }
+                Err(err) => {
+                    return status.with_state(
+                        AssignmentState::Failed,
+                        format!("lease not acquired: {err}"),
+                    );
+                }
+            }
+        }
+        if let Some(controller_session_id) = assignment
+            .config
+            .controller_session_id
+            .as_deref()
+            .filter(|controller| *controller != session_id)
+        {
+            let existing = match self.list_controller_pairings(controller_session_id).await {
+                Ok(existing) => existing,
+                Err(err) => {
+                    return status.with_state(
+                        AssignmentState::Failed,
+                        format!("pairing not applied: {err}"),
+                    );
+                }
+            };
+            let cadence = assignment.config.update_cadence.unwrap_or_default();
+            let current = existing
+                .iter()
+                .find(|pairing| pairing.child_session_id == session_id);
+            let owned = current.is_some_and(|pairing| {
+                pairing.automation_assignment_id.as_deref() == Some(assignment.id.as_str())
+            });
+            // A pairing set up by hand stays as its owner configured it.
+            if current.is_some() && !owned {
+                let matches = current.is_some_and(|pairing| {
+                    pairing.prompt_template == assignment.config.prompt_template
+                        && pairing.update_cadence == cadence
+                });
+                if matches {
+                    status.paired = true;
+                    return status;
+                }
+                return status.with_state(
+                    AssignmentState::Conflict,
+                    "controller pairing exists with different settings",
+                );
+            }
+            let up_to_date = current.is_some_and(|pairing| {
+                pairing.prompt_template == assignment.config.prompt_template
+                    && pairing.update_cadence == cadence
+            });
+            if !up_to_date {
+                if let Err(err) = self
+                    .upsert_controller_pairing_with_owner(
+                        controller_session_id,
+                        session_id,
+                        assignment.config.prompt_template.clone(),
+                        Some(cadence),
+                        account,
+                        Some(&assignment.id),
+                    )
+                    .await
+                {
+                    return status.with_state(
+                        AssignmentState::Failed,
+                        format!("pairing not applied: {err}"),
+                    );
+                }
+            }
+            status.paired = true;
+            status.owns_pairing = true;
+            status.controller_session_id = Some(controller_session_id.to_string());
+        }
+        status
+    }
+
+    /// Releases everything reconciliation acquired for one assignment.
+    async fn release_automation_assignment(&self, private_beach_id: &str, assignment_id: &str) {
+        let released: Vec<AssignmentStatus> = {
+            let mut statuses = self.assignment_status.write().await;
+            let Some(beach) = statuses.get_mut(private_beach_id) else {
+                return;
+            };
+            let keys: Vec<(String, String)> = beach
+                .keys()
+                .filter(|(id, _)| id == assignment_id)
+                .cloned()
+                .collect();
+            let released = keys.iter().filter_map(|key| beach.remove(key)).collect();
+            if beach.is_empty() {
+                statuses.remove(private_beach_id);
+            }
+            released
+        };
+        for status in released {
+            self.release_assignment_target(status).await;
+        }
+    }
+
+    async fn release_assignment_target(&self, mut status: AssignmentStatus) {
+        // Release failures are expected when the session already ended; the
+        // lease then lapses on its own TTL.
+        if let Some(token) = status.lease_token.take() {
+            if let Err(err) = self
+                .release_controller(&status.session_id, &token, None)
+                .await
+            {
+                debug!(
+                    target = "private_beach.automation",
+                    assignment_id = %status.assignment_id,
+                    session_id = %status.session_id,
+                    error = %err,
+                    "assignment lease release skipped"
+                );
+            }
+        }
+        if let Some(controller_session_id) = status
+            .controller_session_id
+            .take()
+            .filter(|_| status.owns_pairing)
+        {
+            if let Err(err) = self
+                .delete_controller_pairing(&controller_session_id, &status.session_id, None)
+                .await
+            {
+                debug!(
+                    target = "private_beach.automation",
+                    assignment_id = %status.assignment_id,
+                    session_id = %status.session_id,
+                    error = %err,
+                    "assignment pairing removal skipped"
+                );
+            }
+        }
+        status.state = AssignmentState::Released;
+        status.lease_expires_at_ms = None;
+        status.paired = false;
+        status.owns_pairing = false;
+        status.message = None;
+        status.updated_at_ms = now_ms();
+        self.publish_assignment_status(&status).await;
+    }
+
+    async fn publish_assignment_status(&self, status: &AssignmentStatus) {
+        self.publish(
+            &status.session_id,
+            StreamEvent::AutomationAssignment(status.clone()),
+        )
+        .await;
+    }
+
+    async fn attach_assignment_status(
+        &self,
+        private_beach_id: &str,
+        mut assignments: Vec<AutomationAssignment>,
+    ) -> Vec<AutomationAssignment> {
+        let statuses = self.assignment_status.read().await;
+        if let Some(beach) = statuses.get(private_beach_id) {
+            for assignment in &mut assignments {
+                assignment.status = beach
+                    .values()
+                    .filter(|status| status.assignment_id == assignment.id)
+                    .cloned()
+                    .collect();
+                assignment
+                    .status
+                    .sort_by(|a, b| a.session_id.cmp(&b.session_id));
+            }
+        }
+        assignments
+    }
+
+    async fn load_beach_assignments(
+        &self,
+        private_beach_id: &str,
+    ) -> Result<Vec<AutomationAssignment>, StateError> {
+        match &self.backend {
+            Backend::Memory => Ok(self.memory_automation_assignments(private_beach_id).await),
+            Backend::Postgres(pool) => {
+                let id = parse_uuid(private_beach_id, "private_beach_id")?;
+                let mut tx = pool.begin().await?;
+                self.set_rls_context_tx(&mut tx, &id).await?;
+                let assignments = self.fetch_automation_assignments_tx(&mut tx, &id).await?;
+                tx.commit().await?;
+                Ok(assignments)
+            }
+        }
+    }
+
+    async fn memory_automation_assignments(
+        &self,
+        private_beach_id: &str,
+    ) -> Vec<AutomationAssignment> {
+        let assignments = self.fallback.automation_assignments.read().await;
+        let mut out: Vec<AutomationAssignment> = assignments
+            .values()
+            .filter(|assignment| assignment.private_beach_id == private_beach_id)
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| {
+            a.created_at_ms
+                .cmp(&b.created_at_ms)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        out
+    }
+
+    async fn ensure_memory_assignment_sessions(
+        &self,
+        private_beach_id: &str,
+        session_id: Option<&str>,
+        config: &AssignmentConfig,
+    ) -> Result<(), StateError> {
+        let sessions = self.fallback.sessions.read().await;
+        for id in session_id
+            .into_iter()
+            .chain(config.controller_session_id.as_deref())
+        {
+            let in_beach = sessions
+                .get(id)
+                .is_some_and(|record| record.private_beach_id == private_beach_id);
+            if !in_beach {
+                return Err(StateError::SessionNotFound);
+            }
+        }
+        Ok(())
+    }
+
+    /// Maps an origin session id to its internal id, requiring it to belong to the beach.
+    async fn resolve_assignment_session(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        private_beach_id: &Uuid,
+    ) -> Result<Uuid, StateError> {
+        let session_uuid = parse_uuid(session_id, "session_id")?;
+        self.fetch_session_identifiers_for_private_beach(pool, &session_uuid, private_beach_id)
+            .await?
+            .map(|identifiers| identifiers.session_id)
+            .ok_or(StateError::SessionNotFound)
+    }
+
+    async fn fetch_automation_assignments_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+    ) -> Result<Vec<AutomationAssignment>, StateError> {
+        let rows: Vec<AutomationAssignmentRow> = sqlx::query_as(&format!(
+            "{AUTOMATION_ASSIGNMENT_SELECT} WHERE a.private_beach_id = $1 ORDER BY a.created_at, a.id"
+        ))
+        .bind(private_beach_id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(rows.into_iter().map(AutomationAssignment::from).collect())
+    }
+
+    async fn fetch_automation_assignment_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        private_beach_id: &Uuid,
+        assignment_id: &Uuid,
+    ) -> Result<AutomationAssignment, StateError> {
+        let row: AutomationAssignmentRow = sqlx::query_as(&format!(
+            "{AUTOMATION_ASSIGNMENT_SELECT} WHERE a.id = $1 AND a.private_beach_id = $2"
+        ))
+        .bind(assignment_id)
+        .bind(private_beach_id)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(StateError::AutomationAssignmentNotFound)?;
+        Ok(row.into())
+    }
+}
+
+// ---- Private Beaches: webhooks ----
+
+/// How often the background loop claims and delivers due webhooks.
+#[allow(dead_code)]
+pub(crate) const WEBHOOK_DELIVERY_INTERVAL: StdDuration = StdDuration::from_secs(2);
+const WEBHOOK_DELIVERY_BATCH: i64 = 50;
+const WEBHOOK_DELIVERY_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+/// Claimed deliveries become due again after this long if the worker dies mid-attempt.
+const WEBHOOK_CLAIM_SECS: i64 = 60;
+const WEBHOOK_DEAD_LETTER_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub private_beach_id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub description: Option<String>,
+    /// Signing secret; only returned when the webhook is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_by_account_id: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+/// Partial update for a webhook; unset fields are left unchanged.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct WebhookUpdate {
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Replaces every event filter when present.
+    #[serde(default)]
+    pub event_types: Option<Vec<String>>,
+    #[serde(default)]
+    pub active: Option<bool>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookDeadLetter {
+    pub id: String,
+    pub subscription_id: String,
+    pub event_type: String,
+    pub session_id: Option<String>,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at_ms: i64,
+    pub dead_at_ms: i64,
+    pub replayed_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct WebhookSubscriptionRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    url: String,
+    event_types: Vec<String>,
+    active: bool,
+    description: Option<String>,
+    created_by_account_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<WebhookSubscriptionRow> for WebhookSubscription {
+    fn from(row: WebhookSubscriptionRow) -> Self {
+        Self {
+            id: row.id.to_string(),
+            private_beach_id: row.private_beach_id.to_string(),
+            url: row.url,
+            event_types: row.event_types,
+            active: row.active,
+            description: row.description,
+            secret: None,
+            created_by_account_id: row.created_by_account_id.map(|id| id.to_string()),
+            created_at_ms: row.created_at.timestamp_millis(),
+            updated_at_ms: row.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct WebhookDeadLetterRow {
+    id: Uuid,
+    subscription_id: Uuid,
+    event_type: String,
+    session_id: Option<String>,
+    payload: Json<serde_json::Value>,
+    attempts: i32,
+    last_status: Option<i32>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    dead_at: DateTime<Utc>,
+    replayed_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDeadLetterRow> for WebhookDeadLetter {
+    fn from(row: WebhookDeadLetterRow) -> Self {
+        Self {
+            id: row.id.to_string(),
+            subscription_id: row.subscription_id.to_string(),
+            event_type: row.event_type,
+            session_id: row.session_id,
+            payload: row.payload.0,
+            attempts: row.attempts,
+            last_status: row.last_status,
+            last_error: row.last_error,
+            created_at_ms: row.created_at.timestamp_millis(),
+            dead_at_ms: row.dead_at.timestamp_millis(),
+            replayed_at_ms: row.replayed_at.map(|at| at.timestamp_millis()),
+        }
+    }
+}
+
+/// A queued delivery claimed by the worker, joined with its subscription.
+#[derive(Debug, Clone, FromRow)]
+struct WebhookDeliveryRow {
+    id: Uuid,
+    private_beach_id: Uuid,
+    event_type: String,
+    session_id: Option<String>,
+    payload: Json<serde_json::Value>,
+    attempts: i32,
+    created_at: DateTime<Utc>,
+    url: String,
+    secret: String,
+}
+
+const WEBHOOK_SUBSCRIPTION_COLUMNS: &str = "id, private_beach_id, url, event_types, active, description, created_by_account_id, created_at, updated_at";
+const WEBHOOK_DEAD_LETTER_COLUMNS: &str = "id, subscription_id, event_type, session_id, payload, attempts, last_status, last_error, created_at, dead_at, replayed_at";
+
+fn normalize_webhook_url(url: &str) -> Result<String, StateError> {
+    let url = url.trim();
+    match Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => {
+            Ok(url.to_string())
+        }
+        _ => Err(StateError::InvalidIdentifier(format!(
+            "invalid webhook url '{url}'"
+        ))),
+    }
+}
+
+fn normalize_webhook_events(events: Vec<String>) -> Result<Vec<String>, StateError> {
+    let mut out: Vec<String> = Vec::with_capacity(events.len());
+    for event in events {
+        let event = event.trim().to_string();
+        webhooks::validate_event_filter(&event).map_err(StateError::InvalidIdentifier)?;
+        if !out.contains(&event) {
+            out.push(event);
+        }
+    }
+    if out.is_empty() {
+        return Err(StateError::InvalidIdentifier(
+            "webhook requires at least one event type".into(),
+        ));
+    }
+    Ok(out)
+}
+
+/// Webhook event name for a stream event, e.g. `controller.lease_released`.
+fn webhook_event_type(event: &StreamEvent) -> String {
+    match event {
+        StreamEvent::ControllerEvent(ev) => {
+            let name = match ev.event_type {
+                ControllerEventType::Registered => "registered",
+                ControllerEventType::LeaseAcquired => "lease_acquired",
+                ControllerEventType::LeaseReleased => "lease_released",
+                ControllerEventType::ActionsQueued => "actions_queued",
+                ControllerEventType::ActionsAcked => "actions_acked",
+                ControllerEventType::HealthReported => "health_reported",
+                ControllerEventType::StateUpdated => "state_updated",
+                ControllerEventType::PairingAdded => "pairing_added",
+                ControllerEventType::PairingRemoved => "pairing_removed",
+                ControllerEventType::EmergencyStop => "emergency_stop",
+            };
+            format!("controller.{name}")
+        }
+        StreamEvent::State(_) => "session.state".into(),
+        StreamEvent::Health(_) => "session.health".into(),
+        StreamEvent::ControllerPairing(event) => match event.action {
+            ControllerPairingAction::Added => "pairing.added".into(),
+            ControllerPairingAction::Updated => "pairing.updated".into(),
+            ControllerPairingAction::Removed => "pairing.removed".into(),
+        },
+        StreamEvent::Devtools(_) => "session.devtools".into(),
+        StreamEvent::AutomationAssignment(_) => "automation.assignment".into(),
+    }
+}
+
+impl AppState {
+    pub async fn list_webhooks(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<WebhookSubscription>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let rows: Vec<WebhookSubscriptionRow> = sqlx::query_as(&format!(
+            "SELECT {WEBHOOK_SUBSCRIPTION_COLUMNS} FROM webhook_subscription WHERE private_beach_id = $1 ORDER BY created_at, id"
+        ))
+        .bind(id)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(rows.into_iter().map(WebhookSubscription::from).collect())
+    }
+
+    pub async fn create_webhook(
+        &self,
+        private_beach_id: &str,
+        url: &str,
+        event_types: Vec<String>,
+        secret: Option<&str>,
+        description: Option<&str>,
+        account: Option<Uuid>,
+    ) -> Result<WebhookSubscription, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => {
+                return Err(StateError::Database(sqlx::Error::Protocol(
+                    "requires postgres backend".into(),
+                )));
+            }
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let url = normalize_webhook_url(url)?;
+        self.check_webhook_destination(&url).await?;
+        let event_types = normalize_webhook_events(event_types)?;
+        let secret = match secret.map(str::trim) {
+            Some("") => {
+                return Err(StateError::InvalidIdentifier(
+                    "webhook secret cannot be empty".into(),
+                ));
+            }
+            Some(secret) => secret.to_string(),
+            None => webhooks::generate_webhook_secret(),
+        };
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let row: WebhookSubscriptionRow = sqlx::query_as(&format!(
+            r#"
+            INSERT INTO webhook_subscription (private_beach_id, url, event_types, secret, description, created_by_account_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {WEBHOOK_SUBSCRIPTION_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .bind(&url)
+        .bind(&event_types)
+        .bind(&secret)
+        .bind(description)
+        .bind(account)
+        .fetch_one(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        self.refresh_webhook_index(pool).await;
+        info!(
+            target = "private_beach.webhooks",
+            private_beach_id = %id,
+            webhook_id = %row.id,
+            events = event_types.len(),
+            "webhook created"
+        );
+        let mut webhook = WebhookSubscription::from(row);
+        webhook.secret = Some(secret);
+        Ok(webhook)
+    }
+
+    pub async fn update_webhook(
+        &self,
+        private_beach_id: &str,
+        webhook_id: &str,
+        update: WebhookUpdate,
+        account: Option<Uuid>,
+    ) -> Result<WebhookSubscription, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::WebhookNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let webhook_id = parse_uuid(webhook_id, "webhook_id")?;
+        let url = update
+            .url
+            .as_deref()
+            .map(normalize_webhook_url)
+            .transpose()?;
+        if let Some(url) = url.as_deref() {
+            self.check_webhook_destination(url).await?;
+        }
+        let event_types = update
+            .event_types
+            .map(normalize_webhook_events)
+            .transpose()?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let row: WebhookSubscriptionRow = sqlx::query_as(&format!(
+            r#"
+            UPDATE webhook_subscription
+            SET url = COALESCE($3, url),
+                event_types = COALESCE($4, event_types),
+                active = COALESCE($5, active),
+                description = COALESCE($6, description),
+                updated_at = NOW()
+            WHERE id = $1 AND private_beach_id = $2
+            RETURNING {WEBHOOK_SUBSCRIPTION_COLUMNS}
+            "#
+        ))
+        .bind(webhook_id)
+        .bind(id)
+        .bind(url)
+        .bind(event_types)
+        .bind(update.active)
+        .bind(update.description)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(StateError::WebhookNotFound)?;
+        tx.commit().await?;
+        self.refresh_webhook_index(pool).await;
+        Ok(row.into())
+    }
+
+    pub async fn delete_webhook(
+        &self,
+        private_beach_id: &str,
+        webhook_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<(), StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::WebhookNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let webhook_id = parse_uuid(webhook_id, "webhook_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let deleted =
+            sqlx::query("DELETE FROM webhook_subscription WHERE id = $1 AND private_beach_id = $2")
+                .bind(webhook_id)
+                .bind(id)
+                .execute(tx.as_mut())
+                .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(StateError::WebhookNotFound);
+        }
+        tx.commit().await?;
+        self.refresh_webhook_index(pool).await;
+        info!(
+            target = "private_beach.webhooks",
+            private_beach_id = %id,
+            webhook_id = %webhook_id,
+            "webhook deleted"
+        );
+        Ok(())
+    }
+
+    pub async fn list_webhook_dead_letters(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<Vec<WebhookDeadLetter>, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok(Vec::new()),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let rows: Vec<WebhookDeadLetterRow> = sqlx::query_as(&format!(
+            "SELECT {WEBHOOK_DEAD_LETTER_COLUMNS} FROM webhook_dead_letter WHERE private_beach_id = $1 ORDER BY dead_at DESC LIMIT $2"
+        ))
+        .bind(id)
+        .bind(WEBHOOK_DEAD_LETTER_LIMIT)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(rows.into_iter().map(WebhookDeadLetter::from).collect())
+    }
+
+    /// Re-queues a dead-lettered delivery with a fresh retry budget. The
+    /// delivery keeps its id, so receivers can de-duplicate on
+    /// `x-beach-delivery`.
+    pub async fn replay_webhook_dead_letter(
+        &self,
+        private_beach_id: &str,
+        dead_letter_id: &str,
+        account: Option<Uuid>,
+    ) -> Result<WebhookDeadLetter, StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Err(StateError::WebhookDeadLetterNotFound),
+        };
+        let id = parse_uuid(private_beach_id, "private_beach_id")?;
+        let dead_letter_id = parse_uuid(dead_letter_id, "dead_letter_id")?;
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref())
+            .await?;
+        self.set_rls_context_tx(&mut tx, &id).await?;
+        self.ensure_member_manager_tx(&mut tx, &id, account).await?;
+        let row: WebhookDeadLetterRow = sqlx::query_as(&format!(
+            r#"
+            UPDATE webhook_dead_letter
+            SET replayed_at = NOW()
+            WHERE id = $1 AND private_beach_id = $2
+            RETURNING {WEBHOOK_DEAD_LETTER_COLUMNS}
+            "#
+        ))
+        .bind(dead_letter_id)
+        .bind(id)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(StateError::WebhookDeadLetterNotFound)?;
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery (id, subscription_id, private_beach_id, event_type, session_id, payload, created_at)
+            SELECT id, subscription_id, private_beach_id, event_type, session_id, payload, created_at
+            FROM webhook_dead_letter
+            WHERE id = $1
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(dead_letter_id)
+        .execute(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        info!(
+            target = "private_beach.webhooks",
+            private_beach_id = %id,
+            delivery_id = %dead_letter_id,
+            "webhook dead letter replayed"
+        );
+        Ok(row.into())
+    }
+
+    /// Refuses webhook URLs that resolve to loopback, private or link-local
+    /// addresses.
+    async fn check_webhook_destination(&self, url: &str) -> Result<(), StateError> {
+        let parsed = Url::parse(url)
+            .map_err(|_| StateError::InvalidIdentifier(format!("invalid webhook url '{url}'")))?;
+        webhooks::resolve_destination(&parsed, self.webhook_private_targets)
+            .await
+            .map(|_| ())
+            .map_err(StateError::InvalidIdentifier)
+    }
+
+    /// Whether any active subscription could want `event_type` for this
+    /// session. Sessions whose beach is not cached yet count as wanted.
+    async fn webhook_wanted(&self, session_id: &str, event_type: &str) -> bool {
+        let index = self.webhook_index.read().await;
+        if index.is_empty() {
+            return false;
+        }
+        match index.session_beach(session_id) {
+            Some(private_beach_id) => !index.matching(&private_beach_id, event_type).is_empty(),
+            None => true,
+        }
+    }
+
+    /// Mirrors a stream event to webhook subscribers. Called from `publish`
+    /// before the SSE fan-out so deliveries do not depend on live viewers.
+    async fn queue_webhook_event(&self, session_id: &str, event: &StreamEvent) {
+        if matches!(self.backend, Backend::Memory) {
+            return;
+        }
+        let event_type = webhook_event_type(event);
+        if !self.webhook_wanted(session_id, &event_type).await {
+            return;
+        }
+        let data = match event {
+            StreamEvent::ControllerEvent(ev) => serde_json::to_value(ev),
+            StreamEvent::State(diff) => serde_json::to_value(diff),
+            StreamEvent::Health(hb) => serde_json::to_value(hb),
+            StreamEvent::ControllerPairing(event) => serde_json::to_value(event),
+            StreamEvent::Devtools(event) => serde_json::to_value(event),
+            StreamEvent::AutomationAssignment(status) => serde_json::to_value(status),
+        };
+        match data {
+            Ok(data) => self.enqueue_webhook(session_id, event_type, data).await,
+            Err(err) => warn!(
+                target = "private_beach.webhooks",
+                session_id = %session_id,
+                event_type = %event_type,
+                error = %err,
+                "failed to serialize webhook payload"
+            ),
+        }
+    }
+
+    /// Queues one delivery per matching subscription. The insert runs in the
+    /// background so event publishing never waits on Postgres.
+    async fn enqueue_webhook(&self, session_id: &str, event_type: String, data: serde_json::Value) {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p.clone(),
+            Backend::Memory => return,
+        };
+        if !self.webhook_wanted(session_id, &event_type).await {
+            return;
+        }
+        let state = self.clone();
+        let session_id = session_id.to_string();
+        tokio::spawn(async move {
+            if let Err(err) = state
+                .insert_webhook_deliveries(&pool, &session_id, &event_type, data)
+                .await
+            {
+                warn!(
+                    target = "private_beach.webhooks",
+                    session_id = %session_id,
+                    event_type = %event_type,
+                    error = %err,
+                    "failed to queue webhook deliveries"
+                );
+            }
+        });
+    }
+
+    async fn insert_webhook_deliveries(
+        &self,
+        pool: &PgPool,
+        session_id: &str,
+        event_type: &str,
+        data: serde_json::Value,
+    ) -> Result<(), StateError> {
+        let cached = self.webhook_index.read().await.session_beach(session_id);
+        let private_beach_id = match cached {
+            Some(id) => parse_uuid(&id, "private_beach_id")?,
+            None => {
+                let session_uuid = parse_uuid(session_id, "session_id")?;
+                let identifiers = self.fetch_session_identifiers(pool, &session_uuid).await?;
+                self.webhook_index
+                    .write()
+                    .await
+                    .remember_session(session_id, &identifiers.private_beach_id.to_string());
+                identifiers.private_beach_id
+            }
+        };
+        let subscriptions = self
+            .webhook_index
+            .read()
+            .await
+            .matching(&private_beach_id.to_string(), event_type);
+        if subscriptions.is_empty() {
+            return Ok(());
+        }
+        let mut tx = pool.begin().await?;
+        self.set_rls_context_tx(&mut tx, &private_beach_id).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery (subscription_id, private_beach_id, event_type, session_id, payload)
+            SELECT id, private_beach_id, $3, $4, $5
+            FROM webhook_subscription
+            WHERE id = ANY($1) AND private_beach_id = $2 AND active
+            "#,
+        )
+        .bind(&subscriptions)
+        .bind(private_beach_id)
+        .bind(event_type)
+        .bind(session_id)
+        .bind(Json(data))
+        .execute(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn refresh_webhook_index(&self, pool: &PgPool) {
+        match sqlx::query_as::<_, (Uuid, Uuid, Vec<String>)>(
+            "SELECT private_beach_id, id, event_types FROM webhook_subscription WHERE active",
+        )
+        .fetch_all(pool)
+        .await
+        {
+            Ok(rows) => {
+                self.webhook_index
+                    .write()
+                    .await
+                    .replace(rows.into_iter().map(|(private_beach_id, id, events)| {
+                        (private_beach_id.to_string(), id, events)
+                    }))
+            }
+            Err(err) => warn!(
+                target = "private_beach.webhooks",
+                error = %err,
+                "failed to refresh webhook subscriptions"
+            ),
+        }
+    }
+
+    /// One pass of the delivery worker: refreshes the subscription index,
+    /// claims due deliveries and attempts them concurrently. Deliveries for
+    /// paused subscriptions stay queued until the webhook is re-activated.
+    pub async fn deliver_due_webhooks(&self) {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return,
+        };
+        self.refresh_webhook_index(pool).await;
+        let claimed: Vec<WebhookDeliveryRow> = match sqlx::query_as(
+            r#"
+            WITH due AS (
+                SELECT d.id
+                FROM webhook_delivery d
+                JOIN webhook_subscription s ON s.id = d.subscription_id
+                WHERE d.next_attempt_at <= NOW() AND s.active
+                ORDER BY d.next_attempt_at
+                LIMIT $1
+                FOR UPDATE OF d SKIP LOCKED
+            )
+            UPDATE webhook_delivery d
+            SET next_attempt_at = $2
+            FROM due, webhook_subscription s
+            WHERE d.id = due.id AND s.id = d.subscription_id
+            RETURNING d.id, d.private_beach_id, d.event_type, d.session_id, d.payload,
+                      d.attempts, d.created_at, s.url, s.secret
+            "#,
+        )
+        .bind(WEBHOOK_DELIVERY_BATCH)
+        .bind(Utc::now() + Duration::seconds(WEBHOOK_CLAIM_SECS))
+        .fetch_all(pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(err) => {
+                warn!(
+                    target = "private_beach.webhooks",
+                    error = %err,
+                    "failed to claim webhook deliveries"
+                );
+                return;
+            }
+        };
+        futures_util::future::join_all(
+            claimed
+                .into_iter()
+                .map(|delivery| self.attempt_webhook_delivery(pool, delivery)),
+        )
+        .await;
+    }
+
+    /// A client that connects only to the addresses checked just now and does
+    /// not follow redirects, so neither DNS nor the receiver can steer the
+    /// delivery onto a private address.
+    async fn webhook_client(&self, url: &str) -> Result<reqwest::Client, String> {
+        let parsed = Url::parse(url).map_err(|err| err.to_string())?;
+        let addrs = webhooks::resolve_destination(&parsed, self.webhook_private_targets).await?;
+        let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+        if let Some(url::Host::Domain(domain)) = parsed.host() {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        builder.build().map_err(|err| err.to_string())
+    }
+
+    async fn attempt_webhook_delivery(&self, pool: &PgPool, delivery: WebhookDeliveryRow) {
+        let envelope = json!({
+            "id": delivery.id,
+            "event": delivery.event_type,
+            "private_beach_id": delivery.private_beach_id,
+            "session_id": delivery.session_id,
+            "created_at_ms": delivery.created_at.timestamp_millis(),
+            "data": delivery.payload.0,
+        });
+        let body = serde_json::to_vec(&envelope).unwrap_or_default();
+        let timestamp = Utc::now().timestamp();
+        let result = match self.webhook_client(&delivery.url).await {
+            Ok(client) => client
+                .post(&delivery.url)
+                .timeout(WEBHOOK_DELIVERY_TIMEOUT)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(
+                    webhooks::WEBHOOK_SIGNATURE_HEADER,
+                    webhooks::sign_payload(&delivery.secret, timestamp, &body),
+                )
+                .header(webhooks::WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+                .header(webhooks::WEBHOOK_EVENT_HEADER, &delivery.event_type)
+                .header(webhooks::WEBHOOK_DELIVERY_HEADER, delivery.id.to_string())
+                .body(body)
+                .send()
+                .await
+                .map_err(|err| err.to_string()),
+            Err(err) => Err(err),
+        };
+        let (status, error) = match result {
+            Ok(response) if response.status().is_success() => {
+                if let Err(err) = sqlx::query("DELETE FROM webhook_delivery WHERE id = $1")
+                    .bind(delivery.id)
+                    .execute(pool)
+                    .await
+                {
+                    warn!(
+                        target = "private_beach.webhooks",
+                        delivery_id = %delivery.id,
+                        error = %err,
+                        "failed to clear delivered webhook"
+                    );
+                }
+                return;
+            }
+            Ok(response) => (
+                Some(i32::from(response.status().as_u16())),
+                format!("unexpected status {}", response.status()),
+            ),
+            Err(err) => (None, err),
+        };
+        let attempts = delivery.attempts + 1;
+        let outcome = if attempts >= webhooks::MAX_WEBHOOK_ATTEMPTS {
+            warn!(
+                target = "private_beach.webhooks",
+                private_beach_id = %delivery.private_beach_id,
+                delivery_id = %delivery.id,
+                event_type = %delivery.event_type,
+                attempts,
+                error = %error,
+                "webhook delivery dead-lettered"
+            );
+            sqlx::query(
+                r#"
+                WITH moved AS (
+                    DELETE FROM webhook_delivery
+                    WHERE id = $1
+                    RETURNING id, subscription_id, private_beach_id, event_type, session_id, payload, created_at
+                )
+                INSERT INTO webhook_dead_letter
+                    (id, subscription_id, private_beach_id, event_type, session_id, payload,
+                     attempts, last_status, last_error, created_at)
+                SELECT id, subscription_id, private_beach_id, event_type, session_id, payload,
+                       $2, $3, $4, created_at
+                FROM moved
+                ON CONFLICT (id) DO UPDATE
+                SET attempts = EXCLUDED.attempts,
+                    last_status = EXCLUDED.last_status,
+                    last_error = EXCLUDED.last_error,
+                    dead_at = NOW(),
+                    replayed_at = NULL
+                "#,
+            )
+            .bind(delivery.id)
+            .bind(attempts)
+            .bind(status)
+            .bind(&error)
+            .execute(pool)
+            .await
+        } else {
+            let delay = Duration::from_std(webhooks::retry_delay(attempts))
+                .unwrap_or_else(|_| Duration::hours(1));
+            debug!(
+                target = "private_beach.webhooks",
+                delivery_id = %delivery.id,
+                attempts,
+                retry_in_ms = delay.num_milliseconds(),
+                error = %error,
+                "webhook delivery failed; will retry"
+            );
+            sqlx::query(
+                r#"
+                UPDATE webhook_delivery
+                SET attempts = $2, next_attempt_at = $3, last_status = $4, last_error = $5
+                WHERE id = $1
+                "#,
+            )
+            .bind(delivery.id)
+            .bind(attempts)
+            .bind(Utc::now() + delay)
+            .bind(status)
+            .bind(&error)
+            .execute(pool)
+            .await
+        };
+        if let Err(err) = outcome {
+            warn!(
+                target = "private_beach.webhooks",
+                delivery_id = %delivery.id,
+                error = %err,
+                "failed to record webhook delivery attempt"
+            );
+        }
+    }
+}
+
+// ---- Private Beaches: controller audit ----
+
+/// A `controller_event` or `controller_lease` row joined with its session so
+/// exports and archives carry public ids.
+#[derive(Debug, Clone, FromRow)]
+struct ControllerAuditRow {
+    kind: String,
+    id: Uuid,
+    private_beach_id: Uuid,
+    session_id: Uuid,
+    occurred_at: DateTime<Utc>,
+    event_type: Option<String>,
+    controller_account_id: Option<Uuid>,
+    issued_by_account_id: Option<Uuid>,
+    reason: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ControllerAuditRow> for ControllerAuditRecord {
+    fn from(row: ControllerAuditRow) -> Self {
+        Self {
+            kind: if row.kind == "lease" {
+                AuditRecordKind::Lease
+            } else {
+                AuditRecordKind::Event
+            },
+            id: row.id.to_string(),
+            private_beach_id: row.private_beach_id.to_string(),
+            session_id: row.session_id.to_string(),
+            occurred_at_ms: row.occurred_at.timestamp_millis(),
+            event_type: row.event_type,
+            controller_account_id: row.controller_account_id.map(|id| id.to_string()),
+            issued_by_account_id: row.issued_by_account_id.map(|id| id.to_string()),
+            reason: row.reason,
+            expires_at_ms: row.expires_at.map(|at| at.timestamp_millis()),
+            revoked_at_ms: row.revoked_at.map(|at| at.timestamp_millis()),
+        }
+    }
+}
+
+const CONTROLLER_AUDIT_EVENT_SELECT: &str = "SELECT 'event' AS kind, e.id, s.private_beach_id, s.origin_session_id AS session_id, e.occurred_at, e.event_type::text AS event_type, e.controller_account_id, e.issued_by_account_id, e.reason, NULL::timestamptz AS expires_at, NULL::timestamptz AS revoked_at FROM controller_event e JOIN session s ON s.id = e.session_id";
+const CONTROLLER_AUDIT_LEASE_SELECT: &str = "SELECT 'lease' AS kind, l.id, s.private_beach_id, s.origin_session_id AS session_id, l.issued_at AS occurred_at, NULL::text AS event_type, l.controller_account_id, l.issued_by_account_id, l.reason, l.expires_at, l.revoked_at FROM controller_lease l JOIN session s ON s.id = l.session_id";
+
+#[derive(Debug, Clone, Copy)]
+enum ControllerAuditTable {
+    Events,
+    Leases,
+}
+
+impl ControllerAuditTable {
+    fn name(self) -> &'static str {
+        match self {
+            ControllerAuditTable::Events => "controller_event",
+            ControllerAuditTable::Leases => "controller_lease",
+        }
+    }
+
+    /// Oldest rows past the cutoff (`$1`), locked for deletion, at most `$2`.
+    /// Leases count from when they ended; a lease still referenced by an event
+    /// stays until that event has been archived.
+    fn expired_sql(self) -> String {
+        match self {
+            ControllerAuditTable::Events => format!(
+                "{CONTROLLER_AUDIT_EVENT_SELECT} WHERE e.occurred_at < $1 ORDER BY e.occurred_at, e.id LIMIT $2 FOR UPDATE OF e SKIP LOCKED"
+            ),
+            ControllerAuditTable::Leases => format!(
+                "{CONTROLLER_AUDIT_LEASE_SELECT} WHERE COALESCE(l.revoked_at, l.expires_at) < $1 AND NOT EXISTS (SELECT 1 FROM controller_event ce WHERE ce.controller_token_id = l.id) ORDER BY COALESCE(l.revoked_at, l.expires_at), l.id LIMIT $2 FOR UPDATE OF l SKIP LOCKED"
+            ),
+        }
+    }
+}
+
+impl AppState {
+    /// Events in `[from, to)` and leases overlapping it, oldest first. The
+    /// flag is set when [`audit::MAX_AUDIT_EXPORT_ROWS`] cut the result short.
+    pub async fn controller_audit_records(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        filter: &AuditFilter,
+    ) -> Result<(Vec<ControllerAuditRecord>, bool), StateError> {
+        let pool = match &self.backend {
+            Backend::Postgres(p) => p,
+            Backend::Memory => return Ok((Vec::new(), false)),
+        };
+        let beach_uuid = parse_uuid(private_beach_id, "private_beach_id")?;
+        let from = to_datetime(filter.from_ms)?;
+        let to = to_datetime(filter.to_ms)?;
+        let limit = audit::MAX_AUDIT_EXPORT_ROWS as i64 + 1;
+
+        let mut tx = pool.begin().await?;
+        self.set_account_context_tx(&mut tx, account.as_ref()).await?;
+        self.set_rls_context_tx(&mut tx, &beach_uuid).await?;
+        self.ensure_member_manager_tx(&mut tx, &beach_uuid, account).await?;
+        // Merged in SQL so the row cap applies to the combined timeline rather
+        // than to each table, which would drop the newest rows of one of them.
+        let rows: Vec<ControllerAuditRow> = sqlx::query_as(&format!(
+            "({CONTROLLER_AUDIT_EVENT_SELECT} WHERE s.private_beach_id = $1 AND e.occurred_at >= $2 AND e.occurred_at < $3 AND ($4::uuid IS NULL OR e.controller_account_id = $4 OR e.issued_by_account_id = $4) AND ($5::uuid IS NULL OR s.origin_session_id = $5)) UNION ALL ({CONTROLLER_AUDIT_LEASE_SELECT} WHERE s.private_beach_id = $1 AND l.issued_at < $3 AND COALESCE(l.revoked_at, l.expires_at) >= $2 AND ($4::uuid IS NULL OR l.controller_account_id = $4 OR l.issued_by_account_id = $4) AND ($5::uuid IS NULL OR s.origin_session_id = $5)) ORDER BY occurred_at, id LIMIT $6"
+        ))
+        .bind(beach_uuid)
+        .bind(from)
+        .bind(to)
+        .bind(filter.account_id)
+        .bind(filter.session_id)
+        .bind(limit)
+        .fetch_all(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+
+        let truncated = rows.len() > audit::MAX_AUDIT_EXPORT_ROWS;
+        let records = rows
+            .into_iter()
+            .take(audit::MAX_AUDIT_EXPORT_ROWS)
+            .map(ControllerAuditRecord::from)
+            .collect();
+        Ok((records, truncated))
+    }
+
+    pub async fn controller_audit_summary(
+        &self,
+        private_beach_id: &str,
+        account: Option<Uuid>,
+        filter: &AuditFilter,
+    ) -> Result<ControllerAuditSummary, StateError> {
+        let (records, truncated) = self
+            .controller_audit_records(private_beach_id, account, filter)
+            .await?;
+        // Leases are clipped to the requested window so totals add up per range.
+        let clipped: Vec<ControllerAuditRecord> = records
+            .into_iter()
+            .map(|mut record| {
+                if record.kind == AuditRecordKind::Lease {
+                    record.occurred_at_ms = record.occurred_at_ms.max(filter.from_ms);
+                }
+                record
+            })
+            .collect();
+        Ok(ControllerAuditSummary {
+            private_beach_id: private_beach_id.to_string(),
+            from_ms: filter.from_ms,
+            to_ms: filter.to_ms,
+            accounts: audit::summarize(&clipped, filter.to_ms.min(now_ms())),
+            truncated,
+        })
+    }
+
+    /// Moves controller events and finished leases older than the retention
+    /// cutoff into the archive store, one JSONL file per batch, deleting rows
+    /// only after their batch is written. No-op without a retention policy.
+    pub async fn archive_expired_controller_audit(&self) {
+        let (pool, retention) = match (&self.backend, &self.audit_retention) {
+            (Backend::Postgres(pool), Some(retention)) => (pool, retention),
+            _ => return,
+        };
+        let cutoff = retention.cutoff(Utc::now());
+        let mut archived = 0usize;
+        for table in [ControllerAuditTable::Events, ControllerAuditTable::Leases] {
+            loop {
+                match self
+                    .archive_controller_audit_batch(pool, retention, table, cutoff)
+                    .await
+                {
+                    Ok(count) => {
+                        archived += count;
+                        if (count as i64) < audit::AUDIT_ARCHIVE_BATCH {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        warn!(
+                            target = "private_beach.audit",
+                            table = table.name(),
+                            error = %err,
+                            "controller audit archive failed"
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+        if archived > 0 {
+            info!(
+                target = "private_beach.audit",
+                archived,
+                cutoff = %cutoff,
+                "archived expired controller audit rows"
+            );
+        }
+    }
+
+    async fn archive_controller_audit_batch(
+        &self,
+        pool: &PgPool,
+        retention: &AuditRetention,
+        table: ControllerAuditTable,
+        cutoff: DateTime<Utc>,
+    ) -> Result<usize, StateError> {
+        let mut tx = pool.begin().await?;
+        let rows: Vec<ControllerAuditRow> = sqlx::query_as(&table.expired_sql())
+            .bind(cutoff)
+            .bind(audit::AUDIT_ARCHIVE_BATCH)
+            .fetch_all(tx.as_mut())
+            .await?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+        let records: Vec<ControllerAuditRecord> =
+            rows.into_iter().map(ControllerAuditRecord::from).collect();
+        let key = audit::archive_key(Utc::now(), Uuid::new_v4());
+        retention
+            .archive
+            .put(&key, Bytes::from(audit::encode_jsonl(&records)))
+            .await
+            .map_err(blob_state_err)?;
+        sqlx::query(&format!("DELETE FROM {} WHERE id = ANY($1)", table.name()))
+            .bind(&ids)
+            .execute(tx.as_mut())
+            .await?;
+        tx.commit().await?;
+        Ok(ids.len())
     }
 }
 
+fn to_datetime(ms: i64) -> Result<DateTime<Utc>, StateError> {
+    DateTime::<Utc>::from_timestamp_millis(ms)
+        .ok_or_else(|| StateError::InvalidIdentifier(format!("timestamp {ms} out of range")))
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, FromRow)]
 struct LeaseRow {
@@ -8387,6 +12422,10 @@ async fn viewer_connect_once(
                                 WireHostFrame::Cursor { .. } => "cursor".to_string(),
                                 WireHostFrame::Heartbeat { .. } => "heartbeat".to_string(),
                                 WireHostFrame::Extension { .. } => "extension".to_string(),
+                                WireHostFrame::SearchResults { .. } => {
+                                    "search_results".to_string()
+                                }
+                                WireHostFrame::Modes { .. } => "modes".to_string(),
                                 WireHostFrame::Shutdown => "shutdown".to_string(),
                             };
                             debug!(
@@ -9488,35 +13527,27 @@ async fn drive_controller_forwarder(
                 }
                 for action in actions {
                     let action_id = action.id.clone();
-                    match action_terminal_bytes(&action) {
-                        Ok(bytes) => {
+                    match controller_action_payload(&action) {
+                        Ok(controller_payload) => {
                             let inflight_before = pending.len();
                             next_seq = next_seq.saturating_add(1);
-                            let controller_payload = match serde_json::to_vec(&serde_json::json!({
-                                "action_id": action_id.clone(),
-                                "bytes": bytes,
-                            })) {
-                                Ok(payload) => payload,
-                                Err(err) => {
-                                    warn!(
-                                        target = "controller.forwarder",
-                                        session_id = %session_id,
-                                        error = %err,
-                                        "failed to encode controller input payload"
-                                    );
-                                    continue;
-                                }
-                            };
                             let sent_at = loop {
-                                let send_result = match transport
-                                    .send_namespaced("controller", "input", &controller_payload)
-                                {
+                                let send_result = match transport.send_namespaced(
+                                    "controller",
+                                    "action",
+                                    &controller_payload,
+                                ) {
                                     Ok(seq) => Ok(seq),
                                     Err(TransportError::Setup(_)) => {
                                         let encoded =
-                                            encode_client_frame_binary(&ClientFrame::Input {
-                                                seq: next_seq,
-                                                data: controller_payload.clone(),
+                                            encode_client_frame_binary(&ClientFrame::Extension {
+                                                frame: ExtensionFrame {
+                                                    namespace: "manager".into(),
+                                                    kind: manager_topics::TOPIC_ACTION.into(),
+                                                    payload: Bytes::from(
+                                                        controller_payload.clone(),
+                                                    ),
+                                                },
                                             });
                                         transport.send_bytes(&encoded)
                                     }
@@ -9712,16 +13743,52 @@ async fn select_controller_transport(
     (primary_transport, label, via_fast_path)
 }
 
-fn action_terminal_bytes(action: &ActionCommand) -> Result<Vec<u8>, String> {
-    if action.action_type.as_str() != "terminal_write" {
-        return Err(format!("unsupported action type {}", action.action_type));
+/// Caps each action's `expires_at` at the expiry of the lease that queued it,
+/// which is how long the host keeps a controller's window size.
+fn bind_actions_to_lease(actions: &mut [ActionCommand], lease_expires_at: SystemTime) {
+    for action in actions {
+        action.expires_at = Some(
+            action
+                .expires_at
+                .map_or(lease_expires_at, |at| at.min(lease_expires_at)),
+        );
+    }
+}
+
+/// Checks every action against the catalog and the harness's advertised
+/// capabilities; the whole batch is rejected on the first bad action.
+fn validate_controller_actions(
+    actions: &[ActionCommand],
+    capabilities: &[String],
+) -> Result<(), StateError> {
+    let supported = ActionKind::supported_by(capabilities);
+    for action in actions {
+        let parsed =
+            ControllerAction::parse(&action.action_type, &action.payload).and_then(|parsed| {
+                if supported.contains(&parsed.kind()) {
+                    Ok(parsed)
+                } else {
+                    Err(ActionValidationError::Unsupported(parsed.kind()))
+                }
+            });
+        if let Err(error) = parsed {
+            return Err(StateError::InvalidAction {
+                action_id: action.id.clone(),
+                error,
+            });
+        }
     }
-    let bytes = action
-        .payload
-        .get("bytes")
-        .and_then(|value| value.as_str())
-        .ok_or_else(|| "terminal_write payload missing bytes".to_string())?;
-    Ok(bytes.as_bytes().to_vec())
+    Ok(())
+}
+
+/// Envelope the host's controller channel decodes back into an
+/// [`ActionCommand`]. The action travels structured so the host can apply
+/// resize and signal actions, not just PTY input.
+fn controller_action_payload(action: &ActionCommand) -> Result<Vec<u8>, String> {
+    ControllerAction::parse(&action.action_type, &action.payload)
+        .map_err(|err| err.to_string())?;
+    serde_json::to_vec(&json!({ "type": "action", "payload": action }))
+        .map_err(|err| format!("failed to encode controller action: {err}"))
 }
 
 async fn fail_pending_actions(
@@ -9889,7 +13956,7 @@ pub enum JoinForwardError {
     Decode(#[from] serde_json::Error),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AdvertisedTransportKind {
     WebRtc,
@@ -9897,7 +13964,7 @@ pub enum AdvertisedTransportKind {
     Ipc,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AdvertisedTransport {
     pub kind: AdvertisedTransportKind,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -9906,7 +13973,7 @@ pub struct AdvertisedTransport {
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct JoinSessionResponsePayload {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -9920,6 +13987,7 @@ pub struct JoinSessionResponsePayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub websocket_url: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Object>>)]
     pub ice_servers: Option<Vec<TurnIceServer>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ice_servers_expires_at_ms: Option<i64>,
@@ -10955,6 +15023,49 @@ mod tests {
             .get()
     }
 
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn queue_actions_validates_catalog_and_capabilities() {
+        let state = AppState::new().with_controller_strict_gating(false);
+        let token = insert_manual_session(&state, "sess-actions", |record| {
+            record.capabilities = vec![
+                "terminal_diff_v1".into(),
+                ActionKind::TerminalWrite.capability(),
+                ActionKind::Key.capability(),
+            ];
+        })
+        .await;
+        state
+            .queue_actions("sess-actions", &token, vec![new_action("ok")], None)
+            .await
+            .expect("key action accepted");
+
+        let resize = |id: &str, cols: u16| ActionCommand {
+            id: id.into(),
+            action_type: "resize".into(),
+            payload: json!({ "cols": cols, "rows": 24 }),
+            expires_at: None,
+        };
+        for (action, code) in [
+            (resize("bad-size", 0), "invalid_action_payload"),
+            (resize("not-advertised", 80), "action_not_supported"),
+        ] {
+            let expected_id = action.id.clone();
+            match state
+                .queue_actions("sess-actions", &token, vec![action], None)
+                .await
+            {
+                Err(StateError::InvalidAction { action_id, error }) => {
+                    assert_eq!(action_id, expected_id);
+                    assert_eq!(error.code(), code);
+                }
+                other => panic!("expected invalid action, got {other:?}"),
+            }
+        }
+
+        let sessions = state.fallback.sessions.read().await;
+        assert_eq!(sessions["sess-actions"].pending_actions.len(), 1);
+    }
+
     #[tokio::test]
     async fn controller_commands_reject_child_not_attached_state() {
         let state = AppState::new().with_controller_strict_gating(true);
@@ -11000,4 +15111,552 @@ mod tests {
         let after = drop_metric(ControllerCommandDropReason::ChildOffline);
         assert_eq!(after, before + 1);
     }
+
+    #[test]
+    fn share_link_rejection_checks_revocation_expiry_and_uses() {
+        let now = Utc::now();
+        let past = Some(now - chrono::Duration::minutes(1));
+        let future = Some(now + chrono::Duration::minutes(1));
+        assert_eq!(share_link_rejection(None, future, Some(2), 1, now), None);
+        assert_eq!(
+            share_link_rejection(past, future, None, 0, now),
+            Some(ShareLinkRejection::Revoked)
+        );
+        assert_eq!(
+            share_link_rejection(None, past, None, 0, now),
+            Some(ShareLinkRejection::Expired)
+        );
+        assert_eq!(
+            share_link_rejection(None, None, Some(2), 2, now),
+            Some(ShareLinkRejection::Exhausted)
+        );
+
+        let mut link = ShareLinkRow {
+            id: Uuid::new_v4(),
+            private_beach_id: Uuid::new_v4(),
+            created_by_account_id: None,
+            label: None,
+            granted_role: MembershipRole::Viewer,
+            max_uses: Some(1),
+            use_count: 0,
+            expires_at: None,
+            revoked_at: None,
+            created_at: now,
+        };
+        let member = Some((Uuid::new_v4(), MembershipRole::Contributor));
+        let redemption = link
+            .member_redemption(member, now)
+            .expect("live link answers existing members")
+            .expect("existing membership returned");
+        assert!(redemption.already_member);
+        assert_eq!(redemption.role, MembershipRole::Contributor);
+        assert!(matches!(link.member_redemption(None, now), Ok(None)));
+        link.use_count = 1;
+        assert_eq!(
+            link.member_redemption(member, now).err(),
+            Some(ShareLinkRejection::Exhausted)
+        );
+        link.use_count = 0;
+        link.revoked_at = past;
+        assert_eq!(
+            link.member_redemption(member, now).err(),
+            Some(ShareLinkRejection::Revoked)
+        );
+        link.revoked_at = None;
+        link.expires_at = past;
+        assert_eq!(
+            link.member_redemption(member, now).err(),
+            Some(ShareLinkRejection::Expired)
+        );
+
+        let token = generate_share_link_token();
+        assert!(token.starts_with("bsl_"));
+        assert_eq!(hash_share_link_token(&token).len(), 64);
+        assert_ne!(hash_share_link_token(&token), token);
+    }
+
+    #[test]
+    fn group_grants_are_trimmed_and_deduplicated() {
+        let grants = normalize_group_grants(vec![
+            GroupGrant {
+                scope: " pb:control.write ".into(),
+                session_tag: Some("prod".into()),
+            },
+            GroupGrant {
+                scope: "pb:control.write".into(),
+                session_tag: Some("PROD".into()),
+            },
+            GroupGrant {
+                scope: "pb:sessions.read".into(),
+                session_tag: Some("  ".into()),
+            },
+        ])
+        .expect("valid grants");
+        assert_eq!(
+            grants,
+            vec![
+                GroupGrant {
+                    scope: "pb:control.write".into(),
+                    session_tag: Some("prod".into()),
+                },
+                GroupGrant {
+                    scope: "pb:sessions.read".into(),
+                    session_tag: None,
+                },
+            ]
+        );
+        for scope in ["pb:control write", "*", "pb:control.*", "pb:beaches.admin"] {
+            assert!(
+                normalize_group_grants(vec![GroupGrant {
+                    scope: scope.into(),
+                    session_tag: None,
+                }])
+                .is_err(),
+                "{scope} must be rejected"
+            );
+        }
+    }
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn session_tags_filter_memory_listing() {
+        let state = AppState::new();
+        insert_manual_session(&state, "shell-prod", |record| {
+            record.harness_type = HarnessType::TerminalShim;
+            record.location_hint = Some("eu-west/db-01".into());
+        })
+        .await;
+        insert_manual_session(&state, "widget-prod", |_| {}).await;
+        insert_manual_session(&state, "shell-dev", |record| {
+            record.harness_type = HarnessType::TerminalShim;
+            record.last_seen_at_ms = 1_000;
+        })
+        .await;
+
+        let tags = state
+            .add_session_tag("shell-prod", " prod ")
+            .await
+            .expect("tag added");
+        assert_eq!(tags, vec!["prod".to_string()]);
+        let tags = state
+            .add_session_tag("shell-prod", "PROD")
+            .await
+            .expect("duplicate tag ignored");
+        assert_eq!(tags, vec!["prod".to_string()]);
+        state
+            .add_session_tag("widget-prod", "prod")
+            .await
+            .expect("tag added");
+        assert!(matches!(
+            state.add_session_tag("shell-dev", "two words").await,
+            Err(StateError::InvalidIdentifier(_))
+        ));
+
+        let ids = |rows: Vec<SessionSummary>| {
+            let mut ids: Vec<String> = rows.into_iter().map(|row| row.session_id).collect();
+            ids.sort();
+            ids
+        };
+        let prod_shells = SessionFilter {
+            tag: Some("Prod".into()),
+            kind: Some(SessionKind::Terminal),
+            status: Some(SessionStatus::Active),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(state
+                .list_sessions_filtered("pb-test", &prod_shells)
+                .await
+                .unwrap()),
+            vec!["shell-prod".to_string()]
+        );
+        let by_text = SessionFilter {
+            q: Some("DB-01".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(state
+                .list_sessions_filtered("pb-test", &by_text)
+                .await
+                .unwrap()),
+            vec!["shell-prod".to_string()]
+        );
+        let stale = SessionFilter {
+            last_seen_before_ms: Some(5_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(state
+                .list_sessions_filtered("pb-test", &stale)
+                .await
+                .unwrap()),
+            vec!["shell-dev".to_string()]
+        );
+        let ended = SessionFilter {
+            status: Some(SessionStatus::Ended),
+            ..Default::default()
+        };
+        assert!(state
+            .list_sessions_filtered("pb-test", &ended)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let tags = state
+            .remove_session_tag("shell-prod", "PROD")
+            .await
+            .expect("tag removed");
+        assert!(tags.is_empty());
+        assert_eq!(state.list_sessions("pb-test").await.unwrap().len(), 3);
+    }
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn file_store_versions_lists_and_soft_deletes() {
+        let state = AppState::new();
+        insert_manual_session(&state, "sess-artifacts", |_| {}).await;
+
+        let first = state
+            .upload_file(
+                "pb-test",
+                None,
+                "/logs//build.txt",
+                Some("text/plain".into()),
+                Bytes::from_static(b"v1"),
+            )
+            .await
+            .expect("first upload");
+        assert_eq!(first.path, "logs/build.txt");
+        assert_eq!(first.version, 1);
+        let second = state
+            .upload_session_artifact(
+                "sess-artifacts",
+                "logs/build.txt",
+                None,
+                Bytes::from_static(b"v2"),
+            )
+            .await
+            .expect("session upload");
+        assert_eq!(second.version, 2);
+        assert_eq!(
+            second.uploaded_by_session_id.as_deref(),
+            Some("sess-artifacts")
+        );
+        state
+            .upload_file("pb-test", None, "notes.md", None, Bytes::from_static(b"hi"))
+            .await
+            .expect("second path");
+        assert!(matches!(
+            state
+                .upload_file("pb-test", None, "../escape", None, Bytes::new())
+                .await,
+            Err(StateError::InvalidIdentifier(_))
+        ));
+
+        let listed = state
+            .list_files("pb-test", None, Some("logs/"), false)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].version, 2);
+
+        let (latest, data) = state
+            .download_file("pb-test", None, "logs/build.txt", None)
+            .await
+            .unwrap();
+        assert_eq!(latest.version, 2);
+        assert_eq!(data, Bytes::from_static(b"v2"));
+        let (_, old) = state
+            .download_file("pb-test", None, "logs/build.txt", Some(1))
+            .await
+            .unwrap();
+        assert_eq!(old, Bytes::from_static(b"v1"));
+
+        assert_eq!(
+            state
+                .delete_file("pb-test", None, "logs/build.txt")
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(matches!(
+            state
+                .download_file("pb-test", None, "logs/build.txt", None)
+                .await,
+            Err(StateError::FileNotFound)
+        ));
+        assert_eq!(
+            state
+                .list_files("pb-test", None, None, false)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            state
+                .list_files("pb-test", None, None, true)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        let versions = state
+            .file_versions("pb-test", None, "logs/build.txt")
+            .await
+            .unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert!(versions.iter().all(|v| v.deleted_at_ms.is_some()));
+    }
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn automation_assignment_reconciles_leases_and_pairings() {
+        let state = AppState::new();
+        insert_manual_session(&state, "agent", |_| {}).await;
+        insert_manual_session(&state, "child-a", |_| {}).await;
+        state
+            .acquire_controller("agent", None, None, None)
+            .await
+            .expect("agent lease");
+        let mut child_events = state.subscribe_session("child-a").await;
+
+        let owner = Uuid::new_v4().to_string();
+        let config = AssignmentConfig {
+            controller_session_id: Some("agent".into()),
+            prompt_template: Some("watch the build".into()),
+            ..Default::default()
+        };
+        let assignment = state
+            .create_automation_assignment(
+                "pb-test",
+                &owner,
+                AutomationRole::Controller,
+                None,
+                config.clone(),
+                None,
+            )
+            .await
+            .expect("assignment created");
+        assert_eq!(assignment.status.len(), 1);
+        let status = &assignment.status[0];
+        assert_eq!(status.session_id, "child-a");
+        assert_eq!(status.state, AssignmentState::Active);
+        assert!(status.paired);
+        assert!(status.lease_expires_at_ms.is_some());
+        assert!(matches!(
+            state
+                .create_automation_assignment(
+                    "pb-test",
+                    &owner,
+                    AutomationRole::Observer,
+                    None,
+                    config,
+                    None,
+                )
+                .await,
+            Err(StateError::AutomationAssignmentConflict)
+        ));
+
+        insert_manual_session(&state, "child-b", |_| {}).await;
+        state.reconcile_automation_assignments().await;
+        let children = |pairings: Vec<ControllerPairing>| {
+            let mut ids: Vec<String> = pairings
+                .into_iter()
+                .map(|pairing| pairing.child_session_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            children(state.list_controller_pairings("agent").await.unwrap()),
+            vec!["child-a".to_string(), "child-b".to_string()]
+        );
+        let listed = state
+            .list_automation_assignments("pb-test", None)
+            .await
+            .expect("list");
+        assert_eq!(listed[0].status.len(), 2);
+        // Renewing the held lease on each pass is silent.
+        state.reconcile_automation_assignments().await;
+        let acquired = state
+            .controller_events("child-a")
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|event| matches!(event.event_type, ControllerEventType::LeaseAcquired))
+            .count();
+        assert_eq!(acquired, 1);
+
+        state
+            .delete_automation_assignment("pb-test", &assignment.id, None)
+            .await
+            .expect("deleted");
+        assert!(children(state.list_controller_pairings("agent").await.unwrap()).is_empty());
+        {
+            let sessions = state.fallback.sessions.read().await;
+            let reason = assignment_lease_reason(&assignment.id);
+            for child in ["child-a", "child-b"] {
+                assert!(sessions[child]
+                    .controller_leases
+                    .values()
+                    .all(|lease| lease.reason.as_deref() != Some(reason.as_str())));
+            }
+        }
+
+        let mut states = Vec::new();
+        while let Ok(event) = child_events.try_recv() {
+            if let StreamEvent::AutomationAssignment(status) = event {
+                states.push(status.state);
+            }
+        }
+        assert_eq!(
+            states,
+            vec![AssignmentState::Active, AssignmentState::Released]
+        );
+    }
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn automation_assignment_leaves_manual_pairings_alone() {
+        let state = AppState::new();
+        insert_manual_session(&state, "agent", |_| {}).await;
+        insert_manual_session(&state, "child-a", |_| {}).await;
+        insert_manual_session(&state, "child-b", |_| {}).await;
+        state
+            .acquire_controller("agent", None, None, None)
+            .await
+            .expect("agent lease");
+        for (child, prompt) in [("child-a", "watch the build"), ("child-b", "by hand")] {
+            state
+                .upsert_controller_pairing(
+                    "agent",
+                    child,
+                    Some(prompt.into()),
+                    Some(ControllerUpdateCadence::Slow),
+                    None,
+                )
+                .await
+                .expect("manual pairing");
+        }
+
+        let config = AssignmentConfig {
+            controller_session_id: Some("agent".into()),
+            prompt_template: Some("watch the build".into()),
+            update_cadence: Some(ControllerUpdateCadence::Slow),
+            ..Default::default()
+        };
+        let assignment = state
+            .create_automation_assignment(
+                "pb-test",
+                &Uuid::new_v4().to_string(),
+                AutomationRole::Observer,
+                None,
+                config,
+                None,
+            )
+            .await
+            .expect("assignment created");
+        let status = |session_id: &str| {
+            assignment
+                .status
+                .iter()
+                .find(|status| status.session_id == session_id)
+                .cloned()
+                .expect("status")
+        };
+        let matching = status("child-a");
+        assert_eq!(matching.state, AssignmentState::Active);
+        assert!(matching.paired);
+        assert!(!matching.owns_pairing);
+        let conflicting = status("child-b");
+        assert_eq!(conflicting.state, AssignmentState::Conflict);
+        assert!(!conflicting.paired);
+
+        state.reconcile_automation_assignments().await;
+        state
+            .delete_automation_assignment("pb-test", &assignment.id, None)
+            .await
+            .expect("deleted");
+        let mut pairings: Vec<(String, Option<String>)> = state
+            .list_controller_pairings("agent")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|pairing| (pairing.child_session_id, pairing.prompt_template))
+            .collect();
+        pairings.sort();
+        assert_eq!(
+            pairings,
+            vec![
+                ("child-a".to_string(), Some("watch the build".to_string())),
+                ("child-b".to_string(), Some("by hand".to_string())),
+            ]
+        );
+    }
+
+    #[test_timeout::tokio_timeout_test(10)]
+    async fn automation_assignment_keeps_its_pairing_across_restarts() {
+        let state = AppState::new();
+        insert_manual_session(&state, "agent", |_| {}).await;
+        insert_manual_session(&state, "child-a", |_| {}).await;
+        state
+            .acquire_controller("agent", None, None, None)
+            .await
+            .expect("agent lease");
+        let config = AssignmentConfig {
+            controller_session_id: Some("agent".into()),
+            prompt_template: Some("watch the build".into()),
+            ..Default::default()
+        };
+        let assignment = state
+            .create_automation_assignment(
+                "pb-test",
+                &Uuid::new_v4().to_string(),
+                AutomationRole::Observer,
+                None,
+                config.clone(),
+                None,
+            )
+            .await
+            .expect("assignment created");
+        let pairings = state.list_controller_pairings("agent").await.unwrap();
+        assert_eq!(
+            pairings[0].automation_assignment_id.as_deref(),
+            Some(assignment.id.as_str())
+        );
+
+        // A restart loses every in-memory status; ownership comes back from
+        // the stored pairing.
+        state.assignment_status.write().await.clear();
+        state.reconcile_automation_assignments().await;
+        let updated = state
+            .update_automation_assignment(
+                "pb-test",
+                &assignment.id,
+                None,
+                Some(AssignmentConfig {
+                    update_cadence: Some(ControllerUpdateCadence::Fast),
+                    ..config
+                }),
+                None,
+            )
+            .await
+            .expect("assignment updated");
+        assert_eq!(updated.status[0].state, AssignmentState::Active);
+        assert!(updated.status[0].owns_pairing);
+        let pairings = state.list_controller_pairings("agent").await.unwrap();
+        assert_eq!(pairings[0].update_cadence, ControllerUpdateCadence::Fast);
+
+        state.assignment_status.write().await.clear();
+        state.reconcile_automation_assignments().await;
+        state
+            .delete_automation_assignment("pb-test", &assignment.id, None)
+            .await
+            .expect("deleted");
+        assert!(state
+            .list_controller_pairings("agent")
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
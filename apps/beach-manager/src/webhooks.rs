@@ -0,0 +1,315 @@
+//! Building blocks for outbound webhooks.
+//!
+//! Subscriptions, the delivery queue and dead letters live in Postgres (see the
+//! `webhook_*` tables); this module holds the parts that do not touch the
+//! database: event naming and filters, request signing, the retry schedule,
+//! destination checks and the in-process index `AppState::publish` consults
+//! before queueing anything.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use url::{Host, Url};
+use uuid::Uuid;
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-beach-signature";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-beach-timestamp";
+pub const WEBHOOK_EVENT_HEADER: &str = "x-beach-event";
+pub const WEBHOOK_DELIVERY_HEADER: &str = "x-beach-delivery";
+
+/// Deliveries are dead-lettered after this many failed attempts.
+pub const MAX_WEBHOOK_ATTEMPTS: i32 = 8;
+const RETRY_BASE: Duration = Duration::from_secs(5);
+const RETRY_CAP: Duration = Duration::from_secs(60 * 60);
+
+/// Event names a subscription may filter on. A trailing `.*` matches a prefix.
+pub const WEBHOOK_EVENT_TYPES: &[&str] = &[
+    "controller.registered",
+    "controller.lease_acquired",
+    "controller.lease_released",
+    "controller.actions_queued",
+    "controller.actions_acked",
+    "controller.health_reported",
+    "controller.state_updated",
+    "controller.pairing_added",
+    "controller.pairing_removed",
+    "controller.emergency_stop",
+    "pairing.added",
+    "pairing.updated",
+    "pairing.removed",
+    "session.state",
+    "session.health",
+    "session.devtools",
+    "automation.assignment",
+];
+
+/// Accepts exact event names and `prefix.*` wildcards over known events.
+pub fn validate_event_filter(filter: &str) -> Result<(), String> {
+    let known = match filter.strip_suffix(".*") {
+        Some(prefix) => WEBHOOK_EVENT_TYPES
+            .iter()
+            .any(|event| event.starts_with(&format!("{prefix}."))),
+        None => WEBHOOK_EVENT_TYPES.contains(&filter),
+    };
+    if known {
+        Ok(())
+    } else {
+        Err(format!("unknown webhook event '{filter}'"))
+    }
+}
+
+/// Subscriptions must name their events: `session.state` fires on every
+/// terminal diff, so there is deliberately no match-everything filter.
+pub fn event_matches(filters: &[String], event_type: &str) -> bool {
+    filters
+        .iter()
+        .any(|filter| match filter.strip_suffix(".*") {
+            Some(prefix) => event_type
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with('.')),
+            None => filter == event_type,
+        })
+}
+
+/// `sha256=<hex>` over `"{timestamp}.{body}"`, keyed by the subscription secret.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delay before retrying a delivery that has failed `attempts` times.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    RETRY_BASE.saturating_mul(1 << exponent).min(RETRY_CAP)
+}
+
+/// Whether deliveries may connect to `addr`. Loopback, RFC 1918, link-local
+/// (which covers the 169.254.169.254 metadata endpoint), unique-local and
+/// other non-routable addresses are refused so a subscription cannot reach
+/// the manager's own network.
+pub fn is_public_address(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10.
+                || (a == 100 && (b & 0xc0) == 64))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local, fc00::/7.
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10.
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolves the host of a webhook URL. Every address must be public unless
+/// `allow_private` is set; the returned addresses are the ones to connect to,
+/// so a later DNS answer cannot redirect the delivery.
+pub async fn resolve_destination(
+    url: &Url,
+    allow_private: bool,
+) -> Result<Vec<SocketAddr>, String> {
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format!("webhook url '{url}' has no port"))?;
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+        Some(Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|err| format!("webhook host '{domain}' did not resolve: {err}"))?
+            .collect(),
+        None => return Err(format!("webhook url '{url}' has no host")),
+    };
+    if addrs.is_empty() {
+        return Err(format!("webhook url '{url}' resolved to no addresses"));
+    }
+    if !allow_private {
+        if let Some(addr) = addrs.iter().find(|addr| !is_public_address(addr.ip())) {
+            return Err(format!(
+                "webhook url '{url}' resolves to non-public address {}",
+                addr.ip()
+            ));
+        }
+    }
+    Ok(addrs)
+}
+
+pub fn generate_webhook_secret() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+/// Active subscriptions per beach plus a session → beach cache so `publish`
+/// can skip events nobody subscribed to. The delivery loop refreshes the
+/// subscriptions; cached sessions are only dropped when the session or its
+/// beach goes away, since a session never changes beach.
+#[derive(Debug, Default)]
+pub struct WebhookIndex {
+    by_beach: HashMap<String, Vec<(Uuid, Vec<String>)>>,
+    session_beaches: HashMap<String, String>,
+}
+
+impl WebhookIndex {
+    /// Swaps in the active subscriptions, keeping the session cache.
+    pub fn replace(
+        &mut self,
+        subscriptions: impl IntoIterator<Item = (String, Uuid, Vec<String>)>,
+    ) {
+        self.by_beach.clear();
+        for (private_beach_id, id, filters) in subscriptions {
+            self.by_beach
+                .entry(private_beach_id)
+                .or_default()
+                .push((id, filters));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_beach.is_empty()
+    }
+
+    /// Subscription ids in `private_beach_id` that want `event_type`.
+    pub fn matching(&self, private_beach_id: &str, event_type: &str) -> Vec<Uuid> {
+        self.by_beach
+            .get(private_beach_id)
+            .map(|subs| {
+                subs.iter()
+                    .filter(|(_, filters)| event_matches(filters, event_type))
+                    .map(|(id, _)| *id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn session_beach(&self, session_id: &str) -> Option<String> {
+        self.session_beaches.get(session_id).cloned()
+    }
+
+    pub fn remember_session(&mut self, session_id: &str, private_beach_id: &str) {
+        self.session_beaches
+            .insert(session_id.to_string(), private_beach_id.to_string());
+    }
+
+    pub fn forget_session(&mut self, session_id: &str) {
+        self.session_beaches.remove(session_id);
+    }
+
+    pub fn forget_beach(&mut self, private_beach_id: &str) {
+        self.by_beach.remove(private_beach_id);
+        self.session_beaches
+            .retain(|_, beach| beach.as_str() != private_beach_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_signatures_and_backoff() {
+        let filters = vec!["controller.*".to_string(), "session.health".to_string()];
+        assert!(event_matches(&filters, "controller.emergency_stop"));
+        assert!(event_matches(&filters, "session.health"));
+        assert!(!event_matches(&filters, "session.state"));
+        assert!(!event_matches(
+            &["control.*".to_string()],
+            "controller.registered"
+        ));
+        assert!(!event_matches(&[], "session.state"));
+        assert!(validate_event_filter("pairing.*").is_ok());
+        assert!(validate_event_filter("pairing.exploded").is_err());
+
+        assert_eq!(
+            sign_payload("secret", 1_700_000_000, b"{}"),
+            sign_payload("secret", 1_700_000_000, b"{}")
+        );
+        assert_ne!(
+            sign_payload("secret", 1_700_000_000, b"{}"),
+            sign_payload("other", 1_700_000_000, b"{}")
+        );
+        assert!(sign_payload("secret", 0, b"").starts_with("sha256="));
+
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(3), Duration::from_secs(20));
+        assert_eq!(retry_delay(40), Duration::from_secs(60 * 60));
+    }
+
+    #[tokio::test]
+    async fn destinations_must_be_public() {
+        for blocked in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(
+                !is_public_address(blocked.parse().unwrap()),
+                "{blocked} should be refused"
+            );
+        }
+        assert!(is_public_address("93.184.216.34".parse().unwrap()));
+        assert!(is_public_address("2606:2800:220:1::".parse().unwrap()));
+
+        let metadata = Url::parse("http://169.254.169.254/latest/meta-data").unwrap();
+        assert!(resolve_destination(&metadata, false).await.is_err());
+        let loopback = Url::parse("http://[::1]:8080/hook").unwrap();
+        assert!(resolve_destination(&loopback, false).await.is_err());
+        assert_eq!(
+            resolve_destination(&loopback, true).await.unwrap(),
+            vec!["[::1]:8080".parse().unwrap()]
+        );
+    }
+
+    #[test]
+    fn index_keeps_sessions_across_refreshes() {
+        let mut index = WebhookIndex::default();
+        let id = Uuid::new_v4();
+        index.replace([("pb-1".to_string(), id, vec!["controller.*".to_string()])]);
+        index.remember_session("s-1", "pb-1");
+        index.remember_session("s-2", "pb-2");
+
+        index.replace([("pb-1".to_string(), id, vec!["controller.*".to_string()])]);
+        assert_eq!(index.session_beach("s-1").as_deref(), Some("pb-1"));
+        assert_eq!(index.matching("pb-1", "controller.registered"), vec![id]);
+
+        index.forget_session("s-1");
+        assert_eq!(index.session_beach("s-1"), None);
+        index.forget_beach("pb-2");
+        assert_eq!(index.session_beach("s-2"), None);
+    }
+}
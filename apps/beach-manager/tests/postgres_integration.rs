@@ -21,7 +21,12 @@ use sqlx::postgres::PgPoolOptions;
 use tower::ServiceExt;
 use uuid::Uuid;
 
-use beach_manager::{routes::build_router, state::AppState};
+use beach_manager::{
+    audit,
+    routes::build_router,
+    state::{AppState, AssignmentConfig, AutomationRole, GroupGrant, GroupRole, StateError},
+    webhooks,
+};
 
 // Single end-to-end flow against a real Postgres database using the SQLx path.
 #[ignore]
@@ -332,3 +337,510 @@ async fn postgres_viewer_credentials_for_multi_beach_session() {
     assert_eq!(json_b["session_id"], origin_session_id);
     assert_eq!(json_b["private_beach_id"], private_beach_b.to_string());
 }
+
+#[ignore]
+#[tokio::test]
+async fn postgres_group_grants_follow_beach_membership() {
+    let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for this test");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .expect("connect to postgres");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    let state = AppState::with_db(pool.clone());
+
+    let mut accounts = Vec::new();
+    for _ in 0..3 {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO account (id, type, beach_gate_subject) VALUES ($1, 'human', $2)")
+            .bind(id)
+            .bind(format!("group-test-{id}"))
+            .execute(&pool)
+            .await
+            .expect("insert account");
+        accounts.push(id);
+    }
+    let (owner, member, outsider) = (accounts[0], accounts[1], accounts[2]);
+
+    let beach = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO private_beach (id, name, slug, owner_account_id) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(beach)
+    .bind("Group Beach")
+    .bind(format!("group-{beach}"))
+    .bind(owner)
+    .execute(&pool)
+    .await
+    .expect("insert private beach");
+    sqlx::query(
+        "INSERT INTO private_beach_membership (private_beach_id, account_id, role) VALUES ($1, $2, 'contributor')",
+    )
+    .bind(beach)
+    .bind(member)
+    .execute(&pool)
+    .await
+    .expect("insert membership");
+
+    let beach_id = beach.to_string();
+    let invalid = state
+        .create_beach_group(
+            &beach_id,
+            "wildcard",
+            None,
+            vec![GroupGrant {
+                scope: "*".into(),
+                session_tag: None,
+            }],
+            Some(owner),
+        )
+        .await;
+    assert!(matches!(invalid, Err(StateError::InvalidIdentifier(_))));
+
+    let group = state
+        .create_beach_group(
+            &beach_id,
+            "operators",
+            None,
+            vec![GroupGrant {
+                scope: "pb:control.write".into(),
+                session_tag: None,
+            }],
+            Some(owner),
+        )
+        .await
+        .expect("create group");
+
+    let outsider_add = state
+        .upsert_group_member(
+            &beach_id,
+            &group.id,
+            &outsider.to_string(),
+            GroupRole::Member,
+            Some(owner),
+        )
+        .await;
+    assert!(matches!(
+        outsider_add,
+        Err(StateError::InvalidIdentifier(_))
+    ));
+
+    state
+        .upsert_group_member(
+            &beach_id,
+            &group.id,
+            &member.to_string(),
+            GroupRole::Member,
+            Some(owner),
+        )
+        .await
+        .expect("add beach member to group");
+    assert_eq!(
+        state
+            .group_scopes_for_beach(member, &beach_id)
+            .await
+            .expect("scopes while a member"),
+        vec!["pb:control.write".to_string()]
+    );
+
+    sqlx::query(
+        "UPDATE private_beach_membership SET status = 'suspended' WHERE private_beach_id = $1 AND account_id = $2",
+    )
+    .bind(beach)
+    .bind(member)
+    .execute(&pool)
+    .await
+    .expect("suspend membership");
+    assert!(state
+        .group_scopes_for_beach(member, &beach_id)
+        .await
+        .expect("scopes after removal")
+        .is_empty());
+}
+
+#[ignore]
+#[tokio::test]
+async fn postgres_automation_renewals_are_silent() {
+    let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for this test");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .expect("connect to postgres");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    let state = AppState::with_db(pool.clone());
+
+    let controller = Uuid::new_v4();
+    sqlx::query("INSERT INTO account (id, type, beach_gate_subject) VALUES ($1, 'agent', $2)")
+        .bind(controller)
+        .bind(format!("automation-test-{controller}"))
+        .execute(&pool)
+        .await
+        .expect("insert account");
+
+    let private_beach_id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO private_beach (id, name, slug) VALUES ($1, $2, $3)")
+        .bind(Uuid::parse_str(&private_beach_id).unwrap())
+        .bind("Automation Beach")
+        .bind(format!("automation-{private_beach_id}"))
+        .execute(&pool)
+        .await
+        .expect("insert private beach");
+    let session_id = Uuid::new_v4().to_string();
+    state
+        .register_session(RegisterSessionRequest {
+            session_id: session_id.clone(),
+            private_beach_id: private_beach_id.clone(),
+            harness_type: HarnessType::TerminalShim,
+            capabilities: vec!["terminal_diff_v1".into()],
+            location_hint: None,
+            metadata: None,
+            version: "1.0.0".into(),
+            viewer_passcode: None,
+            transport_mode: Some(TransportMode::FastPath),
+        })
+        .await
+        .expect("register session");
+
+    let assignment = state
+        .create_automation_assignment(
+            &private_beach_id,
+            &controller.to_string(),
+            AutomationRole::Controller,
+            Some(&session_id),
+            AssignmentConfig::default(),
+            None,
+        )
+        .await
+        .expect("create assignment");
+    assert!(assignment.status[0].lease_expires_at_ms.is_some());
+
+    let session_uuid = Uuid::parse_str(&session_id).unwrap();
+    let lease_acquired = || async {
+        sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM controller_event e
+            JOIN session s ON s.id = e.session_id
+            WHERE s.origin_session_id = $1 AND e.event_type = 'lease_acquired'
+            "#,
+        )
+        .bind(session_uuid)
+        .fetch_one(&pool)
+        .await
+        .expect("count controller events")
+    };
+    // Registration and the assignment each acquired a lease.
+    let before = lease_acquired().await;
+    assert_eq!(before, 2);
+    for _ in 0..3 {
+        state.reconcile_automation_assignments().await;
+    }
+    assert_eq!(
+        lease_acquired().await,
+        before,
+        "renewals must not record lease_acquired"
+    );
+}
+
+type ReceivedWebhook = (axum::http::HeaderMap, axum::body::Bytes);
+
+/// Local webhook receiver answering every post with `status`.
+async fn spawn_webhook_receiver(
+    status: StatusCode,
+) -> (
+    String,
+    tokio::sync::mpsc::UnboundedReceiver<ReceivedWebhook>,
+) {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    let app = Router::new().route(
+        "/hook",
+        axum::routing::post(
+            move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                let _ = tx.send((headers, body));
+                async move { status }
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind webhook receiver");
+    let addr = listener.local_addr().expect("receiver addr");
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    (format!("http://{addr}/hook"), rx)
+}
+
+#[ignore]
+#[tokio::test]
+async fn postgres_webhooks_queue_and_deliver() {
+    let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for this test");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .expect("connect to postgres");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    // Receivers run on loopback, which only the permissive state may target.
+    let state = AppState::with_db(pool.clone()).with_private_webhook_targets(true);
+    let strict = AppState::with_db(pool.clone());
+
+    let private_beach_id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO private_beach (id, name, slug) VALUES ($1, $2, $3)")
+        .bind(Uuid::parse_str(&private_beach_id).unwrap())
+        .bind("Webhook Beach")
+        .bind(format!("webhooks-{private_beach_id}"))
+        .execute(&pool)
+        .await
+        .expect("insert private beach");
+    let session_id = Uuid::new_v4().to_string();
+    state
+        .register_session(RegisterSessionRequest {
+            session_id: session_id.clone(),
+            private_beach_id: private_beach_id.clone(),
+            harness_type: HarnessType::TerminalShim,
+            capabilities: vec!["terminal_diff_v1".into()],
+            location_hint: None,
+            metadata: None,
+            version: "1.0.0".into(),
+            viewer_passcode: None,
+            transport_mode: Some(TransportMode::FastPath),
+        })
+        .await
+        .expect("register session");
+
+    let events = vec!["controller.lease_acquired".to_string()];
+    let (ok_url, mut ok_rx) = spawn_webhook_receiver(StatusCode::NO_CONTENT).await;
+    let (failing_url, mut failing_rx) =
+        spawn_webhook_receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+
+    for url in [
+        ok_url.as_str(),
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.8/hook",
+    ] {
+        let refused = strict
+            .create_webhook(&private_beach_id, url, events.clone(), None, None, None)
+            .await;
+        assert!(
+            matches!(refused, Err(StateError::InvalidIdentifier(_))),
+            "{url} must be refused"
+        );
+    }
+
+    let ok = state
+        .create_webhook(&private_beach_id, &ok_url, events.clone(), None, None, None)
+        .await
+        .expect("create webhook");
+    let failing = state
+        .create_webhook(&private_beach_id, &failing_url, events, None, None, None)
+        .await
+        .expect("create failing webhook");
+
+    state
+        .acquire_controller(&session_id, None, None, None)
+        .await
+        .expect("acquire lease");
+
+    // Deliveries are inserted in the background, one per subscription.
+    let beach_uuid = Uuid::parse_str(&private_beach_id).unwrap();
+    let mut queued = 0;
+    for _ in 0..50 {
+        queued = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM webhook_delivery WHERE private_beach_id = $1",
+        )
+        .bind(beach_uuid)
+        .fetch_one(&pool)
+        .await
+        .expect("count deliveries");
+        if queued >= 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(queued, 2, "one delivery per matching subscription");
+
+    state.deliver_due_webhooks().await;
+
+    let (headers, body) = ok_rx.try_recv().expect("webhook delivered");
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    assert_eq!(
+        header(webhooks::WEBHOOK_EVENT_HEADER),
+        "controller.lease_acquired"
+    );
+    let timestamp: i64 = header(webhooks::WEBHOOK_TIMESTAMP_HEADER)
+        .parse()
+        .expect("timestamp header");
+    assert_eq!(
+        header(webhooks::WEBHOOK_SIGNATURE_HEADER),
+        webhooks::sign_payload(ok.secret.as_deref().unwrap(), timestamp, &body)
+    );
+    let envelope: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(envelope["session_id"], session_id.as_str());
+    assert!(failing_rx.try_recv().is_ok(), "failing receiver was called");
+
+    let failing_uuid = Uuid::parse_str(&failing.id).unwrap();
+    let (attempts, last_status): (i32, Option<i32>) = sqlx::query_as(
+        "SELECT attempts, last_status FROM webhook_delivery WHERE subscription_id = $1",
+    )
+    .bind(failing_uuid)
+    .fetch_one(&pool)
+    .await
+    .expect("failed delivery stays queued");
+    assert_eq!((attempts, last_status), (1, Some(500)));
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM webhook_delivery WHERE private_beach_id = $1")
+            .bind(beach_uuid)
+            .fetch_one(&pool)
+            .await
+            .expect("count deliveries");
+    assert_eq!(remaining, 1, "successful delivery is cleared");
+
+    // Destinations are checked again when delivering, not just on registration.
+    sqlx::query("UPDATE webhook_delivery SET next_attempt_at = NOW() WHERE subscription_id = $1")
+        .bind(failing_uuid)
+        .execute(&pool)
+        .await
+        .expect("make retry due");
+    strict.deliver_due_webhooks().await;
+    let (attempts, last_error): (i32, Option<String>) = sqlx::query_as(
+        "SELECT attempts, last_error FROM webhook_delivery WHERE subscription_id = $1",
+    )
+    .bind(failing_uuid)
+    .fetch_one(&pool)
+    .await
+    .expect("refused delivery stays queued");
+    assert_eq!(attempts, 2);
+    assert!(last_error.unwrap_or_default().contains("non-public"));
+    assert!(
+        failing_rx.try_recv().is_err(),
+        "refused delivery never connects"
+    );
+}
+
+#[ignore]
+#[tokio::test]
+async fn postgres_controller_audit_caps_the_merged_timeline() {
+    let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for this test");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .expect("connect to postgres");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("apply migrations");
+
+    let state = AppState::with_db(pool.clone());
+
+    let private_beach_id = Uuid::new_v4().to_string();
+    sqlx::query("INSERT INTO private_beach (id, name, slug) VALUES ($1, $2, $3)")
+        .bind(Uuid::parse_str(&private_beach_id).unwrap())
+        .bind("Audit Beach")
+        .bind(format!("audit-{private_beach_id}"))
+        .execute(&pool)
+        .await
+        .expect("insert private beach");
+    let session_id = Uuid::new_v4().to_string();
+    state
+        .register_session(RegisterSessionRequest {
+            session_id: session_id.clone(),
+            private_beach_id: private_beach_id.clone(),
+            harness_type: HarnessType::TerminalShim,
+            capabilities: vec!["terminal_diff_v1".into()],
+            location_hint: None,
+            metadata: None,
+            version: "1.0.0".into(),
+            viewer_passcode: None,
+            transport_mode: Some(TransportMode::FastPath),
+        })
+        .await
+        .expect("register session");
+
+    // One lease in the middle of a run of events, then enough events after it
+    // to overflow the export cap.
+    let start = chrono::Utc::now() - chrono::Duration::hours(1);
+    let internal_session: Uuid =
+        sqlx::query_scalar("SELECT id FROM session WHERE origin_session_id = $1")
+            .bind(Uuid::parse_str(&session_id).unwrap())
+            .fetch_one(&pool)
+            .await
+            .expect("session row");
+    sqlx::query(
+        r#"
+        INSERT INTO controller_event (session_id, event_type, occurred_at)
+        SELECT $1, 'actions_acked', $2::timestamptz + make_interval(secs => n / 1000.0)
+        FROM generate_series(0, $3) AS n
+        "#,
+    )
+    .bind(internal_session)
+    .bind(start)
+    .bind(audit::MAX_AUDIT_EXPORT_ROWS as i32)
+    .execute(&pool)
+    .await
+    .expect("insert events");
+    sqlx::query(
+        "INSERT INTO controller_lease (session_id, reason, issued_at, expires_at) VALUES ($1, 'audit-test', $2, $3)",
+    )
+    .bind(internal_session)
+    .bind(start + chrono::Duration::milliseconds(5))
+    .bind(start + chrono::Duration::minutes(5))
+    .execute(&pool)
+    .await
+    .expect("insert lease");
+
+    let filter = audit::AuditFilter {
+        from_ms: start.timestamp_millis(),
+        to_ms: chrono::Utc::now().timestamp_millis() + 60_000,
+        account_id: None,
+        session_id: None,
+    };
+    let (records, truncated) = state
+        .controller_audit_records(&private_beach_id, None, &filter)
+        .await
+        .expect("audit records");
+    assert!(truncated);
+    assert_eq!(records.len(), audit::MAX_AUDIT_EXPORT_ROWS);
+    assert!(records
+        .windows(2)
+        .all(|pair| pair[0].occurred_at_ms <= pair[1].occurred_at_ms));
+    let lease = records
+        .iter()
+        .position(|record| record.reason.as_deref() == Some("audit-test"))
+        .expect("lease interleaved with events");
+    assert!(
+        (5..=7).contains(&lease),
+        "lease sorted by issue time, found at {lease}"
+    );
+
+    let summary = state
+        .controller_audit_summary(&private_beach_id, None, &filter)
+        .await
+        .expect("audit summary");
+    assert!(summary.truncated);
+}
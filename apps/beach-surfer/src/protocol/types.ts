@@ -1,4 +1,4 @@
-export const PROTOCOL_VERSION = 2;
+export const PROTOCOL_VERSION = 3;
 export const FEATURE_CURSOR_SYNC = 1 << 0;
 
 export enum Lane {
@@ -4,8 +4,9 @@ import {
   decodeHostFrameBinary,
   encodeClientFrameBinary,
   encodeHostFrameBinary,
+  UnknownHostFrameError,
 } from './wire';
-import { Lane } from './types';
+import { Lane, PROTOCOL_VERSION } from './types';
 
 function roundTripHost(frame: Parameters<typeof encodeHostFrameBinary>[0]) {
   const encoded = encodeHostFrameBinary(frame);
@@ -145,4 +146,9 @@ describe('wire codec', () => {
       count: 64,
     });
   });
+
+  it('reports host frame kinds it does not know', () => {
+    const modesFrame = Uint8Array.of((PROTOCOL_VERSION << 5) | 12, 0, 0);
+    expect(() => decodeHostFrameBinary(modesFrame)).toThrow(UnknownHostFrameError);
+  });
 });
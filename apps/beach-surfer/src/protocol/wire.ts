@@ -58,6 +58,14 @@ function ensureByte(value: number, label: string): number {
   return value;
 }
 
+/** Thrown for host frame kinds added after this client; callers skip them. */
+export class UnknownHostFrameError extends RangeError {
+  constructor(readonly kind: number) {
+    super(`unknown host frame type: ${kind}`);
+    this.name = 'UnknownHostFrameError';
+  }
+}
+
 function writeHeader(kind: number, out: number[]): void {
   const version = PROTOCOL_VERSION & ((1 << VERSION_BITS) - 1);
   out.push(((version << 5) | (kind & TYPE_MASK)) & 0xff);
@@ -493,7 +501,7 @@ export function decodeHostFrameBinary(input: ArrayBuffer | Uint8Array): HostFram
     case HOST_KIND_SHUTDOWN:
       return { type: 'shutdown' };
     default:
-      throw new RangeError(`unknown host frame type: ${kind}`);
+      throw new UnknownHostFrameError(kind);
   }
 }
 
@@ -1,4 +1,4 @@
-import { decodeHostFrameBinary, encodeClientFrameBinary } from '../protocol/wire';
+import { UnknownHostFrameError, decodeHostFrameBinary, encodeClientFrameBinary } from '../protocol/wire';
 import type { ClientFrame, HostFrame } from '../protocol/types';
 import { WebRtcTransport, type SecureTransportSummary } from './webrtc';
 
@@ -52,6 +52,10 @@ export class DataChannelTerminalTransport extends EventTarget implements Termina
           }
           this.dispatchEvent(new CustomEvent<HostFrame>('frame', { detail: frame }));
         } catch (error) {
+          if (error instanceof UnknownHostFrameError) {
+            this.log(`skipping unknown host frame (${error.kind})`);
+            return;
+          }
           this.log(`failed to decode host frame: ${error instanceof Error ? error.message : String(error)}`);
           const err = new Event('error');
           Object.assign(err, { error });
@@ -0,0 +1,453 @@
+//! Render packed terminal rows into styled, text-based exports (HTML, ANSI, SVG).
+//!
+//! Every cell's [`StyleId`](super::StyleId) is resolved through the [`StyleTable`]
+//! so the output carries real colors and attributes instead of raw style ids.
+//!
+//! ```rust
+//! # use beach_client_core::cache::terminal::packed::{StyleTable, Style, pack_cell};
+//! # use beach_client_core::cache::terminal::export::{ExportFormat, StyledRow, export_rows};
+//! let table = StyleTable::new();
+//! let id = table.ensure_id(Style::default());
+//! let cells = [u64::from(pack_cell('h', id)), u64::from(pack_cell('i', id))];
+//! let row = StyledRow::from_packed(0, &cells, &table);
+//! assert_eq!(export_rows(ExportFormat::Ansi, &[row]), "hi\n");
+//! ```
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use super::packed::{PackedCell, Style, StyleTable, unpack_cell};
+
+const ATTR_BOLD: u8 = 1 << 0;
+const ATTR_ITALIC: u8 = 1 << 1;
+const ATTR_UNDERLINE: u8 = 1 << 2;
+const ATTR_STRIKETHROUGH: u8 = 1 << 3;
+const ATTR_REVERSE: u8 = 1 << 4;
+const ATTR_BLINK: u8 = 1 << 5;
+const ATTR_DIM: u8 = 1 << 6;
+const ATTR_HIDDEN: u8 = 1 << 7;
+
+const DEFAULT_FG: Rgb = Rgb(0xe5, 0xe5, 0xe5);
+const DEFAULT_BG: Rgb = Rgb(0x00, 0x00, 0x00);
+
+const SVG_CELL_WIDTH: usize = 9;
+const SVG_CELL_HEIGHT: usize = 18;
+const SVG_FONT_SIZE: usize = 15;
+
+/// Output formats supported by [`export_rows`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Html,
+    Ansi,
+    Svg,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Html, ExportFormat::Ansi, ExportFormat::Svg];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "html" => Some(ExportFormat::Html),
+            "ansi" => Some(ExportFormat::Ansi),
+            "svg" => Some(ExportFormat::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Ansi => "ansi",
+            ExportFormat::Svg => "svg",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "text/html",
+            ExportFormat::Ansi => "text/x-ansi",
+            ExportFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// A single row of cells with their styles already resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyledRow {
+    pub row: u64,
+    pub cells: Vec<(char, Style)>,
+}
+
+impl StyledRow {
+    pub fn from_packed(row: u64, cells: &[u64], style_table: &StyleTable) -> Self {
+        let cells = cells
+            .iter()
+            .map(|raw| {
+                let (ch, style_id) = unpack_cell(PackedCell::from(*raw));
+                (ch, style_table.get(style_id).unwrap_or_default())
+            })
+            .collect();
+        Self { row, cells }
+    }
+
+    /// Cells with trailing default-styled blanks removed.
+    fn trimmed(&self) -> &[(char, Style)] {
+        let default = Style::default();
+        let mut end = self.cells.len();
+        while end > 0 {
+            let (ch, style) = self.cells[end - 1];
+            if (ch == ' ' || ch == '\0') && style == default {
+                end -= 1;
+            } else {
+                break;
+            }
+        }
+        &self.cells[..end]
+    }
+
+    /// Groups consecutive cells sharing a style into runs.
+    fn runs(&self) -> Vec<(Style, String)> {
+        let mut runs: Vec<(Style, String)> = Vec::new();
+        for (ch, style) in self.trimmed() {
+            let ch = if *ch == '\0' { ' ' } else { *ch };
+            match runs.last_mut() {
+                Some((current, text)) if current == style => text.push(ch),
+                _ => runs.push((*style, ch.to_string())),
+            }
+        }
+        runs
+    }
+}
+
+/// Renders `rows` in the requested `format`.
+pub fn export_rows(format: ExportFormat, rows: &[StyledRow]) -> String {
+    match format {
+        ExportFormat::Html => export_html(rows),
+        ExportFormat::Ansi => export_ansi(rows),
+        ExportFormat::Svg => export_svg(rows),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Rgb(u8, u8, u8);
+
+impl Rgb {
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Foreground and background after applying reverse/hidden attributes.
+fn resolve_colors(style: &Style) -> (Rgb, Rgb) {
+    let mut fg = resolve_color(style.fg).unwrap_or(DEFAULT_FG);
+    let mut bg = resolve_color(style.bg).unwrap_or(DEFAULT_BG);
+    if style.attrs & ATTR_REVERSE != 0 {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    if style.attrs & ATTR_HIDDEN != 0 {
+        fg = bg;
+    }
+    (fg, bg)
+}
+
+fn resolve_color(packed: u32) -> Option<Rgb> {
+    match (packed >> 24) as u8 {
+        1 => Some(indexed_rgb((packed & 0xFF) as u8)),
+        2 => Some(Rgb(
+            ((packed >> 16) & 0xFF) as u8,
+            ((packed >> 8) & 0xFF) as u8,
+            (packed & 0xFF) as u8,
+        )),
+        _ => None,
+    }
+}
+
+/// Standard xterm 256-color palette.
+fn indexed_rgb(idx: u8) -> Rgb {
+    const BASE: [Rgb; 16] = [
+        Rgb(0x00, 0x00, 0x00),
+        Rgb(0xcd, 0x00, 0x00),
+        Rgb(0x00, 0xcd, 0x00),
+        Rgb(0xcd, 0xcd, 0x00),
+        Rgb(0x00, 0x00, 0xee),
+        Rgb(0xcd, 0x00, 0xcd),
+        Rgb(0x00, 0xcd, 0xcd),
+        Rgb(0xe5, 0xe5, 0xe5),
+        Rgb(0x7f, 0x7f, 0x7f),
+        Rgb(0xff, 0x00, 0x00),
+        Rgb(0x00, 0xff, 0x00),
+        Rgb(0xff, 0xff, 0x00),
+        Rgb(0x5c, 0x5c, 0xff),
+        Rgb(0xff, 0x00, 0xff),
+        Rgb(0x00, 0xff, 0xff),
+        Rgb(0xff, 0xff, 0xff),
+    ];
+    match idx {
+        0..=15 => BASE[idx as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = idx - 16;
+            Rgb(level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (idx - 232) * 10;
+            Rgb(gray, gray, gray)
+        }
+    }
+}
+
+fn escape_xml(text: &str, out: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+fn text_decoration(attrs: u8) -> Option<&'static str> {
+    match (attrs & ATTR_UNDERLINE != 0, attrs & ATTR_STRIKETHROUGH != 0) {
+        (true, true) => Some("underline line-through"),
+        (true, false) => Some("underline"),
+        (false, true) => Some("line-through"),
+        (false, false) => None,
+    }
+}
+
+fn export_html(rows: &[StyledRow]) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<pre class=\"beach-export\" style=\"background:{};color:{};font-family:monospace;line-height:1.2;padding:8px\">",
+        DEFAULT_BG.hex(),
+        DEFAULT_FG.hex()
+    );
+    for (idx, row) in rows.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        for (style, text) in row.runs() {
+            if style == Style::default() {
+                escape_xml(&text, &mut out);
+                continue;
+            }
+            let (fg, bg) = resolve_colors(&style);
+            let mut css = format!("color:{};background:{}", fg.hex(), bg.hex());
+            if style.attrs & ATTR_BOLD != 0 {
+                css.push_str(";font-weight:bold");
+            }
+            if style.attrs & ATTR_ITALIC != 0 {
+                css.push_str(";font-style:italic");
+            }
+            if style.attrs & ATTR_DIM != 0 {
+                css.push_str(";opacity:0.6");
+            }
+            if let Some(decoration) = text_decoration(style.attrs) {
+                let _ = write!(css, ";text-decoration:{decoration}");
+            }
+            let _ = write!(out, "<span style=\"{css}\">");
+            escape_xml(&text, &mut out);
+            out.push_str("</span>");
+        }
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+fn sgr_color(packed: u32, background: bool, params: &mut Vec<String>) {
+    let base = if background { 40 } else { 30 };
+    match (packed >> 24) as u8 {
+        1 => {
+            let idx = (packed & 0xFF) as u8;
+            match idx {
+                0..=7 => params.push((base + idx as u32).to_string()),
+                8..=15 => params.push((base + 60 + (idx - 8) as u32).to_string()),
+                _ => params.push(format!("{};5;{idx}", base + 8)),
+            }
+        }
+        2 => params.push(format!(
+            "{};2;{};{};{}",
+            base + 8,
+            (packed >> 16) & 0xFF,
+            (packed >> 8) & 0xFF,
+            packed & 0xFF
+        )),
+        _ => {}
+    }
+}
+
+fn sgr_for(style: &Style) -> String {
+    let mut params = vec!["0".to_string()];
+    let attrs = [
+        (ATTR_BOLD, "1"),
+        (ATTR_DIM, "2"),
+        (ATTR_ITALIC, "3"),
+        (ATTR_UNDERLINE, "4"),
+        (ATTR_BLINK, "5"),
+        (ATTR_REVERSE, "7"),
+        (ATTR_HIDDEN, "8"),
+        (ATTR_STRIKETHROUGH, "9"),
+    ];
+    for (bit, code) in attrs {
+        if style.attrs & bit != 0 {
+            params.push(code.to_string());
+        }
+    }
+    sgr_color(style.fg, false, &mut params);
+    sgr_color(style.bg, true, &mut params);
+    format!("\x1b[{}m", params.join(";"))
+}
+
+fn export_ansi(rows: &[StyledRow]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        let mut styled = false;
+        for (style, text) in row.runs() {
+            if style == Style::default() {
+                if styled {
+                    out.push_str("\x1b[0m");
+                    styled = false;
+                }
+            } else {
+                out.push_str(&sgr_for(&style));
+                styled = true;
+            }
+            out.push_str(&text);
+        }
+        if styled {
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn export_svg(rows: &[StyledRow]) -> String {
+    let cols = rows
+        .iter()
+        .map(|row| row.cells.len())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let width = cols * SVG_CELL_WIDTH;
+    let height = rows.len().max(1) * SVG_CELL_HEIGHT;
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{SVG_FONT_SIZE}\">"
+    );
+    let _ = write!(
+        out,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        DEFAULT_BG.hex()
+    );
+    for (line, row) in rows.iter().enumerate() {
+        let y = line * SVG_CELL_HEIGHT;
+        let baseline = y + SVG_CELL_HEIGHT - (SVG_CELL_HEIGHT - SVG_FONT_SIZE) / 2 - 3;
+        let mut col = 0usize;
+        for (style, text) in row.runs() {
+            let len = text.chars().count();
+            let x = col * SVG_CELL_WIDTH;
+            col += len;
+            let (fg, bg) = resolve_colors(&style);
+            if bg != DEFAULT_BG {
+                let _ = write!(
+                    out,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{SVG_CELL_HEIGHT}\" fill=\"{}\"/>",
+                    len * SVG_CELL_WIDTH,
+                    bg.hex()
+                );
+            }
+            if text.trim().is_empty() {
+                continue;
+            }
+            let _ = write!(
+                out,
+                "<text x=\"{x}\" y=\"{baseline}\" fill=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" xml:space=\"preserve\"",
+                fg.hex(),
+                len * SVG_CELL_WIDTH
+            );
+            if style.attrs & ATTR_BOLD != 0 {
+                out.push_str(" font-weight=\"bold\"");
+            }
+            if style.attrs & ATTR_ITALIC != 0 {
+                out.push_str(" font-style=\"italic\"");
+            }
+            if style.attrs & ATTR_DIM != 0 {
+                out.push_str(" opacity=\"0.6\"");
+            }
+            if let Some(decoration) = text_decoration(style.attrs) {
+                let _ = write!(out, " text-decoration=\"{decoration}\"");
+            }
+            out.push('>');
+            escape_xml(&text, &mut out);
+            out.push_str("</text>");
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::terminal::packed::{pack_cell, pack_color_indexed, pack_color_rgb};
+
+    fn row_from(text: &str, style: Style, table: &StyleTable) -> StyledRow {
+        let id = table.ensure_id(style);
+        let cells: Vec<u64> = text.chars().map(|ch| pack_cell(ch, id).into()).collect();
+        StyledRow::from_packed(0, &cells, table)
+    }
+
+    #[test]
+    fn ansi_export_emits_sgr_for_styled_runs() {
+        let table = StyleTable::new();
+        let style = Style {
+            fg: pack_color_indexed(1),
+            bg: pack_color_rgb(1, 2, 3),
+            attrs: ATTR_BOLD,
+        };
+        let row = row_from("err", style, &table);
+        assert_eq!(
+            export_rows(ExportFormat::Ansi, &[row]),
+            "\x1b[0;1;31;48;2;1;2;3merr\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn html_export_escapes_and_resolves_colors() {
+        let table = StyleTable::new();
+        let style = Style {
+            fg: pack_color_indexed(196),
+            ..Style::default()
+        };
+        let row = row_from("<a&b>", style, &table);
+        let html = export_rows(ExportFormat::Html, &[row]);
+        assert!(html.contains("color:#ff0000"));
+        assert!(html.contains("&lt;a&amp;b&gt;"));
+    }
+
+    #[test]
+    fn svg_export_trims_trailing_blanks() {
+        let table = StyleTable::new();
+        let row = row_from("ok   ", Style::default(), &table);
+        let svg = export_rows(ExportFormat::Svg, &[row]);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">ok</text>"));
+    }
+
+    #[test]
+    fn reverse_swaps_colors() {
+        let style = Style {
+            attrs: ATTR_REVERSE,
+            ..Style::default()
+        };
+        assert_eq!(resolve_colors(&style), (DEFAULT_BG, DEFAULT_FG));
+    }
+}
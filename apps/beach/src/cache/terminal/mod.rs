@@ -1,5 +1,7 @@
 pub mod cache;
+pub mod export;
 pub mod packed;
+pub mod search;
 
 pub use cache::{TerminalCellSnapshot, TerminalGrid};
 pub use packed::{
@@ -0,0 +1,203 @@
+//! Literal text search over a [`TerminalGrid`], including retained history.
+//!
+//! Runs on the host so clients can locate matches in scrollback without
+//! backfilling every row first. Columns are reported as cell indices, which
+//! line up with client-side row text because each cell holds one `char`.
+
+use super::cache::TerminalGrid;
+use super::packed::{PackedCell, unpack_cell};
+
+/// Upper bound on matches returned from a single search.
+pub const MAX_SEARCH_RESULTS: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchQuery {
+    pub pattern: String,
+    pub case_sensitive: bool,
+    pub direction: SearchDirection,
+    /// Exclusive starting position `(row, col)`. Forward searches begin at the
+    /// oldest retained row and backward searches at the newest when unset.
+    pub origin: Option<(u64, usize)>,
+    pub max_results: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub row: u64,
+    pub col: usize,
+    pub len: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchOutcome {
+    pub matches: Vec<SearchMatch>,
+    /// `true` when more matches exist beyond `max_results`.
+    pub truncated: bool,
+}
+
+pub fn search_grid(grid: &TerminalGrid, query: &SearchQuery) -> SearchOutcome {
+    let mut outcome = SearchOutcome::default();
+    let needle: Vec<char> = normalize(&query.pattern, query.case_sensitive);
+    let limit = query.max_results.clamp(1, MAX_SEARCH_RESULTS);
+    let (Some(first_row), Some(last_row)) = (grid.first_row_id(), grid.last_row_id()) else {
+        return outcome;
+    };
+    if needle.is_empty() || grid.cols() == 0 {
+        return outcome;
+    }
+
+    let mut buffer = vec![0u64; grid.cols()];
+    let mut scan_row = |absolute: u64, outcome: &mut SearchOutcome| -> bool {
+        let Some(index) = grid.index_of_row(absolute) else {
+            return true;
+        };
+        if grid.snapshot_row_into(index, &mut buffer).is_err() {
+            return true;
+        }
+        let haystack: Vec<char> = buffer
+            .iter()
+            .map(|raw| unpack_cell(PackedCell::from(*raw)).0)
+            .map(|ch| fold(ch, query.case_sensitive))
+            .collect();
+        let mut cols = find_all(&haystack, &needle);
+        if let Some((origin_row, origin_col)) = query.origin {
+            if origin_row == absolute {
+                cols.retain(|col| match query.direction {
+                    SearchDirection::Forward => *col > origin_col,
+                    SearchDirection::Backward => *col < origin_col,
+                });
+            }
+        }
+        if query.direction == SearchDirection::Backward {
+            cols.reverse();
+        }
+        for col in cols {
+            if outcome.matches.len() >= limit {
+                outcome.truncated = true;
+                return false;
+            }
+            outcome.matches.push(SearchMatch {
+                row: absolute,
+                col,
+                len: needle.len(),
+            });
+        }
+        true
+    };
+
+    match query.direction {
+        SearchDirection::Forward => {
+            let start = query
+                .origin
+                .map_or(first_row, |(row, _)| row.max(first_row));
+            for absolute in start..=last_row {
+                if !scan_row(absolute, &mut outcome) {
+                    break;
+                }
+            }
+        }
+        SearchDirection::Backward => {
+            let start = query.origin.map_or(last_row, |(row, _)| row.min(last_row));
+            for absolute in (first_row..=start).rev() {
+                if !scan_row(absolute, &mut outcome) {
+                    break;
+                }
+            }
+        }
+    }
+    outcome
+}
+
+fn fold(ch: char, case_sensitive: bool) -> char {
+    if case_sensitive {
+        ch
+    } else {
+        ch.to_lowercase().next().unwrap_or(ch)
+    }
+}
+
+fn normalize(pattern: &str, case_sensitive: bool) -> Vec<char> {
+    pattern.chars().map(|ch| fold(ch, case_sensitive)).collect()
+}
+
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    if needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle)
+        .map(|(col, _)| col)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::terminal::StyleId;
+
+    fn grid_with_rows(lines: &[&str]) -> TerminalGrid {
+        let grid = TerminalGrid::new(lines.len(), 16);
+        for (row, line) in lines.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let cell = TerminalGrid::pack_char_with_style(ch, StyleId::DEFAULT);
+                grid.write_packed_cell_if_newer(row, col, 1, cell).unwrap();
+            }
+        }
+        grid
+    }
+
+    fn query(pattern: &str, direction: SearchDirection) -> SearchQuery {
+        SearchQuery {
+            pattern: pattern.to_string(),
+            case_sensitive: false,
+            direction,
+            origin: None,
+            max_results: 10,
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn forward_search_returns_matches_in_order() {
+        let grid = grid_with_rows(&["error one", "ok", "ERROR two error"]);
+        let outcome = search_grid(&grid, &query("error", SearchDirection::Forward));
+        let hits: Vec<(u64, usize)> = outcome.matches.iter().map(|m| (m.row, m.col)).collect();
+        assert_eq!(hits, vec![(0, 0), (2, 0), (2, 10)]);
+        assert!(!outcome.truncated);
+    }
+
+    #[test_timeout::timeout]
+    fn backward_search_respects_origin_and_limit() {
+        let grid = grid_with_rows(&["foo", "foo foo", "bar"]);
+        let mut request = query("foo", SearchDirection::Backward);
+        request.origin = Some((1, 4));
+        request.max_results = 1;
+        let outcome = search_grid(&grid, &request);
+        assert_eq!(
+            outcome.matches,
+            vec![SearchMatch {
+                row: 1,
+                col: 0,
+                len: 3
+            }]
+        );
+        assert!(outcome.truncated);
+    }
+
+    #[test_timeout::timeout]
+    fn case_sensitive_search_skips_mismatched_case() {
+        let grid = grid_with_rows(&["Panic panic"]);
+        let mut request = query("Panic", SearchDirection::Forward);
+        request.case_sensitive = true;
+        let outcome = search_grid(&grid, &request);
+        assert_eq!(outcome.matches.len(), 1);
+        assert_eq!(outcome.matches[0].col, 0);
+    }
+}
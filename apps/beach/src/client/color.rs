@@ -0,0 +1,646 @@
+//! Client-side color handling. Host cells carry whatever colors the remote
+//! program chose, usually 256-color or truecolor. This module detects what the
+//! local terminal can display, remaps the ANSI colors through the user's theme,
+//! and quantizes the rest so they don't come out garbled.
+
+use crate::terminal::config::{ThemeConfig, load_user_config};
+use clap::ValueEnum;
+use ratatui::style::{Color, Style};
+use std::env;
+use std::path::PathBuf;
+use tracing::debug;
+
+type Rgb = (u8, u8, u8);
+
+/// Color depth of the local terminal, ordered from least to most capable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ColorLevel {
+    /// No colors; only attributes such as bold and reverse.
+    #[value(name = "none")]
+    Mono,
+    /// The 16 ANSI colors (Linux console, `screen`).
+    #[value(name = "16")]
+    Ansi16,
+    /// The xterm 256-color palette.
+    #[value(name = "256")]
+    Ansi256,
+    /// 24-bit RGB.
+    #[value(name = "truecolor", alias = "24bit")]
+    TrueColor,
+}
+
+impl ColorLevel {
+    /// Detects local support from `NO_COLOR`, `COLORTERM`, and the terminfo
+    /// `colors` capability for `$TERM`.
+    pub fn detect() -> Self {
+        detect_level(
+            env::var("NO_COLOR").ok().as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+            terminfo_max_colors,
+        )
+    }
+}
+
+fn detect_level(
+    no_color: Option<&str>,
+    colorterm: Option<&str>,
+    term: Option<&str>,
+    max_colors: impl Fn(&str) -> Option<i32>,
+) -> ColorLevel {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return ColorLevel::Mono;
+    }
+    if colorterm.is_some_and(|value| {
+        value.eq_ignore_ascii_case("truecolor") || value.eq_ignore_ascii_case("24bit")
+    }) {
+        return ColorLevel::TrueColor;
+    }
+    // Without TERM (Windows consoles) there is nothing to go on; pass colors
+    // through as before.
+    let Some(term) = term.filter(|term| !term.is_empty()) else {
+        return ColorLevel::TrueColor;
+    };
+    if term == "dumb" {
+        return ColorLevel::Mono;
+    }
+    if term.ends_with("-direct") || term.ends_with("-truecolor") {
+        return ColorLevel::TrueColor;
+    }
+    if let Some(colors) = max_colors(term) {
+        return match colors {
+            n if n >= 1 << 24 => ColorLevel::TrueColor,
+            n if n >= 256 => ColorLevel::Ansi256,
+            n if n >= 8 => ColorLevel::Ansi16,
+            _ => ColorLevel::Mono,
+        };
+    }
+    if term.contains("256color") {
+        ColorLevel::Ansi256
+    } else if ["linux", "screen", "vt", "ansi", "cons", "rxvt"]
+        .iter()
+        .any(|prefix| term.starts_with(prefix))
+    {
+        ColorLevel::Ansi16
+    } else {
+        ColorLevel::TrueColor
+    }
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(base) = directories::BaseDirs::new() {
+        dirs.push(base.home_dir().join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        for entry in list.split(':') {
+            // An empty entry stands for the system default location.
+            let entry = if entry.is_empty() {
+                "/usr/share/terminfo"
+            } else {
+                entry
+            };
+            dirs.push(PathBuf::from(entry));
+        }
+    }
+    for dir in [
+        "/etc/terminfo",
+        "/lib/terminfo",
+        "/usr/share/terminfo",
+        "/usr/lib/terminfo",
+    ] {
+        dirs.push(PathBuf::from(dir));
+    }
+    dirs
+}
+
+fn terminfo_max_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let data = terminfo_dirs().into_iter().find_map(|dir| {
+        // ncurses files entries by first letter; macOS uses its hex code.
+        [
+            dir.join(first.to_string()).join(term),
+            dir.join(format!("{:x}", first as u32)).join(term),
+        ]
+        .into_iter()
+        .find_map(|path| std::fs::read(path).ok())
+    })?;
+    parse_terminfo_max_colors(&data)
+}
+
+/// Index of `max_colors` in the compiled terminfo numbers section.
+const TERMINFO_MAX_COLORS: usize = 13;
+
+/// Reads `max_colors` from a compiled terminfo entry, in either the legacy
+/// 16-bit or the ncurses 6.1 32-bit number format.
+fn parse_terminfo_max_colors(data: &[u8]) -> Option<i32> {
+    let header = |index: usize| -> Option<usize> {
+        let bytes = data.get(index * 2..index * 2 + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let number_width = match header(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names_len = header(1)?;
+    let bools_len = header(2)?;
+    let numbers_len = header(3)?;
+    if numbers_len <= TERMINFO_MAX_COLORS {
+        return None;
+    }
+    let mut offset = 12 + names_len + bools_len;
+    // The numbers section starts on an even byte.
+    offset += offset % 2;
+    offset += TERMINFO_MAX_COLORS * number_width;
+    let bytes = data.get(offset..offset + number_width)?;
+    let value = if number_width == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i32
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+    (value >= 0).then_some(value)
+}
+
+/// xterm's default values for the 16 ANSI colors.
+const XTERM_ANSI: [Rgb; 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel values of the 6x6x6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn indexed_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => XTERM_ANSI[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[((cube / 6) % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn distance(a: Rgb, b: Rgb) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+/// Nearest entry in the 256-color palette, choosing between the color cube
+/// and the grayscale ramp the same way tmux does.
+fn nearest_256(rgb: Rgb) -> u8 {
+    let cube_index = |value: u8| -> u8 {
+        match value {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (value - 35) / 40,
+        }
+    };
+    let (r, g, b) = (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+    let cube = (
+        CUBE_LEVELS[r as usize],
+        CUBE_LEVELS[g as usize],
+        CUBE_LEVELS[b as usize],
+    );
+    if cube == rgb {
+        return 16 + 36 * r + 6 * g + b;
+    }
+    let average = ((rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3) as u8;
+    let gray_index = if average > 238 {
+        23
+    } else {
+        average.saturating_sub(3) / 10
+    };
+    let gray = 8 + gray_index * 10;
+    if distance((gray, gray, gray), rgb) < distance(cube, rgb) {
+        232 + gray_index
+    } else {
+        16 + 36 * r + 6 * g + b
+    }
+}
+
+fn nearest_ansi16(rgb: Rgb) -> u8 {
+    (0..16u8)
+        .min_by_key(|index| distance(XTERM_ANSI[*index as usize], rgb))
+        .unwrap_or(7)
+}
+
+/// Named colors so 16-color terminals receive SGR 30-37/90-97 rather than
+/// the 256-color form.
+fn ansi_color(index: u8) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+const fn hex(value: u32) -> Option<Rgb> {
+    Some(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+const SOLARIZED_PALETTE: [Option<Rgb>; 16] = [
+    hex(0x073642),
+    hex(0xdc322f),
+    hex(0x859900),
+    hex(0xb58900),
+    hex(0x268bd2),
+    hex(0xd33682),
+    hex(0x2aa198),
+    hex(0xeee8d5),
+    hex(0x002b36),
+    hex(0xcb4b16),
+    hex(0x586e75),
+    hex(0x657b83),
+    hex(0x839496),
+    hex(0x6c71c4),
+    hex(0x93a1a1),
+    hex(0xfdf6e3),
+];
+
+const GRUVBOX_DARK_PALETTE: [Option<Rgb>; 16] = [
+    hex(0x282828),
+    hex(0xcc241d),
+    hex(0x98971a),
+    hex(0xd79921),
+    hex(0x458588),
+    hex(0xb16286),
+    hex(0x689d6a),
+    hex(0xa89984),
+    hex(0x928374),
+    hex(0xfb4934),
+    hex(0xb8bb26),
+    hex(0xfabd2f),
+    hex(0x83a598),
+    hex(0xd3869b),
+    hex(0x8ec07c),
+    hex(0xebdbb2),
+];
+
+/// Replacement values for the 16 ANSI colors and the default foreground and
+/// background. Unset entries keep the local terminal's own colors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    palette: [Option<Rgb>; 16],
+    foreground: Option<Rgb>,
+    background: Option<Rgb>,
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 4] = [
+        "default",
+        "solarized-dark",
+        "solarized-light",
+        "gruvbox-dark",
+    ];
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "solarized-dark" => Theme {
+                palette: SOLARIZED_PALETTE,
+                foreground: hex(0x839496),
+                background: hex(0x002b36),
+            },
+            "solarized-light" => Theme {
+                palette: SOLARIZED_PALETTE,
+                foreground: hex(0x657b83),
+                background: hex(0xfdf6e3),
+            },
+            "gruvbox-dark" => Theme {
+                palette: GRUVBOX_DARK_PALETTE,
+                foreground: hex(0xebdbb2),
+                background: hex(0x282828),
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Loads `[client.theme]` from `~/.beach/config`.
+    pub fn load() -> Self {
+        load_user_config()
+            .and_then(|config| config.client)
+            .and_then(|client| client.theme)
+            .map(|config| Self::from_config(&config))
+            .unwrap_or_default()
+    }
+
+    fn from_config(config: &ThemeConfig) -> Self {
+        let mut theme = match config.name.as_deref() {
+            Some(name) => Self::builtin(name).unwrap_or_else(|| {
+                debug!(
+                    target = "client::config",
+                    name,
+                    available = ?Theme::BUILTIN,
+                    "unknown theme, using default"
+                );
+                Theme::default()
+            }),
+            None => Theme::default(),
+        };
+        for (slot, value) in theme.palette.iter_mut().zip(&config.palette) {
+            if value.is_empty() {
+                continue;
+            }
+            match parse_hex_color(value) {
+                Some(rgb) => *slot = Some(rgb),
+                None => debug!(target = "client::config", value = %value, "invalid palette color"),
+            }
+        }
+        if let Some(value) = config.foreground.as_deref() {
+            theme.foreground = parse_hex_color(value).or(theme.foreground);
+        }
+        if let Some(value) = config.background.as_deref() {
+            theme.background = parse_hex_color(value).or(theme.background);
+        }
+        theme
+    }
+}
+
+/// Parses `#rrggbb` or `#rgb`; the leading `#` is optional.
+fn parse_hex_color(value: &str) -> Option<Rgb> {
+    let digits = value.trim().trim_start_matches('#');
+    if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&digits[range], 16).ok();
+    match digits.len() {
+        6 => Some((channel(0..2)?, channel(2..4)?, channel(4..6)?)),
+        3 => Some((
+            channel(0..1)? * 17,
+            channel(1..2)? * 17,
+            channel(2..3)? * 17,
+        )),
+        _ => None,
+    }
+}
+
+/// Maps host colors to what the local terminal should be sent.
+#[derive(Clone, Debug)]
+pub struct ColorMapper {
+    level: ColorLevel,
+    theme: Theme,
+}
+
+impl Default for ColorMapper {
+    fn default() -> Self {
+        Self::new(ColorLevel::TrueColor, Theme::default())
+    }
+}
+
+impl ColorMapper {
+    pub fn new(level: ColorLevel, theme: Theme) -> Self {
+        Self { level, theme }
+    }
+
+    /// Uses `level` when given (the `--colors` flag), otherwise detects it,
+    /// and applies the configured theme.
+    pub fn load(level: Option<ColorLevel>) -> Self {
+        let level = level.unwrap_or_else(ColorLevel::detect);
+        debug!(target = "client::render", ?level, "client color level");
+        Self::new(level, Theme::load())
+    }
+
+    /// Maps a host color: themed ANSI entries first, then quantized to the
+    /// local color level.
+    pub fn map(&self, color: Color) -> Color {
+        let color = match color {
+            // A 16-color terminal already applies its own palette.
+            Color::Indexed(index) if index < 16 && self.level > ColorLevel::Ansi16 => {
+                self.theme.palette[index as usize]
+                    .map(|(r, g, b)| Color::Rgb(r, g, b))
+                    .unwrap_or(color)
+            }
+            other => other,
+        };
+        self.downgrade(color)
+    }
+
+    fn downgrade(&self, color: Color) -> Color {
+        match (self.level, color) {
+            (ColorLevel::Mono, _) => Color::Reset,
+            (ColorLevel::TrueColor, color) => color,
+            (ColorLevel::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (ColorLevel::Ansi16, Color::Rgb(r, g, b)) => ansi_color(nearest_ansi16((r, g, b))),
+            (ColorLevel::Ansi16, Color::Indexed(index)) if index < 16 => ansi_color(index),
+            (ColorLevel::Ansi16, Color::Indexed(index)) => {
+                ansi_color(nearest_ansi16(indexed_rgb(index)))
+            }
+            (_, color) => color,
+        }
+    }
+
+    /// Foreground for a cell; `None` means the terminal default, which the
+    /// theme may replace.
+    pub fn foreground(&self, color: Option<Color>) -> Option<Color> {
+        self.resolve(color, self.theme.foreground)
+    }
+
+    pub fn background(&self, color: Option<Color>) -> Option<Color> {
+        self.resolve(color, self.theme.background)
+    }
+
+    fn resolve(&self, color: Option<Color>, default: Option<Rgb>) -> Option<Color> {
+        if self.level == ColorLevel::Mono {
+            return None;
+        }
+        match color {
+            Some(color) => Some(self.map(color)),
+            None => default.map(|(r, g, b)| self.downgrade(Color::Rgb(r, g, b))),
+        }
+    }
+
+    /// Style for cells the host never styled, such as blank rows.
+    pub fn base_style(&self) -> Style {
+        let mut style = Style::default();
+        if let Some(color) = self.foreground(None) {
+            style = style.fg(color);
+        }
+        if let Some(color) = self.background(None) {
+            style = style.bg(color);
+        }
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::timeout]
+    fn detection_prefers_no_color_then_colorterm_then_terminfo() {
+        let terminfo = |colors: i32| move |_: &str| Some(colors);
+        let missing = |_: &str| None;
+        assert_eq!(
+            detect_level(Some("1"), Some("truecolor"), Some("xterm"), missing),
+            ColorLevel::Mono
+        );
+        assert_eq!(
+            detect_level(None, Some("24bit"), Some("linux"), terminfo(8)),
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            detect_level(None, None, Some("xterm-256color"), terminfo(256)),
+            ColorLevel::Ansi256
+        );
+        assert_eq!(
+            detect_level(None, None, Some("linux"), terminfo(8)),
+            ColorLevel::Ansi16
+        );
+        assert_eq!(
+            detect_level(None, None, Some("screen-256color"), missing),
+            ColorLevel::Ansi256
+        );
+        assert_eq!(
+            detect_level(None, None, Some("screen"), missing),
+            ColorLevel::Ansi16
+        );
+        assert_eq!(
+            detect_level(None, None, Some("dumb"), missing),
+            ColorLevel::Mono
+        );
+        assert_eq!(
+            detect_level(None, None, None, missing),
+            ColorLevel::TrueColor
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn parses_max_colors_from_compiled_terminfo() {
+        fn entry(magic: u16, width: usize, max_colors: i32) -> Vec<u8> {
+            let names = b"test|synthetic\0";
+            let bools = [1u8, 0, 1];
+            let numbers = 15usize;
+            let mut data = Vec::new();
+            for value in [
+                magic,
+                names.len() as u16,
+                bools.len() as u16,
+                numbers as u16,
+                0,
+                0,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(names);
+            data.extend_from_slice(&bools);
+            if data.len() % 2 == 1 {
+                data.push(0);
+            }
+            for index in 0..numbers {
+                let value = if index == TERMINFO_MAX_COLORS {
+                    max_colors
+                } else {
+                    -1
+                };
+                if width == 2 {
+                    data.extend_from_slice(&(value as i16).to_le_bytes());
+                } else {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            data
+        }
+
+        assert_eq!(parse_terminfo_max_colors(&entry(0o432, 2, 256)), Some(256));
+        assert_eq!(
+            parse_terminfo_max_colors(&entry(0o1036, 4, 1 << 24)),
+            Some(1 << 24)
+        );
+        assert_eq!(parse_terminfo_max_colors(&entry(0o432, 2, -1)), None);
+        assert_eq!(parse_terminfo_max_colors(b"not terminfo"), None);
+    }
+
+    #[test_timeout::timeout]
+    fn quantizes_to_local_palette() {
+        assert_eq!(nearest_256((255, 0, 0)), 196);
+        assert_eq!(nearest_256((0x87, 0xaf, 0xd7)), 110);
+        assert_eq!(nearest_256((128, 128, 128)), 244);
+        assert_eq!(nearest_ansi16((250, 10, 10)), 9);
+
+        let mapper = ColorMapper::new(ColorLevel::Ansi256, Theme::default());
+        assert_eq!(mapper.map(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(mapper.map(Color::Indexed(42)), Color::Indexed(42));
+
+        let mapper = ColorMapper::new(ColorLevel::Ansi16, Theme::default());
+        assert_eq!(mapper.map(Color::Indexed(1)), Color::Red);
+        assert_eq!(mapper.map(Color::Indexed(196)), Color::LightRed);
+        assert_eq!(mapper.map(Color::Rgb(0, 0, 0)), Color::Black);
+
+        let mapper = ColorMapper::new(ColorLevel::Mono, Theme::default());
+        assert_eq!(mapper.foreground(Some(Color::Indexed(2))), None);
+        assert_eq!(mapper.base_style(), Style::default());
+    }
+
+    #[test_timeout::timeout]
+    fn theme_remaps_ansi_and_default_colors() {
+        let config = ThemeConfig {
+            name: Some("solarized-dark".into()),
+            foreground: Some("#fff".into()),
+            background: None,
+            palette: vec!["".into(), "#010203".into(), "nope".into()],
+        };
+        let theme = Theme::from_config(&config);
+        assert_eq!(theme.palette[0], hex(0x073642));
+        assert_eq!(theme.palette[1], Some((1, 2, 3)));
+        assert_eq!(theme.palette[2], hex(0x859900));
+        assert_eq!(theme.foreground, Some((255, 255, 255)));
+        assert_eq!(theme.background, hex(0x002b36));
+
+        let truecolor = ColorMapper::new(ColorLevel::TrueColor, theme.clone());
+        assert_eq!(truecolor.map(Color::Indexed(1)), Color::Rgb(1, 2, 3));
+        assert_eq!(truecolor.map(Color::Indexed(100)), Color::Indexed(100));
+        assert_eq!(
+            truecolor.base_style(),
+            Style::default()
+                .fg(Color::Rgb(255, 255, 255))
+                .bg(Color::Rgb(0x00, 0x2b, 0x36))
+        );
+
+        // 16-color terminals keep their own ANSI palette.
+        let ansi16 = ColorMapper::new(ColorLevel::Ansi16, theme);
+        assert_eq!(ansi16.map(Color::Indexed(1)), Color::Red);
+        assert_eq!(ansi16.foreground(None), Some(Color::White));
+    }
+}
@@ -1,12 +1,14 @@
 use crate::cache::Seq;
-use crate::cache::terminal::StyleId;
+use crate::cache::terminal::export::StyledRow;
+use crate::cache::terminal::{Style as PackedStyle, StyleId};
+use crate::client::color::ColorMapper;
 use ratatui::Frame;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Paragraph, Widget};
-use std::collections::HashMap;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
+use std::collections::{HashMap, HashSet};
 use tracing::{Level, trace};
 
 #[derive(Clone, Copy, Debug)]
@@ -39,6 +41,31 @@ pub enum SelectionMode {
     Block,
 }
 
+/// Another peer's copy-mode cursor and selection, drawn in the peer's color.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerOverlay {
+    pub label: String,
+    pub color: Color,
+    pub typing: bool,
+    pub cursor: Option<SelectionPosition>,
+    pub selection: Option<(SelectionPosition, SelectionPosition, SelectionMode)>,
+}
+
+/// Chat history drawn over the bottom of the terminal body.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChatPane {
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Message being composed; shown on the last line with the cursor.
+    pub input: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PeerMark {
+    Cursor(Color),
+    Selection(Color),
+}
+
 #[derive(Clone, Debug)]
 struct SelectionRange {
     anchor: SelectionPosition,
@@ -127,6 +154,7 @@ struct PredictedCell {
 #[derive(Clone, Debug)]
 struct CachedStyle {
     style: Style,
+    packed: PackedStyle,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -191,6 +219,12 @@ pub struct GridRenderer {
     styles: HashMap<u32, CachedStyle>,
     debug_context: Option<GridUpdateDebugContext>,
     cursor: Option<GridCursor>,
+    /// PTY size (cols, rows) the host settled on; rows is 0 when unknown.
+    host_viewport: Option<(usize, usize)>,
+    colors: ColorMapper,
+    peers: Vec<PeerOverlay>,
+    chat: Option<ChatPane>,
+    note_rows: HashSet<u64>,
 }
 
 impl GridRenderer {
@@ -215,11 +249,17 @@ impl GridRenderer {
             styles: HashMap::new(),
             debug_context: None,
             cursor: None,
+            host_viewport: None,
+            colors: ColorMapper::default(),
+            peers: Vec::new(),
+            chat: None,
+            note_rows: HashSet::new(),
         };
         renderer.styles.insert(
             StyleId::DEFAULT.0,
             CachedStyle {
                 style: Style::default(),
+                packed: PackedStyle::default(),
             },
         );
         renderer.ensure_capacity(rows, cols);
@@ -936,8 +976,19 @@ impl GridRenderer {
     }
 
     pub fn set_style(&mut self, id: u32, fg: u32, bg: u32, attrs: u8) {
-        let style = decode_packed_style(fg, bg, attrs);
-        self.styles.insert(id, CachedStyle { style });
+        let style = decode_packed_style(&self.colors, fg, bg, attrs);
+        let packed = PackedStyle { fg, bg, attrs };
+        self.styles.insert(id, CachedStyle { style, packed });
+        self.mark_dirty();
+    }
+
+    /// Switches the local color handling and re-decodes every cached style.
+    pub fn set_color_mapper(&mut self, colors: ColorMapper) {
+        self.colors = colors;
+        for cached in self.styles.values_mut() {
+            let PackedStyle { fg, bg, attrs } = cached.packed;
+            cached.style = decode_packed_style(&self.colors, fg, bg, attrs);
+        }
         self.mark_dirty();
     }
 
@@ -1247,6 +1298,42 @@ impl GridRenderer {
         }
     }
 
+    /// Replaces the other peers' overlays; the roster in the status line
+    /// lists them in the given order.
+    pub fn set_peer_overlays(&mut self, peers: Vec<PeerOverlay>) {
+        if self.peers != peers {
+            self.peers = peers;
+            self.mark_dirty();
+        }
+    }
+
+    pub fn set_chat_pane(&mut self, pane: Option<ChatPane>) {
+        if self.chat != pane {
+            self.chat = pane;
+            self.mark_dirty();
+        }
+    }
+
+    /// Rows that carry a pinned note get a marker in their last column.
+    pub fn set_note_rows(&mut self, rows: HashSet<u64>) {
+        if self.note_rows != rows {
+            self.note_rows = rows;
+            self.mark_dirty();
+        }
+    }
+
+    fn peer_mark_at(&self, pos: SelectionPosition) -> Option<PeerMark> {
+        if let Some(peer) = self.peers.iter().find(|peer| peer.cursor == Some(pos)) {
+            return Some(PeerMark::Cursor(peer.color));
+        }
+        self.peers.iter().find_map(|peer| {
+            let (anchor, head, mode) = peer.selection?;
+            SelectionRange::new(anchor, head, mode)
+                .contains(pos)
+                .then_some(PeerMark::Selection(peer.color))
+        })
+    }
+
     pub fn set_status_message<S: Into<String>>(&mut self, message: Option<S>) {
         self.set_status_internal(message.map(Into::into), None, false);
     }
@@ -1299,8 +1386,33 @@ impl GridRenderer {
         }
     }
 
-    pub fn on_resize(&mut self, _cols: u16, rows: u16) {
-        let usable = rows.saturating_sub(1) as usize;
+    /// Records the PTY size the host is using. Windows larger than it are
+    /// letterboxed rather than showing cells the host never wrote.
+    pub fn set_host_viewport(&mut self, cols: usize, rows: Option<usize>) {
+        let viewport = Some((cols.max(1), rows.unwrap_or(0)));
+        if self.host_viewport != viewport {
+            self.host_viewport = viewport;
+            self.mark_dirty();
+        }
+    }
+
+    /// Body size for a `width`×`height` area, clamped to the host's PTY.
+    fn body_size(&self, width: u16, height: u16) -> (u16, u16) {
+        let Some((cols, rows)) = self.host_viewport else {
+            return (width, height);
+        };
+        let clamp = |value: usize| u16::try_from(value).unwrap_or(u16::MAX);
+        let height = if rows > 0 {
+            height.min(clamp(rows))
+        } else {
+            height
+        };
+        (width.min(clamp(cols)), height)
+    }
+
+    pub fn on_resize(&mut self, cols: u16, rows: u16) {
+        let (_, usable) = self.body_size(cols, rows.saturating_sub(1));
+        let usable = usable as usize;
         if usable != self.viewport_height {
             self.viewport_height = usable;
             if self.follow_tail {
@@ -1427,7 +1539,8 @@ impl GridRenderer {
         let area = frame.area();
         let status_lines = 1usize;
         let body_height = area.height.saturating_sub(status_lines as u16);
-        self.viewport_height = body_height.max(1) as usize;
+        let (body_cols, body_rows) = self.body_size(area.width, body_height);
+        self.viewport_height = body_rows.max(1) as usize;
         if self.follow_tail {
             self.scroll_to_tail();
         } else {
@@ -1445,16 +1558,28 @@ impl GridRenderer {
             .constraints(constraints)
             .split(area);
 
+        let body_area = Rect {
+            width: body_cols,
+            height: body_rows,
+            ..chunks[0]
+        };
+        let letterboxed = body_area != chunks[0];
         if body_height > 0 {
+            if letterboxed {
+                frame.render_widget(LetterboxWidget { body: body_area }, chunks[0]);
+            }
             let body = self.render_body();
-            frame.render_widget(body, chunks[0]);
-            if let Some((cursor_x, cursor_y)) = self.cursor_widget_position(chunks[0]) {
+            frame.render_widget(body, body_area);
+            if let Some((cursor_x, cursor_y)) = self.cursor_widget_position(body_area) {
                 frame.set_cursor_position((cursor_x, cursor_y));
             }
+            if let Some(pane) = &self.chat {
+                self.render_chat_pane(frame, pane, body_area);
+            }
         }
 
         if chunks.len() >= 2 {
-            let status = self.render_status_line();
+            let status = self.render_status_line(letterboxed);
             frame.render_widget(status, chunks[1]);
         }
 
@@ -1586,14 +1711,30 @@ impl GridRenderer {
                             let highlight_cursor = cursor_col_for_row
                                 .map(|cursor_col| cursor_col == col)
                                 .unwrap_or(false);
+                            let peer = if self.peers.is_empty() {
+                                None
+                            } else {
+                                self.peer_mark_at(SelectionPosition { row: absolute, col })
+                            };
                             spans.push(self.span_for_cell(
                                 ch,
                                 style_id,
                                 selected,
                                 predicted,
                                 highlight_cursor,
+                                peer,
                             ));
                         }
+                        if self.note_rows.contains(&absolute) {
+                            if let Some(last) = spans.last_mut() {
+                                *last = Span::styled(
+                                    "◆",
+                                    Style::default()
+                                        .fg(Color::Yellow)
+                                        .add_modifier(Modifier::BOLD),
+                                );
+                            }
+                        }
                         entries.push((Line::from(spans), false, absolute));
                     }
                 }
@@ -1655,7 +1796,50 @@ impl GridRenderer {
                 );
             }
         }
-        TerminalBodyWidget { lines }
+        TerminalBodyWidget {
+            lines,
+            base: self.colors.base_style(),
+        }
+    }
+
+    fn render_chat_pane(&self, frame: &mut Frame<'_>, pane: &ChatPane, body: Rect) {
+        if body.height < 3 {
+            return;
+        }
+        let input_lines = usize::from(pane.input.is_some());
+        let wanted = (pane.lines.len() + input_lines).max(1) as u16 + 2;
+        let height = wanted.clamp(3, (body.height / 2).max(3));
+        let area = Rect {
+            y: body.bottom() - height,
+            height,
+            ..body
+        };
+        let inner_height = height.saturating_sub(2) as usize;
+        let history = inner_height.saturating_sub(input_lines);
+        let mut lines: Vec<Line> = pane
+            .lines
+            .iter()
+            .skip(pane.lines.len().saturating_sub(history))
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        if let Some(input) = &pane.input {
+            let prompt = format!("> {input}");
+            let cursor_x = area.x + 1 + prompt.chars().count() as u16;
+            let cursor_y = area.y + height - 2;
+            lines.push(Line::from(Span::styled(
+                prompt,
+                Style::default().fg(Color::Yellow),
+            )));
+            if cursor_x < area.right().saturating_sub(1) {
+                frame.set_cursor_position((cursor_x, cursor_y));
+            }
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(pane.title.as_str())
+            .style(self.colors.base_style());
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
     }
 
     fn span_for_cell(
@@ -1665,13 +1849,26 @@ impl GridRenderer {
         selected: bool,
         predicted: bool,
         highlight_cursor: bool,
+        peer: Option<PeerMark>,
     ) -> Span<'static> {
         let mut style = style_id
             .and_then(|id| self.styles.get(&id).map(|cached| cached.style))
-            .unwrap_or_default();
+            .unwrap_or_else(|| self.colors.base_style());
         if predicted && self.prediction_flagging {
             style = style.add_modifier(Modifier::UNDERLINED);
         }
+        match peer {
+            Some(PeerMark::Cursor(color)) => {
+                style = style
+                    .fg(Color::Black)
+                    .bg(color)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            Some(PeerMark::Selection(color)) => {
+                style = style.fg(Color::Black).bg(color);
+            }
+            None => {}
+        }
         if selected {
             style = style
                 .fg(Color::Black)
@@ -1708,6 +1905,36 @@ impl GridRenderer {
         }
     }
 
+    /// Loaded rows in the current viewport with their host styles resolved.
+    pub fn visible_styled_rows(&self) -> Vec<StyledRow> {
+        let top = self.viewport_top();
+        let mut rows = Vec::with_capacity(self.viewport_height());
+        for absolute in top..top.saturating_add(self.viewport_height() as u64) {
+            let Some(rel) = self.relative_row(absolute) else {
+                continue;
+            };
+            if let RowSlot::Loaded(state) = &self.rows[rel] {
+                let cells = state
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        let packed = cell
+                            .style_id
+                            .and_then(|id| self.styles.get(&id))
+                            .map(|cached| cached.packed)
+                            .unwrap_or_default();
+                        (cell.ch, packed)
+                    })
+                    .collect();
+                rows.push(StyledRow {
+                    row: absolute,
+                    cells,
+                });
+            }
+        }
+        rows
+    }
+
     pub fn row_text(&self, absolute_row: u64) -> Option<String> {
         let rel = self.relative_row(absolute_row)?;
         match self.rows.get(rel)? {
@@ -1768,7 +1995,7 @@ impl GridRenderer {
         gap_start.map(|row| (row, gap_len))
     }
 
-    fn render_status_line(&self) -> Paragraph<'_> {
+    fn render_status_line(&self, letterboxed: bool) -> Paragraph<'_> {
         let total_rows = self.total_rows();
         let displayed = self.viewport_height.min(self.rows.len());
         let status_line = format!(
@@ -1781,6 +2008,24 @@ impl GridRenderer {
             spans.push(Span::raw("  "));
         }
         spans.push(Span::raw(status_line));
+        if let Some((cols, rows)) = self.host_viewport.filter(|_| letterboxed) {
+            spans.push(Span::styled(
+                format!(" • session {cols}×{rows}"),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        for (idx, peer) in self.peers.iter().enumerate() {
+            spans.push(Span::raw(if idx == 0 { " • " } else { " " }));
+            let label = if peer.typing {
+                format!("{}✎", peer.label)
+            } else {
+                peer.label.clone()
+            };
+            spans.push(Span::styled(
+                label,
+                Style::default().fg(peer.color).add_modifier(Modifier::BOLD),
+            ));
+        }
 
         if let Some(message) = &self.status_message {
             spans.push(Span::raw(format!(" • {}", message)));
@@ -1810,12 +2055,12 @@ struct StatusIndicator {
     style: Style,
 }
 
-fn decode_packed_style(fg: u32, bg: u32, attrs: u8) -> Style {
+fn decode_packed_style(colors: &ColorMapper, fg: u32, bg: u32, attrs: u8) -> Style {
     let mut style = Style::default();
-    if let Some(color) = decode_color(fg) {
+    if let Some(color) = colors.foreground(decode_color(fg)) {
         style = style.fg(color);
     }
-    if let Some(color) = decode_color(bg) {
+    if let Some(color) = colors.background(decode_color(bg)) {
         style = style.bg(color);
     }
     let modifiers = decode_modifiers(attrs);
@@ -1869,10 +2114,34 @@ fn decode_modifiers(attrs: u8) -> Modifier {
 
 struct TerminalBodyWidget {
     lines: Vec<Line<'static>>,
+    /// Theme default colors for cells the host left unstyled.
+    base: Style,
+}
+
+/// Shades the part of the window outside the host's PTY, like tmux does for
+/// clients larger than the window.
+struct LetterboxWidget {
+    body: Rect,
+}
+
+impl Widget for LetterboxWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let style = Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if x >= self.body.right() || y >= self.body.bottom() {
+                    buf.set_string(x, y, "·", style);
+                }
+            }
+        }
+    }
 }
 
 impl Widget for TerminalBodyWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.base);
         let mut blank_line: Option<Line<'static>> = None;
         let max_rows = area.height as usize;
         let lines = self.lines;
@@ -1923,6 +2192,89 @@ mod tests {
         );
     }
 
+    #[test_timeout::timeout]
+    fn larger_window_letterboxes_around_host_pty() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut renderer = GridRenderer::new(0, 4);
+        renderer.set_base_row(0);
+        renderer.set_history_origin(0);
+        for row in 0..2u64 {
+            renderer.apply_row_from_cells(row as usize, row as Seq, &decode_line("abcd"));
+        }
+        renderer.set_host_viewport(4, Some(2));
+
+        let mut terminal = Terminal::new(TestBackend::new(8, 4)).unwrap();
+        terminal.draw(|frame| renderer.render_frame(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| {
+            (0..8)
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+        assert_eq!(row(0), "abcd····");
+        assert_eq!(row(1), "abcd····");
+        assert_eq!(row(2), "········");
+        assert_eq!(renderer.viewport_height(), 2);
+    }
+
+    #[test_timeout::timeout]
+    fn color_mapper_downgrades_styles_and_fills_theme_background() {
+        use crate::client::color::{ColorLevel, Theme};
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut renderer = GridRenderer::new(0, 4);
+        renderer.set_base_row(0);
+        renderer.set_history_origin(0);
+        // Truecolor red foreground from the host.
+        renderer.set_style(7, (2 << 24) | 0xff0000, 0, 0);
+        renderer.apply_row_from_cells(0, 1, &[('a', Some(7)), ('b', None)]);
+        renderer.set_color_mapper(ColorMapper::new(
+            ColorLevel::Ansi256,
+            Theme::builtin("gruvbox-dark").unwrap(),
+        ));
+
+        let mut terminal = Terminal::new(TestBackend::new(4, 3)).unwrap();
+        terminal.draw(|frame| renderer.render_frame(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let background = Color::Indexed(235);
+        assert_eq!(buffer[(0, 0)].fg, Color::Indexed(196));
+        assert_eq!(buffer[(0, 0)].bg, background);
+        assert_eq!(buffer[(1, 0)].bg, background);
+        assert_eq!(buffer[(3, 1)].bg, background);
+    }
+
+    #[test_timeout::timeout]
+    fn peer_overlays_highlight_cells_and_fill_the_roster() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut renderer = GridRenderer::new(0, 6);
+        renderer.set_base_row(0);
+        renderer.set_history_origin(0);
+        renderer.apply_row_from_cells(0, 1, &decode_line("abcdef"));
+        renderer.set_peer_overlays(vec![PeerOverlay {
+            label: "ben".into(),
+            color: Color::Green,
+            typing: true,
+            cursor: Some(SelectionPosition { row: 0, col: 4 }),
+            selection: Some((
+                SelectionPosition { row: 0, col: 1 },
+                SelectionPosition { row: 0, col: 4 },
+                SelectionMode::Character,
+            )),
+        }]);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 2)).unwrap();
+        terminal.draw(|frame| renderer.render_frame(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(0, 0)].bg, Color::Reset);
+        assert_eq!(buffer[(2, 0)].bg, Color::Green);
+        assert!(buffer[(4, 0)].modifier.contains(Modifier::UNDERLINED));
+        assert!(!buffer[(2, 0)].modifier.contains(Modifier::UNDERLINED));
+        let status: String = (0..60).map(|x| buffer[(x, 1)].symbol()).collect();
+        assert!(status.contains("ben✎"), "status line: {status}");
+    }
+
     #[test_timeout::timeout]
     fn tail_short_buffer_stays_top_aligned() {
         let mut renderer = GridRenderer::new(0, 10);
@@ -1,2 +1,3 @@
+pub mod color;
 pub mod grid_renderer;
 pub mod terminal;
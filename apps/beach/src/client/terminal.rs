@@ -1,29 +1,56 @@
+mod chat;
+mod clipboard_sync;
 pub mod debug;
+pub mod headless;
+pub mod input;
 pub mod join;
+pub mod keymap;
+pub mod portfwd;
+mod presence;
+pub mod transfer;
 
 use crate::cache::Seq;
 use crate::cache::terminal::{PackedCell, StyleId, unpack_cell};
+use crate::client::color::ColorMapper;
 use crate::client::grid_renderer::{GridRenderer, SelectionMode, SelectionPosition};
+use crate::client::terminal::chat::{Chat, DraftKey, format_message};
+use crate::client::terminal::clipboard_sync::{ClipboardNotice, ClipboardSync};
+use crate::client::terminal::keymap::{ClientAction, Keymap, format_key_binding};
+use crate::client::terminal::portfwd::PortForwards;
+use crate::client::terminal::presence::{self as peer_presence, Presence};
+use crate::client::terminal::transfer::{
+    PathPrompt, PathPromptKey, PathPromptKind, TransferStep, Transfers,
+};
 use crate::debug::server::DiagnosticServer;
+use crate::protocol::chat::{ChatEvent, ChatPost};
+use crate::protocol::clipboard::{ClipboardEvent, ClipboardMode};
+use crate::protocol::portfwd::PortForwardEvent;
+use crate::protocol::presence::PresenceEvent;
+use crate::protocol::transfer::TransferEvent;
 use crate::protocol::{
     self, ClientFrame as WireClientFrame, CursorFrame, ExtensionFrame, FEATURE_CURSOR_SYNC,
-    HostFrame as WireHostFrame, Update as WireUpdate, ViewportCommand,
+    FEATURE_HISTORY_SEARCH, HostFrame as WireHostFrame, KITTY_KEYBOARD_REPORT_ALL_KEYS,
+    KITTY_KEYBOARD_REPORT_ALTERNATES, KITTY_KEYBOARD_REPORT_EVENTS, ModifyOtherKeys, MouseTracking,
+    SearchHit, TerminalModesFrame, Update as WireUpdate, ViewportCommand,
 };
+use crate::session::terminal::transfer::DEFAULT_TRANSFER_MAX_BYTES;
 use crate::telemetry::{self, PerfGuard};
-use crate::transport::{Payload, Transport, TransportError, extensions};
+use crate::transport::extensions::{self, ExtensionEvent};
+use crate::transport::{ExtensionDirection, ExtensionLane, Payload, Transport, TransportError};
 #[cfg(not(test))]
 use copypasta::{ClipboardContext, ClipboardProvider};
 use crossterm::{
     cursor::{MoveTo, Show},
     event::{
-        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
-        MouseEventKind,
+        self, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, KeyboardEnhancementFlags, MouseButton, MouseEvent, MouseEventKind,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     execute,
     terminal::{
         Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
-        enable_raw_mode, size as crossterm_size,
+        enable_raw_mode, size as crossterm_size, supports_keyboard_enhancement,
     },
 };
 use ratatui::{
@@ -33,9 +60,10 @@ use ratatui::{
 };
 use serde_json::{Map, Value, json};
 use std::cmp;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{
     Arc,
     mpsc::{Receiver, TryRecvError},
@@ -51,8 +79,6 @@ const BACKFILL_MIN_INTERVAL: Duration = Duration::from_millis(250);
 const BACKFILL_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 const MOUSE_SCROLL_LINES: isize = 5;
 const COPY_MODE_KEYSET_ENV: &str = "BEACH_COPY_MODE_KEYS";
-const SCROLL_TOGGLE_KEY_ENV: &str = "BEACH_SCROLL_TOGGLE_KEY";
-const COPY_SHORTCUTS_ENV: &str = "BEACH_COPY_SHORTCUTS";
 const SCROLL_TOGGLE_DOUBLE_ESC: Duration = Duration::from_millis(400);
 const TMUX_PREFIX_TIMEOUT: Duration = Duration::from_millis(500);
 const AUTH_SPINNER_FRAMES: [&str; 4] = ["-", "\\", "|", "/"];
@@ -191,6 +217,29 @@ struct CopyModeSearch {
     pattern: String,
 }
 
+/// Hits the host returns per history search; repeating the search walks them
+/// before asking again.
+const HISTORY_SEARCH_BATCH: u32 = 32;
+
+#[derive(Clone, Debug)]
+struct PendingHistorySearch {
+    id: u64,
+    pattern: String,
+    direction: CopyModeSearchDirection,
+    /// Set once the search restarted from the far end of history.
+    wrapped: bool,
+}
+
+/// History search hits not visited yet. They stay valid while the cursor sits
+/// on the hit it last jumped to.
+#[derive(Clone, Debug)]
+struct HistorySearchHits {
+    pattern: String,
+    direction: CopyModeSearchDirection,
+    at: SelectionPosition,
+    hits: VecDeque<SearchHit>,
+}
+
 #[derive(Clone, Copy, Debug)]
 enum CopyModeCommand {
     Move { rows: isize, cols: isize },
@@ -219,115 +268,6 @@ enum ViewMode {
     Scrollback,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct KeyBinding {
-    code: KeyCode,
-    modifiers: KeyModifiers,
-}
-
-impl KeyBinding {
-    const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
-        Self { code, modifiers }
-    }
-
-    fn matches(&self, key: &KeyEvent) -> bool {
-        key.kind == KeyEventKind::Press && key.code == self.code && key.modifiers == self.modifiers
-    }
-}
-
-fn default_scroll_toggle_bindings() -> Vec<KeyBinding> {
-    vec![KeyBinding::new(KeyCode::Esc, KeyModifiers::CONTROL)]
-}
-
-fn parse_key_binding(value: &str) -> Option<KeyBinding> {
-    let mut modifiers = KeyModifiers::NONE;
-    let mut key_token: Option<String> = None;
-    for part in value.split('+') {
-        let trimmed = part.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let lower = trimmed.to_ascii_lowercase();
-        match lower.as_str() {
-            "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
-            "alt" | "option" | "opt" => modifiers |= KeyModifiers::ALT,
-            "shift" => modifiers |= KeyModifiers::SHIFT,
-            "super" => modifiers |= KeyModifiers::SUPER,
-            "cmd" | "command" => modifiers |= KeyModifiers::SUPER,
-            token => {
-                if key_token.is_some() {
-                    return None;
-                }
-                key_token = Some(token.to_string());
-            }
-        }
-    }
-
-    let token = key_token?;
-    let code = match token.as_str() {
-        "esc" | "escape" => KeyCode::Esc,
-        "enter" | "return" => KeyCode::Enter,
-        "tab" => KeyCode::Tab,
-        "backspace" => KeyCode::Backspace,
-        "pageup" => KeyCode::PageUp,
-        "pagedown" => KeyCode::PageDown,
-        "home" => KeyCode::Home,
-        "end" => KeyCode::End,
-        "space" => KeyCode::Char(' '),
-        "delete" => KeyCode::Delete,
-        "insert" => KeyCode::Insert,
-        other => {
-            let mut chars = other.chars();
-            let ch = chars.next()?;
-            if chars.next().is_some() {
-                return None;
-            }
-            KeyCode::Char(ch)
-        }
-    };
-
-    Some(KeyBinding::new(code, modifiers))
-}
-
-fn parse_key_bindings(value: &str) -> Vec<KeyBinding> {
-    value
-        .split(',')
-        .filter_map(|part| parse_key_binding(part.trim()))
-        .collect()
-}
-
-fn format_key_binding(binding: &KeyBinding) -> String {
-    let mut parts: Vec<String> = Vec::new();
-    if binding.modifiers.contains(KeyModifiers::CONTROL) {
-        parts.push("CTRL".to_string());
-    }
-    if binding.modifiers.contains(KeyModifiers::ALT) {
-        parts.push("ALT".to_string());
-    }
-    if binding.modifiers.contains(KeyModifiers::SHIFT) {
-        parts.push("SHIFT".to_string());
-    }
-    if binding.modifiers.contains(KeyModifiers::SUPER) {
-        parts.push("CMD".to_string());
-    }
-    let key = match binding.code {
-        KeyCode::Esc => "ESC".to_string(),
-        KeyCode::Enter => "ENTER".to_string(),
-        KeyCode::Tab => "TAB".to_string(),
-        KeyCode::Backspace => "BACKSPACE".to_string(),
-        KeyCode::PageUp => "PAGEUP".to_string(),
-        KeyCode::PageDown => "PAGEDOWN".to_string(),
-        KeyCode::Home => "HOME".to_string(),
-        KeyCode::End => "END".to_string(),
-        KeyCode::Delete => "DELETE".to_string(),
-        KeyCode::Insert => "INSERT".to_string(),
-        KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
-        _ => format!("{:?}", binding.code).to_ascii_uppercase(),
-    };
-    parts.push(key);
-    parts.join("+")
-}
-
 #[derive(Clone, Debug)]
 struct BackfillRequestState {
     id: u64,
@@ -385,23 +325,34 @@ pub struct TerminalClient {
     prediction_overlay_logged_visible: bool,
     prediction_overlay_logged_underline: bool,
     copy_mode: Option<CopyModeState>,
-    scroll_toggle: Vec<KeyBinding>,
-    scroll_double_esc_enabled: bool,
-    copy_shortcuts: Vec<KeyBinding>,
+    keymap: Keymap,
+    presence: Presence,
+    chat: Chat,
+    transfers: Transfers,
+    transfer_prompt: Option<PathPrompt>,
+    transfer_progress: Option<String>,
+    port_forwards: Option<Arc<PortForwards>>,
+    clipboard_sync: ClipboardSync,
     tail_flash_until: Option<Instant>,
     last_plain_esc: Option<Instant>,
     last_render_at: Option<Instant>,
     render_interval: Duration,
     pending_render: bool,
     predictive_input: bool,
-    forward_mouse_to_host: bool,
+    host_modes: TerminalModesFrame,
     mouse_capture_enabled: bool,
+    keyboard_enhancement_supported: Option<bool>,
+    keyboard_enhancement: Option<KeyboardEnhancementFlags>,
     tmux_prefix_started_at: Option<Instant>,
     subscription_id: Option<u64>,
     handshake_history_rows: u64,
     handshake_snapshot_lines: u32,
     next_backfill_request_id: u64,
     pending_backfills: Vec<BackfillRequestState>,
+    history_search_support: bool,
+    next_search_request_id: u64,
+    pending_search: Option<PendingHistorySearch>,
+    search_hits: Option<HistorySearchHits>,
     last_backfill_request_at: Option<Instant>,
     known_base_row: Option<u64>,
     has_loaded_rows: bool,
@@ -440,70 +391,6 @@ impl TerminalClient {
             "predictive logging initialized"
         );
 
-        // Load user key config (config file overrides defaults; env vars override config file)
-        let user_config = crate::terminal::config::load_user_config();
-
-        // Scroll toggle bindings
-        let scroll_toggle = if let Ok(value) = env::var(SCROLL_TOGGLE_KEY_ENV) {
-            let parsed = parse_key_bindings(&value);
-            if parsed.is_empty() {
-                debug!(
-                    target = "client::config",
-                    value, "invalid scroll toggle binding, using default"
-                );
-                default_scroll_toggle_bindings()
-            } else {
-                parsed
-            }
-        } else if let Some(list) = user_config
-            .as_ref()
-            .and_then(|c| c.client.as_ref())
-            .and_then(|c| c.keys.as_ref())
-            .and_then(|k| k.scroll_toggle.as_ref())
-        {
-            let joined = list.join(",");
-            let parsed = parse_key_bindings(&joined);
-            if parsed.is_empty() {
-                default_scroll_toggle_bindings()
-            } else {
-                parsed
-            }
-        } else {
-            default_scroll_toggle_bindings()
-        };
-
-        // Double-ESC toggle behavior
-        let scroll_double_esc_enabled = user_config
-            .as_ref()
-            .and_then(|c| c.client.as_ref())
-            .and_then(|c| c.keys.as_ref())
-            .and_then(|k| k.double_esc)
-            .unwrap_or(true);
-
-        // Copy shortcuts in copy-mode
-        let copy_shortcuts = if let Ok(value) = env::var(COPY_SHORTCUTS_ENV) {
-            let parsed = parse_key_bindings(&value);
-            if parsed.is_empty() {
-                default_copy_shortcut_bindings()
-            } else {
-                parsed
-            }
-        } else if let Some(list) = user_config
-            .as_ref()
-            .and_then(|c| c.client.as_ref())
-            .and_then(|c| c.keys.as_ref())
-            .and_then(|k| k.copy_shortcuts.as_ref())
-        {
-            let joined = list.join(",");
-            let parsed = parse_key_bindings(&joined);
-            if parsed.is_empty() {
-                default_copy_shortcut_bindings()
-            } else {
-                parsed
-            }
-        } else {
-            default_copy_shortcut_bindings()
-        };
         let mut client = Self {
             transport,
             renderer,
@@ -533,23 +420,37 @@ impl TerminalClient {
             prediction_overlay_logged_visible: false,
             prediction_overlay_logged_underline: false,
             copy_mode: None,
-            scroll_toggle,
-            scroll_double_esc_enabled,
-            copy_shortcuts,
+            keymap: Keymap::load(),
+            presence: Presence::load(),
+            chat: Chat::default(),
+            transfers: Transfers::new(
+                env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+                DEFAULT_TRANSFER_MAX_BYTES,
+            ),
+            transfer_prompt: None,
+            transfer_progress: None,
+            port_forwards: None,
+            clipboard_sync: ClipboardSync::default(),
             tail_flash_until: None,
             last_plain_esc: None,
             last_render_at: None,
             render_interval: Duration::from_millis(16),
             pending_render: false,
             predictive_input: false,
-            forward_mouse_to_host: false,
+            host_modes: TerminalModesFrame::default(),
             mouse_capture_enabled: false,
+            keyboard_enhancement_supported: None,
+            keyboard_enhancement: None,
             tmux_prefix_started_at: None,
             subscription_id: None,
             handshake_history_rows: 0,
             handshake_snapshot_lines: 0,
             next_backfill_request_id: 1,
             pending_backfills: Vec::new(),
+            history_search_support: false,
+            next_search_request_id: 1,
+            pending_search: None,
+            search_hits: None,
             last_backfill_request_at: None,
             known_base_row: None,
             has_loaded_rows: false,
@@ -600,11 +501,26 @@ impl TerminalClient {
         self
     }
 
+    pub fn with_color_mapper(mut self, colors: ColorMapper) -> Self {
+        self.renderer.set_color_mapper(colors);
+        self
+    }
+
     pub fn with_diagnostic_server(mut self, server: DiagnosticServer) -> Self {
         self.diagnostic_server = Some(server);
         self
     }
 
+    pub fn with_port_forwards(mut self, forwards: Arc<PortForwards>) -> Self {
+        self.port_forwards = Some(forwards);
+        self
+    }
+
+    pub fn with_clipboard_sync(mut self, mode: ClipboardMode) -> Self {
+        self.clipboard_sync = ClipboardSync::new(mode);
+        self
+    }
+
     #[cfg(test)]
     pub fn renderer_base_row(&self) -> u64 {
         self.renderer.base_row()
@@ -616,6 +532,7 @@ impl TerminalClient {
     }
 
     fn handle_diagnostic_requests(&mut self) {
+        use crate::cache::terminal::export::export_rows;
         use crate::debug::{
             CacheState, CursorState, DiagnosticRequest, DiagnosticResponse, RendererState,
             TerminalDimensions,
@@ -681,6 +598,13 @@ impl TerminalClient {
                         cursor_viewport_position: self.renderer.cursor_viewport_position(),
                     })
                 }
+                DiagnosticRequest::ExportGrid(format) => {
+                    let rows = self.renderer.visible_styled_rows();
+                    DiagnosticResponse::GridExport {
+                        format,
+                        content: export_rows(format, &rows),
+                    }
+                }
                 DiagnosticRequest::SendInput(text) => {
                     let bytes = text.as_bytes();
                     match self.send_input(bytes) {
@@ -707,6 +631,10 @@ impl TerminalClient {
                 self.tick_authorization();
                 self.maybe_update_tail_flash();
                 self.update_prediction_overlay();
+                self.sync_presence();
+                self.sync_transfers();
+                self.sync_port_forwards();
+                self.sync_clipboard();
                 let message = match self.transport.recv(Duration::from_millis(25)) {
                     Ok(message) => Some(message),
                     Err(TransportError::Timeout) => None,
@@ -739,7 +667,17 @@ impl TerminalClient {
                                 "received binary payload from transport"
                             );
                             let decode_start = Instant::now();
-                            let frame = protocol::decode_host_frame_binary(&bytes)?;
+                            let frame = match protocol::decode_host_frame_binary(&bytes) {
+                                Ok(frame) => frame,
+                                Err(protocol::WireError::UnknownFrameType(kind)) => {
+                                    debug!(
+                                        target = "client::frame",
+                                        kind, "skipping unknown host frame"
+                                    );
+                                    continue;
+                                }
+                                Err(err) => return Err(err.into()),
+                            };
                             let decode_elapsed = decode_start.elapsed();
                             match &frame {
                                 WireHostFrame::Snapshot { .. } => telemetry::record_duration(
@@ -844,6 +782,8 @@ impl TerminalClient {
                 WireHostFrame::InputAck { .. } => "input_ack",
                 WireHostFrame::Cursor { .. } => "cursor",
                 WireHostFrame::Extension { .. } => "extension",
+                WireHostFrame::SearchResults { .. } => "search_results",
+                WireHostFrame::Modes { .. } => "modes",
                 WireHostFrame::Shutdown => "shutdown",
             };
             debug!(
@@ -866,6 +806,12 @@ impl TerminalClient {
                 self.subscription_id = Some(subscription);
                 self.last_seq = cmp::max(self.last_seq, max_seq);
                 self.cursor_support = (features & FEATURE_CURSOR_SYNC) != 0;
+                self.history_search_support = (features & FEATURE_HISTORY_SEARCH) != 0;
+                self.pending_search = None;
+                self.search_hits = None;
+                self.host_modes = TerminalModesFrame::default();
+                self.sync_mouse_capture();
+                self.sync_keyboard_enhancement();
                 self.cursor_authoritative = false;
                 self.cursor_authoritative_pending = false;
                 self.cursor_seq = 0;
@@ -918,6 +864,8 @@ impl TerminalClient {
                 let total_rows = history_rows.max(visible_rows as u32) as usize;
                 let cols = cols as usize;
                 self.renderer.ensure_size(total_rows, cols);
+                self.renderer
+                    .set_host_viewport(cols, viewport_rows.map(|rows| rows as usize));
                 self.renderer.mark_dirty();
                 self.force_render = true;
                 self.cursor_row = visible_rows.saturating_sub(1);
@@ -1054,6 +1002,13 @@ impl TerminalClient {
             WireHostFrame::Extension { frame } => {
                 self.handle_extension_frame(frame);
             }
+            WireHostFrame::SearchResults {
+                request_id,
+                hits,
+                truncated: _,
+            } => {
+                self.handle_search_results(request_id, hits);
+            }
             WireHostFrame::SnapshotComplete { .. } => {
                 debug!(
                     authorization_state = ?self.authorization_state,
@@ -1067,6 +1022,11 @@ impl TerminalClient {
                     self.initial_scroll_done = true;
                 }
             }
+            WireHostFrame::Modes { modes } => {
+                self.host_modes = modes;
+                self.sync_mouse_capture();
+                self.sync_keyboard_enhancement();
+            }
             WireHostFrame::Shutdown => return Err(ClientError::Shutdown),
         }
         Ok(())
@@ -1074,6 +1034,43 @@ impl TerminalClient {
 
     fn handle_extension_frame(&mut self, frame: ExtensionFrame) {
         let _ = extensions::publish(self.transport.id(), frame.clone());
+        if let Some(event) = PresenceEvent::from_frame(&frame) {
+            self.presence.apply(event);
+            self.renderer
+                .set_peer_overlays(self.presence.overlays(peer_presence::now_ms()));
+            return;
+        }
+        if let Some(event) = ChatEvent::from_frame(&frame) {
+            let incoming = match &event {
+                ChatEvent::Message(message) => Some(format_message(message)),
+                _ => None,
+            };
+            self.chat.apply(event);
+            if let Some(text) = incoming.filter(|_| self.chat.unread() > 0) {
+                if self.copy_mode.is_none() {
+                    self.renderer.set_status_message(Some(format!("💬 {text}")));
+                }
+            }
+            self.refresh_chat();
+            return;
+        }
+        if let Some(event) = TransferEvent::from_frame(&frame) {
+            let step = self.transfers.apply(event);
+            self.finish_transfer_step(step);
+            return;
+        }
+        if let Some(event) = PortForwardEvent::from_frame(&frame) {
+            if let Some(forwards) = &self.port_forwards {
+                forwards.handle(event);
+            }
+            return;
+        }
+        if let Some(event) = ClipboardEvent::from_frame(&frame) {
+            if let Some(notice) = self.clipboard_sync.apply(event) {
+                self.apply_clipboard_notice(notice);
+            }
+            return;
+        }
         trace!(
             target = "client::frame",
             namespace = %frame.namespace,
@@ -2220,22 +2217,30 @@ impl TerminalClient {
             while event::poll(Duration::from_millis(0)).unwrap_or(false) {
                 match event::read() {
                     Ok(Event::Key(key)) => {
-                        if self.handle_scroll_toggle(&key)? {
+                        // Releases only arrive when the host application asked
+                        // for them; they never trigger local shortcuts.
+                        if key.kind == KeyEventKind::Release {
+                            if let Some(bytes) = input::encode_key(&key, &self.host_modes) {
+                                pending.push((bytes, false));
+                            }
+                            continue;
+                        }
+                        if self.handle_chat_key(&key)? {
                             continue;
                         }
-                        if self.handle_super_shortcuts(&key)? {
+                        if self.handle_transfer_prompt_key(&key) {
                             continue;
                         }
-                        if self.handle_control_shortcuts(&key)? {
+                        if self.handle_scroll_toggle(&key)? {
                             continue;
                         }
-                        if self.process_copy_mode_key(&key) {
+                        if self.handle_key_bindings(&key)? {
                             continue;
                         }
                         if self.handle_local_key(&key) {
                             continue;
                         }
-                        if let Some(bytes) = encode_key_event(key) {
+                        if let Some(bytes) = input::encode_key(&key, &self.host_modes) {
                             // Key events may arrive rapidly during pastes when bracketed paste is unavailable.
                             // Coalesce later to cut transport overhead.
                             pending.push((bytes, true));
@@ -2243,7 +2248,7 @@ impl TerminalClient {
                     }
                     Ok(Event::Paste(data)) => {
                         // Treat OS paste as a single chunk and skip predictions to avoid client-side CPU overhead.
-                        pending.push((data.into_bytes(), false));
+                        pending.push((input::encode_paste(&data, &self.host_modes), false));
                     }
                     Ok(Event::Resize(cols, rows)) => {
                         self.renderer.on_resize(cols, rows);
@@ -2254,17 +2259,20 @@ impl TerminalClient {
                         if self.handle_mouse_event(&mouse)? {
                             continue;
                         }
-                        if self.forward_mouse_to_host {
-                            if let Some(encoded) = encode_mouse_event(&mouse) {
-                                pending.push((encoded, true));
-                            }
+                        if let Some(encoded) = input::encode_mouse(&mouse, &self.host_modes) {
+                            pending.push((encoded, false));
+                        }
+                    }
+                    Ok(event @ (Event::FocusGained | Event::FocusLost)) => {
+                        let gained = matches!(event, Event::FocusGained);
+                        if let Some(encoded) = input::encode_focus(gained, &self.host_modes) {
+                            pending.push((encoded, false));
                         }
                     }
                     Err(err) => {
                         eprintln!("⚠️  input read error: {err}");
                         break;
                     }
-                    _ => {}
                 }
             }
         }
@@ -2300,98 +2308,326 @@ impl TerminalClient {
         Ok(())
     }
 
+    fn handle_key_bindings(&mut self, key: &KeyEvent) -> Result<bool, ClientError> {
+        if self.handle_tmux_prefix(key)? {
+            return Ok(true);
+        }
+        if self.process_copy_mode_key(key) {
+            return Ok(true);
+        }
+        match self.keymap.root_action(key) {
+            Some(action) => {
+                self.run_client_action(action)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn process_copy_mode_key(&mut self, key: &KeyEvent) -> bool {
-        if self.handle_tmux_prefix(key) {
+        let Some(state) = self.copy_mode.as_ref() else {
+            return false;
+        };
+        let mode = state.mode;
+        // Configurable copy shortcuts (copy selection and exit copy-mode)
+        if self.keymap.is_copy_shortcut(key) {
+            self.copy_selection_to_clipboard(true);
+            return true;
+        }
+        if self.consume_copy_mode_pending_input(key) {
+            return true;
+        }
+        if let Some(command) = self.keymap.copy_mode_command(mode, key) {
+            self.execute_copy_mode_command(command);
             return true;
         }
+        false
+    }
 
-        if self.copy_mode.is_some() {
-            // Configurable copy shortcuts (copy selection and exit copy-mode)
-            if self
-                .copy_shortcuts
-                .iter()
-                .any(|binding| binding.matches(key))
-            {
-                self.copy_selection_to_clipboard(true);
-                return true;
+    fn handle_tmux_prefix(&mut self, key: &KeyEvent) -> Result<bool, ClientError> {
+        self.expire_tmux_prefix();
+
+        if self.keymap.is_prefix(key) {
+            self.tmux_prefix_started_at = Some(Instant::now());
+            return Ok(true);
+        }
+
+        if self.tmux_prefix_started_at.take().is_some() {
+            if let Some(action) = self.keymap.prefix_action(key) {
+                self.run_client_action(action)?;
+                return Ok(true);
             }
-            if self.consume_copy_mode_pending_input(key) {
-                return true;
+        }
+
+        Ok(false)
+    }
+
+    fn run_client_action(&mut self, action: ClientAction) -> Result<(), ClientError> {
+        match action {
+            ClientAction::EnterCopyMode => self.enter_copy_mode(),
+            ClientAction::ToggleScrollback => self.toggle_scrollback(),
+            ClientAction::ScrollPageUp => {
+                self.enter_copy_mode_with(CopyModeCommand::Page { delta: -1 })
             }
-            let (mode, selection_active) = {
-                let state = self.copy_mode.as_ref().unwrap();
-                (state.mode, state.selection_active)
-            };
-            if let Some(command) = copy_mode_command_for_key(mode, selection_active, key) {
-                self.execute_copy_mode_command(command);
-                return true;
+            ClientAction::ScrollPageDown => {
+                self.enter_copy_mode_with(CopyModeCommand::Page { delta: 1 })
             }
-            return false;
+            ClientAction::Search => self
+                .enter_copy_mode_with(CopyModeCommand::Search(CopyModeSearchDirection::Backward)),
+            ClientAction::Paste => self.paste_from_clipboard(),
+            ClientAction::ClearViewport => {
+                self.force_render = true;
+                self.renderer.mark_dirty();
+                self.request_viewport_clear()?;
+            }
+            ClientAction::TogglePredictions => self.toggle_predictions(),
+            ClientAction::ShowPeers => self.show_peers(),
+            ClientAction::ToggleChat => {
+                self.chat.toggle();
+                self.refresh_chat();
+            }
+            ClientAction::PinNote => {
+                let row = match &self.copy_mode {
+                    Some(state) => state.cursor.row,
+                    None => self
+                        .renderer
+                        .get_cursor()
+                        .map(|(row, _, _)| row)
+                        .unwrap_or(self.cursor_row as u64),
+                };
+                self.chat.compose(Some(row));
+                self.refresh_chat();
+            }
+            ClientAction::SendFile => self.open_transfer_prompt(PathPromptKind::Send),
+            ClientAction::ReceiveFile => self.open_transfer_prompt(PathPromptKind::Receive),
+            ClientAction::Detach => return Err(ClientError::Shutdown),
         }
+        Ok(())
+    }
 
-        if matches!(key.code, KeyCode::PageUp) {
-            self.enter_copy_mode();
-            if self.copy_mode.is_some() {
-                self.execute_copy_mode_command(CopyModeCommand::Page { delta: -1 });
-            }
-            return true;
+    fn enter_copy_mode_with(&mut self, command: CopyModeCommand) {
+        self.enter_copy_mode();
+        if self.copy_mode.is_some() {
+            self.execute_copy_mode_command(command);
         }
+    }
 
-        if key.modifiers.contains(KeyModifiers::ALT) {
-            if let KeyCode::Char(c) = key.code {
-                if c.eq_ignore_ascii_case(&'[') {
-                    self.enter_copy_mode();
-                    return true;
+    fn toggle_predictions(&mut self) {
+        self.predictive_input = !self.predictive_input;
+        self.reset_prediction_state();
+        let state = if self.predictive_input { "on" } else { "off" };
+        self.renderer
+            .set_status_message(Some(format!("predictive echo {state}")));
+        self.force_render = true;
+    }
+
+    fn show_peers(&mut self) {
+        let peer = self.transport.peer();
+        let mut roster = format!("peers: you • host (transport {})", peer.0);
+        for label in self.presence.labels() {
+            roster.push_str(" • ");
+            roster.push_str(label);
+        }
+        self.renderer.set_status_message(Some(roster));
+        self.force_render = true;
+    }
+
+    /// Routes keys to the chat draft while a message is being composed.
+    fn handle_chat_key(&mut self, key: &KeyEvent) -> Result<bool, ClientError> {
+        let Some(outcome) = self.chat.handle_key(key, self.presence.label()) else {
+            return Ok(false);
+        };
+        if let DraftKey::Submit(post) = outcome {
+            self.send_chat_post(post)?;
+        }
+        self.refresh_chat();
+        Ok(true)
+    }
+
+    fn send_chat_post(&mut self, post: ChatPost) -> Result<(), ClientError> {
+        self.transport
+            .send_extension(
+                ExtensionDirection::ClientToHost,
+                ChatEvent::Post(post).to_frame(),
+                ExtensionLane::ControlOrdered,
+            )
+            .map_err(ClientError::Transport)?;
+        Ok(())
+    }
+
+    fn refresh_chat(&mut self) {
+        self.renderer.set_chat_pane(self.chat.pane());
+        let note_rows = if self.copy_mode.is_some() {
+            self.chat.note_rows()
+        } else {
+            HashSet::new()
+        };
+        self.renderer.set_note_rows(note_rows);
+        self.force_render = true;
+    }
+
+    fn open_transfer_prompt(&mut self, kind: PathPromptKind) {
+        let prompt = PathPrompt::new(kind);
+        self.renderer.set_status_message(Some(prompt.status()));
+        self.transfer_prompt = Some(prompt);
+        self.force_render = true;
+    }
+
+    /// Routes keys to the file path prompt while it is open.
+    fn handle_transfer_prompt_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(prompt) = self.transfer_prompt.as_mut() else {
+            return false;
+        };
+        match prompt.handle_key(key) {
+            PathPromptKey::Editing => {
+                let status = prompt.status();
+                self.renderer.set_status_message(Some(status));
+            }
+            PathPromptKey::Cancelled => {
+                self.transfer_prompt = None;
+                self.renderer.set_status_message(None::<String>);
+            }
+            PathPromptKey::Submit(kind, path) => {
+                self.transfer_prompt = None;
+                let event = match kind {
+                    PathPromptKind::Send => match self.transfers.upload(Path::new(&path)) {
+                        Ok(offer) => Some(offer),
+                        Err(err) => {
+                            self.renderer
+                                .set_status_message(Some(format!("⇅ {path}: {err}")));
+                            None
+                        }
+                    },
+                    PathPromptKind::Receive => Some(self.transfers.download(&path)),
+                };
+                if let Some(event) = event {
+                    self.finish_transfer_step(TransferStep {
+                        replies: vec![event],
+                        notices: Vec::new(),
+                    });
                 }
             }
         }
+        self.force_render = true;
+        true
+    }
 
-        false
+    /// Sends the next chunks of accepted uploads and keeps the progress line
+    /// current.
+    fn sync_transfers(&mut self) {
+        if self.transfers.is_idle() {
+            return;
+        }
+        let step = self.transfers.pump();
+        self.finish_transfer_step(step);
     }
 
-    fn handle_tmux_prefix(&mut self, key: &KeyEvent) -> bool {
-        self.expire_tmux_prefix();
+    fn finish_transfer_step(&mut self, step: TransferStep) {
+        for event in step.replies {
+            if let Err(err) = self.transport.send_extension(
+                ExtensionDirection::ClientToHost,
+                event.to_frame(),
+                ExtensionLane::ControlOrdered,
+            ) {
+                debug!(
+                    target = "client::transfer",
+                    error = %err,
+                    "failed to send transfer frame"
+                );
+            }
+        }
+        let progress = self.transfers.progress();
+        if let Some(notice) = step.notices.last() {
+            self.renderer
+                .set_status_message(Some(format!("⇅ {}", notice.describe())));
+            self.force_render = true;
+        } else if progress.is_some()
+            && progress != self.transfer_progress
+            && self.transfer_prompt.is_none()
+            && self.copy_mode.is_none()
+        {
+            self.renderer.set_status_message(progress.clone());
+            self.force_render = true;
+        }
+        self.transfer_progress = progress;
+    }
 
-        let is_ctrl_b = matches!(key.code, KeyCode::Char(c) if c.eq_ignore_ascii_case(&'b'))
-            && key.modifiers.contains(KeyModifiers::CONTROL)
-            && !key
-                .modifiers
-                .intersects(KeyModifiers::ALT | KeyModifiers::SUPER);
+    /// Shows forwards that came up, were refused or stopped.
+    fn sync_port_forwards(&mut self) {
+        let Some(forwards) = &self.port_forwards else {
+            return;
+        };
+        let Some(notice) = forwards.take_notices().pop() else {
+            return;
+        };
+        if self.transfer_prompt.is_none() && self.copy_mode.is_none() {
+            self.renderer
+                .set_status_message(Some(format!("⇄ {notice}")));
+            self.force_render = true;
+        }
+    }
 
-        if is_ctrl_b {
-            self.tmux_prefix_started_at = Some(Instant::now());
-            return true;
+    /// Opts in to clipboard sync once the host has accepted us.
+    fn sync_clipboard(&mut self) {
+        if self.subscription_id.is_none() {
+            return;
         }
+        if let Some(hello) = self.clipboard_sync.hello() {
+            self.send_clipboard_event(hello);
+        }
+    }
 
-        if self.tmux_prefix_started_at.take().is_some() {
-            match key.code {
-                KeyCode::Char('[') => {
-                    self.enter_copy_mode();
-                    return true;
-                }
-                KeyCode::PageUp => {
-                    self.enter_copy_mode();
-                    if self.copy_mode.is_some() {
-                        self.execute_copy_mode_command(CopyModeCommand::Page { delta: -1 });
-                    }
-                    return true;
-                }
-                KeyCode::PageDown => {
-                    self.enter_copy_mode();
-                    if self.copy_mode.is_some() {
-                        self.execute_copy_mode_command(CopyModeCommand::Page { delta: 1 });
-                    }
-                    return true;
-                }
-                KeyCode::Char(']') => {
-                    self.paste_from_clipboard();
-                    return true;
-                }
-                _ => {}
+    fn apply_clipboard_notice(&mut self, notice: ClipboardNotice) {
+        let message = match notice {
+            ClipboardNotice::Granted(ClipboardMode::Off) => {
+                "📋 host declined clipboard sync".to_string()
+            }
+            ClipboardNotice::Granted(ClipboardMode::ReadOnly) => {
+                "📋 clipboard sync: receiving host copies".to_string()
+            }
+            ClipboardNotice::Granted(ClipboardMode::WriteOnly) => {
+                "📋 clipboard sync: copy-mode selections go to the host".to_string()
             }
+            ClipboardNotice::Granted(ClipboardMode::Both) => {
+                "📋 clipboard sync: both ways".to_string()
+            }
+            ClipboardNotice::Received(text) => match clipboard_set(&text) {
+                Ok(()) => format!(
+                    "📋 host clipboard copied here ({} characters)",
+                    text.chars().count()
+                ),
+                Err(err) => format!("📋 host clipboard unavailable: {err}"),
+            },
+        };
+        if self.transfer_prompt.is_none() && self.copy_mode.is_none() {
+            self.renderer.set_status_message(Some(message));
+            self.force_render = true;
         }
+    }
 
-        false
+    /// Sends `text` to the host's clipboard when the host accepts our writes.
+    fn push_clipboard_to_host(&mut self, text: &str) -> bool {
+        let Some(chunks) = self.clipboard_sync.outgoing(text) else {
+            return false;
+        };
+        for chunk in chunks {
+            self.send_clipboard_event(chunk);
+        }
+        true
+    }
+
+    fn send_clipboard_event(&self, event: ClipboardEvent) {
+        if let Err(err) = self.transport.send_extension(
+            ExtensionDirection::ClientToHost,
+            event.to_frame(),
+            ExtensionLane::ControlOrdered,
+        ) {
+            debug!(
+                target = "client::clipboard",
+                error = %err,
+                "failed to send clipboard frame"
+            );
+        }
     }
 
     fn expire_tmux_prefix(&mut self) {
@@ -2587,64 +2823,41 @@ impl TerminalClient {
             Some(state) => (state.cursor.row, state.cursor.col),
             None => return false,
         };
-        let total_rows = self.renderer.total_rows();
-        if total_rows == 0 {
+        if self.history_search_support {
+            let cursor = SelectionPosition {
+                row: start_row,
+                col: start_col,
+            };
+            let cached_hit = self
+                .search_hits
+                .as_mut()
+                .filter(|cached| {
+                    cached.pattern == pattern
+                        && cached.direction == direction
+                        && cached.at == cursor
+                })
+                .and_then(|cached| {
+                    let hit = cached.hits.pop_front()?;
+                    cached.at = SelectionPosition {
+                        row: hit.row,
+                        col: hit.col as usize,
+                    };
+                    Some(hit)
+                });
+            if let Some(hit) = cached_hit {
+                self.jump_to_search_hit(hit);
+                return true;
+            }
+            self.search_hits = None;
+            return self.request_history_search(direction, pattern, Some((start_row, start_col)));
+        }
+        if self.renderer.total_rows() == 0 {
             return false;
         }
 
-        let mut found: Option<SelectionPosition> = None;
-        match direction {
-            CopyModeSearchDirection::Forward => {
-                let mut row = start_row;
-                let last_row = total_rows.saturating_sub(1);
-                let mut first = true;
-                while row <= last_row {
-                    if let Some(text) = self.renderer.row_text(row) {
-                        let start_idx = if first {
-                            start_col.saturating_add(1)
-                        } else {
-                            0
-                        };
-                        if start_idx < text.len() {
-                            if let Some(offset) = text[start_idx..].find(pattern) {
-                                let col = start_idx + offset;
-                                found = Some(SelectionPosition { row, col });
-                                break;
-                            }
-                        }
-                    }
-                    if row == last_row {
-                        break;
-                    }
-                    row = row.saturating_add(1);
-                    first = false;
-                }
-            }
-            CopyModeSearchDirection::Backward => {
-                let mut row = start_row;
-                let lower_bound = self.renderer.base_row();
-                let mut first = true;
-                loop {
-                    if let Some(text) = self.renderer.row_text(row) {
-                        let end_idx = if first { start_col } else { text.len() };
-                        if end_idx > 0 && end_idx <= text.len() {
-                            if let Some(offset) = text[..end_idx].rfind(pattern) {
-                                found = Some(SelectionPosition { row, col: offset });
-                                break;
-                            }
-                        }
-                    }
-                    if row <= lower_bound {
-                        break;
-                    }
-                    if row == 0 {
-                        break;
-                    }
-                    row -= 1;
-                    first = false;
-                }
-            }
-        }
+        let found = self
+            .find_in_loaded_rows(direction, pattern, Some((start_row, start_col)))
+            .or_else(|| self.find_in_loaded_rows(direction, pattern, None));
 
         let pattern_owned = pattern.to_string();
         if let Some(position) = found {
@@ -2669,17 +2882,195 @@ impl TerminalClient {
         }
     }
 
+    /// Searches the rows this client has loaded, starting just past `origin`
+    /// or at the far end of the buffer when it is `None`. Rows are compared
+    /// char by char, folded the same way the host folds history, so hit
+    /// columns line up with cells.
+    fn find_in_loaded_rows(
+        &self,
+        direction: CopyModeSearchDirection,
+        pattern: &str,
+        origin: Option<(u64, usize)>,
+    ) -> Option<SelectionPosition> {
+        let case_sensitive = is_case_sensitive(pattern);
+        let fold = |ch: char| {
+            if case_sensitive {
+                ch
+            } else {
+                ch.to_lowercase().next().unwrap_or(ch)
+            }
+        };
+        let needle: Vec<char> = pattern.chars().map(fold).collect();
+        let row_chars = |row: u64| {
+            self.renderer
+                .row_text(row)
+                .map(|text| text.chars().map(fold).collect::<Vec<char>>())
+        };
+        let matches_at = |chars: &[char], col: usize| chars[col..].starts_with(&needle);
+        let first_row = self.renderer.base_row();
+        let last_row = self.renderer.total_rows().checked_sub(1)?;
+        match direction {
+            CopyModeSearchDirection::Forward => {
+                let (start_row, mut start_col) =
+                    origin.map_or((first_row, 0), |(row, col)| (row, col.saturating_add(1)));
+                for row in start_row..=last_row {
+                    let col = row_chars(row).and_then(|chars| {
+                        let last_col = chars.len().checked_sub(needle.len())?;
+                        (start_col..=last_col).find(|&col| matches_at(&chars, col))
+                    });
+                    if let Some(col) = col {
+                        return Some(SelectionPosition { row, col });
+                    }
+                    start_col = 0;
+                }
+            }
+            CopyModeSearchDirection::Backward => {
+                let (start_row, mut end_col) =
+                    origin.map_or((last_row, None), |(row, col)| (row, Some(col)));
+                for row in (first_row..=start_row.min(last_row)).rev() {
+                    let col = row_chars(row).and_then(|chars| {
+                        let end = end_col.map_or(chars.len(), |end| end.min(chars.len()));
+                        let last_col = end.checked_sub(needle.len())?;
+                        (0..=last_col).rev().find(|&col| matches_at(&chars, col))
+                    });
+                    if let Some(col) = col {
+                        return Some(SelectionPosition { row, col });
+                    }
+                    end_col = None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Asks the host to search its full history, which covers rows this client
+    /// has not backfilled yet, from just past `origin` or from the far end of
+    /// history when it is `None`. The cursor moves once `SearchResults`
+    /// arrives.
+    fn request_history_search(
+        &mut self,
+        direction: CopyModeSearchDirection,
+        pattern: &str,
+        origin: Option<(u64, usize)>,
+    ) -> bool {
+        let request_id = self.next_search_request_id;
+        self.next_search_request_id = self.next_search_request_id.saturating_add(1);
+        let frame = WireClientFrame::Search {
+            request_id,
+            pattern: pattern.to_string(),
+            case_sensitive: is_case_sensitive(pattern),
+            backward: direction == CopyModeSearchDirection::Backward,
+            from_row: origin.map(|(row, _)| row),
+            from_col: origin.map_or(0, |(_, col)| col as u32),
+            max_results: HISTORY_SEARCH_BATCH,
+        };
+        let bytes = protocol::encode_client_frame_binary(&frame);
+        if let Err(err) = self.transport.send_bytes(&bytes) {
+            debug!(
+                target = "client::search",
+                request_id,
+                error = %err,
+                "failed to send history search request"
+            );
+            return false;
+        }
+        trace!(
+            target = "client::search",
+            request_id,
+            origin = ?origin,
+            "requested history search"
+        );
+        if let Some(state) = self.copy_mode.as_mut() {
+            state.last_search = Some(CopyModeSearch {
+                direction,
+                pattern: pattern.to_string(),
+            });
+        }
+        self.pending_search = Some(PendingHistorySearch {
+            id: request_id,
+            pattern: pattern.to_string(),
+            direction,
+            wrapped: origin.is_none(),
+        });
+        true
+    }
+
+    fn handle_search_results(&mut self, request_id: u64, hits: Vec<SearchHit>) {
+        let pending = match self.pending_search.take() {
+            Some(pending) if pending.id == request_id => pending,
+            other => {
+                self.pending_search = other;
+                trace!(
+                    target = "client::search",
+                    request_id, "ignoring stale search results"
+                );
+                return;
+            }
+        };
+        if self.copy_mode.is_none() {
+            return;
+        }
+        let mut hits = VecDeque::from(hits);
+        let Some(hit) = hits.pop_front() else {
+            // Nothing past the cursor: wrap around to the far end once.
+            if pending.wrapped
+                || !self.request_history_search(pending.direction, &pending.pattern, None)
+            {
+                let pattern = pending.pattern;
+                self.renderer
+                    .set_status_message(Some(format!(r#"copy-mode: "{pattern}" not found"#)));
+                self.force_render = true;
+            }
+            return;
+        };
+        self.search_hits = Some(HistorySearchHits {
+            pattern: pending.pattern,
+            direction: pending.direction,
+            at: SelectionPosition {
+                row: hit.row,
+                col: hit.col as usize,
+            },
+            hits,
+        });
+        self.jump_to_search_hit(hit);
+    }
+
+    fn jump_to_search_hit(&mut self, hit: SearchHit) {
+        if let Some(state) = self.copy_mode.as_mut() {
+            state.selection_active = false;
+        }
+        let position = SelectionPosition {
+            row: hit.row,
+            col: hit.col as usize,
+        };
+        self.set_copy_cursor_position(position, true);
+        self.update_copy_mode_status();
+        self.force_render = true;
+    }
+
     fn set_copy_cursor_position(&mut self, position: SelectionPosition, ensure_visible: bool) {
-        let (selection_active, anchor, mode) = match self.copy_mode.as_mut() {
+        let (selection_active, anchor, mode, previous_row) = match self.copy_mode.as_mut() {
             Some(state) => {
+                let previous_row = state.cursor.row;
                 state.cursor = position;
                 if !state.selection_active {
                     state.anchor = position;
                 }
-                (state.selection_active, state.anchor, state.selection_mode)
+                (
+                    state.selection_active,
+                    state.anchor,
+                    state.selection_mode,
+                    previous_row,
+                )
             }
             None => return,
         };
+        if previous_row != position.row
+            && (self.chat.notes_at(previous_row).next().is_some()
+                || self.chat.notes_at(position.row).next().is_some())
+        {
+            self.update_copy_mode_status();
+        }
 
         if selection_active {
             self.renderer.set_selection(anchor, position, mode);
@@ -2706,6 +3097,7 @@ impl TerminalClient {
     }
 
     fn update_copy_mode_status(&mut self) {
+        self.refresh_chat();
         let Some(state) = &self.copy_mode else {
             self.renderer.set_status_message::<String>(None);
             return;
@@ -2714,18 +3106,30 @@ impl TerminalClient {
             self.update_copy_mode_prompt();
             return;
         }
-        let main = String::from("scrollback");
+        let mut main = String::from("scrollback");
+        for note in self.chat.notes_at(state.cursor.row) {
+            main.push_str(" • note ");
+            main.push_str(&format_message(note));
+        }
         let mut highlight = String::from("ESC exit");
         if state.selection_active {
             highlight.push_str(" • CTRL+C copy");
         }
-        highlight.push_str(" • CTRL+Q quit");
+        if let Some(hint) = self.detach_hint() {
+            highlight.push_str(" • ");
+            highlight.push_str(&hint);
+        }
         self.renderer
             .set_status_with_highlight(Some(main), Some(highlight));
         self.force_render = true;
     }
 
     fn handle_mouse_event(&mut self, mouse: &MouseEvent) -> Result<bool, ClientError> {
+        // Outside copy mode the host application owns the mouse once it has
+        // enabled tracking, including the wheel.
+        if self.copy_mode.is_none() && self.host_modes.mouse_tracking != MouseTracking::Off {
+            return Ok(false);
+        }
         match mouse.kind {
             MouseEventKind::ScrollUp => {
                 self.handle_mouse_scroll(-MOUSE_SCROLL_LINES);
@@ -2762,6 +3166,14 @@ impl TerminalClient {
         self.apply_scroll_delta(delta, true);
     }
 
+    /// Captures the mouse while copy mode needs it or the host application has
+    /// asked for mouse reports.
+    fn sync_mouse_capture(&mut self) {
+        let wanted =
+            self.copy_mode.is_some() || self.host_modes.mouse_tracking != MouseTracking::Off;
+        self.set_mouse_capture(wanted);
+    }
+
     fn set_mouse_capture(&mut self, enabled: bool) {
         if !self.render_enabled || self.mouse_capture_enabled == enabled {
             return;
@@ -2792,6 +3204,48 @@ impl TerminalClient {
         }
     }
 
+    /// Asks the local terminal for the key reporting the host application
+    /// negotiated, so re-encoded events can carry Ctrl+Shift+letter, releases
+    /// and the like. Terminals without the kitty protocol keep legacy events.
+    fn sync_keyboard_enhancement(&mut self) {
+        if !self.render_enabled || self.tui.is_none() {
+            return;
+        }
+        let wanted = local_keyboard_flags(&self.host_modes);
+        if wanted == self.keyboard_enhancement {
+            return;
+        }
+        if wanted.is_some()
+            && !*self
+                .keyboard_enhancement_supported
+                .get_or_insert_with(|| supports_keyboard_enhancement().unwrap_or(false))
+        {
+            return;
+        }
+
+        let mut stdout = io::stdout();
+        let result = self
+            .keyboard_enhancement
+            .map_or(Ok(()), |_| execute!(stdout, PopKeyboardEnhancementFlags))
+            .and_then(|()| match wanted {
+                Some(flags) => execute!(stdout, PushKeyboardEnhancementFlags(flags)),
+                None => Ok(()),
+            });
+        match result {
+            Ok(()) => self.keyboard_enhancement = wanted,
+            Err(err) => {
+                let err_text = err.to_string();
+                debug!(
+                    target = "client::keyboard",
+                    error = %err_text,
+                    "failed to update keyboard enhancement flags"
+                );
+                self.keyboard_enhancement = None;
+                self.show_error_status(format!("keyboard protocol: {err_text}"));
+            }
+        }
+    }
+
     fn show_error_status<S: Into<String>>(&mut self, message: S) {
         self.renderer.set_status_error_message(Some(message.into()));
         self.force_render = true;
@@ -2825,22 +3279,37 @@ impl TerminalClient {
 
     fn tail_highlight_text(&self) -> String {
         let mut parts: Vec<String> = Vec::new();
-        if self.scroll_double_esc_enabled {
+        if self.keymap.double_esc() {
             parts.push("ESC ESC to scrollback".to_string());
         }
-        if let Some(binding) = self.scroll_toggle.first() {
+        if let Some(binding) = self
+            .keymap
+            .root_bindings(ClientAction::ToggleScrollback)
+            .first()
+        {
             parts.push(format!("{} to scrollback", format_key_binding(binding)));
         }
-        parts.push("CTRL+Q quit".to_string());
+        parts.extend(self.detach_hint());
         parts.join(" • ")
     }
 
+    fn detach_hint(&self) -> Option<String> {
+        self.keymap
+            .root_bindings(ClientAction::Detach)
+            .first()
+            .map(|binding| format!("{} quit", format_key_binding(binding)))
+    }
+
     fn apply_scrollback_status(&mut self) {
         if self.copy_mode.is_some() {
             self.update_copy_mode_status();
         } else {
             let text = self.scrollback_base_status();
-            let highlight = "ESC exit • CTRL+Q quit".to_string();
+            let mut highlight = "ESC exit".to_string();
+            if let Some(hint) = self.detach_hint() {
+                highlight.push_str(" • ");
+                highlight.push_str(&hint);
+            }
             self.renderer
                 .set_status_with_highlight(Some(text), Some(highlight));
             self.force_render = true;
@@ -2959,17 +3428,10 @@ impl TerminalClient {
         }
 
         let mut triggered = false;
-
-        if self
-            .scroll_toggle
-            .iter()
-            .any(|binding| binding.matches(key))
-        {
+
+        if self.keymap.root_action(key) == Some(ClientAction::ToggleScrollback) {
             triggered = true;
-        } else if self.scroll_double_esc_enabled
-            && key.code == KeyCode::Esc
-            && key.modifiers.is_empty()
-        {
+        } else if self.keymap.double_esc() && key.code == KeyCode::Esc && key.modifiers.is_empty() {
             let now = Instant::now();
             if let Some(last) = self.last_plain_esc {
                 if now.saturating_duration_since(last) <= SCROLL_TOGGLE_DOUBLE_ESC {
@@ -2990,14 +3452,18 @@ impl TerminalClient {
         }
 
         self.last_plain_esc = None;
+        self.toggle_scrollback();
+        Ok(true)
+    }
 
+    fn toggle_scrollback(&mut self) {
         if self.copy_mode.is_some() {
             self.exit_copy_mode();
             self.renderer.scroll_to_tail();
             self.renderer.set_follow_tail(true);
             self.set_view_mode(ViewMode::Tail);
             self.force_render = true;
-            return Ok(true);
+            return;
         }
 
         if matches!(self.view_mode, ViewMode::Scrollback) || !self.renderer.is_following_tail() {
@@ -3005,7 +3471,7 @@ impl TerminalClient {
             self.renderer.set_follow_tail(true);
             self.set_view_mode(ViewMode::Tail);
             self.force_render = true;
-            return Ok(true);
+            return;
         }
 
         self.renderer.set_follow_tail(false);
@@ -3017,7 +3483,6 @@ impl TerminalClient {
         } else {
             self.set_view_mode(ViewMode::Scrollback);
         }
-        Ok(true)
     }
 
     fn handle_mouse_primary_down(&mut self, mouse: &MouseEvent) {
@@ -3201,7 +3666,8 @@ impl TerminalClient {
 
     fn exit_copy_mode(&mut self) {
         if self.copy_mode.take().is_some() {
-            self.set_mouse_capture(false);
+            self.refresh_chat();
+            self.sync_mouse_capture();
             self.renderer.clear_selection();
             self.renderer.set_follow_tail(true);
             self.renderer.mark_dirty();
@@ -3390,10 +3856,11 @@ impl TerminalClient {
             return;
         };
 
+        let pushed = self.push_clipboard_to_host(&text);
         match clipboard_set(&text) {
             Ok(()) => {
                 let line_count = text.lines().count();
-                let message = if line_count > 1 {
+                let mut message = if line_count > 1 {
                     format!("copied {line_count} lines to clipboard")
                 } else {
                     let char_count = text.chars().count();
@@ -3403,6 +3870,9 @@ impl TerminalClient {
                         format!("copied {char_count} characters to clipboard")
                     }
                 };
+                if pushed {
+                    message.push_str(" and the host's");
+                }
 
                 if exit_after {
                     self.exit_copy_mode();
@@ -3416,40 +3886,15 @@ impl TerminalClient {
                 if exit_after {
                     self.exit_copy_mode();
                 }
-                self.show_error_status(format!("copy failed: {}", err));
-            }
-        }
-    }
-
-    fn handle_control_shortcuts(&mut self, key: &KeyEvent) -> Result<bool, ClientError> {
-        if !key.modifiers.contains(KeyModifiers::CONTROL) {
-            return Ok(false);
-        }
-        match key.code {
-            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'q') => {
-                return Err(ClientError::Shutdown);
-            }
-            _ => {}
-        }
-        Ok(false)
-    }
-
-    fn handle_super_shortcuts(&mut self, key: &KeyEvent) -> Result<bool, ClientError> {
-        if !key.modifiers.contains(KeyModifiers::SUPER) {
-            return Ok(false);
-        }
-
-        match key.code {
-            KeyCode::Char(c) if c.eq_ignore_ascii_case(&'k') => {
-                self.force_render = true;
-                self.renderer.mark_dirty();
-                self.request_viewport_clear()?;
-                return Ok(true);
+                if pushed {
+                    self.show_error_status(format!(
+                        "copied to the host's clipboard; local copy failed: {err}"
+                    ));
+                } else {
+                    self.show_error_status(format!("copy failed: {}", err));
+                }
             }
-            _ => {}
         }
-
-        Ok(false)
     }
 
     fn handle_local_key(&mut self, key: &KeyEvent) -> bool {
@@ -3524,12 +3969,48 @@ impl TerminalClient {
                 "input sent"
             );
         }
+        self.presence.note_input(peer_presence::now_ms());
         if allow_predictions {
             self.register_prediction(self.input_seq, bytes);
         }
         Ok(())
     }
 
+    /// Publishes this client's copy-mode cursor, selection and typing time
+    /// when they change, and refreshes the other peers' overlays.
+    fn sync_presence(&mut self) {
+        if !self.presence.enabled() || self.subscription_id.is_none() {
+            return;
+        }
+        let (cursor, selection) = match &self.copy_mode {
+            Some(state) => (
+                Some(state.cursor),
+                state.selection_active.then_some((
+                    state.anchor,
+                    state.cursor,
+                    state.selection_mode,
+                )),
+            ),
+            None => (None, None),
+        };
+        if let Some(state) = self.presence.next_update(cursor, selection) {
+            let frame = PresenceEvent::Update { peer: 0, state }.to_frame();
+            if let Err(err) = self.transport.send_extension(
+                ExtensionDirection::ClientToHost,
+                frame,
+                ExtensionLane::StateUnordered,
+            ) {
+                debug!(
+                    target = "client::presence",
+                    error = %err,
+                    "failed to publish presence"
+                );
+            }
+        }
+        self.renderer
+            .set_peer_overlays(self.presence.overlays(peer_presence::now_ms()));
+    }
+
     fn set_authorization_state(&mut self, state: AuthorizationState, message: Option<String>) {
         if self.authorization_state == state && self.authorization_message == message {
             return;
@@ -4905,8 +5386,13 @@ impl TerminalClient {
         enable_raw_mode()
             .map_err(|err| ClientError::Transport(TransportError::Setup(err.to_string())))?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)
-            .map_err(|err| ClientError::Transport(TransportError::Setup(err.to_string())))?;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            EnableFocusChange
+        )
+        .map_err(|err| ClientError::Transport(TransportError::Setup(err.to_string())))?;
         self.mouse_capture_enabled = false;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)
@@ -4926,6 +5412,8 @@ impl TerminalClient {
         self.renderer.mark_dirty();
         self.force_render = true;
         self.tui = Some(terminal);
+        self.sync_mouse_capture();
+        self.sync_keyboard_enhancement();
         Ok(())
     }
 
@@ -4933,6 +5421,9 @@ impl TerminalClient {
         if !self.render_enabled {
             return Ok(());
         }
+        if self.keyboard_enhancement.take().is_some() {
+            execute!(io::stdout(), PopKeyboardEnhancementFlags).ok();
+        }
         if let Some(mut terminal) = self.tui.take() {
             terminal.show_cursor().ok();
             terminal
@@ -4945,6 +5436,7 @@ impl TerminalClient {
         execute!(
             stdout,
             DisableMouseCapture,
+            DisableFocusChange,
             DisableBracketedPaste,
             LeaveAlternateScreen
         )
@@ -5085,49 +5577,6 @@ enum CursorHint {
     Exact(usize, usize),
 }
 
-fn encode_key_event(key: KeyEvent) -> Option<Vec<u8>> {
-    match key.code {
-        KeyCode::Char(c) => {
-            let mut bytes = Vec::new();
-            if key.modifiers.contains(KeyModifiers::ALT) {
-                bytes.push(0x1b);
-            }
-            if key.modifiers.contains(KeyModifiers::CONTROL) {
-                let lower = c.to_ascii_lowercase();
-                if lower.is_ascii_lowercase() {
-                    bytes.push((lower as u8 - b'a') + 1);
-                } else {
-                    return None;
-                }
-            } else {
-                bytes.push(c as u8);
-            }
-            Some(bytes)
-        }
-        KeyCode::Enter => {
-            // Send LF by default to be robust against PTY configurations that
-            // don't map CR->NL on input. This avoids the "carriage return"
-            // behavior where Enter returns to column 0 without advancing the
-            // line on some hosts.
-            Some(vec![b'\n'])
-        }
-        KeyCode::Tab => Some(vec![b'\t']),
-        KeyCode::Backspace => Some(vec![0x7f]),
-        KeyCode::Esc => Some(vec![0x1b]),
-        KeyCode::Up => Some(b"\x1b[A".to_vec()),
-        KeyCode::Down => Some(b"\x1b[B".to_vec()),
-        KeyCode::Right => Some(b"\x1b[C".to_vec()),
-        KeyCode::Left => Some(b"\x1b[D".to_vec()),
-        KeyCode::Home => Some(b"\x1b[H".to_vec()),
-        KeyCode::End => Some(b"\x1b[F".to_vec()),
-        KeyCode::PageUp => Some(b"\x1b[5~".to_vec()),
-        KeyCode::PageDown => Some(b"\x1b[6~".to_vec()),
-        KeyCode::Delete => Some(b"\x1b[3~".to_vec()),
-        KeyCode::Insert => Some(b"\x1b[2~".to_vec()),
-        _ => None,
-    }
-}
-
 #[allow(dead_code)]
 fn is_copy_shortcut(key: &KeyEvent) -> bool {
     match key.code {
@@ -5142,181 +5591,6 @@ fn is_copy_shortcut(key: &KeyEvent) -> bool {
     }
 }
 
-fn default_copy_shortcut_bindings() -> Vec<KeyBinding> {
-    vec![
-        // Cmd/Ctrl(OS) + C
-        KeyBinding::new(KeyCode::Char('c'), KeyModifiers::SUPER),
-        // Ctrl+Shift+C (common in terminals)
-        KeyBinding::new(
-            KeyCode::Char('c'),
-            KeyModifiers::CONTROL.union(KeyModifiers::SHIFT),
-        ),
-        // Ctrl+Insert (Windows)
-        KeyBinding::new(KeyCode::Insert, KeyModifiers::CONTROL),
-        // Plain Ctrl+C while in copy-mode
-        KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
-    ]
-}
-
-fn copy_mode_command_for_key(
-    mode: CopyModeKeySet,
-    selection_active: bool,
-    key: &KeyEvent,
-) -> Option<CopyModeCommand> {
-    if key.modifiers.contains(KeyModifiers::ALT) {
-        if let KeyCode::Char(c) = key.code {
-            match c.to_ascii_lowercase() {
-                'y' => return Some(CopyModeCommand::CopySelectionAndExit),
-                'c' => return Some(CopyModeCommand::ClearSelection),
-                'v' => return Some(CopyModeCommand::SetMode(CopyModeKeySet::Vi)),
-                'e' => return Some(CopyModeCommand::SetMode(CopyModeKeySet::Emacs)),
-                ']' | '}' => return Some(CopyModeCommand::Cancel),
-                _ => {}
-            }
-        }
-    }
-
-    if key.modifiers.contains(KeyModifiers::CONTROL) {
-        if let KeyCode::Char(c) = key.code {
-            if c.eq_ignore_ascii_case(&'g') {
-                return Some(CopyModeCommand::Cancel);
-            }
-        }
-    }
-
-    match key.code {
-        KeyCode::Esc => Some(CopyModeCommand::Cancel),
-        KeyCode::Enter => Some(CopyModeCommand::CopySelectionAndExit),
-        KeyCode::Up => Some(CopyModeCommand::Move { rows: -1, cols: 0 }),
-        KeyCode::Down => Some(CopyModeCommand::Move { rows: 1, cols: 0 }),
-        KeyCode::Left => Some(CopyModeCommand::Move { rows: 0, cols: -1 }),
-        KeyCode::Right => Some(CopyModeCommand::Move { rows: 0, cols: 1 }),
-        KeyCode::PageUp => Some(CopyModeCommand::Page { delta: -1 }),
-        KeyCode::PageDown => Some(CopyModeCommand::Page { delta: 1 }),
-        KeyCode::Home => Some(CopyModeCommand::MoveToLineStart),
-        KeyCode::End => Some(CopyModeCommand::MoveToLineEnd),
-        _ => match mode {
-            CopyModeKeySet::Vi => copy_mode_command_vi(selection_active, key),
-            CopyModeKeySet::Emacs => copy_mode_command_emacs(selection_active, key),
-        },
-    }
-}
-
-fn copy_mode_command_vi(selection_active: bool, key: &KeyEvent) -> Option<CopyModeCommand> {
-    if key.modifiers.contains(KeyModifiers::CONTROL) {
-        if let KeyCode::Char(c) = key.code {
-            return match c.to_ascii_lowercase() {
-                'b' => Some(CopyModeCommand::Page { delta: -1 }),
-                'f' => Some(CopyModeCommand::Page { delta: 1 }),
-                'u' => Some(CopyModeCommand::HalfPage { delta: -1 }),
-                'd' => Some(CopyModeCommand::HalfPage { delta: 1 }),
-                'c' => Some(CopyModeCommand::Cancel),
-                'v' => Some(CopyModeCommand::SetSelectionMode(SelectionMode::Block)),
-                _ => None,
-            };
-        }
-    }
-
-    match key.code {
-        KeyCode::Char('G') => Some(CopyModeCommand::JumpBottom),
-        KeyCode::Char('N') => Some(CopyModeCommand::RepeatLastSearch(
-            CopyModeSearchDirection::Backward,
-        )),
-        KeyCode::Char('0') => Some(CopyModeCommand::MoveToLineStart),
-        KeyCode::Char('$') => Some(CopyModeCommand::MoveToLineEnd),
-        KeyCode::Char('^') => Some(CopyModeCommand::MoveToLineStart),
-        KeyCode::Char('V') => Some(CopyModeCommand::SetSelectionMode(SelectionMode::Line)),
-        KeyCode::Char(' ') => {
-            if selection_active {
-                Some(CopyModeCommand::ToggleSelection)
-            } else {
-                Some(CopyModeCommand::BeginSelection)
-            }
-        }
-        KeyCode::Char('/') => Some(CopyModeCommand::Search(CopyModeSearchDirection::Forward)),
-        KeyCode::Char('?') => Some(CopyModeCommand::Search(CopyModeSearchDirection::Backward)),
-        KeyCode::Char(c) => {
-            let lower = c.to_ascii_lowercase();
-            match lower {
-                'h' => Some(CopyModeCommand::Move { rows: 0, cols: -1 }),
-                'j' => Some(CopyModeCommand::Move { rows: 1, cols: 0 }),
-                'k' => Some(CopyModeCommand::Move { rows: -1, cols: 0 }),
-                'l' => Some(CopyModeCommand::Move { rows: 0, cols: 1 }),
-                'g' => Some(CopyModeCommand::JumpTop),
-                'w' => Some(CopyModeCommand::MoveWord(WordMotion::NextStart)),
-                'e' => Some(CopyModeCommand::MoveWord(WordMotion::NextEnd)),
-                'b' => Some(CopyModeCommand::MoveWord(WordMotion::PrevStart)),
-                'y' => Some(CopyModeCommand::CopySelectionAndExit),
-                'v' => Some(CopyModeCommand::ToggleSelection),
-                'q' => Some(CopyModeCommand::Cancel),
-                'n' => Some(CopyModeCommand::RepeatLastSearch(
-                    CopyModeSearchDirection::Forward,
-                )),
-                _ => None,
-            }
-        }
-        _ => None,
-    }
-}
-
-fn copy_mode_command_emacs(selection_active: bool, key: &KeyEvent) -> Option<CopyModeCommand> {
-    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
-    let alt = key.modifiers.contains(KeyModifiers::ALT);
-
-    if ctrl {
-        if let KeyCode::Char(c) = key.code {
-            return match c.to_ascii_lowercase() {
-                'f' => Some(CopyModeCommand::Move { rows: 0, cols: 1 }),
-                'b' => Some(CopyModeCommand::Move { rows: 0, cols: -1 }),
-                'n' => Some(CopyModeCommand::Move { rows: 1, cols: 0 }),
-                'p' => Some(CopyModeCommand::Move { rows: -1, cols: 0 }),
-                'a' => Some(CopyModeCommand::MoveToLineStart),
-                'e' => Some(CopyModeCommand::MoveToLineEnd),
-                'v' => Some(CopyModeCommand::Page { delta: 1 }),
-                'w' => Some(CopyModeCommand::CopySelectionAndExit),
-                'y' => Some(CopyModeCommand::CopySelection),
-                's' => Some(CopyModeCommand::Search(CopyModeSearchDirection::Forward)),
-                'r' => Some(CopyModeCommand::Search(CopyModeSearchDirection::Backward)),
-                'g' => Some(CopyModeCommand::Cancel),
-                ' ' => {
-                    if selection_active {
-                        Some(CopyModeCommand::ToggleSelection)
-                    } else {
-                        Some(CopyModeCommand::BeginSelection)
-                    }
-                }
-                _ => None,
-            };
-        }
-    }
-
-    if alt {
-        if let KeyCode::Char(c) = key.code {
-            return match c.to_ascii_lowercase() {
-                'f' => Some(CopyModeCommand::MoveWord(WordMotion::NextStart)),
-                'b' => Some(CopyModeCommand::MoveWord(WordMotion::PrevStart)),
-                'd' => Some(CopyModeCommand::MoveWord(WordMotion::NextEnd)),
-                'v' => Some(CopyModeCommand::Page { delta: -1 }),
-                'w' => Some(CopyModeCommand::CopySelection),
-                'y' => Some(CopyModeCommand::CopySelectionAndExit),
-                _ => None,
-            };
-        }
-    }
-
-    match key.code {
-        KeyCode::Char(' ') => {
-            if selection_active {
-                Some(CopyModeCommand::ToggleSelection)
-            } else {
-                Some(CopyModeCommand::BeginSelection)
-            }
-        }
-        KeyCode::Char('y') => Some(CopyModeCommand::CopySelection),
-        _ => None,
-    }
-}
-
 fn default_copy_mode_keyset() -> CopyModeKeySet {
     match env::var(COPY_MODE_KEYSET_ENV) {
         Ok(value) if value.eq_ignore_ascii_case("emacs") => CopyModeKeySet::Emacs,
@@ -5324,6 +5598,11 @@ fn default_copy_mode_keyset() -> CopyModeKeySet {
     }
 }
 
+/// Smart case: a pattern with an uppercase letter matches case exactly.
+fn is_case_sensitive(pattern: &str) -> bool {
+    pattern.chars().any(char::is_uppercase)
+}
+
 fn reverse_search_direction(direction: CopyModeSearchDirection) -> CopyModeSearchDirection {
     match direction {
         CopyModeSearchDirection::Forward => CopyModeSearchDirection::Backward,
@@ -5399,42 +5678,36 @@ fn find_prev_word_start_in_line(chars: &[char], start_col: Option<usize>) -> Opt
     Some(idx)
 }
 
-fn is_word_char(ch: char) -> bool {
-    ch.is_alphanumeric() || ch == '_'
-}
-
-fn encode_mouse_event(mouse: &MouseEvent) -> Option<Vec<u8>> {
-    let (mut code, suffix) = match mouse.kind {
-        MouseEventKind::Down(button) => (mouse_button_code(button)?, 'M'),
-        MouseEventKind::Up(_) => (3, 'm'),
-        MouseEventKind::Drag(button) => (mouse_button_code(button)? + 32, 'M'),
-        MouseEventKind::ScrollUp => (64, 'M'),
-        MouseEventKind::ScrollDown => (65, 'M'),
-        _ => return None,
-    };
-
-    if mouse.modifiers.contains(KeyModifiers::SHIFT) {
-        code += 4;
-    }
-    if mouse.modifiers.contains(KeyModifiers::ALT) {
-        code += 8;
-    }
-    if mouse.modifiers.contains(KeyModifiers::CONTROL) {
-        code += 16;
+/// Local crossterm flags needed to re-encode keys in the host's protocol.
+fn local_keyboard_flags(modes: &TerminalModesFrame) -> Option<KeyboardEnhancementFlags> {
+    if modes.kitty_keyboard == 0 {
+        return (modes.modify_other_keys != ModifyOtherKeys::Off)
+            .then_some(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES);
+    }
+    let mut flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+    for (bit, local) in [
+        (
+            KITTY_KEYBOARD_REPORT_EVENTS,
+            KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+        ),
+        (
+            KITTY_KEYBOARD_REPORT_ALTERNATES,
+            KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS,
+        ),
+        (
+            KITTY_KEYBOARD_REPORT_ALL_KEYS,
+            KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES,
+        ),
+    ] {
+        if modes.kitty_keyboard & bit != 0 {
+            flags |= local;
+        }
     }
-
-    let column = mouse.column.saturating_add(1);
-    let row = mouse.row.saturating_add(1);
-    let sequence = format!("\u{1b}[<{code};{column};{row}{suffix}");
-    Some(sequence.into_bytes())
+    Some(flags)
 }
 
-fn mouse_button_code(button: MouseButton) -> Option<u16> {
-    match button {
-        MouseButton::Left => Some(0),
-        MouseButton::Middle => Some(1),
-        MouseButton::Right => Some(2),
-    }
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
 }
 
 #[cfg(test)]
@@ -5925,6 +6198,39 @@ mod tests {
         assert_eq!(cursor.col, 4);
     }
 
+    #[test]
+    fn loaded_row_search_folds_unicode_and_reports_char_columns() {
+        let mut client = new_client();
+        client.renderer.ensure_size(3, 32);
+        client.renderer.apply_row_from_text(0, 1, "café ÉTÉ été");
+
+        let find = |direction, pattern: &str, origin| {
+            client
+                .find_in_loaded_rows(direction, pattern, origin)
+                .map(|position| (position.row, position.col))
+        };
+        assert_eq!(
+            find(CopyModeSearchDirection::Forward, "été", None),
+            Some((0, 5))
+        );
+        assert_eq!(
+            find(CopyModeSearchDirection::Forward, "été", Some((0, 5))),
+            Some((0, 9))
+        );
+        assert_eq!(
+            find(CopyModeSearchDirection::Backward, "été", Some((0, 9))),
+            Some((0, 5))
+        );
+        assert_eq!(
+            find(CopyModeSearchDirection::Forward, "ÉTÉ", Some((0, 5))),
+            None
+        );
+        assert_eq!(
+            find(CopyModeSearchDirection::Forward, "é ", Some((0, 0))),
+            Some((0, 3))
+        );
+    }
+
     #[test]
     fn vi_half_page_motions_respect_viewport() {
         let mut client = new_client();
@@ -6077,7 +6383,9 @@ mod tests {
         let before_top = client.renderer.viewport_top();
         assert!(client.copy_mode.is_none());
 
-        client.process_copy_mode_key(&key(KeyCode::PageUp, KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::PageUp, KeyModifiers::NONE))
+            .unwrap();
 
         assert!(client.copy_mode.is_some(), "PageUp should enter copy mode");
         let viewport = client.renderer.viewport_height() as u64;
@@ -6110,7 +6418,9 @@ mod tests {
         client.renderer.scroll_to_tail();
 
         let before_top = client.renderer.viewport_top();
-        client.process_copy_mode_key(&key(KeyCode::PageUp, KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::PageUp, KeyModifiers::NONE))
+            .unwrap();
 
         let viewport = client.renderer.viewport_height() as u64;
         let expected_top = before_top
@@ -6133,10 +6443,14 @@ mod tests {
         }
         client.renderer.scroll_to_tail();
 
-        client.process_copy_mode_key(&key(KeyCode::PageUp, KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::PageUp, KeyModifiers::NONE))
+            .unwrap();
         assert!(client.copy_mode.is_some());
 
-        client.process_copy_mode_key(&key(KeyCode::PageDown, KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::PageDown, KeyModifiers::NONE))
+            .unwrap();
 
         assert!(
             client.copy_mode.is_none(),
@@ -6158,13 +6472,17 @@ mod tests {
         client.renderer.scroll_to_tail();
 
         assert!(client.copy_mode.is_none());
-        client.process_copy_mode_key(&key(KeyCode::Char('b'), KeyModifiers::CONTROL));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
         assert!(
             client.copy_mode.is_none(),
             "prefix should not enter copy mode yet"
         );
 
-        client.process_copy_mode_key(&key(KeyCode::Char('['), KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('['), KeyModifiers::NONE))
+            .unwrap();
         assert!(
             client.copy_mode.is_some(),
             "Ctrl-B [ should enter copy mode"
@@ -6185,8 +6503,12 @@ mod tests {
 
         let before_top = client.renderer.viewport_top();
 
-        client.process_copy_mode_key(&key(KeyCode::Char('b'), KeyModifiers::CONTROL));
-        client.process_copy_mode_key(&key(KeyCode::PageUp, KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::PageUp, KeyModifiers::NONE))
+            .unwrap();
 
         assert!(client.copy_mode.is_some());
         let viewport = client.renderer.viewport_height() as u64;
@@ -6206,11 +6528,15 @@ mod tests {
         }
         client.renderer.scroll_to_tail();
 
-        client.process_copy_mode_key(&key(KeyCode::Char('b'), KeyModifiers::CONTROL));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
         client.tmux_prefix_started_at =
             Some(Instant::now() - TMUX_PREFIX_TIMEOUT - Duration::from_millis(1));
 
-        client.process_copy_mode_key(&key(KeyCode::Char('['), KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('['), KeyModifiers::NONE))
+            .unwrap();
         assert!(
             client.copy_mode.is_none(),
             "expired prefix should not enter copy mode"
@@ -6224,8 +6550,12 @@ mod tests {
         let mut client = TerminalClient::new(transport.clone()).with_render(false);
 
         client.subscription_id = Some(1);
-        client.process_copy_mode_key(&key(KeyCode::Char('b'), KeyModifiers::CONTROL));
-        client.process_copy_mode_key(&key(KeyCode::Char(']'), KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::Char(']'), KeyModifiers::NONE))
+            .unwrap();
 
         let frames = transport.take();
         assert_eq!(frames.len(), 1, "expected single paste frame");
@@ -6245,8 +6575,12 @@ mod tests {
         let mut client = TerminalClient::new(transport.clone()).with_render(false);
 
         client.subscription_id = Some(1);
-        client.process_copy_mode_key(&key(KeyCode::Char('b'), KeyModifiers::CONTROL));
-        client.process_copy_mode_key(&key(KeyCode::Char(']'), KeyModifiers::NONE));
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::Char(']'), KeyModifiers::NONE))
+            .unwrap();
 
         assert!(
             transport.take().is_empty(),
@@ -6254,6 +6588,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn key_bindings_run_prefix_and_root_actions() {
+        let mut client = new_client();
+        client.keymap = Keymap::default();
+        assert!(!client.predictive_input);
+
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::Char('p'), KeyModifiers::NONE))
+            .unwrap();
+        assert!(client.predictive_input);
+        let (status, _) = client.renderer.status_for_test();
+        assert_eq!(status.as_deref(), Some("predictive echo on"));
+
+        // Without the prefix, `p` is ordinary input.
+        assert!(
+            !client
+                .handle_key_bindings(&key(KeyCode::Char('p'), KeyModifiers::NONE))
+                .unwrap()
+        );
+        assert!(matches!(
+            client.handle_key_bindings(&key(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            Err(ClientError::Shutdown)
+        ));
+    }
+
+    #[test]
+    fn chat_prefix_posts_messages_and_copy_mode_shows_notes() {
+        use crate::protocol::chat::ChatMessage;
+
+        let transport: Arc<RecordingTransport> = Arc::new(RecordingTransport::default());
+        let mut client = TerminalClient::new(transport.clone()).with_render(false);
+        client.keymap = Keymap::default();
+
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::Char('c'), KeyModifiers::NONE))
+            .unwrap();
+        for ch in "hi".chars() {
+            assert!(
+                client
+                    .handle_chat_key(&key(KeyCode::Char(ch), KeyModifiers::NONE))
+                    .unwrap()
+            );
+        }
+        client
+            .handle_chat_key(&key(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
+        assert!(
+            !client
+                .handle_chat_key(&key(KeyCode::Char('x'), KeyModifiers::NONE))
+                .unwrap()
+        );
+
+        let frames = transport.take();
+        assert_eq!(frames.len(), 1, "expected a single chat frame");
+        match protocol::decode_client_frame_binary(&frames[0]).expect("decode chat frame") {
+            WireClientFrame::Extension { frame } => match ChatEvent::from_frame(&frame) {
+                Some(ChatEvent::Post(post)) => {
+                    assert_eq!(post.text, "hi");
+                    assert_eq!(post.row, None);
+                }
+                other => panic!("unexpected chat event {other:?}"),
+            },
+            other => panic!("unexpected frame {other:?}"),
+        }
+
+        client.handle_extension_frame(
+            ChatEvent::Message(ChatMessage {
+                id: 1,
+                author: "ana".into(),
+                text: "flaky here".into(),
+                sent_ms: 0,
+                row: Some(3),
+            })
+            .to_frame(),
+        );
+        client.copy_mode = Some(CopyModeState::new(
+            SelectionPosition { row: 3, col: 0 },
+            CopyModeKeySet::Vi,
+        ));
+        client.update_copy_mode_status();
+        let (status, _) = client.renderer.status_for_test();
+        assert_eq!(
+            status.as_deref(),
+            Some("scrollback • note ana (row 3): flaky here")
+        );
+    }
+
+    #[test]
+    fn send_file_prompt_offers_the_typed_path() {
+        let path = env::temp_dir().join(format!("beach-send-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"report").unwrap();
+        let transport: Arc<RecordingTransport> = Arc::new(RecordingTransport::default());
+        let mut client = TerminalClient::new(transport.clone()).with_render(false);
+        client.keymap = Keymap::default();
+
+        client
+            .handle_key_bindings(&key(KeyCode::Char('b'), KeyModifiers::CONTROL))
+            .unwrap();
+        client
+            .handle_key_bindings(&key(KeyCode::Char('s'), KeyModifiers::NONE))
+            .unwrap();
+        for ch in path.display().to_string().chars() {
+            assert!(client.handle_transfer_prompt_key(&key(KeyCode::Char(ch), KeyModifiers::NONE)));
+        }
+        client.handle_transfer_prompt_key(&key(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!client.handle_transfer_prompt_key(&key(KeyCode::Char('x'), KeyModifiers::NONE)));
+
+        let frames = transport.take();
+        assert_eq!(frames.len(), 1, "expected a single offer frame");
+        match protocol::decode_client_frame_binary(&frames[0]).expect("decode offer frame") {
+            WireClientFrame::Extension { frame } => match TransferEvent::from_frame(&frame) {
+                Some(TransferEvent::Offer(offer)) => {
+                    assert_eq!(offer.size, 6);
+                    assert_eq!(
+                        Some(offer.name.as_str()),
+                        path.file_name().and_then(|n| n.to_str())
+                    );
+                }
+                other => panic!("unexpected transfer event {other:?}"),
+            },
+            other => panic!("unexpected frame {other:?}"),
+        }
+        let (status, _) = client.renderer.status_for_test();
+        assert!(status.is_some_and(|status| status.starts_with("⇡ beach-send-")));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn vi_ctrl_v_switches_to_block_selection() {
         let mut client = new_client();
@@ -6347,7 +6815,7 @@ mod tests {
         );
 
         client
-            .handle_control_shortcuts(&key(KeyCode::Char('c'), KeyModifiers::CONTROL))
+            .handle_key_bindings(&key(KeyCode::Char('c'), KeyModifiers::CONTROL))
             .expect("ctrl+c shortcut should succeed");
 
         assert!(client.copy_mode.is_none());
@@ -6393,6 +6861,68 @@ mod tests {
         assert_eq!(copied, "goodbye");
     }
 
+    #[test]
+    fn copied_selection_is_pushed_to_the_host_clipboard() {
+        clipboard::clear();
+        let transport: Arc<RecordingTransport> = Arc::new(RecordingTransport::default());
+        let mut client = TerminalClient::new(transport.clone())
+            .with_render(false)
+            .with_clipboard_sync(ClipboardMode::Both);
+        client.subscription_id = Some(1);
+        client.sync_clipboard();
+        client.handle_extension_frame(
+            ClipboardEvent::Granted {
+                mode: ClipboardMode::WriteOnly,
+            }
+            .to_frame(),
+        );
+        client.renderer.ensure_size(1, 16);
+        client.renderer.apply_row_from_text(0, 1, "deploy");
+        client.copy_mode = Some(CopyModeState::new(
+            SelectionPosition { row: 0, col: 0 },
+            CopyModeKeySet::Vi,
+        ));
+        if let Some(state) = client.copy_mode.as_mut() {
+            state.selection_active = true;
+        }
+        client.renderer.set_selection(
+            SelectionPosition { row: 0, col: 0 },
+            SelectionPosition { row: 0, col: 5 },
+            SelectionMode::Character,
+        );
+
+        client.copy_selection_to_clipboard(true);
+
+        let events: Vec<ClipboardEvent> = transport
+            .take()
+            .iter()
+            .map(|bytes| match protocol::decode_client_frame_binary(bytes) {
+                Ok(WireClientFrame::Extension { frame }) => {
+                    ClipboardEvent::from_frame(&frame).expect("clipboard frame")
+                }
+                other => panic!("unexpected frame {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ClipboardEvent::Hello {
+                    mode: ClipboardMode::Both
+                },
+                ClipboardEvent::Chunk {
+                    id: 1,
+                    last: true,
+                    data: bytes::Bytes::from_static(b"deploy"),
+                },
+            ]
+        );
+        let (status, _) = client.renderer.status_for_test();
+        assert_eq!(
+            status.as_deref(),
+            Some("copied 6 characters to clipboard and the host's")
+        );
+    }
+
     #[test]
     fn ctrl_esc_toggle_enters_and_exits_scrollback() {
         let mut client = new_client();
@@ -0,0 +1,218 @@
+//! Client half of the `beach.chat` channel: the log the host replays, the
+//! toggleable chat pane and the message being composed.
+
+use crate::client::grid_renderer::ChatPane;
+use crate::protocol::chat::{ChatEvent, ChatMessage, ChatPost};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+struct Draft {
+    text: String,
+    row: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum DraftKey {
+    /// The key edited the draft or was swallowed while composing.
+    Consumed,
+    /// Enter on a non-empty draft; the post is ready to send.
+    Submit(ChatPost),
+    /// Esc: composing ended without sending.
+    Cancelled,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Chat {
+    messages: Vec<ChatMessage>,
+    visible: bool,
+    draft: Option<Draft>,
+    unread: usize,
+}
+
+impl Chat {
+    pub(super) fn apply(&mut self, event: ChatEvent) {
+        match event {
+            ChatEvent::History(messages) => {
+                self.messages = messages;
+            }
+            ChatEvent::Message(message) => {
+                if self
+                    .messages
+                    .iter()
+                    .any(|existing| existing.id == message.id)
+                {
+                    return;
+                }
+                if !self.visible {
+                    self.unread += 1;
+                }
+                self.messages.push(message);
+            }
+            ChatEvent::Post(_) => {}
+        }
+    }
+
+    pub(super) fn unread(&self) -> usize {
+        self.unread
+    }
+
+    /// Shows the pane and starts a message, or hides a visible pane.
+    pub(super) fn toggle(&mut self) {
+        if self.visible {
+            self.visible = false;
+            self.draft = None;
+        } else {
+            self.compose(None);
+        }
+    }
+
+    /// Shows the pane and starts a message; `row` pins it as a note.
+    pub(super) fn compose(&mut self, row: Option<u64>) {
+        self.visible = true;
+        self.unread = 0;
+        self.draft = Some(Draft {
+            text: String::new(),
+            row,
+        });
+    }
+
+    pub(super) fn handle_key(&mut self, key: &KeyEvent, author: &str) -> Option<DraftKey> {
+        let draft = self.draft.as_mut()?;
+        let outcome = match key.code {
+            KeyCode::Esc => {
+                self.draft = None;
+                DraftKey::Cancelled
+            }
+            KeyCode::Enter => {
+                let draft = self.draft.take().unwrap_or_default();
+                if draft.text.trim().is_empty() {
+                    DraftKey::Cancelled
+                } else {
+                    DraftKey::Submit(ChatPost {
+                        author: author.to_string(),
+                        text: draft.text,
+                        row: draft.row,
+                    })
+                }
+            }
+            KeyCode::Backspace => {
+                draft.text.pop();
+                DraftKey::Consumed
+            }
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                draft.text.push(ch);
+                DraftKey::Consumed
+            }
+            _ => DraftKey::Consumed,
+        };
+        Some(outcome)
+    }
+
+    pub(super) fn note_rows(&self) -> HashSet<u64> {
+        self.messages
+            .iter()
+            .filter_map(|message| message.row)
+            .collect()
+    }
+
+    pub(super) fn notes_at(&self, row: u64) -> impl Iterator<Item = &ChatMessage> {
+        self.messages
+            .iter()
+            .filter(move |message| message.row == Some(row))
+    }
+
+    pub(super) fn pane(&self) -> Option<ChatPane> {
+        if !self.visible {
+            return None;
+        }
+        let title = match self.draft.as_ref().and_then(|draft| draft.row) {
+            Some(row) => format!(" note on row {row} • Enter send • Esc cancel "),
+            None if self.draft.is_some() => " chat • Enter send • Esc cancel ".to_string(),
+            None => " chat ".to_string(),
+        };
+        Some(ChatPane {
+            title,
+            lines: self.messages.iter().map(format_message).collect(),
+            input: self.draft.as_ref().map(|draft| draft.text.clone()),
+        })
+    }
+}
+
+pub(super) fn format_message(message: &ChatMessage) -> String {
+    match message.row {
+        Some(row) => format!("{} (row {row}): {}", message.author, message.text),
+        None => format!("{}: {}", message.author, message.text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn message(id: u64, row: Option<u64>) -> ChatMessage {
+        ChatMessage {
+            id,
+            author: "ben".into(),
+            text: format!("message {id}"),
+            sent_ms: 0,
+            row,
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn composes_and_submits_notes() {
+        let mut chat = Chat::default();
+        assert_eq!(chat.handle_key(&key(KeyCode::Char('x')), "ana"), None);
+
+        chat.compose(Some(42));
+        for ch in "hi!".chars() {
+            chat.handle_key(&key(KeyCode::Char(ch)), "ana");
+        }
+        chat.handle_key(&key(KeyCode::Backspace), "ana");
+        assert_eq!(
+            chat.pane().and_then(|pane| pane.input),
+            Some("hi".to_string())
+        );
+        assert_eq!(
+            chat.handle_key(&key(KeyCode::Enter), "ana"),
+            Some(DraftKey::Submit(ChatPost {
+                author: "ana".into(),
+                text: "hi".into(),
+                row: Some(42),
+            }))
+        );
+        assert_eq!(chat.pane().map(|pane| pane.input), Some(None));
+    }
+
+    #[test_timeout::timeout]
+    fn tracks_unread_messages_and_note_rows() {
+        let mut chat = Chat::default();
+        chat.apply(ChatEvent::History(vec![message(1, None)]));
+        chat.apply(ChatEvent::Message(message(2, Some(7))));
+        chat.apply(ChatEvent::Message(message(2, Some(7))));
+        assert_eq!(chat.unread(), 1);
+        assert_eq!(chat.note_rows(), HashSet::from([7]));
+        assert_eq!(chat.notes_at(7).count(), 1);
+
+        chat.toggle();
+        assert_eq!(chat.unread(), 0);
+        assert_eq!(
+            chat.pane().map(|pane| pane.lines),
+            Some(vec![
+                "ben: message 1".to_string(),
+                "ben (row 7): message 2".to_string(),
+            ])
+        );
+        chat.toggle();
+        assert!(chat.pane().is_none());
+    }
+}
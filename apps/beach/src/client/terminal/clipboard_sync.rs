@@ -0,0 +1,107 @@
+//! Client side of `beach.clipboard`: opts in once the session is up, hands
+//! the host's clipboard writes to the local clipboard and chunks copy-mode
+//! selections for the host.
+
+use crate::protocol::clipboard::{ClipboardEvent, ClipboardMode};
+use crate::session::terminal::clipboard::{ClipboardAssembler, clipboard_chunks};
+
+/// What a host clipboard frame means for the user.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum ClipboardNotice {
+    /// The host answered the opt-in with these directions.
+    Granted(ClipboardMode),
+    /// The host's clipboard changed to this text.
+    Received(String),
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ClipboardSync {
+    requested: ClipboardMode,
+    granted: ClipboardMode,
+    hello_sent: bool,
+    next_id: u64,
+    incoming: ClipboardAssembler,
+}
+
+impl ClipboardSync {
+    pub(super) fn new(requested: ClipboardMode) -> Self {
+        Self {
+            requested,
+            ..Self::default()
+        }
+    }
+
+    /// The opt-in to send, once, if sync was requested.
+    pub(super) fn hello(&mut self) -> Option<ClipboardEvent> {
+        if self.requested == ClipboardMode::Off || self.hello_sent {
+            return None;
+        }
+        self.hello_sent = true;
+        Some(ClipboardEvent::Hello {
+            mode: self.requested,
+        })
+    }
+
+    pub(super) fn apply(&mut self, event: ClipboardEvent) -> Option<ClipboardNotice> {
+        match event {
+            ClipboardEvent::Granted { mode } => {
+                // Never use more than we asked for, whatever the host says.
+                self.granted = mode.intersect(self.requested);
+                Some(ClipboardNotice::Granted(self.granted))
+            }
+            ClipboardEvent::Chunk { id, last, data } if self.granted.reads() => self
+                .incoming
+                .push(id, last, &data)
+                .map(ClipboardNotice::Received),
+            _ => None,
+        }
+    }
+
+    /// Chunks `text` for the host, or `None` when the host does not accept
+    /// our writes or the text is too large.
+    pub(super) fn outgoing(&mut self, text: &str) -> Option<Vec<ClipboardEvent>> {
+        if !self.granted.writes() {
+            return None;
+        }
+        self.next_id += 1;
+        clipboard_chunks(self.next_id, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test_timeout::timeout]
+    fn follows_the_granted_directions() {
+        let mut sync = ClipboardSync::new(ClipboardMode::ReadOnly);
+        assert_eq!(
+            sync.hello(),
+            Some(ClipboardEvent::Hello {
+                mode: ClipboardMode::ReadOnly
+            })
+        );
+        assert_eq!(sync.hello(), None);
+
+        let chunk = ClipboardEvent::Chunk {
+            id: 1,
+            last: true,
+            data: Bytes::from_static(b"ls -la"),
+        };
+        assert_eq!(sync.apply(chunk.clone()), None);
+        assert_eq!(
+            sync.apply(ClipboardEvent::Granted {
+                mode: ClipboardMode::Both
+            }),
+            Some(ClipboardNotice::Granted(ClipboardMode::ReadOnly))
+        );
+        assert_eq!(
+            sync.apply(chunk),
+            Some(ClipboardNotice::Received("ls -la".into()))
+        );
+        assert_eq!(sync.outgoing("selection"), None);
+
+        assert_eq!(ClipboardSync::default().hello(), None);
+    }
+}
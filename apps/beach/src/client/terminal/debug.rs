@@ -1,3 +1,4 @@
+use crate::cache::terminal::export::ExportFormat;
 use crate::debug::ipc::send_diagnostic_request;
 use crate::debug::{DiagnosticRequest, DiagnosticResponse};
 use crate::terminal::cli::DebugArgs;
@@ -15,6 +16,19 @@ pub fn run(args: DebugArgs) -> Result<(), CliError> {
         return Ok(());
     }
 
+    if let Some(format) = args.export {
+        let format = ExportFormat::parse(&format).ok_or_else(|| {
+            CliError::InvalidArgument(format!(
+                "Unknown export format: {}. Valid options: html, ansi, svg",
+                format
+            ))
+        })?;
+        let response = send_diagnostic_request(session_id, DiagnosticRequest::ExportGrid(format))
+            .map_err(|err| CliError::Runtime(err.to_string()))?;
+        print_response(&response);
+        return Ok(());
+    }
+
     let requests = if let Some(query) = args.query {
         match query.to_lowercase().as_str() {
             "cursor" => vec![
@@ -111,6 +125,9 @@ fn print_response(response: &DiagnosticResponse) {
             }
             println!();
         }
+        DiagnosticResponse::GridExport { content, .. } => {
+            print!("{}", content);
+        }
         DiagnosticResponse::InputSent { bytes } => {
             println!("Input sent: {} bytes", bytes);
         }
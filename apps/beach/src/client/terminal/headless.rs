@@ -0,0 +1,424 @@
+//! Streaming output for `beach join --headless --output <jsonl|text>`.
+//!
+//! `jsonl` writes one JSON object per decoded host frame, replacing packed
+//! cell updates with the text of the rows they touched. `text` prints rows
+//! once the cursor has moved past them, which suits line-oriented output
+//! such as build logs better than full-screen programs.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::debug;
+
+use crate::cache::terminal::TerminalGrid;
+use crate::protocol::{self, ClientFrame as WireClientFrame, CursorFrame, HostFrame, Lane, Update};
+use crate::sdk::{apply_update, grid_for_frame, rows_text};
+use crate::terminal::cli::HeadlessOutput;
+use crate::terminal::error::CliError;
+use crate::transport::{Payload, Transport, TransportError};
+
+const RECV_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize)]
+struct RowText {
+    row: u64,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CursorEvent {
+    row: u64,
+    col: u32,
+    visible: bool,
+}
+
+impl From<&CursorFrame> for CursorEvent {
+    fn from(cursor: &CursorFrame) -> Self {
+        Self {
+            row: cursor.row as u64,
+            col: cursor.col,
+            visible: cursor.visible,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    Hello {
+        subscription: u64,
+        features: u32,
+    },
+    Grid {
+        cols: u32,
+        rows: u32,
+        history_rows: u32,
+        base_row: u64,
+    },
+    Rows {
+        source: &'static str,
+        rows: Vec<RowText>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cursor: Option<CursorEvent>,
+    },
+    Cursor {
+        #[serde(flatten)]
+        cursor: CursorEvent,
+    },
+    Shutdown,
+}
+
+pub(crate) struct HeadlessStream<W: Write> {
+    format: HeadlessOutput,
+    out: W,
+    grid: Arc<TerminalGrid>,
+    cursor_row: Option<u64>,
+    /// Next row `text` mode will print; unset until the visible snapshot lands.
+    next_row: Option<u64>,
+}
+
+impl<W: Write> HeadlessStream<W> {
+    pub(crate) fn new(format: HeadlessOutput, out: W) -> Self {
+        Self {
+            format,
+            out,
+            grid: Arc::new(TerminalGrid::new(24, 80)),
+            cursor_row: None,
+            next_row: None,
+        }
+    }
+
+    /// Applies `frame` and writes its output. Returns `false` once the host
+    /// has shut down.
+    pub(crate) fn handle_frame(&mut self, frame: &HostFrame) -> io::Result<bool> {
+        let event = match frame {
+            HostFrame::Hello {
+                subscription,
+                features,
+                ..
+            } => Some(StreamEvent::Hello {
+                subscription: *subscription,
+                features: *features,
+            }),
+            HostFrame::Grid {
+                cols,
+                history_rows,
+                base_row,
+                viewport_rows,
+            } => {
+                self.grid = Arc::new(grid_for_frame(
+                    *cols,
+                    *history_rows,
+                    *base_row,
+                    *viewport_rows,
+                ));
+                Some(StreamEvent::Grid {
+                    cols: *cols,
+                    rows: viewport_rows.unwrap_or(*history_rows),
+                    history_rows: *history_rows,
+                    base_row: *base_row,
+                })
+            }
+            HostFrame::Snapshot {
+                updates, cursor, ..
+            } => Some(self.apply_updates("snapshot", updates, cursor.as_ref())),
+            HostFrame::Delta {
+                updates, cursor, ..
+            } => Some(self.apply_updates("delta", updates, cursor.as_ref())),
+            HostFrame::HistoryBackfill {
+                updates, cursor, ..
+            } => Some(self.apply_updates("backfill", updates, cursor.as_ref())),
+            HostFrame::Cursor { cursor, .. } => {
+                self.cursor_row = Some(cursor.row as u64);
+                Some(StreamEvent::Cursor {
+                    cursor: cursor.into(),
+                })
+            }
+            HostFrame::SnapshotComplete { lane, .. } => {
+                if *lane == Lane::Foreground && self.next_row.is_none() {
+                    self.next_row = Some(self.viewport_top());
+                }
+                None
+            }
+            HostFrame::Shutdown => Some(StreamEvent::Shutdown),
+            HostFrame::Heartbeat { .. }
+            | HostFrame::InputAck { .. }
+            | HostFrame::Extension { .. }
+            | HostFrame::SearchResults { .. }
+            | HostFrame::Modes { .. } => None,
+        };
+
+        match self.format {
+            HeadlessOutput::Jsonl => match &event {
+                Some(event) => self.write_json(event)?,
+                None => self.write_json(frame)?,
+            },
+            HeadlessOutput::Text => self.flush_text(matches!(frame, HostFrame::Shutdown))?,
+        }
+        self.out.flush()?;
+        Ok(!matches!(frame, HostFrame::Shutdown))
+    }
+
+    /// Prints whatever `text` mode was still holding back.
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+        if self.format == HeadlessOutput::Text {
+            self.flush_text(true)?;
+            self.out.flush()?;
+        }
+        Ok(())
+    }
+
+    fn apply_updates(
+        &mut self,
+        source: &'static str,
+        updates: &[Update],
+        cursor: Option<&CursorFrame>,
+    ) -> StreamEvent {
+        let mut touched = BTreeSet::new();
+        for update in updates {
+            apply_update(&self.grid, update);
+            match update {
+                Update::Cell { row, .. }
+                | Update::Row { row, .. }
+                | Update::RowSegment { row, .. } => {
+                    touched.insert(*row as u64);
+                }
+                Update::Rect { rows, .. } => {
+                    touched.extend(rows[0] as u64..rows[1] as u64);
+                }
+                Update::Trim { .. } | Update::Style { .. } => {}
+            }
+        }
+        if let Some(cursor) = cursor {
+            self.cursor_row = Some(cursor.row as u64);
+        }
+        let mut rows = Vec::with_capacity(touched.len());
+        for row in touched {
+            rows.extend(
+                rows_text(&self.grid, row..row + 1)
+                    .into_iter()
+                    .map(|(row, text)| RowText { row, text }),
+            );
+        }
+        StreamEvent::Rows {
+            source,
+            rows,
+            cursor: cursor.map(CursorEvent::from),
+        }
+    }
+
+    fn viewport_top(&self) -> u64 {
+        let (viewport, _) = self.grid.viewport_size();
+        let first = self.grid.first_row_id().unwrap_or(0);
+        self.grid
+            .last_row_id()
+            .map(|last| (last + 1).saturating_sub(viewport as u64))
+            .unwrap_or(0)
+            .max(first)
+    }
+
+    fn flush_text(&mut self, include_tail: bool) -> io::Result<()> {
+        let Some(next_row) = self.next_row else {
+            return Ok(());
+        };
+        let Some(last_row) = self.grid.last_row_id() else {
+            return Ok(());
+        };
+        let end = if include_tail {
+            last_row + 1
+        } else {
+            self.cursor_row.unwrap_or(last_row).min(last_row + 1)
+        };
+        if end <= next_row {
+            return Ok(());
+        }
+        let mut lines = rows_text(&self.grid, next_row..end);
+        if include_tail {
+            while lines.last().is_some_and(|(_, text)| text.is_empty()) {
+                lines.pop();
+            }
+        }
+        for (_, text) in lines {
+            writeln!(self.out, "{text}")?;
+        }
+        self.next_row = Some(end);
+        Ok(())
+    }
+
+    fn write_json<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, value).map_err(io::Error::other)?;
+        self.out.write_all(b"\n")
+    }
+}
+
+/// Streams host output to stdout until the host shuts down or the transport
+/// closes. Intended to run on a blocking thread.
+pub(crate) fn run_stream(
+    transport: Arc<dyn Transport>,
+    format: HeadlessOutput,
+    initial_resize: Option<(u16, u16)>,
+) -> Result<(), CliError> {
+    let negotiation_error = |err: TransportError| CliError::TransportNegotiation(err.to_string());
+    transport
+        .send_text("__ready__")
+        .map_err(negotiation_error)?;
+    if let Some((cols, rows)) = initial_resize {
+        let resize = WireClientFrame::Resize { cols, rows };
+        transport
+            .send_bytes(&protocol::encode_client_frame_binary(&resize))
+            .map_err(negotiation_error)?;
+    }
+
+    let stdout = io::stdout();
+    let mut stream = HeadlessStream::new(format, stdout.lock());
+    let written = loop {
+        match transport.recv(RECV_POLL) {
+            Ok(message) => match message.payload {
+                Payload::Binary(bytes) => {
+                    let frame = match protocol::decode_host_frame_binary(&bytes) {
+                        Ok(frame) => frame,
+                        Err(protocol::WireError::UnknownFrameType(kind)) => {
+                            debug!(
+                                target = "beach::client::headless",
+                                kind, "skipping unknown host frame"
+                            );
+                            continue;
+                        }
+                        Err(err) => {
+                            return Err(CliError::TransportNegotiation(format!(
+                                "failed to decode host frame: {err}"
+                            )));
+                        }
+                    };
+                    match stream.handle_frame(&frame) {
+                        Ok(true) => {}
+                        Ok(false) => break Ok(()),
+                        Err(err) => break Err(err),
+                    }
+                }
+                Payload::Text(text) => {
+                    debug!(
+                        target = "beach::client::headless",
+                        payload = %text.trim(),
+                        "ignoring text payload"
+                    );
+                }
+            },
+            Err(TransportError::Timeout) => continue,
+            Err(TransportError::ChannelClosed) => break stream.finish(),
+            Err(err) => return Err(negotiation_error(err)),
+        }
+    };
+    match written {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(CliError::Io(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::terminal::{StyleId, pack_cell};
+
+    fn row(row: u32, text: &str) -> Update {
+        Update::Row {
+            row,
+            seq: 1,
+            cells: text
+                .chars()
+                .map(|ch| pack_cell(ch, StyleId::DEFAULT).into())
+                .collect(),
+        }
+    }
+
+    fn cursor(row: u32) -> Option<CursorFrame> {
+        Some(CursorFrame {
+            row,
+            col: 0,
+            seq: 1,
+            visible: true,
+            blink: false,
+        })
+    }
+
+    fn delta(updates: Vec<Update>, cursor_row: u32) -> HostFrame {
+        HostFrame::Delta {
+            subscription: 1,
+            watermark: 1,
+            has_more: false,
+            updates,
+            cursor: cursor(cursor_row),
+        }
+    }
+
+    fn grid_frame() -> HostFrame {
+        HostFrame::Grid {
+            cols: 8,
+            history_rows: 20,
+            base_row: 0,
+            viewport_rows: Some(4),
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn text_mode_prints_rows_once_committed() {
+        let mut out = Vec::new();
+        {
+            let mut stream = HeadlessStream::new(HeadlessOutput::Text, &mut out);
+            stream.handle_frame(&grid_frame()).unwrap();
+            stream
+                .handle_frame(&HostFrame::Snapshot {
+                    subscription: 1,
+                    lane: Lane::Foreground,
+                    watermark: 1,
+                    has_more: false,
+                    updates: vec![row(0, "$ make")],
+                    cursor: cursor(1),
+                })
+                .unwrap();
+            stream
+                .handle_frame(&HostFrame::SnapshotComplete {
+                    subscription: 1,
+                    lane: Lane::Foreground,
+                })
+                .unwrap();
+            stream
+                .handle_frame(&delta(vec![row(1, "build ok"), row(2, "$")], 2))
+                .unwrap();
+            assert!(!stream.handle_frame(&HostFrame::Shutdown).unwrap());
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), "$ make\nbuild ok\n$\n");
+    }
+
+    #[test_timeout::timeout]
+    fn jsonl_mode_emits_touched_rows() {
+        let mut out = Vec::new();
+        {
+            let mut stream = HeadlessStream::new(HeadlessOutput::Jsonl, &mut out);
+            stream.handle_frame(&grid_frame()).unwrap();
+            stream
+                .handle_frame(&delta(vec![row(0, "hello")], 1))
+                .unwrap();
+            stream
+                .handle_frame(&HostFrame::InputAck { seq: 3 })
+                .unwrap();
+        }
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["type"], "grid");
+        assert_eq!(lines[0]["rows"], 4);
+        assert_eq!(lines[1]["type"], "rows");
+        assert_eq!(lines[1]["source"], "delta");
+        assert_eq!(lines[1]["rows"][0]["text"], "hello");
+        assert_eq!(lines[1]["cursor"]["row"], 1);
+        assert_eq!(lines[2]["type"], "input_ack");
+    }
+}
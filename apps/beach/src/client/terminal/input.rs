@@ -0,0 +1,716 @@
+//! Turns local key, mouse, paste and focus events into the bytes an xterm
+//! would send, following the input modes the host application enabled.
+//!
+//! The host PTY advertises `TERM=xterm-256color`, so sequences match that
+//! terminfo entry: modified keys use the `CSI 1;<mod>` form and F13–F24 are
+//! F1–F12 with Shift added. Applications that negotiate the kitty keyboard
+//! protocol or xterm `modifyOtherKeys` get those encodings instead, which can
+//! express Ctrl+Shift+letter, Ctrl+Enter and key release.
+
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, ModifierKeyCode, MouseButton,
+    MouseEvent, MouseEventKind,
+};
+use harness_proto::keys::{self, ESC, control_byte, function_key, tilde_key};
+
+use crate::protocol::{
+    KITTY_KEYBOARD_REPORT_ALL_KEYS, KITTY_KEYBOARD_REPORT_ALTERNATES, KITTY_KEYBOARD_REPORT_EVENTS,
+    KITTY_KEYBOARD_REPORT_TEXT, ModifyOtherKeys, MouseEncoding, MouseTracking, TerminalModesFrame,
+};
+
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+/// Largest 1-based coordinate the legacy X10 mouse encoding can carry.
+const X10_MAX_COORD: u32 = 223;
+/// Largest 1-based coordinate the UTF-8 (1005) mouse encoding can carry.
+const UTF8_MAX_COORD: u32 = 2015;
+
+pub(crate) fn encode_key(key: &KeyEvent, modes: &TerminalModesFrame) -> Option<Vec<u8>> {
+    if modes.kitty_keyboard != 0 {
+        return encode_kitty_key(key, modes);
+    }
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    if modes.modify_other_keys != ModifyOtherKeys::Off {
+        if let Some(bytes) = encode_modify_other_keys(key, modes.modify_other_keys) {
+            return Some(bytes);
+        }
+    }
+    encode_legacy_key(key, modes)
+}
+
+fn encode_legacy_key(key: &KeyEvent, modes: &TerminalModesFrame) -> Option<Vec<u8>> {
+    let mods = key.modifiers;
+    let param = modifier_param(mods);
+
+    if modes.app_keypad && param.is_none() && key.state.contains(KeyEventState::KEYPAD) {
+        if let Some(final_byte) = keypad_final(key.code) {
+            return Some(vec![ESC, b'O', final_byte]);
+        }
+    }
+
+    match key.code {
+        KeyCode::Char(c) => Some(encode_char(c, mods)),
+        // Send LF by default to be robust against PTY configurations that
+        // don't map CR->NL on input. This avoids the "carriage return"
+        // behavior where Enter returns to column 0 without advancing the
+        // line on some hosts.
+        KeyCode::Enter => Some(with_alt(mods, &[b'\n'])),
+        KeyCode::Tab if mods.contains(KeyModifiers::SHIFT) => Some(b"\x1b[Z".to_vec()),
+        KeyCode::Tab => Some(with_alt(mods, &[b'\t'])),
+        KeyCode::BackTab => Some(b"\x1b[Z".to_vec()),
+        KeyCode::Backspace if mods.contains(KeyModifiers::CONTROL) => Some(with_alt(mods, &[0x08])),
+        KeyCode::Backspace => Some(with_alt(mods, &[0x7f])),
+        KeyCode::Esc => Some(with_alt(mods, &[ESC])),
+        KeyCode::Up => Some(cursor_key(b'A', param, modes)),
+        KeyCode::Down => Some(cursor_key(b'B', param, modes)),
+        KeyCode::Right => Some(cursor_key(b'C', param, modes)),
+        KeyCode::Left => Some(cursor_key(b'D', param, modes)),
+        KeyCode::Home => Some(cursor_key(b'H', param, modes)),
+        KeyCode::End => Some(cursor_key(b'F', param, modes)),
+        KeyCode::Insert => Some(tilde_key(2, param)),
+        KeyCode::Delete => Some(tilde_key(3, param)),
+        KeyCode::PageUp => Some(tilde_key(5, param)),
+        KeyCode::PageDown => Some(tilde_key(6, param)),
+        KeyCode::F(n) => function_key(n, param),
+        _ => None,
+    }
+}
+
+/// How the kitty protocol identifies a key: `CSI number ; mods final`.
+struct KittyKey {
+    number: u32,
+    final_byte: u8,
+    /// Shifted form of a text key, reported with `REPORT_ALTERNATES`.
+    shifted: Option<char>,
+    /// Text keys are sent as plain text when unmodified.
+    text: Option<char>,
+    /// Enter, Tab and Backspace keep their legacy bytes when unmodified.
+    legacy_control: bool,
+}
+
+impl KittyKey {
+    const fn csi(number: u32, final_byte: u8) -> Self {
+        Self {
+            number,
+            final_byte,
+            shifted: None,
+            text: None,
+            legacy_control: false,
+        }
+    }
+}
+
+fn kitty_key(code: KeyCode) -> Option<KittyKey> {
+    let key = match code {
+        KeyCode::Char(c) => {
+            let base = c.to_lowercase().next().unwrap_or(c);
+            KittyKey {
+                number: base as u32,
+                shifted: (base != c).then_some(c),
+                text: Some(c),
+                ..KittyKey::csi(0, b'u')
+            }
+        }
+        KeyCode::Enter | KeyCode::Tab | KeyCode::BackTab | KeyCode::Backspace => {
+            let number = match code {
+                KeyCode::Enter => 13,
+                KeyCode::Backspace => 127,
+                _ => 9,
+            };
+            KittyKey {
+                legacy_control: true,
+                ..KittyKey::csi(number, b'u')
+            }
+        }
+        KeyCode::Esc => KittyKey::csi(27, b'u'),
+        KeyCode::Up => KittyKey::csi(1, b'A'),
+        KeyCode::Down => KittyKey::csi(1, b'B'),
+        KeyCode::Right => KittyKey::csi(1, b'C'),
+        KeyCode::Left => KittyKey::csi(1, b'D'),
+        KeyCode::KeypadBegin => KittyKey::csi(1, b'E'),
+        KeyCode::Home => KittyKey::csi(1, b'H'),
+        KeyCode::End => KittyKey::csi(1, b'F'),
+        KeyCode::Insert => KittyKey::csi(2, b'~'),
+        KeyCode::Delete => KittyKey::csi(3, b'~'),
+        KeyCode::PageUp => KittyKey::csi(5, b'~'),
+        KeyCode::PageDown => KittyKey::csi(6, b'~'),
+        KeyCode::F(1) => KittyKey::csi(1, b'P'),
+        KeyCode::F(2) => KittyKey::csi(1, b'Q'),
+        // `CSI R` is a cursor position report, so F3 uses the tilde form.
+        KeyCode::F(3) => KittyKey::csi(13, b'~'),
+        KeyCode::F(4) => KittyKey::csi(1, b'S'),
+        KeyCode::F(n @ 5..=12) => {
+            const CODES: [u32; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            KittyKey::csi(CODES[usize::from(n - 5)], b'~')
+        }
+        KeyCode::F(n @ 13..=35) => KittyKey::csi(57376 + u32::from(n - 13), b'u'),
+        KeyCode::CapsLock => KittyKey::csi(57358, b'u'),
+        KeyCode::ScrollLock => KittyKey::csi(57359, b'u'),
+        KeyCode::NumLock => KittyKey::csi(57360, b'u'),
+        KeyCode::PrintScreen => KittyKey::csi(57361, b'u'),
+        KeyCode::Pause => KittyKey::csi(57362, b'u'),
+        KeyCode::Menu => KittyKey::csi(57363, b'u'),
+        KeyCode::Modifier(modifier) => {
+            let number = match modifier {
+                ModifierKeyCode::LeftShift => 57441,
+                ModifierKeyCode::LeftControl => 57442,
+                ModifierKeyCode::LeftAlt => 57443,
+                ModifierKeyCode::LeftSuper => 57444,
+                ModifierKeyCode::LeftHyper => 57445,
+                ModifierKeyCode::LeftMeta => 57446,
+                ModifierKeyCode::RightShift => 57447,
+                ModifierKeyCode::RightControl => 57448,
+                ModifierKeyCode::RightAlt => 57449,
+                ModifierKeyCode::RightSuper => 57450,
+                ModifierKeyCode::RightHyper => 57451,
+                ModifierKeyCode::RightMeta => 57452,
+                ModifierKeyCode::IsoLevel3Shift => 57453,
+                ModifierKeyCode::IsoLevel5Shift => 57454,
+            };
+            KittyKey::csi(number, b'u')
+        }
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Kitty modifier bits (`Shift=1 Alt=2 Ctrl=4 Super=8 Hyper=16 Meta=32`,
+/// plus Caps Lock and Num Lock).
+fn kitty_modifier_bits(key: &KeyEvent, include_locks: bool) -> u32 {
+    let mods = key.modifiers;
+    let mut bits = 0;
+    for (modifier, bit) in [
+        (KeyModifiers::SHIFT, 1),
+        (KeyModifiers::ALT, 2),
+        (KeyModifiers::CONTROL, 4),
+        (KeyModifiers::SUPER, 8),
+        (KeyModifiers::HYPER, 16),
+        (KeyModifiers::META, 32),
+    ] {
+        if mods.contains(modifier) {
+            bits |= bit;
+        }
+    }
+    if include_locks {
+        if key.state.contains(KeyEventState::CAPS_LOCK) {
+            bits |= 64;
+        }
+        if key.state.contains(KeyEventState::NUM_LOCK) {
+            bits |= 128;
+        }
+    }
+    bits
+}
+
+/// Encodes `key` with the kitty keyboard protocol at the flags the host
+/// application pushed. Keys the protocol leaves in legacy form fall back to
+/// [`encode_legacy_key`].
+fn encode_kitty_key(key: &KeyEvent, modes: &TerminalModesFrame) -> Option<Vec<u8>> {
+    let flags = modes.kitty_keyboard;
+    let all_keys = flags & KITTY_KEYBOARD_REPORT_ALL_KEYS != 0;
+    let event = match key.kind {
+        KeyEventKind::Press => 1,
+        KeyEventKind::Repeat => 2,
+        KeyEventKind::Release => 3,
+    };
+    if event == 3 && flags & KITTY_KEYBOARD_REPORT_EVENTS == 0 {
+        return None;
+    }
+
+    let mut kitty = kitty_key(key.code)?;
+    let mut bits = kitty_modifier_bits(key, false);
+    if key.code == KeyCode::BackTab {
+        bits |= 1;
+    }
+    // Shift alone still produces text, so it does not force the CSI form.
+    let modified = bits & !1 != 0;
+
+    if !all_keys {
+        // Lock and modifier keys on their own are only reported in
+        // report-all mode.
+        if matches!(
+            key.code,
+            KeyCode::Modifier(_) | KeyCode::CapsLock | KeyCode::ScrollLock | KeyCode::NumLock
+        ) {
+            return None;
+        }
+        if kitty.text.is_some() && !modified && event != 3 {
+            return encode_legacy_key(key, modes);
+        }
+        if kitty.legacy_control {
+            if event == 3 {
+                return None;
+            }
+            if bits == 0 {
+                return encode_legacy_key(key, modes);
+            }
+        }
+        if kitty.final_byte != b'u' && bits == 0 && event != 3 {
+            return encode_legacy_key(key, modes);
+        }
+    }
+
+    // Lock modifiers are only reported for text keys in report-all mode.
+    if all_keys || kitty.text.is_none() {
+        bits = kitty_modifier_bits(key, true) | (bits & 1);
+    }
+    if flags & KITTY_KEYBOARD_REPORT_ALTERNATES == 0 {
+        kitty.shifted = None;
+    }
+    let text = kitty
+        .text
+        .filter(|_| all_keys && flags & KITTY_KEYBOARD_REPORT_TEXT != 0)
+        .filter(|_| !modified && event != 3);
+
+    let mut seq = String::from("\x1b[");
+    let mut fields = String::new();
+    if bits != 0 || event != 1 || text.is_some() {
+        fields.push_str(&format!(";{}", bits + 1));
+        if event != 1 {
+            fields.push_str(&format!(":{event}"));
+        }
+    }
+    if let Some(text) = text {
+        fields.push_str(&format!(";{}", text as u32));
+    }
+    if kitty.number != 1 || kitty.final_byte == b'u' || !fields.is_empty() {
+        seq.push_str(&kitty.number.to_string());
+    }
+    if let Some(shifted) = kitty.shifted {
+        seq.push_str(&format!(":{}", shifted as u32));
+    }
+    seq.push_str(&fields);
+    seq.push(kitty.final_byte as char);
+    Some(seq.into_bytes())
+}
+
+/// xterm `modifyOtherKeys` form `CSI 27 ; mod ; code ~`, or `None` when the
+/// legacy encoding already says everything.
+fn encode_modify_other_keys(key: &KeyEvent, level: ModifyOtherKeys) -> Option<Vec<u8>> {
+    let mods = key.modifiers;
+    let param = modifier_param(mods)?;
+    let ctrl = mods.contains(KeyModifiers::CONTROL);
+    let shift = mods.contains(KeyModifiers::SHIFT);
+    let (code, unambiguous) = match key.code {
+        // Shifted text is the character itself.
+        KeyCode::Char(_) if !mods.intersects(!KeyModifiers::SHIFT) => return None,
+        KeyCode::Char(c) => {
+            let control = ctrl && !shift && !c.is_ascii_digit() && control_byte(c).is_some();
+            (c as u32, !ctrl || control)
+        }
+        KeyCode::Tab if mods == KeyModifiers::SHIFT => return None,
+        KeyCode::Enter => (13, !ctrl && !shift),
+        KeyCode::Tab => (9, !ctrl && !shift),
+        KeyCode::Backspace => (127, !ctrl && !shift),
+        KeyCode::Esc => (27, !ctrl && !shift),
+        _ => return None,
+    };
+    if level == ModifyOtherKeys::Ambiguous && unambiguous {
+        return None;
+    }
+    Some(format!("\x1b[27;{param};{code}~").into_bytes())
+}
+
+/// Mouse report for `mouse`, or `None` when the host application did not ask
+/// for this kind of event.
+pub(crate) fn encode_mouse(mouse: &MouseEvent, modes: &TerminalModesFrame) -> Option<Vec<u8>> {
+    let tracking = modes.mouse_tracking;
+    if tracking == MouseTracking::Off {
+        return None;
+    }
+    let (mut code, pressed) = match mouse.kind {
+        MouseEventKind::Down(button) => (mouse_button_code(button), true),
+        MouseEventKind::Up(button) => (mouse_button_code(button), false),
+        MouseEventKind::Drag(button)
+            if matches!(tracking, MouseTracking::Drag | MouseTracking::Motion) =>
+        {
+            (mouse_button_code(button) + 32, true)
+        }
+        MouseEventKind::Moved if tracking == MouseTracking::Motion => (3 + 32, true),
+        MouseEventKind::ScrollUp => (64, true),
+        MouseEventKind::ScrollDown => (65, true),
+        MouseEventKind::ScrollLeft => (66, true),
+        MouseEventKind::ScrollRight => (67, true),
+        _ => return None,
+    };
+
+    if mouse.modifiers.contains(KeyModifiers::SHIFT) {
+        code += 4;
+    }
+    if mouse.modifiers.contains(KeyModifiers::ALT) {
+        code += 8;
+    }
+    if mouse.modifiers.contains(KeyModifiers::CONTROL) {
+        code += 16;
+    }
+
+    let column = u32::from(mouse.column) + 1;
+    let row = u32::from(mouse.row) + 1;
+    match modes.mouse_encoding {
+        MouseEncoding::Sgr => {
+            let suffix = if pressed { 'M' } else { 'm' };
+            Some(format!("\x1b[<{code};{column};{row}{suffix}").into_bytes())
+        }
+        MouseEncoding::X10 | MouseEncoding::Utf8 => {
+            // The legacy encodings cannot say which button was released.
+            if !pressed {
+                code = (code & !0b11) | 3;
+            }
+            let mut bytes = b"\x1b[M".to_vec();
+            bytes.push(32 + code as u8);
+            if modes.mouse_encoding == MouseEncoding::X10 {
+                if column > X10_MAX_COORD || row > X10_MAX_COORD {
+                    return None;
+                }
+                bytes.push(32 + column as u8);
+                bytes.push(32 + row as u8);
+            } else {
+                if column > UTF8_MAX_COORD || row > UTF8_MAX_COORD {
+                    return None;
+                }
+                let mut buf = [0u8; 4];
+                for value in [column, row] {
+                    let ch = char::from_u32(32 + value)?;
+                    bytes.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                }
+            }
+            Some(bytes)
+        }
+    }
+}
+
+/// Wraps pasted text in bracketed-paste markers when the application asked
+/// for them. Embedded end markers are dropped so the paste cannot end early
+/// and have the rest run as typed input.
+pub(crate) fn encode_paste(text: &str, modes: &TerminalModesFrame) -> Vec<u8> {
+    if !modes.bracketed_paste {
+        return text.as_bytes().to_vec();
+    }
+    let body = text.replace(PASTE_END, "");
+    let mut bytes = Vec::with_capacity(body.len() + PASTE_START.len() + PASTE_END.len());
+    bytes.extend_from_slice(PASTE_START.as_bytes());
+    bytes.extend_from_slice(body.as_bytes());
+    bytes.extend_from_slice(PASTE_END.as_bytes());
+    bytes
+}
+
+pub(crate) fn encode_focus(gained: bool, modes: &TerminalModesFrame) -> Option<Vec<u8>> {
+    if !modes.focus_reporting {
+        return None;
+    }
+    Some(if gained { b"\x1b[I" } else { b"\x1b[O" }.to_vec())
+}
+
+fn modifier_param(mods: KeyModifiers) -> Option<u8> {
+    keys::modifier_param(
+        mods.contains(KeyModifiers::SHIFT),
+        mods.contains(KeyModifiers::ALT),
+        mods.contains(KeyModifiers::CONTROL),
+        mods.intersects(KeyModifiers::SUPER | KeyModifiers::META),
+    )
+}
+
+fn encode_char(c: char, mods: KeyModifiers) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(5);
+    if mods.contains(KeyModifiers::ALT) {
+        bytes.push(ESC);
+    }
+    match control_byte(c).filter(|_| mods.contains(KeyModifiers::CONTROL)) {
+        Some(byte) => bytes.push(byte),
+        None => {
+            let mut buf = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    bytes
+}
+
+fn with_alt(mods: KeyModifiers, bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() + 1);
+    if mods.contains(KeyModifiers::ALT) {
+        out.push(ESC);
+    }
+    out.extend_from_slice(bytes);
+    out
+}
+
+fn cursor_key(final_byte: u8, param: Option<u8>, modes: &TerminalModesFrame) -> Vec<u8> {
+    keys::cursor_key(final_byte, param, modes.app_cursor)
+}
+
+/// SS3 final byte for a keypad key in application keypad mode.
+fn keypad_final(code: KeyCode) -> Option<u8> {
+    match code {
+        KeyCode::Char(c @ '0'..='9') => Some(b'p' + (c as u8 - b'0')),
+        KeyCode::Char('*') => Some(b'j'),
+        KeyCode::Char('+') => Some(b'k'),
+        KeyCode::Char(',') => Some(b'l'),
+        KeyCode::Char('-') => Some(b'm'),
+        KeyCode::Char('.') => Some(b'n'),
+        KeyCode::Char('/') => Some(b'o'),
+        KeyCode::Char('=') => Some(b'X'),
+        KeyCode::Enter => Some(b'M'),
+        _ => None,
+    }
+}
+
+fn mouse_button_code(button: MouseButton) -> u16 {
+    match button {
+        MouseButton::Left => 0,
+        MouseButton::Middle => 1,
+        MouseButton::Right => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::KITTY_KEYBOARD_DISAMBIGUATE;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn cursor_keys_follow_decckm_and_modifiers() {
+        let normal = TerminalModesFrame::default();
+        let app = TerminalModesFrame {
+            app_cursor: true,
+            ..TerminalModesFrame::default()
+        };
+        let up = key(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(encode_key(&up, &normal).unwrap(), b"\x1b[A");
+        assert_eq!(encode_key(&up, &app).unwrap(), b"\x1bOA");
+        assert_eq!(
+            encode_key(&key(KeyCode::End, KeyModifiers::NONE), &app).unwrap(),
+            b"\x1bOF"
+        );
+        let ctrl_left = key(KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(encode_key(&ctrl_left, &app).unwrap(), b"\x1b[1;5D");
+        let shift_alt_right = key(KeyCode::Right, KeyModifiers::SHIFT | KeyModifiers::ALT);
+        assert_eq!(encode_key(&shift_alt_right, &normal).unwrap(), b"\x1b[1;4C");
+        let ctrl_delete = key(KeyCode::Delete, KeyModifiers::CONTROL);
+        assert_eq!(encode_key(&ctrl_delete, &normal).unwrap(), b"\x1b[3;5~");
+    }
+
+    #[test_timeout::timeout]
+    fn function_keys_cover_f1_to_f24() {
+        let modes = TerminalModesFrame::default();
+        let cases: [(u8, KeyModifiers, &[u8]); 7] = [
+            (1, KeyModifiers::NONE, b"\x1bOP"),
+            (4, KeyModifiers::CONTROL, b"\x1b[1;5S"),
+            (5, KeyModifiers::NONE, b"\x1b[15~"),
+            (12, KeyModifiers::SHIFT, b"\x1b[24;2~"),
+            (13, KeyModifiers::NONE, b"\x1b[1;2P"),
+            (17, KeyModifiers::NONE, b"\x1b[15;2~"),
+            (24, KeyModifiers::CONTROL, b"\x1b[24;6~"),
+        ];
+        for (n, mods, expected) in cases {
+            assert_eq!(
+                encode_key(&key(KeyCode::F(n), mods), &modes).unwrap(),
+                expected,
+                "F{n} {mods:?}"
+            );
+        }
+        assert!(encode_key(&key(KeyCode::F(25), KeyModifiers::NONE), &modes).is_none());
+    }
+
+    #[test_timeout::timeout]
+    fn chars_encode_utf8_and_control_bytes() {
+        let modes = TerminalModesFrame::default();
+        let cases: [(char, KeyModifiers, &[u8]); 6] = [
+            ('é', KeyModifiers::NONE, "é".as_bytes()),
+            ('日', KeyModifiers::ALT, "\x1b日".as_bytes()),
+            ('c', KeyModifiers::CONTROL, b"\x03"),
+            (' ', KeyModifiers::CONTROL, b"\x00"),
+            (']', KeyModifiers::CONTROL, b"\x1d"),
+            ('x', KeyModifiers::CONTROL | KeyModifiers::ALT, b"\x1b\x18"),
+        ];
+        for (c, mods, expected) in cases {
+            assert_eq!(
+                encode_key(&key(KeyCode::Char(c), mods), &modes).unwrap(),
+                expected,
+                "{c:?} {mods:?}"
+            );
+        }
+
+        let keypad = KeyEvent::new_with_kind_and_state(
+            KeyCode::Char('5'),
+            KeyModifiers::NONE,
+            KeyEventKind::Press,
+            KeyEventState::KEYPAD,
+        );
+        assert_eq!(encode_key(&keypad, &modes).unwrap(), b"5");
+        let app_keypad = TerminalModesFrame {
+            app_keypad: true,
+            ..TerminalModesFrame::default()
+        };
+        assert_eq!(encode_key(&keypad, &app_keypad).unwrap(), b"\x1bOu");
+    }
+
+    #[test_timeout::timeout]
+    fn kitty_keyboard_encodes_ambiguous_keys() {
+        let kitty = |flags: u8| TerminalModesFrame {
+            app_cursor: true,
+            kitty_keyboard: flags,
+            ..TerminalModesFrame::default()
+        };
+        let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        let release =
+            |code| KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Release);
+        let left_shift = KeyCode::Modifier(ModifierKeyCode::LeftShift);
+
+        let disambiguate = kitty(KITTY_KEYBOARD_DISAMBIGUATE);
+        let cases: [(KeyEvent, &[u8]); 11] = [
+            (key(KeyCode::Char('a'), KeyModifiers::NONE), b"a"),
+            (key(KeyCode::Char('A'), KeyModifiers::SHIFT), b"A"),
+            (key(KeyCode::Char('A'), ctrl_shift), b"\x1b[97;6u"),
+            (key(KeyCode::Char('a'), KeyModifiers::ALT), b"\x1b[97;3u"),
+            (key(KeyCode::Enter, KeyModifiers::NONE), b"\n"),
+            (key(KeyCode::Enter, KeyModifiers::CONTROL), b"\x1b[13;5u"),
+            (key(KeyCode::BackTab, KeyModifiers::SHIFT), b"\x1b[9;2u"),
+            (key(KeyCode::Esc, KeyModifiers::NONE), b"\x1b[27u"),
+            (key(KeyCode::Up, KeyModifiers::NONE), b"\x1bOA"),
+            (key(KeyCode::F(3), KeyModifiers::CONTROL), b"\x1b[13;5~"),
+            (key(KeyCode::F(13), KeyModifiers::NONE), b"\x1b[57376u"),
+        ];
+        for (event, expected) in cases {
+            assert_eq!(
+                encode_key(&event, &disambiguate).unwrap(),
+                expected,
+                "{event:?}"
+            );
+        }
+        assert!(encode_key(&release(KeyCode::Char('a')), &disambiguate).is_none());
+        assert!(encode_key(&key(left_shift, KeyModifiers::NONE), &disambiguate).is_none());
+
+        let events = kitty(KITTY_KEYBOARD_DISAMBIGUATE | KITTY_KEYBOARD_REPORT_EVENTS);
+        assert_eq!(
+            encode_key(&release(KeyCode::Char('a')), &events).unwrap(),
+            b"\x1b[97;1:3u"
+        );
+        assert!(encode_key(&release(KeyCode::Enter), &events).is_none());
+
+        let alternates = kitty(KITTY_KEYBOARD_DISAMBIGUATE | KITTY_KEYBOARD_REPORT_ALTERNATES);
+        assert_eq!(
+            encode_key(&key(KeyCode::Char('A'), ctrl_shift), &alternates).unwrap(),
+            b"\x1b[97:65;6u"
+        );
+
+        let all_keys = kitty(
+            KITTY_KEYBOARD_DISAMBIGUATE
+                | KITTY_KEYBOARD_REPORT_ALL_KEYS
+                | KITTY_KEYBOARD_REPORT_TEXT,
+        );
+        let cases: [(KeyEvent, &[u8]); 4] = [
+            (
+                key(KeyCode::Char('a'), KeyModifiers::NONE),
+                b"\x1b[97;1;97u",
+            ),
+            (key(KeyCode::Enter, KeyModifiers::NONE), b"\x1b[13u"),
+            (key(KeyCode::Up, KeyModifiers::NONE), b"\x1b[A"),
+            (key(left_shift, KeyModifiers::NONE), b"\x1b[57441u"),
+        ];
+        for (event, expected) in cases {
+            assert_eq!(
+                encode_key(&event, &all_keys).unwrap(),
+                expected,
+                "{event:?}"
+            );
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn modify_other_keys_reports_modified_keys() {
+        let level = |modify_other_keys| TerminalModesFrame {
+            modify_other_keys,
+            ..TerminalModesFrame::default()
+        };
+        let ambiguous = level(ModifyOtherKeys::Ambiguous);
+        let all = level(ModifyOtherKeys::All);
+        let ctrl_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+
+        let ctrl_a = key(KeyCode::Char('a'), KeyModifiers::CONTROL);
+        assert_eq!(encode_key(&ctrl_a, &ambiguous).unwrap(), b"\x01");
+        assert_eq!(encode_key(&ctrl_a, &all).unwrap(), b"\x1b[27;5;97~");
+        let ctrl_shift_a = key(KeyCode::Char('A'), ctrl_shift);
+        assert_eq!(
+            encode_key(&ctrl_shift_a, &ambiguous).unwrap(),
+            b"\x1b[27;6;65~"
+        );
+        let ctrl_enter = key(KeyCode::Enter, KeyModifiers::CONTROL);
+        assert_eq!(
+            encode_key(&ctrl_enter, &ambiguous).unwrap(),
+            b"\x1b[27;5;13~"
+        );
+        let shift_a = key(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(encode_key(&shift_a, &all).unwrap(), b"A");
+        let shift_tab = key(KeyCode::Tab, KeyModifiers::SHIFT);
+        assert_eq!(encode_key(&shift_tab, &all).unwrap(), b"\x1b[Z");
+    }
+
+    #[test_timeout::timeout]
+    fn mouse_reports_follow_tracking_and_encoding() {
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 4, 9);
+        assert!(encode_mouse(&down, &TerminalModesFrame::default()).is_none());
+
+        let click_sgr = TerminalModesFrame {
+            mouse_tracking: MouseTracking::Click,
+            mouse_encoding: MouseEncoding::Sgr,
+            ..TerminalModesFrame::default()
+        };
+        assert_eq!(encode_mouse(&down, &click_sgr).unwrap(), b"\x1b[<0;5;10M");
+        let up = mouse(MouseEventKind::Up(MouseButton::Right), 4, 9);
+        assert_eq!(encode_mouse(&up, &click_sgr).unwrap(), b"\x1b[<2;5;10m");
+        let drag = mouse(MouseEventKind::Drag(MouseButton::Left), 4, 9);
+        assert!(encode_mouse(&drag, &click_sgr).is_none());
+
+        let drag_x10 = TerminalModesFrame {
+            mouse_tracking: MouseTracking::Drag,
+            ..TerminalModesFrame::default()
+        };
+        assert_eq!(encode_mouse(&drag, &drag_x10).unwrap(), b"\x1b[M@%*");
+        assert_eq!(encode_mouse(&up, &drag_x10).unwrap(), b"\x1b[M#%*");
+        let far = mouse(MouseEventKind::Down(MouseButton::Left), 300, 0);
+        assert!(encode_mouse(&far, &drag_x10).is_none());
+
+        let motion_utf8 = TerminalModesFrame {
+            mouse_tracking: MouseTracking::Motion,
+            mouse_encoding: MouseEncoding::Utf8,
+            ..TerminalModesFrame::default()
+        };
+        let mut expected = b"\x1b[MC".to_vec();
+        expected.extend_from_slice("\u{14d}!".as_bytes());
+        let moved = mouse(MouseEventKind::Moved, 300, 0);
+        assert_eq!(encode_mouse(&moved, &motion_utf8).unwrap(), expected);
+    }
+
+    #[test_timeout::timeout]
+    fn paste_and_focus_respect_modes() {
+        let off = TerminalModesFrame::default();
+        let on = TerminalModesFrame {
+            bracketed_paste: true,
+            focus_reporting: true,
+            ..TerminalModesFrame::default()
+        };
+        assert_eq!(encode_paste("ls\n", &off), b"ls\n");
+        assert_eq!(
+            encode_paste("a\x1b[201~rm -rf ~\n", &on),
+            b"\x1b[200~arm -rf ~\n\x1b[201~"
+        );
+        assert!(encode_focus(true, &off).is_none());
+        assert_eq!(encode_focus(true, &on).unwrap(), b"\x1b[I");
+        assert_eq!(encode_focus(false, &on).unwrap(), b"\x1b[O");
+    }
+}
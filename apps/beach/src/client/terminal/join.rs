@@ -1,5 +1,7 @@
-use super::{ClientError, TerminalClient};
+use super::portfwd::PortForwards;
+use super::{ClientError, TerminalClient, headless};
 use crate::auth;
+use crate::client::color::ColorMapper;
 use crate::mcp::client_proxy::spawn_client_proxy;
 use crate::mcp::default_socket_path as mcp_default_socket_path;
 use crate::protocol::terminal::bootstrap;
@@ -7,7 +9,6 @@ use crate::session::terminal::tty::RawModeGuard;
 use crate::session::{JoinedSession, SessionConfig, SessionManager, TransportOffer};
 use crate::terminal::cli::JoinArgs;
 use crate::terminal::error::CliError;
-use crate::transport::TransportKind;
 use crate::transport::ssh::validate::{
     HeadlessOptions, log_report as log_headless_report, parse_headless_resize_spec,
     run_headless_validation,
@@ -15,7 +16,9 @@ use crate::transport::ssh::validate::{
 use crate::transport::terminal::negotiation::{
     NegotiatedSingle, NegotiatedTransport, negotiate_transport,
 };
+use crate::transport::{Transport, TransportKind};
 use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
@@ -45,27 +48,17 @@ pub async fn run_with_notify(
         headless,
         headless_timeout,
         headless_resize,
+        output,
+        colors,
+        forward,
+        remote_forward,
+        clipboard_sync,
     } = args;
 
     let (session_id, inferred_base) = interpret_session_target(&target)?;
     let base = inferred_base.unwrap_or_else(|| base_url.to_string());
 
-    let requires_token = auth::manager_requires_access_token(&base);
-    let access_token = auth::maybe_access_token(profile_override.as_deref(), requires_token)
-        .await
-        .map_err(|err| CliError::Auth(err.to_string()))?;
-    if requires_token && access_token.is_none() {
-        return Err(CliError::Auth(
-            "This private beach requires authentication. Run `beach login` and try again.".into(),
-        ));
-    }
-
-    let mut config = SessionConfig::new(&base)?;
-    if let Some(token) = access_token.clone() {
-        config = config.with_bearer_token(Some(token));
-    }
-
-    let manager = SessionManager::new(config)?;
+    let (manager, access_token) = session_manager_for(&base, profile_override.as_deref()).await?;
     let passcode = match passcode {
         Some(code) => code,
         None => prompt_passcode()?,
@@ -93,7 +86,10 @@ pub async fn run_with_notify(
         .unwrap_or(TransportKind::WebRtc);
 
     info!(session_id = %joined.session_id(), transport = ?banner_kind, "joined session");
-    print_join_banner(&joined, banner_kind, headless);
+    // Streaming modes own stdout, so keep the banner out of their output.
+    if output.is_none() {
+        print_join_banner(&joined, banner_kind, headless);
+    }
 
     if headless {
         let parsed_resize = match headless_resize.as_deref() {
@@ -103,6 +99,29 @@ pub async fn run_with_notify(
         if let Some(tx) = connected_notify {
             let _ = tx.send(());
         }
+        if let Some(format) = output {
+            let negotiated = negotiate_transport(
+                joined.handle(),
+                Some(trimmed_pass.as_str()),
+                label.as_deref().or(Some("headless-stream")),
+                false,
+                None,
+            )
+            .await?;
+            let transport = match negotiated {
+                NegotiatedTransport::Single(single) => single.transport,
+                NegotiatedTransport::WebRtcOfferer { .. } => {
+                    return Err(CliError::TransportNegotiation(
+                        "unexpected offerer transport while joining session".into(),
+                    ));
+                }
+            };
+            return tokio::task::spawn_blocking(move || {
+                headless::run_stream(transport, format, parsed_resize)
+            })
+            .await
+            .map_err(|err| CliError::Runtime(format!("headless stream task failed: {err}")))?;
+        }
         let timeout_secs = headless_timeout.max(1);
         println!(
             "⚙️  Running headless client validation (timeout {}s)...",
@@ -154,6 +173,24 @@ pub async fn run_with_notify(
     }
 
     let client_transport = transport.clone();
+    let port_forwards = if forward.is_empty() && remote_forward.is_empty() {
+        None
+    } else {
+        let forwards = PortForwards::start(transport.clone(), &forward, &remote_forward)?;
+        for spec in &forward {
+            println!(
+                "🔀 Forwarding 127.0.0.1:{} to {}:{} on the host",
+                spec.listen_port, spec.host, spec.port
+            );
+        }
+        for spec in &remote_forward {
+            println!(
+                "🔀 Asking the host to forward its port {} to {}:{}",
+                spec.listen_port, spec.host, spec.port
+            );
+        }
+        Some(forwards)
+    };
 
     if mcp {
         if let Some(channels) = webrtc_channels.clone() {
@@ -229,11 +266,16 @@ pub async fn run_with_notify(
             .unwrap_or(true);
         let mut client = TerminalClient::new(client_transport)
             .with_predictive_input(interactive && predictive_env)
-            .with_diagnostic_server(diagnostic_server);
+            .with_color_mapper(ColorMapper::load(colors))
+            .with_diagnostic_server(diagnostic_server)
+            .with_clipboard_sync(clipboard_sync);
 
         if let Some(latency_ms) = inject_latency {
             client = client.with_injected_latency_ms(latency_ms);
         }
+        if let Some(forwards) = port_forwards {
+            client = client.with_port_forwards(forwards);
+        }
 
         match client.run() {
             Ok(()) | Err(ClientError::Shutdown) => {}
@@ -246,6 +288,109 @@ pub async fn run_with_notify(
     Ok(())
 }
 
+/// A joined session whose MCP data channel is open. `transport` carries the
+/// regular terminal stream and must stay alive for the channel to remain open.
+pub(crate) struct McpChannelSession {
+    pub session_id: String,
+    pub transport: Arc<dyn Transport>,
+    pub mcp: Arc<dyn Transport>,
+}
+
+/// Joins `target` and negotiates the terminal transport without starting a UI.
+pub(crate) async fn join_transport(
+    base_url: &str,
+    target: &str,
+    passcode: &str,
+    label: Option<&str>,
+    profile_override: Option<&str>,
+    request_mcp: bool,
+) -> Result<(String, NegotiatedSingle), CliError> {
+    let (session_id, inferred_base) = interpret_session_target(target)?;
+    let base = inferred_base.unwrap_or_else(|| base_url.to_string());
+    let (manager, _) = session_manager_for(&base, profile_override).await?;
+    let passcode = passcode.trim().to_ascii_uppercase();
+    let joined = manager
+        .join(
+            &session_id,
+            Some(passcode.as_str()),
+            None,
+            label,
+            request_mcp,
+        )
+        .await?;
+    let negotiated = negotiate_transport(
+        joined.handle(),
+        Some(passcode.as_str()),
+        label,
+        request_mcp,
+        None,
+    )
+    .await?;
+    match negotiated {
+        NegotiatedTransport::Single(single) => Ok((session_id, single)),
+        NegotiatedTransport::WebRtcOfferer { .. } => Err(CliError::TransportNegotiation(
+            "unexpected offerer transport while joining session".into(),
+        )),
+    }
+}
+
+/// Joins `target` with MCP requested and waits for the host's MCP channel.
+pub(crate) async fn join_mcp_channel(
+    base_url: &str,
+    target: &str,
+    passcode: &str,
+    label: Option<&str>,
+    profile_override: Option<&str>,
+) -> Result<McpChannelSession, CliError> {
+    let (session_id, single) =
+        join_transport(base_url, target, passcode, label, profile_override, true).await?;
+    let Some(channels) = single.webrtc_channels else {
+        return Err(CliError::TransportNegotiation(
+            "mcp channel unavailable for this transport".into(),
+        ));
+    };
+    let mcp = match timeout(
+        crate::server::terminal::host::MCP_CHANNEL_TIMEOUT,
+        channels.wait_for(crate::server::terminal::host::MCP_CHANNEL_LABEL),
+    )
+    .await
+    {
+        Ok(Ok(mcp)) => mcp,
+        Ok(Err(err)) => return Err(CliError::TransportNegotiation(err.to_string())),
+        Err(_) => {
+            return Err(CliError::TransportNegotiation(format!(
+                "timed out waiting for mcp channel on session {session_id}"
+            )));
+        }
+    };
+    Ok(McpChannelSession {
+        session_id,
+        transport: single.transport,
+        mcp,
+    })
+}
+
+async fn session_manager_for(
+    base: &str,
+    profile_override: Option<&str>,
+) -> Result<(SessionManager, Option<String>), CliError> {
+    let requires_token = auth::manager_requires_access_token(base);
+    let access_token = auth::maybe_access_token(profile_override, requires_token)
+        .await
+        .map_err(|err| CliError::Auth(err.to_string()))?;
+    if requires_token && access_token.is_none() {
+        return Err(CliError::Auth(
+            "This private beach requires authentication. Run `beach login` and try again.".into(),
+        ));
+    }
+
+    let mut config = SessionConfig::new(base)?;
+    if let Some(token) = access_token.clone() {
+        config = config.with_bearer_token(Some(token));
+    }
+    Ok((SessionManager::new(config)?, access_token))
+}
+
 pub(crate) fn interpret_session_target(target: &str) -> Result<(String, Option<String>), CliError> {
     if let Ok(id) = Uuid::parse_str(target) {
         return Ok((id.to_string(), None));
@@ -327,7 +472,7 @@ fn base_from_url(url: &Url) -> Option<String> {
     Some(base.to_string())
 }
 
-fn prompt_passcode() -> Result<String, CliError> {
+pub(super) fn prompt_passcode() -> Result<String, CliError> {
     print!("🔐 Enter passcode: ");
     io::stdout().flush()?;
     let mut buf = String::new();
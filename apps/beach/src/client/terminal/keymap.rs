@@ -0,0 +1,860 @@
+//! Key bindings for the TUI client. Every local action has a name and a
+//! default binding. Users can rebind any of them under `[client.keys]` in
+//! `~/.beach/config`:
+//!
+//! ```toml
+//! [client.keys]
+//! prefix = "ctrl+a"
+//!
+//! [client.keys.bind]
+//! detach = ["ctrl+q"]
+//!
+//! [client.keys.prefix_bind]
+//! toggle-predictions = ["p"]
+//!
+//! [client.keys.copy_mode_vi]
+//! cursor-left = ["h", "left"]
+//! ```
+//!
+//! An entry replaces the defaults for that action and takes its keys away
+//! from any other action in the same table. An empty list unbinds the action.
+
+use super::{CopyModeCommand, CopyModeKeySet, CopyModeSearchDirection, WordMotion};
+use crate::client::grid_renderer::SelectionMode;
+use crate::terminal::config::{KeyConfig, load_user_config};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use tracing::debug;
+
+pub(super) const SCROLL_TOGGLE_KEY_ENV: &str = "BEACH_SCROLL_TOGGLE_KEY";
+pub(super) const COPY_SHORTCUTS_ENV: &str = "BEACH_COPY_SHORTCUTS";
+const DEFAULT_PREFIX: &str = "ctrl+b";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub(super) fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let (code, modifiers) = normalize(code, modifiers);
+        Self { code, modifiers }
+    }
+
+    pub(super) fn matches(&self, key: &KeyEvent) -> bool {
+        key.kind == KeyEventKind::Press && Self::new(key.code, key.modifiers) == *self
+    }
+}
+
+/// Terminals disagree on whether shifted characters carry `SHIFT`, so
+/// bindings and events are folded to one form: plain characters keep their
+/// case, characters chorded with CTRL or ALT are lowercased with `SHIFT`
+/// kept as a modifier, and `SHIFT` is dropped from punctuation.
+fn normalize(code: KeyCode, mut modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    let KeyCode::Char(ch) = code else {
+        return (code, modifiers);
+    };
+    if !ch.is_ascii_alphabetic() {
+        modifiers.remove(KeyModifiers::SHIFT);
+        return (code, modifiers);
+    }
+    if ch.is_ascii_uppercase() {
+        modifiers.insert(KeyModifiers::SHIFT);
+    }
+    if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+        return (KeyCode::Char(ch.to_ascii_lowercase()), modifiers);
+    }
+    let ch = if modifiers.contains(KeyModifiers::SHIFT) {
+        ch.to_ascii_uppercase()
+    } else {
+        ch
+    };
+    modifiers.remove(KeyModifiers::SHIFT);
+    (KeyCode::Char(ch), modifiers)
+}
+
+pub(super) fn parse_key_binding(value: &str) -> Option<KeyBinding> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut key_token: Option<&str> = None;
+    for part in value.split('+') {
+        let trimmed = part.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match trimmed.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+            "alt" | "option" | "opt" => modifiers |= KeyModifiers::ALT,
+            "shift" => modifiers |= KeyModifiers::SHIFT,
+            "super" => modifiers |= KeyModifiers::SUPER,
+            "cmd" | "command" => modifiers |= KeyModifiers::SUPER,
+            _ => {
+                if key_token.is_some() {
+                    return None;
+                }
+                key_token = Some(trimmed);
+            }
+        }
+    }
+
+    let token = key_token?;
+    let code = match token.to_ascii_lowercase().as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "space" => KeyCode::Char(' '),
+        "plus" => KeyCode::Char('+'),
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        lower => {
+            if let Some(number) = lower
+                .strip_prefix('f')
+                .and_then(|digits| digits.parse::<u8>().ok())
+                .filter(|number| (1..=24).contains(number))
+            {
+                KeyCode::F(number)
+            } else {
+                // Single characters keep their case: `G` and `g` differ.
+                let mut chars = token.chars();
+                let ch = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(ch)
+            }
+        }
+    };
+
+    Some(KeyBinding::new(code, modifiers))
+}
+
+pub(super) fn parse_key_bindings(value: &str) -> Vec<KeyBinding> {
+    value
+        .split(',')
+        .filter_map(|part| parse_key_binding(part.trim()))
+        .collect()
+}
+
+pub(super) fn format_key_binding(binding: &KeyBinding) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if binding.modifiers.contains(KeyModifiers::CONTROL) {
+        parts.push("CTRL".to_string());
+    }
+    if binding.modifiers.contains(KeyModifiers::ALT) {
+        parts.push("ALT".to_string());
+    }
+    if binding.modifiers.contains(KeyModifiers::SHIFT) {
+        parts.push("SHIFT".to_string());
+    }
+    if binding.modifiers.contains(KeyModifiers::SUPER) {
+        parts.push("CMD".to_string());
+    }
+    let key = match binding.code {
+        KeyCode::Esc => "ESC".to_string(),
+        KeyCode::Enter => "ENTER".to_string(),
+        KeyCode::Tab => "TAB".to_string(),
+        KeyCode::Backspace => "BACKSPACE".to_string(),
+        KeyCode::PageUp => "PAGEUP".to_string(),
+        KeyCode::PageDown => "PAGEDOWN".to_string(),
+        KeyCode::Home => "HOME".to_string(),
+        KeyCode::End => "END".to_string(),
+        KeyCode::Delete => "DELETE".to_string(),
+        KeyCode::Insert => "INSERT".to_string(),
+        KeyCode::Char(' ') => "SPACE".to_string(),
+        KeyCode::Char('+') => "PLUS".to_string(),
+        KeyCode::Char(c) if binding.modifiers.is_empty() => c.to_string(),
+        KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+        _ => format!("{:?}", binding.code).to_ascii_uppercase(),
+    };
+    parts.push(key);
+    parts.join("+")
+}
+
+/// A client action that can be bound to a key, either directly or after the
+/// prefix key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientAction {
+    EnterCopyMode,
+    ToggleScrollback,
+    ScrollPageUp,
+    ScrollPageDown,
+    Search,
+    Paste,
+    ClearViewport,
+    TogglePredictions,
+    ShowPeers,
+    ToggleChat,
+    PinNote,
+    SendFile,
+    ReceiveFile,
+    Detach,
+}
+
+impl ClientAction {
+    pub const ALL: [ClientAction; 14] = [
+        ClientAction::EnterCopyMode,
+        ClientAction::ToggleScrollback,
+        ClientAction::ScrollPageUp,
+        ClientAction::ScrollPageDown,
+        ClientAction::Search,
+        ClientAction::Paste,
+        ClientAction::ClearViewport,
+        ClientAction::TogglePredictions,
+        ClientAction::ShowPeers,
+        ClientAction::ToggleChat,
+        ClientAction::PinNote,
+        ClientAction::SendFile,
+        ClientAction::ReceiveFile,
+        ClientAction::Detach,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ClientAction::EnterCopyMode => "enter-copy-mode",
+            ClientAction::ToggleScrollback => "toggle-scrollback",
+            ClientAction::ScrollPageUp => "scroll-page-up",
+            ClientAction::ScrollPageDown => "scroll-page-down",
+            ClientAction::Search => "search",
+            ClientAction::Paste => "paste",
+            ClientAction::ClearViewport => "clear-viewport",
+            ClientAction::TogglePredictions => "toggle-predictions",
+            ClientAction::ShowPeers => "show-peers",
+            ClientAction::ToggleChat => "toggle-chat",
+            ClientAction::PinNote => "pin-note",
+            ClientAction::SendFile => "send-file",
+            ClientAction::ReceiveFile => "receive-file",
+            ClientAction::Detach => "detach",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ClientAction::EnterCopyMode => "enter copy mode",
+            ClientAction::ToggleScrollback => "switch between tail and scrollback",
+            ClientAction::ScrollPageUp => "enter copy mode one page up",
+            ClientAction::ScrollPageDown => "enter copy mode one page down",
+            ClientAction::Search => "enter copy mode and search backward",
+            ClientAction::Paste => "paste the local clipboard",
+            ClientAction::ClearViewport => "clear the host viewport",
+            ClientAction::TogglePredictions => "toggle predictive echo",
+            ClientAction::ShowPeers => "show who is attached",
+            ClientAction::ToggleChat => "show the chat pane and write a message, or hide it",
+            ClientAction::PinNote => "pin a note to the copy-mode cursor row",
+            ClientAction::SendFile => "send a local file to the host",
+            ClientAction::ReceiveFile => "fetch a file from the host",
+            ClientAction::Detach => "detach from the session",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+const ROOT_DEFAULTS: &[(ClientAction, &[&str])] = &[
+    (ClientAction::EnterCopyMode, &["alt+["]),
+    (ClientAction::ToggleScrollback, &["ctrl+esc"]),
+    (ClientAction::ScrollPageUp, &["pageup"]),
+    (ClientAction::ClearViewport, &["cmd+k"]),
+    (ClientAction::Detach, &["ctrl+q"]),
+];
+
+const PREFIX_DEFAULTS: &[(ClientAction, &[&str])] = &[
+    (ClientAction::EnterCopyMode, &["["]),
+    (ClientAction::ScrollPageUp, &["pageup"]),
+    (ClientAction::ScrollPageDown, &["pagedown"]),
+    (ClientAction::Search, &["/"]),
+    (ClientAction::Paste, &["]"]),
+    (ClientAction::TogglePredictions, &["p"]),
+    (ClientAction::ShowPeers, &["w"]),
+    (ClientAction::ToggleChat, &["c"]),
+    (ClientAction::PinNote, &["n"]),
+    (ClientAction::SendFile, &["s"]),
+    (ClientAction::ReceiveFile, &["r"]),
+    (ClientAction::Detach, &["d"]),
+];
+
+/// Copy-mode commands by name, following tmux's copy-mode command names.
+const COPY_MODE_COMMANDS: &[(&str, CopyModeCommand)] = &[
+    ("cursor-up", CopyModeCommand::Move { rows: -1, cols: 0 }),
+    ("cursor-down", CopyModeCommand::Move { rows: 1, cols: 0 }),
+    ("cursor-left", CopyModeCommand::Move { rows: 0, cols: -1 }),
+    ("cursor-right", CopyModeCommand::Move { rows: 0, cols: 1 }),
+    ("start-of-line", CopyModeCommand::MoveToLineStart),
+    ("end-of-line", CopyModeCommand::MoveToLineEnd),
+    ("page-up", CopyModeCommand::Page { delta: -1 }),
+    ("page-down", CopyModeCommand::Page { delta: 1 }),
+    ("halfpage-up", CopyModeCommand::HalfPage { delta: -1 }),
+    ("halfpage-down", CopyModeCommand::HalfPage { delta: 1 }),
+    ("history-top", CopyModeCommand::JumpTop),
+    ("history-bottom", CopyModeCommand::JumpBottom),
+    (
+        "next-word",
+        CopyModeCommand::MoveWord(WordMotion::NextStart),
+    ),
+    (
+        "next-word-end",
+        CopyModeCommand::MoveWord(WordMotion::NextEnd),
+    ),
+    (
+        "previous-word",
+        CopyModeCommand::MoveWord(WordMotion::PrevStart),
+    ),
+    ("begin-selection", CopyModeCommand::BeginSelection),
+    ("clear-selection", CopyModeCommand::ClearSelection),
+    ("toggle-selection", CopyModeCommand::ToggleSelection),
+    (
+        "select-line",
+        CopyModeCommand::SetSelectionMode(SelectionMode::Line),
+    ),
+    (
+        "rectangle-toggle",
+        CopyModeCommand::SetSelectionMode(SelectionMode::Block),
+    ),
+    ("copy-selection", CopyModeCommand::CopySelection),
+    (
+        "copy-selection-and-cancel",
+        CopyModeCommand::CopySelectionAndExit,
+    ),
+    ("cancel", CopyModeCommand::Cancel),
+    ("vi-keys", CopyModeCommand::SetMode(CopyModeKeySet::Vi)),
+    (
+        "emacs-keys",
+        CopyModeCommand::SetMode(CopyModeKeySet::Emacs),
+    ),
+    (
+        "search-forward",
+        CopyModeCommand::Search(CopyModeSearchDirection::Forward),
+    ),
+    (
+        "search-backward",
+        CopyModeCommand::Search(CopyModeSearchDirection::Backward),
+    ),
+    (
+        "search-again",
+        CopyModeCommand::RepeatLastSearch(CopyModeSearchDirection::Forward),
+    ),
+    (
+        "search-reverse",
+        CopyModeCommand::RepeatLastSearch(CopyModeSearchDirection::Backward),
+    ),
+];
+
+/// Bindings shared by both copy-mode key sets. They come first so they win
+/// over a key-set binding for the same key.
+const COPY_MODE_COMMON_DEFAULTS: &[(&str, &[&str])] = &[
+    ("cancel", &["esc", "ctrl+g", "alt+]", "alt+}"]),
+    ("copy-selection-and-cancel", &["enter", "alt+y"]),
+    ("clear-selection", &["alt+c"]),
+    ("vi-keys", &["alt+v"]),
+    ("emacs-keys", &["alt+e"]),
+    ("cursor-up", &["up"]),
+    ("cursor-down", &["down"]),
+    ("cursor-left", &["left"]),
+    ("cursor-right", &["right"]),
+    ("page-up", &["pageup"]),
+    ("page-down", &["pagedown"]),
+    ("start-of-line", &["home"]),
+    ("end-of-line", &["end"]),
+];
+
+const COPY_MODE_VI_DEFAULTS: &[(&str, &[&str])] = &[
+    ("cursor-left", &["h"]),
+    ("cursor-down", &["j"]),
+    ("cursor-up", &["k"]),
+    ("cursor-right", &["l"]),
+    ("start-of-line", &["0", "^"]),
+    ("end-of-line", &["$"]),
+    ("page-up", &["ctrl+b"]),
+    ("page-down", &["ctrl+f"]),
+    ("halfpage-up", &["ctrl+u"]),
+    ("halfpage-down", &["ctrl+d"]),
+    ("history-top", &["g"]),
+    ("history-bottom", &["G"]),
+    ("next-word", &["w"]),
+    ("next-word-end", &["e"]),
+    ("previous-word", &["b"]),
+    ("toggle-selection", &["space", "v"]),
+    ("select-line", &["V"]),
+    ("rectangle-toggle", &["ctrl+v"]),
+    ("copy-selection-and-cancel", &["y"]),
+    ("cancel", &["q"]),
+    ("search-forward", &["/"]),
+    ("search-backward", &["?"]),
+    ("search-again", &["n"]),
+    ("search-reverse", &["N"]),
+];
+
+const COPY_MODE_EMACS_DEFAULTS: &[(&str, &[&str])] = &[
+    ("cursor-left", &["ctrl+b"]),
+    ("cursor-down", &["ctrl+n"]),
+    ("cursor-up", &["ctrl+p"]),
+    ("cursor-right", &["ctrl+f"]),
+    ("start-of-line", &["ctrl+a"]),
+    ("end-of-line", &["ctrl+e"]),
+    ("page-down", &["ctrl+v"]),
+    ("next-word", &["alt+f"]),
+    ("next-word-end", &["alt+d"]),
+    ("previous-word", &["alt+b"]),
+    ("toggle-selection", &["ctrl+space", "space"]),
+    ("copy-selection", &["ctrl+y", "alt+w", "y"]),
+    ("copy-selection-and-cancel", &["ctrl+w"]),
+    ("search-forward", &["ctrl+s"]),
+    ("search-backward", &["ctrl+r"]),
+];
+
+fn named_copy_mode_command(name: &str) -> Option<CopyModeCommand> {
+    COPY_MODE_COMMANDS
+        .iter()
+        .find(|(candidate, _)| *candidate == name)
+        .map(|(_, command)| *command)
+}
+
+/// Ordered action-to-keys table. Lookups return the first action bound to a
+/// key, so earlier entries win when defaults overlap.
+#[derive(Clone, Debug)]
+struct BindingTable<A> {
+    entries: Vec<(A, Vec<KeyBinding>)>,
+}
+
+impl<A: Copy + PartialEq> BindingTable<A> {
+    fn from_defaults<'a>(defaults: impl IntoIterator<Item = (A, &'a [&'a str])>) -> Self {
+        let mut table = Self {
+            entries: Vec::new(),
+        };
+        for (action, keys) in defaults {
+            let bindings = keys.iter().filter_map(|key| parse_key_binding(key));
+            match table.entries.iter_mut().find(|(bound, _)| *bound == action) {
+                Some((_, existing)) => existing.extend(bindings),
+                None => table.entries.push((action, bindings.collect())),
+            }
+        }
+        table
+    }
+
+    fn lookup(&self, key: &KeyEvent) -> Option<A> {
+        self.entries
+            .iter()
+            .find(|(_, bindings)| bindings.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    fn bindings(&self, action: A) -> &[KeyBinding] {
+        self.entries
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, bindings)| bindings.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn rebind(&mut self, action: A, bindings: Vec<KeyBinding>) {
+        for (_, existing) in &mut self.entries {
+            existing.retain(|binding| !bindings.contains(binding));
+        }
+        match self.entries.iter_mut().find(|(bound, _)| *bound == action) {
+            Some((_, existing)) => *existing = bindings,
+            None => self.entries.push((action, bindings)),
+        }
+    }
+
+    /// Applies `[client.keys.*]` overrides. Entries whose keys all fail to
+    /// parse keep their defaults so a typo does not silently unbind them.
+    fn apply_overrides(
+        &mut self,
+        table: &str,
+        overrides: &BTreeMap<String, Vec<String>>,
+        resolve: impl Fn(&str) -> Option<A>,
+    ) {
+        for (name, keys) in overrides {
+            let Some(action) = resolve(name) else {
+                debug!(
+                    target = "client::config",
+                    table,
+                    action = %name,
+                    "unknown key binding action"
+                );
+                continue;
+            };
+            let bindings: Vec<KeyBinding> = keys
+                .iter()
+                .filter_map(|key| parse_key_binding(key))
+                .collect();
+            if bindings.is_empty() && !keys.is_empty() {
+                debug!(
+                    target = "client::config",
+                    table,
+                    action = %name,
+                    ?keys,
+                    "invalid key bindings, keeping defaults"
+                );
+                continue;
+            }
+            self.rebind(action, bindings);
+        }
+    }
+}
+
+/// The client's active key bindings.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    prefix: Option<KeyBinding>,
+    root: BindingTable<ClientAction>,
+    prefixed: BindingTable<ClientAction>,
+    copy_mode_vi: BindingTable<&'static str>,
+    copy_mode_emacs: BindingTable<&'static str>,
+    copy_shortcuts: Vec<KeyBinding>,
+    double_esc: bool,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&KeyConfig::default(), None, None)
+    }
+}
+
+impl Keymap {
+    /// Loads bindings from `~/.beach/config`. The `BEACH_SCROLL_TOGGLE_KEY`
+    /// and `BEACH_COPY_SHORTCUTS` environment variables override the file.
+    pub fn load() -> Self {
+        let config = load_user_config()
+            .and_then(|config| config.client)
+            .and_then(|client| client.keys)
+            .unwrap_or_default();
+        Self::from_config(
+            &config,
+            env::var(SCROLL_TOGGLE_KEY_ENV).ok(),
+            env::var(COPY_SHORTCUTS_ENV).ok(),
+        )
+    }
+
+    fn from_config(
+        config: &KeyConfig,
+        scroll_toggle_env: Option<String>,
+        copy_shortcuts_env: Option<String>,
+    ) -> Self {
+        let prefix_value = config.prefix.as_deref().unwrap_or(DEFAULT_PREFIX).trim();
+        let prefix = if prefix_value.is_empty() || prefix_value.eq_ignore_ascii_case("none") {
+            None
+        } else {
+            parse_key_binding(prefix_value).or_else(|| {
+                debug!(
+                    target = "client::config",
+                    value = prefix_value,
+                    "invalid prefix key, using default"
+                );
+                parse_key_binding(DEFAULT_PREFIX)
+            })
+        };
+
+        let mut root = BindingTable::from_defaults(ROOT_DEFAULTS.iter().copied());
+        if let Some(list) = config.scroll_toggle.as_ref() {
+            let parsed = parse_key_bindings(&list.join(","));
+            if !parsed.is_empty() {
+                root.rebind(ClientAction::ToggleScrollback, parsed);
+            }
+        }
+        root.apply_overrides("bind", &config.bind, ClientAction::from_name);
+        if let Some(value) = scroll_toggle_env {
+            let parsed = parse_key_bindings(&value);
+            if parsed.is_empty() {
+                debug!(
+                    target = "client::config",
+                    value, "invalid scroll toggle binding, using default"
+                );
+            } else {
+                root.rebind(ClientAction::ToggleScrollback, parsed);
+            }
+        }
+
+        let mut prefixed = BindingTable::from_defaults(PREFIX_DEFAULTS.iter().copied());
+        prefixed.apply_overrides("prefix_bind", &config.prefix_bind, ClientAction::from_name);
+
+        let copy_mode_table = |defaults: &[(&'static str, &'static [&'static str])],
+                               overrides: &BTreeMap<String, Vec<String>>,
+                               table: &str| {
+            let mut bindings = BindingTable::from_defaults(
+                COPY_MODE_COMMON_DEFAULTS.iter().chain(defaults).copied(),
+            );
+            bindings.apply_overrides(table, overrides, |name| {
+                COPY_MODE_COMMANDS
+                    .iter()
+                    .find(|(candidate, _)| *candidate == name)
+                    .map(|(candidate, _)| *candidate)
+            });
+            bindings
+        };
+        let copy_mode_vi = copy_mode_table(COPY_MODE_VI_DEFAULTS, &config.copy_mode_vi, "vi");
+        let copy_mode_emacs =
+            copy_mode_table(COPY_MODE_EMACS_DEFAULTS, &config.copy_mode_emacs, "emacs");
+
+        let copy_shortcuts = copy_shortcuts_env
+            .map(|value| parse_key_bindings(&value))
+            .filter(|parsed| !parsed.is_empty())
+            .or_else(|| {
+                config
+                    .copy_shortcuts
+                    .as_ref()
+                    .map(|list| parse_key_bindings(&list.join(",")))
+                    .filter(|parsed| !parsed.is_empty())
+            })
+            .unwrap_or_else(default_copy_shortcut_bindings);
+
+        Self {
+            prefix,
+            root,
+            prefixed,
+            copy_mode_vi,
+            copy_mode_emacs,
+            copy_shortcuts,
+            double_esc: config.double_esc.unwrap_or(true),
+        }
+    }
+
+    pub(super) fn is_prefix(&self, key: &KeyEvent) -> bool {
+        self.prefix.is_some_and(|prefix| prefix.matches(key))
+    }
+
+    pub(super) fn root_action(&self, key: &KeyEvent) -> Option<ClientAction> {
+        self.root.lookup(key)
+    }
+
+    pub(super) fn prefix_action(&self, key: &KeyEvent) -> Option<ClientAction> {
+        self.prefixed.lookup(key)
+    }
+
+    pub(super) fn root_bindings(&self, action: ClientAction) -> &[KeyBinding] {
+        self.root.bindings(action)
+    }
+
+    pub(super) fn copy_mode_command(
+        &self,
+        mode: CopyModeKeySet,
+        key: &KeyEvent,
+    ) -> Option<CopyModeCommand> {
+        let table = match mode {
+            CopyModeKeySet::Vi => &self.copy_mode_vi,
+            CopyModeKeySet::Emacs => &self.copy_mode_emacs,
+        };
+        table.lookup(key).and_then(named_copy_mode_command)
+    }
+
+    pub(super) fn is_copy_shortcut(&self, key: &KeyEvent) -> bool {
+        self.copy_shortcuts
+            .iter()
+            .any(|binding| binding.matches(key))
+    }
+
+    pub(super) fn double_esc(&self) -> bool {
+        self.double_esc
+    }
+
+    /// Human-readable listing of every binding, printed by `beach keys`.
+    pub fn describe(&self) -> String {
+        let keys = |bindings: &[KeyBinding]| {
+            if bindings.is_empty() {
+                "(unbound)".to_string()
+            } else {
+                bindings
+                    .iter()
+                    .map(format_key_binding)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+        let mut out = String::new();
+        let _ = writeln!(out, "bind (root):");
+        for action in ClientAction::ALL {
+            let _ = writeln!(
+                out,
+                "  {:<24} {:<20} {}",
+                keys(self.root.bindings(action)),
+                action.name(),
+                action.description()
+            );
+        }
+        let _ = writeln!(out);
+        match self.prefix {
+            Some(prefix) => {
+                let _ = writeln!(out, "prefix_bind (after {}):", format_key_binding(&prefix));
+            }
+            None => {
+                let _ = writeln!(out, "prefix_bind (prefix disabled):");
+            }
+        }
+        for action in ClientAction::ALL {
+            let _ = writeln!(
+                out,
+                "  {:<24} {:<20} {}",
+                keys(self.prefixed.bindings(action)),
+                action.name(),
+                action.description()
+            );
+        }
+        for (title, table) in [
+            ("copy_mode_vi", &self.copy_mode_vi),
+            ("copy_mode_emacs", &self.copy_mode_emacs),
+        ] {
+            let _ = writeln!(out);
+            let _ = writeln!(out, "{title}:");
+            for (name, _) in COPY_MODE_COMMANDS {
+                let _ = writeln!(out, "  {:<24} {}", keys(table.bindings(*name)), name);
+            }
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "copy_shortcuts: {}", keys(&self.copy_shortcuts));
+        let _ = writeln!(
+            out,
+            "double_esc: {}",
+            if self.double_esc { "on" } else { "off" }
+        );
+        out
+    }
+}
+
+fn default_copy_shortcut_bindings() -> Vec<KeyBinding> {
+    vec![
+        // Cmd/Ctrl(OS) + C
+        KeyBinding::new(KeyCode::Char('c'), KeyModifiers::SUPER),
+        // Ctrl+Shift+C (common in terminals)
+        KeyBinding::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL.union(KeyModifiers::SHIFT),
+        ),
+        // Ctrl+Insert (Windows)
+        KeyBinding::new(KeyCode::Insert, KeyModifiers::CONTROL),
+        // Plain Ctrl+C while in copy-mode
+        KeyBinding::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn overrides(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, keys)| {
+                (
+                    name.to_string(),
+                    keys.iter().map(|key| key.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test_timeout::timeout]
+    fn bindings_fold_shifted_characters() {
+        let upper_g = parse_key_binding("G").unwrap();
+        assert!(upper_g.matches(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(upper_g.matches(&key(KeyCode::Char('G'), KeyModifiers::NONE)));
+        assert!(!upper_g.matches(&key(KeyCode::Char('g'), KeyModifiers::NONE)));
+        assert_eq!(parse_key_binding("shift+g"), Some(upper_g));
+
+        let copy = parse_key_binding("ctrl+shift+c").unwrap();
+        assert!(copy.matches(&key(
+            KeyCode::Char('C'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )));
+        assert!(!copy.matches(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert_eq!(format_key_binding(&copy), "CTRL+SHIFT+C");
+
+        let question = parse_key_binding("?").unwrap();
+        assert!(question.matches(&key(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+        assert_eq!(parse_key_binding("f5").map(|b| b.code), Some(KeyCode::F(5)));
+    }
+
+    #[test_timeout::timeout]
+    fn config_rebinds_actions_and_prefix() {
+        let config = KeyConfig {
+            prefix: Some("ctrl+a".into()),
+            bind: overrides(&[("detach", &["ctrl+d"]), ("enter-copy-mode", &[])]),
+            prefix_bind: overrides(&[("toggle-predictions", &["d"]), ("bogus", &["x"])]),
+            copy_mode_vi: overrides(&[("cursor-left", &["y"])]),
+            ..KeyConfig::default()
+        };
+        let keymap = Keymap::from_config(&config, None, None);
+
+        assert!(keymap.is_prefix(&key(KeyCode::Char('a'), KeyModifiers::CONTROL)));
+        assert!(!keymap.is_prefix(&key(KeyCode::Char('b'), KeyModifiers::CONTROL)));
+        assert_eq!(
+            keymap.root_action(&key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(ClientAction::Detach)
+        );
+        assert_eq!(
+            keymap.root_action(&key(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(
+            keymap.root_action(&key(KeyCode::Char('['), KeyModifiers::ALT)),
+            None
+        );
+        // Rebinding `d` takes it away from the default detach binding.
+        assert_eq!(
+            keymap.prefix_action(&key(KeyCode::Char('d'), KeyModifiers::NONE)),
+            Some(ClientAction::TogglePredictions)
+        );
+        assert!(keymap.prefixed.bindings(ClientAction::Detach).is_empty());
+
+        assert!(matches!(
+            keymap.copy_mode_command(
+                CopyModeKeySet::Vi,
+                &key(KeyCode::Char('y'), KeyModifiers::NONE)
+            ),
+            Some(CopyModeCommand::Move { rows: 0, cols: -1 })
+        ));
+        assert!(
+            keymap
+                .copy_mode_command(
+                    CopyModeKeySet::Vi,
+                    &key(KeyCode::Char('h'), KeyModifiers::NONE)
+                )
+                .is_none()
+        );
+        assert!(matches!(
+            keymap.copy_mode_command(
+                CopyModeKeySet::Emacs,
+                &key(KeyCode::Char('y'), KeyModifiers::NONE)
+            ),
+            Some(CopyModeCommand::CopySelection)
+        ));
+    }
+
+    #[test_timeout::timeout]
+    fn environment_overrides_config_and_prefix_can_be_disabled() {
+        let config = KeyConfig {
+            prefix: Some("none".into()),
+            scroll_toggle: Some(vec!["ctrl+s".into()]),
+            ..KeyConfig::default()
+        };
+        let keymap = Keymap::from_config(&config, Some("alt+s".into()), None);
+        assert!(!keymap.is_prefix(&key(KeyCode::Char('b'), KeyModifiers::CONTROL)));
+        assert_eq!(
+            keymap.root_action(&key(KeyCode::Char('s'), KeyModifiers::ALT)),
+            Some(ClientAction::ToggleScrollback)
+        );
+        assert_eq!(
+            keymap.root_action(&key(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            None
+        );
+
+        let listing = keymap.describe();
+        assert!(listing.contains("prefix_bind (prefix disabled):"));
+        assert!(listing.contains("ALT+S"));
+    }
+}
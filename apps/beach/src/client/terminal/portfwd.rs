@@ -0,0 +1,406 @@
+//! Client half of `beach.portfwd`: `beach join --forward` listens locally and
+//! has the host dial the destination for every connection, while
+//! `--remote-forward` asks the host to listen and dials the destination here.
+
+use crate::protocol::portfwd::PortForwardEvent;
+use crate::session::terminal::portfwd::{ForwardStreams, PortForwardSink, dial, parse_host_port};
+use crate::transport::extensions::ExtensionEvent;
+use crate::transport::{ExtensionDirection, ExtensionLane, Transport};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::debug;
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// `LISTEN_PORT:HOST:PORT`, as with ssh's `-L` and `-R`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardSpec {
+    pub listen_port: u16,
+    pub host: String,
+    pub port: u16,
+}
+
+impl FromStr for ForwardSpec {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (listen, target) = value
+            .split_once(':')
+            .ok_or_else(|| format!("expected LISTEN_PORT:HOST:PORT, got '{value}'"))?;
+        let listen_port = listen
+            .parse::<u16>()
+            .map_err(|_| format!("invalid listen port in '{value}'"))?;
+        let (host, port) = parse_host_port(target)?;
+        Ok(Self {
+            listen_port,
+            host,
+            port,
+        })
+    }
+}
+
+impl fmt::Display for ForwardSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "{}:[{}]:{}", self.listen_port, self.host, self.port)
+        } else {
+            write!(f, "{}:{}:{}", self.listen_port, self.host, self.port)
+        }
+    }
+}
+
+/// The forwards of one joined client. Dropping it closes the local listeners
+/// and every forwarded connection.
+pub struct PortForwards {
+    streams: Arc<ForwardStreams>,
+    /// Local connections waiting for the host to dial, with their forward.
+    pending: Arc<Mutex<HashMap<u64, (ForwardSpec, TcpStream)>>>,
+    /// Remote forwards by the port the host listens on.
+    remote: HashMap<u16, ForwardSpec>,
+    notices: Arc<Mutex<Vec<String>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl PortForwards {
+    /// Binds the local listeners and asks the host for the remote ones.
+    pub fn start(
+        transport: Arc<dyn Transport>,
+        local: &[ForwardSpec],
+        remote: &[ForwardSpec],
+    ) -> io::Result<Arc<Self>> {
+        let sink: PortForwardSink = Arc::new(move |event: PortForwardEvent| {
+            if let Err(err) = transport.send_extension(
+                ExtensionDirection::ClientToHost,
+                event.to_frame(),
+                ExtensionLane::ControlOrdered,
+            ) {
+                debug!(
+                    target = "client::portfwd",
+                    error = %err,
+                    "failed to send port forward frame"
+                );
+            }
+        });
+        let forwards = Arc::new(Self {
+            streams: Arc::new(ForwardStreams::new(sink)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            remote: remote
+                .iter()
+                .map(|spec| (spec.listen_port, spec.clone()))
+                .collect(),
+            notices: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+        });
+        let next_stream = Arc::new(AtomicU64::new(1));
+        for spec in local {
+            let listener = TcpListener::bind(("127.0.0.1", spec.listen_port)).map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("cannot listen on 127.0.0.1:{}: {err}", spec.listen_port),
+                )
+            })?;
+            forwards.accept_local(listener, spec.clone(), Arc::clone(&next_stream))?;
+        }
+        for spec in remote {
+            forwards.streams.emit(PortForwardEvent::Listen {
+                port: spec.listen_port,
+            });
+        }
+        Ok(forwards)
+    }
+
+    /// Applies a frame from the host.
+    pub fn handle(&self, event: PortForwardEvent) {
+        match event {
+            PortForwardEvent::Opened { stream } => {
+                let Some((_, socket)) = self.pending.lock().unwrap().remove(&stream) else {
+                    return;
+                };
+                if let Err(err) = self.streams.attach(stream, socket) {
+                    self.streams.emit(PortForwardEvent::Reset {
+                        stream,
+                        reason: err.to_string(),
+                    });
+                }
+            }
+            PortForwardEvent::Accepted { stream, port } => {
+                let Some(spec) = self.remote.get(&port).cloned() else {
+                    self.streams.emit(PortForwardEvent::Reset {
+                        stream,
+                        reason: format!("no remote forward for port {port}"),
+                    });
+                    return;
+                };
+                let streams = Arc::clone(&self.streams);
+                let notices = Arc::clone(&self.notices);
+                thread::spawn(move || match dial(&spec.host, spec.port) {
+                    Ok(socket) => {
+                        if let Err(err) = streams.connected(stream, socket) {
+                            streams.emit(PortForwardEvent::Reset {
+                                stream,
+                                reason: err.to_string(),
+                            });
+                        }
+                    }
+                    Err(err) => {
+                        let reason = format!("{}:{}: {err}", spec.host, spec.port);
+                        notices
+                            .lock()
+                            .unwrap()
+                            .push(format!("remote forward {spec} failed: {reason}"));
+                        streams.emit(PortForwardEvent::Reset { stream, reason });
+                    }
+                });
+            }
+            PortForwardEvent::Listening { port } => {
+                if let Some(spec) = self.remote.get(&port) {
+                    self.notice(format!("remote forward {spec} is listening on the host"));
+                }
+            }
+            PortForwardEvent::Unlisten { port, reason } => {
+                if let Some(spec) = self.remote.get(&port) {
+                    self.notice(format!("remote forward {spec} stopped: {reason}"));
+                }
+            }
+            PortForwardEvent::Data { stream, data } => self.streams.data(stream, data),
+            PortForwardEvent::Credit { stream, bytes } => self.streams.credit(stream, bytes),
+            PortForwardEvent::Close { stream } => self.streams.close(stream),
+            PortForwardEvent::Reset { stream, reason } => {
+                let refused = self.pending.lock().unwrap().remove(&stream);
+                if let Some((spec, _)) = refused {
+                    self.notice(format!("forward {spec} refused: {reason}"));
+                }
+                self.streams.reset(stream);
+            }
+            PortForwardEvent::Connect { .. } | PortForwardEvent::Listen { .. } => {}
+        }
+    }
+
+    /// Messages worth showing the user since the last call.
+    pub fn take_notices(&self) -> Vec<String> {
+        std::mem::take(&mut *self.notices.lock().unwrap())
+    }
+
+    fn notice(&self, message: String) {
+        self.notices.lock().unwrap().push(message);
+    }
+
+    fn accept_local(
+        &self,
+        listener: TcpListener,
+        spec: ForwardSpec,
+        next_stream: Arc<AtomicU64>,
+    ) -> io::Result<()> {
+        listener.set_nonblocking(true)?;
+        let streams = Arc::clone(&self.streams);
+        let pending = Arc::clone(&self.pending);
+        let stop = Arc::clone(&self.stop);
+        thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((socket, _)) => {
+                        if socket.set_nonblocking(false).is_err() {
+                            continue;
+                        }
+                        let stream = next_stream.fetch_add(2, Ordering::SeqCst);
+                        pending
+                            .lock()
+                            .unwrap()
+                            .insert(stream, (spec.clone(), socket));
+                        streams.emit(PortForwardEvent::Connect {
+                            stream,
+                            host: spec.host.clone(),
+                            port: spec.port,
+                        });
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(ACCEPT_POLL);
+                    }
+                    Err(err) => {
+                        debug!(
+                            target = "client::portfwd",
+                            forward = %spec,
+                            error = %err,
+                            "local forward listener failed"
+                        );
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Drop for PortForwards {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.pending.lock().unwrap().clear();
+        self.streams.close_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{self, ClientFrame, HostFrame};
+    use crate::server::terminal::portfwd::{ForwardRule, PortForwardHub, PortForwardSettings};
+    use crate::transport::extensions::ExtensionHubs;
+    use crate::transport::{Payload, TransportError, TransportKind, TransportPair};
+    use std::io::{Read, Write};
+
+    #[test_timeout::timeout]
+    fn parses_forward_specs() {
+        let spec: ForwardSpec = "8080:localhost:3000".parse().unwrap();
+        assert_eq!(
+            spec,
+            ForwardSpec {
+                listen_port: 8080,
+                host: "localhost".into(),
+                port: 3000,
+            }
+        );
+        assert_eq!(spec.to_string(), "8080:localhost:3000");
+        let spec: ForwardSpec = "2222:[::1]:22".parse().unwrap();
+        assert_eq!(spec.host, "::1");
+        assert_eq!(spec.to_string(), "2222:[::1]:22");
+        assert!("8080".parse::<ForwardSpec>().is_err());
+        assert!("8080:3000".parse::<ForwardSpec>().is_err());
+        assert!("x:localhost:3000".parse::<ForwardSpec>().is_err());
+    }
+
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    /// Runs a host hub on the server end of an IPC pair until the pair closes.
+    fn spawn_host(server: Arc<dyn Transport>, settings: PortForwardSettings) {
+        thread::spawn(move || {
+            let hubs = ExtensionHubs::default().with(Arc::new(PortForwardHub::new(settings)));
+            hubs.join(&server);
+            loop {
+                match server.recv(Duration::from_millis(50)) {
+                    Ok(message) => {
+                        let Payload::Binary(bytes) = message.payload else {
+                            continue;
+                        };
+                        let Ok(ClientFrame::Extension { frame }) =
+                            protocol::decode_client_frame_binary(&bytes)
+                        else {
+                            continue;
+                        };
+                        hubs.dispatch(&server, &frame);
+                    }
+                    Err(TransportError::Timeout) => {}
+                    Err(_) => break,
+                }
+            }
+            hubs.leave(server.id().0);
+        });
+    }
+
+    /// Feeds host frames to the client forwards until `done` is set.
+    fn spawn_client(
+        client: Arc<dyn Transport>,
+        forwards: Arc<PortForwards>,
+        done: Arc<AtomicBool>,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                let Ok(message) = client.recv(Duration::from_millis(50)) else {
+                    continue;
+                };
+                let Payload::Binary(bytes) = message.payload else {
+                    continue;
+                };
+                let Ok(HostFrame::Extension { frame }) = protocol::decode_host_frame_binary(&bytes)
+                else {
+                    continue;
+                };
+                if let Some(event) = PortForwardEvent::from_frame(&frame) {
+                    forwards.handle(event);
+                }
+            }
+        })
+    }
+
+    #[test_timeout::timeout]
+    fn local_and_remote_forwards_carry_tcp_streams() {
+        let service = TcpListener::bind("127.0.0.1:0").unwrap();
+        let service_port = service.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for socket in service.incoming() {
+                let mut socket = socket.unwrap();
+                thread::spawn(move || {
+                    let mut request = Vec::new();
+                    socket.read_to_end(&mut request).unwrap();
+                    request.reverse();
+                    socket.write_all(&request).unwrap();
+                });
+            }
+        });
+        let local_port = free_port();
+        let remote_port = free_port();
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+        let client: Arc<dyn Transport> = Arc::from(pair.client);
+        spawn_host(
+            server,
+            PortForwardSettings {
+                allow_connect: vec![ForwardRule {
+                    host: "localhost".into(),
+                    port: service_port,
+                }],
+                allow_listen: vec![remote_port],
+            },
+        );
+        let spec = |listen_port| ForwardSpec {
+            listen_port,
+            host: "127.0.0.1".into(),
+            port: service_port,
+        };
+        let forwards =
+            PortForwards::start(client.clone(), &[spec(local_port)], &[spec(remote_port)]).unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+        let pump = spawn_client(client, Arc::clone(&forwards), Arc::clone(&done));
+
+        let round_trip = |port: u16| {
+            let mut socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            socket.write_all(b"beach").unwrap();
+            socket.shutdown(std::net::Shutdown::Write).unwrap();
+            let mut reply = Vec::new();
+            socket.read_to_end(&mut reply).unwrap();
+            reply
+        };
+        assert_eq!(round_trip(local_port), b"hcaeb");
+
+        // The host's listener comes up asynchronously.
+        let mut notices = Vec::new();
+        while notices.is_empty() {
+            thread::sleep(Duration::from_millis(20));
+            notices = forwards.take_notices();
+        }
+        assert_eq!(
+            notices,
+            vec![format!(
+                "remote forward {} is listening on the host",
+                spec(remote_port)
+            )]
+        );
+        assert_eq!(round_trip(remote_port), b"hcaeb");
+
+        done.store(true, Ordering::SeqCst);
+        pump.join().unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+//! Client half of the presence extension: publishes this client's copy-mode
+//! cursor, selection and typing activity, and tracks what other peers publish.
+
+use crate::client::grid_renderer::{PeerOverlay, SelectionMode, SelectionPosition};
+use crate::protocol::presence::{
+    PresenceEvent, PresencePoint, PresenceSelection, PresenceSelectionMode, PresenceState,
+};
+use crate::terminal::config::{PresenceConfig, load_user_config};
+use ratatui::style::Color;
+use std::collections::BTreeMap;
+use std::env;
+use std::time::SystemTime;
+
+/// A peer counts as typing for this long after its last keystroke.
+const TYPING_WINDOW_MS: u64 = 3_000;
+/// Keystrokes closer together than this are not republished.
+const INPUT_PUBLISH_INTERVAL_MS: u64 = 1_000;
+const PEER_COLORS: [Color; 6] = [
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::LightRed,
+    Color::LightCyan,
+];
+
+#[derive(Debug, Default)]
+pub(super) struct Presence {
+    enabled: bool,
+    label: String,
+    last_input_ms: Option<u64>,
+    published: Option<PresenceState>,
+    peers: BTreeMap<u64, PresenceState>,
+}
+
+impl Presence {
+    pub(super) fn load() -> Self {
+        let config = load_user_config()
+            .and_then(|config| config.client)
+            .and_then(|client| client.presence)
+            .unwrap_or_default();
+        let user = env::var("USER").or_else(|_| env::var("USERNAME")).ok();
+        Self::from_config(&config, user)
+    }
+
+    fn from_config(config: &PresenceConfig, user: Option<String>) -> Self {
+        let label = config
+            .label
+            .clone()
+            .or(user)
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "guest".to_string());
+        Self {
+            enabled: config.enabled.unwrap_or(true),
+            label,
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(super) fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub(super) fn note_input(&mut self, now_ms: u64) {
+        let stale = self
+            .last_input_ms
+            .is_none_or(|last| now_ms.saturating_sub(last) >= INPUT_PUBLISH_INTERVAL_MS);
+        if stale {
+            self.last_input_ms = Some(now_ms);
+        }
+    }
+
+    /// Returns the state to publish if it changed since the last call.
+    pub(super) fn next_update(
+        &mut self,
+        cursor: Option<SelectionPosition>,
+        selection: Option<(SelectionPosition, SelectionPosition, SelectionMode)>,
+    ) -> Option<PresenceState> {
+        if !self.enabled {
+            return None;
+        }
+        let state = PresenceState {
+            label: self.label.clone(),
+            cursor: cursor.map(point),
+            selection: selection.map(|(anchor, head, mode)| PresenceSelection {
+                anchor: point(anchor),
+                head: point(head),
+                mode: match mode {
+                    SelectionMode::Character => PresenceSelectionMode::Character,
+                    SelectionMode::Line => PresenceSelectionMode::Line,
+                    SelectionMode::Block => PresenceSelectionMode::Block,
+                },
+            }),
+            last_input_ms: self.last_input_ms,
+        };
+        if self.published.as_ref() == Some(&state) {
+            return None;
+        }
+        self.published = Some(state.clone());
+        Some(state)
+    }
+
+    pub(super) fn apply(&mut self, event: PresenceEvent) {
+        if !self.enabled {
+            return;
+        }
+        match event {
+            PresenceEvent::Update { peer, state } => {
+                self.peers.insert(peer, state);
+            }
+            PresenceEvent::Leave { peer } => {
+                self.peers.remove(&peer);
+            }
+        }
+    }
+
+    pub(super) fn labels(&self) -> Vec<&str> {
+        self.peers
+            .values()
+            .map(|state| state.label.as_str())
+            .collect()
+    }
+
+    pub(super) fn overlays(&self, now_ms: u64) -> Vec<PeerOverlay> {
+        self.peers
+            .iter()
+            .map(|(peer, state)| PeerOverlay {
+                label: state.label.clone(),
+                color: PEER_COLORS[(*peer as usize) % PEER_COLORS.len()],
+                typing: state
+                    .last_input_ms
+                    .is_some_and(|last| now_ms.saturating_sub(last) < TYPING_WINDOW_MS),
+                cursor: state.cursor.map(position),
+                selection: state.selection.map(|selection| {
+                    let mode = match selection.mode {
+                        PresenceSelectionMode::Character => SelectionMode::Character,
+                        PresenceSelectionMode::Line => SelectionMode::Line,
+                        PresenceSelectionMode::Block => SelectionMode::Block,
+                    };
+                    (position(selection.anchor), position(selection.head), mode)
+                }),
+            })
+            .collect()
+    }
+}
+
+/// Unix time in milliseconds, the clock presence timestamps use.
+pub(super) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn point(pos: SelectionPosition) -> PresencePoint {
+    PresencePoint {
+        row: pos.row,
+        col: u32::try_from(pos.col).unwrap_or(u32::MAX),
+    }
+}
+
+fn position(point: PresencePoint) -> SelectionPosition {
+    SelectionPosition {
+        row: point.row,
+        col: point.col as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::timeout]
+    fn publishes_changes_only_and_tracks_peers() {
+        let mut presence = Presence::from_config(&PresenceConfig::default(), Some("ana".into()));
+        let cursor = SelectionPosition { row: 40, col: 3 };
+
+        let state = presence
+            .next_update(Some(cursor), None)
+            .expect("first update");
+        assert_eq!(state.label, "ana");
+        assert_eq!(state.cursor, Some(PresencePoint { row: 40, col: 3 }));
+        assert_eq!(presence.next_update(Some(cursor), None), None);
+
+        presence.note_input(10_000);
+        assert!(presence.next_update(Some(cursor), None).is_some());
+        presence.note_input(10_500);
+        assert_eq!(presence.next_update(Some(cursor), None), None);
+
+        presence.apply(PresenceEvent::Update {
+            peer: 2,
+            state: PresenceState {
+                label: "ben".into(),
+                cursor: Some(PresencePoint { row: 7, col: 1 }),
+                last_input_ms: Some(9_000),
+                ..PresenceState::default()
+            },
+        });
+        let overlays = presence.overlays(10_000);
+        assert_eq!(overlays.len(), 1);
+        assert!(overlays[0].typing);
+        assert_eq!(
+            overlays[0].cursor,
+            Some(SelectionPosition { row: 7, col: 1 })
+        );
+        assert!(!presence.overlays(20_000)[0].typing);
+
+        presence.apply(PresenceEvent::Leave { peer: 2 });
+        assert!(presence.labels().is_empty());
+    }
+
+    #[test_timeout::timeout]
+    fn disabled_presence_neither_publishes_nor_tracks() {
+        let config = PresenceConfig {
+            enabled: Some(false),
+            label: None,
+        };
+        let mut presence = Presence::from_config(&config, None);
+        assert_eq!(presence.next_update(None, None), None);
+        presence.apply(PresenceEvent::Leave { peer: 1 });
+        presence.apply(PresenceEvent::Update {
+            peer: 1,
+            state: PresenceState::default(),
+        });
+        assert!(presence.overlays(0).is_empty());
+    }
+}
@@ -0,0 +1,576 @@
+//! Client half of `beach.transfer`: files sent to or fetched from the host.
+//! The same [`Transfers`] bookkeeping drives `beach send` / `beach receive`
+//! and the TUI's `send-file` / `receive-file` commands.
+
+use super::join::{join_transport, prompt_passcode};
+use crate::protocol::transfer::TransferEvent;
+use crate::protocol::{self, HostFrame};
+use crate::session::terminal::transfer::{
+    DEFAULT_TRANSFER_MAX_BYTES, IncomingFile, OutgoingFile, TransferError,
+};
+use crate::terminal::cli::{ReceiveArgs, SendArgs};
+use crate::terminal::error::CliError;
+use crate::transport::extensions::ExtensionEvent;
+use crate::transport::{ExtensionDirection, ExtensionLane, Payload, Transport, TransportError};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+enum Download {
+    /// Asked the host for `path`; waiting for its offer.
+    Requested(String),
+    Receiving(IncomingFile),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum TransferNotice {
+    Sent { name: String },
+    Saved { path: PathBuf },
+    Failed { name: String, reason: String },
+}
+
+impl TransferNotice {
+    pub(super) fn describe(&self) -> String {
+        match self {
+            TransferNotice::Sent { name } => format!("sent {name} to the host"),
+            TransferNotice::Saved { path } => format!("saved {}", path.display()),
+            TransferNotice::Failed { name, reason } => format!("{name} failed: {reason}"),
+        }
+    }
+}
+
+/// Frames to send back to the host and transfers that ended.
+#[derive(Debug, Default)]
+pub(super) struct TransferStep {
+    pub(super) replies: Vec<TransferEvent>,
+    pub(super) notices: Vec<TransferNotice>,
+}
+
+impl TransferStep {
+    fn fail(&mut self, id: u64, name: String, reason: String) {
+        self.replies.push(TransferEvent::Cancel {
+            id,
+            reason: reason.clone(),
+        });
+        self.notices.push(TransferNotice::Failed { name, reason });
+    }
+}
+
+pub(super) struct Transfers {
+    next_id: u64,
+    /// Where downloads are saved.
+    dir: PathBuf,
+    max_bytes: u64,
+    uploads: HashMap<u64, OutgoingFile>,
+    downloads: HashMap<u64, Download>,
+}
+
+impl Transfers {
+    pub(super) fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            next_id: 1,
+            dir,
+            max_bytes,
+            uploads: HashMap::new(),
+            downloads: HashMap::new(),
+        }
+    }
+
+    /// Hashes `path` and returns the offer to send. The host enforces its own
+    /// size limit.
+    pub(super) fn upload(&mut self, path: &Path) -> Result<TransferEvent, TransferError> {
+        let id = self.allocate_id();
+        let outgoing = OutgoingFile::open(id, path, u64::MAX)?;
+        let offer = TransferEvent::Offer(outgoing.offer().clone());
+        self.uploads.insert(id, outgoing);
+        Ok(offer)
+    }
+
+    pub(super) fn download(&mut self, path: &str) -> TransferEvent {
+        let id = self.allocate_id();
+        self.downloads
+            .insert(id, Download::Requested(path.to_string()));
+        TransferEvent::Request {
+            id,
+            path: path.to_string(),
+        }
+    }
+
+    pub(super) fn is_idle(&self) -> bool {
+        self.uploads.is_empty() && self.downloads.is_empty()
+    }
+
+    pub(super) fn apply(&mut self, event: TransferEvent) -> TransferStep {
+        let mut step = TransferStep::default();
+        match event {
+            TransferEvent::Offer(offer) => {
+                let id = offer.id;
+                let Some(Download::Requested(path)) = self.downloads.remove(&id) else {
+                    step.replies.push(TransferEvent::Cancel {
+                        id,
+                        reason: "no file was requested".into(),
+                    });
+                    return step;
+                };
+                match IncomingFile::create(&self.dir, offer, self.max_bytes) {
+                    Ok(incoming) => {
+                        step.replies.push(TransferEvent::Accept {
+                            id,
+                            offset: incoming.offset(),
+                        });
+                        if incoming.is_complete() {
+                            finish_download(&mut step, id, incoming);
+                        } else {
+                            self.downloads.insert(id, Download::Receiving(incoming));
+                        }
+                    }
+                    Err(err) => step.fail(id, path, err.to_string()),
+                }
+            }
+            TransferEvent::Accept { id, offset } => {
+                if let Some(outgoing) = self.uploads.get_mut(&id) {
+                    if let Err(err) = outgoing.start(offset) {
+                        let name = outgoing.offer().name.clone();
+                        self.uploads.remove(&id);
+                        step.fail(id, name, err.to_string());
+                    }
+                }
+            }
+            TransferEvent::Ack { id, offset } => {
+                if let Some(outgoing) = self.uploads.get_mut(&id) {
+                    outgoing.acknowledge(offset);
+                }
+            }
+            TransferEvent::Chunk { id, offset, data } => {
+                let Some(Download::Receiving(incoming)) = self.downloads.get_mut(&id) else {
+                    return step;
+                };
+                match incoming.write_chunk(offset, &data) {
+                    Ok(_) if incoming.is_complete() => {
+                        if let Some(Download::Receiving(incoming)) = self.downloads.remove(&id) {
+                            finish_download(&mut step, id, incoming);
+                        }
+                    }
+                    Ok(Some(offset)) => step.replies.push(TransferEvent::Ack { id, offset }),
+                    Ok(None) => {}
+                    Err(err) => {
+                        let name = incoming.offer().name.clone();
+                        self.downloads.remove(&id);
+                        step.fail(id, name, err.to_string());
+                    }
+                }
+            }
+            TransferEvent::Complete { id } => {
+                if let Some(outgoing) = self.uploads.remove(&id) {
+                    step.notices.push(TransferNotice::Sent {
+                        name: outgoing.offer().name.clone(),
+                    });
+                }
+            }
+            TransferEvent::Cancel { id, reason } => {
+                let name = match (self.uploads.remove(&id), self.downloads.remove(&id)) {
+                    (Some(outgoing), _) => outgoing.offer().name.clone(),
+                    (_, Some(Download::Requested(path))) => path,
+                    (_, Some(Download::Receiving(incoming))) => incoming.offer().name.clone(),
+                    (None, None) => return step,
+                };
+                step.notices.push(TransferNotice::Failed { name, reason });
+            }
+            TransferEvent::Request { id, .. } => {
+                step.replies.push(TransferEvent::Cancel {
+                    id,
+                    reason: "clients do not serve files".into(),
+                });
+            }
+        }
+        step
+    }
+
+    /// Reads the next chunks of every accepted upload, up to each window.
+    pub(super) fn pump(&mut self) -> TransferStep {
+        let mut step = TransferStep::default();
+        let mut failed = Vec::new();
+        for (id, outgoing) in self.uploads.iter_mut() {
+            loop {
+                match outgoing.next_chunk() {
+                    Ok(Some(chunk)) => step.replies.push(chunk),
+                    Ok(None) => break,
+                    Err(err) => {
+                        failed.push((*id, outgoing.offer().name.clone(), err.to_string()));
+                        break;
+                    }
+                }
+            }
+        }
+        for (id, name, reason) in failed {
+            self.uploads.remove(&id);
+            step.fail(id, name, reason);
+        }
+        step
+    }
+
+    /// One line summarizing active transfers, e.g. `⇡ build.log 40%`.
+    pub(super) fn progress(&self) -> Option<String> {
+        let mut parts: Vec<String> = self
+            .uploads
+            .values()
+            .map(|outgoing| {
+                let offer = outgoing.offer();
+                format!(
+                    "⇡ {} {}%",
+                    offer.name,
+                    percent(outgoing.acked(), offer.size)
+                )
+            })
+            .chain(self.downloads.values().map(|download| match download {
+                Download::Requested(path) => format!("⇣ {path} waiting for host"),
+                Download::Receiving(incoming) => {
+                    let offer = incoming.offer();
+                    format!(
+                        "⇣ {} {}%",
+                        offer.name,
+                        percent(incoming.offset(), offer.size)
+                    )
+                }
+            }))
+            .collect();
+        if parts.is_empty() {
+            return None;
+        }
+        parts.sort();
+        Some(parts.join(" • "))
+    }
+
+    fn allocate_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+fn finish_download(step: &mut TransferStep, id: u64, incoming: IncomingFile) {
+    let name = incoming.offer().name.clone();
+    match incoming.finish() {
+        Ok(path) => {
+            step.replies.push(TransferEvent::Complete { id });
+            step.notices.push(TransferNotice::Saved { path });
+        }
+        Err(err) => step.fail(id, name, err.to_string()),
+    }
+}
+
+fn percent(done: u64, total: u64) -> u64 {
+    if total == 0 {
+        100
+    } else {
+        done.min(total) * 100 / total
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PathPromptKind {
+    Send,
+    Receive,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum PathPromptKey {
+    Editing,
+    Submit(PathPromptKind, String),
+    Cancelled,
+}
+
+/// Status-line prompt for the path of a file to send or fetch.
+#[derive(Debug)]
+pub(super) struct PathPrompt {
+    kind: PathPromptKind,
+    buffer: String,
+}
+
+impl PathPrompt {
+    pub(super) fn new(kind: PathPromptKind) -> Self {
+        Self {
+            kind,
+            buffer: String::new(),
+        }
+    }
+
+    pub(super) fn handle_key(&mut self, key: &KeyEvent) -> PathPromptKey {
+        match key.code {
+            KeyCode::Esc => PathPromptKey::Cancelled,
+            KeyCode::Enter => match self.buffer.trim() {
+                "" => PathPromptKey::Cancelled,
+                path => PathPromptKey::Submit(self.kind, path.to_string()),
+            },
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                PathPromptKey::Editing
+            }
+            KeyCode::Char(ch)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.buffer.push(ch);
+                PathPromptKey::Editing
+            }
+            _ => PathPromptKey::Editing,
+        }
+    }
+
+    pub(super) fn status(&self) -> String {
+        match self.kind {
+            PathPromptKind::Send => format!("send file: {}", self.buffer),
+            PathPromptKind::Receive => format!("fetch from host: {}", self.buffer),
+        }
+    }
+}
+
+/// `beach send`: uploads one file to the session's host.
+pub async fn send(
+    base_url: &str,
+    args: SendArgs,
+    profile_override: Option<String>,
+) -> Result<(), CliError> {
+    let SendArgs {
+        target,
+        path,
+        passcode,
+        label,
+    } = args;
+    let mut transfers = Transfers::new(PathBuf::from("."), DEFAULT_TRANSFER_MAX_BYTES);
+    let offer = transfers
+        .upload(&path)
+        .map_err(|err| CliError::Transfer(format!("{}: {err}", path.display())))?;
+    run_cli_transfer(
+        base_url,
+        &target,
+        passcode,
+        label,
+        profile_override,
+        transfers,
+        offer,
+    )
+    .await
+}
+
+/// `beach receive`: fetches one file from the session's host.
+pub async fn receive(
+    base_url: &str,
+    args: ReceiveArgs,
+    profile_override: Option<String>,
+) -> Result<(), CliError> {
+    let ReceiveArgs {
+        target,
+        path,
+        passcode,
+        label,
+        output_dir,
+        max_size,
+    } = args;
+    fs::create_dir_all(&output_dir)?;
+    let mut transfers = Transfers::new(output_dir, max_size.unwrap_or(DEFAULT_TRANSFER_MAX_BYTES));
+    let request = transfers.download(&path);
+    run_cli_transfer(
+        base_url,
+        &target,
+        passcode,
+        label,
+        profile_override,
+        transfers,
+        request,
+    )
+    .await
+}
+
+async fn run_cli_transfer(
+    base_url: &str,
+    target: &str,
+    passcode: Option<String>,
+    label: Option<String>,
+    profile_override: Option<String>,
+    transfers: Transfers,
+    first: TransferEvent,
+) -> Result<(), CliError> {
+    let passcode = match passcode {
+        Some(code) => code,
+        None => prompt_passcode()?,
+    };
+    let (session_id, single) = join_transport(
+        base_url,
+        target,
+        &passcode,
+        label.as_deref(),
+        profile_override.as_deref(),
+        false,
+    )
+    .await?;
+    info!(target = "client::transfer", session_id = %session_id, "transport established for file transfer");
+    let transport = single.transport;
+    tokio::task::spawn_blocking(move || drive_cli_transfer(transport, transfers, first))
+        .await
+        .map_err(|err| CliError::Runtime(format!("file transfer task failed: {err}")))?
+}
+
+fn drive_cli_transfer(
+    transport: Arc<dyn Transport>,
+    mut transfers: Transfers,
+    first: TransferEvent,
+) -> Result<(), CliError> {
+    let show_progress = io::stderr().is_terminal();
+    let mut last_progress = Instant::now();
+    let mut failure = None;
+    send_events(transport.as_ref(), std::slice::from_ref(&first))?;
+    eprintln!("⇅ waiting for the host to approve the transfer...");
+    while !transfers.is_idle() {
+        let mut step = transfers.pump();
+        match transport.recv(Duration::from_millis(50)) {
+            Ok(message) => {
+                if let Payload::Binary(bytes) = message.payload {
+                    match protocol::decode_host_frame_binary(&bytes) {
+                        Ok(HostFrame::Extension { frame }) => {
+                            if let Some(event) = TransferEvent::from_frame(&frame) {
+                                let applied = transfers.apply(event);
+                                step.replies.extend(applied.replies);
+                                step.notices.extend(applied.notices);
+                            }
+                        }
+                        Ok(HostFrame::Shutdown) => {
+                            return Err(CliError::Transfer("the host ended the session".into()));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Err(TransportError::Timeout) => {}
+            Err(err) => return Err(CliError::Transfer(format!("connection lost: {err}"))),
+        }
+        send_events(transport.as_ref(), &step.replies)?;
+        for notice in step.notices {
+            if show_progress {
+                eprint!("\r\x1b[2K");
+            }
+            eprintln!("⇅ {}", notice.describe());
+            if let TransferNotice::Failed { reason, .. } = notice {
+                failure = Some(reason);
+            }
+        }
+        if show_progress && last_progress.elapsed() >= PROGRESS_INTERVAL {
+            if let Some(progress) = transfers.progress() {
+                eprint!("\r\x1b[2K{progress}");
+                let _ = io::stderr().flush();
+            }
+            last_progress = Instant::now();
+        }
+    }
+    match failure {
+        Some(reason) => Err(CliError::Transfer(reason)),
+        None => Ok(()),
+    }
+}
+
+fn send_events(transport: &dyn Transport, events: &[TransferEvent]) -> Result<(), CliError> {
+    for event in events {
+        transport
+            .send_extension(
+                ExtensionDirection::ClientToHost,
+                event.to_frame(),
+                ExtensionLane::ControlOrdered,
+            )
+            .map_err(|err| CliError::Transfer(format!("connection lost: {err}")))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::transfer::TransferOffer;
+    use crate::session::terminal::transfer::sha256_file;
+    use bytes::Bytes;
+
+    #[test_timeout::timeout]
+    fn uploads_and_downloads_follow_the_host() {
+        let dir =
+            std::env::temp_dir().join(format!("beach-client-transfer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("notes.txt");
+        fs::write(&source, b"pinned notes").unwrap();
+        let mut transfers = Transfers::new(dir.join("downloads"), 1024);
+        fs::create_dir_all(dir.join("downloads")).unwrap();
+
+        let Ok(TransferEvent::Offer(offer)) = transfers.upload(&source) else {
+            panic!("expected an offer");
+        };
+        assert!(transfers.pump().replies.is_empty(), "nothing before accept");
+        transfers.apply(TransferEvent::Accept {
+            id: offer.id,
+            offset: 7,
+        });
+        assert_eq!(
+            transfers.pump().replies,
+            vec![TransferEvent::Chunk {
+                id: offer.id,
+                offset: 7,
+                data: Bytes::from_static(b"notes"),
+            }]
+        );
+        assert_eq!(
+            transfers
+                .apply(TransferEvent::Complete { id: offer.id })
+                .notices,
+            vec![TransferNotice::Sent {
+                name: "notes.txt".into()
+            }]
+        );
+
+        let TransferEvent::Request { id, .. } = transfers.download("remote/notes.txt") else {
+            panic!("expected a request");
+        };
+        let step = transfers.apply(TransferEvent::Offer(TransferOffer {
+            id,
+            name: "notes.txt".into(),
+            size: 12,
+            sha256: sha256_file(&source).unwrap(),
+        }));
+        assert_eq!(step.replies, vec![TransferEvent::Accept { id, offset: 0 }]);
+        assert_eq!(transfers.progress(), Some("⇣ notes.txt 0%".to_string()));
+        let step = transfers.apply(TransferEvent::Chunk {
+            id,
+            offset: 0,
+            data: Bytes::from_static(b"pinned notes"),
+        });
+        assert_eq!(step.replies, vec![TransferEvent::Complete { id }]);
+        assert_eq!(
+            step.notices,
+            vec![TransferNotice::Saved {
+                path: dir.join("downloads").join("notes.txt")
+            }]
+        );
+        assert!(transfers.is_idle());
+
+        let TransferEvent::Request { id, .. } = transfers.download("huge.bin") else {
+            panic!("expected a request");
+        };
+        let step = transfers.apply(TransferEvent::Offer(TransferOffer {
+            id,
+            name: "huge.bin".into(),
+            size: 4096,
+            sha256: "0".repeat(64),
+        }));
+        assert!(matches!(
+            step.replies.as_slice(),
+            [TransferEvent::Cancel { .. }]
+        ));
+        assert!(transfers.is_idle());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -3,12 +3,15 @@ pub mod server;
 
 use serde::{Deserialize, Serialize};
 
+use crate::cache::terminal::export::ExportFormat;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DiagnosticRequest {
     GetCursorState,
     GetTerminalDimensions,
     GetCacheState,
     GetRendererState,
+    ExportGrid(ExportFormat),
     SendInput(String),
 }
 
@@ -55,6 +58,12 @@ pub enum DiagnosticResponse {
     TerminalDimensions(TerminalDimensions),
     CacheState(CacheState),
     RendererState(RendererState),
-    InputSent { bytes: usize },
+    GridExport {
+        format: ExportFormat,
+        content: String,
+    },
+    InputSent {
+        bytes: usize,
+    },
     Error(String),
 }
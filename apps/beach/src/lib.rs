@@ -6,6 +6,7 @@ pub mod mcp;
 pub mod metrics;
 pub mod model;
 pub mod protocol;
+pub mod sdk;
 pub mod server;
 pub mod session;
 pub mod sync;
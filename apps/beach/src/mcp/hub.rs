@@ -0,0 +1,623 @@
+//! Aggregating MCP server that fronts several remote beach sessions.
+//!
+//! Each upstream is the `mcp-jsonrpc` WebRTC channel of a joined session. The
+//! hub speaks the same JSON-RPC surface as [`crate::mcp::McpServer`] and routes
+//! requests by the `session_id` argument or the session segment of a
+//! `beach://session/<id>/...` resource URI. Leases stay owned by each host, so
+//! they remain scoped per session; the hub only remembers which session issued
+//! a lease until the lease expires.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use serde_json::{Value, json};
+use tokio::io::{self, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::time::timeout;
+use tracing::{debug, info, warn};
+
+use crate::mcp::McpConfig;
+use crate::mcp::protocol::{
+    JSONRPC_VERSION, JsonRpcRequest, JsonRpcResponse, JsonRpcResult, internal_error,
+    invalid_params, method_not_found, not_found,
+};
+use crate::mcp::terminal::{ACQUIRE_LEASE, LIST_SESSIONS, RELEASE_LEASE};
+use crate::transport::{Payload, Transport, TransportError};
+
+const UPSTREAM_RECV_TIMEOUT: Duration = Duration::from_secs(30);
+const UPSTREAM_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Upper bound on how long a lease route is kept, matching the longest TTL a
+/// host grants.
+const MAX_LEASE_TTL: Duration = Duration::from_secs(120);
+
+/// JSON-RPC client for one session's MCP channel.
+pub struct HubUpstream {
+    session_id: String,
+    transport: Arc<dyn Transport>,
+    next_id: AtomicU64,
+    pending: StdMutex<HashMap<u64, oneshot::Sender<Value>>>,
+    subscriptions: StdMutex<HashMap<String, mpsc::Sender<Value>>>,
+}
+
+impl HubUpstream {
+    pub fn spawn(session_id: impl Into<String>, transport: Arc<dyn Transport>) -> Arc<Self> {
+        let upstream = Arc::new(Self {
+            session_id: session_id.into(),
+            transport,
+            next_id: AtomicU64::new(1),
+            pending: StdMutex::new(HashMap::new()),
+            subscriptions: StdMutex::new(HashMap::new()),
+        });
+        let reader = Arc::clone(&upstream);
+        tokio::task::spawn_blocking(move || reader.read_loop());
+        upstream
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, HubError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let mut line = serde_json::to_string(&json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "id": id,
+            "method": method,
+            "params": params,
+        }))
+        .map_err(|err| HubError::Internal(err.to_string()))?;
+        line.push('\n');
+        let transport = Arc::clone(&self.transport);
+        let sent = tokio::task::spawn_blocking(move || transport.send_text(&line))
+            .await
+            .map_err(|err| HubError::Internal(err.to_string()))
+            .and_then(|res| res.map_err(|err| HubError::Upstream(err.to_string())));
+        if let Err(err) = sent {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(err);
+        }
+        let response = match timeout(UPSTREAM_CALL_TIMEOUT, rx).await {
+            Ok(Ok(value)) => value,
+            Ok(Err(_)) => return Err(HubError::Upstream("upstream session closed".into())),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                return Err(HubError::Upstream("upstream request timed out".into()));
+            }
+        };
+        if let Some(error) = response.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            return Err(HubError::Upstream(message.to_string()));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    fn read_loop(&self) {
+        loop {
+            let text = match self.transport.recv(UPSTREAM_RECV_TIMEOUT) {
+                Ok(message) => match message.payload {
+                    Payload::Text(text) => text,
+                    Payload::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                },
+                Err(TransportError::Timeout) => continue,
+                Err(err) => {
+                    debug!(
+                        target = "mcp::hub",
+                        session_id = %self.session_id,
+                        error = %err,
+                        "upstream channel closed"
+                    );
+                    break;
+                }
+            };
+            for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                match serde_json::from_str::<Value>(line) {
+                    Ok(value) => self.dispatch(value),
+                    Err(err) => warn!(
+                        target = "mcp::hub",
+                        session_id = %self.session_id,
+                        error = %err,
+                        "invalid upstream payload"
+                    ),
+                }
+            }
+        }
+        // Dropping the senders fails every in-flight call.
+        self.pending.lock().unwrap().clear();
+        self.subscriptions.lock().unwrap().clear();
+    }
+
+    fn dispatch(&self, value: Value) {
+        if let Some(id) = value.get("id").and_then(Value::as_u64) {
+            if let Some(tx) = self.pending.lock().unwrap().remove(&id) {
+                let _ = tx.send(value);
+            }
+            return;
+        }
+        let subscription_id = value
+            .pointer("/params/subscription_id")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let Some(subscription_id) = subscription_id else {
+            return;
+        };
+        let target = self
+            .subscriptions
+            .lock()
+            .unwrap()
+            .get(&subscription_id)
+            .cloned();
+        if let Some(tx) = target {
+            if tx.try_send(value).is_err() {
+                debug!(
+                    target = "mcp::hub",
+                    session_id = %self.session_id,
+                    subscription_id = %subscription_id,
+                    "dropping notification for slow or closed connection"
+                );
+            }
+        }
+    }
+}
+
+pub struct McpHub {
+    config: McpConfig,
+    service: Arc<HubService>,
+}
+
+impl McpHub {
+    pub fn new(config: McpConfig, upstreams: Vec<Arc<HubUpstream>>) -> Self {
+        let upstreams = upstreams
+            .into_iter()
+            .map(|upstream| (upstream.session_id.clone(), upstream))
+            .collect();
+        Self {
+            config,
+            service: Arc::new(HubService {
+                upstreams,
+                leases: LeaseRoutes::default(),
+            }),
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        if self.config.use_stdio {
+            handle_connection(io::stdin(), io::stdout(), self.service).await;
+            return Ok(());
+        }
+        let path: PathBuf = self
+            .config
+            .socket
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("MCP socket path missing"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create socket dir {parent:?}"))?;
+        }
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("remove existing socket {path:?}"))?;
+        }
+        let listener =
+            UnixListener::bind(&path).with_context(|| format!("bind MCP socket at {path:?}"))?;
+        info!(socket = %path.display(), sessions = self.service.upstreams.len(), "MCP hub listening");
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let service = Arc::clone(&self.service);
+            tokio::spawn(async move {
+                let (reader, writer) = stream.into_split();
+                handle_connection(reader, writer, service).await;
+            });
+        }
+    }
+}
+
+struct HubService {
+    /// Keyed by session id, which also orders merged listings.
+    upstreams: BTreeMap<String, Arc<HubUpstream>>,
+    leases: LeaseRoutes,
+}
+
+/// Lease id to owning session, so `releaseLease` can be routed. Entries lapse
+/// with the lease they describe.
+#[derive(Default)]
+struct LeaseRoutes {
+    routes: StdMutex<HashMap<String, LeaseRoute>>,
+}
+
+struct LeaseRoute {
+    session_id: String,
+    deadline: Instant,
+}
+
+impl LeaseRoutes {
+    /// Records the lease in an `acquireLease` result, expiring it at the
+    /// host's `expires_at` (unix milliseconds).
+    fn insert(&self, session_id: String, lease: &Value) {
+        let Some(lease_id) = lease.get("lease_id").and_then(Value::as_str) else {
+            return;
+        };
+        let ttl = lease
+            .get("expires_at")
+            .and_then(Value::as_u64)
+            .map(|expires_at| {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                Duration::from_millis(expires_at).saturating_sub(now)
+            })
+            .map_or(MAX_LEASE_TTL, |ttl| ttl.min(MAX_LEASE_TTL));
+        let mut routes = self.routes.lock().unwrap();
+        let now = Instant::now();
+        routes.retain(|_, route| route.deadline > now);
+        routes.insert(
+            lease_id.to_string(),
+            LeaseRoute {
+                session_id,
+                deadline: now + ttl,
+            },
+        );
+    }
+
+    fn take(&self, lease_id: &str) -> Result<String, HubError> {
+        let route = self
+            .routes
+            .lock()
+            .unwrap()
+            .remove(lease_id)
+            .ok_or_else(|| HubError::NotFound("lease not found".into()))?;
+        if route.deadline <= Instant::now() {
+            return Err(HubError::NotFound("lease expired".into()));
+        }
+        Ok(route.session_id)
+    }
+}
+
+struct HubConnection {
+    outgoing: mpsc::Sender<Value>,
+    subscriptions: Mutex<HashMap<String, Arc<HubUpstream>>>,
+}
+
+async fn handle_connection<R, W>(reader: R, mut writer: W, service: Arc<HubService>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<Value>(128);
+    let writer_task = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let Ok(mut text) = serde_json::to_string(&message) else {
+                continue;
+            };
+            text.push('\n');
+            if writer.write_all(text.as_bytes()).await.is_err() || writer.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let connection = Arc::new(HubConnection {
+        outgoing: tx.clone(),
+        subscriptions: Mutex::new(HashMap::new()),
+    });
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => {
+                warn!(target = "mcp::hub", error = %err, "connection read error");
+                break;
+            }
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<JsonRpcRequest>(trimmed) {
+            Ok(request) if request.jsonrpc != JSONRPC_VERSION => request
+                .id
+                .map(|id| invalid_params(Some(id), "jsonrpc version must be 2.0")),
+            Ok(request) => service.handle_request(&connection, request).await,
+            Err(err) => Some(invalid_params(None, format!("invalid request: {err}"))),
+        };
+        if let Some(response) = response {
+            if let Ok(value) = serde_json::to_value(response) {
+                if tx.send(value).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let subscriptions: Vec<_> = connection.subscriptions.lock().await.drain().collect();
+    for (subscription_id, upstream) in subscriptions {
+        upstream
+            .subscriptions
+            .lock()
+            .unwrap()
+            .remove(&subscription_id);
+        let _ = upstream
+            .call(
+                "resources/unsubscribe",
+                json!({"subscription_id": subscription_id}),
+            )
+            .await;
+    }
+    writer_task.abort();
+}
+
+impl HubService {
+    async fn handle_request(
+        &self,
+        connection: &Arc<HubConnection>,
+        request: JsonRpcRequest,
+    ) -> Option<JsonRpcResponse> {
+        let id = request.id.clone();
+        let params = request.params.unwrap_or_else(|| json!({}));
+        let result = match request.method.as_str() {
+            "initialize" => Ok(json!({
+                "protocolVersion": "2024-10-01",
+                "capabilities": {
+                    "resources": true,
+                    "tools": true,
+                    "notifications": ["resources/updated"]
+                },
+                "sessions": self.upstreams.keys().collect::<Vec<_>>(),
+            })),
+            "ping" => Ok(json!({"ok": true})),
+            "resources/list" => self.collect("resources/list", &params, "resources").await,
+            "resources/read" => self.route_by_uri("resources/read", params).await,
+            "resources/subscribe" => self.subscribe(connection, params).await,
+            "resources/unsubscribe" => self.unsubscribe(connection, params).await,
+            "tools/list" => self.tools_list().await,
+            "tools/call" => self.tools_call(params).await,
+            method => return Some(method_not_found(id, method)),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(value) => JsonRpcResponse::Result(JsonRpcResult::new(id, value)),
+            Err(err) => err.into_response(Some(id)),
+        })
+    }
+
+    fn upstream(&self, session_id: &str) -> Result<&Arc<HubUpstream>, HubError> {
+        self.upstreams
+            .get(session_id)
+            .ok_or_else(|| HubError::NotFound(format!("session {session_id} not joined by hub")))
+    }
+
+    /// Fans a request out to every upstream at once and concatenates `field`
+    /// arrays in session order, so one stalled session delays the reply by a
+    /// single call timeout at most.
+    async fn collect(&self, method: &str, params: &Value, field: &str) -> Result<Value, HubError> {
+        let calls = self
+            .upstreams
+            .values()
+            .map(|upstream| upstream.call(method, params.clone()));
+        let replies = join_all(calls).await;
+        let mut items = Vec::new();
+        for (upstream, reply) in self.upstreams.values().zip(replies) {
+            match reply {
+                Ok(value) => {
+                    if let Some(values) = value.get(field).and_then(Value::as_array) {
+                        items.extend(values.iter().cloned());
+                    }
+                }
+                Err(err) => warn!(
+                    target = "mcp::hub",
+                    session_id = %upstream.session_id,
+                    method,
+                    error = %err,
+                    "upstream request failed"
+                ),
+            }
+        }
+        let mut result = serde_json::Map::new();
+        result.insert(field.to_string(), Value::Array(items));
+        Ok(Value::Object(result))
+    }
+
+    async fn route_by_uri(&self, method: &str, params: Value) -> Result<Value, HubError> {
+        let session_id = params
+            .pointer("/resource/uri")
+            .and_then(Value::as_str)
+            .and_then(session_from_uri)
+            .ok_or_else(|| HubError::Invalid("resource.uri missing or invalid".into()))?;
+        self.upstream(session_id)?
+            .call(method, params.clone())
+            .await
+    }
+
+    async fn subscribe(
+        &self,
+        connection: &Arc<HubConnection>,
+        params: Value,
+    ) -> Result<Value, HubError> {
+        let session_id = params
+            .pointer("/resource/uri")
+            .and_then(Value::as_str)
+            .and_then(session_from_uri)
+            .ok_or_else(|| HubError::Invalid("resource.uri missing or invalid".into()))?;
+        let upstream = Arc::clone(self.upstream(session_id)?);
+        let value = upstream.call("resources/subscribe", params).await?;
+        if let Some(subscription_id) = value.get("subscription_id").and_then(Value::as_str) {
+            upstream
+                .subscriptions
+                .lock()
+                .unwrap()
+                .insert(subscription_id.to_string(), connection.outgoing.clone());
+            connection
+                .subscriptions
+                .lock()
+                .await
+                .insert(subscription_id.to_string(), upstream);
+        }
+        Ok(value)
+    }
+
+    async fn unsubscribe(
+        &self,
+        connection: &Arc<HubConnection>,
+        params: Value,
+    ) -> Result<Value, HubError> {
+        let subscription_id = params
+            .get("subscription_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| HubError::Invalid("subscription_id missing".into()))?;
+        let upstream = connection
+            .subscriptions
+            .lock()
+            .await
+            .remove(subscription_id)
+            .ok_or_else(|| HubError::NotFound("subscription not found".into()))?;
+        upstream
+            .subscriptions
+            .lock()
+            .unwrap()
+            .remove(subscription_id);
+        upstream.call("resources/unsubscribe", params).await
+    }
+
+    async fn tools_list(&self) -> Result<Value, HubError> {
+        let listed = self.collect("tools/list", &json!({}), "tools").await?;
+        let mut seen = HashSet::new();
+        let tools: Vec<Value> = listed["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|tool| {
+                let name = tool.get("name").and_then(Value::as_str).unwrap_or_default();
+                seen.insert(name.to_string())
+            })
+            .cloned()
+            .collect();
+        Ok(json!({"tools": tools}))
+    }
+
+    async fn tools_call(&self, params: Value) -> Result<Value, HubError> {
+        let name = params
+            .get("name")
+            .or_else(|| params.get("tool"))
+            .and_then(Value::as_str)
+            .ok_or_else(|| HubError::Invalid("tool name missing".into()))?
+            .to_string();
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        match name.as_str() {
+            LIST_SESSIONS => self.collect("tools/call", &params, "sessions").await,
+            RELEASE_LEASE => {
+                let lease_id = arguments
+                    .get("lease_id")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| HubError::Invalid("lease_id required".into()))?;
+                let session_id = self.leases.take(lease_id)?;
+                self.upstream(&session_id)?.call("tools/call", params).await
+            }
+            _ => {
+                let session_id = arguments
+                    .get("session_id")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| HubError::Invalid("session_id required".into()))?
+                    .to_string();
+                let value = self
+                    .upstream(&session_id)?
+                    .call("tools/call", params)
+                    .await?;
+                if name == ACQUIRE_LEASE {
+                    self.leases.insert(session_id, &value);
+                }
+                Ok(value)
+            }
+        }
+    }
+}
+
+fn session_from_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix("beach://session/")?
+        .split('/')
+        .next()
+        .filter(|session_id| !session_id.is_empty())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum HubError {
+    #[error("{0}")]
+    Invalid(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Upstream(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl HubError {
+    fn into_response(self, id: Option<Value>) -> JsonRpcResponse {
+        match self {
+            HubError::Invalid(message) => invalid_params(id, message),
+            HubError::NotFound(message) => not_found(id, message),
+            HubError::Upstream(message) | HubError::Internal(message) => {
+                internal_error(id, message)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::timeout]
+    fn session_from_uri_extracts_session_segment() {
+        assert_eq!(
+            session_from_uri("beach://session/abc-123/terminal/grid"),
+            Some("abc-123")
+        );
+        assert_eq!(session_from_uri("beach://session//terminal/grid"), None);
+        assert_eq!(session_from_uri("file:///tmp/grid"), None);
+    }
+
+    #[test_timeout::timeout]
+    fn lease_routes_lapse_with_the_lease() {
+        let routes = LeaseRoutes::default();
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        routes.insert(
+            "alpha".into(),
+            &json!({"lease_id": "live", "expires_at": now_ms + 30_000}),
+        );
+        routes.insert(
+            "beta".into(),
+            &json!({"lease_id": "stale", "expires_at": now_ms - 1}),
+        );
+        assert_eq!(routes.take("live").unwrap(), "alpha");
+        assert!(matches!(routes.take("live"), Err(HubError::NotFound(_))));
+        assert!(matches!(
+            routes.take("stale"),
+            Err(HubError::NotFound(message)) if message == "lease expired"
+        ));
+    }
+
+    #[test_timeout::timeout]
+    fn missing_targets_are_not_reported_as_unknown_methods() {
+        let JsonRpcResponse::Error(error) =
+            HubError::NotFound("session x not joined by hub".into()).into_response(Some(json!(1)))
+        else {
+            panic!("expected an error response");
+        };
+        assert_eq!(error.error.code, crate::mcp::protocol::ERROR_NOT_FOUND);
+    }
+}
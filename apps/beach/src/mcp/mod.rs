@@ -2,6 +2,7 @@ pub mod auth;
 pub mod bridge;
 pub mod client;
 pub mod client_proxy;
+pub mod hub;
 pub mod protocol;
 pub mod registry;
 pub mod server;
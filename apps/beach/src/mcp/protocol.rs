@@ -98,6 +98,7 @@ pub const ERROR_INTERNAL: i64 = -32603;
 
 pub const ERROR_UNAUTHORIZED: i64 = -32001;
 pub const ERROR_CONFLICT: i64 = -32002;
+pub const ERROR_NOT_FOUND: i64 = -32003;
 
 pub fn method_not_found(id: Option<Value>, method: &str) -> JsonRpcResponse {
     JsonRpcResponse::Error(JsonRpcErrorResponse::new(
@@ -133,3 +134,12 @@ pub fn unauthorized(id: Option<Value>, message: impl Into<String>) -> JsonRpcRes
 pub fn conflict(id: Option<Value>, message: impl Into<String>) -> JsonRpcResponse {
     JsonRpcResponse::Error(JsonRpcErrorResponse::new(id, ERROR_CONFLICT, message, None))
 }
+
+pub fn not_found(id: Option<Value>, message: impl Into<String>) -> JsonRpcResponse {
+    JsonRpcResponse::Error(JsonRpcErrorResponse::new(
+        id,
+        ERROR_NOT_FOUND,
+        message,
+        None,
+    ))
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+use crate::server::terminal::chat::ChatHub;
 use crate::server::terminal::{PtyProcess, PtyWriter};
 use crate::sync::terminal::TerminalSync;
 
@@ -10,6 +11,7 @@ pub struct TerminalSession {
     pub sync: Arc<TerminalSync>,
     pub writer: PtyWriter,
     pub process: Arc<PtyProcess>,
+    pub chat: Arc<ChatHub>,
 }
 
 impl TerminalSession {
@@ -18,12 +20,14 @@ impl TerminalSession {
         sync: Arc<TerminalSync>,
         writer: PtyWriter,
         process: Arc<PtyProcess>,
+        chat: Arc<ChatHub>,
     ) -> Self {
         Self {
             session_id: session_id.into(),
             sync,
             writer,
             process,
+            chat,
         }
     }
 }
@@ -569,6 +569,12 @@ fn parse_resource_uri(
         ["terminal", "grid"] => crate::mcp::terminal::TerminalResource::Grid,
         ["terminal", "history"] => crate::mcp::terminal::TerminalResource::History,
         ["terminal", "cursor"] => crate::mcp::terminal::TerminalResource::Cursor,
+        ["terminal", "export", format] => {
+            crate::cache::terminal::export::ExportFormat::parse(format)
+                .map(crate::mcp::terminal::TerminalResource::Export)
+                .ok_or_else(|| McpError::invalid("unknown export format"))?
+        }
+        ["chat"] => crate::mcp::terminal::TerminalResource::Chat,
         _ => return Err(McpError::invalid("unknown resource")),
     };
     Ok((session_id.to_string(), resource))
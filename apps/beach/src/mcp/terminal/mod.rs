@@ -3,8 +3,8 @@ mod tools;
 
 pub use resources::{ResourceDescriptor, TerminalResource};
 pub use tools::{
-    ACQUIRE_LEASE, LIST_SESSIONS, REQUEST_HISTORY, RESIZE, SEND_KEYS, SEND_TEXT, SET_VIEWPORT,
-    TerminalToolDescriptor, handle_list_sessions,
+    ACQUIRE_LEASE, CHAT_SEND, LIST_SESSIONS, RELEASE_LEASE, REQUEST_HISTORY, RESIZE, SEARCH,
+    SEND_KEYS, SEND_TEXT, SET_VIEWPORT, TerminalToolDescriptor, handle_list_sessions,
 };
 
 use std::sync::Arc;
@@ -16,7 +16,7 @@ use crate::mcp::auth::LeaseManager;
 use crate::mcp::registry::TerminalSession;
 
 use resources::{GridSnapshotRequest, HistoryReadRequest};
-use tools::{SendKeysRequest, SendTextRequest};
+use tools::{ChatSendRequest, SendKeysRequest, SendTextRequest};
 
 #[derive(Clone)]
 pub struct TerminalSurface {
@@ -51,6 +51,11 @@ impl TerminalSurface {
                 resources::read_history_segment(&self.session, &request)
             }
             TerminalResource::Cursor => resources::read_cursor_state(&self.session),
+            TerminalResource::Export(format) => {
+                let request = GridSnapshotRequest::from_params(params)?;
+                resources::read_grid_export(&self.session, *format, &request)
+            }
+            TerminalResource::Chat => resources::read_chat_log(&self.session),
         }
     }
 
@@ -94,6 +99,11 @@ impl TerminalSurface {
                 let response = tools::handle_request_history(&self.session, params, leases)?;
                 Ok(response)
             }
+            tools::SEARCH => tools::handle_search(&self.session, params, leases),
+            tools::CHAT_SEND => {
+                let request = ChatSendRequest::from_params(params)?;
+                tools::handle_chat_send(&self.session, request)
+            }
             _ => Err(anyhow::anyhow!("unknown tool: {name}")),
         }
     }
@@ -117,7 +127,10 @@ impl TerminalSurface {
                     cancel_rx,
                 ))
             }
-            TerminalResource::History | TerminalResource::Cursor => {
+            TerminalResource::History
+            | TerminalResource::Cursor
+            | TerminalResource::Export(_)
+            | TerminalResource::Chat => {
                 Err(anyhow::anyhow!("subscription not supported for resource"))
             }
         }
@@ -9,6 +9,7 @@ use tokio::sync::oneshot;
 use tokio::time::sleep;
 
 use crate::cache::GridCache;
+use crate::cache::terminal::export::{ExportFormat, StyledRow, export_rows};
 use crate::cache::terminal::{PackedCell, unpack_cell};
 use crate::mcp::registry::TerminalSession;
 use crate::model::terminal::diff::CacheUpdate;
@@ -28,6 +29,8 @@ pub enum TerminalResource {
     Grid,
     History,
     Cursor,
+    Export(ExportFormat),
+    Chat,
 }
 
 impl TerminalResource {
@@ -42,6 +45,10 @@ impl TerminalResource {
                 ["terminal", "grid"] => Some(TerminalResource::Grid),
                 ["terminal", "history"] => Some(TerminalResource::History),
                 ["terminal", "cursor"] => Some(TerminalResource::Cursor),
+                ["terminal", "export", format] => {
+                    ExportFormat::parse(format).map(TerminalResource::Export)
+                }
+                ["chat"] => Some(TerminalResource::Chat),
                 _ => None,
             }
         } else {
@@ -50,7 +57,7 @@ impl TerminalResource {
     }
 
     pub fn descriptors(session_id: &str) -> Vec<ResourceDescriptor> {
-        vec![
+        let mut descriptors = vec![
             ResourceDescriptor {
                 uri: format!("beach://session/{session_id}/terminal/grid"),
                 name: "Terminal Grid".to_string(),
@@ -72,7 +79,30 @@ impl TerminalResource {
                 resource_type: "terminal.cursor".to_string(),
                 read_only: true,
             },
-        ]
+        ];
+        descriptors.extend(ExportFormat::ALL.iter().map(|format| ResourceDescriptor {
+            uri: format!(
+                "beach://session/{session_id}/terminal/export/{}",
+                format.as_str()
+            ),
+            name: format!("Terminal Export ({})", format.as_str().to_uppercase()),
+            description: Some(format!(
+                "Styled viewport rendering as {}",
+                format.mime_type()
+            )),
+            resource_type: format!("terminal.export.{}", format.as_str()),
+            read_only: true,
+        }));
+        descriptors.push(ResourceDescriptor {
+            uri: format!("beach://session/{session_id}/chat"),
+            name: "Chat".to_string(),
+            description: Some(
+                "Session chat log, including notes pinned to history rows".to_string(),
+            ),
+            resource_type: "chat.log".to_string(),
+            read_only: true,
+        });
+        descriptors
     }
 }
 
@@ -99,6 +129,16 @@ impl GridSnapshotRequest {
             Ok(Self::default())
         }
     }
+
+    /// Returns the absolute top row and row count, defaulting to the tail of the grid.
+    fn resolve_viewport(&self, grid_rows: usize, first_row: u64, last_row: u64) -> (u64, usize) {
+        let desired_rows = self.rows.unwrap_or_else(|| grid_rows.min(80)).max(1);
+        let viewport_top = self
+            .top
+            .or_else(|| last_row.checked_sub(desired_rows as u64 - 1))
+            .unwrap_or(first_row);
+        (viewport_top, desired_rows)
+    }
 }
 
 #[derive(Debug)]
@@ -136,14 +176,7 @@ pub fn read_grid_snapshot(
     let (rows, cols) = grid.dims();
     let first_row = grid.first_row_id().unwrap_or(0);
     let last_row = grid.last_row_id().unwrap_or(first_row);
-
-    let desired_rows = request.rows.unwrap_or_else(|| rows.min(80)).max(1);
-
-    let viewport_top = request
-        .top
-        .or_else(|| last_row.checked_sub(desired_rows as u64 - 1))
-        .unwrap_or(first_row);
-
+    let (viewport_top, desired_rows) = request.resolve_viewport(rows, first_row, last_row);
     let viewport_bottom = viewport_top.saturating_add(desired_rows as u64);
 
     let mut lines = Vec::new();
@@ -204,6 +237,41 @@ pub fn read_history_segment(
     }))
 }
 
+pub fn read_grid_export(
+    session: &Arc<TerminalSession>,
+    format: ExportFormat,
+    request: &GridSnapshotRequest,
+) -> Result<Value> {
+    let grid = session.sync.grid().clone();
+    let (rows, cols) = grid.dims();
+    let first_row = grid.first_row_id().unwrap_or(0);
+    let last_row = grid.last_row_id().unwrap_or(first_row);
+    let (viewport_top, desired_rows) = request.resolve_viewport(rows, first_row, last_row);
+
+    let mut styled = Vec::with_capacity(desired_rows);
+    let mut buffer = vec![0u64; cols.max(1)];
+    for absolute in viewport_top..viewport_top.saturating_add(desired_rows as u64) {
+        if let Some(index) = grid.index_of_row(absolute) {
+            if grid.snapshot_row_into(index, &mut buffer).is_ok() {
+                styled.push(StyledRow::from_packed(absolute, &buffer, &grid.style_table));
+            }
+        }
+    }
+
+    Ok(json!({
+        "session_id": session.session_id,
+        "format": format.as_str(),
+        "mime_type": format.mime_type(),
+        "cols": cols,
+        "viewport": {"top": viewport_top, "rows": desired_rows},
+        "content": export_rows(format, &styled),
+    }))
+}
+
+pub fn read_chat_log(session: &Arc<TerminalSession>) -> Result<Value> {
+    Ok(json!({ "messages": session.chat.messages() }))
+}
+
 pub fn read_cursor_state(session: &Arc<TerminalSession>) -> Result<Value> {
     let sync = session.sync.clone();
     let config = sync.config().clone();
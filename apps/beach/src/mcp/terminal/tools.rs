@@ -7,8 +7,11 @@ use serde_json::{Value, json};
 use uuid::Uuid;
 
 use crate::cache::GridCache;
+use crate::cache::terminal::search::{SearchDirection, SearchQuery, search_grid};
+use crate::cache::terminal::{PackedCell, unpack_cell};
 use crate::mcp::auth::{LeaseInfo, LeaseManager, LeaseScope};
 use crate::mcp::registry::{TerminalSession, global_registry};
+use crate::protocol::chat::ChatPost;
 
 pub const ACQUIRE_LEASE: &str = "beach.terminal.acquireLease";
 pub const RELEASE_LEASE: &str = "beach.terminal.releaseLease";
@@ -17,7 +20,9 @@ pub const SEND_KEYS: &str = "beach.terminal.sendKeys";
 pub const RESIZE: &str = "beach.terminal.resize";
 pub const SET_VIEWPORT: &str = "beach.terminal.setViewport";
 pub const REQUEST_HISTORY: &str = "beach.terminal.requestHistory";
+pub const SEARCH: &str = "beach.terminal.search";
 pub const LIST_SESSIONS: &str = "beach.sessions.list";
+pub const CHAT_SEND: &str = "beach.chat.send";
 
 #[derive(Clone, Debug, serde::Serialize)]
 pub struct TerminalToolDescriptor {
@@ -27,11 +32,23 @@ pub struct TerminalToolDescriptor {
 }
 
 pub fn list_tools(read_only: bool) -> Vec<TerminalToolDescriptor> {
-    let mut tools = vec![TerminalToolDescriptor {
-        name: LIST_SESSIONS.to_string(),
-        description: "List active beach sessions".to_string(),
-        requires_lease: false,
-    }];
+    let mut tools = vec![
+        TerminalToolDescriptor {
+            name: LIST_SESSIONS.to_string(),
+            description: "List active beach sessions".to_string(),
+            requires_lease: false,
+        },
+        TerminalToolDescriptor {
+            name: SEARCH.to_string(),
+            description: "Search scrollback history for literal text".to_string(),
+            requires_lease: false,
+        },
+        TerminalToolDescriptor {
+            name: CHAT_SEND.to_string(),
+            description: "Post a chat message, or a note pinned to a history row".to_string(),
+            requires_lease: false,
+        },
+    ];
 
     if read_only {
         return tools;
@@ -105,6 +122,32 @@ impl SendTextRequest {
     }
 }
 
+pub struct ChatSendRequest {
+    pub session_id: String,
+    pub text: String,
+    pub author: Option<String>,
+    pub row: Option<u64>,
+}
+
+impl ChatSendRequest {
+    pub fn from_params(value: &Value) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Helper {
+            session_id: String,
+            text: String,
+            author: Option<String>,
+            row: Option<u64>,
+        }
+        let helper: Helper = serde_json::from_value(value.clone())?;
+        Ok(Self {
+            session_id: helper.session_id,
+            text: helper.text,
+            author: helper.author,
+            row: helper.row,
+        })
+    }
+}
+
 pub struct SendKeysRequest {
     pub session_id: String,
     pub keys: Vec<KeySpec>,
@@ -250,6 +293,85 @@ pub fn handle_request_history(
     }))
 }
 
+pub fn handle_chat_send(session: &Arc<TerminalSession>, request: ChatSendRequest) -> Result<Value> {
+    ensure_session_match(session, &request.session_id)?;
+    let message = session
+        .chat
+        .post(ChatPost {
+            author: request.author.unwrap_or_else(|| "mcp".to_string()),
+            text: request.text,
+            row: request.row,
+        })
+        .ok_or_else(|| anyhow!("text must not be empty"))?;
+    Ok(json!({ "message": message }))
+}
+
+pub fn handle_search(
+    session: &Arc<TerminalSession>,
+    params: &Value,
+    _leases: &LeaseManager,
+) -> Result<Value> {
+    #[derive(Deserialize)]
+    struct Helper {
+        session_id: String,
+        query: String,
+        #[serde(default)]
+        case_sensitive: bool,
+        #[serde(default)]
+        direction: Option<String>,
+        from_row: Option<u64>,
+        #[serde(default)]
+        from_col: usize,
+        #[serde(default = "default_search_limit")]
+        max_results: usize,
+    }
+    let helper: Helper = serde_json::from_value(params.clone())?;
+    ensure_session_match(session, &helper.session_id)?;
+    if helper.query.is_empty() {
+        return Err(anyhow!("query must not be empty"));
+    }
+    let direction = match helper.direction.as_deref() {
+        None | Some("forward") => SearchDirection::Forward,
+        Some("backward") => SearchDirection::Backward,
+        Some(other) => return Err(anyhow!("unsupported search direction: {other}")),
+    };
+    let grid = session.sync.grid().clone();
+    let outcome = search_grid(
+        &grid,
+        &SearchQuery {
+            pattern: helper.query,
+            case_sensitive: helper.case_sensitive,
+            direction,
+            origin: helper.from_row.map(|row| (row, helper.from_col)),
+            max_results: helper.max_results,
+        },
+    );
+    let mut buffer = vec![0u64; grid.cols().max(1)];
+    let matches = outcome
+        .matches
+        .iter()
+        .map(|hit| {
+            let text = match grid.index_of_row(hit.row) {
+                Some(index) if grid.snapshot_row_into(index, &mut buffer).is_ok() => {
+                    Some(row_text(&buffer))
+                }
+                _ => None,
+            };
+            json!({
+                "row": hit.row,
+                "col": hit.col,
+                "len": hit.len,
+                "text": text,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "session_id": session.session_id,
+        "matches": matches,
+        "truncated": outcome.truncated,
+    }))
+}
+
 pub fn handle_acquire_lease(
     leases: &LeaseManager,
     session_id: &str,
@@ -378,6 +500,18 @@ fn parse_uuid(value: &str) -> Result<Uuid> {
     Uuid::parse_str(value).map_err(|err| anyhow!("invalid uuid: {err}"))
 }
 
+fn row_text(buffer: &[u64]) -> String {
+    let text: String = buffer
+        .iter()
+        .map(|cell| unpack_cell(PackedCell::from(*cell)).0)
+        .collect();
+    text.trim_end_matches(' ').to_string()
+}
+
 fn default_ttl() -> u64 {
     30_000
 }
+
+fn default_search_limit() -> usize {
+    50
+}
@@ -0,0 +1,94 @@
+//! Chat side channel carried as `beach.chat` extension frames. Clients post
+//! messages to the host, which numbers, timestamps and signs them, keeps the
+//! latest ones in its log and fans every message out to all peers. A message
+//! with a `row` is a note pinned to that absolute history row.
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::transport::extensions::{ExtensionEvent, from_json_payload, json_payload};
+
+pub const CHAT_NAMESPACE: &str = "beach.chat";
+pub const CHAT_KIND_POST: &str = "post";
+pub const CHAT_KIND_MESSAGE: &str = "message";
+pub const CHAT_KIND_HISTORY: &str = "history";
+/// Longer messages are truncated by the host.
+pub const CHAT_MAX_TEXT_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub id: u64,
+    pub author: String,
+    pub text: String,
+    /// Unix time in milliseconds at which the host received the message.
+    pub sent_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatPost {
+    /// Ignored for posts from peers, which the host signs with their
+    /// transport instead.
+    pub author: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatEvent {
+    /// Client to host: a new message or note.
+    Post(ChatPost),
+    /// Host to client: one logged message.
+    Message(ChatMessage),
+    /// Host to client: the whole log, sent when a peer attaches.
+    History(Vec<ChatMessage>),
+}
+
+impl ExtensionEvent for ChatEvent {
+    const NAMESPACE: &'static str = CHAT_NAMESPACE;
+
+    fn encode(&self) -> (&'static str, Bytes) {
+        match self {
+            ChatEvent::Post(post) => (CHAT_KIND_POST, json_payload(post)),
+            ChatEvent::Message(message) => (CHAT_KIND_MESSAGE, json_payload(message)),
+            ChatEvent::History(messages) => (CHAT_KIND_HISTORY, json_payload(messages)),
+        }
+    }
+
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self> {
+        match kind {
+            CHAT_KIND_POST => from_json_payload(payload).map(ChatEvent::Post),
+            CHAT_KIND_MESSAGE => from_json_payload(payload).map(ChatEvent::Message),
+            CHAT_KIND_HISTORY => from_json_payload(payload).map(ChatEvent::History),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::assert_round_trips;
+
+    #[test_timeout::timeout]
+    fn chat_events_round_trip() {
+        let note = ChatMessage {
+            id: 3,
+            author: "ana".into(),
+            text: "this is where the build broke".into(),
+            sent_ms: 1_700_000_000_000,
+            row: Some(512),
+        };
+        assert_round_trips([
+            ChatEvent::Post(ChatPost {
+                author: "ana".into(),
+                text: "hi".into(),
+                row: None,
+            }),
+            ChatEvent::Message(note.clone()),
+            ChatEvent::History(vec![note]),
+        ]);
+    }
+}
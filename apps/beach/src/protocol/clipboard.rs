@@ -0,0 +1,156 @@
+//! Clipboard sync carried as `beach.clipboard` extension frames. A peer opts
+//! in with the directions it wants, the host answers with the directions it
+//! grants, and clipboard contents then travel as numbered chunks in either
+//! direction.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::transport::extensions::{ExtensionEvent, from_json_payload, json_payload};
+
+pub const CLIPBOARD_NAMESPACE: &str = "beach.clipboard";
+pub const CLIPBOARD_KIND_HELLO: &str = "hello";
+pub const CLIPBOARD_KIND_GRANTED: &str = "granted";
+pub const CLIPBOARD_KIND_CHUNK: &str = "chunk";
+/// Data bytes per chunk frame.
+pub const CLIPBOARD_CHUNK_BYTES: usize = 16 * 1024;
+
+const CHUNK_HEADER_LEN: usize = 9;
+
+/// Which way clipboard contents may flow, seen from the peer: `read-only`
+/// peers receive the host's clipboard, `write-only` peers set it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardMode {
+    #[default]
+    Off,
+    ReadOnly,
+    WriteOnly,
+    Both,
+}
+
+impl ClipboardMode {
+    pub fn reads(self) -> bool {
+        matches!(self, ClipboardMode::ReadOnly | ClipboardMode::Both)
+    }
+
+    pub fn writes(self) -> bool {
+        matches!(self, ClipboardMode::WriteOnly | ClipboardMode::Both)
+    }
+
+    /// The directions both modes allow.
+    pub fn intersect(self, other: ClipboardMode) -> ClipboardMode {
+        match (
+            self.reads() && other.reads(),
+            self.writes() && other.writes(),
+        ) {
+            (true, true) => ClipboardMode::Both,
+            (true, false) => ClipboardMode::ReadOnly,
+            (false, true) => ClipboardMode::WriteOnly,
+            (false, false) => ClipboardMode::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardEvent {
+    /// Client to host: opt in to the directions in `mode`.
+    Hello { mode: ClipboardMode },
+    /// Host to client: the directions the host allows this peer.
+    Granted { mode: ClipboardMode },
+    /// Part of clipboard write `id`; `last` marks its final part.
+    Chunk { id: u64, last: bool, data: Bytes },
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModePayload {
+    mode: ClipboardMode,
+}
+
+impl ExtensionEvent for ClipboardEvent {
+    const NAMESPACE: &'static str = CLIPBOARD_NAMESPACE;
+
+    fn encode(&self) -> (&'static str, Bytes) {
+        match self {
+            ClipboardEvent::Hello { mode } => (
+                CLIPBOARD_KIND_HELLO,
+                json_payload(&ModePayload { mode: *mode }),
+            ),
+            ClipboardEvent::Granted { mode } => (
+                CLIPBOARD_KIND_GRANTED,
+                json_payload(&ModePayload { mode: *mode }),
+            ),
+            // Chunks skip JSON: the big-endian id, a last-part flag, then the
+            // raw bytes.
+            ClipboardEvent::Chunk { id, last, data } => {
+                let mut buf = BytesMut::with_capacity(CHUNK_HEADER_LEN + data.len());
+                buf.put_u64(*id);
+                buf.put_u8(u8::from(*last));
+                buf.put_slice(data);
+                (CLIPBOARD_KIND_CHUNK, buf.freeze())
+            }
+        }
+    }
+
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self> {
+        match kind {
+            CLIPBOARD_KIND_HELLO => from_json_payload::<ModePayload>(payload)
+                .map(|hello| ClipboardEvent::Hello { mode: hello.mode }),
+            CLIPBOARD_KIND_GRANTED => from_json_payload::<ModePayload>(payload)
+                .map(|granted| ClipboardEvent::Granted { mode: granted.mode }),
+            CLIPBOARD_KIND_CHUNK => {
+                if payload.len() < CHUNK_HEADER_LEN {
+                    return None;
+                }
+                let id = u64::from_be_bytes(payload[..8].try_into().ok()?);
+                Some(ClipboardEvent::Chunk {
+                    id,
+                    last: payload[8] != 0,
+                    data: payload.slice(CHUNK_HEADER_LEN..),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::assert_round_trips;
+
+    #[test_timeout::timeout]
+    fn clipboard_events_round_trip() {
+        assert_round_trips([
+            ClipboardEvent::Hello {
+                mode: ClipboardMode::Both,
+            },
+            ClipboardEvent::Granted {
+                mode: ClipboardMode::ReadOnly,
+            },
+            ClipboardEvent::Chunk {
+                id: 4,
+                last: true,
+                data: Bytes::from_static("héllo".as_bytes()),
+            },
+        ]);
+        assert_eq!(
+            ClipboardEvent::Hello {
+                mode: ClipboardMode::WriteOnly
+            }
+            .to_frame()
+            .payload,
+            Bytes::from_static(br#"{"mode":"write-only"}"#)
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn modes_intersect_by_direction() {
+        use ClipboardMode::*;
+        assert_eq!(Both.intersect(ReadOnly), ReadOnly);
+        assert_eq!(WriteOnly.intersect(Both), WriteOnly);
+        assert_eq!(ReadOnly.intersect(WriteOnly), Off);
+        assert_eq!(Both.intersect(Off), Off);
+    }
+}
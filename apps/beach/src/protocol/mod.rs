@@ -1,10 +1,27 @@
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
-pub const PROTOCOL_VERSION: u8 = 2;
+/// Version 3 added the terminal modes frame, which hosts send during every
+/// handshake; clients skip frame kinds they do not recognise.
+pub const PROTOCOL_VERSION: u8 = 3;
 pub const FEATURE_CURSOR_SYNC: u32 = 1 << 0;
+pub const FEATURE_HISTORY_SEARCH: u32 = 1 << 1;
+pub const FEATURE_TERMINAL_MODES: u32 = 1 << 2;
 
+/// Kitty progressive keyboard enhancement flags (`CSI > flags u`).
+pub const KITTY_KEYBOARD_DISAMBIGUATE: u8 = 1 << 0;
+pub const KITTY_KEYBOARD_REPORT_EVENTS: u8 = 1 << 1;
+pub const KITTY_KEYBOARD_REPORT_ALTERNATES: u8 = 1 << 2;
+pub const KITTY_KEYBOARD_REPORT_ALL_KEYS: u8 = 1 << 3;
+pub const KITTY_KEYBOARD_REPORT_TEXT: u8 = 1 << 4;
+pub const KITTY_KEYBOARD_ALL: u8 = (1 << 5) - 1;
+
+pub mod chat;
+pub mod clipboard;
+pub mod portfwd;
+pub mod presence;
 pub mod terminal;
+pub mod transfer;
 pub mod wire;
 
 pub use wire::{
@@ -49,6 +66,114 @@ pub struct CursorFrame {
     pub blink: bool,
 }
 
+/// Which mouse events the host application asked to receive (DECSET 1000,
+/// 1002 and 1003).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum MouseTracking {
+    #[default]
+    Off = 0,
+    /// Button presses and releases.
+    Click = 1,
+    /// Presses, releases and motion while a button is held.
+    Drag = 2,
+    /// Every motion event.
+    Motion = 3,
+}
+
+impl MouseTracking {
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MouseTracking::Off),
+            1 => Some(MouseTracking::Click),
+            2 => Some(MouseTracking::Drag),
+            3 => Some(MouseTracking::Motion),
+            _ => None,
+        }
+    }
+}
+
+/// How mouse reports are encoded (legacy X10, DECSET 1005 or DECSET 1006).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum MouseEncoding {
+    #[default]
+    X10 = 0,
+    Utf8 = 1,
+    Sgr = 2,
+}
+
+impl MouseEncoding {
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MouseEncoding::X10),
+            1 => Some(MouseEncoding::Utf8),
+            2 => Some(MouseEncoding::Sgr),
+            _ => None,
+        }
+    }
+}
+
+/// xterm `modifyOtherKeys` level (`CSI > 4 ; level m`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum ModifyOtherKeys {
+    #[default]
+    Off = 0,
+    /// Report modified keys whose legacy encoding would be ambiguous.
+    Ambiguous = 1,
+    /// Report every modified key, including Ctrl+letter.
+    All = 2,
+}
+
+impl ModifyOtherKeys {
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(ModifyOtherKeys::Off),
+            1 => Some(ModifyOtherKeys::Ambiguous),
+            2 => Some(ModifyOtherKeys::All),
+            _ => None,
+        }
+    }
+}
+
+/// Input-affecting modes of the host's emulator. Clients encode keys, mouse,
+/// paste and focus events from these so remote applications see the same
+/// bytes a local terminal would send.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalModesFrame {
+    /// DECCKM: cursor keys send `ESC O` instead of `ESC [`.
+    pub app_cursor: bool,
+    /// DECKPAM: keypad keys send application sequences.
+    pub app_keypad: bool,
+    pub mouse_tracking: MouseTracking,
+    pub mouse_encoding: MouseEncoding,
+    pub bracketed_paste: bool,
+    pub focus_reporting: bool,
+    pub alt_screen: bool,
+    /// Active kitty keyboard flags (`KITTY_KEYBOARD_*`); zero means legacy
+    /// encoding.
+    #[serde(default)]
+    pub kitty_keyboard: u8,
+    #[serde(default)]
+    pub modify_other_keys: ModifyOtherKeys,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExtensionFrame {
     pub namespace: String,
@@ -95,6 +220,13 @@ pub enum Update {
     },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub row: u64,
+    pub col: u32,
+    pub len: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum HostFrame {
@@ -157,6 +289,14 @@ pub enum HostFrame {
         #[serde(flatten)]
         frame: ExtensionFrame,
     },
+    SearchResults {
+        request_id: u64,
+        hits: Vec<SearchHit>,
+        truncated: bool,
+    },
+    Modes {
+        modes: TerminalModesFrame,
+    },
     Shutdown,
 }
 
@@ -199,6 +339,17 @@ pub enum ClientFrame {
     ViewportCommand {
         command: ViewportCommand,
     },
+    Search {
+        request_id: u64,
+        pattern: String,
+        case_sensitive: bool,
+        backward: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from_row: Option<u64>,
+        #[serde(default)]
+        from_col: u32,
+        max_results: u32,
+    },
     Extension {
         #[serde(flatten)]
         frame: ExtensionFrame,
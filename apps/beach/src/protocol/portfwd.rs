@@ -0,0 +1,307 @@
+//! TCP port forwarding carried as `beach.portfwd` extension frames. Each TCP
+//! connection becomes a stream: the side holding the accepted socket asks the
+//! other side to dial, data then flows both ways in chunks, and every chunk is
+//! paid back with a credit once it reached the far socket, which keeps at most
+//! a window of unacknowledged bytes per direction in flight.
+//!
+//! Local forwards (`beach join --forward`) send `connect`; remote forwards
+//! (`--remote-forward`) send `listen`, after which the host announces each
+//! connection it accepts with `accepted`. Streams the client opens use odd
+//! ids and streams the host opens use even ids.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::transport::extensions::{ExtensionEvent, from_json_payload, json_payload};
+
+pub const PORTFWD_NAMESPACE: &str = "beach.portfwd";
+pub const PORTFWD_KIND_CONNECT: &str = "connect";
+pub const PORTFWD_KIND_LISTEN: &str = "listen";
+pub const PORTFWD_KIND_LISTENING: &str = "listening";
+pub const PORTFWD_KIND_UNLISTEN: &str = "unlisten";
+pub const PORTFWD_KIND_ACCEPTED: &str = "accepted";
+pub const PORTFWD_KIND_OPENED: &str = "opened";
+pub const PORTFWD_KIND_DATA: &str = "data";
+pub const PORTFWD_KIND_CREDIT: &str = "credit";
+pub const PORTFWD_KIND_CLOSE: &str = "close";
+pub const PORTFWD_KIND_RESET: &str = "reset";
+/// Largest data payload per frame.
+pub const PORTFWD_CHUNK_BYTES: usize = 16 * 1024;
+
+const DATA_HEADER_LEN: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortForwardEvent {
+    /// Client to host: dial `host:port` from the host for `stream`.
+    Connect {
+        stream: u64,
+        host: String,
+        port: u16,
+    },
+    /// Client to host: listen on the host's loopback `port`.
+    Listen {
+        port: u16,
+    },
+    /// Host to client: the listener on `port` is up.
+    Listening {
+        port: u16,
+    },
+    /// Host to client: the listener on `port` was refused or has stopped.
+    Unlisten {
+        port: u16,
+        reason: String,
+    },
+    /// Host to client: the listener on `port` accepted a connection.
+    Accepted {
+        stream: u64,
+        port: u16,
+    },
+    /// The dialing side connected; data may flow.
+    Opened {
+        stream: u64,
+    },
+    Data {
+        stream: u64,
+        data: Bytes,
+    },
+    /// The receiver wrote `bytes` more to its socket.
+    Credit {
+        stream: u64,
+        bytes: u64,
+    },
+    /// The sender's socket reached end of file; no more data follows.
+    Close {
+        stream: u64,
+    },
+    /// The stream was refused or failed; both sides drop it.
+    Reset {
+        stream: u64,
+        reason: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConnectPayload {
+    stream: u64,
+    host: String,
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PortPayload {
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnlistenPayload {
+    port: u16,
+    reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AcceptedPayload {
+    stream: u64,
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StreamPayload {
+    stream: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreditPayload {
+    stream: u64,
+    bytes: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResetPayload {
+    stream: u64,
+    reason: String,
+}
+
+impl ExtensionEvent for PortForwardEvent {
+    const NAMESPACE: &'static str = PORTFWD_NAMESPACE;
+
+    fn encode(&self) -> (&'static str, Bytes) {
+        match self {
+            PortForwardEvent::Connect { stream, host, port } => (
+                PORTFWD_KIND_CONNECT,
+                json_payload(&ConnectPayload {
+                    stream: *stream,
+                    host: host.clone(),
+                    port: *port,
+                }),
+            ),
+            PortForwardEvent::Listen { port } => (
+                PORTFWD_KIND_LISTEN,
+                json_payload(&PortPayload { port: *port }),
+            ),
+            PortForwardEvent::Listening { port } => (
+                PORTFWD_KIND_LISTENING,
+                json_payload(&PortPayload { port: *port }),
+            ),
+            PortForwardEvent::Unlisten { port, reason } => (
+                PORTFWD_KIND_UNLISTEN,
+                json_payload(&UnlistenPayload {
+                    port: *port,
+                    reason: reason.clone(),
+                }),
+            ),
+            PortForwardEvent::Accepted { stream, port } => (
+                PORTFWD_KIND_ACCEPTED,
+                json_payload(&AcceptedPayload {
+                    stream: *stream,
+                    port: *port,
+                }),
+            ),
+            PortForwardEvent::Opened { stream } => (
+                PORTFWD_KIND_OPENED,
+                json_payload(&StreamPayload { stream: *stream }),
+            ),
+            // Data skips JSON: the big-endian stream id, then the raw bytes.
+            PortForwardEvent::Data { stream, data } => {
+                let mut buf = BytesMut::with_capacity(DATA_HEADER_LEN + data.len());
+                buf.put_u64(*stream);
+                buf.put_slice(data);
+                (PORTFWD_KIND_DATA, buf.freeze())
+            }
+            PortForwardEvent::Credit { stream, bytes } => (
+                PORTFWD_KIND_CREDIT,
+                json_payload(&CreditPayload {
+                    stream: *stream,
+                    bytes: *bytes,
+                }),
+            ),
+            PortForwardEvent::Close { stream } => (
+                PORTFWD_KIND_CLOSE,
+                json_payload(&StreamPayload { stream: *stream }),
+            ),
+            PortForwardEvent::Reset { stream, reason } => (
+                PORTFWD_KIND_RESET,
+                json_payload(&ResetPayload {
+                    stream: *stream,
+                    reason: reason.clone(),
+                }),
+            ),
+        }
+    }
+
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self> {
+        match kind {
+            PORTFWD_KIND_CONNECT => from_json_payload::<ConnectPayload>(payload).map(|connect| {
+                PortForwardEvent::Connect {
+                    stream: connect.stream,
+                    host: connect.host,
+                    port: connect.port,
+                }
+            }),
+            PORTFWD_KIND_LISTEN => from_json_payload::<PortPayload>(payload)
+                .map(|listen| PortForwardEvent::Listen { port: listen.port }),
+            PORTFWD_KIND_LISTENING => from_json_payload::<PortPayload>(payload).map(|listening| {
+                PortForwardEvent::Listening {
+                    port: listening.port,
+                }
+            }),
+            PORTFWD_KIND_UNLISTEN => {
+                from_json_payload::<UnlistenPayload>(payload).map(|unlisten| {
+                    PortForwardEvent::Unlisten {
+                        port: unlisten.port,
+                        reason: unlisten.reason,
+                    }
+                })
+            }
+            PORTFWD_KIND_ACCEPTED => {
+                from_json_payload::<AcceptedPayload>(payload).map(|accepted| {
+                    PortForwardEvent::Accepted {
+                        stream: accepted.stream,
+                        port: accepted.port,
+                    }
+                })
+            }
+            PORTFWD_KIND_OPENED => {
+                from_json_payload::<StreamPayload>(payload).map(|opened| PortForwardEvent::Opened {
+                    stream: opened.stream,
+                })
+            }
+            PORTFWD_KIND_DATA => {
+                if payload.len() < DATA_HEADER_LEN {
+                    return None;
+                }
+                let stream = u64::from_be_bytes(payload[..DATA_HEADER_LEN].try_into().ok()?);
+                Some(PortForwardEvent::Data {
+                    stream,
+                    data: payload.slice(DATA_HEADER_LEN..),
+                })
+            }
+            PORTFWD_KIND_CREDIT => {
+                from_json_payload::<CreditPayload>(payload).map(|credit| PortForwardEvent::Credit {
+                    stream: credit.stream,
+                    bytes: credit.bytes,
+                })
+            }
+            PORTFWD_KIND_CLOSE => {
+                from_json_payload::<StreamPayload>(payload).map(|close| PortForwardEvent::Close {
+                    stream: close.stream,
+                })
+            }
+            PORTFWD_KIND_RESET => {
+                from_json_payload::<ResetPayload>(payload).map(|reset| PortForwardEvent::Reset {
+                    stream: reset.stream,
+                    reason: reset.reason,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ExtensionFrame;
+    use crate::transport::extensions::assert_round_trips;
+
+    #[test_timeout::timeout]
+    fn port_forward_events_round_trip() {
+        assert_round_trips([
+            PortForwardEvent::Connect {
+                stream: 1,
+                host: "localhost".into(),
+                port: 3000,
+            },
+            PortForwardEvent::Listen { port: 9000 },
+            PortForwardEvent::Listening { port: 9000 },
+            PortForwardEvent::Unlisten {
+                port: 9000,
+                reason: "not allowed".into(),
+            },
+            PortForwardEvent::Accepted {
+                stream: 2,
+                port: 9000,
+            },
+            PortForwardEvent::Opened { stream: 1 },
+            PortForwardEvent::Data {
+                stream: 1,
+                data: Bytes::from_static(b"GET / HTTP/1.1\r\n"),
+            },
+            PortForwardEvent::Credit {
+                stream: 1,
+                bytes: 16,
+            },
+            PortForwardEvent::Close { stream: 1 },
+            PortForwardEvent::Reset {
+                stream: 2,
+                reason: "connection refused".into(),
+            },
+        ]);
+
+        let truncated = ExtensionFrame {
+            namespace: PORTFWD_NAMESPACE.into(),
+            kind: PORTFWD_KIND_DATA.into(),
+            payload: Bytes::from_static(b"short"),
+        };
+        assert_eq!(PortForwardEvent::from_frame(&truncated), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Presence side channel. Clients publish where their attention is as
+//! `beach.presence` extension frames; the host stamps each update with the
+//! sender's peer id and relays it to everyone else.
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::transport::extensions::{ExtensionEvent, from_json_payload, json_payload};
+
+pub const PRESENCE_NAMESPACE: &str = "beach.presence";
+pub const PRESENCE_KIND_UPDATE: &str = "update";
+pub const PRESENCE_KIND_LEAVE: &str = "leave";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresencePoint {
+    pub row: u64,
+    pub col: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceSelectionMode {
+    #[default]
+    Character,
+    Line,
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceSelection {
+    pub anchor: PresencePoint,
+    pub head: PresencePoint,
+    #[serde(default)]
+    pub mode: PresenceSelectionMode,
+}
+
+/// What one peer is looking at. Rows are absolute history rows.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceState {
+    pub label: String,
+    /// Copy-mode cursor; `None` while the peer follows the live terminal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<PresencePoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<PresenceSelection>,
+    /// Unix time in milliseconds of the peer's last keystroke.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_input_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PresenceEvent {
+    /// A peer's state; `peer` is zero on frames a client sends to the host.
+    Update {
+        peer: u64,
+        state: PresenceState,
+    },
+    Leave {
+        peer: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct UpdatePayload {
+    #[serde(default)]
+    peer: u64,
+    #[serde(flatten)]
+    state: PresenceState,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeavePayload {
+    peer: u64,
+}
+
+impl ExtensionEvent for PresenceEvent {
+    const NAMESPACE: &'static str = PRESENCE_NAMESPACE;
+
+    fn encode(&self) -> (&'static str, Bytes) {
+        match self {
+            PresenceEvent::Update { peer, state } => (
+                PRESENCE_KIND_UPDATE,
+                json_payload(&UpdatePayload {
+                    peer: *peer,
+                    state: state.clone(),
+                }),
+            ),
+            PresenceEvent::Leave { peer } => (
+                PRESENCE_KIND_LEAVE,
+                json_payload(&LeavePayload { peer: *peer }),
+            ),
+        }
+    }
+
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self> {
+        match kind {
+            PRESENCE_KIND_UPDATE => {
+                let payload: UpdatePayload = from_json_payload(payload)?;
+                Some(PresenceEvent::Update {
+                    peer: payload.peer,
+                    state: payload.state,
+                })
+            }
+            PRESENCE_KIND_LEAVE => {
+                let payload: LeavePayload = from_json_payload(payload)?;
+                Some(PresenceEvent::Leave { peer: payload.peer })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::assert_round_trips;
+
+    #[test_timeout::timeout]
+    fn presence_events_round_trip() {
+        assert_round_trips([
+            PresenceEvent::Update {
+                peer: 7,
+                state: PresenceState {
+                    label: "alice".into(),
+                    cursor: Some(PresencePoint { row: 120, col: 4 }),
+                    selection: Some(PresenceSelection {
+                        anchor: PresencePoint { row: 118, col: 0 },
+                        head: PresencePoint { row: 120, col: 4 },
+                        mode: PresenceSelectionMode::Line,
+                    }),
+                    last_input_ms: Some(1_700_000_000_000),
+                },
+            },
+            PresenceEvent::Leave { peer: 7 },
+        ]);
+    }
+}
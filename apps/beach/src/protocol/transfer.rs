@@ -0,0 +1,214 @@
+//! File transfers carried as `beach.transfer` extension frames. The sender
+//! offers a file with its size and SHA-256, the receiver accepts from the
+//! offset it already holds (which is how interrupted transfers resume), and
+//! chunks follow in order with periodic acks pacing the sender. A client pulls
+//! a host file by sending a request; the host then offers it.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+
+use crate::transport::extensions::{ExtensionEvent, from_json_payload, json_payload};
+
+pub const TRANSFER_NAMESPACE: &str = "beach.transfer";
+pub const TRANSFER_KIND_REQUEST: &str = "request";
+pub const TRANSFER_KIND_OFFER: &str = "offer";
+pub const TRANSFER_KIND_ACCEPT: &str = "accept";
+pub const TRANSFER_KIND_CHUNK: &str = "chunk";
+pub const TRANSFER_KIND_ACK: &str = "ack";
+pub const TRANSFER_KIND_COMPLETE: &str = "complete";
+pub const TRANSFER_KIND_CANCEL: &str = "cancel";
+/// Data bytes per chunk frame.
+pub const TRANSFER_CHUNK_BYTES: usize = 32 * 1024;
+
+const CHUNK_HEADER_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferOffer {
+    /// Chosen by the client that started the transfer; unique per client.
+    pub id: u64,
+    /// Bare file name; receivers never honor directories in it.
+    pub name: String,
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the whole file.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferEvent {
+    /// Client to host: asks the host to offer the file at `path`.
+    Request { id: u64, path: String },
+    /// Sender to receiver: announces a file.
+    Offer(TransferOffer),
+    /// Receiver to sender: send everything from `offset` on.
+    Accept { id: u64, offset: u64 },
+    /// Sender to receiver: `data` belongs at `offset`.
+    Chunk { id: u64, offset: u64, data: Bytes },
+    /// Receiver to sender: everything below `offset` is on disk.
+    Ack { id: u64, offset: u64 },
+    /// Receiver to sender: the file arrived and its hash matched.
+    Complete { id: u64 },
+    /// Either side: the transfer stopped. Partial data is kept for a resume.
+    Cancel { id: u64, reason: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct RequestPayload {
+    id: u64,
+    path: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OffsetPayload {
+    id: u64,
+    offset: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdPayload {
+    id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CancelPayload {
+    id: u64,
+    reason: String,
+}
+
+impl ExtensionEvent for TransferEvent {
+    const NAMESPACE: &'static str = TRANSFER_NAMESPACE;
+
+    fn encode(&self) -> (&'static str, Bytes) {
+        match self {
+            TransferEvent::Request { id, path } => (
+                TRANSFER_KIND_REQUEST,
+                json_payload(&RequestPayload {
+                    id: *id,
+                    path: path.clone(),
+                }),
+            ),
+            TransferEvent::Offer(offer) => (TRANSFER_KIND_OFFER, json_payload(offer)),
+            TransferEvent::Accept { id, offset } => (
+                TRANSFER_KIND_ACCEPT,
+                json_payload(&OffsetPayload {
+                    id: *id,
+                    offset: *offset,
+                }),
+            ),
+            // Chunks skip JSON: a fixed big-endian header, then the raw bytes.
+            TransferEvent::Chunk { id, offset, data } => {
+                let mut buf = BytesMut::with_capacity(CHUNK_HEADER_LEN + data.len());
+                buf.put_u64(*id);
+                buf.put_u64(*offset);
+                buf.put_slice(data);
+                (TRANSFER_KIND_CHUNK, buf.freeze())
+            }
+            TransferEvent::Ack { id, offset } => (
+                TRANSFER_KIND_ACK,
+                json_payload(&OffsetPayload {
+                    id: *id,
+                    offset: *offset,
+                }),
+            ),
+            TransferEvent::Complete { id } => {
+                (TRANSFER_KIND_COMPLETE, json_payload(&IdPayload { id: *id }))
+            }
+            TransferEvent::Cancel { id, reason } => (
+                TRANSFER_KIND_CANCEL,
+                json_payload(&CancelPayload {
+                    id: *id,
+                    reason: reason.clone(),
+                }),
+            ),
+        }
+    }
+
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self> {
+        match kind {
+            TRANSFER_KIND_REQUEST => {
+                from_json_payload::<RequestPayload>(payload).map(|request| TransferEvent::Request {
+                    id: request.id,
+                    path: request.path,
+                })
+            }
+            TRANSFER_KIND_OFFER => from_json_payload(payload).map(TransferEvent::Offer),
+            TRANSFER_KIND_ACCEPT => {
+                from_json_payload::<OffsetPayload>(payload).map(|accept| TransferEvent::Accept {
+                    id: accept.id,
+                    offset: accept.offset,
+                })
+            }
+            TRANSFER_KIND_CHUNK => {
+                if payload.len() < CHUNK_HEADER_LEN {
+                    return None;
+                }
+                let id = u64::from_be_bytes(payload[..8].try_into().ok()?);
+                let offset = u64::from_be_bytes(payload[8..CHUNK_HEADER_LEN].try_into().ok()?);
+                Some(TransferEvent::Chunk {
+                    id,
+                    offset,
+                    data: payload.slice(CHUNK_HEADER_LEN..),
+                })
+            }
+            TRANSFER_KIND_ACK => {
+                from_json_payload::<OffsetPayload>(payload).map(|ack| TransferEvent::Ack {
+                    id: ack.id,
+                    offset: ack.offset,
+                })
+            }
+            TRANSFER_KIND_COMPLETE => from_json_payload::<IdPayload>(payload)
+                .map(|complete| TransferEvent::Complete { id: complete.id }),
+            TRANSFER_KIND_CANCEL => {
+                from_json_payload::<CancelPayload>(payload).map(|cancel| TransferEvent::Cancel {
+                    id: cancel.id,
+                    reason: cancel.reason,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ExtensionFrame;
+    use crate::transport::extensions::assert_round_trips;
+
+    #[test_timeout::timeout]
+    fn transfer_events_round_trip() {
+        assert_round_trips([
+            TransferEvent::Request {
+                id: 1,
+                path: "logs/build.log".into(),
+            },
+            TransferEvent::Offer(TransferOffer {
+                id: 1,
+                name: "build.log".into(),
+                size: 70_000,
+                sha256: "ab".repeat(32),
+            }),
+            TransferEvent::Accept { id: 1, offset: 0 },
+            TransferEvent::Chunk {
+                id: 1,
+                offset: 32_768,
+                data: Bytes::from_static(b"\x00\x01binary"),
+            },
+            TransferEvent::Ack {
+                id: 1,
+                offset: 65_536,
+            },
+            TransferEvent::Complete { id: 1 },
+            TransferEvent::Cancel {
+                id: 1,
+                reason: "denied".into(),
+            },
+        ]);
+
+        let truncated = ExtensionFrame {
+            namespace: TRANSFER_NAMESPACE.into(),
+            kind: TRANSFER_KIND_CHUNK.into(),
+            payload: Bytes::from_static(b"short"),
+        };
+        assert_eq!(TransferEvent::from_frame(&truncated), None);
+    }
+}
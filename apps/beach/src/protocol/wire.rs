@@ -1,6 +1,7 @@
 use super::{
-    ClientFrame, CursorFrame, ExtensionFrame, HostFrame, Lane, LaneBudgetFrame, PROTOCOL_VERSION,
-    SyncConfigFrame, Update, ViewportCommand,
+    ClientFrame, CursorFrame, ExtensionFrame, HostFrame, KITTY_KEYBOARD_ALL, Lane, LaneBudgetFrame,
+    ModifyOtherKeys, MouseEncoding, MouseTracking, PROTOCOL_VERSION, SearchHit, SyncConfigFrame,
+    TerminalModesFrame, Update, ViewportCommand,
 };
 use bytes::Bytes;
 use std::str;
@@ -20,6 +21,14 @@ const HOST_KIND_SHUTDOWN: u8 = 7;
 const HOST_KIND_HISTORY_BACKFILL: u8 = 8;
 const HOST_KIND_CURSOR: u8 = 9;
 const HOST_KIND_EXTENSION: u8 = 10;
+const HOST_KIND_SEARCH_RESULTS: u8 = 11;
+const HOST_KIND_MODES: u8 = 12;
+
+const MODE_APP_CURSOR: u32 = 1 << 0;
+const MODE_APP_KEYPAD: u32 = 1 << 1;
+const MODE_BRACKETED_PASTE: u32 = 1 << 2;
+const MODE_FOCUS_REPORTING: u32 = 1 << 3;
+const MODE_ALT_SCREEN: u32 = 1 << 4;
 
 const UPDATE_KIND_CELL: u8 = 0;
 const UPDATE_KIND_RECT: u8 = 1;
@@ -33,6 +42,7 @@ const CLIENT_KIND_RESIZE: u8 = 1;
 const CLIENT_KIND_REQUEST_BACKFILL: u8 = 2;
 const CLIENT_KIND_VIEWPORT_COMMAND: u8 = 3;
 const CLIENT_KIND_EXTENSION: u8 = 4;
+const CLIENT_KIND_SEARCH: u8 = 5;
 const CLIENT_KIND_UNKNOWN: u8 = TYPE_MASK;
 
 const ENV_BINARY_PROTOCOL: &str = "BEACH_PROTO_BINARY";
@@ -183,6 +193,25 @@ pub fn encode_host_frame_binary(frame: &HostFrame) -> Vec<u8> {
             write_string(&mut buf, &frame.kind);
             write_bytes(&mut buf, frame.payload.as_ref());
         }
+        HostFrame::SearchResults {
+            request_id,
+            hits,
+            truncated,
+        } => {
+            write_header(&mut buf, HOST_KIND_SEARCH_RESULTS);
+            write_var_u64(&mut buf, *request_id);
+            buf.push(*truncated as u8);
+            write_var_u32(&mut buf, hits.len() as u32);
+            for hit in hits {
+                write_var_u64(&mut buf, hit.row);
+                write_var_u32(&mut buf, hit.col);
+                write_var_u32(&mut buf, hit.len);
+            }
+        }
+        HostFrame::Modes { modes } => {
+            write_header(&mut buf, HOST_KIND_MODES);
+            encode_modes(&mut buf, modes);
+        }
         HostFrame::Shutdown => {
             write_header(&mut buf, HOST_KIND_SHUTDOWN);
         }
@@ -331,6 +360,26 @@ pub fn decode_host_frame_binary(bytes: &[u8]) -> Result<HostFrame, WireError> {
                 },
             })
         }
+        HOST_KIND_SEARCH_RESULTS => {
+            let request_id = cursor.read_var_u64()?;
+            let truncated = cursor.read_bool()?;
+            let count = cursor.read_var_u32()? as usize;
+            let mut hits = Vec::with_capacity(count.min(1024));
+            for _ in 0..count {
+                let row = cursor.read_var_u64()?;
+                let col = cursor.read_var_u32()?;
+                let len = cursor.read_var_u32()?;
+                hits.push(SearchHit { row, col, len });
+            }
+            Ok(HostFrame::SearchResults {
+                request_id,
+                hits,
+                truncated,
+            })
+        }
+        HOST_KIND_MODES => Ok(HostFrame::Modes {
+            modes: decode_modes(&mut cursor)?,
+        }),
         HOST_KIND_SHUTDOWN => Ok(HostFrame::Shutdown),
         other => Err(WireError::UnknownFrameType(other)),
     }
@@ -366,6 +415,27 @@ pub fn encode_client_frame_binary(frame: &ClientFrame) -> Vec<u8> {
             write_header(&mut buf, CLIENT_KIND_VIEWPORT_COMMAND);
             buf.push(command.as_u8());
         }
+        ClientFrame::Search {
+            request_id,
+            pattern,
+            case_sensitive,
+            backward,
+            from_row,
+            from_col,
+            max_results,
+        } => {
+            write_header(&mut buf, CLIENT_KIND_SEARCH);
+            write_var_u64(&mut buf, *request_id);
+            write_string(&mut buf, pattern);
+            buf.push(*case_sensitive as u8);
+            buf.push(*backward as u8);
+            buf.push(from_row.is_some() as u8);
+            if let Some(row) = from_row {
+                write_var_u64(&mut buf, *row);
+            }
+            write_var_u32(&mut buf, *from_col);
+            write_var_u32(&mut buf, *max_results);
+        }
         ClientFrame::Extension { frame } => {
             write_header(&mut buf, CLIENT_KIND_EXTENSION);
             write_string(&mut buf, &frame.namespace);
@@ -412,6 +482,28 @@ pub fn decode_client_frame_binary(bytes: &[u8]) -> Result<ClientFrame, WireError
                 .ok_or(WireError::InvalidData("unknown viewport command"))?;
             Ok(ClientFrame::ViewportCommand { command })
         }
+        CLIENT_KIND_SEARCH => {
+            let request_id = cursor.read_var_u64()?;
+            let pattern = read_string(&mut cursor)?;
+            let case_sensitive = cursor.read_bool()?;
+            let backward = cursor.read_bool()?;
+            let from_row = if cursor.read_bool()? {
+                Some(cursor.read_var_u64()?)
+            } else {
+                None
+            };
+            let from_col = cursor.read_var_u32()?;
+            let max_results = cursor.read_var_u32()?;
+            Ok(ClientFrame::Search {
+                request_id,
+                pattern,
+                case_sensitive,
+                backward,
+                from_row,
+                from_col,
+                max_results,
+            })
+        }
         CLIENT_KIND_EXTENSION => {
             let namespace = read_string(&mut cursor)?;
             let kind = read_string(&mut cursor)?;
@@ -615,6 +707,55 @@ fn decode_cursor(cursor: &mut Cursor<'_>) -> Result<CursorFrame, WireError> {
     })
 }
 
+fn encode_modes(buf: &mut Vec<u8>, modes: &TerminalModesFrame) {
+    let mut flags = 0u32;
+    for (set, bit) in [
+        (modes.app_cursor, MODE_APP_CURSOR),
+        (modes.app_keypad, MODE_APP_KEYPAD),
+        (modes.bracketed_paste, MODE_BRACKETED_PASTE),
+        (modes.focus_reporting, MODE_FOCUS_REPORTING),
+        (modes.alt_screen, MODE_ALT_SCREEN),
+    ] {
+        if set {
+            flags |= bit;
+        }
+    }
+    write_var_u32(buf, flags);
+    buf.push(modes.mouse_tracking.as_u8());
+    buf.push(modes.mouse_encoding.as_u8());
+    buf.push(modes.kitty_keyboard);
+    buf.push(modes.modify_other_keys.as_u8());
+}
+
+fn decode_modes(cursor: &mut Cursor<'_>) -> Result<TerminalModesFrame, WireError> {
+    // Unknown flag bits are ignored so hosts can add modes without breaking
+    // older clients.
+    let flags = cursor.read_var_u32()?;
+    let mouse_tracking = MouseTracking::from_u8(cursor.read_u8()?)
+        .ok_or(WireError::InvalidData("invalid mouse tracking"))?;
+    let mouse_encoding = MouseEncoding::from_u8(cursor.read_u8()?)
+        .ok_or(WireError::InvalidData("invalid mouse encoding"))?;
+    let (kitty_keyboard, modify_other_keys) = if cursor.remaining() > 0 {
+        let kitty = cursor.read_u8()? & KITTY_KEYBOARD_ALL;
+        let modify = ModifyOtherKeys::from_u8(cursor.read_u8()?)
+            .ok_or(WireError::InvalidData("invalid modify other keys"))?;
+        (kitty, modify)
+    } else {
+        (0, ModifyOtherKeys::Off)
+    };
+    Ok(TerminalModesFrame {
+        app_cursor: flags & MODE_APP_CURSOR != 0,
+        app_keypad: flags & MODE_APP_KEYPAD != 0,
+        mouse_tracking,
+        mouse_encoding,
+        bracketed_paste: flags & MODE_BRACKETED_PASTE != 0,
+        focus_reporting: flags & MODE_FOCUS_REPORTING != 0,
+        alt_screen: flags & MODE_ALT_SCREEN != 0,
+        kitty_keyboard,
+        modify_other_keys,
+    })
+}
+
 fn encode_sync_config(buf: &mut Vec<u8>, config: &SyncConfigFrame) {
     write_var_u32(buf, config.snapshot_budgets.len() as u32);
     for LaneBudgetFrame { lane, max_updates } in &config.snapshot_budgets {
@@ -771,6 +912,7 @@ fn decode_lane(cursor: &mut Cursor<'_>) -> Result<Lane, WireError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::{KITTY_KEYBOARD_DISAMBIGUATE, KITTY_KEYBOARD_REPORT_EVENTS};
     use bytes::Bytes;
 
     #[test_timeout::timeout]
@@ -980,4 +1122,100 @@ mod tests {
         let decoded_client = decode_client_frame_binary(&encoded_client).expect("client extension");
         assert_eq!(client_frame, decoded_client);
     }
+
+    #[test_timeout::timeout]
+    fn encode_decode_search_frames() {
+        for from_row in [None, Some(1_024)] {
+            let request = ClientFrame::Search {
+                request_id: 3,
+                pattern: "panicked at".to_string(),
+                case_sensitive: false,
+                backward: true,
+                from_row,
+                from_col: 12,
+                max_results: 1,
+            };
+            let encoded = encode_client_frame_binary(&request);
+            let decoded = decode_client_frame_binary(&encoded).expect("client search");
+            assert_eq!(request, decoded);
+        }
+
+        let results = HostFrame::SearchResults {
+            request_id: 3,
+            hits: vec![
+                SearchHit {
+                    row: 1_000,
+                    col: 4,
+                    len: 11,
+                },
+                SearchHit {
+                    row: 7,
+                    col: 0,
+                    len: 11,
+                },
+            ],
+            truncated: true,
+        };
+        let encoded = encode_host_frame_binary(&results);
+        let decoded = decode_host_frame_binary(&encoded).expect("search results");
+        assert_eq!(results, decoded);
+    }
+
+    #[test_timeout::timeout]
+    fn unknown_host_frame_kinds_are_distinguishable() {
+        // Clients skip frames newer hosts add rather than dropping the session.
+        let mut future = Vec::new();
+        write_header(&mut future, TYPE_MASK);
+        write_var_u64(&mut future, 7);
+        assert_eq!(
+            decode_host_frame_binary(&future),
+            Err(WireError::UnknownFrameType(TYPE_MASK))
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn encode_decode_modes_frame() {
+        for modes in [
+            TerminalModesFrame::default(),
+            TerminalModesFrame {
+                app_cursor: true,
+                app_keypad: true,
+                mouse_tracking: MouseTracking::Drag,
+                mouse_encoding: MouseEncoding::Sgr,
+                bracketed_paste: true,
+                focus_reporting: true,
+                alt_screen: true,
+                kitty_keyboard: KITTY_KEYBOARD_DISAMBIGUATE | KITTY_KEYBOARD_REPORT_EVENTS,
+                modify_other_keys: ModifyOtherKeys::All,
+            },
+        ] {
+            let frame = HostFrame::Modes { modes };
+            let encoded = encode_host_frame_binary(&frame);
+            let decoded = decode_host_frame_binary(&encoded).expect("modes frame");
+            assert_eq!(frame, decoded);
+        }
+
+        let mut future = Vec::new();
+        write_header(&mut future, HOST_KIND_MODES);
+        write_var_u32(&mut future, MODE_APP_CURSOR | (1 << 20));
+        future.extend_from_slice(&[MouseTracking::Motion.as_u8(), 9]);
+        assert_eq!(
+            decode_host_frame_binary(&future),
+            Err(WireError::InvalidData("invalid mouse encoding"))
+        );
+        let last = future.len() - 1;
+        future[last] = MouseEncoding::Utf8.as_u8();
+        let decoded = decode_host_frame_binary(&future).expect("unknown flag bits");
+        assert_eq!(
+            decoded,
+            HostFrame::Modes {
+                modes: TerminalModesFrame {
+                    app_cursor: true,
+                    mouse_tracking: MouseTracking::Motion,
+                    mouse_encoding: MouseEncoding::Utf8,
+                    ..TerminalModesFrame::default()
+                },
+            }
+        );
+    }
 }
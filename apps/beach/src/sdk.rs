@@ -0,0 +1,634 @@
+//! Programmatic access to shared sessions for tests and automation.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use beach_client_core::sdk::{Regex, Session};
+//!
+//! # async fn demo() -> Result<(), beach_client_core::sdk::SdkError> {
+//! let session = Session::join("https://beach.sh/sessions/<id>", "ABC123").await?;
+//! session.send_keys("echo hello\r")?;
+//! session
+//!     .wait_for(&Regex::new("^hello$").unwrap(), Duration::from_secs(5))
+//!     .await?;
+//! println!("{}", session.screen().text());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub use regex::Regex;
+use thiserror::Error;
+use tokio::sync::{oneshot, watch};
+use tracing::{debug, trace};
+
+use crate::cache::terminal::{PackedCell, Style, StyleId, TerminalGrid, unpack_cell};
+use crate::client::terminal::join::join_transport;
+use crate::protocol::{self, ClientFrame, CursorFrame, HostFrame, Update};
+use crate::session::SessionError;
+use crate::terminal::error::CliError;
+use crate::transport::{Payload, Transport, TransportError};
+
+const DEFAULT_SESSION_SERVER: &str = "https://api.beach.sh";
+const RECV_POLL: Duration = Duration::from_millis(250);
+const HISTORY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum SdkError {
+    #[error("invalid session target: {0}")]
+    InvalidTarget(String),
+    #[error("authentication failed: {0}")]
+    Auth(String),
+    #[error("failed to join session: {0}")]
+    Join(String),
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    #[error("session closed")]
+    Closed,
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+impl SdkError {
+    fn from_join(err: CliError) -> Self {
+        match err {
+            CliError::InvalidSessionTarget { .. }
+            | CliError::MissingPasscode
+            | CliError::InvalidArgument(_)
+            | CliError::Session(SessionError::InvalidJoinCode) => {
+                SdkError::InvalidTarget(err.to_string())
+            }
+            CliError::Auth(message)
+            | CliError::Session(SessionError::AuthenticationFailed(message)) => {
+                SdkError::Auth(message)
+            }
+            other => SdkError::Join(other.to_string()),
+        }
+    }
+}
+
+/// Options for [`Session::join_with`].
+#[derive(Clone, Debug)]
+pub struct JoinOptions {
+    session_server: String,
+    label: Option<String>,
+    profile: Option<String>,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self {
+            session_server: std::env::var("BEACH_SESSION_SERVER")
+                .unwrap_or_else(|_| DEFAULT_SESSION_SERVER.to_string()),
+            label: Some("beach-sdk".to_string()),
+            profile: None,
+        }
+    }
+}
+
+impl JoinOptions {
+    pub fn with_session_server(mut self, url: impl Into<String>) -> Self {
+        self.session_server = url.into();
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+}
+
+/// Text of the host's visible viewport at one point in time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Screen {
+    /// Absolute row id of `lines[0]`.
+    pub top_row: u64,
+    pub lines: Vec<String>,
+    /// Absolute `(row, col)` of the cursor when the host reports one.
+    pub cursor: Option<(u64, usize)>,
+}
+
+impl Screen {
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// A visible line matched by [`Session::wait_for`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScreenMatch {
+    pub row: u64,
+    pub line: String,
+}
+
+/// A joined session driven without a TUI. Dropping it stops the reader
+/// thread and releases the transport.
+pub struct Session {
+    session_id: String,
+    transport: Arc<dyn Transport>,
+    state: Arc<SessionState>,
+    reader: Option<JoinHandle<()>>,
+    input_seq: AtomicU64,
+    next_request_id: AtomicU64,
+}
+
+impl Session {
+    /// Joins by session id or share URL using [`JoinOptions::default`].
+    pub async fn join(target: &str, passcode: &str) -> Result<Self, SdkError> {
+        Self::join_with(target, passcode, JoinOptions::default()).await
+    }
+
+    pub async fn join_with(
+        target: &str,
+        passcode: &str,
+        options: JoinOptions,
+    ) -> Result<Self, SdkError> {
+        let (session_id, single) = join_transport(
+            &options.session_server,
+            target,
+            passcode,
+            options.label.as_deref(),
+            options.profile.as_deref(),
+            false,
+        )
+        .await
+        .map_err(SdkError::from_join)?;
+        let session = Self::attach(session_id, single.transport);
+        session.transport.send_text("__ready__")?;
+        Ok(session)
+    }
+
+    /// Wraps an already negotiated transport and starts applying host frames.
+    pub fn attach(session_id: impl Into<String>, transport: Arc<dyn Transport>) -> Self {
+        let state = Arc::new(SessionState::new());
+        let reader_state = Arc::clone(&state);
+        let reader_transport = Arc::clone(&transport);
+        let reader = std::thread::spawn(move || read_loop(reader_transport, reader_state));
+        Self {
+            session_id: session_id.into(),
+            transport,
+            state,
+            reader: Some(reader),
+            input_seq: AtomicU64::new(1),
+            next_request_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Sends raw input, e.g. `"ls\r"` or `"\x1b[A"` for the up arrow.
+    pub fn send_keys(&self, keys: impl AsRef<[u8]>) -> Result<(), SdkError> {
+        let frame = ClientFrame::Input {
+            seq: self.input_seq.fetch_add(1, Ordering::Relaxed),
+            data: keys.as_ref().to_vec(),
+        };
+        self.send_frame(&frame)
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), SdkError> {
+        self.send_frame(&ClientFrame::Resize { cols, rows })
+    }
+
+    pub fn screen(&self) -> Screen {
+        self.state.screen()
+    }
+
+    /// Waits until a visible line matches `pattern`.
+    pub async fn wait_for(
+        &self,
+        pattern: &Regex,
+        timeout: Duration,
+    ) -> Result<ScreenMatch, SdkError> {
+        let mut changes = self.state.changes.subscribe();
+        let wait = async {
+            loop {
+                let screen = self.state.screen();
+                let found = screen
+                    .lines
+                    .iter()
+                    .enumerate()
+                    .find(|(_, line)| pattern.is_match(line));
+                if let Some((offset, line)) = found {
+                    return Ok(ScreenMatch {
+                        row: screen.top_row + offset as u64,
+                        line: line.clone(),
+                    });
+                }
+                if self.state.closed.load(Ordering::Acquire) {
+                    return Err(SdkError::Closed);
+                }
+                if changes.changed().await.is_err() {
+                    return Err(SdkError::Closed);
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| SdkError::Timeout(timeout))?
+    }
+
+    /// Returns the text of absolute rows in `range`, fetched from the host's
+    /// scrollback (waiting for its hello first if needed). Rows the host has
+    /// already trimmed are omitted, so the result can be shorter than `range`.
+    pub async fn history(&self, range: Range<u64>) -> Result<Vec<(u64, String)>, SdkError> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let subscription = self.subscription().await?;
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.state.backfills.lock().unwrap().insert(request_id, tx);
+        self.send_frame(&ClientFrame::RequestBackfill {
+            subscription,
+            request_id,
+            start_row: range.start,
+            count: (range.end - range.start).min(u32::MAX as u64) as u32,
+        })?;
+        match tokio::time::timeout(HISTORY_TIMEOUT, rx).await {
+            Ok(Ok(())) => Ok(self.state.rows(range)),
+            Ok(Err(_)) => Err(SdkError::Closed),
+            Err(_) => {
+                self.state.backfills.lock().unwrap().remove(&request_id);
+                Err(SdkError::Timeout(HISTORY_TIMEOUT))
+            }
+        }
+    }
+
+    async fn subscription(&self) -> Result<u64, SdkError> {
+        let mut changes = self.state.changes.subscribe();
+        let wait = async {
+            loop {
+                if let Some(subscription) = self.state.subscription() {
+                    return Ok(subscription);
+                }
+                if self.state.closed.load(Ordering::Acquire) || changes.changed().await.is_err() {
+                    return Err(SdkError::Closed);
+                }
+            }
+        };
+        tokio::time::timeout(HISTORY_TIMEOUT, wait)
+            .await
+            .map_err(|_| SdkError::Timeout(HISTORY_TIMEOUT))?
+    }
+
+    fn send_frame(&self, frame: &ClientFrame) -> Result<(), SdkError> {
+        if self.state.closed.load(Ordering::Acquire) {
+            return Err(SdkError::Closed);
+        }
+        let bytes = protocol::encode_client_frame_binary(frame);
+        self.transport.send_bytes(&bytes)?;
+        Ok(())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.state.close();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+struct SessionState {
+    grid: Mutex<Arc<TerminalGrid>>,
+    cursor: Mutex<Option<CursorFrame>>,
+    subscription: Mutex<Option<u64>>,
+    backfills: Mutex<HashMap<u64, oneshot::Sender<()>>>,
+    changes: watch::Sender<u64>,
+    closed: AtomicBool,
+}
+
+impl SessionState {
+    fn new() -> Self {
+        let (changes, _) = watch::channel(0);
+        Self {
+            grid: Mutex::new(Arc::new(TerminalGrid::new(24, 80))),
+            cursor: Mutex::new(None),
+            subscription: Mutex::new(None),
+            backfills: Mutex::new(HashMap::new()),
+            changes,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    fn grid(&self) -> Arc<TerminalGrid> {
+        self.grid.lock().unwrap().clone()
+    }
+
+    fn subscription(&self) -> Option<u64> {
+        *self.subscription.lock().unwrap()
+    }
+
+    fn apply_frame(&self, frame: HostFrame) {
+        match frame {
+            HostFrame::Hello { subscription, .. } => {
+                *self.subscription.lock().unwrap() = Some(subscription);
+                *self.cursor.lock().unwrap() = None;
+            }
+            HostFrame::Grid {
+                cols,
+                history_rows,
+                base_row,
+                viewport_rows,
+            } => {
+                let grid = grid_for_frame(cols, history_rows, base_row, viewport_rows);
+                *self.grid.lock().unwrap() = Arc::new(grid);
+            }
+            HostFrame::Snapshot {
+                updates, cursor, ..
+            }
+            | HostFrame::Delta {
+                updates, cursor, ..
+            } => {
+                self.apply_updates(&updates, cursor);
+            }
+            HostFrame::HistoryBackfill {
+                request_id,
+                updates,
+                more,
+                cursor,
+                ..
+            } => {
+                self.apply_updates(&updates, cursor);
+                if !more {
+                    if let Some(tx) = self.backfills.lock().unwrap().remove(&request_id) {
+                        let _ = tx.send(());
+                    }
+                }
+            }
+            HostFrame::Cursor { cursor, .. } => {
+                *self.cursor.lock().unwrap() = Some(cursor);
+            }
+            HostFrame::Shutdown => {
+                self.closed.store(true, Ordering::Release);
+            }
+            HostFrame::Heartbeat { .. }
+            | HostFrame::SnapshotComplete { .. }
+            | HostFrame::InputAck { .. }
+            | HostFrame::Extension { .. }
+            | HostFrame::SearchResults { .. }
+            | HostFrame::Modes { .. } => return,
+        }
+        self.changes.send_modify(|generation| *generation += 1);
+    }
+
+    fn apply_updates(&self, updates: &[Update], cursor: Option<CursorFrame>) {
+        let grid = self.grid();
+        for update in updates {
+            apply_update(&grid, update);
+        }
+        if let Some(cursor) = cursor {
+            *self.cursor.lock().unwrap() = Some(cursor);
+        }
+    }
+
+    fn screen(&self) -> Screen {
+        let grid = self.grid();
+        let (viewport, _) = grid.viewport_size();
+        let Some(last_row) = grid.last_row_id() else {
+            return Screen::default();
+        };
+        let first_row = grid.first_row_id().unwrap_or(last_row);
+        let top_row = last_row
+            .saturating_add(1)
+            .saturating_sub(viewport as u64)
+            .max(first_row);
+        let lines = rows_text(&grid, top_row..last_row.saturating_add(1))
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect();
+        let cursor = self
+            .cursor
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cursor| cursor.visible)
+            .map(|cursor| (cursor.row as u64, cursor.col as usize));
+        Screen {
+            top_row,
+            lines,
+            cursor,
+        }
+    }
+
+    fn rows(&self, range: Range<u64>) -> Vec<(u64, String)> {
+        rows_text(&self.grid(), range)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.backfills.lock().unwrap().clear();
+        self.changes.send_modify(|generation| *generation += 1);
+    }
+}
+
+/// Builds an empty grid matching a `HostFrame::Grid` announcement.
+pub(crate) fn grid_for_frame(
+    cols: u32,
+    history_rows: u32,
+    base_row: u64,
+    viewport_rows: Option<u32>,
+) -> TerminalGrid {
+    let viewport = viewport_rows.unwrap_or(history_rows).max(1) as usize;
+    let cols = cols.max(1) as usize;
+    let history = (history_rows as usize).max(viewport);
+    let grid = TerminalGrid::with_history_limit(viewport, cols, history);
+    grid.set_viewport_size(viewport, cols);
+    grid.set_row_offset(base_row);
+    grid
+}
+
+pub(crate) fn apply_update(grid: &TerminalGrid, update: &Update) {
+    match update {
+        Update::Cell {
+            row,
+            col,
+            seq,
+            cell,
+        } => {
+            let _ = grid.write_packed_cell_if_newer(
+                *row as usize,
+                *col as usize,
+                *seq,
+                PackedCell::from(*cell),
+            );
+        }
+        Update::Row { row, seq, cells } => {
+            for (col, cell) in cells.iter().enumerate() {
+                let _ = grid.write_packed_cell_if_newer(
+                    *row as usize,
+                    col,
+                    *seq,
+                    PackedCell::from(*cell),
+                );
+            }
+        }
+        Update::RowSegment {
+            row,
+            start_col,
+            seq,
+            cells,
+        } => {
+            for (offset, cell) in cells.iter().enumerate() {
+                let _ = grid.write_packed_cell_if_newer(
+                    *row as usize,
+                    *start_col as usize + offset,
+                    *seq,
+                    PackedCell::from(*cell),
+                );
+            }
+        }
+        Update::Rect {
+            rows,
+            cols,
+            seq,
+            cell,
+        } => {
+            let _ = grid.fill_rect_with_cell_if_newer(
+                rows[0] as usize,
+                cols[0] as usize,
+                rows[1] as usize,
+                cols[1] as usize,
+                *seq,
+                PackedCell::from(*cell),
+            );
+        }
+        Update::Trim { count, .. } => {
+            if *count > 0 {
+                grid.set_row_offset(grid.row_offset().saturating_add(*count as u64));
+            }
+        }
+        Update::Style {
+            id, fg, bg, attrs, ..
+        } => {
+            let style = Style {
+                fg: *fg,
+                bg: *bg,
+                attrs: *attrs,
+            };
+            grid.style_table.insert_at(StyleId(*id), style);
+        }
+    }
+}
+
+pub(crate) fn rows_text(grid: &TerminalGrid, range: Range<u64>) -> Vec<(u64, String)> {
+    let mut buffer = vec![0u64; grid.cols().max(1)];
+    let mut rows = Vec::new();
+    for absolute in range {
+        let Some(index) = grid.index_of_row(absolute) else {
+            continue;
+        };
+        if grid.snapshot_row_into(index, &mut buffer).is_err() {
+            continue;
+        }
+        let text: String = buffer
+            .iter()
+            .map(|cell| unpack_cell(PackedCell::from(*cell)).0)
+            .collect();
+        rows.push((absolute, text.trim_end_matches(' ').to_string()));
+    }
+    rows
+}
+
+fn read_loop(transport: Arc<dyn Transport>, state: Arc<SessionState>) {
+    loop {
+        if state.closed.load(Ordering::Acquire) {
+            break;
+        }
+        match transport.recv(RECV_POLL) {
+            Ok(message) => match message.payload {
+                Payload::Binary(bytes) => match protocol::decode_host_frame_binary(&bytes) {
+                    Ok(frame) => state.apply_frame(frame),
+                    Err(err) => {
+                        debug!(target = "beach::sdk", error = %err, "failed to decode host frame");
+                    }
+                },
+                Payload::Text(text) => {
+                    trace!(target = "beach::sdk", payload = %text.trim(), "ignoring text payload");
+                }
+            },
+            Err(TransportError::Timeout) => continue,
+            Err(err) => {
+                debug!(target = "beach::sdk", error = %err, "session transport closed");
+                break;
+            }
+        }
+    }
+    state.close();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::terminal::pack_cell;
+
+    fn row_update(row: u32, text: &str) -> Update {
+        Update::Row {
+            row,
+            seq: 1,
+            cells: text
+                .chars()
+                .map(|ch| pack_cell(ch, StyleId::DEFAULT).into())
+                .collect(),
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn screen_tracks_viewport_and_history() {
+        let state = SessionState::new();
+        state.apply_frame(HostFrame::Grid {
+            cols: 8,
+            history_rows: 10,
+            base_row: 0,
+            viewport_rows: Some(2),
+        });
+        state.apply_frame(HostFrame::Delta {
+            subscription: 1,
+            watermark: 1,
+            has_more: false,
+            updates: vec![
+                row_update(0, "first"),
+                row_update(1, "second"),
+                row_update(2, "$ ls"),
+            ],
+            cursor: Some(CursorFrame {
+                row: 2,
+                col: 4,
+                seq: 1,
+                visible: true,
+                blink: false,
+            }),
+        });
+
+        let screen = state.screen();
+        assert_eq!(screen.top_row, 1);
+        assert_eq!(screen.lines, vec!["second", "$ ls"]);
+        assert_eq!(screen.cursor, Some((2, 4)));
+        assert_eq!(
+            state.rows(0..2),
+            vec![(0, "first".to_string()), (1, "second".to_string())]
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn shutdown_marks_session_closed() {
+        let state = SessionState::new();
+        let changes = state.changes.subscribe();
+        state.apply_frame(HostFrame::Shutdown);
+        assert!(state.closed.load(Ordering::Acquire));
+        assert!(changes.has_changed().unwrap());
+    }
+}
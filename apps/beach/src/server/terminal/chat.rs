@@ -0,0 +1,212 @@
+//! Host side of the `beach.chat` channel: the session's chat log and the
+//! peers it fans messages out to. MCP agents post through the same hub.
+//! Peers' messages are signed with their transport, not the author they
+//! claim, and only the latest [`CHAT_LOG_CAPACITY`] messages are kept.
+
+use crate::protocol::chat::{CHAT_MAX_TEXT_BYTES, ChatEvent, ChatMessage, ChatPost};
+use crate::transport::Transport;
+use crate::transport::extensions::{ExtensionHub, ExtensionPeers, send_event};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Messages the log holds; older ones drop out of it and out of the history
+/// late joiners receive.
+pub const CHAT_LOG_CAPACITY: usize = 256;
+
+#[derive(Default)]
+struct ChatLog {
+    messages: VecDeque<ChatMessage>,
+    next_id: u64,
+}
+
+#[derive(Default)]
+pub struct ChatHub {
+    log: Mutex<ChatLog>,
+    peers: ExtensionPeers<()>,
+}
+
+impl ChatHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs a message posted on the host, as MCP agents do, under the author
+    /// it names and delivers it to every peer. Returns `None` when the text is
+    /// blank.
+    pub fn post(&self, post: ChatPost) -> Option<ChatMessage> {
+        let author = match post.author.trim() {
+            "" => "anonymous",
+            author => author,
+        };
+        self.append(author.to_string(), post)
+    }
+
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.log.lock().unwrap().messages.iter().cloned().collect()
+    }
+
+    fn append(&self, author: String, post: ChatPost) -> Option<ChatMessage> {
+        let text = truncate(post.text.trim(), CHAT_MAX_TEXT_BYTES);
+        if text.is_empty() {
+            return None;
+        }
+        let message = {
+            let mut log = self.log.lock().unwrap();
+            log.next_id += 1;
+            let message = ChatMessage {
+                id: log.next_id,
+                author,
+                text: text.to_string(),
+                sent_ms: SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+                row: post.row,
+            };
+            if log.messages.len() == CHAT_LOG_CAPACITY {
+                log.messages.pop_front();
+            }
+            log.messages.push_back(message.clone());
+            message
+        };
+        let event = ChatEvent::Message(message.clone());
+        for transport in self.peers.select(|_, _| true) {
+            send_event(&transport, &event);
+        }
+        Some(message)
+    }
+}
+
+impl ExtensionHub for ChatHub {
+    type Event = ChatEvent;
+
+    /// Registers a peer's transport and sends it the log so far.
+    fn join(&self, transport: &Arc<dyn Transport>) {
+        self.peers.join(transport, ());
+        send_event(transport, &ChatEvent::History(self.messages()));
+    }
+
+    /// Logs a peer's post, delivering it back to the author too.
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: ChatEvent) {
+        if let ChatEvent::Post(post) = event {
+            self.append(peer_author(transport.id().0), post);
+        }
+    }
+
+    fn leave(&self, peer: u64) {
+        self.peers.leave(peer);
+    }
+}
+
+fn peer_author(peer: u64) -> String {
+    format!("peer {peer}")
+}
+
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::recv_event;
+    use crate::transport::{TransportKind, TransportPair};
+
+    #[test_timeout::timeout]
+    fn logs_messages_and_replays_them_to_late_joiners() {
+        let hub = ChatHub::new();
+        let first = TransportPair::new(TransportKind::Ipc);
+        let first_server: Arc<dyn Transport> = Arc::from(first.server);
+        hub.join(&first_server);
+        assert_eq!(
+            recv_event(first.client.as_ref()),
+            Some(ChatEvent::History(Vec::new()))
+        );
+
+        let posted = hub
+            .post(ChatPost {
+                author: " ".into(),
+                text: "  see row 40  ".into(),
+                row: Some(40),
+            })
+            .expect("message logged");
+        assert_eq!(posted.author, "anonymous");
+        assert_eq!(posted.text, "see row 40");
+        assert_eq!(
+            recv_event(first.client.as_ref()),
+            Some(ChatEvent::Message(posted.clone()))
+        );
+        assert!(
+            hub.post(ChatPost {
+                author: "ana".into(),
+                text: "   ".into(),
+                row: None,
+            })
+            .is_none()
+        );
+
+        let second = TransportPair::new(TransportKind::Ipc);
+        hub.join(&Arc::from(second.server));
+        assert_eq!(
+            recv_event(second.client.as_ref()),
+            Some(ChatEvent::History(vec![posted]))
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn signs_peer_posts_with_their_transport_and_caps_the_log() {
+        let hub = Arc::new(ChatHub::new());
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+        hub.join(&server);
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(ChatEvent::History(Vec::new()))
+        );
+
+        hub.handle(
+            &server,
+            ChatEvent::Post(ChatPost {
+                author: "host".into(),
+                text: "trust me".into(),
+                row: None,
+            }),
+        );
+        match recv_event(pair.client.as_ref()) {
+            Some(ChatEvent::Message(message)) => {
+                assert_eq!(message.author, peer_author(server.id().0));
+            }
+            other => panic!("expected message, got {other:?}"),
+        }
+
+        for n in 0..CHAT_LOG_CAPACITY {
+            hub.post(ChatPost {
+                author: "mcp".into(),
+                text: format!("message {n}"),
+                row: None,
+            });
+        }
+        let messages = hub.messages();
+        assert_eq!(messages.len(), CHAT_LOG_CAPACITY);
+        assert_eq!(messages[0].text, "message 0");
+        assert_eq!(
+            messages[CHAT_LOG_CAPACITY - 1].id,
+            CHAT_LOG_CAPACITY as u64 + 1
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn truncates_on_char_boundaries() {
+        assert_eq!(truncate("héllo", 2), "h");
+        assert_eq!(truncate("héllo", 3), "hé");
+        assert_eq!(truncate("hi", 10), "hi");
+    }
+}
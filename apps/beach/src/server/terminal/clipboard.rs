@@ -0,0 +1,266 @@
+//! Host side of `beach.clipboard`. Peers that opted in receive whatever the
+//! hosted application copies with OSC 52, and their copy-mode selections land
+//! on the host's clipboard, each within the directions `--clipboard-sync`
+//! allows.
+
+use crate::protocol::clipboard::{ClipboardEvent, ClipboardMode};
+use crate::session::terminal::clipboard::{ClipboardAssembler, clipboard_chunks};
+use crate::transport::Transport;
+use crate::transport::extensions::{ExtensionHub, ExtensionPeers, send_event};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, info, warn};
+
+/// Stores text on the host's clipboard.
+pub type HostClipboardWriter = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Puts `text` on the system clipboard when the host has a display, and
+/// otherwise asks the host's terminal to hold it with OSC 52.
+pub fn write_host_clipboard(text: &str) -> Result<(), String> {
+    if has_display() {
+        match ClipboardContext::new().and_then(|mut ctx| ctx.set_contents(text.to_string())) {
+            Ok(()) => return Ok(()),
+            Err(err) => debug!(
+                target = "host::clipboard",
+                error = %err,
+                "system clipboard unavailable; falling back to OSC 52"
+            ),
+        }
+    }
+    let stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Err("the host has no display or terminal to hold the clipboard".into());
+    }
+    let mut stdout = stdout.lock();
+    stdout
+        .write_all(osc52_sequence(text).as_bytes())
+        .and_then(|()| stdout.flush())
+        .map_err(|err| err.to_string())
+}
+
+fn has_display() -> bool {
+    cfg!(any(target_os = "macos", windows))
+        || ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .any(|var| env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+struct PeerClipboard {
+    mode: ClipboardMode,
+    incoming: ClipboardAssembler,
+}
+
+pub(crate) struct ClipboardHub {
+    /// The most any peer is granted.
+    policy: ClipboardMode,
+    writer: HostClipboardWriter,
+    peers: ExtensionPeers<PeerClipboard>,
+    next_id: AtomicU64,
+}
+
+impl ClipboardHub {
+    pub(crate) fn new(policy: ClipboardMode, writer: HostClipboardWriter) -> Self {
+        Self {
+            policy,
+            writer,
+            peers: ExtensionPeers::default(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Sends `text` to every peer allowed to read the host clipboard, except
+    /// the one it came from.
+    pub(crate) fn publish(&self, text: &str, origin: Option<u64>) {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let Some(chunks) = clipboard_chunks(id, text) else {
+            debug!(
+                target = "host::clipboard",
+                bytes = text.len(),
+                "clipboard write too large to share"
+            );
+            return;
+        };
+        let readers = self
+            .peers
+            .select(|peer, state| state.mode.reads() && Some(peer) != origin);
+        for transport in readers {
+            for chunk in &chunks {
+                send_event(&transport, chunk);
+            }
+        }
+    }
+}
+
+impl ExtensionHub for ClipboardHub {
+    type Event = ClipboardEvent;
+
+    /// Registers a peer; it stays out of sync until it says hello.
+    fn join(&self, transport: &Arc<dyn Transport>) {
+        self.peers.join(
+            transport,
+            PeerClipboard {
+                mode: ClipboardMode::Off,
+                incoming: ClipboardAssembler::default(),
+            },
+        );
+    }
+
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: ClipboardEvent) {
+        let peer = transport.id().0;
+        let mut peers = self.peers.lock();
+        let Some(state) = peers.get_mut(&peer).map(|entry| &mut entry.state) else {
+            return;
+        };
+        match event {
+            ClipboardEvent::Hello { mode } => {
+                state.mode = mode.intersect(self.policy);
+                info!(
+                    target = "host::clipboard",
+                    transport_id = peer,
+                    requested = ?mode,
+                    granted = ?state.mode,
+                    "peer opted in to clipboard sync"
+                );
+                send_event(transport, &ClipboardEvent::Granted { mode: state.mode });
+            }
+            ClipboardEvent::Chunk { id, last, data } => {
+                if !state.mode.writes() {
+                    return;
+                }
+                let Some(text) = state.incoming.push(id, last, &data) else {
+                    return;
+                };
+                drop(peers);
+                if let Err(err) = (self.writer)(&text) {
+                    warn!(
+                        target = "host::clipboard",
+                        transport_id = peer,
+                        error = %err,
+                        "failed to store peer clipboard on the host"
+                    );
+                }
+                self.publish(&text, Some(peer));
+            }
+            ClipboardEvent::Granted { .. } => {}
+        }
+    }
+
+    fn leave(&self, peer: u64) {
+        self.peers.leave(peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::{assert_no_event, recv_event};
+    use crate::transport::{TransportKind, TransportPair};
+    use bytes::Bytes;
+    use std::sync::Mutex;
+
+    #[test_timeout::timeout]
+    fn grants_directions_within_the_host_policy() {
+        let stored: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink = Arc::clone(&stored);
+        let writer: HostClipboardWriter = Arc::new(move |text: &str| {
+            sink.lock().unwrap().push(text.to_string());
+            Ok(())
+        });
+        let hub = Arc::new(ClipboardHub::new(ClipboardMode::ReadOnly, writer));
+        let reader = TransportPair::new(TransportKind::Ipc);
+        let writer_peer = TransportPair::new(TransportKind::Ipc);
+        let reader_server: Arc<dyn Transport> = Arc::from(reader.server);
+        let writer_server: Arc<dyn Transport> = Arc::from(writer_peer.server);
+        let writer_id = writer_server.id().0;
+        hub.join(&reader_server);
+        hub.join(&writer_server);
+
+        hub.handle(
+            &reader_server,
+            ClipboardEvent::Hello {
+                mode: ClipboardMode::Both,
+            },
+        );
+        assert_eq!(
+            recv_event(reader.client.as_ref()),
+            Some(ClipboardEvent::Granted {
+                mode: ClipboardMode::ReadOnly
+            })
+        );
+        // Not granted write access, so the host clipboard stays untouched.
+        hub.handle(
+            &reader_server,
+            ClipboardEvent::Chunk {
+                id: 1,
+                last: true,
+                data: Bytes::from_static(b"nope"),
+            },
+        );
+        assert!(stored.lock().unwrap().is_empty());
+
+        hub.publish("from the host", None);
+        assert_eq!(
+            recv_event(reader.client.as_ref()),
+            Some(ClipboardEvent::Chunk {
+                id: 1,
+                last: true,
+                data: Bytes::from_static(b"from the host"),
+            })
+        );
+        // A peer that never said hello receives nothing.
+        assert_no_event(writer_peer.client.as_ref());
+        hub.leave(writer_id);
+    }
+
+    #[test_timeout::timeout]
+    fn peer_writes_reach_the_host_and_other_readers() {
+        let stored: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink = Arc::clone(&stored);
+        let writer: HostClipboardWriter = Arc::new(move |text: &str| {
+            sink.lock().unwrap().push(text.to_string());
+            Ok(())
+        });
+        let hub = Arc::new(ClipboardHub::new(ClipboardMode::Both, writer));
+        let first = TransportPair::new(TransportKind::Ipc);
+        let second = TransportPair::new(TransportKind::Ipc);
+        let first_server: Arc<dyn Transport> = Arc::from(first.server);
+        let second_server: Arc<dyn Transport> = Arc::from(second.server);
+        hub.join(&first_server);
+        hub.join(&second_server);
+        for (server, client) in [
+            (&first_server, &first.client),
+            (&second_server, &second.client),
+        ] {
+            hub.handle(
+                server,
+                ClipboardEvent::Hello {
+                    mode: ClipboardMode::Both,
+                },
+            );
+            assert_eq!(
+                recv_event(client.as_ref()),
+                Some(ClipboardEvent::Granted {
+                    mode: ClipboardMode::Both
+                })
+            );
+        }
+
+        for chunk in clipboard_chunks(9, "selection").unwrap() {
+            hub.handle(&first_server, chunk);
+        }
+        assert_eq!(*stored.lock().unwrap(), vec!["selection".to_string()]);
+        assert!(matches!(
+            recv_event(second.client.as_ref()),
+            Some(ClipboardEvent::Chunk { data, last: true, .. }) if data == "selection"
+        ));
+        assert_no_event(first.client.as_ref());
+    }
+}
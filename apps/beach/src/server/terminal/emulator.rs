@@ -8,17 +8,26 @@ use crate::model::terminal::cell::{Cell as HeavyCell, CellAttributes, Color as H
 use crate::model::terminal::diff::{
     CacheUpdate, CellWrite, HistoryTrim, RowSnapshot, StyleDefinition,
 };
+use crate::protocol::{
+    KITTY_KEYBOARD_DISAMBIGUATE, KITTY_KEYBOARD_REPORT_ALL_KEYS, KITTY_KEYBOARD_REPORT_ALTERNATES,
+    KITTY_KEYBOARD_REPORT_EVENTS, KITTY_KEYBOARD_REPORT_TEXT, ModifyOtherKeys, MouseEncoding,
+    MouseTracking, TerminalModesFrame,
+};
 use alacritty_terminal::{
     Term,
     event::{Event, EventListener},
     grid::Dimensions,
     index::{Column, Line, Point},
-    term::{Config, cell::Cell as AlacrittyCell, cell::Flags as CellFlags},
-    vte::ansi::{Color as AnsiColor, CursorShape, NamedColor, Processor},
+    term::{Config, TermMode, cell::Cell as AlacrittyCell, cell::Flags as CellFlags},
+    vte::ansi::{
+        Color as AnsiColor, CursorShape, Handler, ModifyOtherKeys as AnsiModifyOtherKeys,
+        NamedColor, Processor,
+    },
 };
 use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
 use tracing::{Level, debug, trace};
 
 pub type EmulatorResult = Vec<CacheUpdate>;
@@ -30,6 +39,19 @@ pub trait TerminalEmulator: Send {
         Vec::new()
     }
     fn resize(&mut self, rows: usize, cols: usize);
+    /// Input modes the running application has enabled.
+    fn modes(&self) -> TerminalModesFrame {
+        TerminalModesFrame::default()
+    }
+    /// Answers to queries the application wrote (device attributes, cursor
+    /// position, keyboard mode), to be written back to the PTY.
+    fn take_replies(&mut self) -> Vec<u8> {
+        Vec::new()
+    }
+    /// Text the application copied with OSC 52 since the last call.
+    fn take_clipboard_writes(&mut self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 #[derive(Default)]
@@ -245,11 +267,48 @@ impl Dimensions for TermDimensions {
     }
 }
 
-#[derive(Clone, Copy, Default)]
-struct EventProxy;
+/// Collects the replies and OSC 52 clipboard writes alacritty produces while
+/// parsing; other events only matter to a windowed terminal.
+#[derive(Clone, Default)]
+struct EventProxy {
+    replies: Arc<Mutex<Vec<u8>>>,
+    clipboard: Arc<Mutex<Vec<String>>>,
+}
 
 impl EventListener for EventProxy {
-    fn send_event(&self, _event: Event) {}
+    fn send_event(&self, event: Event) {
+        match event {
+            Event::PtyWrite(text) => self
+                .replies
+                .lock()
+                .unwrap()
+                .extend_from_slice(text.as_bytes()),
+            // An empty payload clears the clipboard; there is nothing to share.
+            Event::ClipboardStore(_, text) if !text.is_empty() => {
+                self.clipboard.lock().unwrap().push(text)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// alacritty parses `CSI > 4 ; n m` but does not keep the level, so a second
+/// parser that ignores everything else records it.
+#[derive(Default)]
+struct ModifyOtherKeysState(ModifyOtherKeys);
+
+impl Handler for ModifyOtherKeysState {
+    fn set_modify_other_keys(&mut self, mode: AnsiModifyOtherKeys) {
+        self.0 = match mode {
+            AnsiModifyOtherKeys::Reset => ModifyOtherKeys::Off,
+            AnsiModifyOtherKeys::EnableExceptWellDefined => ModifyOtherKeys::Ambiguous,
+            AnsiModifyOtherKeys::EnableAll => ModifyOtherKeys::All,
+        };
+    }
+
+    fn reset_state(&mut self) {
+        self.0 = ModifyOtherKeys::Off;
+    }
 }
 
 pub struct AlacrittyEmulator {
@@ -260,6 +319,9 @@ pub struct AlacrittyEmulator {
     snapshot: GridSnapshot,
     cursor_frames_enabled: bool,
     last_cursor: Option<CursorState>,
+    events: EventProxy,
+    modify_other_keys: ModifyOtherKeysState,
+    modify_other_keys_parser: Processor,
 }
 
 unsafe impl Send for AlacrittyEmulator {}
@@ -270,15 +332,19 @@ impl AlacrittyEmulator {
         let dimensions = TermDimensions::new(viewport_cols.max(1), viewport_rows.max(1));
         let config = Config {
             scrolling_history: grid.history_limit(),
+            kitty_keyboard: true,
             ..Config::default()
         };
-        let mut term = Term::new(config, &dimensions, EventProxy);
+        let events = EventProxy::default();
+        let mut term = Term::new(config, &dimensions, events.clone());
         let mut parser = Processor::new();
         // Enable standard LF behavior so shells that rely on ESC[20h behave normally.
         for byte in b"\x1b[20h" {
             parser.advance(&mut term, *byte);
         }
         term.reset_damage();
+        // Nothing has asked anything yet; drop what the setup above produced.
+        events.replies.lock().unwrap().clear();
         Self {
             term,
             parser,
@@ -287,6 +353,9 @@ impl AlacrittyEmulator {
             snapshot: GridSnapshot::default(),
             cursor_frames_enabled,
             last_cursor: None,
+            events,
+            modify_other_keys: ModifyOtherKeysState::default(),
+            modify_other_keys_parser: Processor::new(),
         }
     }
 
@@ -683,6 +752,8 @@ impl TerminalEmulator for AlacrittyEmulator {
         if !chunk.is_empty() {
             for byte in chunk {
                 self.parser.advance(&mut self.term, *byte);
+                self.modify_other_keys_parser
+                    .advance(&mut self.modify_other_keys, *byte);
             }
         }
         self.collect_full_diff(grid)
@@ -696,6 +767,71 @@ impl TerminalEmulator for AlacrittyEmulator {
         let dims = TermDimensions::new(cols.max(1), rows.max(1));
         self.term.resize(dims);
     }
+
+    fn modes(&self) -> TerminalModesFrame {
+        let mut modes = modes_from_term(*self.term.mode());
+        modes.modify_other_keys = self.modify_other_keys.0;
+        modes
+    }
+
+    fn take_replies(&mut self) -> Vec<u8> {
+        std::mem::take(&mut *self.events.replies.lock().unwrap())
+    }
+
+    fn take_clipboard_writes(&mut self) -> Vec<String> {
+        std::mem::take(&mut *self.events.clipboard.lock().unwrap())
+    }
+}
+
+fn modes_from_term(mode: TermMode) -> TerminalModesFrame {
+    let mouse_tracking = if mode.contains(TermMode::MOUSE_MOTION) {
+        MouseTracking::Motion
+    } else if mode.contains(TermMode::MOUSE_DRAG) {
+        MouseTracking::Drag
+    } else if mode.contains(TermMode::MOUSE_REPORT_CLICK) {
+        MouseTracking::Click
+    } else {
+        MouseTracking::Off
+    };
+    let mouse_encoding = if mode.contains(TermMode::SGR_MOUSE) {
+        MouseEncoding::Sgr
+    } else if mode.contains(TermMode::UTF8_MOUSE) {
+        MouseEncoding::Utf8
+    } else {
+        MouseEncoding::X10
+    };
+    // alacritty keeps a separate kitty stack per screen and swaps it in with
+    // the alternate screen, so the active flags are always in `mode`.
+    let kitty_keyboard = [
+        (
+            TermMode::DISAMBIGUATE_ESC_CODES,
+            KITTY_KEYBOARD_DISAMBIGUATE,
+        ),
+        (TermMode::REPORT_EVENT_TYPES, KITTY_KEYBOARD_REPORT_EVENTS),
+        (
+            TermMode::REPORT_ALTERNATE_KEYS,
+            KITTY_KEYBOARD_REPORT_ALTERNATES,
+        ),
+        (
+            TermMode::REPORT_ALL_KEYS_AS_ESC,
+            KITTY_KEYBOARD_REPORT_ALL_KEYS,
+        ),
+        (TermMode::REPORT_ASSOCIATED_TEXT, KITTY_KEYBOARD_REPORT_TEXT),
+    ]
+    .into_iter()
+    .filter(|(term_mode, _)| mode.contains(*term_mode))
+    .fold(0, |flags, (_, flag)| flags | flag);
+    TerminalModesFrame {
+        app_cursor: mode.contains(TermMode::APP_CURSOR),
+        app_keypad: mode.contains(TermMode::APP_KEYPAD),
+        mouse_tracking,
+        mouse_encoding,
+        bracketed_paste: mode.contains(TermMode::BRACKETED_PASTE),
+        focus_reporting: mode.contains(TermMode::FOCUS_IN_OUT),
+        alt_screen: mode.contains(TermMode::ALT_SCREEN),
+        kitty_keyboard,
+        ..TerminalModesFrame::default()
+    }
 }
 
 fn convert_cell(cell: &AlacrittyCell) -> HeavyCell {
@@ -774,6 +910,75 @@ mod tests {
         }
     }
 
+    #[test_timeout::timeout]
+    fn alacritty_tracks_input_modes() {
+        let grid = TerminalGrid::new(24, 80);
+        let mut emulator = AlacrittyEmulator::new(&grid, false);
+        assert_eq!(emulator.modes(), TerminalModesFrame::default());
+
+        // What vim and htop send on startup.
+        emulator.handle_output(
+            b"\x1b[?1049h\x1b[?1h\x1b=\x1b[?2004h\x1b[?1002h\x1b[?1006h\x1b[?1004h",
+            &grid,
+        );
+        assert_eq!(
+            emulator.modes(),
+            TerminalModesFrame {
+                app_cursor: true,
+                app_keypad: true,
+                mouse_tracking: MouseTracking::Drag,
+                mouse_encoding: MouseEncoding::Sgr,
+                bracketed_paste: true,
+                focus_reporting: true,
+                alt_screen: true,
+                ..TerminalModesFrame::default()
+            }
+        );
+
+        // Kitty flags follow the screen they were pushed on.
+        emulator.handle_output(b"\x1b[>1u\x1b[>4;2m", &grid);
+        let modes = emulator.modes();
+        assert_eq!(modes.kitty_keyboard, KITTY_KEYBOARD_DISAMBIGUATE);
+        assert_eq!(modes.modify_other_keys, ModifyOtherKeys::All);
+
+        // Applications probe for support before relying on it.
+        assert!(emulator.take_replies().is_empty());
+        emulator.handle_output(b"\x1b[?u\x1b[5n", &grid);
+        assert_eq!(emulator.take_replies(), b"\x1b[?1u\x1b[0n");
+        assert!(emulator.take_replies().is_empty());
+
+        emulator.handle_output(b"\x1b[?1002l\x1b[?1006l\x1b[?1l\x1b>\x1b[?1049l", &grid);
+        let modes = emulator.modes();
+        assert!(!modes.app_cursor && !modes.app_keypad && !modes.alt_screen);
+        assert_eq!(modes.mouse_tracking, MouseTracking::Off);
+        assert_eq!(modes.mouse_encoding, MouseEncoding::X10);
+        assert_eq!(modes.kitty_keyboard, 0);
+        assert_eq!(modes.modify_other_keys, ModifyOtherKeys::All);
+    }
+
+    #[test_timeout::timeout]
+    fn alacritty_captures_osc52_clipboard_writes() {
+        use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+        let grid = TerminalGrid::new(24, 80);
+        let mut emulator = AlacrittyEmulator::new(&grid, false);
+        let write = format!("\x1b]52;c;{}\x07", STANDARD.encode("copied text"));
+        let (head, tail) = write.as_bytes().split_at(9);
+        emulator.handle_output(head, &grid);
+        assert!(emulator.take_clipboard_writes().is_empty());
+        emulator.handle_output(tail, &grid);
+        assert_eq!(emulator.take_clipboard_writes(), vec!["copied text"]);
+
+        let st_terminated = format!("ls\x1b]52;;{}\x1b\\done", STANDARD.encode("ünï"));
+        emulator.handle_output(st_terminated.as_bytes(), &grid);
+        assert_eq!(emulator.take_clipboard_writes(), vec!["ünï"]);
+
+        // Queries, clears and other OSCs carry no text to share.
+        emulator.handle_output(b"\x1b]52;c;?\x07\x1b]52;c;\x07", &grid);
+        emulator.handle_output(b"\x1b]0;title\x07\x1b]8;;http://x\x1b\\", &grid);
+        assert!(emulator.take_clipboard_writes().is_empty());
+    }
+
     #[test_timeout::timeout]
     fn session_origin_updates_when_viewport_shifts() {
         let grid = TerminalGrid::new(24, 80);
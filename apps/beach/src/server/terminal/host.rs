@@ -1,5 +1,7 @@
 use crate::auth;
 use crate::cache::terminal::TerminalGrid;
+use crate::cache::terminal::search::{SearchDirection, SearchQuery};
+use crate::client::color::ColorMapper;
 use crate::client::terminal::join::{kind_label, summarize_offers};
 use crate::client::terminal::{ClientError, TerminalClient};
 use crate::mcp::{
@@ -15,14 +17,20 @@ use crate::model::terminal::CursorState;
 use crate::model::terminal::diff::CacheUpdate;
 use crate::protocol::terminal::bootstrap;
 use crate::protocol::{self, HostFrame};
+use crate::server::terminal::chat::ChatHub;
+use crate::server::terminal::clipboard::{ClipboardHub, write_host_clipboard};
+use crate::server::terminal::portfwd::{PortForwardHub, PortForwardSettings};
+use crate::server::terminal::presence::PresenceHub;
+use crate::server::terminal::resize::{CONTROLLER_SIZE_TTL, ResizeCoordinator, ResizePeer};
 use crate::server::terminal::runtime::{
-    MAX_PTY_COLS, MAX_PTY_ROWS, build_spawn_config, handle_viewport_command,
-    spawn_local_resize_monitor,
+    build_spawn_config, handle_viewport_command, search_results_frame, spawn_local_resize_monitor,
 };
+use crate::server::terminal::transfer::{TransferHub, TransferPolicy, TransferSettings};
 use crate::server::terminal::{
     AlacrittyEmulator, LocalEcho, PtyProcess, PtyWriter, TerminalEmulator, TerminalRuntime,
 };
 use crate::session::terminal::authorization::JoinAuthorizer;
+use crate::session::terminal::transfer::DEFAULT_TRANSFER_MAX_BYTES;
 use crate::session::terminal::tty::{HostInputGate, RawModeGuard};
 use crate::session::{HostSession, SessionConfig, SessionHandle, SessionManager, TransportOffer};
 use crate::sync::SyncConfig;
@@ -35,6 +43,7 @@ use crate::terminal::cli::{BootstrapOutput, HostArgs};
 use crate::terminal::config::cursor_sync_enabled;
 use crate::terminal::error::CliError;
 use crate::transport as transport_mod;
+use crate::transport::extensions::ExtensionHubs;
 use crate::transport::terminal::negotiation::{
     HeartbeatPublisher, NegotiatedTransport, SharedTransport, negotiate_transport,
 };
@@ -44,6 +53,7 @@ use beach_buggy::{
     AckStatus as CtrlAckStatus, ActionAck as CtrlActionAck, ActionCommand as CtrlActionCommand,
     ManagerTransport,
 };
+use harness_proto::actions::ControllerAction;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Write as _;
@@ -52,9 +62,9 @@ use std::process;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{
-    RwLock as AsyncRwLock,
+    RwLock as AsyncRwLock, broadcast,
     mpsc::{self, UnboundedSender},
     oneshot,
 };
@@ -155,6 +165,7 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
     configure_bootstrap_signal_handling(ignore_sighup);
     let local_preview_requested = args.local_preview;
     let local_preview_enabled = local_preview_requested && !bootstrap_mode;
+    let preview_colors = args.colors;
     if local_preview_requested && !local_preview_enabled {
         warn!("local preview disabled when bootstrap output is active");
     }
@@ -298,11 +309,14 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
 
     let emulator = Box::new(AlacrittyEmulator::new(&grid, cursor_sync));
     let local_echo = Arc::new(LocalEcho::new());
+    // An interactive host terminal sees the application's queries through the
+    // mirrored output and answers them itself.
     let (runtime, updates) = TerminalRuntime::spawn(
         spawn_config,
         emulator,
         grid.clone(),
         true,
+        !interactive,
         Some(local_echo.clone()),
     )
     .map_err(|err| CliError::Runtime(err.to_string()))?;
@@ -334,12 +348,14 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
     let mut mcp_task: Option<JoinHandle<()>> = None;
     let mut mcp_handle: Option<McpServerHandle> = None;
     let mcp_bridges: Arc<Mutex<Vec<JoinHandle<()>>>> = Arc::new(Mutex::new(Vec::new()));
+    let chat = Arc::new(ChatHub::new());
     let _mcp_guard: Option<McpRegistryGuard> = if args.mcp {
         let session = McpTerminalSession::new(
             session_id.clone(),
             terminal_sync.clone(),
             writer.clone(),
             process_handle.clone(),
+            Arc::clone(&chat),
         );
         let guard = mcp_global_registry().register_terminal(session);
         let resolved_socket = if args.mcp_stdio {
@@ -394,6 +410,47 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
 
     let mut local_preview_task: Option<tokio::task::JoinHandle<()>> = None;
     let local_server_transport: Arc<Mutex<Option<Arc<dyn Transport>>>> = Arc::new(Mutex::new(None));
+    let resize = Arc::new(ResizeCoordinator::new(
+        args.resize_policy,
+        process_handle.clone(),
+        emulator_handle.clone(),
+        grid.clone(),
+        transports.clone(),
+        Arc::clone(&local_server_transport),
+    ));
+    let presence = Arc::new(PresenceHub::new());
+    if args.file_transfers == TransferPolicy::Prompt && input_gate.is_none() {
+        warn!("file transfer prompts require an interactive TTY; transfers will be refused");
+    }
+    let transfer_dir = match args.transfer_dir.clone() {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    let transfers = Arc::new(TransferHub::new(
+        TransferSettings {
+            policy: args.file_transfers,
+            max_bytes: args.transfer_max_size.unwrap_or(DEFAULT_TRANSFER_MAX_BYTES),
+            dir: transfer_dir,
+        },
+        input_gate.clone(),
+    ));
+    for rule in &args.allow_forward {
+        info!(destination = %rule, "peers may forward connections");
+    }
+    let port_forwards = Arc::new(PortForwardHub::new(PortForwardSettings {
+        allow_connect: args.allow_forward.clone(),
+        allow_listen: args.allow_remote_forward.clone(),
+    }));
+    let clipboard = Arc::new(ClipboardHub::new(
+        args.clipboard_sync,
+        Arc::new(write_host_clipboard),
+    ));
+    let extensions = ExtensionHubs::default()
+        .with(presence)
+        .with(Arc::clone(&chat))
+        .with(transfers)
+        .with(port_forwards)
+        .with(Arc::clone(&clipboard));
 
     if local_preview_enabled {
         let pair = transport_mod::TransportPair::new(TransportKind::Ipc);
@@ -404,8 +461,8 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
             let handle = spawn_input_listener(
                 local_server.clone(),
                 writer.clone(),
-                process_handle.clone(),
-                emulator_handle.clone(),
+                Arc::clone(&resize),
+                extensions.clone(),
                 grid.clone(),
                 backfill_tx.clone(),
                 None,
@@ -420,7 +477,9 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
         }
 
         local_preview_task = Some(tokio::task::spawn_blocking(move || {
-            let client = TerminalClient::new(local_client_transport).with_predictive_input(true);
+            let client = TerminalClient::new(local_client_transport)
+                .with_predictive_input(true)
+                .with_color_mapper(ColorMapper::load(preview_colors));
             match client.run() {
                 Ok(()) | Err(ClientError::Shutdown) => {}
                 Err(err) => eprintln!("⚠️  preview client error: {err}"),
@@ -440,18 +499,16 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
             .as_ref()
             .expect("interactive input gate must exist")
             .clone();
-        let handle = spawn_local_stdin_forwarder(writer.clone(), local_echo.clone(), Some(gate));
+        let handle = spawn_local_stdin_forwarder(
+            writer.clone(),
+            local_echo.clone(),
+            Some(gate),
+            Arc::clone(&resize),
+        );
         input_handles.lock().unwrap().push(handle);
 
         let running = Arc::new(AtomicBool::new(true));
-        let resize_handle = spawn_local_resize_monitor(
-            running.clone(),
-            process_handle.clone(),
-            emulator_handle.clone(),
-            grid.clone(),
-            transports.clone(),
-            Arc::clone(&local_server_transport),
-        );
+        let resize_handle = spawn_local_resize_monitor(running.clone(), Arc::clone(&resize));
         input_handles.lock().unwrap().push(resize_handle);
 
         Some(running)
@@ -484,6 +541,7 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
         input_handles.clone(),
         forwarder_cmd_tx.clone(),
         transports.clone(),
+        Arc::clone(&resize),
         Arc::clone(&authorizer),
         mcp_handle.clone(),
         Arc::clone(&mcp_bridges),
@@ -512,7 +570,21 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
         Some(forwarder_cmd_tx.clone()),
         transports.clone(),
         cursor_sync,
+        runtime.modes(),
     );
+    let clipboard_task = {
+        let mut writes = runtime.clipboard_writes();
+        let clipboard = Arc::clone(&clipboard);
+        tokio::spawn(async move {
+            loop {
+                match writes.recv().await {
+                    Ok(text) => clipboard.publish(&text, None),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    };
 
     runtime
         .wait()
@@ -528,6 +600,7 @@ pub async fn run(base_url: &str, args: HostArgs) -> Result<(), CliError> {
     }
 
     accept_task.abort();
+    clipboard_task.abort();
     let _ = accept_task.await;
 
     let transports_snapshot: Vec<Arc<SharedTransport>> = {
@@ -658,16 +731,47 @@ impl ControllerActionContext {
     }
 }
 
-#[allow(dead_code)]
-fn controller_action_bytes<'a>(action: &'a CtrlActionCommand) -> Result<&'a str, String> {
-    if action.action_type.as_str() != "terminal_write" {
-        return Err(format!("unsupported action type {}", action.action_type));
+/// Handles a controller action may touch on the host.
+#[derive(Clone)]
+struct ControllerActionTarget {
+    writer: PtyWriter,
+    process: Arc<PtyProcess>,
+    resize: Arc<ResizeCoordinator>,
+}
+
+impl ControllerActionTarget {
+    /// Applies `action`. `expires_at` is when the lease that sent it lapses.
+    fn apply(
+        &self,
+        action: &ControllerAction,
+        expires_at: Option<SystemTime>,
+    ) -> Result<(), String> {
+        let lease_deadline = Instant::now()
+            + expires_at.map_or(CONTROLLER_SIZE_TTL, |at| {
+                at.duration_since(SystemTime::now()).unwrap_or_default()
+            });
+        self.resize.extend_controller(lease_deadline);
+        if let Some(bytes) = action.pty_bytes() {
+            return self.writer.write(&bytes).map_err(|err| err.to_string());
+        }
+        match action {
+            ControllerAction::Resize { cols, rows } => {
+                // The controller is one more window while its lease lasts; the
+                // resize policy decides whether its size wins.
+                self.resize
+                    .report_controller_size(*cols, *rows, lease_deadline);
+                Ok(())
+            }
+            ControllerAction::Signal { signal } => {
+                self.process.signal(*signal).map_err(|err| err.to_string())
+            }
+            other => Err(format!("{} action produced no input", other.kind())),
+        }
     }
-    action
-        .payload
-        .get("bytes")
-        .and_then(|value| value.as_str())
-        .ok_or_else(|| "terminal_write payload missing bytes".to_string())
+}
+
+fn controller_action(action: &CtrlActionCommand) -> Result<ControllerAction, String> {
+    ControllerAction::parse(&action.action_type, &action.payload).map_err(|err| err.to_string())
 }
 
 #[allow(dead_code)]
@@ -731,7 +835,7 @@ fn spawn_action_consumer(
 fn spawn_unified_action_consumer(
     ctx: Arc<ControllerActionContext>,
     bridge: Arc<UnifiedBuggyTransport>,
-    writer_for_actions: PtyWriter,
+    target: ControllerActionTarget,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let session_for_actions = ctx.session_id().to_string();
@@ -765,7 +869,7 @@ fn spawn_unified_action_consumer(
                             session_id = %session_for_actions,
                             kind = "action",
                             action_id = %cmd.id,
-                            preview = %controller_action_bytes(&cmd).ok().map(action_preview).unwrap_or_else(|| "<invalid>".into()),
+                            preview = %controller_action(&cmd).ok().map(|action| action_preview(&format!("{action:?}"))).unwrap_or_else(|| "<invalid>".into()),
                             "received fastpath action via extension"
                         );
                         metrics::EXTENSION_RECEIVED
@@ -773,19 +877,20 @@ fn spawn_unified_action_consumer(
                             .inc();
                         let mut status = CtrlAckStatus::Ok;
                         let mut error_message = None;
-                        match controller_action_bytes(&cmd) {
-                            Ok(bytes) => match writer_for_actions.write(bytes.as_bytes()) {
+                        match controller_action(&cmd) {
+                            Ok(action) => match target.apply(&action, cmd.expires_at) {
                                 Ok(()) => {}
                                 Err(err) => {
                                     warn!(
                                         target = "controller.actions",
                                         session_id = %session_for_actions,
                                         command_id = %cmd.id,
+                                        action = %action.kind(),
                                         error = %err,
-                                        "pty write failed for extension action"
+                                        "failed to apply extension action"
                                     );
                                     status = CtrlAckStatus::Rejected;
-                                    error_message = Some(err.to_string());
+                                    error_message = Some(err);
                                 }
                             },
                             Err(err) => {
@@ -1006,8 +1111,8 @@ fn print_host_banner(
 fn spawn_input_listener(
     transport: Arc<dyn Transport>,
     writer: PtyWriter,
-    process: Arc<PtyProcess>,
-    emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
+    resize: Arc<ResizeCoordinator>,
+    extensions: ExtensionHubs,
     grid: Arc<TerminalGrid>,
     _backfill_tx: UnboundedSender<BackfillCommand>,
     _forwarder_tx: Option<UnboundedSender<ForwarderCommand>>,
@@ -1021,6 +1126,8 @@ fn spawn_input_listener(
     thread::spawn(move || {
         let transport_id = transport.id().0;
         let transport_kind = transport.kind();
+        let peer = ResizePeer::Transport(transport_id);
+        extensions.join(&transport);
         loop {
             if let Some(g) = &gate {
                 g.wait_until_resumed();
@@ -1037,13 +1144,10 @@ fn spawn_input_listener(
                                     if writer.write(&data).is_err() {
                                         break;
                                     }
+                                    resize.note_activity(peer);
                                 }
                                 protocol::ClientFrame::Resize { cols, rows } => {
-                                    let _ = process
-                                        .resize(cols.min(MAX_PTY_COLS), rows.min(MAX_PTY_ROWS));
-                                    if let Ok(mut guard) = emulator.lock() {
-                                        guard.resize(rows as usize, cols as usize);
-                                    }
+                                    resize.report_size(peer, cols, rows);
                                 }
                                 protocol::ClientFrame::ViewportCommand { command } => {
                                     let _ = handle_viewport_command(
@@ -1055,6 +1159,39 @@ fn spawn_input_listener(
                                         &_forwarder_tx,
                                     );
                                 }
+                                protocol::ClientFrame::Search {
+                                    request_id,
+                                    pattern,
+                                    case_sensitive,
+                                    backward,
+                                    from_row,
+                                    from_col,
+                                    max_results,
+                                } => {
+                                    let query = SearchQuery {
+                                        pattern,
+                                        case_sensitive,
+                                        direction: if backward {
+                                            SearchDirection::Backward
+                                        } else {
+                                            SearchDirection::Forward
+                                        },
+                                        origin: from_row.map(|row| (row, from_col as usize)),
+                                        max_results: max_results as usize,
+                                    };
+                                    let frame = search_results_frame(&grid, request_id, &query);
+                                    if let Err(err) = send_host_frame(&transport, frame) {
+                                        debug!(
+                                            target = "sync::incoming",
+                                            transport_id,
+                                            error = %err,
+                                            "failed to send search results"
+                                        );
+                                    }
+                                }
+                                protocol::ClientFrame::Extension { frame } => {
+                                    extensions.dispatch(&transport, &frame);
+                                }
                                 _ => {}
                             }
                         }
@@ -1065,6 +1202,8 @@ fn spawn_input_listener(
                 Err(_) => continue,
             }
         }
+        resize.remove_peer(peer);
+        extensions.leave(transport_id);
         drop(controller_ctx);
         drop(client_label);
         drop(client_peer_id);
@@ -1077,6 +1216,7 @@ fn spawn_local_stdin_forwarder(
     writer: PtyWriter,
     local_echo: Arc<LocalEcho>,
     gate: Option<Arc<HostInputGate>>,
+    resize: Arc<ResizeCoordinator>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
         let mut stdin = io::stdin();
@@ -1090,6 +1230,7 @@ fn spawn_local_stdin_forwarder(
                 Ok(n) => {
                     let _ = writer.write(&buf[..n]);
                     local_echo.record_input(&buf[..n]);
+                    resize.note_activity(ResizePeer::Host);
                 }
                 Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
                 Err(_) => break,
@@ -1103,13 +1244,14 @@ fn spawn_webrtc_acceptor(
     session_handle: SessionHandle,
     join_code: Option<String>,
     writer: PtyWriter,
-    _process_handle: Arc<PtyProcess>,
+    process_handle: Arc<PtyProcess>,
     _emulator_handle: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
     _grid: Arc<TerminalGrid>,
     _backfill_tx: UnboundedSender<BackfillCommand>,
     _input_handles: Arc<Mutex<Vec<thread::JoinHandle<()>>>>,
     _forwarder_cmd_tx: UnboundedSender<ForwarderCommand>,
     transports: Arc<Mutex<Vec<Arc<SharedTransport>>>>,
+    resize: Arc<ResizeCoordinator>,
     _authorizer: Arc<JoinAuthorizer>,
     _mcp_handle: Option<McpServerHandle>,
     _mcp_bridges: Arc<Mutex<Vec<JoinHandle<()>>>>,
@@ -1174,7 +1316,12 @@ fn spawn_webrtc_acceptor(
                 "failed to set unified bridge"
             );
         }
-        let _ = spawn_unified_action_consumer(controller_ctx, bridge, writer);
+        let target = ControllerActionTarget {
+            writer,
+            process: process_handle,
+            resize,
+        };
+        let _ = spawn_unified_action_consumer(controller_ctx, bridge, target);
 
         // Keep the transport warm with heartbeats to avoid idle timeouts.
         HeartbeatPublisher::new(transport, None).spawn(Duration::from_secs(15), None);
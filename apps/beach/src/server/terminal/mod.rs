@@ -1,18 +1,26 @@
+pub mod chat;
+pub mod clipboard;
 mod emulator;
 pub mod host;
+pub mod portfwd;
+mod presence;
 mod pty;
+pub mod resize;
 pub mod runtime;
+pub mod transfer;
 
 pub use emulator::{AlacrittyEmulator, EmulatorResult, SimpleTerminalEmulator, TerminalEmulator};
 pub use pty::{Command, PtyProcess, PtyReader, PtyWriter, SpawnConfig, resize_pty};
 
 use crate::cache::terminal::{TerminalGrid, unpack_cell};
 use crate::model::terminal::diff::CacheUpdate;
+use crate::protocol::TerminalModesFrame;
 use crate::telemetry::{self, PerfGuard};
 use anyhow::Result;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{broadcast, watch};
 use tokio::task::JoinHandle;
 use tracing::{self, Level, trace};
 
@@ -65,6 +73,14 @@ pub struct TerminalRuntime {
     writer: PtyWriter,
     reader_handle: JoinHandle<()>,
     emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
+    modes: watch::Receiver<TerminalModesFrame>,
+    clipboard: broadcast::Sender<String>,
+}
+
+/// What the read loop reports besides grid updates.
+struct OutputTaps {
+    modes: watch::Sender<TerminalModesFrame>,
+    clipboard: broadcast::Sender<String>,
 }
 
 impl TerminalRuntime {
@@ -73,18 +89,26 @@ impl TerminalRuntime {
         emulator: Box<dyn TerminalEmulator + Send>,
         grid: Arc<TerminalGrid>,
         mirror_stdout: bool,
+        answer_queries: bool,
         local_echo: Option<Arc<LocalEcho>>,
     ) -> Result<(Self, UnboundedReceiver<CacheUpdate>)> {
         let (process_raw, reader, writer) = PtyProcess::spawn(config)?;
         let process = Arc::new(process_raw);
         let emulator = Arc::new(Mutex::new(emulator));
         let (tx, rx) = mpsc::unbounded_channel();
+        let (modes_tx, modes) = watch::channel(TerminalModesFrame::default());
+        let (clipboard, _) = broadcast::channel(16);
 
         let reader_handle = tokio::spawn(read_loop(
             reader,
+            answer_queries.then(|| writer.clone()),
             emulator.clone(),
             grid,
             tx,
+            OutputTaps {
+                modes: modes_tx,
+                clipboard: clipboard.clone(),
+            },
             mirror_stdout,
             local_echo.clone(),
         ));
@@ -95,6 +119,8 @@ impl TerminalRuntime {
                 writer,
                 reader_handle,
                 emulator,
+                modes,
+                clipboard,
             },
             rx,
         ))
@@ -120,6 +146,16 @@ impl TerminalRuntime {
         self.emulator.clone()
     }
 
+    /// Follows the emulator's input modes; changes after each PTY chunk.
+    pub fn modes(&self) -> watch::Receiver<TerminalModesFrame> {
+        self.modes.clone()
+    }
+
+    /// Clipboard writes the hosted application makes with OSC 52.
+    pub fn clipboard_writes(&self) -> broadcast::Receiver<String> {
+        self.clipboard.subscribe()
+    }
+
     pub fn shutdown(&self) {
         self.process.shutdown();
     }
@@ -138,9 +174,11 @@ impl TerminalRuntime {
 
 async fn read_loop(
     reader: PtyReader,
+    replies: Option<PtyWriter>,
     emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
     grid: Arc<TerminalGrid>,
     tx: UnboundedSender<CacheUpdate>,
+    taps: OutputTaps,
     mirror_stdout: bool,
     local_echo: Option<Arc<LocalEcho>>,
 ) {
@@ -169,10 +207,29 @@ async fn read_loop(
                 } else if forwarded.is_empty() {
                     continue;
                 }
-                let updates = {
+                let (updates, modes, answers, copied) = {
                     let mut emulator = emulator.lock().unwrap();
-                    emulator.handle_output(&chunk, &grid)
+                    let updates = emulator.handle_output(&chunk, &grid);
+                    (
+                        updates,
+                        emulator.modes(),
+                        emulator.take_replies(),
+                        emulator.take_clipboard_writes(),
+                    )
                 };
+                for text in copied {
+                    let _ = taps.clipboard.send(text);
+                }
+                if let Some(writer) = replies.as_ref().filter(|_| !answers.is_empty()) {
+                    if let Err(err) = writer.write(&answers) {
+                        trace!(target = "server::pty", error = %err, "query reply dropped");
+                    }
+                }
+                taps.modes.send_if_modified(|current| {
+                    let changed = *current != modes;
+                    *current = modes;
+                    changed
+                });
                 for update in updates {
                     log_update_sample(grid.as_ref(), &update);
                     apply_update(&grid, &update);
@@ -328,7 +385,8 @@ mod tests {
         let command = Command::new("/usr/bin/env").arg("printf").arg("hello");
         let config = SpawnConfig::new(command, 80, 24);
 
-        let spawn_result = TerminalRuntime::spawn(config, emulator, grid.clone(), false, None);
+        let spawn_result =
+            TerminalRuntime::spawn(config, emulator, grid.clone(), false, true, None);
         let (runtime, mut updates) = match spawn_result {
             Ok(value) => value,
             Err(err) => {
@@ -384,7 +442,8 @@ mod tests {
             .arg("for i in {1..150}; do echo \"Line $i: Test\"; done");
         let config = SpawnConfig::new(command, cols as u16, rows as u16);
 
-        let spawn_result = TerminalRuntime::spawn(config, emulator, grid.clone(), false, None);
+        let spawn_result =
+            TerminalRuntime::spawn(config, emulator, grid.clone(), false, true, None);
         let (runtime, mut updates) = match spawn_result {
             Ok(value) => value,
             Err(err) => {
@@ -0,0 +1,395 @@
+//! Host side of `beach.portfwd`: dials destinations for peers' local forwards
+//! and listens on loopback ports for their remote forwards. Nothing is
+//! forwarded unless the host allowlisted it with `--allow-forward` or
+//! `--allow-remote-forward`.
+
+use crate::protocol::portfwd::PortForwardEvent;
+use crate::session::terminal::portfwd::{ForwardStreams, PortForwardSink, dial, parse_host_port};
+use crate::transport::Transport;
+use crate::transport::extensions::{ExtensionHub, ExtensionPeers, send_event};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// A destination peers may reach through a local forward.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardRule {
+    pub host: String,
+    pub port: u16,
+}
+
+impl ForwardRule {
+    fn permits(&self, host: &str, port: u16) -> bool {
+        port == self.port
+            && (host.eq_ignore_ascii_case(&self.host)
+                || (is_loopback(host) && is_loopback(&self.host)))
+    }
+}
+
+/// `PORT` (on localhost) or `HOST:PORT`.
+impl FromStr for ForwardRule {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Ok(port) = value.parse::<u16>() {
+            return Ok(Self {
+                host: "localhost".into(),
+                port,
+            });
+        }
+        let (host, port) = parse_host_port(value)?;
+        Ok(Self { host, port })
+    }
+}
+
+impl fmt::Display for ForwardRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PortForwardSettings {
+    /// Destinations peers may connect to with local forwards.
+    pub allow_connect: Vec<ForwardRule>,
+    /// Loopback ports peers may listen on with remote forwards.
+    pub allow_listen: Vec<u16>,
+}
+
+struct PeerForwards {
+    streams: Arc<ForwardStreams>,
+    /// Connections accepted on the peer's listeners, waiting for it to dial.
+    pending: Arc<Mutex<HashMap<u64, TcpStream>>>,
+    /// Stop flags of the peer's listeners, by port.
+    listeners: HashMap<u16, Arc<AtomicBool>>,
+}
+
+impl PeerForwards {
+    fn shutdown(&mut self) {
+        for stop in self.listeners.values() {
+            stop.store(true, Ordering::SeqCst);
+        }
+        self.pending.lock().unwrap().clear();
+        self.streams.close_all();
+    }
+}
+
+pub(crate) struct PortForwardHub {
+    settings: PortForwardSettings,
+    peers: ExtensionPeers<PeerForwards>,
+    /// Host-opened streams take even ids.
+    next_stream: Arc<AtomicU64>,
+}
+
+impl PortForwardHub {
+    pub(crate) fn new(settings: PortForwardSettings) -> Self {
+        Self {
+            settings,
+            peers: ExtensionPeers::default(),
+            next_stream: Arc::new(AtomicU64::new(2)),
+        }
+    }
+
+    fn connect(
+        &self,
+        peer: u64,
+        streams: Arc<ForwardStreams>,
+        stream: u64,
+        host: String,
+        port: u16,
+    ) {
+        let refuse = |reason: String| {
+            streams.emit(PortForwardEvent::Reset { stream, reason });
+        };
+        if stream & 1 == 0 {
+            refuse("client streams must use odd ids".into());
+            return;
+        }
+        if !self
+            .settings
+            .allow_connect
+            .iter()
+            .any(|rule| rule.permits(&host, port))
+        {
+            refuse(format!(
+                "the host does not allow forwarding to {host}:{port}"
+            ));
+            return;
+        }
+        thread::spawn(move || match dial(&host, port) {
+            Ok(socket) => {
+                if let Err(err) = streams.connected(stream, socket) {
+                    streams.emit(PortForwardEvent::Reset {
+                        stream,
+                        reason: err.to_string(),
+                    });
+                    return;
+                }
+                info!(
+                    target = "host::portfwd",
+                    transport_id = peer,
+                    stream,
+                    destination = %format!("{host}:{port}"),
+                    "forwarded connection opened"
+                );
+            }
+            Err(err) => streams.emit(PortForwardEvent::Reset {
+                stream,
+                reason: format!("{host}:{port}: {err}"),
+            }),
+        });
+    }
+
+    fn listen(&self, peer: u64, forwards: &mut PeerForwards, port: u16) -> io::Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        forwards.listeners.insert(port, Arc::clone(&stop));
+        let streams = Arc::clone(&forwards.streams);
+        let pending = Arc::clone(&forwards.pending);
+        let next_stream = Arc::clone(&self.next_stream);
+        info!(
+            target = "host::portfwd",
+            transport_id = peer,
+            port,
+            "listening for remote forward"
+        );
+        thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((socket, _)) => {
+                        if socket.set_nonblocking(false).is_err() {
+                            continue;
+                        }
+                        let stream = next_stream.fetch_add(2, Ordering::SeqCst);
+                        pending.lock().unwrap().insert(stream, socket);
+                        streams.emit(PortForwardEvent::Accepted { stream, port });
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(ACCEPT_POLL);
+                    }
+                    Err(err) => {
+                        streams.emit(PortForwardEvent::Unlisten {
+                            port,
+                            reason: err.to_string(),
+                        });
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl ExtensionHub for PortForwardHub {
+    type Event = PortForwardEvent;
+
+    fn join(&self, transport: &Arc<dyn Transport>) {
+        let sink_transport = transport.clone();
+        let sink: PortForwardSink =
+            Arc::new(move |event: PortForwardEvent| send_event(&sink_transport, &event));
+        self.peers.join(
+            transport,
+            PeerForwards {
+                streams: Arc::new(ForwardStreams::new(sink)),
+                pending: Arc::new(Mutex::new(HashMap::new())),
+                listeners: HashMap::new(),
+            },
+        );
+    }
+
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: PortForwardEvent) {
+        let peer = transport.id().0;
+        let mut peers = self.peers.lock();
+        let Some(forwards) = peers.get_mut(&peer).map(|entry| &mut entry.state) else {
+            return;
+        };
+        let streams = Arc::clone(&forwards.streams);
+        match event {
+            PortForwardEvent::Connect { stream, host, port } => {
+                drop(peers);
+                self.connect(peer, streams, stream, host, port);
+            }
+            PortForwardEvent::Listen { port } => {
+                let reason = if !self.settings.allow_listen.contains(&port) {
+                    Some(format!("the host does not allow listening on port {port}"))
+                } else if forwards.listeners.contains_key(&port) {
+                    Some(format!("already listening on port {port}"))
+                } else {
+                    match self.listen(peer, forwards, port) {
+                        Ok(()) => None,
+                        Err(err) => Some(format!("cannot listen on port {port}: {err}")),
+                    }
+                };
+                drop(peers);
+                streams.emit(match reason {
+                    Some(reason) => PortForwardEvent::Unlisten { port, reason },
+                    None => PortForwardEvent::Listening { port },
+                });
+            }
+            PortForwardEvent::Opened { stream } => {
+                let socket = forwards.pending.lock().unwrap().remove(&stream);
+                drop(peers);
+                let Some(socket) = socket else {
+                    return;
+                };
+                if let Err(err) = streams.attach(stream, socket) {
+                    streams.emit(PortForwardEvent::Reset {
+                        stream,
+                        reason: err.to_string(),
+                    });
+                }
+            }
+            PortForwardEvent::Data { stream, data } => streams.data(stream, data),
+            PortForwardEvent::Credit { stream, bytes } => streams.credit(stream, bytes),
+            PortForwardEvent::Close { stream } => streams.close(stream),
+            PortForwardEvent::Reset { stream, reason } => {
+                forwards.pending.lock().unwrap().remove(&stream);
+                streams.reset(stream);
+                debug!(
+                    target = "host::portfwd",
+                    transport_id = peer,
+                    stream,
+                    reason = %reason,
+                    "peer reset forwarded stream"
+                );
+            }
+            PortForwardEvent::Listening { .. }
+            | PortForwardEvent::Unlisten { .. }
+            | PortForwardEvent::Accepted { .. } => {}
+        }
+    }
+
+    /// Stops a detached peer's listeners and drops its streams.
+    fn leave(&self, peer: u64) {
+        if let Some(mut forwards) = self.peers.leave(peer) {
+            forwards.shutdown();
+        }
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::recv_event;
+    use crate::transport::{TransportKind, TransportPair};
+    use bytes::Bytes;
+    use std::io::{Read, Write};
+
+    #[test_timeout::timeout]
+    fn allowlist_rules_match_loopback_aliases() {
+        let rule: ForwardRule = "3000".parse().unwrap();
+        assert!(rule.permits("localhost", 3000));
+        assert!(rule.permits("127.0.0.1", 3000));
+        assert!(rule.permits("::1", 3000));
+        assert!(!rule.permits("localhost", 3001));
+        assert!(!rule.permits("example.com", 3000));
+
+        let rule: ForwardRule = "db.internal:5432".parse().unwrap();
+        assert!(rule.permits("DB.internal", 5432));
+        assert!(!rule.permits("localhost", 5432));
+    }
+
+    #[test_timeout::timeout]
+    fn dials_allowlisted_destinations_only() {
+        let service = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = service.local_addr().unwrap().port();
+        let hub = Arc::new(PortForwardHub::new(PortForwardSettings {
+            allow_connect: vec![ForwardRule {
+                host: "localhost".into(),
+                port,
+            }],
+            allow_listen: Vec::new(),
+        }));
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+        let peer = server.id().0;
+        hub.join(&server);
+
+        hub.handle(
+            &server,
+            PortForwardEvent::Connect {
+                stream: 1,
+                host: "localhost".into(),
+                port: port.wrapping_add(1),
+            },
+        );
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(PortForwardEvent::Reset {
+                stream: 1,
+                reason: format!(
+                    "the host does not allow forwarding to localhost:{}",
+                    port.wrapping_add(1)
+                ),
+            })
+        );
+        hub.handle(&server, PortForwardEvent::Listen { port: 9 });
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(PortForwardEvent::Unlisten {
+                port: 9,
+                reason: "the host does not allow listening on port 9".into(),
+            })
+        );
+
+        hub.handle(
+            &server,
+            PortForwardEvent::Connect {
+                stream: 3,
+                host: "127.0.0.1".into(),
+                port,
+            },
+        );
+        let (mut accepted, _) = service.accept().unwrap();
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(PortForwardEvent::Opened { stream: 3 })
+        );
+        hub.handle(
+            &server,
+            PortForwardEvent::Data {
+                stream: 3,
+                data: Bytes::from_static(b"ping"),
+            },
+        );
+        let mut buf = [0; 4];
+        accepted.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(PortForwardEvent::Credit {
+                stream: 3,
+                bytes: 4
+            })
+        );
+        accepted.write_all(b"pong").unwrap();
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(PortForwardEvent::Data {
+                stream: 3,
+                data: Bytes::from_static(b"pong"),
+            })
+        );
+
+        hub.leave(peer);
+        let mut rest = Vec::new();
+        accepted.read_to_end(&mut rest).unwrap();
+        assert!(rest.is_empty());
+    }
+}
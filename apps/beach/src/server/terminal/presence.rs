@@ -0,0 +1,122 @@
+//! Relays `beach.presence` updates between attached peers. The host keeps the
+//! latest state of every peer so late joiners see who is already there.
+
+use crate::protocol::presence::{PresenceEvent, PresenceState};
+use crate::transport::Transport;
+use crate::transport::extensions::{ExtensionHub, ExtensionPeers, send_event};
+use std::sync::Arc;
+
+#[derive(Default)]
+pub(crate) struct PresenceHub {
+    /// Each peer's latest state, once it has sent one.
+    peers: ExtensionPeers<Option<PresenceState>>,
+}
+
+impl PresenceHub {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `peer`'s latest state and forwards it to every other peer.
+    pub(crate) fn update(&self, peer: u64, state: PresenceState) {
+        {
+            let mut peers = self.peers.lock();
+            let Some(entry) = peers.get_mut(&peer) else {
+                return;
+            };
+            if entry.state.as_ref() == Some(&state) {
+                return;
+            }
+            entry.state = Some(state.clone());
+        }
+        let event = PresenceEvent::Update { peer, state };
+        for transport in self.peers.select(|id, _| id != peer) {
+            send_event(&transport, &event);
+        }
+    }
+}
+
+impl ExtensionHub for PresenceHub {
+    type Event = PresenceEvent;
+
+    /// Registers a peer's transport and replays everyone else's state to it.
+    fn join(&self, transport: &Arc<dyn Transport>) {
+        let peer = transport.id().0;
+        self.peers.join(transport, None);
+        let existing: Vec<PresenceEvent> = self
+            .peers
+            .lock()
+            .iter()
+            .filter(|(id, _)| **id != peer)
+            .filter_map(|(id, entry)| {
+                entry
+                    .state
+                    .clone()
+                    .map(|state| PresenceEvent::Update { peer: *id, state })
+            })
+            .collect();
+        for event in existing {
+            send_event(transport, &event);
+        }
+    }
+
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: PresenceEvent) {
+        if let PresenceEvent::Update { state, .. } = event {
+            self.update(transport.id().0, state);
+        }
+    }
+
+    /// Drops a detached peer and tells the others it left.
+    fn leave(&self, peer: u64) {
+        if !matches!(self.peers.leave(peer), Some(Some(_))) {
+            return;
+        }
+        let event = PresenceEvent::Leave { peer };
+        for transport in self.peers.select(|_, _| true) {
+            send_event(&transport, &event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::extensions::{assert_no_event, recv_event};
+    use crate::transport::{TransportKind, TransportPair};
+
+    fn state(label: &str) -> PresenceState {
+        PresenceState {
+            label: label.into(),
+            ..PresenceState::default()
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn relays_updates_to_other_peers_and_replays_to_late_joiners() {
+        let hub = PresenceHub::new();
+        let first = TransportPair::new(TransportKind::Ipc);
+        let second = TransportPair::new(TransportKind::Ipc);
+        let first_server: Arc<dyn Transport> = Arc::from(first.server);
+        let second_server: Arc<dyn Transport> = Arc::from(second.server);
+        let first_id = first_server.id().0;
+
+        hub.join(&first_server);
+        hub.update(first_id, state("alice"));
+
+        hub.join(&second_server);
+        assert_eq!(
+            recv_event(second.client.as_ref()),
+            Some(PresenceEvent::Update {
+                peer: first_id,
+                state: state("alice"),
+            })
+        );
+        assert_no_event(first.client.as_ref());
+
+        hub.leave(first_id);
+        assert_eq!(
+            recv_event(second.client.as_ref()),
+            Some(PresenceEvent::Leave { peer: first_id })
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use harness_proto::actions::PtySignal;
 use portable_pty::{Child, CommandBuilder, PtyPair, PtySize, native_pty_system};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
@@ -117,6 +118,30 @@ impl PtyProcess {
         };
         master.resize(size).context("resize PTY")
     }
+
+    /// Delivers `signal` to the PTY child process.
+    #[cfg(unix)]
+    pub fn signal(&self, signal: PtySignal) -> Result<()> {
+        let guard = self.child.lock().unwrap();
+        let pid = guard
+            .as_ref()
+            .and_then(|child| child.process_id())
+            .context("PTY child is not running")?;
+        let signo = match signal {
+            PtySignal::Sigint => libc::SIGINT,
+            PtySignal::Sigterm => libc::SIGTERM,
+        };
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        if unsafe { libc::kill(pid as libc::pid_t, signo) } != 0 {
+            return Err(std::io::Error::last_os_error()).context("signal PTY child");
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn signal(&self, signal: PtySignal) -> Result<()> {
+        anyhow::bail!("{signal:?} is not supported on this platform")
+    }
 }
 
 impl Drop for PtyProcess {
@@ -0,0 +1,337 @@
+//! Picks the PTY size when several peers view one session. Every peer reports
+//! its window size and the coordinator resizes the PTY from the aggregate
+//! instead of letting the last `Resize` frame win.
+
+use crate::cache::terminal::TerminalGrid;
+use crate::server::terminal::runtime::{MAX_PTY_COLS, MAX_PTY_ROWS, broadcast_viewport};
+use crate::server::terminal::{PtyProcess, TerminalEmulator};
+use crate::transport::Transport;
+use crate::transport::terminal::negotiation::SharedTransport;
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// How long a controller's size holds when its action carries no lease expiry.
+pub(crate) const CONTROLLER_SIZE_TTL: Duration = Duration::from_secs(30);
+
+/// How the host sizes the PTY when attached windows disagree, modeled on
+/// tmux's `window-size` option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ResizePolicy {
+    /// Fit the smallest window in each dimension so nobody sees clipped output.
+    Smallest,
+    /// Fit the largest window in each dimension.
+    Largest,
+    /// Follow the host's own terminal; peers only size the PTY when the host
+    /// has no terminal.
+    Host,
+    /// Follow whichever peer most recently typed or resized.
+    #[default]
+    LatestActive,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum ResizePeer {
+    /// The terminal the host runs in.
+    Host,
+    /// An automation controller sizing the session through the manager.
+    Controller,
+    Transport(u64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PeerSize {
+    cols: u16,
+    rows: u16,
+    /// Logical clock of the peer's last input or resize.
+    last_active: u64,
+}
+
+#[derive(Default)]
+struct ResizeState {
+    peers: HashMap<ResizePeer, PeerSize>,
+    clock: u64,
+    applied: Option<(u16, u16)>,
+    /// When the controller's size lapses along with the lease behind it.
+    controller_expires_at: Option<Instant>,
+    controller_timer: bool,
+}
+
+impl ResizeState {
+    /// Drops the controller's size once its lease has lapsed; returns `true`
+    /// when it was removed.
+    fn expire_controller(&mut self, now: Instant) -> bool {
+        if self.controller_expires_at.is_some_and(|at| at > now) {
+            return false;
+        }
+        self.controller_expires_at = None;
+        self.peers.remove(&ResizePeer::Controller).is_some()
+    }
+}
+
+pub(crate) struct ResizeCoordinator {
+    policy: ResizePolicy,
+    process: Arc<PtyProcess>,
+    emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
+    grid: Arc<TerminalGrid>,
+    transports: Arc<Mutex<Vec<Arc<SharedTransport>>>>,
+    local_server_transport: Arc<Mutex<Option<Arc<dyn Transport>>>>,
+    state: Mutex<ResizeState>,
+}
+
+impl ResizeCoordinator {
+    pub(crate) fn new(
+        policy: ResizePolicy,
+        process: Arc<PtyProcess>,
+        emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
+        grid: Arc<TerminalGrid>,
+        transports: Arc<Mutex<Vec<Arc<SharedTransport>>>>,
+        local_server_transport: Arc<Mutex<Option<Arc<dyn Transport>>>>,
+    ) -> Self {
+        let (rows, cols) = grid.viewport_size();
+        let state = ResizeState {
+            applied: Some((cols as u16, rows as u16)),
+            ..ResizeState::default()
+        };
+        Self {
+            policy,
+            process,
+            emulator,
+            grid,
+            transports,
+            local_server_transport,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Records the window size `peer` wants and resizes the PTY if the
+    /// policy's choice changed.
+    pub(crate) fn report_size(&self, peer: ResizePeer, cols: u16, rows: u16) {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let last_active = state.clock;
+        state.peers.insert(
+            peer,
+            PeerSize {
+                cols: cols.clamp(1, MAX_PTY_COLS),
+                rows: rows.clamp(1, MAX_PTY_ROWS),
+                last_active,
+            },
+        );
+        self.reconcile(&mut state);
+    }
+
+    /// Marks `peer` as the most recently active one, which moves the PTY to
+    /// its size under [`ResizePolicy::LatestActive`].
+    pub(crate) fn note_activity(&self, peer: ResizePeer) {
+        if self.policy != ResizePolicy::LatestActive {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        let Some(size) = state.peers.get_mut(&peer) else {
+            return;
+        };
+        size.last_active = clock;
+        self.reconcile(&mut state);
+    }
+
+    /// Records a controller resize that holds until `expires_at`.
+    pub(crate) fn report_controller_size(
+        self: &Arc<Self>,
+        cols: u16,
+        rows: u16,
+        expires_at: Instant,
+    ) {
+        self.report_size(ResizePeer::Controller, cols, rows);
+        self.extend_controller(expires_at);
+    }
+
+    /// Keeps the controller's size while its lease, renewed by each action it
+    /// sends, is live.
+    pub(crate) fn extend_controller(self: &Arc<Self>, expires_at: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if !state.peers.contains_key(&ResizePeer::Controller) {
+            return;
+        }
+        state.controller_expires_at = Some(
+            state
+                .controller_expires_at
+                .map_or(expires_at, |at| at.max(expires_at)),
+        );
+        if std::mem::replace(&mut state.controller_timer, true) {
+            return;
+        }
+        drop(state);
+        let coordinator = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut deadline = expires_at;
+            loop {
+                tokio::time::sleep_until(deadline.into()).await;
+                let mut state = coordinator.state.lock().unwrap();
+                if state.expire_controller(Instant::now()) {
+                    coordinator.reconcile(&mut state);
+                }
+                match state.controller_expires_at {
+                    Some(at) => deadline = at,
+                    None => {
+                        state.controller_timer = false;
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Forgets a detached peer so its window no longer constrains the PTY.
+    pub(crate) fn remove_peer(&self, peer: ResizePeer) {
+        let mut state = self.state.lock().unwrap();
+        if state.peers.remove(&peer).is_some() {
+            self.reconcile(&mut state);
+        }
+    }
+
+    fn reconcile(&self, state: &mut ResizeState) {
+        let Some((cols, rows)) = choose_size(self.policy, &state.peers) else {
+            return;
+        };
+        if state.applied == Some((cols, rows)) {
+            return;
+        }
+        if let Err(err) = self.process.resize(cols, rows) {
+            warn!(
+                target = "host::resize",
+                cols,
+                rows,
+                error = %err,
+                "failed to apply PTY resize"
+            );
+            return;
+        }
+        if let Ok(mut emulator) = self.emulator.lock() {
+            emulator.resize(rows as usize, cols as usize);
+        }
+        self.grid.set_viewport_size(rows as usize, cols as usize);
+        broadcast_viewport(
+            cols,
+            rows,
+            &self.grid,
+            &self.transports,
+            &self.local_server_transport,
+        );
+        debug!(
+            target = "host::resize",
+            policy = ?self.policy,
+            peers = state.peers.len(),
+            cols,
+            rows,
+            "applied PTY resize"
+        );
+        state.applied = Some((cols, rows));
+    }
+}
+
+fn choose_size(policy: ResizePolicy, peers: &HashMap<ResizePeer, PeerSize>) -> Option<(u16, u16)> {
+    let latest = || {
+        peers
+            .values()
+            .max_by_key(|size| size.last_active)
+            .map(|size| (size.cols, size.rows))
+    };
+    match policy {
+        ResizePolicy::Smallest => Some((
+            peers.values().map(|size| size.cols).min()?,
+            peers.values().map(|size| size.rows).min()?,
+        )),
+        ResizePolicy::Largest => Some((
+            peers.values().map(|size| size.cols).max()?,
+            peers.values().map(|size| size.rows).max()?,
+        )),
+        ResizePolicy::Host => peers
+            .get(&ResizePeer::Host)
+            .map(|size| (size.cols, size.rows))
+            .or_else(latest),
+        ResizePolicy::LatestActive => latest(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peers(entries: &[(ResizePeer, u16, u16, u64)]) -> HashMap<ResizePeer, PeerSize> {
+        entries
+            .iter()
+            .map(|&(peer, cols, rows, last_active)| {
+                (
+                    peer,
+                    PeerSize {
+                        cols,
+                        rows,
+                        last_active,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test_timeout::timeout]
+    fn policies_aggregate_peer_sizes() {
+        let attached = peers(&[
+            (ResizePeer::Host, 120, 40, 1),
+            (ResizePeer::Transport(7), 80, 50, 3),
+            (ResizePeer::Transport(9), 200, 24, 2),
+        ]);
+        assert_eq!(
+            choose_size(ResizePolicy::Smallest, &attached),
+            Some((80, 24))
+        );
+        assert_eq!(
+            choose_size(ResizePolicy::Largest, &attached),
+            Some((200, 50))
+        );
+        assert_eq!(choose_size(ResizePolicy::Host, &attached), Some((120, 40)));
+        assert_eq!(
+            choose_size(ResizePolicy::LatestActive, &attached),
+            Some((80, 50))
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn controller_size_lapses_with_its_lease() {
+        let now = Instant::now();
+        let mut state = ResizeState {
+            peers: peers(&[
+                (ResizePeer::Transport(7), 120, 40, 1),
+                (ResizePeer::Controller, 80, 24, 2),
+            ]),
+            controller_expires_at: Some(now + CONTROLLER_SIZE_TTL),
+            ..ResizeState::default()
+        };
+        assert!(!state.expire_controller(now));
+        assert_eq!(
+            choose_size(ResizePolicy::Smallest, &state.peers),
+            Some((80, 24))
+        );
+
+        assert!(state.expire_controller(now + CONTROLLER_SIZE_TTL));
+        assert_eq!(state.controller_expires_at, None);
+        assert_eq!(
+            choose_size(ResizePolicy::Smallest, &state.peers),
+            Some((120, 40))
+        );
+    }
+
+    #[test_timeout::timeout]
+    fn host_policy_falls_back_without_a_host_terminal() {
+        let headless = peers(&[
+            (ResizePeer::Transport(1), 100, 30, 5),
+            (ResizePeer::Transport(2), 90, 20, 6),
+        ]);
+        assert_eq!(choose_size(ResizePolicy::Host, &headless), Some((90, 20)));
+        assert_eq!(choose_size(ResizePolicy::Smallest, &HashMap::new()), None);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::cache::terminal::TerminalGrid;
-use crate::protocol::{HostFrame, ViewportCommand};
-use crate::server::terminal::{
-    Command as PtyCommand, PtyProcess, PtyWriter, SpawnConfig, TerminalEmulator,
-};
+use crate::cache::terminal::search::{SearchQuery, search_grid};
+use crate::protocol::{HostFrame, SearchHit, ViewportCommand};
+use crate::server::terminal::resize::{ResizeCoordinator, ResizePeer};
+use crate::server::terminal::{Command as PtyCommand, PtyWriter, SpawnConfig};
 use crate::sync::terminal::server_pipeline::{self, ForwarderCommand};
 use crate::terminal::error::CliError;
 use crate::transport::terminal::negotiation::SharedTransport;
@@ -86,51 +86,50 @@ pub(crate) fn handle_viewport_command(
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
+pub(crate) fn search_results_frame(
+    grid: &TerminalGrid,
+    request_id: u64,
+    query: &SearchQuery,
+) -> HostFrame {
+    let outcome = search_grid(grid, query);
+    trace!(
+        target = "sync::incoming",
+        request_id,
+        hits = outcome.matches.len(),
+        truncated = outcome.truncated,
+        "history search completed"
+    );
+    HostFrame::SearchResults {
+        request_id,
+        hits: outcome
+            .matches
+            .into_iter()
+            .map(|hit| SearchHit {
+                row: hit.row,
+                col: hit.col as u32,
+                len: hit.len as u32,
+            })
+            .collect(),
+        truncated: outcome.truncated,
+    }
+}
+
 pub(crate) fn spawn_local_resize_monitor(
     running: Arc<AtomicBool>,
-    process: Arc<PtyProcess>,
-    emulator: Arc<Mutex<Box<dyn TerminalEmulator + Send>>>,
-    grid: Arc<TerminalGrid>,
-    transports: Arc<Mutex<Vec<Arc<SharedTransport>>>>,
-    local_server_transport: Arc<Mutex<Option<Arc<dyn Transport>>>>,
+    resize: Arc<ResizeCoordinator>,
 ) -> thread::JoinHandle<()> {
     thread::spawn(move || {
         let mut last_size: Option<(u16, u16)> = None;
         while running.load(Ordering::Relaxed) {
-            let (cols_raw, rows_raw) = detect_terminal_size();
-            let cols = cols_raw.min(MAX_PTY_COLS);
-            let rows = rows_raw.min(MAX_PTY_ROWS);
-            let current = (cols, rows);
-
+            let current = detect_terminal_size();
             if Some(current) != last_size {
-                let mut applied = true;
-                if let Err(err) = process.resize(cols, rows) {
-                    warn!(
-                        target = "host::local_resize",
-                        cols,
-                        rows,
-                        error = %err,
-                        "failed to apply PTY resize"
-                    );
-                    applied = false;
-                }
-
-                if applied {
-                    if let Ok(mut emulator) = emulator.lock() {
-                        emulator.resize(rows as usize, cols as usize);
-                    }
-                    grid.set_viewport_size(rows as usize, cols as usize);
-
-                    broadcast_viewport(cols, rows, &grid, &transports, &local_server_transport);
-
-                    trace!(
-                        target = "host::local_resize",
-                        cols, rows, "applied local terminal resize"
-                    );
-
-                    last_size = Some(current);
-                }
+                let (cols, rows) = current;
+                resize.report_size(ResizePeer::Host, cols, rows);
+                trace!(
+                    target = "host::local_resize",
+                    cols, rows, "reported local terminal size"
+                );
+                last_size = Some(current);
             }
 
             thread::sleep(Duration::from_millis(200));
@@ -140,7 +139,7 @@ pub(crate) fn spawn_local_resize_monitor(
     })
 }
 
-fn broadcast_viewport(
+pub(crate) fn broadcast_viewport(
     cols: u16,
     rows: u16,
     grid: &Arc<TerminalGrid>,
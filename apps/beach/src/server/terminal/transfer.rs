@@ -0,0 +1,517 @@
+//! Host side of `beach.transfer`: peers push files into the host's transfer
+//! directory or pull files from the host, each once the host consents.
+
+use crate::protocol::transfer::{TransferEvent, TransferOffer};
+use crate::session::terminal::authorization::confirm_on_host;
+use crate::session::terminal::transfer::{IncomingFile, OutgoingFile, TransferError};
+use crate::session::terminal::tty::HostInputGate;
+use crate::transport::Transport;
+use crate::transport::extensions::{ExtensionHub, send_event};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tracing::{debug, info};
+
+/// Whether the host accepts file transfers from its peers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TransferPolicy {
+    /// Ask on the host terminal before every transfer; refuse when there is
+    /// no terminal to ask on.
+    #[default]
+    Prompt,
+    /// Accept every transfer within the size limit.
+    Allow,
+    /// Refuse all transfers.
+    Deny,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransferSettings {
+    pub policy: TransferPolicy,
+    pub max_bytes: u64,
+    /// Where pushed files land and what pull paths resolve against; pulls
+    /// never reach outside it.
+    pub dir: PathBuf,
+}
+
+enum HostTransfer {
+    Receiving(IncomingFile),
+    Sending(OutgoingFile),
+}
+
+pub(crate) struct TransferHub {
+    settings: TransferSettings,
+    gate: Option<Arc<HostInputGate>>,
+    prompt_lock: Mutex<()>,
+    /// Active transfers keyed by (transport id, transfer id).
+    transfers: Mutex<HashMap<(u64, u64), HostTransfer>>,
+}
+
+impl TransferHub {
+    pub(crate) fn new(settings: TransferSettings, gate: Option<Arc<HostInputGate>>) -> Self {
+        Self {
+            settings,
+            gate,
+            prompt_lock: Mutex::new(()),
+            transfers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn receive(&self, transport: &Arc<dyn Transport>, offer: TransferOffer) {
+        let peer = transport.id().0;
+        let id = offer.id;
+        if offer.size > self.settings.max_bytes {
+            let err = TransferError::TooLarge {
+                size: offer.size,
+                limit: self.settings.max_bytes,
+            };
+            cancel(transport, id, &err.to_string());
+            return;
+        }
+        let details = vec![
+            format!("file      : {}", offer.name),
+            format!("size      : {} bytes", offer.size),
+            format!("sha-256   : {}", offer.sha256),
+            format!("save in   : {}", self.settings.dir.display()),
+            format!("peer      : transport {peer}"),
+        ];
+        if let Err(reason) = self.consent("Incoming file from a beach peer", &details) {
+            cancel(transport, id, reason);
+            return;
+        }
+        let incoming =
+            match IncomingFile::create(&self.settings.dir, offer, self.settings.max_bytes) {
+                Ok(incoming) => incoming,
+                Err(err) => {
+                    cancel(transport, id, &err.to_string());
+                    return;
+                }
+            };
+        let offset = incoming.offset();
+        let complete = incoming.is_complete();
+        self.transfers
+            .lock()
+            .unwrap()
+            .insert((peer, id), HostTransfer::Receiving(incoming));
+        send_event(transport, &TransferEvent::Accept { id, offset });
+        if complete {
+            self.finish_receive(transport, id);
+        }
+    }
+
+    fn send(&self, transport: &Arc<dyn Transport>, id: u64, path: &str) {
+        let peer = transport.id().0;
+        let path = match self.resolve(path) {
+            Ok(path) => path,
+            Err(err) => {
+                cancel(transport, id, &err.to_string());
+                return;
+            }
+        };
+        let outgoing = match OutgoingFile::open(id, &path, self.settings.max_bytes) {
+            Ok(outgoing) => outgoing,
+            Err(err) => {
+                cancel(transport, id, &err.to_string());
+                return;
+            }
+        };
+        let details = vec![
+            format!("file      : {}", path.display()),
+            format!("size      : {} bytes", outgoing.offer().size),
+            format!("peer      : transport {peer}"),
+        ];
+        if let Err(reason) = self.consent("A beach peer requests a file", &details) {
+            cancel(transport, id, reason);
+            return;
+        }
+        let offer = outgoing.offer().clone();
+        self.transfers
+            .lock()
+            .unwrap()
+            .insert((peer, id), HostTransfer::Sending(outgoing));
+        send_event(transport, &TransferEvent::Offer(offer));
+    }
+
+    /// Resolves a pull path inside the transfer directory. Only plain
+    /// relative components are accepted, and the canonical result must stay
+    /// under the canonical directory so symlinks cannot lead out of it.
+    fn resolve(&self, path: &str) -> Result<PathBuf, TransferError> {
+        let outside = || TransferError::OutsideDir(path.to_string());
+        let relative = Path::new(path);
+        if relative.as_os_str().is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(outside());
+        }
+        let dir = self.settings.dir.canonicalize()?;
+        let resolved = dir.join(relative).canonicalize()?;
+        if !resolved.starts_with(&dir) {
+            return Err(outside());
+        }
+        Ok(resolved)
+    }
+
+    fn consent(&self, title: &str, details: &[String]) -> Result<(), &'static str> {
+        match self.settings.policy {
+            TransferPolicy::Allow => Ok(()),
+            TransferPolicy::Deny => Err("file transfers are disabled on this host"),
+            TransferPolicy::Prompt => {
+                let Some(gate) = &self.gate else {
+                    return Err("the host has no terminal to approve transfers on");
+                };
+                let _prompt = self.prompt_lock.lock().unwrap();
+                if confirm_on_host(gate, title, details) {
+                    Ok(())
+                } else {
+                    Err("the host declined the transfer")
+                }
+            }
+        }
+    }
+
+    fn write_chunk(&self, transport: &Arc<dyn Transport>, id: u64, offset: u64, data: &[u8]) {
+        let key = (transport.id().0, id);
+        let mut transfers = self.transfers.lock().unwrap();
+        let Some(HostTransfer::Receiving(incoming)) = transfers.get_mut(&key) else {
+            return;
+        };
+        match incoming.write_chunk(offset, data) {
+            Ok(_) if incoming.is_complete() => {
+                drop(transfers);
+                self.finish_receive(transport, id);
+            }
+            Ok(Some(offset)) => send_event(transport, &TransferEvent::Ack { id, offset }),
+            Ok(None) => {}
+            Err(err) => {
+                transfers.remove(&key);
+                cancel(transport, id, &err.to_string());
+            }
+        }
+    }
+
+    fn finish_receive(&self, transport: &Arc<dyn Transport>, id: u64) {
+        let peer = transport.id().0;
+        let Some(HostTransfer::Receiving(incoming)) =
+            self.transfers.lock().unwrap().remove(&(peer, id))
+        else {
+            return;
+        };
+        match incoming.finish() {
+            Ok(path) => {
+                info!(
+                    target = "host::transfer",
+                    transport_id = peer,
+                    path = %path.display(),
+                    "file received from peer"
+                );
+                send_event(transport, &TransferEvent::Complete { id });
+            }
+            Err(err) => cancel(transport, id, &err.to_string()),
+        }
+    }
+}
+
+impl ExtensionHub for TransferHub {
+    type Event = TransferEvent;
+
+    /// Handles a transfer frame from the peer on `transport`. Offers and
+    /// requests wait for consent on their own thread so the peer's terminal
+    /// input keeps flowing meanwhile.
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: TransferEvent) {
+        let peer = transport.id().0;
+        match event {
+            TransferEvent::Offer(offer) => {
+                let hub = Arc::clone(self);
+                let transport = transport.clone();
+                thread::spawn(move || hub.receive(&transport, offer));
+            }
+            TransferEvent::Request { id, path } => {
+                let hub = Arc::clone(self);
+                let transport = transport.clone();
+                thread::spawn(move || hub.send(&transport, id, &path));
+            }
+            TransferEvent::Accept { id, offset } => {
+                let mut transfers = self.transfers.lock().unwrap();
+                if let Some(HostTransfer::Sending(outgoing)) = transfers.get_mut(&(peer, id)) {
+                    if let Err(err) = outgoing.start(offset) {
+                        transfers.remove(&(peer, id));
+                        cancel(transport, id, &err.to_string());
+                        return;
+                    }
+                    pump(&mut transfers, transport, id);
+                }
+            }
+            TransferEvent::Ack { id, offset } => {
+                let mut transfers = self.transfers.lock().unwrap();
+                if let Some(HostTransfer::Sending(outgoing)) = transfers.get_mut(&(peer, id)) {
+                    outgoing.acknowledge(offset);
+                    pump(&mut transfers, transport, id);
+                }
+            }
+            TransferEvent::Chunk { id, offset, data } => {
+                self.write_chunk(transport, id, offset, &data);
+            }
+            TransferEvent::Complete { id } => {
+                if let Some(HostTransfer::Sending(outgoing)) =
+                    self.transfers.lock().unwrap().remove(&(peer, id))
+                {
+                    info!(
+                        target = "host::transfer",
+                        transport_id = peer,
+                        name = %outgoing.offer().name,
+                        size = outgoing.offer().size,
+                        "file delivered to peer"
+                    );
+                }
+            }
+            TransferEvent::Cancel { id, reason } => {
+                if self.transfers.lock().unwrap().remove(&(peer, id)).is_some() {
+                    info!(
+                        target = "host::transfer",
+                        transport_id = peer,
+                        id,
+                        reason = %reason,
+                        "peer cancelled file transfer"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Drops a detached peer's transfers. Partial uploads stay on disk so the
+    /// peer can resume them.
+    fn leave(&self, peer: u64) {
+        self.transfers
+            .lock()
+            .unwrap()
+            .retain(|(transport_id, _), _| *transport_id != peer);
+    }
+}
+
+/// Sends chunks of an outgoing transfer until its window fills.
+fn pump(
+    transfers: &mut HashMap<(u64, u64), HostTransfer>,
+    transport: &Arc<dyn Transport>,
+    id: u64,
+) {
+    let key = (transport.id().0, id);
+    let Some(HostTransfer::Sending(outgoing)) = transfers.get_mut(&key) else {
+        return;
+    };
+    loop {
+        match outgoing.next_chunk() {
+            Ok(Some(chunk)) => send_event(transport, &chunk),
+            Ok(None) => break,
+            Err(err) => {
+                transfers.remove(&key);
+                cancel(transport, id, &err.to_string());
+                break;
+            }
+        }
+    }
+}
+
+fn cancel(transport: &Arc<dyn Transport>, id: u64, reason: &str) {
+    debug!(
+        target = "host::transfer",
+        transport_id = transport.id().0,
+        id,
+        reason,
+        "file transfer refused or failed"
+    );
+    send_event(
+        transport,
+        &TransferEvent::Cancel {
+            id,
+            reason: reason.to_string(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::terminal::transfer::sha256_file;
+    use crate::transport::extensions::recv_event;
+    use crate::transport::{TransportKind, TransportPair};
+    use bytes::Bytes;
+    use std::fs;
+
+    fn hub(policy: TransferPolicy, dir: &std::path::Path) -> Arc<TransferHub> {
+        Arc::new(TransferHub::new(
+            TransferSettings {
+                policy,
+                max_bytes: 1024,
+                dir: dir.to_path_buf(),
+            },
+            None,
+        ))
+    }
+
+    #[test_timeout::timeout]
+    fn receives_pushed_files_and_serves_requested_ones() {
+        let dir =
+            std::env::temp_dir().join(format!("beach-host-transfer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.txt");
+        fs::write(&source, b"hello host").unwrap();
+        let hub = hub(TransferPolicy::Allow, &dir);
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+        let client = pair.client;
+
+        hub.handle(
+            &server,
+            TransferEvent::Offer(TransferOffer {
+                id: 1,
+                name: "pushed.txt".into(),
+                size: 10,
+                sha256: sha256_file(&source).unwrap(),
+            }),
+        );
+        assert_eq!(
+            recv_event(client.as_ref()),
+            Some(TransferEvent::Accept { id: 1, offset: 0 })
+        );
+        hub.handle(
+            &server,
+            TransferEvent::Chunk {
+                id: 1,
+                offset: 0,
+                data: Bytes::from_static(b"hello host"),
+            },
+        );
+        assert_eq!(
+            recv_event(client.as_ref()),
+            Some(TransferEvent::Complete { id: 1 })
+        );
+        assert_eq!(fs::read(dir.join("pushed.txt")).unwrap(), b"hello host");
+
+        hub.handle(
+            &server,
+            TransferEvent::Request {
+                id: 2,
+                path: "source.txt".into(),
+            },
+        );
+        match recv_event(client.as_ref()) {
+            Some(TransferEvent::Offer(offer)) => assert_eq!(offer.size, 10),
+            other => panic!("expected offer, got {other:?}"),
+        }
+        hub.handle(&server, TransferEvent::Accept { id: 2, offset: 6 });
+        assert_eq!(
+            recv_event(client.as_ref()),
+            Some(TransferEvent::Chunk {
+                id: 2,
+                offset: 6,
+                data: Bytes::from_static(b"host"),
+            })
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test_timeout::timeout]
+    fn refuses_requests_outside_the_transfer_directory() {
+        let root =
+            std::env::temp_dir().join(format!("beach-host-transfer-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("shared");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(root.join("secret.txt"), b"secret").unwrap();
+        let hub = hub(TransferPolicy::Allow, &dir);
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+
+        for (id, path) in [
+            (5, "../secret.txt"),
+            (6, "/etc/passwd"),
+            (7, "nested/../../secret.txt"),
+        ] {
+            hub.handle(
+                &server,
+                TransferEvent::Request {
+                    id,
+                    path: path.into(),
+                },
+            );
+            assert_eq!(
+                recv_event(pair.client.as_ref()),
+                Some(TransferEvent::Cancel {
+                    id,
+                    reason: format!("'{path}' is outside the transfer directory"),
+                })
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("secret.txt"), dir.join("link.txt")).unwrap();
+            hub.handle(
+                &server,
+                TransferEvent::Request {
+                    id: 8,
+                    path: "link.txt".into(),
+                },
+            );
+            assert_eq!(
+                recv_event(pair.client.as_ref()),
+                Some(TransferEvent::Cancel {
+                    id: 8,
+                    reason: "'link.txt' is outside the transfer directory".into(),
+                })
+            );
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test_timeout::timeout]
+    fn refuses_transfers_without_consent() {
+        let dir =
+            std::env::temp_dir().join(format!("beach-host-transfer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("secret.txt"), b"secret").unwrap();
+        let pair = TransportPair::new(TransportKind::Ipc);
+        let server: Arc<dyn Transport> = Arc::from(pair.server);
+
+        // Prompting needs the host terminal; a host without one refuses.
+        hub(TransferPolicy::Prompt, &dir).handle(
+            &server,
+            TransferEvent::Request {
+                id: 3,
+                path: "secret.txt".into(),
+            },
+        );
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(TransferEvent::Cancel {
+                id: 3,
+                reason: "the host has no terminal to approve transfers on".into(),
+            })
+        );
+
+        hub(TransferPolicy::Deny, &dir).handle(
+            &server,
+            TransferEvent::Offer(TransferOffer {
+                id: 4,
+                name: "pushed.txt".into(),
+                size: 10,
+                sha256: "0".repeat(64),
+            }),
+        );
+        assert_eq!(
+            recv_event(pair.client.as_ref()),
+            Some(TransferEvent::Cancel {
+                id: 4,
+                reason: "file transfers are disabled on this host".into(),
+            })
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
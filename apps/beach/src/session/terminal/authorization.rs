@@ -9,6 +9,7 @@ use crossterm::terminal::{
 use std::collections::HashMap;
 use std::io::{self, Write};
 use std::sync::Arc;
+use std::thread;
 use std::time::Duration;
 use tokio::sync::Mutex as AsyncMutex;
 use tokio::time::sleep;
@@ -218,36 +219,21 @@ impl InteractiveAuthorizer {
 }
 
 fn run_authorization_prompt(metadata: &JoinAuthorizationMetadata) -> io::Result<bool> {
-    let raw_was_enabled = crossterm::terminal::is_raw_mode_enabled().unwrap_or(false);
-    if !raw_was_enabled {
-        enable_raw_mode()?;
-    }
-
-    let mut stdout = io::stdout();
-    let mut cleanup = PromptCleanup::new(raw_was_enabled);
-    execute!(stdout, EnterAlternateScreen, Clear(ClearType::All), Hide)?;
-    while event::poll(Duration::from_millis(0))? {
-        let _ = event::read()?;
-    }
-    cleanup.alt_screen_active = true;
-    write!(stdout, "\r==============================\r\n")?;
-    write!(stdout, "\r  Incoming beach client join\r\n")?;
-    write!(stdout, "\r==============================\r\n\r\n")?;
-    write!(stdout, "\rtransport : {:?}\r\n", metadata.transport_kind)?;
+    let mut details = vec![format!("transport : {:?}", metadata.transport_kind)];
     if let Some(desc) = &metadata.description {
-        write!(stdout, "\rcontext   : {desc}\r\n")?;
+        details.push(format!("context   : {desc}"));
     }
     if let Some(label) = &metadata.label {
-        write!(stdout, "\rlabel     : {label}\r\n")?;
+        details.push(format!("label     : {label}"));
     }
     if let Some(peer) = &metadata.peer_id {
-        write!(stdout, "\rpeer id   : {peer}\r\n")?;
+        details.push(format!("peer id   : {peer}"));
     }
     if let Some(handshake) = &metadata.handshake_id {
-        write!(stdout, "\rhandshake : {handshake}\r\n")?;
+        details.push(format!("handshake : {handshake}"));
     }
     if let Some(remote) = &metadata.remote_addr {
-        write!(stdout, "\rremote    : {remote}\r\n")?;
+        details.push(format!("remote    : {remote}"));
     }
     if !metadata.metadata.is_empty() {
         let mut extra: Vec<_> = metadata
@@ -257,8 +243,59 @@ fn run_authorization_prompt(metadata: &JoinAuthorizationMetadata) -> io::Result<
             .collect();
         extra.sort_by(|a, b| a.0.cmp(b.0));
         for (key, value) in extra {
-            write!(stdout, "\r{key}: {value}\r\n")?;
+            details.push(format!("{key}: {value}"));
+        }
+    }
+    run_prompt("Incoming beach client join", &details)
+}
+
+/// Asks the host a yes/no question on its terminal the way join approval
+/// does: PTY input is paused while the prompt is up and keys typed meanwhile
+/// are discarded. A prompt that fails counts as a no.
+pub fn confirm_on_host(gate: &HostInputGate, title: &str, details: &[String]) -> bool {
+    gate.pause();
+    thread::sleep(Duration::from_millis(50));
+    let decision = match run_prompt(title, details) {
+        Ok(allow) => allow,
+        Err(err) => {
+            warn!(
+                target = "host::auth",
+                error = %err,
+                title,
+                "host prompt failed; denying"
+            );
+            false
         }
+    };
+    let dropped = gate.resume_and_discard();
+    if dropped > 0 {
+        debug!(
+            target = "host::auth",
+            dropped_bytes = dropped,
+            "discarded buffered stdin bytes after prompt"
+        );
+    }
+    decision
+}
+
+fn run_prompt(title: &str, details: &[String]) -> io::Result<bool> {
+    let raw_was_enabled = crossterm::terminal::is_raw_mode_enabled().unwrap_or(false);
+    if !raw_was_enabled {
+        enable_raw_mode()?;
+    }
+
+    let mut stdout = io::stdout();
+    let mut cleanup = PromptCleanup::new(raw_was_enabled);
+    execute!(stdout, EnterAlternateScreen, Clear(ClearType::All), Hide)?;
+    while event::poll(Duration::from_millis(0))? {
+        let _ = event::read()?;
+    }
+    cleanup.alt_screen_active = true;
+    write!(stdout, "\r==============================\r\n")?;
+    write!(stdout, "\r  {title}\r\n")?;
+    write!(stdout, "\r==============================\r\n\r\n")?;
+    for line in details {
+        write!(stdout, "\r{line}\r\n")?;
     }
     write!(stdout, "\r\n")?;
     write!(
@@ -0,0 +1,106 @@
+//! Chunking shared by both ends of a `beach.clipboard` exchange.
+
+use crate::protocol::clipboard::{CLIPBOARD_CHUNK_BYTES, ClipboardEvent};
+use bytes::Bytes;
+
+/// Largest clipboard write either side sends or accepts.
+pub const MAX_CLIPBOARD_BYTES: usize = 1024 * 1024;
+
+/// Splits `text` into the chunks of write `id`, or returns `None` when it is
+/// over [`MAX_CLIPBOARD_BYTES`].
+pub fn clipboard_chunks(id: u64, text: &str) -> Option<Vec<ClipboardEvent>> {
+    if text.len() > MAX_CLIPBOARD_BYTES {
+        return None;
+    }
+    let bytes = Bytes::copy_from_slice(text.as_bytes());
+    let count = bytes.len().div_ceil(CLIPBOARD_CHUNK_BYTES).max(1);
+    Some(
+        (0..count)
+            .map(|index| {
+                let start = index * CLIPBOARD_CHUNK_BYTES;
+                let end = (start + CLIPBOARD_CHUNK_BYTES).min(bytes.len());
+                ClipboardEvent::Chunk {
+                    id,
+                    last: index + 1 == count,
+                    data: bytes.slice(start..end),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Reassembles clipboard writes from their chunks. A chunk of a new write
+/// abandons any write still in progress.
+#[derive(Debug, Default)]
+pub struct ClipboardAssembler {
+    id: Option<u64>,
+    buf: Vec<u8>,
+    oversized: bool,
+}
+
+impl ClipboardAssembler {
+    /// Adds a chunk; returns the text once the last chunk of a valid write
+    /// arrives.
+    pub fn push(&mut self, id: u64, last: bool, data: &[u8]) -> Option<String> {
+        if self.id != Some(id) {
+            self.id = Some(id);
+            self.buf.clear();
+            self.oversized = false;
+        }
+        if self.buf.len() + data.len() > MAX_CLIPBOARD_BYTES {
+            self.oversized = true;
+            self.buf.clear();
+        }
+        if !self.oversized {
+            self.buf.extend_from_slice(data);
+        }
+        if !last {
+            return None;
+        }
+        self.id = None;
+        let buf = std::mem::take(&mut self.buf);
+        if std::mem::take(&mut self.oversized) {
+            return None;
+        }
+        String::from_utf8(buf).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reassemble(assembler: &mut ClipboardAssembler, events: &[ClipboardEvent]) -> Option<String> {
+        let mut result = None;
+        for event in events {
+            let ClipboardEvent::Chunk { id, last, data } = event else {
+                panic!("unexpected event {event:?}");
+            };
+            result = assembler.push(*id, *last, data);
+        }
+        result
+    }
+
+    #[test_timeout::timeout]
+    fn chunks_reassemble_and_oversized_writes_are_dropped() {
+        let text = "ü".repeat(CLIPBOARD_CHUNK_BYTES);
+        let chunks = clipboard_chunks(1, &text).unwrap();
+        assert_eq!(chunks.len(), 2);
+        let mut assembler = ClipboardAssembler::default();
+        assert_eq!(reassemble(&mut assembler, &chunks), Some(text));
+
+        let empty = clipboard_chunks(2, "").unwrap();
+        assert_eq!(reassemble(&mut assembler, &empty), Some(String::new()));
+
+        assert!(clipboard_chunks(3, &"x".repeat(MAX_CLIPBOARD_BYTES + 1)).is_none());
+        let flood = vec![0x61; CLIPBOARD_CHUNK_BYTES];
+        for _ in 0..MAX_CLIPBOARD_BYTES / CLIPBOARD_CHUNK_BYTES {
+            assert_eq!(assembler.push(4, false, &flood), None);
+        }
+        assert_eq!(assembler.push(4, true, b"!"), None);
+
+        // A new write abandons a half-received one.
+        assert_eq!(assembler.push(5, false, b"stale"), None);
+        assert_eq!(assembler.push(6, true, b"fresh"), Some("fresh".into()));
+    }
+}
@@ -1,2 +1,5 @@
 pub mod authorization;
+pub mod clipboard;
+pub mod portfwd;
+pub mod transfer;
 pub mod tty;
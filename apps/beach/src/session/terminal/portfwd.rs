@@ -0,0 +1,428 @@
+//! Socket plumbing shared by both ends of a `beach.portfwd` exchange. Every
+//! attached stream gets a reader thread that turns socket data into frames,
+//! bounded by the credit window, and a writer thread that drains frames from
+//! the peer into the socket and pays the credit back.
+
+use crate::protocol::portfwd::{PORTFWD_CHUNK_BYTES, PortForwardEvent};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::debug;
+
+/// Unacknowledged bytes a stream may have in flight per direction.
+pub const PORTFWD_WINDOW_BYTES: u64 = 256 * 1024;
+const DIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends a frame to the peer on the other end of the session.
+pub type PortForwardSink = Arc<dyn Fn(PortForwardEvent) + Send + Sync>;
+
+enum Outbound {
+    Data(Bytes),
+    Eof,
+}
+
+struct Stream {
+    socket: TcpStream,
+    writer: Mutex<Option<Sender<Outbound>>>,
+    /// Bytes sent to the peer and not yet credited back.
+    in_flight: Mutex<u64>,
+    credited: Condvar,
+    /// Bytes received from the peer and not yet written to the socket.
+    queued: AtomicU64,
+    reset: AtomicBool,
+    /// Reader and writer threads that finished cleanly.
+    halves_done: AtomicU8,
+}
+
+impl Stream {
+    fn abort(&self) {
+        self.reset.store(true, Ordering::SeqCst);
+        self.writer.lock().unwrap().take();
+        self.credited.notify_all();
+        let _ = self.socket.shutdown(Shutdown::Both);
+    }
+}
+
+/// Streams bound to TCP sockets, keyed by stream id.
+pub struct ForwardStreams {
+    sink: PortForwardSink,
+    streams: Mutex<HashMap<u64, Arc<Stream>>>,
+}
+
+impl ForwardStreams {
+    pub fn new(sink: PortForwardSink) -> Self {
+        Self {
+            sink,
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn emit(&self, event: PortForwardEvent) {
+        (self.sink)(event);
+    }
+
+    pub fn len(&self) -> usize {
+        self.streams.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Starts moving data between `socket` and the peer.
+    pub fn attach(self: &Arc<Self>, id: u64, socket: TcpStream) -> io::Result<()> {
+        self.attach_inner(id, socket, false)
+    }
+
+    /// Attaches a socket this side dialed and tells the peer the stream is
+    /// open. The announcement goes out before any data read from the socket.
+    pub fn connected(self: &Arc<Self>, id: u64, socket: TcpStream) -> io::Result<()> {
+        self.attach_inner(id, socket, true)
+    }
+
+    fn attach_inner(
+        self: &Arc<Self>,
+        id: u64,
+        socket: TcpStream,
+        announce: bool,
+    ) -> io::Result<()> {
+        let _ = socket.set_nodelay(true);
+        let read_half = socket.try_clone()?;
+        let write_half = socket.try_clone()?;
+        let (tx, rx) = mpsc::channel();
+        let stream = Arc::new(Stream {
+            socket,
+            writer: Mutex::new(Some(tx)),
+            in_flight: Mutex::new(0),
+            credited: Condvar::new(),
+            queued: AtomicU64::new(0),
+            reset: AtomicBool::new(false),
+            halves_done: AtomicU8::new(0),
+        });
+        if let Some(previous) = self.streams.lock().unwrap().insert(id, stream.clone()) {
+            previous.abort();
+        }
+        if announce {
+            self.emit(PortForwardEvent::Opened { stream: id });
+        }
+
+        let streams = Arc::clone(self);
+        let reader = stream.clone();
+        thread::spawn(move || streams.pump_socket(id, reader, read_half));
+        let streams = Arc::clone(self);
+        thread::spawn(move || streams.drain_to_socket(id, stream, write_half, rx));
+        Ok(())
+    }
+
+    /// Queues data from the peer for the socket.
+    pub fn data(&self, id: u64, data: Bytes) {
+        let Some(stream) = self.get(id) else {
+            return;
+        };
+        let queued = stream.queued.fetch_add(data.len() as u64, Ordering::SeqCst);
+        if queued + data.len() as u64 > PORTFWD_WINDOW_BYTES {
+            self.fail(id, "peer overran the flow-control window");
+            return;
+        }
+        if let Some(writer) = stream.writer.lock().unwrap().as_ref() {
+            let _ = writer.send(Outbound::Data(data));
+        }
+    }
+
+    pub fn credit(&self, id: u64, bytes: u64) {
+        let Some(stream) = self.get(id) else {
+            return;
+        };
+        let mut in_flight = stream.in_flight.lock().unwrap();
+        *in_flight = in_flight.saturating_sub(bytes);
+        stream.credited.notify_all();
+    }
+
+    /// The peer finished sending: shut the socket's write half once every
+    /// queued byte is written.
+    pub fn close(&self, id: u64) {
+        let Some(stream) = self.get(id) else {
+            return;
+        };
+        if let Some(writer) = stream.writer.lock().unwrap().take() {
+            let _ = writer.send(Outbound::Eof);
+        }
+    }
+
+    /// The peer dropped the stream.
+    pub fn reset(&self, id: u64) {
+        let removed = self.streams.lock().unwrap().remove(&id);
+        if let Some(stream) = removed {
+            stream.abort();
+        }
+    }
+
+    /// Drops the stream locally and tells the peer why.
+    pub fn fail(&self, id: u64, reason: &str) {
+        let removed = self.streams.lock().unwrap().remove(&id);
+        if let Some(stream) = removed {
+            stream.abort();
+            self.emit(PortForwardEvent::Reset {
+                stream: id,
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    /// Drops every stream without telling the peer; used once it is gone.
+    pub fn close_all(&self) {
+        for (_, stream) in self.streams.lock().unwrap().drain() {
+            stream.abort();
+        }
+    }
+
+    fn get(&self, id: u64) -> Option<Arc<Stream>> {
+        self.streams.lock().unwrap().get(&id).cloned()
+    }
+
+    fn pump_socket(&self, id: u64, stream: Arc<Stream>, mut socket: TcpStream) {
+        let mut buf = vec![0; PORTFWD_CHUNK_BYTES];
+        loop {
+            let read = match socket.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    if !stream.reset.load(Ordering::SeqCst) {
+                        self.fail(id, &err.to_string());
+                    }
+                    return;
+                }
+            };
+            {
+                let mut in_flight = stream.in_flight.lock().unwrap();
+                while *in_flight >= PORTFWD_WINDOW_BYTES && !stream.reset.load(Ordering::SeqCst) {
+                    in_flight = stream.credited.wait(in_flight).unwrap();
+                }
+                if stream.reset.load(Ordering::SeqCst) {
+                    return;
+                }
+                *in_flight += read as u64;
+            }
+            self.emit(PortForwardEvent::Data {
+                stream: id,
+                data: Bytes::copy_from_slice(&buf[..read]),
+            });
+        }
+        if !stream.reset.load(Ordering::SeqCst) {
+            self.emit(PortForwardEvent::Close { stream: id });
+            self.half_done(id, &stream);
+        }
+    }
+
+    fn drain_to_socket(
+        &self,
+        id: u64,
+        stream: Arc<Stream>,
+        mut socket: TcpStream,
+        rx: mpsc::Receiver<Outbound>,
+    ) {
+        for outbound in rx {
+            match outbound {
+                Outbound::Data(data) => {
+                    if let Err(err) = socket.write_all(&data) {
+                        if !stream.reset.load(Ordering::SeqCst) {
+                            self.fail(id, &err.to_string());
+                        }
+                        return;
+                    }
+                    stream.queued.fetch_sub(data.len() as u64, Ordering::SeqCst);
+                    self.emit(PortForwardEvent::Credit {
+                        stream: id,
+                        bytes: data.len() as u64,
+                    });
+                }
+                Outbound::Eof => {
+                    let _ = socket.shutdown(Shutdown::Write);
+                    self.half_done(id, &stream);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Forgets the stream once both directions reached end of file.
+    fn half_done(&self, id: u64, stream: &Arc<Stream>) {
+        if stream.halves_done.fetch_add(1, Ordering::SeqCst) + 1 < 2 {
+            return;
+        }
+        let mut streams = self.streams.lock().unwrap();
+        if streams
+            .get(&id)
+            .is_some_and(|current| Arc::ptr_eq(current, stream))
+        {
+            streams.remove(&id);
+            debug!(target = "portfwd", stream = id, "stream finished");
+        }
+    }
+}
+
+/// Splits `HOST:PORT`, accepting a bracketed IPv6 host such as `[::1]:3000`.
+pub fn parse_host_port(value: &str) -> Result<(String, u16), String> {
+    let (host, port) = value
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected HOST:PORT, got '{value}'"))?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    if host.is_empty() {
+        return Err(format!("missing host in '{value}'"));
+    }
+    let port = port
+        .parse::<u16>()
+        .map_err(|_| format!("invalid port in '{value}'"))?;
+    Ok((host.to_string(), port))
+}
+
+/// Connects to `host:port`, trying each resolved address in turn.
+pub fn dial(host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut last_err = io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{host}:{port} did not resolve"),
+    );
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, DIAL_TIMEOUT) {
+            Ok(socket) => return Ok(socket),
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc::Receiver;
+
+    fn channel_sink() -> (PortForwardSink, Receiver<PortForwardEvent>) {
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let sink: PortForwardSink = Arc::new(move |event| {
+            let _ = tx.lock().unwrap().send(event);
+        });
+        (sink, rx)
+    }
+
+    fn socket_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        (client, server)
+    }
+
+    /// Relays frames between two stream tables until both sides went quiet.
+    fn relay(
+        left: &Arc<ForwardStreams>,
+        left_rx: &Receiver<PortForwardEvent>,
+        right: &Arc<ForwardStreams>,
+        right_rx: &Receiver<PortForwardEvent>,
+    ) -> Vec<PortForwardEvent> {
+        let mut seen = Vec::new();
+        let timeout = Duration::from_millis(200);
+        loop {
+            let mut moved = false;
+            for (rx, target) in [(left_rx, right), (right_rx, left)] {
+                while let Ok(event) = rx.recv_timeout(timeout) {
+                    moved = true;
+                    match event.clone() {
+                        PortForwardEvent::Data { stream, data } => target.data(stream, data),
+                        PortForwardEvent::Credit { stream, bytes } => target.credit(stream, bytes),
+                        PortForwardEvent::Close { stream } => target.close(stream),
+                        PortForwardEvent::Reset { stream, .. } => target.reset(stream),
+                        _ => {}
+                    }
+                    seen.push(event);
+                }
+            }
+            if !moved {
+                return seen;
+            }
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn parses_hosts_and_ports() {
+        assert_eq!(
+            parse_host_port("localhost:3000"),
+            Ok(("localhost".into(), 3000))
+        );
+        assert_eq!(parse_host_port("[::1]:22"), Ok(("::1".into(), 22)));
+        assert!(parse_host_port("3000").is_err());
+        assert!(parse_host_port(":3000").is_err());
+        assert!(parse_host_port("localhost:99999").is_err());
+    }
+
+    #[test_timeout::timeout]
+    fn streams_copy_both_directions_and_half_close() {
+        let (left_sink, left_rx) = channel_sink();
+        let (right_sink, right_rx) = channel_sink();
+        let left = Arc::new(ForwardStreams::new(left_sink));
+        let right = Arc::new(ForwardStreams::new(right_sink));
+        let (mut app, left_socket) = socket_pair();
+        let (right_socket, mut service) = socket_pair();
+        left.attach(1, left_socket).unwrap();
+        right.attach(1, right_socket).unwrap();
+
+        // More than a window, so the sender has to wait for credits.
+        let request: Vec<u8> = (0..600_000u32).map(|i| (i % 253) as u8).collect();
+        let expected = request.clone();
+        let writer = thread::spawn(move || {
+            app.write_all(&request).unwrap();
+            app.shutdown(Shutdown::Write).unwrap();
+            let mut reply = Vec::new();
+            app.read_to_end(&mut reply).unwrap();
+            reply
+        });
+        let reader = thread::spawn(move || {
+            let mut received = Vec::new();
+            service.read_to_end(&mut received).unwrap();
+            service.write_all(b"done").unwrap();
+            service.shutdown(Shutdown::Write).unwrap();
+            received
+        });
+        let seen = relay(&left, &left_rx, &right, &right_rx);
+
+        assert_eq!(reader.join().unwrap(), expected);
+        assert_eq!(writer.join().unwrap(), b"done");
+        assert!(
+            !seen
+                .iter()
+                .any(|event| matches!(event, PortForwardEvent::Reset { .. }))
+        );
+        assert!(left.is_empty());
+        assert!(right.is_empty());
+    }
+
+    #[test_timeout::timeout]
+    fn overrunning_the_window_resets_the_stream() {
+        let (sink, rx) = channel_sink();
+        let streams = Arc::new(ForwardStreams::new(sink));
+        let (_app, socket) = socket_pair();
+        streams.attach(3, socket).unwrap();
+        streams.data(3, Bytes::from(vec![0; PORTFWD_WINDOW_BYTES as usize + 1]));
+        let reset = rx
+            .iter()
+            .find(|event| matches!(event, PortForwardEvent::Reset { .. }));
+        assert_eq!(
+            reset,
+            Some(PortForwardEvent::Reset {
+                stream: 3,
+                reason: "peer overran the flow-control window".into(),
+            })
+        );
+        assert!(streams.is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+//! File handling shared by both ends of a `beach.transfer` exchange: the
+//! sending side reads and paces chunks, the receiving side appends them to a
+//! partial file that survives disconnects and checks the hash at the end.
+
+use crate::protocol::transfer::{TRANSFER_CHUNK_BYTES, TransferEvent, TransferOffer};
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Bytes a sender may have in flight beyond the receiver's last ack.
+pub const TRANSFER_WINDOW_BYTES: u64 = 1024 * 1024;
+/// Receivers ack at least this often.
+pub const TRANSFER_ACK_INTERVAL: u64 = 256 * 1024;
+/// Largest file either side accepts unless configured otherwise.
+pub const DEFAULT_TRANSFER_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+const PARTIAL_SUFFIX: &str = "beach-partial";
+
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("{0} is not a regular file")]
+    NotAFile(String),
+    #[error("invalid file name '{0}'")]
+    InvalidName(String),
+    #[error("'{0}' is outside the transfer directory")]
+    OutsideDir(String),
+    #[error("invalid sha-256 '{0}'")]
+    InvalidHash(String),
+    #[error("file is {size} bytes, over the {limit} byte limit")]
+    TooLarge { size: u64, limit: u64 },
+    #[error("expected data at offset {expected}, got {actual}")]
+    OutOfOrder { expected: u64, actual: u64 },
+    #[error("sha-256 mismatch; partial data discarded")]
+    HashMismatch,
+}
+
+/// Reads a local file and produces its chunks, never running more than
+/// [`TRANSFER_WINDOW_BYTES`] ahead of the receiver's acks.
+pub struct OutgoingFile {
+    offer: TransferOffer,
+    file: File,
+    sent: u64,
+    acked: u64,
+    started: bool,
+}
+
+impl OutgoingFile {
+    /// Opens `path` and hashes it so the offer can be sent.
+    pub fn open(id: u64, path: &Path, max_bytes: u64) -> Result<Self, TransferError> {
+        let metadata = fs::metadata(path)?;
+        if !metadata.is_file() {
+            return Err(TransferError::NotAFile(path.display().to_string()));
+        }
+        let size = metadata.len();
+        if size > max_bytes {
+            return Err(TransferError::TooLarge {
+                size,
+                limit: max_bytes,
+            });
+        }
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| TransferError::InvalidName(path.display().to_string()))?;
+        let name = sanitize_name(name)?;
+        let sha256 = sha256_file(path)?;
+        Ok(Self {
+            offer: TransferOffer {
+                id,
+                name,
+                size,
+                sha256,
+            },
+            file: File::open(path)?,
+            sent: 0,
+            acked: 0,
+            started: false,
+        })
+    }
+
+    pub fn offer(&self) -> &TransferOffer {
+        &self.offer
+    }
+
+    /// Begins sending from the offset the receiver already holds.
+    pub fn start(&mut self, offset: u64) -> io::Result<()> {
+        let offset = offset.min(self.offer.size);
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.sent = offset;
+        self.acked = offset;
+        self.started = true;
+        Ok(())
+    }
+
+    pub fn acknowledge(&mut self, offset: u64) {
+        self.acked = self.acked.max(offset.min(self.sent));
+    }
+
+    pub fn acked(&self) -> u64 {
+        self.acked
+    }
+
+    /// Returns the next chunk, or `None` until the transfer is accepted, while
+    /// the window is full, and once every byte has been sent.
+    pub fn next_chunk(&mut self) -> io::Result<Option<TransferEvent>> {
+        if !self.started
+            || self.sent >= self.offer.size
+            || self.sent - self.acked >= TRANSFER_WINDOW_BYTES
+        {
+            return Ok(None);
+        }
+        let len = (self.offer.size - self.sent).min(TRANSFER_CHUNK_BYTES as u64) as usize;
+        let mut data = vec![0; len];
+        self.file.read_exact(&mut data)?;
+        let chunk = TransferEvent::Chunk {
+            id: self.offer.id,
+            offset: self.sent,
+            data: Bytes::from(data),
+        };
+        self.sent += len as u64;
+        Ok(Some(chunk))
+    }
+}
+
+/// Collects an offered file in `dir`. Data goes to a hidden partial file keyed
+/// by the offer's hash, so a later offer of the same content resumes it.
+pub struct IncomingFile {
+    offer: TransferOffer,
+    name: String,
+    dir: PathBuf,
+    partial: PathBuf,
+    file: File,
+    written: u64,
+    acked: u64,
+}
+
+impl IncomingFile {
+    pub fn create(dir: &Path, offer: TransferOffer, max_bytes: u64) -> Result<Self, TransferError> {
+        if offer.size > max_bytes {
+            return Err(TransferError::TooLarge {
+                size: offer.size,
+                limit: max_bytes,
+            });
+        }
+        let name = sanitize_name(&offer.name)?;
+        if offer.sha256.len() != 64 || !offer.sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(TransferError::InvalidHash(offer.sha256));
+        }
+        let partial = dir.join(format!(
+            ".{name}.{}.{PARTIAL_SUFFIX}",
+            &offer.sha256[..16].to_ascii_lowercase()
+        ));
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(false)
+            .open(&partial)?;
+        let mut written = file.metadata()?.len();
+        if written > offer.size {
+            file.set_len(0)?;
+            written = 0;
+        }
+        file.seek(SeekFrom::Start(written))?;
+        Ok(Self {
+            offer,
+            name,
+            dir: dir.to_path_buf(),
+            partial,
+            file,
+            written,
+            acked: written,
+        })
+    }
+
+    pub fn offer(&self) -> &TransferOffer {
+        &self.offer
+    }
+
+    /// Bytes already on disk; the offset to accept from.
+    pub fn offset(&self) -> u64 {
+        self.written
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.written == self.offer.size
+    }
+
+    /// Appends a chunk and returns the offset to ack when an ack is due.
+    pub fn write_chunk(&mut self, offset: u64, data: &[u8]) -> Result<Option<u64>, TransferError> {
+        if offset != self.written {
+            return Err(TransferError::OutOfOrder {
+                expected: self.written,
+                actual: offset,
+            });
+        }
+        let end = self.written + data.len() as u64;
+        if end > self.offer.size {
+            return Err(TransferError::TooLarge {
+                size: end,
+                limit: self.offer.size,
+            });
+        }
+        self.file.write_all(data)?;
+        self.written = end;
+        if self.written - self.acked >= TRANSFER_ACK_INTERVAL {
+            self.acked = self.written;
+            return Ok(Some(self.written));
+        }
+        Ok(None)
+    }
+
+    /// Checks the hash and moves the file into place without overwriting an
+    /// existing file. A partial file whose hash does not match is deleted.
+    pub fn finish(self) -> Result<PathBuf, TransferError> {
+        self.file.sync_all()?;
+        drop(self.file);
+        if !sha256_file(&self.partial)?.eq_ignore_ascii_case(&self.offer.sha256) {
+            let _ = fs::remove_file(&self.partial);
+            return Err(TransferError::HashMismatch);
+        }
+        let destination = unique_destination(&self.dir, &self.name);
+        fs::rename(&self.partial, &destination)?;
+        Ok(destination)
+    }
+}
+
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Accepts a bare file name only; offers cannot place files outside the
+/// receiver's directory.
+fn sanitize_name(name: &str) -> Result<String, TransferError> {
+    let invalid =
+        name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']);
+    if invalid {
+        return Err(TransferError::InvalidName(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+fn unique_destination(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    (1..)
+        .map(|n| dir.join(format!("{name}.{n}")))
+        .find(|path| !path.exists())
+        .expect("unbounded suffixes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("beach-transfer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn relay(outgoing: &mut OutgoingFile, incoming: &mut IncomingFile, max_chunks: usize) {
+        for _ in 0..max_chunks {
+            match outgoing.next_chunk().unwrap() {
+                Some(TransferEvent::Chunk { offset, data, .. }) => {
+                    if let Some(ack) = incoming.write_chunk(offset, &data).unwrap() {
+                        outgoing.acknowledge(ack);
+                    }
+                }
+                Some(other) => panic!("unexpected event {other:?}"),
+                None => break,
+            }
+        }
+    }
+
+    #[test_timeout::timeout]
+    fn resumes_partial_files_and_verifies_the_hash() {
+        let source_dir = scratch_dir();
+        let target_dir = scratch_dir();
+        let source = source_dir.join("build.log");
+        let contents: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&source, &contents).unwrap();
+        fs::write(target_dir.join("build.log"), b"older").unwrap();
+
+        let mut outgoing = OutgoingFile::open(7, &source, u64::MAX).unwrap();
+        let mut incoming =
+            IncomingFile::create(&target_dir, outgoing.offer().clone(), u64::MAX).unwrap();
+        outgoing.start(incoming.offset()).unwrap();
+        relay(&mut outgoing, &mut incoming, 2);
+        drop(incoming);
+
+        // Reconnect: the partial file is picked up and only the rest is sent.
+        let mut outgoing = OutgoingFile::open(8, &source, u64::MAX).unwrap();
+        let mut incoming =
+            IncomingFile::create(&target_dir, outgoing.offer().clone(), u64::MAX).unwrap();
+        assert_eq!(incoming.offset(), 2 * TRANSFER_CHUNK_BYTES as u64);
+        outgoing.start(incoming.offset()).unwrap();
+        relay(&mut outgoing, &mut incoming, usize::MAX);
+        assert!(incoming.is_complete());
+
+        let destination = incoming.finish().unwrap();
+        assert_eq!(destination, target_dir.join("build.log.1"));
+        assert_eq!(fs::read(&destination).unwrap(), contents);
+
+        fs::remove_dir_all(source_dir).unwrap();
+        fs::remove_dir_all(target_dir).unwrap();
+    }
+
+    #[test_timeout::timeout]
+    fn rejects_oversized_unsafe_and_corrupt_offers() {
+        let dir = scratch_dir();
+        let offer = TransferOffer {
+            id: 1,
+            name: "notes.txt".into(),
+            size: 5,
+            sha256: "0".repeat(64),
+        };
+        assert!(matches!(
+            IncomingFile::create(&dir, offer.clone(), 4),
+            Err(TransferError::TooLarge { size: 5, limit: 4 })
+        ));
+        let escaping = TransferOffer {
+            name: "../notes.txt".into(),
+            ..offer.clone()
+        };
+        assert!(matches!(
+            IncomingFile::create(&dir, escaping, u64::MAX),
+            Err(TransferError::InvalidName(_))
+        ));
+
+        let mut incoming = IncomingFile::create(&dir, offer, u64::MAX).unwrap();
+        assert!(matches!(
+            incoming.write_chunk(1, b"x"),
+            Err(TransferError::OutOfOrder {
+                expected: 0,
+                actual: 1
+            })
+        ));
+        incoming.write_chunk(0, b"hello").unwrap();
+        assert!(matches!(
+            incoming.finish(),
+            Err(TransferError::HashMismatch)
+        ));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
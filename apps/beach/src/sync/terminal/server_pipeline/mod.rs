@@ -9,9 +9,9 @@ use crate::cache::Seq;
 use crate::cache::terminal::{PackedCell, StyleId, TerminalGrid, unpack_cell};
 use crate::model::terminal::diff::{CacheUpdate, HistoryTrim, RowSnapshot, StyleDefinition};
 use crate::protocol::{
-    self, ClientFrame as WireClientFrame, CursorFrame, FEATURE_CURSOR_SYNC, HostFrame,
-    Lane as WireLane, LaneBudgetFrame as WireLaneBudget, SyncConfigFrame as WireSyncConfig,
-    Update as WireUpdate,
+    self, ClientFrame as WireClientFrame, CursorFrame, FEATURE_CURSOR_SYNC, FEATURE_HISTORY_SEARCH,
+    FEATURE_TERMINAL_MODES, HostFrame, Lane as WireLane, LaneBudgetFrame as WireLaneBudget,
+    SyncConfigFrame as WireSyncConfig, TerminalModesFrame, Update as WireUpdate,
 };
 use crate::sync::terminal::{TerminalDeltaStream, TerminalSync};
 use crate::sync::{LaneBudget, PriorityLane, ServerSynchronizer, SubscriptionId, SyncConfig};
@@ -24,6 +24,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tokio::time::interval;
 use tracing::{Level, debug, info, trace, warn};
@@ -299,6 +300,8 @@ pub(crate) fn host_frame_label(frame: &HostFrame) -> &'static str {
         HostFrame::Cursor { .. } => "cursor",
         HostFrame::InputAck { .. } => "input_ack",
         HostFrame::Extension { .. } => "extension",
+        HostFrame::SearchResults { .. } => "search_results",
+        HostFrame::Modes { .. } => "modes",
         HostFrame::Shutdown => "shutdown",
     }
 }
@@ -310,6 +313,7 @@ pub(crate) fn client_frame_label(frame: &WireClientFrame) -> &'static str {
         WireClientFrame::Resize { .. } => "resize",
         WireClientFrame::RequestBackfill { .. } => "request_backfill",
         WireClientFrame::ViewportCommand { .. } => "viewport_command",
+        WireClientFrame::Search { .. } => "search",
         WireClientFrame::Extension { .. } => "extension",
         WireClientFrame::Unknown => "unknown",
     }
@@ -826,6 +830,7 @@ pub(crate) fn spawn_update_forwarder(
     forwarder_tx: Option<UnboundedSender<ForwarderCommand>>,
     shared_registry: Arc<Mutex<Vec<Arc<SharedTransport>>>>,
     cursor_sync: bool,
+    mut modes: watch::Receiver<TerminalModesFrame>,
 ) -> JoinHandle<()> {
     // TODO(codex): phase 9 should reevaluate the shared forwarder surface once
     // viewport utilities and spawn config helpers move alongside it.
@@ -951,6 +956,7 @@ pub(crate) fn spawn_update_forwarder(
                 &sync_config,
                 &mut sink.cache,
                 cursor_sync,
+                *modes.borrow(),
             ) {
                 Ok((sync, seq)) => {
                     sink.synchronizer = sync;
@@ -1009,6 +1015,7 @@ pub(crate) fn spawn_update_forwarder(
             sync_config: &SyncConfig,
             stale_transports: &mut Vec<TransportId>,
             cursor_sync: bool,
+            modes: TerminalModesFrame,
         ) {
             sink.handshake_attempts = sink.handshake_attempts.saturating_add(1);
             debug!(
@@ -1026,6 +1033,7 @@ pub(crate) fn spawn_update_forwarder(
                 sync_config,
                 &mut sink.cache,
                 cursor_sync,
+                modes,
             ) {
                 Ok((sync, seq)) => {
                     sink.synchronizer = sync;
@@ -1078,6 +1086,7 @@ pub(crate) fn spawn_update_forwarder(
         }
 
         let mut handshake_timer = interval(Duration::from_millis(200));
+        let mut modes_open = true;
 
         loop {
             tokio::select! {
@@ -1093,6 +1102,7 @@ pub(crate) fn spawn_update_forwarder(
                             &sync_config,
                             &mut stale_transports,
                             cursor_sync,
+                            *modes.borrow(),
                         );
                     }
                 }
@@ -1191,6 +1201,27 @@ pub(crate) fn spawn_update_forwarder(
                         None => break,
                     }
                 }
+                changed = modes.changed(), if modes_open => {
+                    if changed.is_ok() {
+                        let current = *modes.borrow_and_update();
+                        for sink in sinks.iter_mut().filter(|s| s.active && s.handshake_complete) {
+                            // Delta sends notice broken transports; the handshake
+                            // that follows replays the current modes.
+                            if let Err(err) = send_host_frame(&sink.transport, HostFrame::Modes { modes: current }) {
+                                debug!(
+                                    target = "sync::forwarder",
+                                    transport_id = sink.transport.id().0,
+                                    transport = ?sink.transport.kind(),
+                                    error = %err,
+                                    "terminal modes send failed"
+                                );
+                            }
+                        }
+                    } else {
+                        // The runtime exited; clients already have its last modes.
+                        modes_open = false;
+                    }
+                }
                 maybe_forwarder = command_rx.recv() => {
                     if let Some(command) = maybe_forwarder {
                         match command {
@@ -1225,6 +1256,7 @@ pub(crate) fn spawn_update_forwarder(
                                     &sync_config,
                                     &mut sink.cache,
                                     cursor_sync,
+                                    *modes.borrow(),
                                 ) {
                                     Ok((sync, seq)) => {
                                         sink.synchronizer = sync;
@@ -1464,10 +1496,14 @@ pub(crate) fn initialize_transport_snapshot(
     sync_config: &SyncConfig,
     cache: &mut TransmitterCache,
     cursor_sync: bool,
+    modes: TerminalModesFrame,
 ) -> Result<(ServerSynchronizer<TerminalSync, CacheUpdate>, Seq), TransportError> {
     let mut synchronizer = ServerSynchronizer::new(terminal_sync.clone(), sync_config.clone());
     let hello = synchronizer.hello(subscription);
-    let features = if cursor_sync { FEATURE_CURSOR_SYNC } else { 0 };
+    let mut features = FEATURE_HISTORY_SEARCH | FEATURE_TERMINAL_MODES;
+    if cursor_sync {
+        features |= FEATURE_CURSOR_SYNC;
+    }
     debug!(
         target = "sync::handshake",
         transport_id = transport.id().0,
@@ -1509,6 +1545,7 @@ pub(crate) fn initialize_transport_snapshot(
             viewport_rows: Some(viewport_rows as u32),
         },
     )?;
+    send_host_frame(transport, HostFrame::Modes { modes })?;
     transmit_initial_snapshots(transport, &mut synchronizer, cache, subscription)?;
     debug!(
         target = "sync::handshake",
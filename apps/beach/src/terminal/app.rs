@@ -1,10 +1,12 @@
 use crate::auth;
-use crate::client::terminal::{debug, join};
+use crate::client::terminal::keymap::Keymap;
+use crate::client::terminal::{debug, join, transfer};
 use crate::server::terminal::host;
 use crate::terminal::action as action_cli;
 use crate::terminal::auth as auth_cli;
 use crate::terminal::cli::{self, AuthCommand, Command, HostArgs};
 use crate::terminal::error::CliError;
+use crate::terminal::mcp_hub;
 use crate::transport::ssh;
 use std::env;
 use tracing::info;
@@ -30,6 +32,17 @@ pub async fn run(cli: cli::Cli) -> Result<(), CliError> {
             auth_cli::run(AuthCommand::Login(args), cli.profile.clone()).await
         }
         Some(Command::Action(args)) => action_cli::run(cli.profile.as_deref(), args).await,
+        Some(Command::Send(args)) => transfer::send(&session_base, args, cli.profile.clone()).await,
+        Some(Command::Receive(args)) => {
+            transfer::receive(&session_base, args, cli.profile.clone()).await
+        }
+        Some(Command::McpHub(args)) => {
+            mcp_hub::run(&session_base, args, cli.profile.as_deref()).await
+        }
+        Some(Command::Keys) => {
+            print!("{}", Keymap::load().describe());
+            Ok(())
+        }
         None => host::run(&session_base, HostArgs::default()).await,
     }
 }
@@ -1,6 +1,12 @@
 use clap::{Args, Parser, Subcommand, ValueEnum, builder::BoolishValueParser};
 use std::path::PathBuf;
 
+use crate::client::color::ColorLevel;
+use crate::client::terminal::portfwd::ForwardSpec;
+use crate::protocol::clipboard::ClipboardMode;
+use crate::server::terminal::portfwd::ForwardRule;
+use crate::server::terminal::resize::ResizePolicy;
+use crate::server::terminal::transfer::TransferPolicy;
 use crate::telemetry::logging::{LogConfig, LogLevel};
 
 #[derive(Parser, Debug)]
@@ -117,6 +123,15 @@ pub enum Command {
     Login(AuthLoginArgs),
     /// Queue controller actions for a session
     Action(ActionArgs),
+    /// Send a local file to a session's host
+    Send(SendArgs),
+    /// Fetch a file from a session's host
+    Receive(ReceiveArgs),
+    /// Join several sessions and serve their MCP endpoints as one server
+    #[command(name = "mcp-hub")]
+    McpHub(McpHubArgs),
+    /// Print the active TUI key bindings
+    Keys,
 }
 
 #[derive(Subcommand, Debug)]
@@ -315,6 +330,69 @@ pub struct HostArgs {
     )]
     pub legacy_allow_all_clients: bool,
 
+    #[arg(
+        long = "resize-policy",
+        value_enum,
+        default_value_t = ResizePolicy::LatestActive,
+        help = "How to size the PTY when several windows are attached (like tmux window-size)"
+    )]
+    pub resize_policy: ResizePolicy,
+
+    #[arg(
+        long = "colors",
+        value_enum,
+        env = "BEACH_COLORS",
+        help = "Colors the local preview terminal supports: truecolor, 256, 16 or none (detected by default)"
+    )]
+    pub colors: Option<ColorLevel>,
+
+    #[arg(
+        long = "file-transfers",
+        value_enum,
+        default_value_t = TransferPolicy::Prompt,
+        help = "Whether peers may send files to and fetch files from this host"
+    )]
+    pub file_transfers: TransferPolicy,
+
+    #[arg(
+        long = "transfer-dir",
+        value_name = "DIR",
+        help = "Directory received files are saved in and fetched paths resolve against (defaults to the working directory)"
+    )]
+    pub transfer_dir: Option<PathBuf>,
+
+    #[arg(
+        long = "transfer-max-size",
+        value_name = "SIZE",
+        value_parser = parse_byte_size,
+        help = "Largest file a single transfer may carry, e.g. 500M or 2G (defaults to 1G)"
+    )]
+    pub transfer_max_size: Option<u64>,
+
+    #[arg(
+        long = "allow-forward",
+        value_name = "[HOST:]PORT",
+        action = clap::ArgAction::Append,
+        help = "Destination peers may reach with `beach join --forward` (repeatable; HOST defaults to localhost)"
+    )]
+    pub allow_forward: Vec<ForwardRule>,
+
+    #[arg(
+        long = "allow-remote-forward",
+        value_name = "PORT",
+        action = clap::ArgAction::Append,
+        help = "Loopback port peers may listen on with `beach join --remote-forward` (repeatable)"
+    )]
+    pub allow_remote_forward: Vec<u16>,
+
+    #[arg(
+        long = "clipboard-sync",
+        value_enum,
+        default_value_t = ClipboardMode::Off,
+        help = "Clipboard directions peers may opt in to: read-only peers receive OSC 52 copies from the session, write-only peers set this host's clipboard"
+    )]
+    pub clipboard_sync: ClipboardMode,
+
     #[arg(
         long = "bootstrap-output",
         value_enum,
@@ -368,6 +446,12 @@ pub struct HostArgs {
     pub dev_offer_encryption_delay_ms: Option<u64>,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadlessOutput {
+    Jsonl,
+    Text,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum BootstrapOutput {
     #[default]
@@ -375,6 +459,62 @@ pub enum BootstrapOutput {
     Json,
 }
 
+#[derive(Args, Debug)]
+pub struct McpHubArgs {
+    #[arg(
+        long = "session",
+        value_name = "SESSION:CODE",
+        required_unless_present = "private_beach",
+        help = "Session id or share URL followed by its passcode (repeatable)"
+    )]
+    pub sessions: Vec<String>,
+
+    #[arg(
+        long = "private-beach",
+        value_name = "ID",
+        help = "Join every active session of this private beach, discovered through Beach Manager"
+    )]
+    pub private_beach: Option<String>,
+
+    #[arg(
+        long = "tag",
+        value_name = "TAG",
+        requires = "private_beach",
+        help = "Only join private beach sessions carrying this tag"
+    )]
+    pub tag: Option<String>,
+
+    #[arg(
+        long = "manager-url",
+        value_name = "URL",
+        env = "PRIVATE_BEACH_MANAGER_URL",
+        help = "Beach Manager base URL used with --private-beach"
+    )]
+    pub manager_url: Option<String>,
+
+    #[arg(
+        long = "label",
+        value_name = "TEXT",
+        env = "BEACH_CLIENT_LABEL",
+        help = "Optional identifier displayed to each host"
+    )]
+    pub label: Option<String>,
+
+    #[arg(
+        long = "socket",
+        value_name = "PATH",
+        help = "Serve the hub on the specified unix socket (defaults to ~/.beach/mcp/hub.sock)"
+    )]
+    pub socket: Option<PathBuf>,
+
+    #[arg(
+        long = "stdio",
+        action = clap::ArgAction::SetTrue,
+        help = "Serve the hub over stdio instead of a socket"
+    )]
+    pub stdio: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct JoinArgs {
     #[arg(value_name = "SESSION", help = "Session id or share URL")]
@@ -413,7 +553,7 @@ pub struct JoinArgs {
     #[arg(
         long = "headless",
         action = clap::ArgAction::SetTrue,
-        help = "Run without the interactive TUI and exit after the initial snapshot is received"
+        help = "Run without the interactive TUI and exit after the initial snapshot is received (unless --output is set)"
     )]
     pub headless: bool,
 
@@ -431,6 +571,122 @@ pub struct JoinArgs {
         help = "Request a specific terminal size when running headless validation (e.g. 80x24)"
     )]
     pub headless_resize: Option<String>,
+
+    #[arg(
+        long = "output",
+        value_enum,
+        requires = "headless",
+        help = "Keep streaming in headless mode: `jsonl` emits one JSON event per host frame, `text` prints committed lines like `tail -f`"
+    )]
+    pub output: Option<HeadlessOutput>,
+
+    #[arg(
+        long = "colors",
+        value_enum,
+        env = "BEACH_COLORS",
+        help = "Colors the local terminal supports: truecolor, 256, 16 or none (detected from COLORTERM and terminfo by default)"
+    )]
+    pub colors: Option<ColorLevel>,
+
+    #[arg(
+        long = "forward",
+        short = 'L',
+        value_name = "LOCAL_PORT:HOST:PORT",
+        action = clap::ArgAction::Append,
+        conflicts_with = "headless",
+        help = "Listen on local LOCAL_PORT and connect to HOST:PORT from the host (repeatable; the host must allow it)"
+    )]
+    pub forward: Vec<ForwardSpec>,
+
+    #[arg(
+        long = "remote-forward",
+        short = 'R',
+        value_name = "REMOTE_PORT:HOST:PORT",
+        action = clap::ArgAction::Append,
+        conflicts_with = "headless",
+        help = "Listen on the host's loopback REMOTE_PORT and connect to HOST:PORT from here (repeatable; the host must allow it)"
+    )]
+    pub remote_forward: Vec<ForwardSpec>,
+
+    #[arg(
+        long = "clipboard-sync",
+        value_enum,
+        env = "BEACH_CLIPBOARD_SYNC",
+        default_value_t = ClipboardMode::Off,
+        conflicts_with = "headless",
+        help = "Share clipboards with the host: read-only receives its copies, write-only pushes copy-mode selections to it (the host must allow it)"
+    )]
+    pub clipboard_sync: ClipboardMode,
+}
+
+#[derive(Args, Debug)]
+pub struct SendArgs {
+    #[arg(value_name = "SESSION", help = "Session id or share URL")]
+    pub target: String,
+
+    #[arg(value_name = "PATH", help = "Local file to send")]
+    pub path: PathBuf,
+
+    #[arg(
+        long,
+        short = 'p',
+        value_name = "CODE",
+        help = "Six character alphanumeric passcode (prompted interactively if omitted)"
+    )]
+    pub passcode: Option<String>,
+
+    #[arg(
+        long = "label",
+        value_name = "TEXT",
+        env = "BEACH_CLIENT_LABEL",
+        help = "Optional identifier displayed to the host"
+    )]
+    pub label: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReceiveArgs {
+    #[arg(value_name = "SESSION", help = "Session id or share URL")]
+    pub target: String,
+
+    #[arg(
+        value_name = "REMOTE_PATH",
+        help = "File on the host, relative to its transfer directory"
+    )]
+    pub path: String,
+
+    #[arg(
+        long,
+        short = 'p',
+        value_name = "CODE",
+        help = "Six character alphanumeric passcode (prompted interactively if omitted)"
+    )]
+    pub passcode: Option<String>,
+
+    #[arg(
+        long = "label",
+        value_name = "TEXT",
+        env = "BEACH_CLIENT_LABEL",
+        help = "Optional identifier displayed to the host"
+    )]
+    pub label: Option<String>,
+
+    #[arg(
+        long = "output-dir",
+        short = 'o',
+        value_name = "DIR",
+        default_value = ".",
+        help = "Directory to save the file in; an interrupted download resumes from here"
+    )]
+    pub output_dir: PathBuf,
+
+    #[arg(
+        long = "max-size",
+        value_name = "SIZE",
+        value_parser = parse_byte_size,
+        help = "Refuse files larger than this, e.g. 500M or 2G (defaults to 1G)"
+    )]
+    pub max_size: Option<u64>,
 }
 
 #[derive(Args, Debug)]
@@ -602,8 +858,54 @@ pub struct DebugArgs {
         help = "Send input text to the session"
     )]
     pub send: Option<String>,
+
+    #[arg(
+        long,
+        short = 'e',
+        value_name = "FORMAT",
+        help = "Export the rendered viewport with colors: html, ansi, svg"
+    )]
+    pub export: Option<String>,
 }
 
 pub fn parse() -> Cli {
     Cli::parse()
 }
+
+/// Parses a byte count with an optional binary `K`, `M` or `G` suffix.
+fn parse_byte_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let (digits, scale) = match trimmed.char_indices().last() {
+        Some((idx, suffix)) if suffix.is_ascii_alphabetic() => {
+            let scale = match suffix.to_ascii_uppercase() {
+                'K' => 1 << 10,
+                'M' => 1 << 20,
+                'G' => 1 << 30,
+                _ => return Err(format!("unknown size suffix in '{value}'; use K, M or G")),
+            };
+            (&trimmed[..idx], scale)
+        }
+        _ => (trimmed, 1),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(scale))
+        .ok_or_else(|| format!("invalid size '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::timeout]
+    fn parse_byte_size_accepts_binary_suffixes() {
+        assert_eq!(parse_byte_size("512"), Ok(512));
+        assert_eq!(parse_byte_size("4k"), Ok(4096));
+        assert_eq!(parse_byte_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_byte_size(" 2G "), Ok(2 << 30));
+        assert!(parse_byte_size("2T").is_err());
+        assert!(parse_byte_size("M").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 pub fn cursor_sync_enabled() -> bool {
     std::env::var("BEACH_CURSOR_SYNC")
         .map(|value| {
@@ -9,6 +11,7 @@ pub fn cursor_sync_enabled() -> bool {
         .unwrap_or(true)
 }
 
+/// `[client.keys]`: see `client::terminal::keymap` for action names.
 #[derive(Debug, Clone, serde::Deserialize, Default)]
 pub struct KeyConfig {
     #[serde(default)]
@@ -17,12 +20,55 @@ pub struct KeyConfig {
     pub copy_shortcuts: Option<Vec<String>>,
     #[serde(default)]
     pub double_esc: Option<bool>,
+    /// Prefix key for two-key commands (default `ctrl+b`); `"none"` disables it.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Direct bindings, keyed by action name.
+    #[serde(default)]
+    pub bind: BTreeMap<String, Vec<String>>,
+    /// Bindings that apply after the prefix key, keyed by action name.
+    #[serde(default)]
+    pub prefix_bind: BTreeMap<String, Vec<String>>,
+    /// Copy-mode bindings for the vi key set, keyed by command name.
+    #[serde(default)]
+    pub copy_mode_vi: BTreeMap<String, Vec<String>>,
+    /// Copy-mode bindings for the emacs key set, keyed by command name.
+    #[serde(default)]
+    pub copy_mode_emacs: BTreeMap<String, Vec<String>>,
+}
+
+/// `[client.theme]`: a built-in theme plus per-color overrides as `#rrggbb`.
+#[derive(Debug, Clone, serde::Deserialize, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub foreground: Option<String>,
+    #[serde(default)]
+    pub background: Option<String>,
+    /// The 16 ANSI colors in order; empty strings keep the theme's color.
+    #[serde(default)]
+    pub palette: Vec<String>,
+}
+
+/// `[client.presence]`: whether to share this client's cursor, selection and
+/// typing activity with other peers, and the name they see.
+#[derive(Debug, Clone, serde::Deserialize, Default)]
+pub struct PresenceConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, Default)]
 pub struct ClientConfig {
     #[serde(default)]
     pub keys: Option<KeyConfig>,
+    #[serde(default)]
+    pub theme: Option<ThemeConfig>,
+    #[serde(default)]
+    pub presence: Option<PresenceConfig>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, Default)]
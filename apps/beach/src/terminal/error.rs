@@ -34,6 +34,10 @@ pub enum CliError {
     RemoteArchDetection(String),
     #[error("cross-compilation failed: {0}")]
     CrossCompile(String),
+    #[error("file transfer failed: {0}")]
+    Transfer(String),
     #[error("invalid argument: {0}")]
     InvalidArgument(String),
+    #[error("beach manager request failed: {0}")]
+    Manager(String),
 }
@@ -0,0 +1,243 @@
+use crate::auth;
+use crate::client::terminal::join::join_mcp_channel;
+use crate::mcp::McpConfig;
+use crate::mcp::hub::{HubUpstream, McpHub};
+use crate::terminal::cli::McpHubArgs;
+use crate::terminal::error::CliError;
+use crate::transport::{Transport, TransportError};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+pub async fn run(base_url: &str, args: McpHubArgs, profile: Option<&str>) -> Result<(), CliError> {
+    let McpHubArgs {
+        sessions,
+        private_beach,
+        tag,
+        manager_url,
+        label,
+        socket,
+        stdio,
+    } = args;
+
+    let mut targets = Vec::with_capacity(sessions.len());
+    for spec in &sessions {
+        let (target, passcode) = parse_session_spec(spec)?;
+        targets.push((target.to_string(), passcode.to_string()));
+    }
+    if let Some(private_beach) = private_beach {
+        let manager_url = manager_url.ok_or_else(|| {
+            CliError::InvalidArgument(
+                "--private-beach needs --manager-url or PRIVATE_BEACH_MANAGER_URL".into(),
+            )
+        })?;
+        let discovered =
+            discover_sessions(&manager_url, &private_beach, tag.as_deref(), profile).await?;
+        if discovered.is_empty() {
+            return Err(CliError::InvalidArgument(format!(
+                "private beach {private_beach} has no active sessions to join"
+            )));
+        }
+        targets.extend(discovered);
+    }
+
+    let mut upstreams = Vec::with_capacity(targets.len());
+    for (target, passcode) in &targets {
+        let joined =
+            join_mcp_channel(base_url, target, passcode, label.as_deref(), profile).await?;
+        info!(target = "mcp::hub", session_id = %joined.session_id, "joined session for mcp hub");
+        spawn_terminal_drain(joined.session_id.clone(), joined.transport);
+        upstreams.push(HubUpstream::spawn(joined.session_id, joined.mcp));
+    }
+
+    let socket = socket.unwrap_or_else(default_hub_socket_path);
+    if !stdio {
+        eprintln!(
+            "🔌 MCP hub serving {} session(s) at {}",
+            upstreams.len(),
+            socket.display()
+        );
+    }
+    let config = McpConfig {
+        socket: Some(socket),
+        use_stdio: stdio,
+        ..McpConfig::default()
+    };
+    McpHub::new(config, upstreams)
+        .run()
+        .await
+        .map_err(|err| CliError::Runtime(err.to_string()))
+}
+
+/// Splits `TARGET:CODE`, where `TARGET` may itself be a share URL.
+fn parse_session_spec(spec: &str) -> Result<(&str, &str), CliError> {
+    spec.rsplit_once(':')
+        .filter(|(target, code)| {
+            !target.is_empty() && code.len() == 6 && code.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .ok_or_else(|| {
+            CliError::InvalidArgument(format!(
+                "expected SESSION:CODE with a six character passcode, got '{spec}'"
+            ))
+        })
+}
+
+#[derive(Deserialize)]
+struct ManagerSession {
+    session_id: String,
+}
+
+#[derive(Deserialize)]
+struct ViewerCredential {
+    credential_type: String,
+    credential: String,
+    #[serde(default)]
+    passcode: Option<String>,
+}
+
+impl ViewerCredential {
+    /// The passcode carried by either credential shape the manager returns.
+    fn into_passcode(self) -> Option<String> {
+        match self.credential_type.as_str() {
+            "viewer_passcode" => Some(self.credential),
+            _ => self.passcode,
+        }
+    }
+}
+
+/// Lists the active sessions of `private_beach` and fetches a passcode for
+/// each from Beach Manager. Sessions without a credential are skipped.
+async fn discover_sessions(
+    manager_url: &str,
+    private_beach: &str,
+    tag: Option<&str>,
+    profile: Option<&str>,
+) -> Result<Vec<(String, String)>, CliError> {
+    let token = auth::maybe_access_token(profile, auth::manager_requires_access_token(manager_url))
+        .await
+        .map_err(|err| CliError::Auth(err.to_string()))?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|err| CliError::Manager(err.to_string()))?;
+    let base = manager_url.trim_end_matches('/');
+    let get = |url: String| {
+        let request = client.get(url);
+        match &token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    };
+
+    let mut query = vec![("status", "active")];
+    if let Some(tag) = tag {
+        query.push(("tag", tag));
+    }
+    let sessions: Vec<ManagerSession> =
+        get(format!("{base}/private-beaches/{private_beach}/sessions"))
+            .query(&query)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| CliError::Manager(err.to_string()))?
+            .json()
+            .await
+            .map_err(|err| CliError::Manager(err.to_string()))?;
+
+    let mut discovered = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let credential = get(format!(
+            "{base}/private-beaches/{private_beach}/sessions/{}/viewer-credential",
+            session.session_id
+        ))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+        let passcode = match credential {
+            Ok(response) => response
+                .json::<ViewerCredential>()
+                .await
+                .ok()
+                .and_then(ViewerCredential::into_passcode),
+            Err(err) => {
+                warn!(
+                    target = "mcp::hub",
+                    session_id = %session.session_id,
+                    error = %err,
+                    "no viewer credential for session"
+                );
+                None
+            }
+        };
+        match passcode {
+            Some(passcode) => discovered.push((session.session_id, passcode)),
+            None => warn!(
+                target = "mcp::hub",
+                session_id = %session.session_id,
+                "skipping session without a passcode"
+            ),
+        }
+    }
+    Ok(discovered)
+}
+
+fn default_hub_socket_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".into());
+    Path::new(&home).join(".beach").join("mcp").join("hub.sock")
+}
+
+/// The hub only consumes the MCP channel, but the terminal stream must be read
+/// so the host's sender never stalls on a full channel.
+fn spawn_terminal_drain(session_id: String, transport: Arc<dyn Transport>) {
+    tokio::task::spawn_blocking(move || {
+        loop {
+            match transport.recv(Duration::from_secs(30)) {
+                Ok(_) | Err(TransportError::Timeout) => continue,
+                Err(err) => {
+                    debug!(
+                        target = "mcp::hub",
+                        session_id = %session_id,
+                        error = %err,
+                        "terminal stream closed"
+                    );
+                    break;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_timeout::timeout]
+    fn parse_session_spec_accepts_ids_and_urls() {
+        let (target, code) =
+            parse_session_spec("5b1c0d6e-0000-4000-8000-000000000001:ABC123").expect("uuid spec");
+        assert_eq!(target, "5b1c0d6e-0000-4000-8000-000000000001");
+        assert_eq!(code, "ABC123");
+
+        let (target, code) =
+            parse_session_spec("https://beach.sh/sessions/abc:QWERTY").expect("url spec");
+        assert_eq!(target, "https://beach.sh/sessions/abc");
+        assert_eq!(code, "QWERTY");
+
+        assert!(parse_session_spec("https://beach.sh/sessions/abc").is_err());
+    }
+
+    #[test_timeout::timeout]
+    fn viewer_credentials_yield_the_session_passcode() {
+        let token: ViewerCredential = serde_json::from_str(
+            r#"{"credential_type":"viewer_token","credential":"jwt","passcode":"ABC123"}"#,
+        )
+        .unwrap();
+        assert_eq!(token.into_passcode().as_deref(), Some("ABC123"));
+        let passcode: ViewerCredential =
+            serde_json::from_str(r#"{"credential_type":"viewer_passcode","credential":"QWERTY"}"#)
+                .unwrap();
+        assert_eq!(passcode.into_passcode().as_deref(), Some("QWERTY"));
+    }
+}
@@ -1,10 +1,19 @@
+//! Extension namespaces layered on the terminal protocol. Clients subscribe to
+//! the frames their host sends in a namespace; hosts run one [`ExtensionHub`]
+//! per namespace and route every peer's frames through [`ExtensionHubs`].
+
 use std::collections::HashMap;
-use std::sync::{LazyLock, RwLock};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, RwLock};
 
+use bytes::Bytes;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use tokio::sync::broadcast;
+use tracing::debug;
 
-use crate::protocol::ExtensionFrame;
-use crate::transport::TransportId;
+use crate::protocol::{ExtensionFrame, HostFrame};
+use crate::sync::terminal::server_pipeline::send_host_frame;
+use crate::transport::{Transport, TransportId};
 
 type Namespace = String;
 
@@ -39,6 +48,240 @@ pub fn publish(id: TransportId, frame: ExtensionFrame) {
     }
 }
 
+/// The typed events of one extension namespace. Implementors map each event
+/// to a frame kind and payload; framing and the namespace check live here.
+pub(crate) trait ExtensionEvent: Sized {
+    const NAMESPACE: &'static str;
+
+    /// The frame kind and payload carrying this event.
+    fn encode(&self) -> (&'static str, Bytes);
+
+    /// Rebuilds an event of `kind`; `None` for unknown kinds and malformed
+    /// payloads.
+    fn decode(kind: &str, payload: &Bytes) -> Option<Self>;
+
+    fn to_frame(&self) -> ExtensionFrame {
+        let (kind, payload) = self.encode();
+        ExtensionFrame {
+            namespace: Self::NAMESPACE.to_string(),
+            kind: kind.to_string(),
+            payload,
+        }
+    }
+
+    /// Decodes `frame`; `None` when it belongs to another namespace or does
+    /// not decode.
+    fn from_frame(frame: &ExtensionFrame) -> Option<Self> {
+        if frame.namespace != Self::NAMESPACE {
+            return None;
+        }
+        Self::decode(&frame.kind, &frame.payload)
+    }
+}
+
+/// Encodes a JSON payload. Payloads are plain structs, so this cannot fail.
+pub(crate) fn json_payload<T: Serialize>(value: &T) -> Bytes {
+    Bytes::from(serde_json::to_vec(value).expect("extension payload serializes"))
+}
+
+pub(crate) fn from_json_payload<T: DeserializeOwned>(payload: &[u8]) -> Option<T> {
+    serde_json::from_slice(payload).ok()
+}
+
+/// Host side of one extension namespace, shared by every attached peer.
+pub(crate) trait ExtensionHub: Send + Sync + 'static {
+    type Event: ExtensionEvent;
+
+    /// Called once a peer's transport attaches, before any of its frames.
+    fn join(&self, _transport: &Arc<dyn Transport>) {}
+
+    /// Handles an event the peer on `transport` sent.
+    fn handle(self: &Arc<Self>, transport: &Arc<dyn Transport>, event: Self::Event);
+
+    /// Called once the peer's transport has closed.
+    fn leave(&self, _peer: u64) {}
+}
+
+trait Dispatch: Send + Sync {
+    fn join(&self, transport: &Arc<dyn Transport>);
+
+    /// Handles `frame` if it belongs to this hub's namespace.
+    fn dispatch(&self, transport: &Arc<dyn Transport>, frame: &ExtensionFrame) -> bool;
+
+    fn leave(&self, peer: u64);
+}
+
+impl<H: ExtensionHub> Dispatch for Arc<H> {
+    fn join(&self, transport: &Arc<dyn Transport>) {
+        ExtensionHub::join(self.as_ref(), transport);
+    }
+
+    fn dispatch(&self, transport: &Arc<dyn Transport>, frame: &ExtensionFrame) -> bool {
+        if frame.namespace != H::Event::NAMESPACE {
+            return false;
+        }
+        match H::Event::from_frame(frame) {
+            Some(event) => ExtensionHub::handle(self, transport, event),
+            None => debug!(
+                target = "host::extensions",
+                transport_id = transport.id().0,
+                namespace = %frame.namespace,
+                kind = %frame.kind,
+                "dropping undecodable extension frame"
+            ),
+        }
+        true
+    }
+
+    fn leave(&self, peer: u64) {
+        ExtensionHub::leave(self.as_ref(), peer);
+    }
+}
+
+/// The hubs a host runs. Every peer listener joins and leaves all of them and
+/// hands each extension frame to the hub of its namespace.
+#[derive(Clone, Default)]
+pub(crate) struct ExtensionHubs {
+    hubs: Vec<Arc<dyn Dispatch>>,
+}
+
+impl ExtensionHubs {
+    pub(crate) fn with<H: ExtensionHub>(mut self, hub: Arc<H>) -> Self {
+        self.hubs.push(Arc::new(hub));
+        self
+    }
+
+    pub(crate) fn join(&self, transport: &Arc<dyn Transport>) {
+        for hub in &self.hubs {
+            hub.join(transport);
+        }
+    }
+
+    pub(crate) fn dispatch(&self, transport: &Arc<dyn Transport>, frame: &ExtensionFrame) {
+        if !self.hubs.iter().any(|hub| hub.dispatch(transport, frame)) {
+            debug!(
+                target = "host::extensions",
+                transport_id = transport.id().0,
+                namespace = %frame.namespace,
+                "no hub serves extension namespace"
+            );
+        }
+    }
+
+    pub(crate) fn leave(&self, peer: u64) {
+        for hub in &self.hubs {
+            hub.leave(peer);
+        }
+    }
+}
+
+/// A peer attached to a hub, with the hub's state for it.
+pub(crate) struct ExtensionPeer<S> {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) state: S,
+}
+
+/// The peers attached to one hub, keyed by transport id.
+pub(crate) struct ExtensionPeers<S> {
+    peers: Mutex<HashMap<u64, ExtensionPeer<S>>>,
+}
+
+impl<S> Default for ExtensionPeers<S> {
+    fn default() -> Self {
+        Self {
+            peers: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> ExtensionPeers<S> {
+    pub(crate) fn join(&self, transport: &Arc<dyn Transport>, state: S) {
+        self.lock().insert(
+            transport.id().0,
+            ExtensionPeer {
+                transport: transport.clone(),
+                state,
+            },
+        );
+    }
+
+    /// Removes `peer`, returning its state.
+    pub(crate) fn leave(&self, peer: u64) -> Option<S> {
+        self.lock().remove(&peer).map(|entry| entry.state)
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, HashMap<u64, ExtensionPeer<S>>> {
+        self.peers.lock().unwrap()
+    }
+
+    /// Transports of the peers `filter` picks, collected so callers can send
+    /// without holding the lock.
+    pub(crate) fn select(&self, filter: impl Fn(u64, &S) -> bool) -> Vec<Arc<dyn Transport>> {
+        self.lock()
+            .iter()
+            .filter(|(id, entry)| filter(**id, &entry.state))
+            .map(|(_, entry)| entry.transport.clone())
+            .collect()
+    }
+}
+
+/// Sends `event` to the peer on `transport`. A failed send is only logged; the
+/// peer's listener notices the closed transport and leaves every hub.
+pub(crate) fn send_event<E: ExtensionEvent>(transport: &Arc<dyn Transport>, event: &E) {
+    let frame = HostFrame::Extension {
+        frame: event.to_frame(),
+    };
+    if let Err(err) = send_host_frame(transport, frame) {
+        debug!(
+            target = "host::extensions",
+            transport_id = transport.id().0,
+            namespace = E::NAMESPACE,
+            error = %err,
+            "failed to send extension frame"
+        );
+    }
+}
+
+/// Test side of a hub's peer: the next `E` the host sent to `transport`.
+#[cfg(test)]
+pub(crate) fn recv_event<E: ExtensionEvent>(transport: &dyn Transport) -> Option<E> {
+    let message = transport.recv(std::time::Duration::from_secs(2)).ok()?;
+    let crate::transport::Payload::Binary(bytes) = message.payload else {
+        return None;
+    };
+    match crate::protocol::decode_host_frame_binary(&bytes).ok()? {
+        HostFrame::Extension { frame } => E::from_frame(&frame),
+        _ => None,
+    }
+}
+
+/// Asserts that every event survives a trip through an extension frame of
+/// its namespace and that frames of other namespaces are ignored.
+#[cfg(test)]
+pub(crate) fn assert_round_trips<E>(events: impl IntoIterator<Item = E>)
+where
+    E: ExtensionEvent + PartialEq + std::fmt::Debug,
+{
+    for event in events {
+        let frame = event.to_frame();
+        assert_eq!(frame.namespace, E::NAMESPACE);
+        let foreign = ExtensionFrame {
+            namespace: "fastpath".into(),
+            ..frame.clone()
+        };
+        assert_eq!(E::from_frame(&foreign), None);
+        assert_eq!(E::from_frame(&frame), Some(event));
+    }
+}
+
+/// Asserts the host sends nothing to `transport` for a moment.
+#[cfg(test)]
+pub(crate) fn assert_no_event(transport: &dyn Transport) {
+    if let Ok(message) = transport.recv(std::time::Duration::from_millis(200)) {
+        panic!("unexpected frame {:?}", message.payload);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,6 +2,7 @@ pub mod validate;
 
 use crate::auth;
 use crate::client::terminal::join;
+use crate::protocol::clipboard::ClipboardMode;
 use crate::protocol::terminal::bootstrap::{self, BootstrapHandshake};
 use crate::terminal::cli::{JoinArgs, SshArgs};
 use crate::terminal::error::CliError;
@@ -219,6 +220,11 @@ pub async fn run(
         headless: false,
         headless_timeout: 30,
         headless_resize: None,
+        output: None,
+        colors: None,
+        forward: Vec::new(),
+        remote_forward: Vec::new(),
+        clipboard_sync: ClipboardMode::Off,
     };
 
     // If we are keeping the remote host running, we can drop SSH immediately.
@@ -60,6 +60,8 @@ impl ValidationReport {
             | HostFrame::InputAck { .. }
             | HostFrame::Cursor { .. }
             | HostFrame::Extension { .. }
+            | HostFrame::SearchResults { .. }
+            | HostFrame::Modes { .. }
             | HostFrame::Shutdown => {}
         }
     }
@@ -276,6 +278,12 @@ fn run_validation_loop(
                             return Ok(report);
                         }
                     }
+                    Err(protocol::WireError::UnknownFrameType(kind)) => {
+                        debug!(
+                            target = "beach::transport::ssh::validate",
+                            kind, "skipping unknown host frame"
+                        );
+                    }
                     Err(err) => {
                         return Err(ValidationFailure::Decode(err.to_string()));
                     }
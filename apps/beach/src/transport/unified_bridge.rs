@@ -16,7 +16,7 @@ use crate::protocol::{ExtensionFrame, HostFrame};
 use crate::transport::{
     Transport,
     bus::{manager_bus_from_client, manager_topics},
-    extensions,
+    extensions, framed,
 };
 use transport_unified_adapter::UnifiedBus;
 
@@ -24,6 +24,7 @@ use transport_unified_adapter::UnifiedBus;
 const BUS_NAMESPACE: &str = "manager";
 #[allow(dead_code)]
 const LEGACY_NAMESPACE: &str = "fastpath";
+const CONTROLLER_NAMESPACE: &str = "controller";
 // Preferred topics (pulled from shared bus topics for auditability)
 const TOPIC_ACTION: &str = manager_topics::TOPIC_ACTION;
 const TOPIC_ACK: &str = manager_topics::TOPIC_ACK;
@@ -101,6 +102,26 @@ impl UnifiedBuggyTransport {
                 }
             });
         }
+        // Managers forward structured actions on the framed controller namespace.
+        {
+            let mut framed_rx = framed::subscribe(transport.id(), CONTROLLER_NAMESPACE);
+            let actions_tx_framed = actions_tx.clone();
+            tokio::spawn(async move {
+                loop {
+                    match framed_rx.recv().await {
+                        Ok(frame) if frame.kind == KIND_ACTION => {
+                            if let Ok(text) = std::str::from_utf8(&frame.payload) {
+                                if let Ok(action) = parse_action_payload(text) {
+                                    let _ = actions_tx_framed.send(action);
+                                }
+                            }
+                        }
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
         Self {
             transport,
             bus,
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use beach_client_core::cache::terminal::{StyleId, pack_cell};
+use beach_client_core::protocol::{
+    self, ClientFrame, HostFrame, SyncConfigFrame, Update as WireUpdate,
+};
+use beach_client_core::sdk::{Regex, Session};
+use beach_client_core::transport::{
+    Payload, Transport, TransportError, TransportKind, TransportPair,
+};
+
+fn send_host_frame(transport: &dyn Transport, frame: HostFrame) {
+    let bytes = protocol::encode_host_frame_binary(&frame);
+    transport.send_bytes(&bytes).expect("send host frame");
+}
+
+fn recv_client_frame(transport: &dyn Transport) -> ClientFrame {
+    loop {
+        let message = transport
+            .recv(Duration::from_secs(5))
+            .expect("client frame");
+        if let Payload::Binary(bytes) = message.payload {
+            return protocol::decode_client_frame_binary(&bytes).expect("decode client frame");
+        }
+    }
+}
+
+fn row(row: u32, text: &str) -> WireUpdate {
+    WireUpdate::Row {
+        row,
+        seq: 1,
+        cells: text
+            .chars()
+            .map(|ch| pack_cell(ch, StyleId::DEFAULT).into())
+            .collect(),
+    }
+}
+
+#[test_timeout::timeout]
+fn sdk_session_drives_a_loopback_host() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let pair = TransportPair::new(TransportKind::Ipc);
+    let host: Arc<dyn Transport> = Arc::from(pair.server);
+    let session = Session::attach("loopback", Arc::from(pair.client));
+
+    send_host_frame(
+        host.as_ref(),
+        HostFrame::Hello {
+            subscription: 7,
+            max_seq: 0,
+            config: SyncConfigFrame {
+                snapshot_budgets: Vec::new(),
+                delta_budget: 0,
+                heartbeat_ms: 0,
+                initial_snapshot_lines: 0,
+            },
+            features: 0,
+        },
+    );
+    send_host_frame(
+        host.as_ref(),
+        HostFrame::Grid {
+            cols: 16,
+            history_rows: 100,
+            base_row: 0,
+            viewport_rows: Some(2),
+        },
+    );
+    send_host_frame(
+        host.as_ref(),
+        HostFrame::Delta {
+            subscription: 7,
+            watermark: 1,
+            has_more: false,
+            updates: vec![row(40, "$ echo hi"), row(41, "hi")],
+            cursor: None,
+        },
+    );
+
+    let found = runtime
+        .block_on(session.wait_for(&Regex::new("^hi$").unwrap(), Duration::from_secs(5)))
+        .expect("line appears");
+    assert_eq!(found.row, 41);
+
+    session.send_keys("ls\r").unwrap();
+    match recv_client_frame(host.as_ref()) {
+        ClientFrame::Input { data, .. } => assert_eq!(data, b"ls\r"),
+        other => panic!("unexpected frame {other:?}"),
+    }
+
+    // History is fetched from the host, not just read from rows seen so far.
+    let host_side = Arc::clone(&host);
+    let responder = std::thread::spawn(move || match recv_client_frame(host_side.as_ref()) {
+        ClientFrame::RequestBackfill {
+            subscription,
+            request_id,
+            start_row,
+            count,
+        } => {
+            assert_eq!((subscription, start_row, count), (7, 10, 2));
+            send_host_frame(
+                host_side.as_ref(),
+                HostFrame::HistoryBackfill {
+                    subscription,
+                    request_id,
+                    start_row,
+                    count,
+                    updates: vec![row(10, "older"), row(11, "output")],
+                    more: false,
+                    cursor: None,
+                },
+            );
+        }
+        other => panic!("unexpected frame {other:?}"),
+    });
+    let history = runtime.block_on(session.history(10..12)).unwrap();
+    responder.join().unwrap();
+    assert_eq!(
+        history,
+        vec![(10, "older".to_string()), (11, "output".to_string())]
+    );
+
+    // Dropping the session joins its reader and closes the connection.
+    drop(session);
+    let closed = loop {
+        match host.recv(Duration::from_secs(5)) {
+            Ok(_) => continue,
+            Err(err) => break err,
+        }
+    };
+    assert!(matches!(closed, TransportError::ChannelClosed));
+}
@@ -279,6 +279,8 @@ fn late_joiner_receives_snapshot_and_roundtrips_input() {
             | HostFrame::HistoryBackfill { .. }
             | HostFrame::Cursor { .. }
             | HostFrame::Extension { .. }
+            | HostFrame::SearchResults { .. }
+            | HostFrame::Modes { .. }
             | HostFrame::Shutdown => {}
         }
         if view.contains_row("host% echo world") && view.contains_row("world") {
@@ -584,7 +584,9 @@ where
                 | HostFrame::Grid { .. }
                 | HostFrame::Heartbeat { .. }
                 | HostFrame::InputAck { .. }
-                | HostFrame::Extension { .. } => {}
+                | HostFrame::Extension { .. }
+                | HostFrame::SearchResults { .. }
+                | HostFrame::Modes { .. } => {}
             }
         }
     });
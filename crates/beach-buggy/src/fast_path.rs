@@ -742,7 +742,7 @@ fn wire_action_handler(
     }));
 }
 
-const WIRE_PROTOCOL_VERSION: u8 = 2;
+const WIRE_PROTOCOL_VERSION: u8 = 3;
 const CLIENT_KIND_INPUT: u8 = 0;
 
 fn decode_binary_action_message(data: &[u8]) -> HarnessResult<ActionCommand> {
@@ -60,6 +60,7 @@ pub enum HarnessError {
 
 /// Struct used when registering the harness with Beach Manager.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RegisterSessionRequest {
     pub session_id: String,
     pub private_beach_id: String,
@@ -75,6 +76,7 @@ pub struct RegisterSessionRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RegisterSessionResponse {
     pub harness_id: String,
     pub controller_token: Option<String>,
@@ -97,6 +99,7 @@ impl Default for RegisterSessionResponse {
 
 /// Harness classification. More variants can be added as we support new capture types.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum HarnessType {
     TerminalShim,
@@ -108,6 +111,7 @@ pub enum HarnessType {
 
 /// Declares the preferred controller transport for a session.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum TransportMode {
     FastPath,
@@ -122,26 +126,32 @@ impl Default for TransportMode {
 
 /// Diff payload emitted to the manager.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct StateDiff {
     pub sequence: u64,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub emitted_at: SystemTime,
     pub payload: serde_json::Value,
 }
 
 /// Command instruction delivered by the manager.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ActionCommand {
     pub id: String,
     pub action_type: String,
     pub payload: serde_json::Value,
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
     pub expires_at: Option<SystemTime>,
 }
 
 /// Acknowledgement payload returned to the manager.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ActionAck {
     pub id: String,
     pub status: AckStatus,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
     pub applied_at: SystemTime,
     pub latency_ms: Option<u64>,
     pub error_code: Option<String>,
@@ -149,6 +159,7 @@ pub struct ActionAck {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum AckStatus {
     Ok,
@@ -159,6 +170,7 @@ pub enum AckStatus {
 
 /// Heartbeat payload describing current harness health.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct HealthHeartbeat {
     pub queue_depth: usize,
     pub cpu_load: Option<f32>,
@@ -167,6 +179,37 @@ pub struct HealthHeartbeat {
     pub warnings: Vec<String>,
 }
 
+/// Artifact (log, build output, ...) a harness pushes into its private beach's file store.
+#[derive(Debug, Clone)]
+pub struct ArtifactUpload {
+    /// Slash-separated path within the beach, e.g. `logs/build.txt`.
+    pub path: String,
+    pub content_type: Option<String>,
+    pub data: bytes::Bytes,
+}
+
+/// One stored version of a file in a private beach's file store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FileRecord {
+    pub id: String,
+    pub private_beach_id: String,
+    pub path: String,
+    pub version: i32,
+    pub size_bytes: i64,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Hex-encoded SHA-256 of the content.
+    pub checksum: String,
+    #[serde(default)]
+    pub uploaded_by_account_id: Option<String>,
+    #[serde(default)]
+    pub uploaded_by_session_id: Option<String>,
+    pub uploaded_at_ms: i64,
+    #[serde(default)]
+    pub deleted_at_ms: Option<i64>,
+}
+
 /// Manager notification that controller token changed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControllerNotification {
@@ -175,6 +218,7 @@ pub struct ControllerNotification {
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerUpdateCadence {
     Fast,
@@ -184,6 +228,7 @@ pub enum ControllerUpdateCadence {
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum PairingTransportKind {
     #[serde(rename = "webrtc")]
     Rtc,
@@ -200,6 +245,7 @@ impl Default for PairingTransportKind {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PairingTransportStatus {
     pub transport: PairingTransportKind,
     #[serde(default)]
@@ -211,6 +257,7 @@ pub struct PairingTransportStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ControllerPairing {
     pub pairing_id: String,
     pub controller_session_id: String,
@@ -227,6 +274,7 @@ pub struct ControllerPairing {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ControllerPairingAction {
     Added,
@@ -235,6 +283,7 @@ pub enum ControllerPairingAction {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ControllerPairingEvent {
     pub controller_session_id: String,
     pub child_session_id: String,
@@ -342,6 +391,18 @@ pub trait ManagerTransport: Send + Sync + 'static {
         session_id: &str,
         heartbeat: HealthHeartbeat,
     ) -> HarnessResult<()>;
+
+    /// Stores an artifact in the session's private beach file store. Uploading
+    /// to an existing path creates a new version.
+    async fn upload_artifact(
+        &self,
+        _session_id: &str,
+        _artifact: ArtifactUpload,
+    ) -> HarnessResult<FileRecord> {
+        Err(HarnessError::Transport(
+            "artifact uploads are not supported by this transport".into(),
+        ))
+    }
 }
 
 #[async_trait]
@@ -582,6 +643,13 @@ impl<T: ManagerTransport> SessionHarness<T> {
             .await
     }
 
+    /// Pushes an artifact into the private beach file store on behalf of this session.
+    pub async fn upload_artifact(&self, artifact: ArtifactUpload) -> HarnessResult<FileRecord> {
+        self.transport
+            .upload_artifact(&self.config.session_id, artifact)
+            .await
+    }
+
     /// Returns the current controller token if one is held.
     pub async fn controller_token(&self) -> Option<String> {
         self.state.lock().await.controller_token.clone()
@@ -1566,6 +1634,37 @@ impl<P: TokenProvider> ManagerTransport for HttpTransport<P> {
             .await?;
         Ok(())
     }
+
+    async fn upload_artifact(
+        &self,
+        session_id: &str,
+        artifact: ArtifactUpload,
+    ) -> HarnessResult<FileRecord> {
+        let mut url = self.url(&format!("sessions/{session_id}/files/"))?;
+        url.path_segments_mut()
+            .map_err(|_| HarnessError::Transport("base url cannot carry a path".into()))?
+            .pop_if_empty()
+            .extend(
+                artifact
+                    .path
+                    .split('/')
+                    .filter(|segment| !segment.is_empty()),
+            );
+        let content_type = artifact
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".into());
+        let resp = self
+            .request_with_token(
+                self.client
+                    .put(url)
+                    .header(reqwest::header::CONTENT_TYPE, content_type)
+                    .body(artifact.data),
+            )
+            .await?;
+        resp.json::<FileRecord>()
+            .await
+            .map_err(|e| HarnessError::Transport(format!("decode file record: {e}")))
+    }
 }
 
 #[async_trait]
@@ -1666,6 +1765,7 @@ struct InMemoryState {
     acks: Vec<Vec<ActionAck>>,
     health: Vec<HealthHeartbeat>,
     controller_pairings: Vec<ControllerPairing>,
+    artifacts: Vec<FileRecord>,
 }
 
 impl InMemoryTransport {
@@ -1698,6 +1798,10 @@ impl InMemoryTransport {
     pub async fn set_pairings(&self, pairings: Vec<ControllerPairing>) {
         self.inner.lock().await.controller_pairings = pairings;
     }
+
+    pub async fn artifacts(&self) -> Vec<FileRecord> {
+        self.inner.lock().await.artifacts.clone()
+    }
 }
 
 #[async_trait]
@@ -1741,6 +1845,35 @@ impl ManagerTransport for InMemoryTransport {
         state.health.push(heartbeat);
         Ok(())
     }
+
+    async fn upload_artifact(
+        &self,
+        session_id: &str,
+        artifact: ArtifactUpload,
+    ) -> HarnessResult<FileRecord> {
+        let mut state = self.inner.lock().await;
+        let version = state
+            .artifacts
+            .iter()
+            .filter(|record| record.path == artifact.path)
+            .count() as i32
+            + 1;
+        let record = FileRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            private_beach_id: String::new(),
+            path: artifact.path,
+            version,
+            size_bytes: artifact.data.len() as i64,
+            content_type: artifact.content_type,
+            checksum: String::new(),
+            uploaded_by_account_id: None,
+            uploaded_by_session_id: Some(session_id.to_string()),
+            uploaded_at_ms: now_millis(),
+            deleted_at_ms: None,
+        };
+        state.artifacts.push(record.clone());
+        Ok(record)
+    }
 }
 
 #[async_trait]
@@ -1852,6 +1985,40 @@ mod tests {
         assert_eq!(diffs[1].payload["lines"][0], "world");
     }
 
+    #[tokio::test]
+    async fn artifact_uploads_version_per_path() {
+        let transport = InMemoryTransport::with_response(sample_register_response());
+        let harness = SessionHarness::new(
+            HarnessConfig {
+                session_id: "sess-artifacts".into(),
+                private_beach_id: "pb-1".into(),
+                harness_type: HarnessType::TerminalShim,
+                capabilities: Vec::new(),
+                location_hint: None,
+                version: "0.1.0".into(),
+                viewer_passcode: None,
+                transport_mode: TransportMode::FastPath,
+            },
+            transport.clone(),
+        );
+
+        let upload = |data: &'static str| ArtifactUpload {
+            path: "logs/build.txt".into(),
+            content_type: Some("text/plain".into()),
+            data: bytes::Bytes::from_static(data.as_bytes()),
+        };
+        let first = harness.upload_artifact(upload("one")).await.unwrap();
+        let second = harness.upload_artifact(upload("three")).await.unwrap();
+        assert_eq!(first.version, 1);
+        assert_eq!(second.version, 2);
+        assert_eq!(second.size_bytes, 5);
+        assert_eq!(
+            second.uploaded_by_session_id.as_deref(),
+            Some("sess-artifacts")
+        );
+        assert_eq!(transport.artifacts().await.len(), 2);
+    }
+
     #[tokio::test]
     async fn controller_preemption_flushes_pending_actions() {
         let transport = InMemoryTransport::with_response(sample_register_response());
@@ -0,0 +1,546 @@
+//! Versioned catalog of controller actions.
+//!
+//! On the wire an action is still `{ action_type, payload }` (see
+//! `beach_buggy::ActionCommand`); this module is the single definition of which
+//! types exist, what their payloads look like, and how terminal hosts turn them
+//! into PTY input. The manager validates with [`ControllerAction::parse`] before
+//! queueing, and hosts decode with the same function before applying.
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::keys;
+
+/// Bumped whenever an action type is added or a payload changes shape.
+pub const ACTION_CATALOG_VERSION: u32 = 1;
+
+/// Prefix for per-action entries in `SessionDescriptor::capabilities`.
+pub const ACTION_CAPABILITY_PREFIX: &str = "action:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    TerminalWrite,
+    Key,
+    Resize,
+    Signal,
+    Clear,
+    Paste,
+}
+
+impl ActionKind {
+    pub const ALL: [ActionKind; 6] = [
+        ActionKind::TerminalWrite,
+        ActionKind::Key,
+        ActionKind::Resize,
+        ActionKind::Signal,
+        ActionKind::Clear,
+        ActionKind::Paste,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionKind::TerminalWrite => "terminal_write",
+            ActionKind::Key => "key",
+            ActionKind::Resize => "resize",
+            ActionKind::Signal => "signal",
+            ActionKind::Clear => "clear",
+            ActionKind::Paste => "paste",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// Capability string a harness advertises to accept this action.
+    pub fn capability(self) -> String {
+        format!("{ACTION_CAPABILITY_PREFIX}{}", self.as_str())
+    }
+
+    /// Action kinds a harness accepts given its advertised capabilities.
+    ///
+    /// Harnesses that predate the catalog advertise no `action:` entries and
+    /// keep accepting every action type.
+    pub fn supported_by(capabilities: &[String]) -> Vec<ActionKind> {
+        let advertised: Vec<ActionKind> = capabilities
+            .iter()
+            .filter_map(|cap| cap.strip_prefix(ACTION_CAPABILITY_PREFIX))
+            .filter_map(ActionKind::parse)
+            .collect();
+        let opted_in = capabilities
+            .iter()
+            .any(|cap| cap.starts_with(ACTION_CAPABILITY_PREFIX));
+        if opted_in {
+            advertised
+        } else {
+            Self::ALL.to_vec()
+        }
+    }
+
+    /// Capability list for a harness that implements every catalog action.
+    pub fn all_capabilities() -> Vec<String> {
+        Self::ALL.into_iter().map(ActionKind::capability).collect()
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyModifier {
+    Ctrl,
+    Alt,
+    Shift,
+    Meta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PtySignal {
+    Sigint,
+    Sigterm,
+}
+
+/// Named keys accepted by [`ControllerAction::Key`]; anything else must be a
+/// single character.
+pub const NAMED_KEYS: &[&str] = &[
+    "enter",
+    "tab",
+    "escape",
+    "backspace",
+    "delete",
+    "insert",
+    "space",
+    "up",
+    "down",
+    "left",
+    "right",
+    "home",
+    "end",
+    "page_up",
+    "page_down",
+    "f1",
+    "f2",
+    "f3",
+    "f4",
+    "f5",
+    "f6",
+    "f7",
+    "f8",
+    "f9",
+    "f10",
+    "f11",
+    "f12",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControllerAction {
+    /// Raw input written to the PTY as-is.
+    TerminalWrite {
+        bytes: String,
+    },
+    /// A key chord such as `ctrl+c` or `shift+tab`.
+    Key {
+        key: String,
+        modifiers: Vec<KeyModifier>,
+    },
+    Resize {
+        cols: u16,
+        rows: u16,
+    },
+    /// Delivered to the PTY child process.
+    Signal {
+        signal: PtySignal,
+    },
+    /// Asks the foreground program to redraw a clean screen.
+    Clear,
+    Paste {
+        text: String,
+        bracketed: bool,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TerminalWritePayload {
+    bytes: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyPayload {
+    key: String,
+    #[serde(default)]
+    modifiers: Vec<KeyModifier>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResizePayload {
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignalPayload {
+    signal: PtySignal,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClearPayload {}
+
+fn default_bracketed() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PastePayload {
+    text: String,
+    #[serde(default = "default_bracketed")]
+    bracketed: bool,
+}
+
+impl ControllerAction {
+    /// Parses and validates an `{ action_type, payload }` pair against the catalog.
+    pub fn parse(action_type: &str, payload: &Value) -> Result<Self, ActionValidationError> {
+        let kind = ActionKind::parse(action_type)
+            .ok_or_else(|| ActionValidationError::UnknownType(action_type.to_string()))?;
+        let invalid = |message: String| ActionValidationError::InvalidPayload { kind, message };
+        let action = match kind {
+            ActionKind::TerminalWrite => {
+                let p: TerminalWritePayload = decode(kind, payload)?;
+                ControllerAction::TerminalWrite { bytes: p.bytes }
+            }
+            ActionKind::Key => {
+                let p: KeyPayload = decode(kind, payload)?;
+                let key = p.key.trim().to_ascii_lowercase();
+                let single_char = p.key.chars().count() == 1;
+                if !single_char && !NAMED_KEYS.contains(&key.as_str()) {
+                    return Err(invalid(format!("unknown key '{}'", p.key)));
+                }
+                let mut modifiers = p.modifiers;
+                modifiers.sort_by_key(|m| *m as u8);
+                modifiers.dedup();
+                ControllerAction::Key {
+                    key: if single_char { p.key } else { key },
+                    modifiers,
+                }
+            }
+            ActionKind::Resize => {
+                let p: ResizePayload = decode(kind, payload)?;
+                if p.cols == 0 || p.rows == 0 {
+                    return Err(invalid("cols and rows must be non-zero".into()));
+                }
+                ControllerAction::Resize {
+                    cols: p.cols,
+                    rows: p.rows,
+                }
+            }
+            ActionKind::Signal => {
+                let p: SignalPayload = decode(kind, payload)?;
+                ControllerAction::Signal { signal: p.signal }
+            }
+            ActionKind::Clear => {
+                if !payload.is_null() {
+                    let _: ClearPayload = decode(kind, payload)?;
+                }
+                ControllerAction::Clear
+            }
+            ActionKind::Paste => {
+                let p: PastePayload = decode(kind, payload)?;
+                if p.text.is_empty() {
+                    return Err(invalid("text must not be empty".into()));
+                }
+                ControllerAction::Paste {
+                    text: p.text,
+                    bracketed: p.bracketed,
+                }
+            }
+        };
+        Ok(action)
+    }
+
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            ControllerAction::TerminalWrite { .. } => ActionKind::TerminalWrite,
+            ControllerAction::Key { .. } => ActionKind::Key,
+            ControllerAction::Resize { .. } => ActionKind::Resize,
+            ControllerAction::Signal { .. } => ActionKind::Signal,
+            ControllerAction::Clear => ActionKind::Clear,
+            ControllerAction::Paste { .. } => ActionKind::Paste,
+        }
+    }
+
+    /// Wire payload for this action; [`ControllerAction::parse`] accepts it back.
+    pub fn payload(&self) -> Value {
+        match self {
+            ControllerAction::TerminalWrite { bytes } => json!({ "bytes": bytes }),
+            ControllerAction::Key { key, modifiers } => {
+                json!({ "key": key, "modifiers": modifiers })
+            }
+            ControllerAction::Resize { cols, rows } => json!({ "cols": cols, "rows": rows }),
+            ControllerAction::Signal { signal } => json!({ "signal": signal }),
+            ControllerAction::Clear => Value::Null,
+            ControllerAction::Paste { text, bracketed } => {
+                json!({ "text": text, "bracketed": bracketed })
+            }
+        }
+    }
+
+    /// PTY input for actions expressible as bytes; `None` for resize and signal,
+    /// which need the host's process handle.
+    pub fn pty_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            ControllerAction::TerminalWrite { bytes } => Some(bytes.as_bytes().to_vec()),
+            ControllerAction::Key { key, modifiers } => Some(encode_key(key, modifiers)),
+            ControllerAction::Clear => Some(vec![0x0c]),
+            ControllerAction::Paste { text, bracketed } => {
+                let mut out = Vec::with_capacity(text.len() + 12);
+                if *bracketed {
+                    out.extend_from_slice(b"\x1b[200~");
+                }
+                out.extend_from_slice(text.as_bytes());
+                if *bracketed {
+                    out.extend_from_slice(b"\x1b[201~");
+                }
+                Some(out)
+            }
+            ControllerAction::Resize { .. } | ControllerAction::Signal { .. } => None,
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(
+    kind: ActionKind,
+    payload: &Value,
+) -> Result<T, ActionValidationError> {
+    serde_json::from_value(payload.clone()).map_err(|err| ActionValidationError::InvalidPayload {
+        kind,
+        message: err.to_string(),
+    })
+}
+
+/// Encodes a validated key chord the way the terminal client encodes the same
+/// keystroke, assuming normal cursor mode.
+fn encode_key(key: &str, modifiers: &[KeyModifier]) -> Vec<u8> {
+    let has = |m: KeyModifier| modifiers.contains(&m);
+    let shift = has(KeyModifier::Shift);
+    let ctrl = has(KeyModifier::Ctrl);
+    let alt = has(KeyModifier::Alt) || has(KeyModifier::Meta);
+    let param = keys::modifier_param(shift, alt, ctrl, false);
+    let function_key = key
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|_| key.len() > 1);
+    if let Some(n) = function_key {
+        if let Some(bytes) = keys::function_key(n, param) {
+            return bytes;
+        }
+    }
+    let mut out = match key {
+        "up" => return keys::cursor_key(b'A', param, false),
+        "down" => return keys::cursor_key(b'B', param, false),
+        "right" => return keys::cursor_key(b'C', param, false),
+        "left" => return keys::cursor_key(b'D', param, false),
+        "home" => return keys::cursor_key(b'H', param, false),
+        "end" => return keys::cursor_key(b'F', param, false),
+        "insert" => return keys::tilde_key(2, param),
+        "delete" => return keys::tilde_key(3, param),
+        "page_up" => return keys::tilde_key(5, param),
+        "page_down" => return keys::tilde_key(6, param),
+        "tab" if shift => b"\x1b[Z".to_vec(),
+        "enter" => b"\r".to_vec(),
+        "tab" => b"\t".to_vec(),
+        "escape" => vec![keys::ESC],
+        "backspace" => b"\x7f".to_vec(),
+        "space" if ctrl => vec![0x00],
+        "space" => b" ".to_vec(),
+        ch => {
+            let mut ch = ch.to_string();
+            if shift {
+                ch = ch.to_uppercase();
+            }
+            let mut chars = ch.chars();
+            match (chars.next().and_then(keys::control_byte), chars.next()) {
+                (Some(byte), None) if ctrl => vec![byte],
+                _ => ch.into_bytes(),
+            }
+        }
+    };
+    if alt {
+        out.insert(0, keys::ESC);
+    }
+    out
+}
+
+/// Why an action was rejected; `code` is stable for API clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionValidationError {
+    UnknownType(String),
+    InvalidPayload { kind: ActionKind, message: String },
+    Unsupported(ActionKind),
+}
+
+impl ActionValidationError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ActionValidationError::UnknownType(_) => "unknown_action_type",
+            ActionValidationError::InvalidPayload { .. } => "invalid_action_payload",
+            ActionValidationError::Unsupported(_) => "action_not_supported",
+        }
+    }
+}
+
+impl fmt::Display for ActionValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionValidationError::UnknownType(action_type) => {
+                write!(f, "unknown action type '{action_type}'")
+            }
+            ActionValidationError::InvalidPayload { kind, message } => {
+                write!(f, "invalid {kind} payload: {message}")
+            }
+            ActionValidationError::Unsupported(kind) => {
+                write!(f, "harness does not accept {kind} actions")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionValidationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_catalog_payloads_and_rejects_bad_ones() {
+        assert_eq!(
+            ControllerAction::parse("terminal_write", &json!({ "bytes": "ls\r" })).unwrap(),
+            ControllerAction::TerminalWrite {
+                bytes: "ls\r".into()
+            }
+        );
+        assert_eq!(
+            ControllerAction::parse("clear", &Value::Null).unwrap(),
+            ControllerAction::Clear
+        );
+        assert_eq!(
+            ControllerAction::parse("signal", &json!({ "signal": "SIGTERM" })).unwrap(),
+            ControllerAction::Signal {
+                signal: PtySignal::Sigterm
+            }
+        );
+
+        let err = ControllerAction::parse("launch_missiles", &json!({})).unwrap_err();
+        assert_eq!(err.code(), "unknown_action_type");
+        for (action_type, payload) in [
+            ("terminal_write", json!({ "text": "ls" })),
+            ("resize", json!({ "cols": 0, "rows": 24 })),
+            ("key", json!({ "key": "hyper" })),
+            ("signal", json!({ "signal": "SIGKILL" })),
+            ("paste", json!({ "text": "" })),
+        ] {
+            let err = ControllerAction::parse(action_type, &payload).unwrap_err();
+            assert_eq!(err.code(), "invalid_action_payload", "{action_type}");
+        }
+    }
+
+    #[test]
+    fn encodes_key_chords_and_paste() {
+        let bytes = |action_type: &str, payload: Value| {
+            ControllerAction::parse(action_type, &payload)
+                .unwrap()
+                .pty_bytes()
+                .unwrap()
+        };
+        assert_eq!(
+            bytes("key", json!({ "key": "c", "modifiers": ["ctrl"] })),
+            vec![0x03]
+        );
+        assert_eq!(
+            bytes("key", json!({ "key": "Up", "modifiers": ["ctrl"] })),
+            b"\x1b[1;5A".to_vec()
+        );
+        assert_eq!(
+            bytes("key", json!({ "key": "x", "modifiers": ["alt"] })),
+            b"\x1bx".to_vec()
+        );
+        assert_eq!(bytes("key", json!({ "key": "F1" })), b"\x1bOP".to_vec());
+        assert_eq!(
+            bytes("key", json!({ "key": "f4", "modifiers": ["ctrl"] })),
+            b"\x1b[1;5S".to_vec()
+        );
+        assert_eq!(bytes("key", json!({ "key": "f12" })), b"\x1b[24~".to_vec());
+        assert_eq!(
+            bytes("key", json!({ "key": "tab", "modifiers": ["shift"] })),
+            b"\x1b[Z".to_vec()
+        );
+        assert_eq!(
+            bytes("paste", json!({ "text": "hi" })),
+            b"\x1b[200~hi\x1b[201~".to_vec()
+        );
+        assert!(
+            ControllerAction::parse("resize", &json!({ "cols": 80, "rows": 24 }))
+                .unwrap()
+                .pty_bytes()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn payload_round_trips_through_parse() {
+        let actions = [
+            ControllerAction::TerminalWrite {
+                bytes: "echo hi\r".into(),
+            },
+            ControllerAction::Key {
+                key: "c".into(),
+                modifiers: vec![KeyModifier::Ctrl],
+            },
+            ControllerAction::Resize {
+                cols: 120,
+                rows: 40,
+            },
+            ControllerAction::Signal {
+                signal: PtySignal::Sigint,
+            },
+            ControllerAction::Clear,
+            ControllerAction::Paste {
+                text: "hello".into(),
+                bracketed: false,
+            },
+        ];
+        for action in actions {
+            let parsed = ControllerAction::parse(action.kind().as_str(), &action.payload())
+                .expect("payload parses");
+            assert_eq!(parsed, action);
+        }
+    }
+
+    #[test]
+    fn capability_gating_defaults_to_all_for_legacy_harnesses() {
+        assert_eq!(
+            ActionKind::supported_by(&["terminal_diff_v1".into()]),
+            ActionKind::ALL.to_vec()
+        );
+        assert_eq!(
+            ActionKind::supported_by(&[
+                "terminal_diff_v1".into(),
+                ActionKind::Key.capability(),
+                "action:unknown".into(),
+            ]),
+            vec![ActionKind::Key]
+        );
+    }
+}
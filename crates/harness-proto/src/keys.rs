@@ -0,0 +1,72 @@
+//! xterm key encoding shared by the terminal client and controller actions.
+//!
+//! Hosts advertise `TERM=xterm-256color`, so both human keystrokes and
+//! [`crate::actions::ControllerAction::Key`] chords must produce the bytes that
+//! terminfo entry describes: SS3 for unmodified cursor (in DECCKM) and F1–F4
+//! keys, `CSI 1;<mod>` once a modifier is held, and `CSI <n>;<mod>~` for the
+//! editing and remaining function keys.
+
+pub const ESC: u8 = 0x1b;
+
+/// xterm modifier parameter (`1 + Shift + 2·Alt + 4·Ctrl + 8·Meta`), or `None`
+/// when no modifier is held.
+pub fn modifier_param(shift: bool, alt: bool, ctrl: bool, meta: bool) -> Option<u8> {
+    let value = 1 + u8::from(shift) + 2 * u8::from(alt) + 4 * u8::from(ctrl) + 8 * u8::from(meta);
+    (value > 1).then_some(value)
+}
+
+/// Arrow, Home and End keys; `app_cursor` is DECCKM.
+pub fn cursor_key(final_byte: u8, param: Option<u8>, app_cursor: bool) -> Vec<u8> {
+    match param {
+        Some(param) => format!("\x1b[1;{param}{}", final_byte as char).into_bytes(),
+        None if app_cursor => vec![ESC, b'O', final_byte],
+        None => vec![ESC, b'[', final_byte],
+    }
+}
+
+/// Editing and function keys of the `CSI <code> ~` family.
+pub fn tilde_key(code: u8, param: Option<u8>) -> Vec<u8> {
+    match param {
+        Some(param) => format!("\x1b[{code};{param}~").into_bytes(),
+        None => format!("\x1b[{code}~").into_bytes(),
+    }
+}
+
+/// F1–F24; F13–F24 are F1–F12 with Shift added, as in xterm-256color.
+pub fn function_key(n: u8, param: Option<u8>) -> Option<Vec<u8>> {
+    let (base, param) = match n {
+        1..=12 => (n, param),
+        13..=24 => (n - 12, Some(((param.unwrap_or(1) - 1) | 1) + 1)),
+        _ => return None,
+    };
+    let bytes = match base {
+        1..=4 => {
+            let final_byte = b"PQRS"[usize::from(base - 1)];
+            match param {
+                Some(param) => format!("\x1b[1;{param}{}", final_byte as char).into_bytes(),
+                None => vec![ESC, b'O', final_byte],
+            }
+        }
+        _ => {
+            const CODES: [u8; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            tilde_key(CODES[usize::from(base - 5)], param)
+        }
+    };
+    Some(bytes)
+}
+
+/// C0 byte xterm sends for Ctrl+`c`.
+pub fn control_byte(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 1),
+        ' ' | '@' | '2' => Some(0x00),
+        '[' | '3' => Some(0x1b),
+        '\\' | '4' => Some(0x1c),
+        ']' | '5' => Some(0x1d),
+        '^' | '~' | '6' => Some(0x1e),
+        '_' | '/' | '7' => Some(0x1f),
+        '?' | '8' => Some(0x7f),
+        _ => None,
+    }
+}
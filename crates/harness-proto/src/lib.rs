@@ -2,6 +2,9 @@
 //! Keeping this in a dedicated crate allows regeneration of bindings
 //! for TypeScript/Go/etc. without pulling in heavier runtime code.
 
+pub mod actions;
+pub mod keys;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -1,22 +1,46 @@
 //! Asynchronous client for Beach Manager APIs.
 //!
-//! This crate will be consumed by CLI tools, automation agents, and tests.
-//! It should provide ergonomic wrappers around REST/MCP endpoints while
-//! abstracting auth token handling and retry logic.
+//! Consumed by CLI tools, automation agents, and tests. [`ManagerClient`]
+//! wraps the REST surface with the typed bodies in [`models`], refreshes the
+//! bearer token once on `401`, and retries transient failures per
+//! [`RetryPolicy`]. SSE endpoints are exposed as [`futures::Stream`]s.
 
-use reqwest::Client;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{stream, Stream, StreamExt};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
+use reqwest_eventsource::{Event, EventSource};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use thiserror::Error;
 use uuid::Uuid;
 
 pub mod assignment;
 pub mod assignment_store;
+pub mod models;
+pub mod token;
+
+use models::{
+    ActionAck, ActionCommand, AttachByCodeRequest, AttachByCodeResponse, AttachOwnedRequest,
+    AttachOwnedResponse, BeachMeta, BeachSummary, CanvasLayout, ControllerAction,
+    ControllerLeaseRequest, ControllerLeaseResponse, ControllerPairing, ControllerPairingEvent,
+    CreateBeachRequest, CreateControllerPairingRequest, PendingActionsResponse,
+    QueueActionsRequest, ReleaseControllerRequest, SessionFilter, SessionSummary,
+    SessionTagRequest, SessionTagsResponse, StateDiff, UpdateBeachRequest,
+};
+pub use token::{RefreshingToken, StaticToken, TokenSource};
+
+/// Items yielded by the SSE helpers.
+pub type ManagerStream<T> = Pin<Box<dyn Stream<Item = Result<T, ManagerError>> + Send>>;
 
 #[derive(Clone)]
 pub struct ManagerClient {
     http: Client,
     base_url: String,
-    token: String,
+    tokens: Arc<dyn TokenSource>,
+    retry: RetryPolicy,
 }
 
 #[derive(Debug, Error)]
@@ -28,33 +52,753 @@ pub enum ManagerError {
         status: reqwest::StatusCode,
         body: String,
     },
+    #[error("invalid manager url: {0}")]
+    InvalidUrl(String),
+    #[error("decode response: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("token unavailable: {0}")]
+    Token(String),
+    #[error("event stream failed: {0}")]
+    Stream(String),
+}
+
+impl ManagerError {
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ManagerError::UnexpectedStatus { status, .. } => Some(*status),
+            ManagerError::Http(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// Stable `error_code` from the manager's error body, when it sent one.
+    pub fn error_code(&self) -> Option<String> {
+        let ManagerError::UnexpectedStatus { body, .. } = self else {
+            return None;
+        };
+        serde_json::from_str::<serde_json::Value>(body)
+            .ok()?
+            .get("error_code")?
+            .as_str()
+            .map(str::to_string)
+    }
+}
+
+/// Backoff applied to transient failures.
+///
+/// Connection failures and `429` are retried for every call. `502`/`503`/`504`
+/// are only retried for idempotent calls, so actions are never queued twice.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; `1` disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1u32 << attempt.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+fn retryable_status(status: StatusCode, idempotent: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (idempotent
+            && matches!(
+                status,
+                StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ))
 }
 
 impl ManagerClient {
     pub fn new(base_url: impl Into<String>, token: impl Into<String>) -> Self {
+        Self::with_token_source(base_url, StaticToken::new(token))
+    }
+
+    pub fn with_token_source(base_url: impl Into<String>, tokens: impl TokenSource) -> Self {
         Self {
             http: Client::new(),
             base_url: base_url.into(),
-            token: token.into(),
+            tokens: Arc::new(tokens),
+            retry: RetryPolicy::default(),
         }
     }
 
-    pub async fn list_sessions<T>(&self, private_beach_id: Uuid) -> Result<T, ManagerError>
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_http_client(mut self, http: Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Private beaches
+    // ------------------------------------------------------------------------- //
+
+    pub async fn list_private_beaches(&self) -> Result<Vec<BeachSummary>, ManagerError> {
+        self.get_json(&["private-beaches"]).await
+    }
+
+    pub async fn create_private_beach(
+        &self,
+        request: &CreateBeachRequest,
+    ) -> Result<BeachSummary, ManagerError> {
+        self.send_json(Method::POST, &["private-beaches"], request, false)
+            .await
+    }
+
+    pub async fn get_private_beach(
+        &self,
+        private_beach_id: &str,
+    ) -> Result<BeachMeta, ManagerError> {
+        self.get_json(&["private-beaches", private_beach_id]).await
+    }
+
+    pub async fn update_private_beach(
+        &self,
+        private_beach_id: &str,
+        request: &UpdateBeachRequest,
+    ) -> Result<BeachMeta, ManagerError> {
+        self.send_json(
+            Method::PATCH,
+            &["private-beaches", private_beach_id],
+            request,
+            true,
+        )
+        .await
+    }
+
+    pub async fn delete_private_beach(&self, private_beach_id: &str) -> Result<(), ManagerError> {
+        let url = self.url(&["private-beaches", private_beach_id])?;
+        self.execute(|| self.http.delete(url.clone()), true).await?;
+        Ok(())
+    }
+
+    pub async fn get_layout(&self, private_beach_id: &str) -> Result<CanvasLayout, ManagerError> {
+        self.get_json(&["private-beaches", private_beach_id, "layout"])
+            .await
+    }
+
+    pub async fn put_layout(
+        &self,
+        private_beach_id: &str,
+        layout: &CanvasLayout,
+    ) -> Result<CanvasLayout, ManagerError> {
+        self.send_json(
+            Method::PUT,
+            &["private-beaches", private_beach_id, "layout"],
+            layout,
+            true,
+        )
+        .await
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Sessions
+    // ------------------------------------------------------------------------- //
+
+    pub async fn list_sessions(
+        &self,
+        private_beach_id: &str,
+    ) -> Result<Vec<SessionSummary>, ManagerError> {
+        self.list_sessions_filtered(private_beach_id, &SessionFilter::default())
+            .await
+    }
+
+    pub async fn list_sessions_filtered(
+        &self,
+        private_beach_id: &str,
+        filter: &SessionFilter,
+    ) -> Result<Vec<SessionSummary>, ManagerError> {
+        let url = self.url(&["private-beaches", private_beach_id, "sessions"])?;
+        let res = self
+            .execute(|| self.http.get(url.clone()).query(filter), true)
+            .await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn attach_by_code(
+        &self,
+        private_beach_id: &str,
+        session_id: &str,
+        code: &str,
+    ) -> Result<SessionSummary, ManagerError> {
+        let request = AttachByCodeRequest {
+            session_id: session_id.to_string(),
+            code: code.to_string(),
+        };
+        let response: AttachByCodeResponse = self
+            .send_json(
+                Method::POST,
+                &[
+                    "private-beaches",
+                    private_beach_id,
+                    "sessions",
+                    "attach-by-code",
+                ],
+                &request,
+                true,
+            )
+            .await?;
+        Ok(response.session)
+    }
+
+    pub async fn attach_owned(
+        &self,
+        private_beach_id: &str,
+        origin_session_ids: Vec<String>,
+    ) -> Result<AttachOwnedResponse, ManagerError> {
+        self.send_json(
+            Method::POST,
+            &["private-beaches", private_beach_id, "sessions", "attach"],
+            &AttachOwnedRequest { origin_session_ids },
+            true,
+        )
+        .await
+    }
+
+    pub async fn add_session_tag(
+        &self,
+        session_id: &str,
+        tag: &str,
+    ) -> Result<Vec<String>, ManagerError> {
+        let response: SessionTagsResponse = self
+            .send_json(
+                Method::POST,
+                &["sessions", session_id, "tags"],
+                &SessionTagRequest {
+                    tag: tag.to_string(),
+                },
+                true,
+            )
+            .await?;
+        Ok(response.tags)
+    }
+
+    pub async fn remove_session_tag(
+        &self,
+        session_id: &str,
+        tag: &str,
+    ) -> Result<Vec<String>, ManagerError> {
+        let url = self.url(&["sessions", session_id, "tags", tag])?;
+        let res = self.execute(|| self.http.delete(url.clone()), true).await?;
+        Ok(res.json::<SessionTagsResponse>().await?.tags)
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Controller leases and actions
+    // ------------------------------------------------------------------------- //
+
+    pub async fn acquire_controller(
+        &self,
+        session_id: &str,
+        request: &ControllerLeaseRequest,
+    ) -> Result<ControllerLeaseResponse, ManagerError> {
+        self.send_json(
+            Method::POST,
+            &["sessions", session_id, "controller", "lease"],
+            request,
+            true,
+        )
+        .await
+    }
+
+    pub async fn release_controller(
+        &self,
+        session_id: &str,
+        controller_token: &str,
+    ) -> Result<(), ManagerError> {
+        let url = self.url(&["sessions", session_id, "controller", "lease"])?;
+        let body = ReleaseControllerRequest {
+            controller_token: controller_token.to_string(),
+        };
+        self.execute(|| self.http.delete(url.clone()).json(&body), true)
+            .await?;
+        Ok(())
+    }
+
+    /// Queues raw `{ action_type, payload }` commands; the manager validates them
+    /// against the action catalog and rejects the whole batch on the first error.
+    pub async fn queue_actions(
+        &self,
+        session_id: &str,
+        controller_token: &str,
+        actions: Vec<ActionCommand>,
+    ) -> Result<(), ManagerError> {
+        let url = self.url(&["sessions", session_id, "actions"])?;
+        let body = QueueActionsRequest {
+            controller_token: controller_token.to_string(),
+            actions,
+        };
+        self.execute(|| self.http.post(url.clone()).json(&body), false)
+            .await?;
+        Ok(())
+    }
+
+    /// Typed variant of [`ManagerClient::queue_actions`]. Returns the generated
+    /// command ids in order so callers can match acks.
+    pub async fn queue_controller_actions(
+        &self,
+        session_id: &str,
+        controller_token: &str,
+        actions: &[ControllerAction],
+    ) -> Result<Vec<String>, ManagerError> {
+        let commands: Vec<ActionCommand> = actions
+            .iter()
+            .map(|action| ActionCommand {
+                id: Uuid::new_v4().to_string(),
+                action_type: action.kind().as_str().to_string(),
+                payload: action.payload(),
+                expires_at: None,
+            })
+            .collect();
+        let ids = commands.iter().map(|command| command.id.clone()).collect();
+        self.queue_actions(session_id, controller_token, commands)
+            .await?;
+        Ok(ids)
+    }
+
+    /// Drains queued actions for a harness. Pass `controller_token` when the
+    /// bearer token lacks `pb:control.consume`.
+    pub async fn poll_actions(
+        &self,
+        session_id: &str,
+        controller_token: Option<&str>,
+    ) -> Result<Vec<ActionCommand>, ManagerError> {
+        let url = self.url(&["sessions", session_id, "actions", "poll"])?;
+        let res = self
+            .execute(
+                || with_controller_token(self.http.get(url.clone()), controller_token),
+                false,
+            )
+            .await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn pending_actions(
+        &self,
+        session_id: &str,
+        controller_token: Option<&str>,
+    ) -> Result<PendingActionsResponse, ManagerError> {
+        let url = self.url(&["sessions", session_id, "actions", "pending"])?;
+        let res = self
+            .execute(
+                || with_controller_token(self.http.get(url.clone()), controller_token),
+                true,
+            )
+            .await?;
+        Ok(res.json().await?)
+    }
+
+    pub async fn ack_actions(
+        &self,
+        session_id: &str,
+        controller_token: Option<&str>,
+        acks: &[ActionAck],
+    ) -> Result<(), ManagerError> {
+        let url = self.url(&["sessions", session_id, "actions", "ack"])?;
+        self.execute(
+            || with_controller_token(self.http.post(url.clone()), controller_token).json(acks),
+            true,
+        )
+        .await?;
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Controller pairings
+    // ------------------------------------------------------------------------- //
+
+    pub async fn list_controller_pairings(
+        &self,
+        controller_session_id: &str,
+    ) -> Result<Vec<ControllerPairing>, ManagerError> {
+        self.get_json(&["sessions", controller_session_id, "controllers"])
+            .await
+    }
+
+    pub async fn create_controller_pairing(
+        &self,
+        controller_session_id: &str,
+        request: &CreateControllerPairingRequest,
+    ) -> Result<ControllerPairing, ManagerError> {
+        self.send_json(
+            Method::POST,
+            &["sessions", controller_session_id, "controllers"],
+            request,
+            true,
+        )
+        .await
+    }
+
+    pub async fn delete_controller_pairing(
+        &self,
+        controller_session_id: &str,
+        child_session_id: &str,
+    ) -> Result<(), ManagerError> {
+        let url = self.url(&[
+            "sessions",
+            controller_session_id,
+            "controllers",
+            child_session_id,
+        ])?;
+        self.execute(|| self.http.delete(url.clone()), true).await?;
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Streams
+    // ------------------------------------------------------------------------- //
+
+    /// State diffs pushed by the session's harness.
+    pub fn stream_state(&self, session_id: &str) -> Result<ManagerStream<StateDiff>, ManagerError> {
+        let url = self.url(&["sessions", session_id, "state", "stream"])?;
+        Ok(self.subscribe(url, "state"))
+    }
+
+    /// Pairing changes for a controller session.
+    pub fn stream_controller_pairings(
+        &self,
+        controller_session_id: &str,
+    ) -> Result<ManagerStream<ControllerPairingEvent>, ManagerError> {
+        let url = self.url(&["sessions", controller_session_id, "controllers", "stream"])?;
+        Ok(self.subscribe(url, "controller_pairing"))
+    }
+
+    /// Decodes `event` messages from an SSE endpoint.
+    ///
+    /// The underlying source reconnects on transport errors, which are yielded
+    /// without ending the stream. A `401` refreshes the token and reconnects
+    /// once; any other HTTP error is yielded and ends the stream.
+    fn subscribe<T>(&self, url: Url, event: &'static str) -> ManagerStream<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        struct Subscription {
+            client: ManagerClient,
+            url: Url,
+            event: &'static str,
+            source: Option<EventSource>,
+            refresh: bool,
+            auth_retried: bool,
+            done: bool,
+        }
+
+        let subscription = Subscription {
+            client: self.clone(),
+            url,
+            event,
+            source: None,
+            refresh: false,
+            auth_retried: false,
+            done: false,
+        };
+
+        Box::pin(stream::unfold(subscription, |mut sub| async move {
+            loop {
+                if sub.done {
+                    return None;
+                }
+                if sub.source.is_none() {
+                    let token = if std::mem::take(&mut sub.refresh) {
+                        sub.client.tokens.refresh().await
+                    } else {
+                        sub.client.tokens.token().await
+                    };
+                    let token = match token {
+                        Ok(token) => token,
+                        Err(err) => {
+                            sub.done = true;
+                            return Some((Err(err), sub));
+                        }
+                    };
+                    let request = sub.client.http.get(sub.url.clone()).bearer_auth(token);
+                    match EventSource::new(request) {
+                        Ok(source) => sub.source = Some(source),
+                        Err(err) => {
+                            sub.done = true;
+                            return Some((Err(ManagerError::Stream(err.to_string())), sub));
+                        }
+                    }
+                }
+                let source = sub.source.as_mut().expect("event source connected");
+                match source.next().await {
+                    None => return None,
+                    Some(Ok(Event::Open)) => sub.auth_retried = false,
+                    Some(Ok(Event::Message(msg))) if msg.event == sub.event => {
+                        let item = serde_json::from_str::<T>(&msg.data).map_err(ManagerError::from);
+                        return Some((item, sub));
+                    }
+                    Some(Ok(Event::Message(_))) => {}
+                    Some(Err(reqwest_eventsource::Error::InvalidStatusCode(status, _)))
+                        if status == StatusCode::UNAUTHORIZED && !sub.auth_retried =>
+                    {
+                        source.close();
+                        sub.source = None;
+                        sub.refresh = true;
+                        sub.auth_retried = true;
+                    }
+                    Some(Err(reqwest_eventsource::Error::InvalidStatusCode(status, response))) => {
+                        source.close();
+                        sub.done = true;
+                        let body = response.text().await.unwrap_or_default();
+                        return Some((Err(ManagerError::UnexpectedStatus { status, body }), sub));
+                    }
+                    Some(Err(reqwest_eventsource::Error::StreamEnded)) => {}
+                    Some(Err(err)) => {
+                        return Some((Err(ManagerError::Stream(err.to_string())), sub));
+                    }
+                }
+            }
+        }))
+    }
+
+    // ------------------------------------------------------------------------- //
+    // Plumbing
+    // ------------------------------------------------------------------------- //
+
+    fn url(&self, segments: &[&str]) -> Result<Url, ManagerError> {
+        let mut url = Url::parse(&self.base_url)
+            .map_err(|err| ManagerError::InvalidUrl(format!("{}: {err}", self.base_url)))?;
+        url.path_segments_mut()
+            .map_err(|_| ManagerError::InvalidUrl(self.base_url.clone()))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn get_json<T>(&self, segments: &[&str]) -> Result<T, ManagerError>
     where
         T: DeserializeOwned,
     {
-        let url = format!(
-            "{}/private-beaches/{}/sessions",
-            self.base_url, private_beach_id
-        );
-        let res = self.http.get(url).bearer_auth(&self.token).send().await?;
-
-        if res.status().is_success() {
-            Ok(res.json::<T>().await?)
-        } else {
-            let status = res.status();
-            let body = res.text().await.unwrap_or_default();
-            Err(ManagerError::UnexpectedStatus { status, body })
+        let url = self.url(segments)?;
+        let res = self.execute(|| self.http.get(url.clone()), true).await?;
+        Ok(res.json::<T>().await?)
+    }
+
+    async fn send_json<B, T>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        body: &B,
+        idempotent: bool,
+    ) -> Result<T, ManagerError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        let url = self.url(segments)?;
+        let res = self
+            .execute(
+                || self.http.request(method.clone(), url.clone()).json(body),
+                idempotent,
+            )
+            .await?;
+        Ok(res.json::<T>().await?)
+    }
+
+    /// Sends the request built by `build` with auth, token refresh and retries,
+    /// returning the response only when it carries a success status.
+    async fn execute<F>(&self, build: F, idempotent: bool) -> Result<Response, ManagerError>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut token = self.tokens.token().await?;
+        let mut refreshed = false;
+        let mut attempt = 0;
+        loop {
+            let retries_left = attempt + 1 < self.retry.max_attempts;
+            match build().bearer_auth(&token).send().await {
+                Ok(res) if res.status().is_success() => return Ok(res),
+                Ok(res) if res.status() == StatusCode::UNAUTHORIZED && !refreshed => {
+                    refreshed = true;
+                    token = self.tokens.refresh().await?;
+                }
+                Ok(res) if retries_left && retryable_status(res.status(), idempotent) => {
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Ok(res) => {
+                    let status = res.status();
+                    let body = res.text().await.unwrap_or_default();
+                    return Err(ManagerError::UnexpectedStatus { status, body });
+                }
+                Err(err)
+                    if retries_left && (err.is_connect() || (idempotent && err.is_timeout())) =>
+                {
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
         }
     }
 }
+
+fn with_controller_token(
+    request: RequestBuilder,
+    controller_token: Option<&str>,
+) -> RequestBuilder {
+    match controller_token {
+        Some(token) => request.query(&[("controller_token", token)]),
+        None => request,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode as AxumStatus},
+        response::sse::{Event as SseEvent, Sse},
+        routing::get,
+        Json, Router,
+    };
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        format!("http://{addr}/")
+    }
+
+    fn bearer(headers: &HeaderMap) -> String {
+        headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .trim_start_matches("Bearer ")
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn refreshes_token_once_after_unauthorized() {
+        let router = Router::new().route(
+            "/private-beaches",
+            get(|headers: HeaderMap| async move {
+                if bearer(&headers) == "fresh" {
+                    Ok(Json(vec![BeachSummary {
+                        id: "pb-1".into(),
+                        name: "Dunes".into(),
+                        slug: "dunes".into(),
+                        created_at: 0,
+                    }]))
+                } else {
+                    Err(AxumStatus::UNAUTHORIZED)
+                }
+            }),
+        );
+        let base = serve(router).await;
+
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let counter = fetches.clone();
+        let tokens = RefreshingToken::new(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move { Ok(if n == 0 { "stale" } else { "fresh" }.to_string()) }
+        });
+        let client = ManagerClient::with_token_source(base, tokens);
+
+        let beaches = client.list_private_beaches().await.expect("list beaches");
+        assert_eq!(beaches.len(), 1);
+        assert_eq!(beaches[0].slug, "dunes");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_calls_on_unavailable() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let router = Router::new()
+            .route(
+                "/sessions/s-1/controllers",
+                get(|State(hits): State<Arc<AtomicUsize>>| async move {
+                    if hits.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(AxumStatus::SERVICE_UNAVAILABLE)
+                    } else {
+                        Ok(Json(Vec::<ControllerPairing>::new()))
+                    }
+                }),
+            )
+            .with_state(hits.clone());
+        let base = serve(router).await;
+        let client = ManagerClient::new(base, "token").with_retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        });
+
+        let pairings = client.list_controller_pairings("s-1").await.unwrap();
+        assert!(pairings.is_empty());
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let err = client
+            .with_retry_policy(RetryPolicy::none())
+            .list_controller_pairings("missing")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn streams_named_sse_events() {
+        let router = Router::new().route(
+            "/sessions/ctrl/controllers/stream",
+            get(|| async {
+                let event = ControllerPairingEvent {
+                    controller_session_id: "ctrl".into(),
+                    child_session_id: "child".into(),
+                    action: models::ControllerPairingAction::Added,
+                    pairing: None,
+                };
+                let events = vec![
+                    Ok::<_, std::convert::Infallible>(
+                        SseEvent::default().event("state").data("{}"),
+                    ),
+                    Ok(SseEvent::default()
+                        .event("controller_pairing")
+                        .data(serde_json::to_string(&event).unwrap())),
+                ];
+                Sse::new(stream::iter(events))
+            }),
+        );
+        let base = serve(router).await;
+        let client = ManagerClient::new(base, "token");
+
+        let mut events = client.stream_controller_pairings("ctrl").unwrap();
+        let first = events.next().await.expect("event").expect("decoded");
+        assert_eq!(first.child_session_id, "child");
+        assert_eq!(first.action, models::ControllerPairingAction::Added);
+    }
+}
@@ -0,0 +1,427 @@
+//! Request and response bodies for the manager REST surface.
+//!
+//! `beach-manager` serves these exact types, so a field added here is picked up
+//! by both sides. Payloads that are shared with harnesses (actions, acks,
+//! diffs, pairings) come from `beach-buggy` and are re-exported for callers.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub use beach_buggy::{
+    AckStatus, ActionAck, ActionCommand, ControllerPairing, ControllerPairingAction,
+    ControllerPairingEvent, ControllerUpdateCadence, HarnessType, HealthHeartbeat, StateDiff,
+    TransportMode,
+};
+pub use harness_proto::actions::{ActionKind, ControllerAction, KeyModifier, PtySignal};
+
+// ----------------------------------------------------------------------------- //
+// Private beaches
+// ----------------------------------------------------------------------------- //
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateBeachRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateBeachRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BeachSummary {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BeachMeta {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub settings: serde_json::Value,
+    pub created_at: i64,
+}
+
+// ----------------------------------------------------------------------------- //
+// Canvas layout
+// ----------------------------------------------------------------------------- //
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasViewport {
+    pub zoom: f64,
+    pub pan: CanvasPoint,
+}
+
+impl Default for CanvasViewport {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: CanvasPoint::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasTileNode {
+    pub id: String,
+    pub position: CanvasPoint,
+    pub size: CanvasSize,
+    pub z_index: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolbar_pinned: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasAgentNode {
+    pub id: String,
+    pub position: CanvasPoint,
+    pub size: CanvasSize,
+    pub z_index: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasGroupNode {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub member_ids: Vec<String>,
+    pub position: CanvasPoint,
+    pub size: CanvasSize,
+    pub z_index: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collapsed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasAssignment {
+    pub controller_id: String,
+    pub target_type: String,
+    pub target_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum CanvasAgentUpdateMode {
+    #[serde(rename = "idle-summary")]
+    IdleSummary,
+    #[serde(rename = "push")]
+    Push,
+    #[serde(rename = "poll")]
+    Poll,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasAgentRelationship {
+    pub id: String,
+    pub source_id: String,
+    pub target_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_handle_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_handle_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_mode: Option<CanvasAgentUpdateMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_frequency: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasMetadata {
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migrated_from: Option<i64>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agent_relationships: HashMap<String, CanvasAgentRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agent_relationship_order: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasLayout {
+    #[serde(default = "CanvasLayout::default_version")]
+    pub version: u8,
+    #[serde(default)]
+    pub viewport: CanvasViewport,
+    #[serde(default)]
+    pub tiles: HashMap<String, CanvasTileNode>,
+    #[serde(default)]
+    pub agents: HashMap<String, CanvasAgentNode>,
+    #[serde(default)]
+    pub groups: HashMap<String, CanvasGroupNode>,
+    #[serde(default)]
+    pub control_assignments: HashMap<String, CanvasAssignment>,
+    #[serde(default)]
+    pub metadata: CanvasMetadata,
+}
+
+impl CanvasLayout {
+    const fn default_version() -> u8 {
+        3
+    }
+
+    pub fn empty(now_ms: i64) -> Self {
+        Self {
+            version: 3,
+            viewport: CanvasViewport::default(),
+            tiles: HashMap::new(),
+            agents: HashMap::new(),
+            groups: HashMap::new(),
+            control_assignments: HashMap::new(),
+            metadata: CanvasMetadata {
+                created_at: now_ms,
+                updated_at: now_ms,
+                migrated_from: None,
+                agent_relationships: HashMap::new(),
+                agent_relationship_order: Vec::new(),
+            },
+        }
+    }
+
+    pub fn ensure_version(self) -> Result<Self, String> {
+        if self.version != 3 {
+            return Err("layout version must be 3".into());
+        }
+        Ok(self)
+    }
+
+    pub fn with_updated_timestamp(mut self, now_ms: i64) -> Self {
+        if self.metadata.created_at == 0 {
+            self.metadata.created_at = now_ms;
+        }
+        self.metadata.updated_at = now_ms;
+        self
+    }
+}
+
+// ----------------------------------------------------------------------------- //
+// Sessions
+// ----------------------------------------------------------------------------- //
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SessionKind {
+    Terminal,
+    CabanaGui,
+    ManagerConsole,
+    Widget,
+    SpectatorFeed,
+    ServiceDaemon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Active,
+    Ended,
+}
+
+/// Session row returned by `GET /private-beaches/:id/sessions` and attach calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub private_beach_id: String,
+    pub harness_type: HarnessType,
+    pub capabilities: Vec<String>,
+    pub location_hint: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    pub version: String,
+    pub harness_id: String,
+    pub controller_token: Option<String>,
+    pub controller_expires_at_ms: Option<i64>,
+    pub pending_actions: usize,
+    pub pending_unacked: usize,
+    pub last_health: Option<HealthHeartbeat>,
+    #[serde(default)]
+    pub kind: Option<SessionKind>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub last_seen_at_ms: Option<i64>,
+    #[serde(default)]
+    pub ended_at_ms: Option<i64>,
+}
+
+/// Query string for the session list; unset fields do not narrow the result.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub harness_type: Option<HarnessType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<SessionKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_after_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_before_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SessionStatus>,
+    /// Case-insensitive substring match against title and location hint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTagRequest {
+    pub tag: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionTagsResponse {
+    pub session_id: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AttachByCodeRequest {
+    pub session_id: String,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AttachByCodeResponse {
+    pub ok: bool,
+    pub attach_method: String,
+    pub session: SessionSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AttachOwnedRequest {
+    pub origin_session_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AttachOwnedResponse {
+    pub attached: usize,
+    pub duplicates: usize,
+}
+
+// ----------------------------------------------------------------------------- //
+// Controller leases, actions and pairings
+// ----------------------------------------------------------------------------- //
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ControllerLeaseRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requesting_account_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Payload returned to a controller session when the manager grants (or renews) a lease.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ControllerLeaseResponse {
+    pub controller_token: String,
+    pub expires_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReleaseControllerRequest {
+    pub controller_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QueueActionsRequest {
+    pub controller_token: String,
+    pub actions: Vec<ActionCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PendingActionsResponse {
+    pub pending: usize,
+    pub webrtc_ready: bool,
+    pub transport: TransportMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateControllerPairingRequest {
+    pub child_session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_cadence: Option<ControllerUpdateCadence>,
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::ManagerError;
+
+/// Supplies bearer tokens to [`crate::ManagerClient`].
+///
+/// The client calls [`TokenSource::refresh`] once after a `401` and retries the
+/// request with the new token; sources that cannot refresh keep the default.
+#[async_trait]
+pub trait TokenSource: Send + Sync + 'static {
+    async fn token(&self) -> Result<String, ManagerError>;
+
+    async fn refresh(&self) -> Result<String, ManagerError> {
+        self.token().await
+    }
+}
+
+/// Fixed token, e.g. a long-lived service credential.
+#[derive(Clone)]
+pub struct StaticToken {
+    token: String,
+}
+
+impl StaticToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenSource for StaticToken {
+    async fn token(&self) -> Result<String, ManagerError> {
+        Ok(self.token.clone())
+    }
+}
+
+/// Fetches a token on demand and caches it until the manager rejects it.
+pub struct RefreshingToken<F> {
+    fetch: F,
+    cached: RwLock<Option<String>>,
+}
+
+impl<F, Fut> RefreshingToken<F>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<String, ManagerError>> + Send,
+{
+    pub fn new(fetch: F) -> Arc<Self> {
+        Arc::new(Self {
+            fetch,
+            cached: RwLock::new(None),
+        })
+    }
+}
+
+#[async_trait]
+impl<F, Fut> TokenSource for RefreshingToken<F>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<String, ManagerError>> + Send,
+{
+    async fn token(&self) -> Result<String, ManagerError> {
+        if let Some(token) = self.cached.read().await.clone() {
+            return Ok(token);
+        }
+        self.refresh().await
+    }
+
+    async fn refresh(&self) -> Result<String, ManagerError> {
+        let token = (self.fetch)().await?;
+        *self.cached.write().await = Some(token.clone());
+        Ok(token)
+    }
+}
+
+#[async_trait]
+impl<T: TokenSource + ?Sized> TokenSource for Arc<T> {
+    async fn token(&self) -> Result<String, ManagerError> {
+        (**self).token().await
+    }
+
+    async fn refresh(&self) -> Result<String, ManagerError> {
+        (**self).refresh().await
+    }
+}